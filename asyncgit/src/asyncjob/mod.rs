@@ -1,10 +1,122 @@
-//! provides `AsyncJob` trait and `AsyncSingleJob` struct
+//! provides `AsyncJob` trait, `AsyncSingleJob` struct and the
+//! `AsyncJobControl` trait all async jobs share
 
 #![deny(clippy::expect_used)]
 
 use crate::error::Result;
-use crossbeam_channel::Sender;
-use std::sync::{Arc, Mutex, RwLock};
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use std::{
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc, Mutex, RwLock,
+	},
+	thread,
+};
+
+/// where a job runs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JobPriority {
+	/// queued on the shared thread pool
+	#[default]
+	Normal,
+	/// started on a thread of its own right away, for results the
+	/// user is waiting for so they never queue up behind long
+	/// running background jobs
+	High,
+}
+
+/// runs `f` in the background according to `priority`
+pub fn spawn_job<F>(priority: JobPriority, f: F)
+where
+	F: FnOnce() + Send + 'static,
+{
+	match priority {
+		JobPriority::Normal => rayon_core::spawn(f),
+		JobPriority::High => {
+			if let Err(e) = thread::Builder::new()
+				.name(String::from("asyncgit-job"))
+				.spawn(f)
+			{
+				log::error!("spawn job error: {e}");
+			}
+		}
+	}
+}
+
+/// shared flag telling a running job its result is not wanted
+/// anymore
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+	///
+	pub fn cancel(&self) {
+		self.0.store(true, Ordering::Relaxed);
+	}
+
+	///
+	pub fn is_cancelled(&self) -> bool {
+		self.0.load(Ordering::Relaxed)
+	}
+}
+
+/// everyone listening to the progress of a job
+#[derive(Debug)]
+pub struct ProgressSubscribers<P>(Arc<Mutex<Vec<Sender<P>>>>);
+
+impl<P> Default for ProgressSubscribers<P> {
+	fn default() -> Self {
+		Self(Arc::default())
+	}
+}
+
+impl<P> Clone for ProgressSubscribers<P> {
+	fn clone(&self) -> Self {
+		Self(Arc::clone(&self.0))
+	}
+}
+
+impl<P: Clone> ProgressSubscribers<P> {
+	/// receives every progress notified from now on, dropping the
+	/// receiver ends the subscription
+	pub fn subscribe(&self) -> Receiver<P> {
+		let (sender, receiver) = unbounded();
+		if let Ok(mut subscribers) = self.0.lock() {
+			subscribers.push(sender);
+		}
+		receiver
+	}
+
+	/// sends `p` to every subscriber still listening
+	pub fn notify(&self, p: &P) {
+		if let Ok(mut subscribers) = self.0.lock() {
+			subscribers.retain(|subscriber| {
+				subscriber.send(p.clone()).is_ok()
+			});
+		}
+	}
+}
+
+/// the controls every async job offers, whether it runs an
+/// `AsyncJob` in an `AsyncSingleJob` or schedules its work itself
+pub trait AsyncJobControl {
+	/// type of progress
+	type Progress;
+
+	/// where the work of the job runs
+	fn priority(&self) -> JobPriority;
+
+	/// `true` while work is running or queued
+	fn is_pending(&self) -> bool;
+
+	/// drops queued work and tells the running work to stop.
+	/// returns `true` if it actually canceled something
+	fn cancel(&self) -> bool;
+
+	/// receives every change of the progress from now on, dropping
+	/// the receiver ends the subscription
+	fn subscribe_progress(&self) -> Receiver<Self::Progress>;
+}
 
 /// Passed to `AsyncJob::run` allowing sending intermediate progress notifications
 pub struct RunParams<
@@ -13,6 +125,8 @@ pub struct RunParams<
 > {
 	sender: Sender<T>,
 	progress: Arc<RwLock<P>>,
+	subscribers: ProgressSubscribers<P>,
+	cancellation: CancellationToken,
 }
 
 impl<T: Copy + Send, P: Clone + Send + Sync + PartialEq>
@@ -28,15 +142,28 @@ impl<T: Copy + Send, P: Clone + Send + Sync + PartialEq>
 		Ok(())
 	}
 
-	/// set the current progress
+	/// set the current progress, subscribers get told about changes
 	pub fn set_progress(&self, p: P) -> Result<bool> {
 		Ok(if *self.progress.read()? == p {
 			false
 		} else {
+			self.subscribers.notify(&p);
 			*(self.progress.write()?) = p;
 			true
 		})
 	}
+
+	/// `true` once the job got cancelled, long running jobs should
+	/// check this between steps and return early. whatever they
+	/// return still ends up in `take_last`
+	pub fn is_cancelled(&self) -> bool {
+		self.cancellation.is_cancelled()
+	}
+
+	/// for handing the cancellation on to blocking calls
+	pub const fn cancellation(&self) -> &CancellationToken {
+		&self.cancellation
+	}
 }
 
 /// trait that defines an async task we can run on a threadpool
@@ -70,6 +197,10 @@ pub struct AsyncSingleJob<J: AsyncJob> {
 	next: Arc<Mutex<Option<J>>>,
 	last: Arc<Mutex<Option<J>>>,
 	progress: Arc<RwLock<J::Progress>>,
+	subscribers: ProgressSubscribers<J::Progress>,
+	/// of the job currently running
+	cancellation: Arc<Mutex<CancellationToken>>,
+	priority: JobPriority,
 	sender: Sender<J::Notification>,
 	pending: Arc<Mutex<()>>,
 }
@@ -82,17 +213,35 @@ impl<J: 'static + AsyncJob> AsyncSingleJob<J> {
 			last: Arc::new(Mutex::new(None)),
 			pending: Arc::new(Mutex::new(())),
 			progress: Arc::new(RwLock::new(J::Progress::default())),
+			subscribers: ProgressSubscribers::default(),
+			cancellation: Arc::default(),
+			priority: JobPriority::default(),
 			sender,
 		}
 	}
 
+	///
+	#[must_use]
+	pub const fn with_priority(
+		mut self,
+		priority: JobPriority,
+	) -> Self {
+		self.priority = priority;
+		self
+	}
+
 	///
 	pub fn is_pending(&self) -> bool {
 		self.pending.try_lock().is_err()
 	}
 
-	/// makes sure `next` is cleared and returns `true` if it actually canceled something
+	/// makes sure `next` is cleared and the running job is told to
+	/// stop (see `RunParams::is_cancelled`).
+	/// returns `true` if it actually canceled something
 	pub fn cancel(&self) -> bool {
+		let running = self.is_pending();
+		self.cancel_running();
+
 		if let Ok(mut next) = self.next.lock() {
 			if next.is_some() {
 				*next = None;
@@ -100,7 +249,20 @@ impl<J: 'static + AsyncJob> AsyncSingleJob<J> {
 			}
 		}
 
-		false
+		running
+	}
+
+	/// like `spawn` but cancels the running job first, its result
+	/// became stale with `task` anyway
+	pub fn supersede(&self, task: J) -> bool {
+		self.cancel_running();
+		self.spawn(task)
+	}
+
+	/// receives every change of the progress of the jobs run from now
+	/// on, dropping the receiver ends the subscription
+	pub fn subscribe_progress(&self) -> Receiver<J::Progress> {
+		self.subscribers.subscribe()
 	}
 
 	/// take out last finished job
@@ -127,9 +289,15 @@ impl<J: 'static + AsyncJob> AsyncSingleJob<J> {
 		}
 
 		if let Some(task) = self.take_next() {
+			let cancellation = CancellationToken::default();
+			if let Ok(mut current) = self.cancellation.lock() {
+				*current = cancellation.clone();
+			}
+
 			let self_clone = (*self).clone();
-			rayon_core::spawn(move || {
-				if let Err(e) = self_clone.run_job(task) {
+			spawn_job(self.priority, move || {
+				if let Err(e) = self_clone.run_job(task, cancellation)
+				{
 					log::error!("async job error: {e}");
 				}
 			});
@@ -140,14 +308,20 @@ impl<J: 'static + AsyncJob> AsyncSingleJob<J> {
 		false
 	}
 
-	fn run_job(&self, mut task: J) -> Result<()> {
+	fn run_job(
+		&self,
+		mut task: J,
+		cancellation: CancellationToken,
+	) -> Result<()> {
 		//limit the pending scope
 		{
 			let _pending = self.pending.lock()?;
 
 			let notification = task.run(RunParams {
 				progress: self.progress.clone(),
+				subscribers: self.subscribers.clone(),
 				sender: self.sender.clone(),
+				cancellation,
 			})?;
 
 			if let Ok(mut last) = self.last.lock() {
@@ -162,6 +336,12 @@ impl<J: 'static + AsyncJob> AsyncSingleJob<J> {
 		Ok(())
 	}
 
+	fn cancel_running(&self) {
+		if let Ok(cancellation) = self.cancellation.lock() {
+			cancellation.cancel();
+		}
+	}
+
 	fn schedule_next(&self, task: J) {
 		if let Ok(mut next) = self.next.lock() {
 			*next = Some(task);
@@ -173,6 +353,26 @@ impl<J: 'static + AsyncJob> AsyncSingleJob<J> {
 	}
 }
 
+impl<J: 'static + AsyncJob> AsyncJobControl for AsyncSingleJob<J> {
+	type Progress = J::Progress;
+
+	fn priority(&self) -> JobPriority {
+		self.priority
+	}
+
+	fn is_pending(&self) -> bool {
+		Self::is_pending(self)
+	}
+
+	fn cancel(&self) -> bool {
+		Self::cancel(self)
+	}
+
+	fn subscribe_progress(&self) -> Receiver<Self::Progress> {
+		Self::subscribe_progress(self)
+	}
+}
+
 #[cfg(test)]
 mod test {
 	use super::*;
@@ -255,7 +455,7 @@ mod test {
 		);
 	}
 
-	fn wait_for_job(job: &AsyncSingleJob<TestJob>) {
+	fn wait_for_job<J: 'static + AsyncJob>(job: &AsyncSingleJob<J>) {
 		while job.is_pending() {
 			thread::sleep(Duration::from_millis(10));
 		}
@@ -299,4 +499,82 @@ mod test {
 			2
 		);
 	}
+
+	/// counts up until it gets cancelled
+	#[derive(Clone, Default)]
+	struct CountingJob {
+		count: Arc<AtomicU32>,
+	}
+
+	impl AsyncJob for CountingJob {
+		type Notification = ();
+		type Progress = u32;
+
+		fn run(
+			&mut self,
+			params: RunParams<Self::Notification, Self::Progress>,
+		) -> Result<Self::Notification> {
+			while !params.is_cancelled() {
+				let count = self.count.fetch_add(1, Ordering::SeqCst);
+				params.set_progress(count + 1)?;
+				thread::sleep(Duration::from_millis(1));
+			}
+
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn test_cancel_running() {
+		let (sender, receiver) = unbounded();
+
+		let job: AsyncSingleJob<CountingJob> =
+			AsyncSingleJob::new(sender)
+				.with_priority(JobPriority::High);
+		let progress = job.subscribe_progress();
+
+		let task = CountingJob::default();
+		assert!(job.spawn(task.clone()));
+
+		assert_eq!(progress.recv().unwrap(), 1);
+		assert_eq!(progress.recv().unwrap(), 2);
+
+		assert!(job.cancel());
+		receiver.recv().unwrap();
+		wait_for_job(&job);
+
+		let count = task.count.load(Ordering::SeqCst);
+		thread::sleep(Duration::from_millis(20));
+		assert_eq!(task.count.load(Ordering::SeqCst), count);
+		assert!(job.take_last().is_some());
+	}
+
+	#[test]
+	fn test_supersede() {
+		let (sender, receiver) = unbounded();
+
+		let job: AsyncSingleJob<CountingJob> =
+			AsyncSingleJob::new(sender);
+
+		let first = CountingJob::default();
+		let second = CountingJob::default();
+
+		assert!(job.spawn(first.clone()));
+		while first.count.load(Ordering::SeqCst) == 0 {
+			thread::yield_now();
+		}
+
+		assert!(!job.supersede(second.clone()));
+
+		// the first one stops and the second one starts
+		receiver.recv().unwrap();
+		while second.count.load(Ordering::SeqCst) == 0 {
+			thread::yield_now();
+		}
+		assert!(job.is_pending());
+
+		assert!(job.cancel());
+		receiver.recv().unwrap();
+		wait_for_job(&job);
+	}
 }
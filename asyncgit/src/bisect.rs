@@ -0,0 +1,96 @@
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	error::Result,
+	sync::{self, BisectState, RepoPath},
+	AsyncGitNotification, ProgressPercent,
+};
+use std::sync::{Arc, Mutex};
+
+enum JobState {
+	Request,
+	Response(Result<BisectState>),
+}
+
+/// runs a test command on every commit the bisect checks out until
+/// the first bad commit is found, like `git bisect run`
+#[derive(Clone)]
+pub struct AsyncBisectJob {
+	repo: RepoPath,
+	command: String,
+	state: Arc<Mutex<JobState>>,
+}
+
+impl AsyncBisectJob {
+	///
+	pub fn new(repo: RepoPath, command: String) -> Self {
+		Self {
+			repo,
+			command,
+			state: Arc::new(Mutex::new(JobState::Request)),
+		}
+	}
+
+	///
+	pub fn command(&self) -> &str {
+		&self.command
+	}
+
+	/// final result, `None` while the job is still running
+	pub fn result(&self) -> Option<Result<BisectState>> {
+		let mut state = self.state.lock().ok()?;
+
+		match std::mem::replace(&mut *state, JobState::Request) {
+			JobState::Response(result) => Some(result),
+			JobState::Request => None,
+		}
+	}
+
+	fn run_request(
+		&self,
+		params: &RunParams<AsyncGitNotification, ProgressPercent>,
+	) -> Result<BisectState> {
+		let mut done = 0;
+
+		loop {
+			let state =
+				sync::bisect_run_step(&self.repo, &self.command)?;
+
+			// a cancelled run stops after the commit it is testing
+			if state.next.is_none() || params.is_cancelled() {
+				return Ok(state);
+			}
+
+			done += 1;
+			let steps = usize::try_from(state.steps()).unwrap_or(0);
+			if let Err(e) = params.set_progress(ProgressPercent::new(
+				done,
+				done + steps,
+			)) {
+				log::error!("progress error: {e}");
+			}
+
+			if let Err(e) = params.send(AsyncGitNotification::Bisect)
+			{
+				log::error!("send error: {e}");
+			}
+		}
+	}
+}
+
+impl AsyncJob for AsyncBisectJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ProgressPercent;
+
+	fn run(
+		&mut self,
+		params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		let result = self.run_request(&params);
+
+		if let Ok(mut state) = self.state.lock() {
+			*state = JobState::Response(result);
+		}
+
+		Ok(AsyncGitNotification::Bisect)
+	}
+}
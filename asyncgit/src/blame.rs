@@ -1,16 +1,12 @@
 use crate::{
+	asyncjob::{AsyncJob, RunParams},
 	error::Result,
-	hash,
 	sync::{self, CommitId, FileBlame, RepoPath},
-	AsyncGitNotification,
+	AsyncGitNotification, ProgressPercent,
 };
-use crossbeam_channel::Sender;
-use std::{
-	hash::Hash,
-	sync::{
-		atomic::{AtomicUsize, Ordering},
-		Arc, Mutex,
-	},
+use std::sync::{
+	atomic::{AtomicBool, Ordering},
+	Arc, Mutex,
 };
 
 ///
@@ -22,167 +18,158 @@ pub struct BlameParams {
 	pub commit_id: Option<CommitId>,
 }
 
-struct Request<R, A>(R, Option<A>);
-
-#[derive(Default, Clone)]
-struct LastResult<P, R> {
-	params: P,
-	result: R,
+enum JobState {
+	Request,
+	/// lines blamed so far, all of them once the job is done
+	Partial(FileBlame),
+	Response(Result<FileBlame>),
 }
 
-///
-pub struct AsyncBlame {
-	current: Arc<Mutex<Request<u64, FileBlame>>>,
-	last: Arc<Mutex<Option<LastResult<BlameParams, FileBlame>>>>,
-	sender: Sender<AsyncGitNotification>,
-	pending: Arc<AtomicUsize>,
+/// blames a file in chunks, making the lines blamed so far available
+/// while it is still running
+#[derive(Clone)]
+pub struct AsyncBlameJob {
 	repo: RepoPath,
+	params: BlameParams,
+	state: Arc<Mutex<JobState>>,
+	cancellation_flag: Arc<AtomicBool>,
 }
 
-impl AsyncBlame {
+impl AsyncBlameJob {
 	///
-	pub fn new(
-		repo: RepoPath,
-		sender: &Sender<AsyncGitNotification>,
-	) -> Self {
+	pub fn new(repo: RepoPath, params: BlameParams) -> Self {
 		Self {
 			repo,
-			current: Arc::new(Mutex::new(Request(0, None))),
-			last: Arc::new(Mutex::new(None)),
-			sender: sender.clone(),
-			pending: Arc::new(AtomicUsize::new(0)),
+			params,
+			state: Arc::new(Mutex::new(JobState::Request)),
+			cancellation_flag: Arc::new(AtomicBool::new(false)),
 		}
 	}
 
 	///
-	pub fn last(&self) -> Result<Option<(BlameParams, FileBlame)>> {
-		let last = self.last.lock()?;
-
-		Ok(last.clone().map(|last_result| {
-			(last_result.params, last_result.result)
-		}))
+	pub const fn params(&self) -> &BlameParams {
+		&self.params
 	}
 
-	///
-	pub fn refresh(&self) -> Result<()> {
-		if let Ok(Some(param)) = self.get_last_param() {
-			self.clear_current()?;
-			self.request(param)?;
-		}
-		Ok(())
+	/// makes a running job stop after the chunk it is working on
+	pub fn cancel(&self) {
+		self.cancellation_flag.store(true, Ordering::Relaxed);
 	}
 
-	///
-	pub fn is_pending(&self) -> bool {
-		self.pending.load(Ordering::Relaxed) > 0
+	/// lines blamed so far while the job is running
+	pub fn partial(&self) -> Option<FileBlame> {
+		self.state.lock().ok().and_then(|state| match &*state {
+			JobState::Partial(blame) => Some(blame.clone()),
+			_ => None,
+		})
 	}
 
-	///
-	pub fn request(
-		&self,
-		params: BlameParams,
-	) -> Result<Option<FileBlame>> {
-		log::trace!("request");
-
-		let hash = hash(&params);
-
-		{
-			let mut current = self.current.lock()?;
+	/// final result, `None` while the job is still running
+	pub fn result(&self) -> Option<Result<FileBlame>> {
+		let mut state = self.state.lock().ok()?;
 
-			if current.0 == hash {
-				return Ok(current.1.clone());
+		match std::mem::replace(&mut *state, JobState::Request) {
+			JobState::Response(result) => Some(result),
+			other => {
+				*state = other;
+				None
 			}
-
-			current.0 = hash;
-			current.1 = None;
 		}
+	}
+
+	fn run_request(
+		&self,
+		params: &RunParams<AsyncGitNotification, ProgressPercent>,
+	) -> Result<FileBlame> {
+		sync::blame::blame_file_incremental(
+			&self.repo,
+			&self.params.file_path,
+			self.params.commit_id,
+			|file_blame, done| {
+				if self.cancellation_flag.load(Ordering::Relaxed) {
+					return false;
+				}
 
-		let arc_current = Arc::clone(&self.current);
-		let arc_last = Arc::clone(&self.last);
-		let sender = self.sender.clone();
-		let arc_pending = Arc::clone(&self.pending);
-		let repo = self.repo.clone();
-
-		self.pending.fetch_add(1, Ordering::Relaxed);
-
-		rayon_core::spawn(move || {
-			let notify = Self::get_blame_helper(
-				&repo,
-				params,
-				&arc_last,
-				&arc_current,
-				hash,
-			);
-
-			let notify = match notify {
-				Err(err) => {
-					log::error!("get_blame_helper error: {err}");
-					true
+				if let Ok(mut state) = self.state.lock() {
+					*state = JobState::Partial(file_blame.clone());
 				}
-				Ok(notify) => notify,
-			};
 
-			arc_pending.fetch_sub(1, Ordering::Relaxed);
+				if let Err(e) =
+					params.set_progress(ProgressPercent::new(
+						done,
+						file_blame.lines.len(),
+					)) {
+					log::error!("progress error: {e}");
+				}
 
-			sender
-				.send(if notify {
-					AsyncGitNotification::Blame
-				} else {
-					AsyncGitNotification::FinishUnchanged
-				})
-				.expect("error sending blame");
-		});
+				if let Err(e) =
+					params.send(AsyncGitNotification::Blame)
+				{
+					log::error!("send error: {e}");
+				}
 
-		Ok(None)
+				true
+			},
+		)
 	}
+}
 
-	fn get_blame_helper(
-		repo_path: &RepoPath,
-		params: BlameParams,
-		arc_last: &Arc<
-			Mutex<Option<LastResult<BlameParams, FileBlame>>>,
-		>,
-		arc_current: &Arc<Mutex<Request<u64, FileBlame>>>,
-		hash: u64,
-	) -> Result<bool> {
-		let file_blame = sync::blame::blame_file(
-			repo_path,
-			&params.file_path,
-			params.commit_id,
-		)?;
-
-		let mut notify = false;
-		{
-			let mut current = arc_current.lock()?;
-			if current.0 == hash {
-				current.1 = Some(file_blame.clone());
-				notify = true;
-			}
-		}
+impl AsyncJob for AsyncBlameJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ProgressPercent;
+
+	fn run(
+		&mut self,
+		params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		let result = self.run_request(&params);
 
-		{
-			let mut last = arc_last.lock()?;
-			*last = Some(LastResult {
-				result: file_blame,
-				params,
-			});
+		if let Ok(mut state) = self.state.lock() {
+			*state = JobState::Response(result);
 		}
 
-		Ok(notify)
+		Ok(AsyncGitNotification::Blame)
 	}
+}
 
-	fn get_last_param(&self) -> Result<Option<BlameParams>> {
-		Ok(self
-			.last
-			.lock()?
-			.clone()
-			.map(|last_result| last_result.params))
-	}
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		asyncjob::AsyncSingleJob,
+		sync::tests::{repo_init, write_commit_file},
+	};
+	use crossbeam_channel::unbounded;
+	use std::time::Duration;
+
+	#[test]
+	fn test_blame_job() {
+		let (td, repo) = repo_init().unwrap();
+		let repo_path: RepoPath = td.path().to_str().unwrap().into();
+
+		let content = "line\n".repeat(1000);
+		write_commit_file(&repo, "a.txt", &content, "add a");
+
+		let (sender, receiver) = unbounded();
+		let job = AsyncSingleJob::new(sender);
+		let blame = AsyncBlameJob::new(
+			repo_path,
+			BlameParams {
+				file_path: "a.txt".into(),
+				commit_id: None,
+			},
+		);
+		job.spawn(blame.clone());
+
+		let result = loop {
+			receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+			if let Some(result) = blame.result() {
+				break result.unwrap();
+			}
+		};
 
-	fn clear_current(&self) -> Result<()> {
-		let mut current = self.current.lock()?;
-		current.0 = 0;
-		current.1 = None;
-		Ok(())
+		assert_eq!(result.lines.len(), 1000);
+		assert!(result.lines.iter().all(|(hunk, _)| hunk.is_some()));
+		assert_eq!(job.progress().unwrap().progress, 100);
 	}
 }
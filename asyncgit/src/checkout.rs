@@ -0,0 +1,87 @@
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	error::Result,
+	sync::{
+		checkout_branch_autostash, get_head, BranchInfo, RepoPath,
+	},
+	AsyncGitNotification, PostHook,
+};
+use std::sync::{Arc, Mutex};
+
+enum JobState {
+	Request { repo: RepoPath, branch: BranchInfo },
+	Response(Result<PostHook>),
+}
+
+/// switches branches with the uncommitted changes stashed for the
+/// switch, see `checkout_branch_autostash`
+#[derive(Clone, Default)]
+pub struct AsyncCheckoutJob {
+	state: Arc<Mutex<Option<JobState>>>,
+}
+
+///
+impl AsyncCheckoutJob {
+	///
+	pub fn new(repo: RepoPath, branch: BranchInfo) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request {
+				repo,
+				branch,
+			}))),
+		}
+	}
+
+	/// the post-checkout hook to run for the switch if it went
+	/// through
+	pub fn result(&self) -> Option<Result<PostHook>> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request { .. } => None,
+					JobState::Response(result) => Some(result),
+				};
+			}
+		}
+
+		None
+	}
+}
+
+fn checkout(
+	repo: &RepoPath,
+	branch: &BranchInfo,
+) -> Result<PostHook> {
+	let old = get_head(repo).ok();
+
+	checkout_branch_autostash(repo, branch)?;
+
+	Ok(PostHook::Checkout {
+		old,
+		new: get_head(repo)?,
+		branch: true,
+	})
+}
+
+impl AsyncJob for AsyncCheckoutJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		if let Ok(mut state) = self.state.lock() {
+			*state = state.take().map(|state| match state {
+				JobState::Request { repo, branch } => {
+					JobState::Response(checkout(&repo, &branch))
+				}
+				JobState::Response(result) => {
+					JobState::Response(result)
+				}
+			});
+		}
+
+		Ok(AsyncGitNotification::Checkout)
+	}
+}
@@ -0,0 +1,74 @@
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	error::Result,
+	sync::{delete_branches, RepoPath},
+	AsyncGitNotification,
+};
+use std::sync::{Arc, Mutex};
+
+enum JobState {
+	Request {
+		branch_refs: Vec<String>,
+		repo: RepoPath,
+	},
+	Response(Result<()>),
+}
+
+/// deletes a batch of branches
+#[derive(Clone, Default)]
+pub struct AsyncDeleteBranchesJob {
+	state: Arc<Mutex<Option<JobState>>>,
+}
+
+///
+impl AsyncDeleteBranchesJob {
+	///
+	pub fn new(repo: RepoPath, branch_refs: Vec<String>) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request {
+				branch_refs,
+				repo,
+			}))),
+		}
+	}
+
+	///
+	pub fn result(&self) -> Option<Result<()>> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request { .. } => None,
+					JobState::Response(result) => Some(result),
+				};
+			}
+		}
+
+		None
+	}
+}
+
+impl AsyncJob for AsyncDeleteBranchesJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		if let Ok(mut state) = self.state.lock() {
+			*state = state.take().map(|state| match state {
+				JobState::Request { branch_refs, repo } => {
+					JobState::Response(delete_branches(
+						&repo,
+						&branch_refs,
+					))
+				}
+				JobState::Response(result) => {
+					JobState::Response(result)
+				}
+			});
+		}
+
+		Ok(AsyncGitNotification::DeleteBranches)
+	}
+}
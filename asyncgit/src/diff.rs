@@ -1,4 +1,7 @@
 use crate::{
+	asyncjob::{
+		spawn_job, AsyncJobControl, CancellationToken, JobPriority,
+	},
 	error::Result,
 	hash,
 	sync::{
@@ -7,15 +10,23 @@ use crate::{
 	},
 	AsyncGitNotification, FileDiff,
 };
-use crossbeam_channel::Sender;
+use crossbeam_channel::{never, Receiver, Sender};
+use lru::LruCache;
 use std::{
 	hash::Hash,
+	num::NonZeroUsize,
 	sync::{
 		atomic::{AtomicUsize, Ordering},
 		Arc, Mutex,
 	},
 };
 
+/// diffs kept around to switch back to without computing them again
+const CACHE_SIZE: usize = 32;
+/// lines the cached diffs may have in total, bigger diffs are not
+/// cached at all
+const CACHE_LINES: usize = 100_000;
+
 ///
 #[derive(Debug, Hash, Clone, PartialEq, Eq)]
 pub enum DiffType {
@@ -42,6 +53,47 @@ pub struct DiffParams {
 
 struct Request<R, A>(R, Option<A>);
 
+/// recently used diffs, bounded by their number and total lines
+struct DiffCache {
+	diffs: LruCache<u64, FileDiff>,
+	lines: usize,
+	max_lines: usize,
+}
+
+impl DiffCache {
+	fn new(size: usize, max_lines: usize) -> Self {
+		Self {
+			diffs: LruCache::new(
+				NonZeroUsize::new(size).unwrap_or(NonZeroUsize::MIN),
+			),
+			lines: 0,
+			max_lines,
+		}
+	}
+
+	fn get(&mut self, key: u64) -> Option<FileDiff> {
+		self.diffs.get(&key).cloned()
+	}
+
+	fn put(&mut self, key: u64, diff: FileDiff) {
+		if diff.lines > self.max_lines {
+			return;
+		}
+
+		self.lines += diff.lines;
+		if let Some((_, evicted)) = self.diffs.push(key, diff) {
+			self.lines -= evicted.lines;
+		}
+
+		while self.lines > self.max_lines {
+			match self.diffs.pop_lru() {
+				Some((_, evicted)) => self.lines -= evicted.lines,
+				None => break,
+			}
+		}
+	}
+}
+
 #[derive(Default, Clone)]
 struct LastResult<P, R> {
 	params: P,
@@ -54,6 +106,12 @@ pub struct AsyncDiff {
 	last: Arc<Mutex<Option<LastResult<DiffParams, FileDiff>>>>,
 	sender: Sender<AsyncGitNotification>,
 	pending: Arc<AtomicUsize>,
+	/// of the latest request, superseded ones get cancelled
+	cancellation: Mutex<CancellationToken>,
+	/// keyed by the params and the content and settings they were
+	/// computed from
+	cache: Arc<Mutex<DiffCache>>,
+	priority: JobPriority,
 	repo: RepoPath,
 }
 
@@ -69,9 +127,26 @@ impl AsyncDiff {
 			last: Arc::new(Mutex::new(None)),
 			sender: sender.clone(),
 			pending: Arc::new(AtomicUsize::new(0)),
+			cancellation: Mutex::default(),
+			cache: Arc::new(Mutex::new(DiffCache::new(
+				CACHE_SIZE,
+				CACHE_LINES,
+			))),
+			// the diff of the selection is what the user is looking at
+			priority: JobPriority::High,
 		}
 	}
 
+	///
+	#[must_use]
+	pub const fn with_priority(
+		mut self,
+		priority: JobPriority,
+	) -> Self {
+		self.priority = priority;
+		self
+	}
+
 	///
 	pub fn last(&self) -> Result<Option<(DiffParams, FileDiff)>> {
 		let last = self.last.lock()?;
@@ -117,17 +192,27 @@ impl AsyncDiff {
 		let arc_last = Arc::clone(&self.last);
 		let sender = self.sender.clone();
 		let arc_pending = Arc::clone(&self.pending);
+		let arc_cache = Arc::clone(&self.cache);
 		let repo = self.repo.clone();
+		let cancellation = CancellationToken::default();
+
+		{
+			let mut current = self.cancellation.lock()?;
+			current.cancel();
+			*current = cancellation.clone();
+		}
 
 		self.pending.fetch_add(1, Ordering::Relaxed);
 
-		rayon_core::spawn(move || {
+		spawn_job(self.priority, move || {
 			let notify = Self::get_diff_helper(
 				&repo,
 				params,
 				&arc_last,
 				&arc_current,
+				&arc_cache,
 				hash,
+				&cancellation,
 			);
 
 			let notify = match notify {
@@ -159,53 +244,121 @@ impl AsyncDiff {
 			Mutex<Option<LastResult<DiffParams, FileDiff>>>,
 		>,
 		arc_current: &Arc<Mutex<Request<u64, FileDiff>>>,
+		cache: &Mutex<DiffCache>,
 		hash: u64,
+		cancellation: &CancellationToken,
 	) -> Result<bool> {
-		let res = match params.diff_type {
+		if cancellation.is_cancelled() {
+			return Ok(false);
+		}
+
+		let res = Self::get_diff_cached(repo_path, &params, cache)?;
+
+		// a superseded diff finishing late must not replace the
+		// result of the newer one
+		if cancellation.is_cancelled() {
+			return Ok(false);
+		}
+
+		let mut notify = false;
+		{
+			let mut current = arc_current.lock()?;
+			if current.0 == hash {
+				current.1 = Some(res.clone());
+				notify = true;
+			}
+		}
+
+		{
+			let mut last = arc_last.lock()?;
+			*last = Some(LastResult {
+				result: res,
+				params,
+			});
+		}
+
+		Ok(notify)
+	}
+
+	/// computes the diff unless the content and the settings (config
+	/// and attributes) it is computed from did not change since it
+	/// was cached
+	fn get_diff_cached(
+		repo_path: &RepoPath,
+		params: &DiffParams,
+		cache: &Mutex<DiffCache>,
+	) -> Result<FileDiff> {
+		let settings =
+			sync::diff::get_diff_settings(repo_path, &params.path)
+				.map_err(|e| log::error!("diff settings error: {e}"))
+				.ok();
+
+		let key = match params.diff_type {
+			DiffType::Stage | DiffType::WorkDir => {
+				sync::diff::get_diff_snapshot(
+					repo_path,
+					&params.path,
+					params.diff_type == DiffType::Stage,
+				)
+				.unwrap_or_else(|e| {
+					log::error!("diff snapshot error: {e}");
+					None
+				})
+				.map(|snapshot| hash(&(params, snapshot)))
+			}
+			// commits never change
+			DiffType::Commit(_) | DiffType::Commits(_) => {
+				Some(hash(params))
+			}
+		}
+		.zip(settings)
+		.map(|key| hash(&key));
+
+		if let Some(diff) = key.and_then(|key| {
+			cache.lock().ok().and_then(|mut c| c.get(key))
+		}) {
+			return Ok(diff);
+		}
+
+		let diff = Self::get_diff(repo_path, params)?;
+
+		if let Some(key) = key {
+			cache.lock()?.put(key, diff.clone());
+		}
+
+		Ok(diff)
+	}
+
+	fn get_diff(
+		repo_path: &RepoPath,
+		params: &DiffParams,
+	) -> Result<FileDiff> {
+		match params.diff_type {
 			DiffType::Stage => sync::diff::get_diff(
 				repo_path,
 				&params.path,
 				true,
 				Some(params.options),
-			)?,
+			),
 			DiffType::WorkDir => sync::diff::get_diff(
 				repo_path,
 				&params.path,
 				false,
 				Some(params.options),
-			)?,
+			),
 			DiffType::Commit(id) => sync::diff::get_diff_commit(
 				repo_path,
 				id,
 				params.path.clone(),
 				Some(params.options),
-			)?,
+			),
 			DiffType::Commits(ids) => sync::diff::get_diff_commits(
 				repo_path,
 				ids,
 				params.path.clone(),
 				Some(params.options),
-			)?,
-		};
-
-		let mut notify = false;
-		{
-			let mut current = arc_current.lock()?;
-			if current.0 == hash {
-				current.1 = Some(res.clone());
-				notify = true;
-			}
-		}
-
-		{
-			let mut last = arc_last.lock()?;
-			*last = Some(LastResult {
-				result: res,
-				params,
-			});
+			),
 		}
-
-		Ok(notify)
 	}
 
 	fn get_last_param(&self) -> Result<Option<DiffParams>> {
@@ -219,3 +372,68 @@ impl AsyncDiff {
 		Ok(())
 	}
 }
+
+impl AsyncJobControl for AsyncDiff {
+	/// a diff reports no progress
+	type Progress = ();
+
+	fn priority(&self) -> JobPriority {
+		self.priority
+	}
+
+	fn is_pending(&self) -> bool {
+		Self::is_pending(self)
+	}
+
+	/// the running diff finishes but its result gets dropped
+	fn cancel(&self) -> bool {
+		if let Ok(cancellation) = self.cancellation.lock() {
+			cancellation.cancel();
+		}
+
+		if let Err(e) = self.clear_current() {
+			log::error!("clear current error: {e}");
+		}
+
+		self.is_pending()
+	}
+
+	fn subscribe_progress(&self) -> Receiver<Self::Progress> {
+		never()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::DiffCache;
+	use crate::FileDiff;
+
+	fn diff(lines: usize) -> FileDiff {
+		FileDiff {
+			lines,
+			..FileDiff::default()
+		}
+	}
+
+	#[test]
+	fn test_cache_bounded_by_lines() {
+		let mut cache = DiffCache::new(8, 100);
+
+		cache.put(1, diff(40));
+		cache.put(2, diff(40));
+		assert!(cache.get(1).is_some());
+
+		// evicts 2, used least recently
+		cache.put(3, diff(40));
+		assert!(cache.get(2).is_none());
+		assert!(cache.get(1).is_some());
+		assert_eq!(cache.lines, 80);
+
+		cache.put(4, diff(101));
+		assert!(cache.get(4).is_none());
+
+		// replacing an entry does not count it twice
+		cache.put(3, diff(10));
+		assert_eq!(cache.lines, 50);
+	}
+}
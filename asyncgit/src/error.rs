@@ -11,6 +11,29 @@ pub enum GixError {
 	#[error("gix::discover error: {0}")]
 	Discover(#[from] Box<gix::discover::Error>),
 
+	///
+	#[error(
+		"gix::filter::pipeline::convert_to_git::Error error: {0}"
+	)]
+	FilterToGit(
+		#[from] Box<gix::filter::pipeline::convert_to_git::Error>,
+	),
+
+	///
+	#[error(
+		"gix::filter::pipeline::convert_to_worktree::Error error: {0}"
+	)]
+	FilterToWorktree(
+		#[from]
+		Box<gix::filter::pipeline::convert_to_worktree::Error>,
+	),
+
+	///
+	#[error("gix::repository::filter::pipeline::Error error: {0}")]
+	FilterPipeline(
+		#[from] Box<gix::repository::filter::pipeline::Error>,
+	),
+
 	///
 	#[error("gix::head::peel::to_commit error: {0}")]
 	HeadPeelToCommit(#[from] gix::head::peel::to_commit::Error),
@@ -125,6 +148,18 @@ pub enum Error {
 	#[error("git error:{0}")]
 	Git(#[from] git2::Error),
 
+	///
+	#[error("authentication failed: {0}\ncheck the username and the password or token")]
+	Authentication(String),
+
+	///
+	#[error("network error: {0}\nthe remote could not be reached")]
+	Network(String),
+
+	/// the job got cancelled before it finished
+	#[error("cancelled")]
+	Cancelled,
+
 	///
 	#[error("git config error: {0}")]
 	GitConfig(String),
@@ -145,6 +180,10 @@ pub enum Error {
 	#[error("EasyCast error:{0}")]
 	EasyCast(#[from] easy_cast::Error),
 
+	///
+	#[error("regex error: {0}")]
+	Regex(#[from] regex::Error),
+
 	///
 	#[error("no parent of commit found")]
 	NoParent,
@@ -153,6 +192,13 @@ pub enum Error {
 	#[error("not on a branch")]
 	NoBranch,
 
+	///
+	#[error("'{0}' is a protected branch, {1} is blocked")]
+	ProtectedBranch(
+		String,
+		crate::sync::branch::protection::ProtectedAction,
+	),
+
 	///
 	#[error("rayon error: {0}")]
 	ThreadPool(#[from] rayon_core::ThreadPoolBuildError),
@@ -184,6 +230,14 @@ pub enum Error {
 	///
 	#[error("reword error: config commit.gpgsign=true detected.\ngpg signing is not supported for rewording commits with staged changes\ntry unstaging or stashing your changes")]
 	SignRewordLastCommitStaged,
+
+	///
+	#[error("stack error: config commit.gpgsign=true detected.\ngpg signing is not supported for rewriting the patch stack")]
+	SignStackRewrite,
+
+	///
+	#[error("rebase error: config commit.gpgsign=true detected.\ngpg signing is not supported for interactive rebases")]
+	SignRebaseInteractive,
 }
 
 ///
@@ -213,6 +267,54 @@ impl From<gix::discover::Error> for Error {
 	}
 }
 
+impl From<gix::filter::pipeline::convert_to_git::Error> for GixError {
+	fn from(
+		error: gix::filter::pipeline::convert_to_git::Error,
+	) -> Self {
+		Self::FilterToGit(Box::new(error))
+	}
+}
+
+impl From<gix::filter::pipeline::convert_to_git::Error> for Error {
+	fn from(
+		error: gix::filter::pipeline::convert_to_git::Error,
+	) -> Self {
+		Self::Gix(GixError::from(error))
+	}
+}
+
+impl From<gix::filter::pipeline::convert_to_worktree::Error>
+	for GixError
+{
+	fn from(
+		error: gix::filter::pipeline::convert_to_worktree::Error,
+	) -> Self {
+		Self::FilterToWorktree(Box::new(error))
+	}
+}
+
+impl From<gix::filter::pipeline::convert_to_worktree::Error>
+	for Error
+{
+	fn from(
+		error: gix::filter::pipeline::convert_to_worktree::Error,
+	) -> Self {
+		Self::Gix(GixError::from(error))
+	}
+}
+
+impl From<gix::repository::filter::pipeline::Error> for GixError {
+	fn from(error: gix::repository::filter::pipeline::Error) -> Self {
+		Self::FilterPipeline(Box::new(error))
+	}
+}
+
+impl From<gix::repository::filter::pipeline::Error> for Error {
+	fn from(error: gix::repository::filter::pipeline::Error) -> Self {
+		Self::Gix(GixError::from(error))
+	}
+}
+
 impl From<gix::head::peel::to_commit::Error> for Error {
 	fn from(error: gix::head::peel::to_commit::Error) -> Self {
 		Self::Gix(GixError::from(error))
@@ -3,12 +3,16 @@
 use crate::{
 	asyncjob::{AsyncJob, RunParams},
 	error::Result,
-	sync::remotes::fetch_all,
+	progress::Progress,
+	sync::remotes::{fetch_all, unshallow},
 	sync::{cred::BasicAuthCredential, RepoPath},
-	AsyncGitNotification, ProgressPercent,
+	AsyncGitNotification,
+};
+use crossbeam_channel::unbounded;
+use std::{
+	sync::{Arc, Mutex},
+	thread,
 };
-
-use std::sync::{Arc, Mutex};
 
 enum JobState {
 	Request(Option<BasicAuthCredential>),
@@ -20,6 +24,7 @@ enum JobState {
 pub struct AsyncFetchJob {
 	state: Arc<Mutex<Option<JobState>>>,
 	repo: RepoPath,
+	unshallow: bool,
 }
 
 ///
@@ -34,27 +39,90 @@ impl AsyncFetchJob {
 			state: Arc::new(Mutex::new(Some(JobState::Request(
 				basic_credential,
 			)))),
+			unshallow: false,
+		}
+	}
+
+	/// also fetches the history missing in a shallow clone
+	#[must_use]
+	pub const fn with_unshallow(mut self) -> Self {
+		self.unshallow = true;
+		self
+	}
+
+	///
+	pub fn result(&self) -> Option<Result<()>> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request(_) => None,
+					JobState::Response(result) => Some(result),
+				};
+			}
+		}
+
+		None
+	}
+}
+
+impl AsyncFetchJob {
+	fn update_progress(
+		params: &RunParams<AsyncGitNotification, Progress>,
+		progress: Progress,
+	) {
+		match params.set_progress(progress) {
+			Err(e) => log::error!("progress error: {e}"),
+			Ok(true) => {
+				if let Err(e) =
+					params.send(AsyncGitNotification::Fetch)
+				{
+					log::error!("send error: {e}");
+				}
+			}
+			Ok(false) => (),
 		}
 	}
 }
 
 impl AsyncJob for AsyncFetchJob {
 	type Notification = AsyncGitNotification;
-	type Progress = ProgressPercent;
+	type Progress = Progress;
 
 	fn run(
 		&mut self,
-		_params: RunParams<Self::Notification, Self::Progress>,
+		params: RunParams<Self::Notification, Self::Progress>,
 	) -> Result<Self::Notification> {
 		if let Ok(mut state) = self.state.lock() {
 			*state = state.take().map(|state| match state {
 				JobState::Request(basic_credentials) => {
-					//TODO: support progress
-					let result = fetch_all(
-						&self.repo,
-						&basic_credentials,
-						&None,
-					);
+					let (sender, receiver) = unbounded::<Progress>();
+
+					// reports the remotes done so far
+					let result = thread::scope(|s| {
+						s.spawn(|| {
+							for progress in receiver {
+								Self::update_progress(
+									&params, progress,
+								);
+							}
+						});
+
+						if self.unshallow {
+							unshallow(
+								&self.repo,
+								&basic_credentials,
+								&Some(sender),
+								Some(params.cancellation()),
+							)
+						} else {
+							fetch_all(
+								&self.repo,
+								&basic_credentials,
+								&Some(sender),
+								Some(params.cancellation()),
+							)
+						}
+					});
 
 					JobState::Response(result)
 				}
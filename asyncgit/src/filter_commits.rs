@@ -10,10 +10,7 @@ use crate::{
 	AsyncGitNotification, ProgressPercent,
 };
 use std::{
-	sync::{
-		atomic::{AtomicBool, AtomicUsize, Ordering},
-		Arc, Mutex,
-	},
+	sync::{atomic::AtomicUsize, Arc, Mutex},
 	time::{Duration, Instant},
 };
 
@@ -38,7 +35,6 @@ enum JobState {
 pub struct AsyncCommitFilterJob {
 	state: Arc<Mutex<Option<JobState>>>,
 	filter: SharedCommitFilterFn,
-	cancellation_flag: Arc<AtomicBool>,
 }
 
 ///
@@ -48,7 +44,6 @@ impl AsyncCommitFilterJob {
 		repo_path: RepoPath,
 		commits: Vec<CommitId>,
 		filter: SharedCommitFilterFn,
-		cancellation_flag: Arc<AtomicBool>,
 	) -> Self {
 		Self {
 			state: Arc::new(Mutex::new(Some(JobState::Request {
@@ -56,7 +51,6 @@ impl AsyncCommitFilterJob {
 				commits,
 			}))),
 			filter,
-			cancellation_flag,
 		}
 	}
 
@@ -123,10 +117,7 @@ impl AsyncCommitFilterJob {
 								std::sync::atomic::Ordering::Relaxed,
 							);
 
-								if self
-									.cancellation_flag
-									.load(Ordering::Relaxed)
-								{
+								if params.is_cancelled() {
 									return None;
 								}
 
@@ -44,14 +44,19 @@ It wraps libraries like git2 and gix.
 )]
 
 pub mod asyncjob;
+mod bisect;
 mod blame;
 mod branches;
 pub mod cached;
+mod checkout;
 mod commit_files;
+mod delete_branches;
 mod diff;
 mod error;
 mod fetch_job;
 mod filter_commits;
+mod post_hook;
+mod pre_push_hook;
 mod progress;
 mod pull;
 mod push;
@@ -60,32 +65,44 @@ pub mod remote_progress;
 pub mod remote_tags;
 mod revlog;
 mod status;
+mod submodules;
 pub mod sync;
 mod tags;
 mod treefiles;
+mod treesearch;
+mod worktrees;
 
 pub use crate::{
-	blame::{AsyncBlame, BlameParams},
+	bisect::AsyncBisectJob,
+	blame::{AsyncBlameJob, BlameParams},
 	branches::AsyncBranchesJob,
+	checkout::AsyncCheckoutJob,
 	commit_files::{AsyncCommitFiles, CommitFilesParams},
+	delete_branches::AsyncDeleteBranchesJob,
 	diff::{AsyncDiff, DiffParams, DiffType},
 	error::{Error, Result},
 	fetch_job::AsyncFetchJob,
 	filter_commits::{AsyncCommitFilterJob, CommitFilterResult},
-	progress::ProgressPercent,
+	post_hook::{AsyncPostHookJob, PostHook},
+	pre_push_hook::{AsyncPrePushJob, PrePushRequest},
+	progress::{
+		Progress, ProgressPercent, ProgressPhase, ReportProgress,
+	},
 	pull::{AsyncPull, FetchRequest},
 	push::{AsyncPush, PushRequest},
 	push_tags::{AsyncPushTags, PushTagsRequest},
-	remote_progress::{RemoteProgress, RemoteProgressState},
 	revlog::{AsyncLog, FetchStatus},
 	status::{AsyncStatus, StatusParams},
+	submodules::{AsyncSubmoduleJob, SubmoduleOperation},
 	sync::{
-		diff::{DiffLine, DiffLineType, FileDiff},
+		diff::{BinaryFiles, DiffLine, DiffLineType, FileDiff},
 		remotes::push::PushType,
 		status::{StatusItem, StatusItemType},
 	},
-	tags::AsyncTags,
+	tags::{AsyncTagListJob, AsyncTags},
 	treefiles::AsyncTreeFilesJob,
+	treesearch::{AsyncTreeSearchJob, TreeSearchResult},
+	worktrees::AsyncWorktreesJob,
 };
 pub use git2::message_prettify;
 use std::{
@@ -111,6 +128,8 @@ pub enum AsyncGitNotification {
 	///
 	Tags,
 	///
+	TagList,
+	///
 	Push,
 	///
 	PushTags,
@@ -127,7 +146,25 @@ pub enum AsyncGitNotification {
 	///
 	TreeFiles,
 	///
+	TreeSearch,
+	///
 	CommitFilter,
+	///
+	Bisect,
+	///
+	DeleteBranches,
+	///
+	PostHook,
+	/// a line printed by a running hook
+	HookOutput,
+	///
+	PrePush,
+	///
+	Checkout,
+	///
+	Worktrees,
+	///
+	Submodules,
 }
 
 /// helper function to calculate the hash of an arbitrary type that implements the `Hash` trait
@@ -0,0 +1,114 @@
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	error::Result,
+	sync::{
+		hooks_post_checkout, hooks_post_merge, CommitId, HookResult,
+		RepoPath,
+	},
+	AsyncGitNotification,
+};
+use std::sync::{Arc, Mutex};
+
+/// a hook run after git already changed the worktree, so it cannot
+/// stop anything anymore
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PostHook {
+	///
+	Checkout {
+		/// `HEAD` before the checkout, if there was one
+		old: Option<CommitId>,
+		/// `HEAD` after the checkout
+		new: CommitId,
+		/// `false` for a checkout of files
+		branch: bool,
+	},
+	///
+	Merge {
+		///
+		squash: bool,
+	},
+}
+
+impl PostHook {
+	/// the name of the hook
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::Checkout { .. } => "post-checkout",
+			Self::Merge { .. } => "post-merge",
+		}
+	}
+}
+
+enum JobState {
+	Request { repo: RepoPath, hook: PostHook },
+	Response(PostHook, Result<HookResult>),
+}
+
+/// runs a post-checkout or post-merge hook
+#[derive(Clone, Default)]
+pub struct AsyncPostHookJob {
+	state: Arc<Mutex<Option<JobState>>>,
+}
+
+///
+impl AsyncPostHookJob {
+	///
+	pub fn new(repo: RepoPath, hook: PostHook) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request {
+				repo,
+				hook,
+			}))),
+		}
+	}
+
+	/// the hook that ran and how it went
+	pub fn result(&self) -> Option<(PostHook, Result<HookResult>)> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request { .. } => None,
+					JobState::Response(hook, result) => {
+						Some((hook, result))
+					}
+				};
+			}
+		}
+
+		None
+	}
+}
+
+impl AsyncJob for AsyncPostHookJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		if let Ok(mut state) = self.state.lock() {
+			*state = state.take().map(|state| match state {
+				JobState::Request { repo, hook } => {
+					let result = match hook {
+						PostHook::Checkout { old, new, branch } => {
+							hooks_post_checkout(
+								&repo, old, new, branch,
+							)
+						}
+						PostHook::Merge { squash } => {
+							hooks_post_merge(&repo, squash)
+						}
+					};
+
+					JobState::Response(hook, result)
+				}
+				JobState::Response(hook, result) => {
+					JobState::Response(hook, result)
+				}
+			});
+		}
+
+		Ok(AsyncGitNotification::PostHook)
+	}
+}
@@ -0,0 +1,116 @@
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	error::Result,
+	sync::{
+		cred::BasicAuthCredential, hooks_pre_push_streamed,
+		HookOutput, HookResult, PrePushTarget, RepoPath,
+	},
+	AsyncGitNotification,
+};
+use std::sync::{Arc, Mutex};
+
+/// what gets pushed
+#[derive(Clone, Debug)]
+pub struct PrePushRequest {
+	///
+	pub remote: String,
+	/// `None` for pushing tags
+	pub branch: Option<String>,
+	///
+	pub delete: bool,
+	///
+	pub basic_credential: Option<BasicAuthCredential>,
+}
+
+enum JobState {
+	Request {
+		repo: RepoPath,
+		request: PrePushRequest,
+	},
+	Response(Result<HookResult>),
+}
+
+/// runs the pre-push hook, collecting what it prints while it runs
+#[derive(Clone, Default)]
+pub struct AsyncPrePushJob {
+	state: Arc<Mutex<Option<JobState>>>,
+	output: Arc<Mutex<Vec<HookOutput>>>,
+}
+
+///
+impl AsyncPrePushJob {
+	///
+	pub fn new(repo: RepoPath, request: PrePushRequest) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request {
+				repo,
+				request,
+			}))),
+			output: Arc::default(),
+		}
+	}
+
+	/// the lines printed so far
+	pub fn output(&self) -> Vec<HookOutput> {
+		self.output
+			.lock()
+			.map(|output| output.clone())
+			.unwrap_or_default()
+	}
+
+	///
+	pub fn result(&self) -> Option<Result<HookResult>> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request { .. } => None,
+					JobState::Response(result) => Some(result),
+				};
+			}
+		}
+
+		None
+	}
+}
+
+impl AsyncJob for AsyncPrePushJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		// the hook runs without holding the state
+		let request = self.state.lock()?.take();
+
+		if let Some(JobState::Request { repo, request }) = request {
+			let target = request.branch.as_ref().map_or(
+				PrePushTarget::Tags,
+				|branch| PrePushTarget::Branch {
+					branch,
+					delete: request.delete,
+				},
+			);
+
+			let result = hooks_pre_push_streamed(
+				&repo,
+				&request.remote,
+				&target,
+				request.basic_credential,
+				&mut |line| {
+					if let Ok(mut output) = self.output.lock() {
+						output.push(line);
+					}
+					params
+						.send(AsyncGitNotification::HookOutput)
+						.ok();
+				},
+			);
+
+			*self.state.lock()? = Some(JobState::Response(result));
+		}
+
+		Ok(AsyncGitNotification::PrePush)
+	}
+}
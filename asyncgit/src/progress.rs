@@ -28,6 +28,82 @@ impl ProgressPercent {
 	}
 }
 
+/// what a long running job is busy with
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub enum ProgressPhase {
+	/// nothing reported yet
+	#[default]
+	Preparing,
+	/// looking up what the remote has
+	CheckingRemote,
+	/// collecting the objects to send
+	AddingObjects,
+	/// compressing the objects to send
+	Deltas,
+	/// sending objects to the remote
+	Sending,
+	/// receiving objects from the remote
+	Receiving,
+	/// applying commits one by one
+	Rebasing,
+	/// comparing the files in the workdir with the index
+	Scanning,
+	/// waiting to try again after a network failure, `current` is
+	/// the attempt out of `total` retries
+	Retrying,
+	///
+	Done,
+}
+
+/// the progress every job reports, however it tracks it
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct Progress {
+	///
+	pub phase: ProgressPhase,
+	/// steps done in `phase`
+	pub current: usize,
+	/// steps of `phase`
+	pub total: usize,
+	/// bytes transferred so far by network jobs
+	pub bytes: Option<usize>,
+}
+
+impl Progress {
+	///
+	pub const fn new(
+		phase: ProgressPhase,
+		current: usize,
+		total: usize,
+	) -> Self {
+		Self {
+			phase,
+			current,
+			total,
+			bytes: None,
+		}
+	}
+
+	///
+	#[must_use]
+	pub const fn with_bytes(mut self, bytes: usize) -> Self {
+		self.bytes = Some(bytes);
+		self
+	}
+
+	/// of `phase`
+	pub fn percent(&self) -> ProgressPercent {
+		ProgressPercent::new(self.current, self.total)
+	}
+}
+
+/// implemented by the progress types of the different jobs (push,
+/// pull, push tags, submodule clones, status scans, rebase) so they
+/// can be shown the same way. fetch reports `Progress` itself
+pub trait ReportProgress {
+	///
+	fn report(&self) -> Progress;
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -51,4 +127,14 @@ mod tests {
 
 		assert_eq!(prog.progress, 20);
 	}
+
+	#[test]
+	fn test_progress_percent() {
+		let prog = Progress::new(ProgressPhase::Sending, 1, 4)
+			.with_bytes(512);
+
+		assert_eq!(prog.percent().progress, 25);
+		assert_eq!(prog.bytes, Some(512));
+		assert_eq!(Progress::default().percent().progress, 100);
+	}
 }
@@ -1,18 +1,21 @@
 use crate::{
+	asyncjob::{
+		spawn_job, AsyncJobControl, CancellationToken, JobPriority,
+		ProgressSubscribers,
+	},
 	error::{Error, Result},
+	progress::{Progress, ReportProgress},
+	remote_progress,
 	sync::{
 		cred::BasicAuthCredential,
 		remotes::push::push_raw,
 		remotes::push::{ProgressNotification, PushType},
 		RepoPath,
 	},
-	AsyncGitNotification, RemoteProgress,
-};
-use crossbeam_channel::{unbounded, Sender};
-use std::{
-	sync::{Arc, Mutex},
-	thread,
+	AsyncGitNotification,
 };
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use std::sync::{Arc, Mutex};
 
 ///
 #[derive(Default, Clone, Debug)]
@@ -40,6 +43,10 @@ pub struct AsyncPush {
 	state: Arc<Mutex<Option<PushState>>>,
 	last_result: Arc<Mutex<Option<String>>>,
 	progress: Arc<Mutex<Option<ProgressNotification>>>,
+	subscribers: ProgressSubscribers<ProgressNotification>,
+	/// of the running request
+	cancellation: Mutex<CancellationToken>,
+	priority: JobPriority,
 	sender: Sender<AsyncGitNotification>,
 	repo: RepoPath,
 }
@@ -55,14 +62,27 @@ impl AsyncPush {
 			state: Arc::new(Mutex::new(None)),
 			last_result: Arc::new(Mutex::new(None)),
 			progress: Arc::new(Mutex::new(None)),
+			subscribers: ProgressSubscribers::default(),
+			cancellation: Mutex::default(),
+			priority: JobPriority::High,
 			sender: sender.clone(),
 		}
 	}
 
+	/// the default `JobPriority::High` keeps the network out of the
+	/// shared thread pool
+	#[must_use]
+	pub const fn with_priority(
+		mut self,
+		priority: JobPriority,
+	) -> Self {
+		self.priority = priority;
+		self
+	}
+
 	///
-	pub fn is_pending(&self) -> Result<bool> {
-		let state = self.state.lock()?;
-		Ok(state.is_some())
+	pub fn is_pending(&self) -> bool {
+		self.state.lock().is_ok_and(|state| state.is_some())
 	}
 
 	///
@@ -72,36 +92,41 @@ impl AsyncPush {
 	}
 
 	///
-	pub fn progress(&self) -> Result<Option<RemoteProgress>> {
+	pub fn progress(&self) -> Result<Option<Progress>> {
 		let res = self.progress.lock()?;
-		Ok(res.as_ref().map(|progress| progress.clone().into()))
+		Ok(res.as_ref().map(ReportProgress::report))
 	}
 
 	///
 	pub fn request(&self, params: PushRequest) -> Result<()> {
 		log::trace!("request");
 
-		if self.is_pending()? {
+		if self.is_pending() {
 			return Ok(());
 		}
 
 		self.set_request(&params)?;
-		RemoteProgress::set_progress(&self.progress, None)?;
+		remote_progress::set_progress(&self.progress, None)?;
+
+		let cancellation = CancellationToken::default();
+		*self.cancellation.lock()? = cancellation.clone();
 
 		let arc_state = Arc::clone(&self.state);
 		let arc_res = Arc::clone(&self.last_result);
 		let arc_progress = Arc::clone(&self.progress);
+		let subscribers = self.subscribers.clone();
 		let sender = self.sender.clone();
 		let repo = self.repo.clone();
 
-		thread::spawn(move || {
+		spawn_job(self.priority, move || {
 			let (progress_sender, receiver) = unbounded();
 
-			let handle = RemoteProgress::spawn_receiver_thread(
+			let handle = remote_progress::spawn_receiver_thread(
 				AsyncGitNotification::Push,
 				sender.clone(),
 				receiver,
 				arc_progress,
+				subscribers,
 			);
 
 			let res = push_raw(
@@ -113,6 +138,7 @@ impl AsyncPush {
 				params.delete,
 				params.basic_credential.clone(),
 				Some(progress_sender.clone()),
+				Some(&cancellation),
 			);
 
 			progress_sender
@@ -164,7 +190,7 @@ impl AsyncPush {
 		*last_res = match res {
 			Ok(()) => None,
 			Err(e) => {
-				log::error!("push error: {e}",);
+				log::error!("push error: {e}");
 				Some(e.to_string())
 			}
 		};
@@ -172,3 +198,27 @@ impl AsyncPush {
 		Ok(())
 	}
 }
+
+impl AsyncJobControl for AsyncPush {
+	type Progress = ProgressNotification;
+
+	fn priority(&self) -> JobPriority {
+		self.priority
+	}
+
+	fn is_pending(&self) -> bool {
+		Self::is_pending(self)
+	}
+
+	fn cancel(&self) -> bool {
+		if let Ok(cancellation) = self.cancellation.lock() {
+			cancellation.cancel();
+		}
+
+		self.is_pending()
+	}
+
+	fn subscribe_progress(&self) -> Receiver<Self::Progress> {
+		self.subscribers.subscribe()
+	}
+}
@@ -1,17 +1,20 @@
 use crate::{
+	asyncjob::{
+		spawn_job, AsyncJobControl, CancellationToken, JobPriority,
+		ProgressSubscribers,
+	},
 	error::{Error, Result},
+	progress::{Progress, ReportProgress},
+	remote_progress,
 	sync::{
 		cred::BasicAuthCredential,
 		remotes::tags::{push_tags, PushTagsProgress},
 		RepoPath,
 	},
-	AsyncGitNotification, RemoteProgress,
-};
-use crossbeam_channel::{unbounded, Sender};
-use std::{
-	sync::{Arc, Mutex},
-	thread,
+	AsyncGitNotification,
 };
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use std::sync::{Arc, Mutex};
 
 ///
 #[derive(Default, Clone, Debug)]
@@ -31,6 +34,10 @@ pub struct AsyncPushTags {
 	state: Arc<Mutex<Option<PushState>>>,
 	last_result: Arc<Mutex<Option<String>>>,
 	progress: Arc<Mutex<Option<PushTagsProgress>>>,
+	subscribers: ProgressSubscribers<PushTagsProgress>,
+	/// of the running request
+	cancellation: Mutex<CancellationToken>,
+	priority: JobPriority,
 	sender: Sender<AsyncGitNotification>,
 	repo: RepoPath,
 }
@@ -46,14 +53,27 @@ impl AsyncPushTags {
 			state: Arc::new(Mutex::new(None)),
 			last_result: Arc::new(Mutex::new(None)),
 			progress: Arc::new(Mutex::new(None)),
+			subscribers: ProgressSubscribers::default(),
+			cancellation: Mutex::default(),
+			priority: JobPriority::High,
 			sender: sender.clone(),
 		}
 	}
 
+	/// the default `JobPriority::High` keeps the network out of the
+	/// shared thread pool
+	#[must_use]
+	pub const fn with_priority(
+		mut self,
+		priority: JobPriority,
+	) -> Self {
+		self.priority = priority;
+		self
+	}
+
 	///
-	pub fn is_pending(&self) -> Result<bool> {
-		let state = self.state.lock()?;
-		Ok(state.is_some())
+	pub fn is_pending(&self) -> bool {
+		self.state.lock().is_ok_and(|state| state.is_some())
 	}
 
 	///
@@ -63,36 +83,41 @@ impl AsyncPushTags {
 	}
 
 	///
-	pub fn progress(&self) -> Result<Option<PushTagsProgress>> {
+	pub fn progress(&self) -> Result<Option<Progress>> {
 		let res = self.progress.lock()?;
-		Ok(*res)
+		Ok(res.as_ref().map(ReportProgress::report))
 	}
 
 	///
 	pub fn request(&self, params: PushTagsRequest) -> Result<()> {
 		log::trace!("request");
 
-		if self.is_pending()? {
+		if self.is_pending() {
 			return Ok(());
 		}
 
 		self.set_request(&params)?;
-		RemoteProgress::set_progress(&self.progress, None)?;
+		remote_progress::set_progress(&self.progress, None)?;
+
+		let cancellation = CancellationToken::default();
+		*self.cancellation.lock()? = cancellation.clone();
 
 		let arc_state = Arc::clone(&self.state);
 		let arc_res = Arc::clone(&self.last_result);
 		let arc_progress = Arc::clone(&self.progress);
+		let subscribers = self.subscribers.clone();
 		let sender = self.sender.clone();
 		let repo = self.repo.clone();
 
-		thread::spawn(move || {
+		spawn_job(self.priority, move || {
 			let (progress_sender, receiver) = unbounded();
 
-			let handle = RemoteProgress::spawn_receiver_thread(
+			let handle = remote_progress::spawn_receiver_thread(
 				AsyncGitNotification::PushTags,
 				sender.clone(),
 				receiver,
 				arc_progress,
+				subscribers,
 			);
 
 			let res = push_tags(
@@ -100,6 +125,7 @@ impl AsyncPushTags {
 				params.remote.as_str(),
 				params.basic_credential.clone(),
 				Some(progress_sender),
+				Some(&cancellation),
 			);
 
 			handle.join().expect("joining thread failed");
@@ -155,3 +181,27 @@ impl AsyncPushTags {
 		Ok(())
 	}
 }
+
+impl AsyncJobControl for AsyncPushTags {
+	type Progress = PushTagsProgress;
+
+	fn priority(&self) -> JobPriority {
+		self.priority
+	}
+
+	fn is_pending(&self) -> bool {
+		Self::is_pending(self)
+	}
+
+	fn cancel(&self) -> bool {
+		if let Ok(cancellation) = self.cancellation.lock() {
+			cancellation.cancel();
+		}
+
+		self.is_pending()
+	}
+
+	fn subscribe_progress(&self) -> Receiver<Self::Progress> {
+		self.subscribers.subscribe()
+	}
+}
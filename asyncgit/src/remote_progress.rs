@@ -1,8 +1,9 @@
 //!
 
 use crate::{
+	asyncjob::ProgressSubscribers,
 	error::Result,
-	progress::ProgressPercent,
+	progress::{Progress, ProgressPhase, ReportProgress},
 	sync::remotes::push::{AsyncProgress, ProgressNotification},
 	AsyncGitNotification,
 };
@@ -13,136 +14,91 @@ use std::{
 	thread::{self, JoinHandle},
 };
 
-/// used for push/pull
-#[derive(Clone, Debug)]
-pub enum RemoteProgressState {
-	///
-	PackingAddingObject,
-	///
-	PackingDeltafiction,
-	///
-	Pushing,
-	/// fetch progress
-	Transfer,
-	/// remote progress done
-	Done,
-}
-
-///
-#[derive(Clone, Debug)]
-pub struct RemoteProgress {
-	///
-	pub state: RemoteProgressState,
-	///
-	pub progress: ProgressPercent,
-}
-
-impl RemoteProgress {
-	///
-	pub fn new(
-		state: RemoteProgressState,
-		current: usize,
-		total: usize,
-	) -> Self {
-		Self {
-			state,
-			progress: ProgressPercent::new(current, total),
-		}
-	}
-
-	///
-	pub const fn get_progress_percent(&self) -> u8 {
-		self.progress.progress
-	}
-
-	pub(crate) fn set_progress<T>(
-		progress: &Arc<Mutex<Option<T>>>,
-		state: Option<T>,
-	) -> Result<()> {
-		let mut progress = progress.lock()?;
+pub(crate) fn set_progress<T>(
+	progress: &Arc<Mutex<Option<T>>>,
+	state: Option<T>,
+) -> Result<()> {
+	let mut progress = progress.lock()?;
 
-		*progress = state;
+	*progress = state;
 
-		Ok(())
-	}
+	Ok(())
+}
 
-	/// spawn thread to listen to progress notifications coming in from blocking remote git method (fetch/push)
-	pub(crate) fn spawn_receiver_thread<
-		T: 'static + AsyncProgress,
-	>(
-		notification_type: AsyncGitNotification,
-		sender: Sender<AsyncGitNotification>,
-		receiver: Receiver<T>,
-		progress: Arc<Mutex<Option<T>>>,
-	) -> JoinHandle<()> {
-		thread::spawn(move || loop {
-			let incoming = receiver.recv();
-			match incoming {
-				Ok(update) => {
-					Self::set_progress(
-						&progress,
-						Some(update.clone()),
-					)
+/// spawn thread to listen to progress notifications coming in from blocking remote git method (fetch/push)
+pub(crate) fn spawn_receiver_thread<T: 'static + AsyncProgress>(
+	notification_type: AsyncGitNotification,
+	sender: Sender<AsyncGitNotification>,
+	receiver: Receiver<T>,
+	progress: Arc<Mutex<Option<T>>>,
+	subscribers: ProgressSubscribers<T>,
+) -> JoinHandle<()> {
+	thread::spawn(move || loop {
+		let incoming = receiver.recv();
+		match incoming {
+			Ok(update) => {
+				set_progress(&progress, Some(update.clone()))
 					.expect("set progress failed");
-					sender
-						.send(notification_type)
-						.expect("Notification error");
+				subscribers.notify(&update);
+				sender
+					.send(notification_type)
+					.expect("Notification error");
 
-					thread::yield_now();
+				thread::yield_now();
 
-					if update.is_done() {
-						break;
-					}
-				}
-				Err(e) => {
-					log::error!(
-						"remote progress receiver error: {e}",
-					);
+				if update.is_done() {
 					break;
 				}
 			}
-		})
-	}
+			Err(e) => {
+				log::error!("remote progress receiver error: {e}");
+				break;
+			}
+		}
+	})
 }
 
-impl From<ProgressNotification> for RemoteProgress {
-	fn from(progress: ProgressNotification) -> Self {
-		match progress {
-			ProgressNotification::Packing {
+impl ReportProgress for ProgressNotification {
+	fn report(&self) -> Progress {
+		match *self {
+			Self::Packing {
 				stage,
 				current,
 				total,
 			} => match stage {
-				PackBuilderStage::AddingObjects => Self::new(
-					RemoteProgressState::PackingAddingObject,
+				PackBuilderStage::AddingObjects => Progress::new(
+					ProgressPhase::AddingObjects,
 					current,
 					total,
 				),
-				PackBuilderStage::Deltafication => Self::new(
-					RemoteProgressState::PackingDeltafiction,
+				PackBuilderStage::Deltafication => Progress::new(
+					ProgressPhase::Deltas,
 					current,
 					total,
 				),
 			},
-			ProgressNotification::PushTransfer {
-				current,
-				total,
-				..
-			} => Self::new(
-				RemoteProgressState::Pushing,
+			Self::PushTransfer {
 				current,
 				total,
-			),
-			ProgressNotification::Transfer {
+				bytes,
+			} => Progress::new(ProgressPhase::Sending, current, total)
+				.with_bytes(bytes),
+			Self::Transfer {
 				objects,
 				total_objects,
-				..
-			} => Self::new(
-				RemoteProgressState::Transfer,
+				bytes,
+			} => Progress::new(
+				ProgressPhase::Receiving,
 				objects,
 				total_objects,
+			)
+			.with_bytes(bytes),
+			Self::Retry { attempt, retries } => Progress::new(
+				ProgressPhase::Retrying,
+				attempt,
+				retries,
 			),
-			_ => Self::new(RemoteProgressState::Done, 1, 1),
+			_ => Progress::new(ProgressPhase::Done, 1, 1),
 		}
 	}
 }
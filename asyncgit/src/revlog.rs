@@ -39,6 +39,8 @@ pub struct AsyncLogResult {
 pub struct AsyncLog {
 	current: Arc<Mutex<AsyncLogResult>>,
 	current_head: Arc<Mutex<Option<CommitId>>>,
+	/// whether the walked history was cut off by a shallow clone
+	shallow: AtomicBool,
 	sender: Sender<AsyncGitNotification>,
 	pending: Arc<AtomicBool>,
 	background: Arc<AtomicBool>,
@@ -65,6 +67,7 @@ impl AsyncLog {
 				duration: Duration::default(),
 			})),
 			current_head: Arc::new(Mutex::new(None)),
+			shallow: AtomicBool::new(false),
 			sender: sender.clone(),
 			pending: Arc::new(AtomicBool::new(false)),
 			background: Arc::new(AtomicBool::new(false)),
@@ -135,9 +138,15 @@ impl AsyncLog {
 		Ok(*self.current_head.lock()?)
 	}
 
-	///
+	/// whether head moved or a shallow clone got its history since
+	/// the last walk
 	fn head_changed(&self) -> Result<bool> {
-		if let Ok(head) = repo(&self.repo)?.head() {
+		let repo = repo(&self.repo)?;
+		if repo.is_shallow() != self.shallow.load(Ordering::Relaxed) {
+			return Ok(true);
+		}
+
+		if let Ok(head) = repo.head() {
 			return Ok(
 				head.target() != self.current_head()?.map(Into::into)
 			);
@@ -168,7 +177,9 @@ impl AsyncLog {
 		let filter = self.filter.clone();
 		let repo_path = self.repo.clone();
 
-		if let Ok(head) = repo(&self.repo)?.head() {
+		let repo = repo(&self.repo)?;
+		self.shallow.store(repo.is_shallow(), Ordering::Relaxed);
+		if let Ok(head) = repo.head() {
 			*self.current_head.lock()? =
 				head.target().map(CommitId::new);
 		}
@@ -1,21 +1,40 @@
 use crate::{
+	asyncjob::{
+		spawn_job, AsyncJobControl, CancellationToken, JobPriority,
+		ProgressSubscribers,
+	},
 	error::Result,
 	hash,
+	progress::{Progress, ProgressPhase, ReportProgress},
 	sync::{
-		self, status::StatusType, RepoPath, ShowUntrackedFilesConfig,
+		self,
+		status::{ScanProgress, StatusType},
+		RepoPath, ShowUntrackedFilesConfig,
 	},
 	AsyncGitNotification, StatusItem,
 };
-use crossbeam_channel::Sender;
+use crossbeam_channel::{
+	bounded, Receiver, RecvTimeoutError, Sender,
+};
 use std::{
+	collections::HashSet,
 	hash::Hash,
+	path::Path,
 	sync::{
-		atomic::{AtomicUsize, Ordering},
+		atomic::{AtomicBool, AtomicUsize, Ordering},
 		Arc, Mutex,
 	},
-	time::{SystemTime, UNIX_EPOCH},
+	thread,
+	time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
+/// `fetch_changed` scans everything again at least this often, in
+/// case a change was missed
+const FULL_SCAN_INTERVAL: Duration = Duration::from_secs(60);
+
+/// how often the subscribers hear about a running scan
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
 fn current_tick() -> u128 {
 	SystemTime::now()
 		.duration_since(UNIX_EPOCH)
@@ -34,30 +53,98 @@ pub struct StatusParams {
 	tick: u128,
 	status_type: StatusType,
 	config: Option<ShowUntrackedFilesConfig>,
+	show_ignored: bool,
 }
 
 impl StatusParams {
-	///
+	/// `show_ignored` adds the ignored files to a workdir status
 	pub fn new(
 		status_type: StatusType,
 		config: Option<ShowUntrackedFilesConfig>,
+		show_ignored: bool,
 	) -> Self {
 		Self {
 			tick: current_tick(),
 			status_type,
 			config,
+			show_ignored,
 		}
 	}
+
+	/// identifies what a full scan of these params finds
+	fn scan(&self) -> u64 {
+		hash(&(self.status_type, self.config, self.show_ignored))
+	}
 }
 
 struct Request<R, A>(R, Option<A>);
 
+/// what a spawned request shares with the `AsyncStatus`
+struct ScanControl {
+	cancellation: CancellationToken,
+	progress: Arc<Mutex<Option<ScanProgress>>>,
+	subscribers: ProgressSubscribers<Progress>,
+}
+
+impl ScanControl {
+	/// runs `scan`, telling the subscribers how far it got every
+	/// `PROGRESS_INTERVAL` until it returns
+	fn run<T: Send>(
+		&self,
+		scan: impl FnOnce(&ScanProgress) -> Result<T> + Send,
+	) -> Result<T> {
+		let progress = ScanProgress::default();
+		*self.progress.lock()? = Some(progress.clone());
+
+		let (stop, stopped) = bounded::<()>(0);
+		let res = thread::scope(|s| {
+			s.spawn(|| {
+				let mut last = None;
+				while matches!(
+					stopped.recv_timeout(PROGRESS_INTERVAL),
+					Err(RecvTimeoutError::Timeout)
+				) {
+					let report = progress.report();
+					if last != Some(report) {
+						self.subscribers.notify(&report);
+						last = Some(report);
+					}
+				}
+			});
+
+			let res = scan(&progress);
+			drop(stop);
+			res
+		});
+
+		*self.progress.lock()? = None;
+		self.subscribers.notify(&Progress::new(
+			ProgressPhase::Done,
+			1,
+			1,
+		));
+
+		res
+	}
+}
+
 ///
 pub struct AsyncStatus {
 	current: Arc<Mutex<Request<u64, Status>>>,
 	last: Arc<Mutex<Status>>,
 	sender: Sender<AsyncGitNotification>,
 	pending: Arc<AtomicUsize>,
+	/// when and with which `StatusParams::scan` `last` was fully
+	/// scanned
+	last_scan: Arc<Mutex<Option<(Instant, u64)>>>,
+	/// set if changes could not be applied incrementally
+	needs_scan: Arc<AtomicBool>,
+	/// of the latest request
+	cancellation: Mutex<CancellationToken>,
+	/// of the running full scan
+	progress: Arc<Mutex<Option<ScanProgress>>>,
+	subscribers: ProgressSubscribers<Progress>,
+	priority: JobPriority,
 	repo: RepoPath,
 }
 
@@ -73,9 +160,25 @@ impl AsyncStatus {
 			last: Arc::new(Mutex::new(Status::default())),
 			sender,
 			pending: Arc::new(AtomicUsize::new(0)),
+			last_scan: Arc::default(),
+			needs_scan: Arc::default(),
+			cancellation: Mutex::default(),
+			progress: Arc::default(),
+			subscribers: ProgressSubscribers::default(),
+			priority: JobPriority::default(),
 		}
 	}
 
+	///
+	#[must_use]
+	pub const fn with_priority(
+		mut self,
+		priority: JobPriority,
+	) -> Self {
+		self.priority = priority;
+		self
+	}
+
 	///
 	pub fn last(&self) -> Result<Status> {
 		let last = self.last.lock()?;
@@ -87,6 +190,12 @@ impl AsyncStatus {
 		self.pending.load(Ordering::Relaxed) > 0
 	}
 
+	/// of the running full scan
+	pub fn progress(&self) -> Result<Option<Progress>> {
+		let progress = self.progress.lock()?;
+		Ok(progress.as_ref().map(ReportProgress::report))
+	}
+
 	///
 	pub fn fetch(
 		&self,
@@ -120,20 +229,22 @@ impl AsyncStatus {
 		let arc_last = Arc::clone(&self.last);
 		let sender = self.sender.clone();
 		let arc_pending = Arc::clone(&self.pending);
-		let status_type = params.status_type;
-		let config = params.config;
+		let arc_last_scan = Arc::clone(&self.last_scan);
+		let params = *params;
 		let repo = self.repo.clone();
+		let control = self.new_control()?;
 
 		self.pending.fetch_add(1, Ordering::Relaxed);
 
-		rayon_core::spawn(move || {
+		spawn_job(self.priority, move || {
 			if let Err(e) = Self::fetch_helper(
 				&repo,
-				status_type,
-				config,
+				params,
 				hash_request,
 				&arc_current,
 				&arc_last,
+				&arc_last_scan,
+				&control,
 			) {
 				log::error!("fetch_helper: {e}");
 			}
@@ -148,21 +259,166 @@ impl AsyncStatus {
 		Ok(None)
 	}
 
+	/// brings the result of the last `fetch` up to date after only
+	/// `paths` (relative to the workdir) changed, looking at nothing
+	/// else if they are all tracked. scans everything instead if
+	/// `params` differ from that fetch or it is older than
+	/// `FULL_SCAN_INTERVAL`
+	pub fn fetch_changed(
+		&self,
+		params: &StatusParams,
+		paths: &[String],
+	) -> Result<()> {
+		if self.is_pending() {
+			log::trace!("changes blocked, still pending");
+			self.needs_scan.store(true, Ordering::Relaxed);
+			return Ok(());
+		}
+
+		let incremental = !self
+			.needs_scan
+			.swap(false, Ordering::Relaxed)
+			&& params.status_type == StatusType::WorkingDir
+			&& self.last_scan.lock()?.is_some_and(|(at, scan)| {
+				scan == params.scan()
+					&& at.elapsed() < FULL_SCAN_INTERVAL
+			});
+
+		if !incremental {
+			return self.fetch(params).map(|_| ());
+		}
+
+		log::trace!("changes: {paths:?}");
+
+		let arc_current = Arc::clone(&self.current);
+		let arc_last = Arc::clone(&self.last);
+		let arc_last_scan = Arc::clone(&self.last_scan);
+		let arc_needs_scan = Arc::clone(&self.needs_scan);
+		let sender = self.sender.clone();
+		let arc_pending = Arc::clone(&self.pending);
+		let params = *params;
+		let paths = paths.to_vec();
+		let repo = self.repo.clone();
+		let control = self.new_control()?;
+
+		self.pending.fetch_add(1, Ordering::Relaxed);
+
+		spawn_job(self.priority, move || {
+			let res =
+				sync::status::get_status_of_tracked(&repo, &paths)
+					.and_then(|items| {
+						// new files, their folders might show up
+						// instead
+						items.map_or_else(
+							|| {
+								Self::fetch_helper(
+									&repo,
+									params,
+									hash(&params),
+									&arc_current,
+									&arc_last,
+									&arc_last_scan,
+									&control,
+								)
+							},
+							|items| {
+								if control.cancellation.is_cancelled()
+								{
+									arc_needs_scan.store(
+										true,
+										Ordering::Relaxed,
+									);
+									return Ok(());
+								}
+
+								Self::merge_changes(
+									&arc_last, &paths, items,
+								)
+							},
+						)
+					});
+
+			if let Err(e) = res {
+				log::error!("fetch_changed: {e}");
+				arc_needs_scan.store(true, Ordering::Relaxed);
+			}
+
+			arc_pending.fetch_sub(1, Ordering::Relaxed);
+
+			sender
+				.send(AsyncGitNotification::Status)
+				.expect("error sending status");
+		});
+
+		Ok(())
+	}
+
+	/// for the request about to be spawned
+	fn new_control(&self) -> Result<ScanControl> {
+		let cancellation = CancellationToken::default();
+		*self.cancellation.lock()? = cancellation.clone();
+
+		Ok(ScanControl {
+			cancellation,
+			progress: Arc::clone(&self.progress),
+			subscribers: self.subscribers.clone(),
+		})
+	}
+
+	/// replaces the items of `paths` in `last` with `items`
+	fn merge_changes(
+		arc_last: &Arc<Mutex<Status>>,
+		paths: &[String],
+		items: Vec<StatusItem>,
+	) -> Result<()> {
+		let paths: HashSet<&str> =
+			paths.iter().map(String::as_str).collect();
+
+		let mut last = arc_last.lock()?;
+		last.items
+			.retain(|item| !paths.contains(item.path.as_str()));
+		last.items.extend(items);
+		last.items.sort_by(|a, b| {
+			Path::new(&a.path).cmp(Path::new(&b.path))
+		});
+
+		Ok(())
+	}
+
 	fn fetch_helper(
 		repo: &RepoPath,
-		status_type: StatusType,
-		config: Option<ShowUntrackedFilesConfig>,
+		params: StatusParams,
 		hash_request: u64,
 		arc_current: &Arc<Mutex<Request<u64, Status>>>,
 		arc_last: &Arc<Mutex<Status>>,
+		arc_last_scan: &Arc<Mutex<Option<(Instant, u64)>>>,
+		control: &ScanControl,
 	) -> Result<()> {
-		let res = Self::get_status(repo, status_type, config)?;
+		let started = Instant::now();
+		let res = control.run(|progress| {
+			Self::get_status(
+				repo,
+				params.status_type,
+				params.config,
+				params.show_ignored,
+				progress,
+			)
+		})?;
 		log::trace!(
-			"status fetched: {hash_request} (type: {status_type:?})",
+			"status fetched: {hash_request} (type: {:?})",
+			params.status_type
 		);
 
 		{
 			let mut current = arc_current.lock()?;
+			if control.cancellation.is_cancelled() {
+				// the same params have to scan again
+				if current.0 == hash_request {
+					current.0 = 0;
+				}
+				return Ok(());
+			}
+
 			if current.0 == hash_request {
 				current.1 = Some(res.clone());
 			}
@@ -173,6 +429,8 @@ impl AsyncStatus {
 			*last = res;
 		}
 
+		*arc_last_scan.lock()? = Some((started, params.scan()));
+
 		Ok(())
 	}
 
@@ -180,13 +438,50 @@ impl AsyncStatus {
 		repo: &RepoPath,
 		status_type: StatusType,
 		config: Option<ShowUntrackedFilesConfig>,
+		show_ignored: bool,
+		progress: &ScanProgress,
 	) -> Result<Status> {
-		Ok(Status {
-			items: sync::status::get_status(
-				repo,
-				status_type,
-				config,
-			)?,
-		})
+		let mut items = sync::status::get_status_with_progress(
+			repo,
+			status_type,
+			config,
+			progress,
+		)?;
+
+		if show_ignored && status_type != StatusType::Stage {
+			items.extend(sync::get_ignored_files(repo)?);
+			items.sort_by(|a, b| {
+				Path::new(&a.path).cmp(Path::new(&b.path))
+			});
+		}
+
+		Ok(Status { items })
+	}
+}
+
+impl AsyncJobControl for AsyncStatus {
+	/// of the full scans, `fetch_changed` looking at a few tracked
+	/// files only reports nothing
+	type Progress = Progress;
+
+	fn priority(&self) -> JobPriority {
+		self.priority
+	}
+
+	fn is_pending(&self) -> bool {
+		Self::is_pending(self)
+	}
+
+	/// the running scan finishes but its result gets dropped
+	fn cancel(&self) -> bool {
+		if let Ok(cancellation) = self.cancellation.lock() {
+			cancellation.cancel();
+		}
+
+		self.is_pending()
+	}
+
+	fn subscribe_progress(&self) -> Receiver<Self::Progress> {
+		self.subscribers.subscribe()
 	}
 }
@@ -0,0 +1,177 @@
+use crate::{
+	asyncjob::{AsyncJob, CancellationToken, RunParams},
+	error::Result,
+	progress::{Progress, ReportProgress},
+	sync::{
+		add_submodule, init_submodule,
+		remotes::push::ProgressNotification, remove_submodule,
+		sync_submodule, update_submodule, RepoPath,
+	},
+	AsyncGitNotification,
+};
+use crossbeam_channel::{unbounded, Sender};
+use std::{
+	sync::{Arc, Mutex},
+	thread,
+};
+
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SubmoduleOperation {
+	/// checkout recorded commit, optionally including nested ones
+	Update {
+		///
+		name: String,
+		///
+		recursive: bool,
+	},
+	///
+	Init(String),
+	///
+	Sync(String),
+	///
+	Add {
+		///
+		url: String,
+		///
+		path: Option<String>,
+	},
+	///
+	Remove(String),
+}
+
+enum JobState {
+	Request(RepoPath, SubmoduleOperation),
+	Response(Result<()>),
+}
+
+/// runs a submodule operation in the background
+#[derive(Clone, Default)]
+pub struct AsyncSubmoduleJob {
+	state: Arc<Mutex<Option<JobState>>>,
+}
+
+///
+impl AsyncSubmoduleJob {
+	///
+	pub fn new(
+		repo: RepoPath,
+		operation: SubmoduleOperation,
+	) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request(
+				repo, operation,
+			)))),
+		}
+	}
+
+	///
+	pub fn result(&self) -> Option<Result<()>> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request(..) => None,
+					JobState::Response(result) => Some(result),
+				};
+			}
+		}
+
+		None
+	}
+}
+
+/// the clones of `Update` and `Add` report to `progress_sender`
+fn run_operation(
+	repo: &RepoPath,
+	operation: &SubmoduleOperation,
+	progress_sender: Sender<ProgressNotification>,
+	cancellation: &CancellationToken,
+) -> Result<()> {
+	match operation {
+		SubmoduleOperation::Update { name, recursive } => {
+			update_submodule(
+				repo,
+				name,
+				*recursive,
+				&Some(progress_sender),
+				Some(cancellation),
+			)
+		}
+		SubmoduleOperation::Init(name) => init_submodule(repo, name),
+		SubmoduleOperation::Sync(name) => sync_submodule(repo, name),
+		SubmoduleOperation::Add { url, path } => add_submodule(
+			repo,
+			url,
+			path.as_deref(),
+			&Some(progress_sender),
+			Some(cancellation),
+		),
+		SubmoduleOperation::Remove(name) => {
+			remove_submodule(repo, name)
+		}
+	}
+}
+
+impl AsyncSubmoduleJob {
+	fn update_progress(
+		params: &RunParams<AsyncGitNotification, Progress>,
+		progress: Progress,
+	) {
+		match params.set_progress(progress) {
+			Err(e) => log::error!("progress error: {e}"),
+			Ok(true) => {
+				if let Err(e) =
+					params.send(AsyncGitNotification::Submodules)
+				{
+					log::error!("send error: {e}");
+				}
+			}
+			Ok(false) => (),
+		}
+	}
+}
+
+impl AsyncJob for AsyncSubmoduleJob {
+	type Notification = AsyncGitNotification;
+	type Progress = Progress;
+
+	fn run(
+		&mut self,
+		params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		if let Ok(mut state) = self.state.lock() {
+			*state = state.take().map(|state| match state {
+				JobState::Request(repo, operation) => {
+					let (sender, receiver) =
+						unbounded::<ProgressNotification>();
+
+					// reports the transfer of a clone
+					let result = thread::scope(|s| {
+						s.spawn(|| {
+							for notification in receiver {
+								Self::update_progress(
+									&params,
+									notification.report(),
+								);
+							}
+						});
+
+						run_operation(
+							&repo,
+							&operation,
+							sender,
+							params.cancellation(),
+						)
+					});
+
+					JobState::Response(result)
+				}
+				JobState::Response(result) => {
+					JobState::Response(result)
+				}
+			});
+		}
+
+		Ok(AsyncGitNotification::Submodules)
+	}
+}
@@ -0,0 +1,496 @@
+//! `git absorb`: staged hunks become fixup commits of the commits
+//! that last touched their lines
+
+use super::{
+	commit::commit_tree,
+	rebase::RebaseState,
+	rebase_interactive::{RebaseAction, RebaseTodo},
+	repository::repo,
+	stack::{patch_stack, rewrite},
+	CommitId, RepoPath,
+};
+use crate::error::{Error, Result};
+use git2::{
+	build::TreeUpdateBuilder, BlameOptions, Delta, DiffOptions,
+	FileMode, Oid, Patch, Repository,
+};
+use scopetime::scope_time;
+use std::{collections::HashMap, path::Path};
+
+/// commits looked at when the branch has no upstream to limit them
+const MAX_CANDIDATES: usize = 10;
+
+/// what `absorb` did
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AbsorbResult {
+	/// created fixup commits, empty when squashed
+	pub fixups: Vec<CommitId>,
+	/// commits that got changes absorbed
+	pub targets: usize,
+	/// hunks absorbed
+	pub absorbed: usize,
+	/// hunks left staged as no single commit could be told apart
+	pub left: usize,
+	/// squashing stopped on conflicts, the rebase is to be continued
+	/// or aborted
+	pub conflicted: bool,
+}
+
+/// a staged hunk of a modified file, in zero context
+struct StagedHunk {
+	path: String,
+	/// first line in `HEAD` replaced (1-based) or the line the hunk
+	/// is added after when it removes nothing
+	old_start: usize,
+	old_lines: usize,
+	added: Vec<u8>,
+}
+
+/// commits of the branch hunks can be absorbed into, oldest first,
+/// and the commit they are based on
+struct Candidates {
+	base: Oid,
+	commits: Vec<Oid>,
+}
+
+/// commits the staged hunks as `fixup!` commits of the commits that
+/// last touched their lines.
+///
+/// with `autosquash` the fixups are folded in right away. hunks that
+/// cannot be mapped to a single commit stay staged
+pub fn absorb(
+	repo_path: &RepoPath,
+	autosquash: bool,
+) -> Result<AbsorbResult> {
+	scope_time!("absorb");
+
+	let repo = repo(repo_path)?;
+
+	if repo.index()?.has_conflicts() {
+		return Err(Error::Generic(String::from(
+			"cannot absorb with conflicts in the index",
+		)));
+	}
+
+	let candidates = candidates(&repo)?;
+	let hunks = staged_hunks(&repo)?;
+	let total = hunks.len();
+
+	let mut groups: Vec<(Oid, Vec<StagedHunk>)> = Vec::new();
+	let mut blames = HashMap::new();
+	for hunk in hunks {
+		if let Some(target) =
+			target_of(&repo, &candidates, &mut blames, &hunk)?
+		{
+			if let Some((_, group)) =
+				groups.iter_mut().find(|(id, _)| *id == target)
+			{
+				group.push(hunk);
+			} else {
+				groups.push((target, vec![hunk]));
+			}
+		}
+	}
+
+	// fixups in the order of their targets
+	groups.sort_by_key(|(target, _)| {
+		candidates.commits.iter().position(|id| id == target)
+	});
+
+	let absorbed = groups.iter().map(|(_, hunks)| hunks.len()).sum();
+	let mut result = AbsorbResult {
+		fixups: Vec::new(),
+		targets: groups.len(),
+		absorbed,
+		left: total - absorbed,
+		conflicted: false,
+	};
+
+	if groups.is_empty() {
+		return Ok(result);
+	}
+
+	if autosquash
+		&& repo.config()?.get_bool("commit.gpgsign").unwrap_or(false)
+	{
+		return Err(Error::SignStackRewrite);
+	}
+
+	let head = repo.head()?.peel_to_commit()?;
+	let mut applied: Vec<&StagedHunk> = Vec::new();
+	let mut fixups = Vec::new();
+
+	for (target, hunks) in &groups {
+		applied.extend(hunks);
+
+		let tree = tree_with_hunks(&repo, &head.tree()?, &applied)?;
+		let summary = repo
+			.find_commit(*target)?
+			.summary()
+			.unwrap_or_default()
+			.to_string();
+
+		let fixup = commit_tree(
+			&repo,
+			&repo.find_tree(tree)?,
+			&format!("fixup! {summary}"),
+		)?;
+		fixups.push((*target, fixup.get_oid()));
+	}
+
+	if autosquash {
+		let mut todo = Vec::new();
+		for id in &candidates.commits {
+			todo.push(RebaseTodo::pick((*id).into()));
+			todo.extend(
+				fixups.iter().filter(|(target, _)| target == id).map(
+					|(_, fixup)| RebaseTodo {
+						id: (*fixup).into(),
+						action: RebaseAction::Fixup,
+						message: None,
+					},
+				),
+			);
+		}
+
+		match rewrite(&repo, candidates.base, &todo) {
+			Ok(state) => {
+				result.conflicted = state == RebaseState::Conflicted;
+			}
+			Err(e) => {
+				return Err(Error::Generic(format!(
+					"created the fixup commits but squashing failed: {e}"
+				)));
+			}
+		}
+	} else {
+		result.fixups =
+			fixups.into_iter().map(|(_, id)| id.into()).collect();
+	}
+
+	Ok(result)
+}
+
+/// the patch stack over the upstream or the last commits of HEAD up
+/// to the first merge
+fn candidates(repo: &Repository) -> Result<Candidates> {
+	if let Ok(stack) = patch_stack(repo) {
+		return Ok(Candidates {
+			base: stack.base.get_oid(),
+			commits: stack
+				.commits
+				.iter()
+				.map(|id| id.get_oid())
+				.collect(),
+		});
+	}
+
+	let mut commits = Vec::new();
+	let mut commit = repo.head()?.peel_to_commit()?;
+	let base = loop {
+		if commit.parent_count() != 1
+			|| commits.len() == MAX_CANDIDATES
+		{
+			break commit.id();
+		}
+		commits.push(commit.id());
+		commit = commit.parent(0)?;
+	};
+	commits.reverse();
+
+	Ok(Candidates { base, commits })
+}
+
+/// hunks of the staged changes of files modified in place
+fn staged_hunks(repo: &Repository) -> Result<Vec<StagedHunk>> {
+	let head = repo.head()?.peel_to_tree()?;
+	let mut opts = DiffOptions::new();
+	opts.context_lines(0);
+	let diff =
+		repo.diff_tree_to_index(Some(&head), None, Some(&mut opts))?;
+
+	let mut hunks = Vec::new();
+
+	for idx in 0..diff.deltas().len() {
+		let Some(file_patch) = Patch::from_diff(&diff, idx)? else {
+			continue;
+		};
+		let delta = file_patch.delta();
+		if delta.status() != Delta::Modified
+			|| delta.flags().is_binary()
+		{
+			continue;
+		}
+		let path = delta
+			.new_file()
+			.path()
+			.map(|p| p.to_string_lossy().replace('\\', "/"))
+			.unwrap_or_default();
+
+		'hunks: for hunk_idx in 0..file_patch.num_hunks() {
+			let (hunk, lines) = file_patch.hunk(hunk_idx)?;
+			let mut added = Vec::new();
+
+			for line_idx in 0..lines {
+				let line =
+					file_patch.line_in_hunk(hunk_idx, line_idx)?;
+				match line.origin() {
+					'+' => added.extend_from_slice(line.content()),
+					'-' => (),
+					// a changed newline at the end of file
+					_ => continue 'hunks,
+				}
+			}
+
+			hunks.push(StagedHunk {
+				path: path.clone(),
+				old_start: hunk.old_start() as usize,
+				old_lines: hunk.old_lines() as usize,
+				added,
+			});
+		}
+	}
+
+	Ok(hunks)
+}
+
+/// the single candidate commit that last touched the lines of `hunk`,
+/// or both neighbours of a hunk that only adds lines
+fn target_of(
+	repo: &Repository,
+	candidates: &Candidates,
+	blames: &mut HashMap<String, Vec<Oid>>,
+	hunk: &StagedHunk,
+) -> Result<Option<Oid>> {
+	if !blames.contains_key(&hunk.path) {
+		blames.insert(
+			hunk.path.clone(),
+			blame_lines(repo, candidates.base, &hunk.path)?,
+		);
+	}
+	let lines = &blames[&hunk.path];
+
+	// 1-based lines of `HEAD` that decide the target
+	let decisive: Vec<usize> = if hunk.old_lines == 0 {
+		[hunk.old_start, hunk.old_start + 1]
+			.into_iter()
+			.filter(|line| *line >= 1 && *line <= lines.len())
+			.collect()
+	} else {
+		(hunk.old_start..hunk.old_start + hunk.old_lines).collect()
+	};
+
+	let mut targets = decisive.iter().map(|line| lines.get(line - 1));
+	let Some(Some(first)) = targets.next() else {
+		return Ok(None);
+	};
+
+	let single = targets.all(|target| target == Some(first));
+
+	Ok((single && candidates.commits.contains(first))
+		.then_some(*first))
+}
+
+/// commit of each line of `path` in HEAD
+fn blame_lines(
+	repo: &Repository,
+	base: Oid,
+	path: &str,
+) -> Result<Vec<Oid>> {
+	let head = repo.head()?.peel_to_commit()?.id();
+	let mut opts = BlameOptions::new();
+	opts.newest_commit(head).oldest_commit(base);
+
+	let blame = repo.blame_file(Path::new(path), Some(&mut opts))?;
+
+	let mut lines = Vec::new();
+	for hunk in blame.iter() {
+		let id = hunk.final_commit_id();
+		lines.extend(std::iter::repeat_n(id, hunk.lines_in_hunk()));
+	}
+
+	Ok(lines)
+}
+
+/// `tree` with `hunks` applied to its files
+fn tree_with_hunks(
+	repo: &Repository,
+	tree: &git2::Tree,
+	hunks: &[&StagedHunk],
+) -> Result<Oid> {
+	let mut files: HashMap<&str, Vec<&StagedHunk>> = HashMap::new();
+	for hunk in hunks {
+		files.entry(hunk.path.as_str()).or_default().push(hunk);
+	}
+
+	let mut update = TreeUpdateBuilder::new();
+
+	for (path, mut hunks) in files {
+		let entry = tree.get_path(Path::new(path))?;
+		let blob = repo.find_blob(entry.id())?;
+		hunks.sort_by_key(|hunk| hunk.old_start);
+
+		let content = apply_hunks(blob.content(), &hunks);
+		let id = repo.blob(&content)?;
+
+		let mode = match entry.filemode() {
+			0o100_755 => FileMode::BlobExecutable,
+			0o120_000 => FileMode::Link,
+			_ => FileMode::Blob,
+		};
+
+		update.upsert(path, id, mode);
+	}
+
+	Ok(update.create_updated(repo, tree)?)
+}
+
+/// `old` with the sorted `hunks` applied
+fn apply_hunks(old: &[u8], hunks: &[&StagedHunk]) -> Vec<u8> {
+	let lines: Vec<&[u8]> =
+		old.split_inclusive(|b| *b == b'\n').collect();
+	let mut content = Vec::with_capacity(old.len());
+	let mut pos = 0;
+
+	for hunk in hunks {
+		let start = if hunk.old_lines == 0 {
+			hunk.old_start
+		} else {
+			hunk.old_start - 1
+		};
+
+		for line in &lines[pos..start] {
+			content.extend_from_slice(line);
+		}
+		content.extend_from_slice(&hunk.added);
+		pos = start + hunk.old_lines;
+	}
+
+	for line in &lines[pos..] {
+		content.extend_from_slice(line);
+	}
+
+	content
+}
+
+#[cfg(test)]
+mod tests {
+	use super::absorb;
+	use crate::sync::{
+		checkout_branch, create_branch, get_head, stage_add_file,
+		tests::{repo_init, write_commit_file},
+		utils::repo_write_file,
+		RepoPath,
+	};
+	use git2::{BranchType, Repository};
+	use std::path::Path;
+
+	fn summaries(repo: &Repository) -> Vec<String> {
+		let mut walk = repo.revwalk().unwrap();
+		walk.push_head().unwrap();
+		walk.map(|id| {
+			repo.find_commit(id.unwrap())
+				.unwrap()
+				.summary()
+				.unwrap()
+				.to_string()
+		})
+		.collect()
+	}
+
+	fn file_at(repo: &Repository, rev: &str, path: &str) -> String {
+		let tree = repo.revparse_single(rev).unwrap().peel_to_tree();
+		let blob = tree
+			.unwrap()
+			.get_path(Path::new(path))
+			.unwrap()
+			.to_object(repo)
+			.unwrap()
+			.peel_to_blob()
+			.unwrap();
+		String::from_utf8(blob.content().to_vec()).unwrap()
+	}
+
+	/// `feature` over `master` changing `a.txt` and `b.txt`
+	fn absorb_repo(repo: &Repository, repo_path: &RepoPath) {
+		write_commit_file(repo, "a.txt", "1\n2\n3\n4\n", "base");
+		create_branch(repo_path, "feature").unwrap();
+		checkout_branch(repo_path, "feature").unwrap();
+		repo.find_branch("feature", BranchType::Local)
+			.unwrap()
+			.set_upstream(Some("master"))
+			.unwrap();
+
+		write_commit_file(repo, "a.txt", "1\ntwo\n3\n4\n", "edit a");
+		write_commit_file(repo, "b.txt", "x\ny\n", "add b");
+	}
+
+	#[test]
+	fn test_absorb_into_fixups() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		absorb_repo(&repo, repo_path);
+
+		// one hunk per commit and one on a line of the base
+		repo_write_file(&repo, "a.txt", "1\nTWO\n3\nfour\n").unwrap();
+		repo_write_file(&repo, "b.txt", "x\nY\n").unwrap();
+		stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+		stage_add_file(repo_path, Path::new("b.txt")).unwrap();
+
+		let result = absorb(repo_path, false).unwrap();
+		assert_eq!(result.fixups.len(), 2);
+		assert_eq!(result.absorbed, 2);
+		assert_eq!(result.left, 1);
+		assert_eq!(
+			summaries(&repo)[..4],
+			["fixup! add b", "fixup! edit a", "add b", "edit a"]
+		);
+		assert_eq!(
+			file_at(&repo, "HEAD~1", "a.txt"),
+			"1\nTWO\n3\n4\n"
+		);
+		assert_eq!(file_at(&repo, "HEAD", "b.txt"), "x\nY\n");
+
+		// the line of the base is still staged
+		let result = absorb(repo_path, false).unwrap();
+		assert_eq!(result.absorbed, 0);
+		assert_eq!(result.left, 1);
+	}
+
+	#[test]
+	fn test_absorb_autosquash() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		absorb_repo(&repo, repo_path);
+
+		// added right after a line of `add b`
+		repo_write_file(&repo, "a.txt", "1\nTWO\n3\n4\n").unwrap();
+		repo_write_file(&repo, "b.txt", "x\ny\nz\n").unwrap();
+		stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+		stage_add_file(repo_path, Path::new("b.txt")).unwrap();
+
+		let result = absorb(repo_path, true).unwrap();
+		assert!(result.fixups.is_empty());
+		assert_eq!(result.targets, 2);
+		assert_eq!(
+			summaries(&repo)[..3],
+			["add b", "edit a", "base"]
+		);
+		assert_eq!(
+			file_at(&repo, "HEAD~1", "a.txt"),
+			"1\nTWO\n3\n4\n"
+		);
+		assert_eq!(file_at(&repo, "HEAD", "b.txt"), "x\ny\nz\n");
+
+		// nothing left staged
+		let head = get_head(repo_path).unwrap();
+		assert_eq!(absorb(repo_path, true).unwrap().absorbed, 0);
+		assert_eq!(get_head(repo_path).unwrap(), head);
+	}
+}
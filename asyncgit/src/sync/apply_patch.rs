@@ -0,0 +1,332 @@
+//! applying patch files from disk, like `git apply`
+
+use super::RepoPath;
+use crate::{
+	error::{Error, Result},
+	sync::repository::repo,
+};
+use git2::{ApplyOptions, Diff, Repository};
+use scopetime::scope_time;
+use std::{cell::Cell, fs, path::Path};
+
+/// where `apply_patch` applies a patch to
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ApplyLocation {
+	///
+	#[default]
+	WorkDir,
+	///
+	Index,
+	/// worktree and index, like `git apply --index`
+	Both,
+}
+
+impl From<ApplyLocation> for git2::ApplyLocation {
+	fn from(location: ApplyLocation) -> Self {
+		match location {
+			ApplyLocation::WorkDir => Self::WorkDir,
+			ApplyLocation::Index => Self::Index,
+			ApplyLocation::Both => Self::Both,
+		}
+	}
+}
+
+/// a patch that does not apply
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatchConflict {
+	/// subject of the mail or name of the patch file
+	pub patch: String,
+	/// files the hunks of the patch do not apply to
+	pub files: Vec<String>,
+}
+
+/// what `apply_patch` applied
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ApplyPatchResult {
+	/// number of patches applied
+	pub applied: usize,
+	/// files changed by the applied patches
+	pub files: Vec<String>,
+	/// the first patch that does not apply, the patches after it
+	/// are skipped
+	pub conflict: Option<PatchConflict>,
+}
+
+/// applies the unified diff, `.patch` or `.mbox` file at
+/// `patch_path` to `location`.
+///
+/// the patches of a mailbox are applied one after another but
+/// nothing gets committed. each patch is checked first so it either
+/// applies completely or not at all.
+pub fn apply_patch(
+	repo_path: &RepoPath,
+	patch_path: &Path,
+	location: ApplyLocation,
+) -> Result<ApplyPatchResult> {
+	scope_time!("apply_patch");
+
+	let repo = repo(repo_path)?;
+	let content = fs::read(patch_path)?;
+
+	let file_name = patch_path
+		.file_name()
+		.map(|name| name.to_string_lossy().to_string())
+		.unwrap_or_default();
+
+	let patches = split_mailbox(&content, &file_name);
+	if patches.is_empty() {
+		return Err(Error::Generic(format!(
+			"no patch found in '{}'",
+			patch_path.display()
+		)));
+	}
+
+	let mut result = ApplyPatchResult::default();
+
+	for (subject, patch) in patches {
+		let diff = Diff::from_buffer(patch)?;
+
+		let mut check = ApplyOptions::new();
+		check.check(true);
+		if repo
+			.apply(&diff, location.into(), Some(&mut check))
+			.is_err()
+		{
+			result.conflict = Some(PatchConflict {
+				patch: subject,
+				files: conflicting_files(&repo, &diff, location),
+			});
+			break;
+		}
+
+		repo.apply(&diff, location.into(), None)?;
+
+		result.applied += 1;
+		for delta in diff.deltas() {
+			let path = delta
+				.new_file()
+				.path()
+				.or_else(|| delta.old_file().path())
+				.map(|path| path.to_string_lossy().to_string())
+				.unwrap_or_default();
+			if !result.files.contains(&path) {
+				result.files.push(path);
+			}
+		}
+	}
+
+	Ok(result)
+}
+
+/// checks each file of `diff` on its own to find the ones that fail
+fn conflicting_files(
+	repo: &Repository,
+	diff: &Diff,
+	location: ApplyLocation,
+) -> Vec<String> {
+	diff.deltas()
+		.enumerate()
+		.filter(|(idx, _)| {
+			let current = Cell::new(0);
+			let mut check = ApplyOptions::new();
+			check.check(true);
+			check.delta_callback(|_| {
+				let matches = current.get() == *idx;
+				current.set(current.get() + 1);
+				matches
+			});
+
+			repo.apply(diff, location.into(), Some(&mut check))
+				.is_err()
+		})
+		.filter_map(|(_, delta)| {
+			delta
+				.new_file()
+				.path()
+				.or_else(|| delta.old_file().path())
+				.map(|path| path.to_string_lossy().to_string())
+		})
+		.collect()
+}
+
+/// splits a mailbox (`git format-patch` output) into its mails, a
+/// plain diff is a single patch named `file_name`. mails without a
+/// diff are dropped.
+fn split_mailbox<'a>(
+	content: &'a [u8],
+	file_name: &str,
+) -> Vec<(String, &'a [u8])> {
+	let mut starts = Vec::new();
+	let mut offset = 0;
+	for line in content.split_inclusive(|b| *b == b'\n') {
+		if line.starts_with(b"From ") {
+			starts.push(offset);
+		}
+		offset += line.len();
+	}
+
+	if starts.first() != Some(&0) {
+		starts.insert(0, 0);
+	}
+	starts.push(content.len());
+
+	starts
+		.windows(2)
+		.map(|range| &content[range[0]..range[1]])
+		.filter(|mail| {
+			mail.split(|b| *b == b'\n')
+				.any(|line| line.starts_with(b"diff --git "))
+		})
+		.map(|mail| {
+			let subject = String::from_utf8_lossy(mail)
+				.lines()
+				.take_while(|line| !line.is_empty())
+				.find_map(|line| line.strip_prefix("Subject: "))
+				.map_or_else(
+					|| file_name.to_string(),
+					strip_patch_prefix,
+				);
+
+			(subject, mail)
+		})
+		.collect()
+}
+
+/// `[PATCH 1/2] fix it` -> `fix it`
+fn strip_patch_prefix(subject: &str) -> String {
+	subject
+		.strip_prefix('[')
+		.and_then(|rest| rest.split_once("] "))
+		.map_or(subject, |(_, subject)| subject)
+		.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		tests::{repo_init, write_commit_file},
+		utils::{repo_read_file, repo_write_file},
+	};
+
+	fn repo_path(repo: &Repository) -> RepoPath {
+		repo.path()
+			.parent()
+			.unwrap()
+			.as_os_str()
+			.to_str()
+			.unwrap()
+			.into()
+	}
+
+	#[test]
+	fn test_apply_mailbox() {
+		let (_td, repo) = repo_init().unwrap();
+		let repo_path = repo_path(&repo);
+		let root = repo.workdir().unwrap();
+
+		write_commit_file(&repo, "foo.txt", "a\n", "c1");
+		let base = repo.head().unwrap().target().unwrap();
+		write_commit_file(&repo, "foo.txt", "a\nb\n", "c2");
+		write_commit_file(&repo, "bar.txt", "bar\n", "c3");
+
+		let patch = root.join("out.mbox");
+		let out = std::process::Command::new("git")
+			.args(["format-patch", "--stdout", &base.to_string()])
+			.current_dir(root)
+			.output()
+			.unwrap();
+		fs::write(&patch, out.stdout).unwrap();
+
+		let head = repo.find_commit(base).unwrap();
+		repo.reset(head.as_object(), git2::ResetType::Hard, None)
+			.unwrap();
+
+		let res =
+			apply_patch(&repo_path, &patch, ApplyLocation::Both)
+				.unwrap();
+
+		assert_eq!(res.applied, 2);
+		assert_eq!(res.files, vec!["foo.txt", "bar.txt"]);
+		assert_eq!(res.conflict, None);
+		assert_eq!(
+			repo_read_file(&repo, "foo.txt").unwrap(),
+			"a\nb\n"
+		);
+		assert!(repo
+			.status_file(Path::new("bar.txt"))
+			.unwrap()
+			.is_index_new());
+	}
+
+	#[test]
+	fn test_apply_conflict() {
+		let (_td, repo) = repo_init().unwrap();
+		let repo_path = repo_path(&repo);
+		let root = repo.workdir().unwrap();
+
+		write_commit_file(&repo, "foo.txt", "a\n", "c1");
+		write_commit_file(&repo, "bar.txt", "a\n", "c2");
+
+		let patch = root.join("fix.patch");
+		fs::write(
+			&patch,
+			"diff --git a/bar.txt b/bar.txt\n\
+			--- a/bar.txt\n\
+			+++ b/bar.txt\n\
+			@@ -1 +1 @@\n\
+			-a\n\
+			+b\n\
+			diff --git a/foo.txt b/foo.txt\n\
+			--- a/foo.txt\n\
+			+++ b/foo.txt\n\
+			@@ -1 +1 @@\n\
+			-a\n\
+			+b\n",
+		)
+		.unwrap();
+
+		repo_write_file(&repo, "foo.txt", "changed\n").unwrap();
+
+		let res =
+			apply_patch(&repo_path, &patch, ApplyLocation::WorkDir)
+				.unwrap();
+
+		assert_eq!(res.applied, 0);
+		assert_eq!(
+			res.conflict,
+			Some(PatchConflict {
+				patch: String::from("fix.patch"),
+				files: vec![String::from("foo.txt")],
+			})
+		);
+		assert_eq!(repo_read_file(&repo, "bar.txt").unwrap(), "a\n");
+
+		repo_write_file(&repo, "foo.txt", "a\n").unwrap();
+
+		let res =
+			apply_patch(&repo_path, &patch, ApplyLocation::WorkDir)
+				.unwrap();
+
+		assert_eq!(res.applied, 1);
+		assert_eq!(repo_read_file(&repo, "bar.txt").unwrap(), "b\n");
+		assert_eq!(repo_read_file(&repo, "foo.txt").unwrap(), "b\n");
+	}
+
+	#[test]
+	fn test_no_patch() {
+		let (_td, repo) = repo_init().unwrap();
+		let repo_path = repo_path(&repo);
+		let root = repo.workdir().unwrap();
+
+		let patch = root.join("empty.patch");
+		fs::write(&patch, "just text\n").unwrap();
+
+		assert!(apply_patch(
+			&repo_path,
+			&patch,
+			ApplyLocation::WorkDir
+		)
+		.is_err());
+	}
+}
@@ -0,0 +1,472 @@
+//! bisect state kept in the same files and refs `git bisect` uses,
+//! so a bisect can be continued on the command line and vice versa
+
+use super::{
+	branch::{checkout_branch, checkout_commit},
+	repository::repo,
+	utils::work_dir,
+	CommitId, RepoPath,
+};
+use crate::error::{Error, Result};
+use git2::{BranchType, Oid, Repository, Sort};
+use scopetime::scope_time;
+use std::{
+	fs::{self, OpenOptions},
+	io::Write,
+	process::{Command, Stdio},
+};
+
+const BISECT_START: &str = "BISECT_START";
+const BISECT_LOG: &str = "BISECT_LOG";
+const BISECT_FILES: &[&str] = &[
+	BISECT_START,
+	BISECT_LOG,
+	"BISECT_TERMS",
+	"BISECT_NAMES",
+	"BISECT_EXPECTED_REV",
+	"BISECT_ANCESTORS_OK",
+	"BISECT_RUN",
+];
+const REF_BAD: &str = "refs/bisect/bad";
+const REF_GOOD_PREFIX: &str = "refs/bisect/good-";
+const REF_SKIP_PREFIX: &str = "refs/bisect/skip-";
+
+/// exit code of a bisect command for a commit that cannot be tested
+const EXIT_CODE_SKIP: i32 = 125;
+
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BisectMark {
+	/// commit without the change searched for
+	Good,
+	/// commit with the change searched for
+	Bad,
+	/// commit that cannot be tested
+	Skip,
+}
+
+impl BisectMark {
+	const fn name(self) -> &'static str {
+		match self {
+			Self::Good => "good",
+			Self::Bad => "bad",
+			Self::Skip => "skip",
+		}
+	}
+}
+
+/// progress of a running bisect
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BisectState {
+	///
+	pub bad: Option<CommitId>,
+	///
+	pub good: Vec<CommitId>,
+	///
+	pub skipped: Vec<CommitId>,
+	/// commits between good and bad still to be tested
+	pub remaining: usize,
+	/// commit to test next, `None` until a good and a bad commit
+	/// are marked
+	pub next: Option<CommitId>,
+	/// first bad commit once the range is narrowed down to it
+	pub found: Option<CommitId>,
+}
+
+impl BisectState {
+	/// rough number of steps left, as `git bisect` estimates it
+	pub const fn steps(&self) -> u32 {
+		usize::BITS - self.remaining.leading_zeros()
+	}
+
+	/// no commits left to test but skipped ones
+	pub fn only_skipped_left(&self) -> bool {
+		self.found.is_none()
+			&& self.next.is_none()
+			&& self.bad.is_some()
+			&& !self.good.is_empty()
+	}
+}
+
+/// `true` if a bisect was started and not reset yet
+pub fn is_bisecting(repo_path: &RepoPath) -> Result<bool> {
+	let repo = repo(repo_path)?;
+
+	Ok(repo.path().join(BISECT_START).exists())
+}
+
+/// current bisect progress, `None` if no bisect is running
+pub fn bisect_state(
+	repo_path: &RepoPath,
+) -> Result<Option<BisectState>> {
+	scope_time!("bisect_state");
+
+	let repo = repo(repo_path)?;
+
+	if !repo.path().join(BISECT_START).exists() {
+		return Ok(None);
+	}
+
+	get_state(&repo).map(Some)
+}
+
+/// marks `commit` and checks out the next commit to test, starts a
+/// bisect first if none is running
+pub fn bisect_mark(
+	repo_path: &RepoPath,
+	commit: CommitId,
+	mark: BisectMark,
+) -> Result<BisectState> {
+	scope_time!("bisect_mark");
+
+	let repo = repo(repo_path)?;
+
+	if !repo.path().join(BISECT_START).exists() {
+		start(&repo)?;
+	}
+
+	let ref_name = match mark {
+		BisectMark::Bad => REF_BAD.to_string(),
+		BisectMark::Good => format!("{REF_GOOD_PREFIX}{commit}"),
+		BisectMark::Skip => format!("{REF_SKIP_PREFIX}{commit}"),
+	};
+	repo.reference(&ref_name, commit.into(), true, "bisect")?;
+
+	let summary = repo
+		.find_commit(commit.into())?
+		.summary()
+		.unwrap_or_default()
+		.to_string();
+	append_log(
+		&repo,
+		&format!(
+			"# {0}: [{commit}] {summary}\ngit bisect {0} {commit}\n",
+			mark.name()
+		),
+	)?;
+
+	let state = get_state(&repo)?;
+
+	if let Some(next) = state.next {
+		checkout_commit(repo_path, next)?;
+	}
+
+	Ok(state)
+}
+
+/// runs `command` on the next commit to test and marks it by the
+/// exit code like `git bisect run`: 0 is good, 125 skips the commit
+/// and anything else below 128 is bad
+pub fn bisect_run_step(
+	repo_path: &RepoPath,
+	command: &str,
+) -> Result<BisectState> {
+	scope_time!("bisect_run_step");
+
+	let repo = repo(repo_path)?;
+	let state = get_state(&repo)?;
+
+	let Some(next) = state.next else {
+		return Ok(state);
+	};
+
+	if repo.head()?.target() != Some(next.into()) {
+		checkout_commit(repo_path, next)?;
+	}
+
+	let status = shell_command(command)
+		.current_dir(work_dir(&repo)?)
+		.stdin(Stdio::null())
+		.stdout(Stdio::null())
+		.stderr(Stdio::null())
+		.status()?;
+
+	let mark = match status.code() {
+		Some(0) => BisectMark::Good,
+		Some(EXIT_CODE_SKIP) => BisectMark::Skip,
+		Some(code) if (1..128).contains(&code) => BisectMark::Bad,
+		_ => {
+			return Err(Error::Generic(format!(
+				"bisect run stopped, `{command}` exited with {status}"
+			)));
+		}
+	};
+
+	bisect_mark(repo_path, next, mark)
+}
+
+/// ends the bisect and checks out the branch it was started on
+pub fn bisect_reset(repo_path: &RepoPath) -> Result<()> {
+	scope_time!("bisect_reset");
+
+	let repo = repo(repo_path)?;
+
+	let start = fs::read_to_string(repo.path().join(BISECT_START))
+		.unwrap_or_default();
+	let start = start.trim();
+
+	if !start.is_empty() {
+		if repo.find_branch(start, BranchType::Local).is_ok() {
+			checkout_branch(repo_path, start)?;
+		} else {
+			checkout_commit(repo_path, Oid::from_str(start)?.into())?;
+		}
+	}
+
+	for reference in repo.references_glob("refs/bisect/*")? {
+		reference?.delete()?;
+	}
+
+	for file in BISECT_FILES {
+		let path = repo.path().join(file);
+		if path.exists() {
+			fs::remove_file(path)?;
+		}
+	}
+
+	Ok(())
+}
+
+fn start(repo: &Repository) -> Result<()> {
+	let head = repo.head()?;
+	let start = if head.is_branch() {
+		head.shorthand().map(String::from)
+	} else {
+		head.target().map(|id| id.to_string())
+	}
+	.ok_or(Error::NoHead)?;
+
+	fs::write(repo.path().join(BISECT_START), format!("{start}\n"))?;
+	fs::write(repo.path().join(BISECT_LOG), "git bisect start\n")?;
+
+	Ok(())
+}
+
+fn append_log(repo: &Repository, line: &str) -> Result<()> {
+	let mut file = OpenOptions::new()
+		.create(true)
+		.append(true)
+		.open(repo.path().join(BISECT_LOG))?;
+	file.write_all(line.as_bytes())?;
+
+	Ok(())
+}
+
+fn get_state(repo: &Repository) -> Result<BisectState> {
+	let mut state = BisectState::default();
+
+	for reference in repo.references_glob("refs/bisect/*")? {
+		let reference = reference?;
+		let (Some(name), Some(target)) =
+			(reference.name(), reference.target())
+		else {
+			continue;
+		};
+
+		if name == REF_BAD {
+			state.bad = Some(target.into());
+		} else if name.starts_with(REF_GOOD_PREFIX) {
+			state.good.push(target.into());
+		} else if name.starts_with(REF_SKIP_PREFIX) {
+			state.skipped.push(target.into());
+		}
+	}
+
+	let Some(bad) = state.bad else {
+		return Ok(state);
+	};
+	if state.good.is_empty() {
+		return Ok(state);
+	}
+
+	let candidates = candidates(repo, bad, &state.good)?;
+	state.remaining = candidates.len();
+
+	let testable: Vec<_> = candidates
+		.into_iter()
+		.filter(|id| !state.skipped.contains(id))
+		.collect();
+
+	if state.remaining == 0 {
+		state.found = Some(bad);
+	} else {
+		// the list is in topological order, so the commit in the
+		// middle splits the range in about equal halves
+		state.next = testable.get(testable.len() / 2).copied();
+	}
+
+	Ok(state)
+}
+
+/// commits reachable from `bad` but not from any good commit, newest
+/// first and without `bad` itself
+fn candidates(
+	repo: &Repository,
+	bad: CommitId,
+	good: &[CommitId],
+) -> Result<Vec<CommitId>> {
+	let mut walk = repo.revwalk()?;
+	walk.set_sorting(Sort::TOPOLOGICAL)?;
+	walk.push(bad.into())?;
+	for id in good {
+		walk.hide((*id).into())?;
+	}
+
+	let bad: Oid = bad.into();
+
+	Ok(walk
+		.filter_map(std::result::Result::ok)
+		.filter(|id| *id != bad)
+		.map(CommitId::from)
+		.collect())
+}
+
+fn shell_command(command: &str) -> Command {
+	if cfg!(windows) {
+		let mut cmd = Command::new("cmd");
+		cmd.args(["/C", command]);
+		cmd
+	} else {
+		let mut cmd = Command::new("sh");
+		cmd.args(["-c", command]);
+		cmd
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		get_head,
+		tests::{repo_init, write_commit_file},
+		utils::get_head_refname,
+		RepoState,
+	};
+
+	fn commits(repo: &Repository, count: usize) -> Vec<CommitId> {
+		(0..count)
+			.map(|i| {
+				write_commit_file(
+					repo,
+					"file.txt",
+					&format!("{i}"),
+					&format!("commit {i}"),
+				)
+			})
+			.collect()
+	}
+
+	#[test]
+	fn test_bisect_manual() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let ids = commits(&repo, 8);
+
+		assert_eq!(bisect_state(repo_path).unwrap(), None);
+
+		let state =
+			bisect_mark(repo_path, ids[7], BisectMark::Bad).unwrap();
+		assert!(is_bisecting(repo_path).unwrap());
+		assert_eq!(state.next, None);
+		assert_eq!(
+			crate::sync::repo_state(repo_path).unwrap(),
+			RepoState::Bisect
+		);
+
+		let state =
+			bisect_mark(repo_path, ids[0], BisectMark::Good).unwrap();
+		assert_eq!(state.remaining, 6);
+		assert_eq!(state.steps(), 3);
+		assert_eq!(state.next, Some(ids[3]));
+		assert_eq!(get_head(repo_path).unwrap(), ids[3]);
+
+		let state =
+			bisect_mark(repo_path, ids[3], BisectMark::Bad).unwrap();
+		assert_eq!(state.next, Some(ids[1]));
+
+		let state =
+			bisect_mark(repo_path, ids[1], BisectMark::Skip).unwrap();
+		assert_eq!(state.next, Some(ids[2]));
+
+		let state =
+			bisect_mark(repo_path, ids[2], BisectMark::Good).unwrap();
+		assert_eq!(state.found, Some(ids[3]));
+		assert_eq!(state.next, None);
+
+		bisect_reset(repo_path).unwrap();
+
+		assert!(!is_bisecting(repo_path).unwrap());
+		assert_eq!(get_head(repo_path).unwrap(), ids[7]);
+		assert_eq!(
+			get_head_refname(&repo).unwrap(),
+			"refs/heads/master"
+		);
+		assert_eq!(
+			repo.references_glob("refs/bisect/*").unwrap().count(),
+			0
+		);
+	}
+
+	#[test]
+	fn test_bisect_only_skipped_left() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let ids = commits(&repo, 3);
+
+		bisect_mark(repo_path, ids[2], BisectMark::Bad).unwrap();
+		bisect_mark(repo_path, ids[0], BisectMark::Good).unwrap();
+		let state =
+			bisect_mark(repo_path, ids[1], BisectMark::Skip).unwrap();
+
+		assert_eq!(state.remaining, 1);
+		assert!(state.only_skipped_left());
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn test_bisect_run() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let ids = commits(&repo, 10);
+
+		bisect_mark(repo_path, ids[9], BisectMark::Bad).unwrap();
+		let mut state =
+			bisect_mark(repo_path, ids[0], BisectMark::Good).unwrap();
+
+		// the change is introduced in commit 6
+		let command = "test $(cat file.txt) -lt 6";
+		while state.found.is_none() {
+			state = bisect_run_step(repo_path, command).unwrap();
+		}
+
+		assert_eq!(state.found, Some(ids[6]));
+
+		assert!(bisect_run_step(repo_path, "exit 200").is_ok());
+		bisect_reset(repo_path).unwrap();
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn test_bisect_run_abort() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let ids = commits(&repo, 4);
+
+		bisect_mark(repo_path, ids[3], BisectMark::Bad).unwrap();
+		bisect_mark(repo_path, ids[0], BisectMark::Good).unwrap();
+
+		assert!(bisect_run_step(repo_path, "exit 200").is_err());
+	}
+}
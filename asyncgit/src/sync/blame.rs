@@ -3,9 +3,9 @@
 use super::{utils, CommitId, RepoPath};
 use crate::{
 	error::{Error, Result},
-	sync::{get_commits_info, repository::repo},
+	sync::{get_commits_info, repository::repo, CommitInfo},
 };
-use git2::BlameOptions;
+use git2::{BlameOptions, Repository};
 use scopetime::scope_time;
 use std::collections::{HashMap, HashSet};
 use std::io::{BufRead, BufReader};
@@ -53,11 +53,31 @@ fn fixup_windows_path(path: &str) -> String {
 	}
 }
 
+/// lines blamed in the first pass of `blame_file_incremental`,
+/// doubled for every following pass
+const BLAME_FIRST_CHUNK_LINES: usize = 256;
+
 ///
 pub fn blame_file(
 	repo_path: &RepoPath,
 	file_path: &str,
 	commit_id: Option<CommitId>,
+) -> Result<FileBlame> {
+	blame_file_incremental(repo_path, file_path, commit_id, |_, _| {
+		true
+	})
+}
+
+/// blames the file in growing chunks of lines from the top
+///
+/// calls `on_chunk` with the partial result and the number of lines
+/// blamed so far after each chunk and stops early (returning the
+/// partial result) if it returns `false`
+pub fn blame_file_incremental(
+	repo_path: &RepoPath,
+	file_path: &str,
+	commit_id: Option<CommitId>,
+	mut on_chunk: impl FnMut(&FileBlame, usize) -> bool,
 ) -> Result<FileBlame> {
 	scope_time!("blame_file");
 
@@ -79,71 +99,106 @@ pub fn blame_file(
 		return Err(Error::NoBlameOnBinaryFile);
 	}
 
-	let mut opts = BlameOptions::new();
-	opts.newest_commit(commit_id.into());
+	let reader = BufReader::new(blob.content());
 
-	let blame =
-		repo.blame_file(Path::new(file_path), Some(&mut opts))?;
+	let mut file_blame = FileBlame {
+		commit_id,
+		path: file_path.into(),
+		lines: reader
+			.lines()
+			.map(|line| {
+				(None, line.unwrap_or_else(|_| String::new()))
+			})
+			.collect(),
+	};
 
-	let reader = BufReader::new(blob.content());
+	let total = file_blame.lines.len();
+	let mut commit_infos = HashMap::new();
+	let mut start = 0;
+	let mut chunk = BLAME_FIRST_CHUNK_LINES;
+
+	while start < total {
+		let end = start.saturating_add(chunk).min(total);
+
+		blame_lines(
+			&repo,
+			repo_path,
+			&mut file_blame,
+			&mut commit_infos,
+			(start, end),
+		)?;
+
+		if !on_chunk(&file_blame, end) {
+			break;
+		}
+
+		start = end;
+		chunk = chunk.saturating_mul(2);
+	}
+
+	Ok(file_blame)
+}
+
+/// fills in the hunks of the 0-based line range `[start, end)`
+fn blame_lines(
+	repo: &Repository,
+	repo_path: &RepoPath,
+	file_blame: &mut FileBlame,
+	commit_infos: &mut HashMap<CommitId, CommitInfo>,
+	(start, end): (usize, usize),
+) -> Result<()> {
+	let mut opts = BlameOptions::new();
+	opts.newest_commit(file_blame.commit_id.into());
+	// line indices in `BlameOptions` are 1-based and inclusive
+	opts.min_line(start + 1).max_line(end);
 
-	let unique_commit_ids: HashSet<_> = blame
+	let blame = repo
+		.blame_file(Path::new(&file_blame.path), Some(&mut opts))?;
+
+	let new_commit_ids: Vec<_> = blame
 		.iter()
 		.map(|hunk| CommitId::new(hunk.final_commit_id()))
+		.filter(|id| !commit_infos.contains_key(id))
+		.collect::<HashSet<_>>()
+		.into_iter()
 		.collect();
-	let mut commit_ids = Vec::with_capacity(unique_commit_ids.len());
-	commit_ids.extend(unique_commit_ids);
 
-	let commit_infos = get_commits_info(repo_path, &commit_ids, 0)?;
-	let unique_commit_infos: HashMap<_, _> = commit_infos
-		.iter()
-		.map(|commit_info| (commit_info.id, commit_info))
-		.collect();
+	commit_infos.extend(
+		get_commits_info(repo_path, &new_commit_ids, 0)?
+			.into_iter()
+			.map(|commit_info| (commit_info.id, commit_info)),
+	);
 
-	let lines: Vec<(Option<BlameHunk>, String)> = reader
-		.lines()
+	for (i, line) in file_blame
+		.lines
+		.iter_mut()
 		.enumerate()
-		.map(|(i, line)| {
-			// Line indices in a `FileBlame` are 1-based.
-			let corresponding_hunk = blame.get_line(i + 1);
-
-			if let Some(hunk) = corresponding_hunk {
-				let commit_id = CommitId::new(hunk.final_commit_id());
-				// Line indices in a `BlameHunk` are 1-based.
-				let start_line =
-					hunk.final_start_line().saturating_sub(1);
-				let end_line =
-					start_line.saturating_add(hunk.lines_in_hunk());
-
-				if let Some(commit_info) =
-					unique_commit_infos.get(&commit_id)
-				{
-					let hunk = BlameHunk {
-						commit_id,
-						author: commit_info.author.clone(),
-						time: commit_info.time,
-						start_line,
-						end_line,
-					};
-
-					return (
-						Some(hunk),
-						line.unwrap_or_else(|_| String::new()),
-					);
-				}
-			}
-
-			(None, line.unwrap_or_else(|_| String::new()))
-		})
-		.collect();
-
-	let file_blame = FileBlame {
-		commit_id,
-		path: file_path.into(),
-		lines,
-	};
+		.take(end)
+		.skip(start)
+	{
+		// Line indices in a `FileBlame` are 1-based.
+		let Some(hunk) = blame.get_line(i + 1) else {
+			continue;
+		};
+
+		let commit_id = CommitId::new(hunk.final_commit_id());
+		// Line indices in a `BlameHunk` are 1-based.
+		let start_line = hunk.final_start_line().saturating_sub(1);
+		let end_line =
+			start_line.saturating_add(hunk.lines_in_hunk());
+
+		if let Some(commit_info) = commit_infos.get(&commit_id) {
+			line.0 = Some(BlameHunk {
+				commit_id,
+				author: commit_info.author.clone(),
+				time: commit_info.time,
+				start_line,
+				end_line,
+			});
+		}
+	}
 
-	Ok(file_blame)
+	Ok(())
 }
 
 #[cfg(test)]
@@ -151,7 +206,10 @@ mod tests {
 	use super::*;
 	use crate::{
 		error::Result,
-		sync::{commit, stage_add_file, tests::repo_init_empty},
+		sync::{
+			commit, stage_add_file,
+			tests::{repo_init, repo_init_empty, write_commit_file},
+		},
 	};
 	use std::{
 		fs::{File, OpenOptions},
@@ -239,6 +297,44 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn test_blame_incremental() {
+		let (td, repo) = repo_init().unwrap();
+		let repo_path: &RepoPath =
+			&td.path().to_str().unwrap().into();
+
+		let content = "line\n".repeat(1000);
+		write_commit_file(&repo, "a.txt", &content, "add a");
+
+		let mut chunks = Vec::new();
+		let blame = blame_file_incremental(
+			repo_path,
+			"a.txt",
+			None,
+			|_, done| {
+				chunks.push(done);
+				true
+			},
+		)
+		.unwrap();
+
+		assert_eq!(chunks, vec![256, 768, 1000]);
+		assert!(blame.lines.iter().all(|(hunk, _)| hunk.is_some()));
+
+		// stopping early keeps the lines blamed so far
+		let blame = blame_file_incremental(
+			repo_path,
+			"a.txt",
+			None,
+			|_, _| false,
+		)
+		.unwrap();
+
+		assert_eq!(blame.lines.len(), 1000);
+		assert!(blame.lines[255].0.is_some());
+		assert!(blame.lines[256].0.is_none());
+	}
+
 	#[test]
 	fn test_blame_windows_path_dividers() {
 		let file_path = Path::new("bar\\foo");
@@ -0,0 +1,329 @@
+//! switching branches with uncommitted changes by stashing them for
+//! the switch
+
+use super::{checkout_branch, checkout_remote_branch, BranchInfo};
+use crate::{
+	error::{Error, Result},
+	sync::{
+		repository::repo,
+		stash::{
+			stash_apply, stash_apply_index, stash_drop, stash_save,
+		},
+		CommitId, RepoPath,
+	},
+};
+use git2::{
+	build::CheckoutBuilder, BranchType, Oid, Repository,
+	StatusOptions,
+};
+use scopetime::scope_time;
+use std::collections::HashSet;
+
+/// where `HEAD` was before the switch
+enum PreviousHead {
+	Branch(String),
+	Detached(Oid),
+}
+
+impl PreviousHead {
+	fn read(repo: &Repository) -> Result<Self> {
+		let head = repo.head()?;
+
+		if repo.head_detached()? {
+			head.target().map(Self::Detached)
+		} else {
+			head.name().map(|name| Self::Branch(name.to_string()))
+		}
+		.ok_or_else(|| Error::Generic(String::from("invalid HEAD")))
+	}
+
+	/// points `HEAD` back here and checks it out, only safe while
+	/// the uncommitted changes are in the stash
+	fn restore(&self, repo: &Repository) -> Result<()> {
+		match self {
+			Self::Branch(name) => repo.set_head(name)?,
+			Self::Detached(oid) => repo.set_head_detached(*oid)?,
+		}
+
+		repo.checkout_head(Some(CheckoutBuilder::new().force()))?;
+
+		Ok(())
+	}
+}
+
+/// applies and drops `stash`, failing without touching the worktree
+/// if it conflicts with `HEAD` (libgit2 would write conflict markers).
+/// staged changes come back staged unless that conflicts, then they
+/// are only restored in the worktree
+fn unstash(
+	repo_path: &RepoPath,
+	repo: &Repository,
+	stash: CommitId,
+) -> Result<()> {
+	let stash_commit = repo.find_commit(stash.get_oid())?;
+	let base = stash_commit.parent(0)?.tree()?;
+	let head = repo.head()?.peel_to_tree()?;
+
+	if repo
+		.merge_trees(&base, &head, &stash_commit.tree()?, None)?
+		.has_conflicts()
+	{
+		return Err(Error::Generic(String::from(
+			"they conflict with the branch",
+		)));
+	}
+
+	if let Err(e) = stash_apply_index(repo_path, stash) {
+		log::warn!("autostash: restoring the index failed: {e}");
+		stash_apply(repo_path, stash, false)?;
+	}
+	stash_drop(repo_path, stash)
+}
+
+/// full names of the local branches
+fn local_branches(repo: &Repository) -> Result<HashSet<String>> {
+	Ok(repo
+		.branches(Some(BranchType::Local))?
+		.filter_map(|branch| {
+			branch.ok()?.0.get().name().map(String::from)
+		})
+		.collect())
+}
+
+pub(super) fn checkout(
+	repo_path: &RepoPath,
+	branch: &BranchInfo,
+) -> Result<()> {
+	if branch.is_local() {
+		checkout_branch(repo_path, &branch.name)
+	} else {
+		checkout_remote_branch(repo_path, branch)
+	}
+}
+
+/// checks out `branch` with the uncommitted changes of tracked files
+/// stashed for the switch and restored on `branch` afterwards.
+///
+/// if the switch or restoring the changes on `branch` fails, `HEAD`
+/// goes back to where it was (a local branch created for a remote
+/// `branch` is deleted again) and the changes are restored there
+pub fn checkout_branch_autostash(
+	repo_path: &RepoPath,
+	branch: &BranchInfo,
+) -> Result<()> {
+	scope_time!("checkout_branch_autostash");
+
+	let repo = repo(repo_path)?;
+	let previous = PreviousHead::read(&repo)?;
+
+	let dirty = !repo
+		.statuses(Some(StatusOptions::new().include_ignored(false)))?
+		.is_empty();
+	if !dirty {
+		return checkout(repo_path, branch);
+	}
+
+	let stash = stash_save(
+		repo_path,
+		Some(&format!(
+			"gitui autostash: switching to {}",
+			branch.name
+		)),
+		false,
+		false,
+	)?;
+
+	// checking out a remote `branch` creates a local one, even if
+	// the checkout fails afterwards
+	let branches_before = (!branch.is_local())
+		.then(|| local_branches(&repo))
+		.transpose()?;
+	let created = || {
+		let before = branches_before.as_ref()?;
+		local_branches(&repo)
+			.ok()?
+			.into_iter()
+			.find(|name| !before.contains(name))
+	};
+
+	if let Err(e) = checkout(repo_path, branch) {
+		rollback(repo_path, &repo, &previous, stash, created())?;
+		return Err(e);
+	}
+
+	if let Err(e) = unstash(repo_path, &repo, stash) {
+		rollback(repo_path, &repo, &previous, stash, created())?;
+
+		return Err(Error::Generic(format!(
+			"the changes could not be restored on '{}', stayed on the current branch: {e}",
+			branch.name
+		)));
+	}
+
+	Ok(())
+}
+
+fn rollback(
+	repo_path: &RepoPath,
+	repo: &Repository,
+	previous: &PreviousHead,
+	stash: CommitId,
+	created_branch: Option<String>,
+) -> Result<()> {
+	let restored = previous.restore(repo).and_then(|()| {
+		if let Some(created) = created_branch {
+			repo.find_reference(&created)?.delete()?;
+		}
+
+		unstash(repo_path, repo, stash)
+	});
+
+	restored.map_err(|e| {
+		Error::Generic(format!(
+			"switching back failed, the changes are kept in the stash {stash}: {e}"
+		))
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		branch::{create_branch, get_branch_name, get_branches_info},
+		get_stashes, stage_add_file,
+		tests::{get_statuses, repo_init, write_commit_file},
+	};
+	use std::{fs, path::Path};
+
+	#[test]
+	fn test_autostash_checkout() {
+		let (td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "a.txt", "a", "a");
+		create_branch(repo_path, "other").unwrap();
+		write_commit_file(&repo, "b.txt", "b", "on other");
+		checkout_branch(repo_path, "master").unwrap();
+
+		fs::write(td.path().join("a.txt"), "changed").unwrap();
+
+		let branches = get_branches_info(repo_path, true).unwrap();
+		let other =
+			branches.iter().find(|b| b.name == "other").unwrap();
+
+		checkout_branch_autostash(repo_path, other).unwrap();
+
+		assert_eq!(get_branch_name(repo_path).unwrap(), "other");
+		assert_eq!(
+			fs::read_to_string(td.path().join("a.txt")).unwrap(),
+			"changed"
+		);
+		assert!(td.path().join("b.txt").exists());
+		assert!(get_stashes(repo_path).unwrap().is_empty());
+	}
+
+	#[test]
+	fn test_autostash_checkout_keeps_staged() {
+		let (td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "a.txt", "a", "a");
+		create_branch(repo_path, "other").unwrap();
+		write_commit_file(&repo, "b.txt", "b", "on other");
+		checkout_branch(repo_path, "master").unwrap();
+
+		fs::write(td.path().join("a.txt"), "staged").unwrap();
+		stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+
+		let branches = get_branches_info(repo_path, true).unwrap();
+		let other =
+			branches.iter().find(|b| b.name == "other").unwrap();
+
+		checkout_branch_autostash(repo_path, other).unwrap();
+
+		assert_eq!(get_branch_name(repo_path).unwrap(), "other");
+		assert_eq!(get_statuses(repo_path), (0, 1));
+	}
+
+	#[test]
+	fn test_autostash_checkout_remote_rollback() {
+		let (td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "a.txt", "a", "a");
+		create_branch(repo_path, "feature").unwrap();
+		let feature = write_commit_file(&repo, "d", "file", "d");
+		repo.reference(
+			"refs/remotes/origin/feature",
+			feature.into(),
+			false,
+			"",
+		)
+		.unwrap();
+		checkout_branch(repo_path, "master").unwrap();
+		repo.find_branch("feature", BranchType::Local)
+			.unwrap()
+			.delete()
+			.unwrap();
+
+		fs::write(td.path().join("a.txt"), "changed").unwrap();
+		// an untracked folder where `feature` has a file fails the
+		// checkout after the local branch got created
+		fs::create_dir(td.path().join("d")).unwrap();
+		fs::write(td.path().join("d/x"), "x").unwrap();
+
+		let branches = get_branches_info(repo_path, false).unwrap();
+		let remote = branches
+			.iter()
+			.find(|b| b.name == "origin/feature")
+			.unwrap();
+
+		assert!(checkout_branch_autostash(repo_path, remote).is_err());
+
+		assert_eq!(get_branch_name(repo_path).unwrap(), "master");
+		assert_eq!(
+			local_branches(&repo).unwrap(),
+			HashSet::from([String::from("refs/heads/master")])
+		);
+		assert_eq!(
+			fs::read_to_string(td.path().join("a.txt")).unwrap(),
+			"changed"
+		);
+		assert!(get_stashes(repo_path).unwrap().is_empty());
+	}
+
+	#[test]
+	fn test_autostash_checkout_rollback() {
+		let (td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "a.txt", "a", "a");
+		create_branch(repo_path, "other").unwrap();
+		write_commit_file(&repo, "a.txt", "other", "on other");
+		checkout_branch(repo_path, "master").unwrap();
+
+		// conflicts with the version of `other`
+		fs::write(td.path().join("a.txt"), "changed").unwrap();
+
+		let branches = get_branches_info(repo_path, true).unwrap();
+		let other =
+			branches.iter().find(|b| b.name == "other").unwrap();
+
+		assert!(checkout_branch_autostash(repo_path, other).is_err());
+
+		assert_eq!(get_branch_name(repo_path).unwrap(), "master");
+		assert_eq!(
+			fs::read_to_string(td.path().join("a.txt")).unwrap(),
+			"changed"
+		);
+		assert!(get_stashes(repo_path).unwrap().is_empty());
+	}
+}
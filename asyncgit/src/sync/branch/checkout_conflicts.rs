@@ -0,0 +1,233 @@
+//! the local changes a branch checkout would overwrite and how to get
+//! them out of the way
+
+use super::{autostash::checkout, BranchInfo};
+use crate::{
+	error::Result,
+	sync::{
+		repository::repo,
+		reset::{reset_stage, reset_workdir},
+		stash::{
+			stash_apply, stash_drop, stash_push_paths,
+			StashPushOptions,
+		},
+		CommitId, RepoPath,
+	},
+};
+use git2::StatusOptions;
+use scopetime::scope_time;
+use std::collections::HashSet;
+
+/// the files with local changes that checking out `branch` would
+/// overwrite, these make the checkout fail.
+///
+/// like libgit2's safe checkout these are the files that differ
+/// between `HEAD` and `branch` and also have local changes (git2 does
+/// not expose libgit2's dry-run checkout to ask it directly)
+pub fn checkout_conflicts(
+	repo_path: &RepoPath,
+	branch: &BranchInfo,
+) -> Result<Vec<String>> {
+	scope_time!("checkout_conflicts");
+
+	let repo = repo(repo_path)?;
+	let head = repo.head()?.peel_to_tree()?;
+	let target =
+		repo.find_commit(branch.top_commit.get_oid())?.tree()?;
+
+	let diff =
+		repo.diff_tree_to_tree(Some(&head), Some(&target), None)?;
+	let changed = diff
+		.deltas()
+		.flat_map(|delta| {
+			[delta.old_file().path(), delta.new_file().path()]
+		})
+		.flatten()
+		.map(|path| path.to_string_lossy().to_string())
+		.collect::<HashSet<_>>();
+
+	let statuses = repo.statuses(Some(
+		StatusOptions::new()
+			.include_untracked(true)
+			.recurse_untracked_dirs(true)
+			.include_ignored(false),
+	))?;
+
+	let mut conflicts = statuses
+		.iter()
+		.filter_map(|entry| entry.path().map(String::from))
+		.filter(|path| changed.contains(path))
+		.collect::<Vec<_>>();
+	conflicts.sort();
+	conflicts.dedup();
+
+	Ok(conflicts)
+}
+
+/// what to do with the local changes of a file in the way of a
+/// checkout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictResolution {
+	/// keep them in a stash
+	Stash,
+	/// throw them away
+	Discard,
+}
+
+/// gets the local changes of `files` out of the way as chosen and
+/// checks out `branch`, the other local changes are carried over.
+///
+/// returns the stash keeping the stashed files, if the checkout fails
+/// anyway they are restored (discarded files are gone)
+pub fn checkout_branch_resolved(
+	repo_path: &RepoPath,
+	branch: &BranchInfo,
+	files: &[(String, ConflictResolution)],
+) -> Result<Option<CommitId>> {
+	scope_time!("checkout_branch_resolved");
+
+	let stashed = files
+		.iter()
+		.filter(|(_, resolution)| {
+			*resolution == ConflictResolution::Stash
+		})
+		.map(|(path, _)| path.clone())
+		.collect::<Vec<_>>();
+
+	let stash = if stashed.is_empty() {
+		None
+	} else {
+		Some(stash_push_paths(
+			repo_path,
+			&stashed,
+			&StashPushOptions {
+				message: Some(format!(
+					"gitui: local changes in the way of {}",
+					branch.name
+				)),
+				..StashPushOptions::default()
+			},
+		)?)
+	};
+
+	for (path, resolution) in files {
+		if *resolution == ConflictResolution::Discard {
+			reset_stage(repo_path, path)?;
+			reset_workdir(repo_path, path)?;
+		}
+	}
+
+	if let Err(e) = checkout(repo_path, branch) {
+		if let Some(stash) = stash {
+			stash_apply(repo_path, stash, false)?;
+			stash_drop(repo_path, stash)?;
+		}
+
+		return Err(e);
+	}
+
+	Ok(stash)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		branch::{
+			checkout_branch, create_branch, get_branch_name,
+			get_branches_info,
+		},
+		get_stashes,
+		tests::{repo_init, write_commit_file},
+	};
+	use std::fs;
+
+	#[test]
+	fn test_checkout_conflicts() {
+		let (td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "a.txt", "a", "a");
+		write_commit_file(&repo, "b.txt", "b", "b");
+		create_branch(repo_path, "other").unwrap();
+		write_commit_file(&repo, "a.txt", "other", "on other");
+		checkout_branch(repo_path, "master").unwrap();
+
+		fs::write(td.path().join("a.txt"), "changed").unwrap();
+		fs::write(td.path().join("b.txt"), "changed").unwrap();
+
+		let branches = get_branches_info(repo_path, true).unwrap();
+		let other =
+			branches.iter().find(|b| b.name == "other").unwrap();
+
+		assert_eq!(
+			checkout_conflicts(repo_path, other).unwrap(),
+			vec![String::from("a.txt")]
+		);
+		assert_eq!(
+			fs::read_to_string(td.path().join("a.txt")).unwrap(),
+			"changed"
+		);
+
+		let stash = checkout_branch_resolved(
+			repo_path,
+			other,
+			&[(String::from("a.txt"), ConflictResolution::Stash)],
+		)
+		.unwrap();
+
+		assert_eq!(get_branch_name(repo_path).unwrap(), "other");
+		assert_eq!(
+			fs::read_to_string(td.path().join("a.txt")).unwrap(),
+			"other"
+		);
+		// the other change came along
+		assert_eq!(
+			fs::read_to_string(td.path().join("b.txt")).unwrap(),
+			"changed"
+		);
+		assert_eq!(
+			get_stashes(repo_path).unwrap(),
+			vec![stash.unwrap()]
+		);
+	}
+
+	#[test]
+	fn test_checkout_conflicts_discarded() {
+		let (td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "a.txt", "a", "a");
+		create_branch(repo_path, "other").unwrap();
+		write_commit_file(&repo, "new.txt", "new", "on other");
+		checkout_branch(repo_path, "master").unwrap();
+
+		// untracked, added on other
+		fs::write(td.path().join("new.txt"), "untracked").unwrap();
+
+		let branches = get_branches_info(repo_path, true).unwrap();
+		let other =
+			branches.iter().find(|b| b.name == "other").unwrap();
+
+		let conflicts = checkout_conflicts(repo_path, other).unwrap();
+		assert_eq!(conflicts, vec![String::from("new.txt")]);
+
+		let stash = checkout_branch_resolved(
+			repo_path,
+			other,
+			&[(String::from("new.txt"), ConflictResolution::Discard)],
+		)
+		.unwrap();
+
+		assert!(stash.is_none());
+		assert_eq!(get_branch_name(repo_path).unwrap(), "other");
+		assert_eq!(
+			fs::read_to_string(td.path().join("new.txt")).unwrap(),
+			"new"
+		);
+	}
+}
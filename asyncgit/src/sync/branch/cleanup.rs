@@ -0,0 +1,220 @@
+//! finding local branches that are no longer needed
+
+use crate::{
+	error::{Error, Result},
+	sync::{
+		remotes::get_default_remote_in_repo, repository::repo,
+		utils::bytes2string, RepoPath,
+	},
+};
+use git2::{BranchType, Repository};
+use scopetime::scope_time;
+
+use super::protection::{check_protection, ProtectedAction};
+
+/// why a branch is no longer needed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanupReason {
+	/// everything on it is merged into the base branch
+	Merged,
+	/// its upstream was deleted on the remote
+	UpstreamGone,
+}
+
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CleanupCandidate {
+	///
+	pub name: String,
+	///
+	pub reference: String,
+	///
+	pub reason: CleanupReason,
+}
+
+/// local branch the others get merged into: the one `HEAD` of the
+/// default remote points to, else `main` or `master`
+pub fn default_branch(
+	repo_path: &RepoPath,
+) -> Result<Option<String>> {
+	scope_time!("default_branch");
+
+	let repo = repo(repo_path)?;
+
+	let remote_head =
+		get_default_remote_in_repo(&repo).ok().and_then(|remote| {
+			let head = repo
+				.find_reference(&format!(
+					"refs/remotes/{remote}/HEAD"
+				))
+				.ok()?;
+			let target = head.symbolic_target()?;
+			target
+				.strip_prefix(&format!("refs/remotes/{remote}/"))
+				.map(String::from)
+		});
+
+	Ok(remote_head
+		.into_iter()
+		.chain(["main", "master"].map(String::from))
+		.find(|name| is_local_branch(&repo, name)))
+}
+
+fn is_local_branch(repo: &Repository, name: &str) -> bool {
+	repo.find_branch(name, BranchType::Local).is_ok()
+}
+
+/// local branches besides `base` and the checked out one that are
+/// merged into `base` or whose upstream is gone
+pub fn cleanup_candidates(
+	repo_path: &RepoPath,
+	base: &str,
+) -> Result<Vec<CleanupCandidate>> {
+	scope_time!("cleanup_candidates");
+
+	let repo = repo(repo_path)?;
+
+	let base_commit = repo
+		.find_branch(base, BranchType::Local)?
+		.get()
+		.peel_to_commit()?
+		.id();
+
+	let config = repo.config()?.snapshot()?;
+	let mut candidates = Vec::new();
+
+	for branch in repo.branches(Some(BranchType::Local))? {
+		let branch = branch?.0;
+		let name = bytes2string(branch.name_bytes()?)?;
+
+		if branch.is_head() || name == base {
+			continue;
+		}
+
+		let reference = bytes2string(branch.get().name_bytes())?;
+		let commit = branch.get().peel_to_commit()?.id();
+
+		let reason = if commit == base_commit
+			|| repo.graph_descendant_of(base_commit, commit)?
+		{
+			CleanupReason::Merged
+		} else if config
+			.get_str(&format!("branch.{name}.merge"))
+			.is_ok() && branch.upstream().is_err()
+		{
+			CleanupReason::UpstreamGone
+		} else {
+			continue;
+		};
+
+		candidates.push(CleanupCandidate {
+			name,
+			reference,
+			reason,
+		});
+	}
+
+	Ok(candidates)
+}
+
+fn delete_branch_repo(
+	repo: &Repository,
+	branch_ref: &str,
+) -> Result<()> {
+	let mut branch =
+		git2::Branch::wrap(repo.find_reference(branch_ref)?);
+	if let Some(name) = branch.name()? {
+		check_protection(repo, name, ProtectedAction::Delete)?;
+	}
+
+	Ok(branch.delete()?)
+}
+
+/// deletes all the branch references `branch_refs`, even if some of
+/// them fail
+pub fn delete_branches(
+	repo_path: &RepoPath,
+	branch_refs: &[String],
+) -> Result<()> {
+	scope_time!("delete_branches");
+
+	let repo = repo(repo_path)?;
+
+	let failed: Vec<String> = branch_refs
+		.iter()
+		.filter_map(|branch_ref| {
+			delete_branch_repo(&repo, branch_ref)
+				.err()
+				.map(|e| format!("{branch_ref}: {e}"))
+		})
+		.collect();
+
+	if failed.is_empty() {
+		Ok(())
+	} else {
+		Err(Error::Generic(failed.join("\n")))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		checkout_branch, create_branch,
+		tests::{repo_init, write_commit_file},
+	};
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_cleanup_candidates() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		assert_eq!(
+			default_branch(repo_path).unwrap().as_deref(),
+			Some("master")
+		);
+
+		create_branch(repo_path, "merged").unwrap();
+		create_branch(repo_path, "gone").unwrap();
+		write_commit_file(&repo, "gone.txt", "gone", "gone");
+		repo.config()
+			.unwrap()
+			.set_str("branch.gone.remote", "origin")
+			.unwrap();
+		repo.config()
+			.unwrap()
+			.set_str("branch.gone.merge", "refs/heads/gone")
+			.unwrap();
+		create_branch(repo_path, "wip").unwrap();
+		write_commit_file(&repo, "wip.txt", "wip", "wip");
+		checkout_branch(repo_path, "master").unwrap();
+
+		let candidates =
+			cleanup_candidates(repo_path, "master").unwrap();
+		assert_eq!(
+			candidates
+				.iter()
+				.map(|c| (c.name.as_str(), c.reason))
+				.collect::<Vec<_>>(),
+			vec![
+				("gone", CleanupReason::UpstreamGone),
+				("merged", CleanupReason::Merged),
+			]
+		);
+
+		delete_branches(
+			repo_path,
+			&[
+				String::from("refs/heads/merged"),
+				String::from("refs/heads/missing"),
+			],
+		)
+		.unwrap_err();
+		assert!(repo
+			.find_branch("merged", BranchType::Local)
+			.is_err());
+	}
+}
@@ -117,6 +117,7 @@ mod test {
 			"master",
 			None,
 			None,
+			None,
 		)
 		.unwrap();
 		assert!(bytes > 0);
@@ -126,6 +127,7 @@ mod test {
 			"master",
 			None,
 			None,
+			None,
 		)
 		.unwrap();
 		assert_eq!(bytes, 0);
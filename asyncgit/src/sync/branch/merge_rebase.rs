@@ -138,7 +138,8 @@ mod test {
 
 		//lets fetch from origin
 		let bytes =
-			fetch(&clone1_dir.into(), "master", None, None).unwrap();
+			fetch(&clone1_dir.into(), "master", None, None, None)
+				.unwrap();
 		assert!(bytes > 0);
 
 		//we should be one commit behind
@@ -249,7 +250,8 @@ mod test {
 
 		//lets fetch from origin
 
-		fetch(&clone1_dir.into(), "master", None, None).unwrap();
+		fetch(&clone1_dir.into(), "master", None, None, None)
+			.unwrap();
 
 		merge_upstream_rebase(&clone1_dir.into(), "master").unwrap();
 
@@ -329,7 +331,8 @@ mod test {
 			write_commit_file(&clone1, "test2.txt", "foo", "commit3");
 
 		let bytes =
-			fetch(&clone1_dir.into(), "master", None, None).unwrap();
+			fetch(&clone1_dir.into(), "master", None, None, None)
+				.unwrap();
 		assert!(bytes > 0);
 
 		assert_eq!(
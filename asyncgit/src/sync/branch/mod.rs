@@ -1,19 +1,29 @@
 //! branch functions
 
+pub mod autostash;
+pub mod checkout_conflicts;
+pub mod cleanup;
 pub mod merge_commit;
 pub mod merge_ff;
 pub mod merge_rebase;
+pub mod protection;
 pub mod rename;
 
 use super::{utils::bytes2string, RepoPath};
 use crate::{
 	error::{Error, Result},
 	sync::{
-		remotes::get_default_remote_for_push_in_repo,
-		repository::repo, utils::get_head_repo, CommitId,
+		remotes::{
+			advertised::cached_advertised_ref,
+			get_default_remote_for_push_in_repo,
+		},
+		repository::repo,
+		utils::get_head_repo,
+		CommitId,
 	},
 };
-use git2::{Branch, BranchType, Repository};
+use git2::{Branch, BranchType, Oid, Repository};
+use protection::{check_protection, ProtectedAction};
 use scopetime::scope_time;
 use std::collections::HashSet;
 
@@ -91,6 +101,10 @@ pub struct BranchInfo {
 	pub top_commit_message: String,
 	///
 	pub top_commit: CommitId,
+	/// commit time of `top_commit` in seconds since the epoch
+	pub top_commit_time: i64,
+	///
+	pub top_commit_author: String,
 	///
 	pub details: BranchDetails,
 }
@@ -164,6 +178,8 @@ pub fn get_branches_info(
 				.map(String::from);
 
 			let name_bytes = branch.name_bytes()?;
+			let author =
+				bytes2string(top_commit.author().name_bytes())?;
 
 			let upstream_branch =
 				upstream.ok().and_then(|upstream| {
@@ -193,6 +209,8 @@ pub fn get_branches_info(
 					top_commit.summary_bytes().unwrap_or_default(),
 				)?,
 				top_commit: top_commit.id().into(),
+				top_commit_time: top_commit.time().seconds(),
+				top_commit_author: author,
 				details,
 			})
 		})
@@ -205,7 +223,7 @@ pub fn get_branches_info(
 }
 
 ///
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct BranchCompare {
 	///
 	pub ahead: usize,
@@ -290,6 +308,7 @@ pub fn branch_compare_upstream(
 
 	let repo = repo(repo_path)?;
 
+	let branch_name = branch;
 	let branch = repo.find_branch(branch, BranchType::Local)?;
 
 	let upstream = branch.upstream()?;
@@ -299,6 +318,8 @@ pub fn branch_compare_upstream(
 
 	let upstream_commit =
 		upstream.into_reference().peel_to_commit()?.id();
+	let upstream_commit =
+		advertised_upstream_tip(&repo, branch_name, upstream_commit);
 
 	let (ahead, behind) =
 		repo.graph_ahead_behind(branch_commit, upstream_commit)?;
@@ -306,6 +327,62 @@ pub fn branch_compare_upstream(
 	Ok(BranchCompare { ahead, behind })
 }
 
+/// compares each of the local `branches` with its upstream,
+/// opening the repository only once. branches without an upstream
+/// yield `None`
+pub fn branches_compare_upstream(
+	repo_path: &RepoPath,
+	branches: &[String],
+) -> Result<Vec<Option<BranchCompare>>> {
+	scope_time!("branches_compare_upstream");
+
+	let repo = repo(repo_path)?;
+
+	Ok(branches
+		.iter()
+		.map(|name| {
+			let branch =
+				repo.find_branch(name, BranchType::Local).ok()?;
+			let upstream = branch.upstream().ok()?;
+
+			let branch_commit =
+				branch.into_reference().peel_to_commit().ok()?.id();
+			let upstream_commit =
+				upstream.into_reference().peel_to_commit().ok()?.id();
+			let upstream_commit =
+				advertised_upstream_tip(&repo, name, upstream_commit);
+
+			let (ahead, behind) = repo
+				.graph_ahead_behind(branch_commit, upstream_commit)
+				.ok()?;
+
+			Some(BranchCompare { ahead, behind })
+		})
+		.collect())
+}
+
+/// where the remote had the upstream of `branch` when it listed its
+/// refs moments ago, e.g. before a push, in case that is newer than
+/// the tracking branch and the commit is known locally
+fn advertised_upstream_tip(
+	repo: &Repository,
+	branch: &str,
+	tracking: Oid,
+) -> Oid {
+	let advertised = || {
+		let refname = format!("refs/heads/{branch}");
+		let remote = repo.branch_upstream_remote(&refname).ok()?;
+		let remote = repo.find_remote(remote.as_str()?).ok()?;
+		let url = remote.pushurl().or_else(|| remote.url())?;
+		let merge = repo.branch_upstream_merge(&refname).ok()?;
+
+		let oid = cached_advertised_ref(repo, url, merge.as_str()?)?;
+		repo.find_commit(oid).ok().map(|commit| commit.id())
+	};
+
+	advertised().unwrap_or(tracking)
+}
+
 /// Switch branch to given `branch_name`.
 ///
 /// Method will fail if there are conflicting changes between current and target branch. However,
@@ -428,6 +505,9 @@ pub fn delete_branch(
 	if branch.is_head() {
 		return Err(Error::Generic("You cannot be on the branch you want to delete, switch branch, then delete this branch".to_string()));
 	}
+	if let Some(name) = branch.name()? {
+		check_protection(&repo, name, ProtectedAction::Delete)?;
+	}
 	branch.delete()?;
 	Ok(())
 }
@@ -508,7 +588,7 @@ mod tests_create_branch {
 #[cfg(test)]
 mod tests_branch_compare {
 	use super::*;
-	use crate::sync::tests::repo_init;
+	use crate::sync::tests::{repo_init, write_commit_file};
 
 	#[test]
 	fn test_smoke() {
@@ -523,6 +603,44 @@ mod tests_branch_compare {
 
 		assert!(res.is_err());
 	}
+
+	#[test]
+	fn test_batch() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		create_branch(repo_path, "test").unwrap();
+		write_commit_file(&repo, "f.txt", "foo", "c1");
+		write_commit_file(&repo, "f.txt", "bar", "c2");
+		repo.find_branch("test", BranchType::Local)
+			.unwrap()
+			.set_upstream(Some("master"))
+			.unwrap();
+
+		let res = branches_compare_upstream(
+			repo_path,
+			&[
+				String::from("master"),
+				String::from("test"),
+				String::from("missing"),
+			],
+		)
+		.unwrap();
+
+		assert_eq!(
+			res,
+			vec![
+				None,
+				Some(BranchCompare {
+					ahead: 2,
+					behind: 0
+				}),
+				None,
+			]
+		);
+	}
 }
 
 #[cfg(test)]
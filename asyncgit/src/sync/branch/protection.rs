@@ -0,0 +1,206 @@
+//! protected branches, configured per repo in git config:
+//! * `gitui.protectedBranch` (multi-valued): a branch name or a glob
+//!   like `release/*`
+//! * `gitui.protectedBranchPolicy`: `warn` (default) to let gitui
+//!   ask before, or `block` to refuse commits, force pushes and
+//!   deleting them
+
+use crate::{
+	error::{Error, Result},
+	sync::{
+		config::{get_config_strings, get_config_strings_repo},
+		repository::repo,
+		utils::glob_match,
+		RepoPath,
+	},
+};
+use git2::Repository;
+use std::fmt;
+
+const PROTECTED_CONFIG: &str = "gitui.protectedBranch";
+const POLICY_CONFIG: &str = "gitui.protectedBranchPolicy";
+
+/// what is guarded on a protected branch
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProtectedAction {
+	///
+	Commit,
+	///
+	ForcePush,
+	///
+	Delete,
+}
+
+impl fmt::Display for ProtectedAction {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::Commit => "committing",
+			Self::ForcePush => "force pushing",
+			Self::Delete => "deleting",
+		})
+	}
+}
+
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BranchProtection {
+	/// ask before
+	Warn,
+	/// refuse
+	Block,
+}
+
+fn protection_repo(
+	repo: &Repository,
+	branch: &str,
+) -> Result<Option<BranchProtection>> {
+	for pattern in get_config_strings_repo(repo, PROTECTED_CONFIG)? {
+		if glob_match(pattern.trim(), branch) {
+			return Ok(Some(
+				match repo
+					.config()?
+					.get_string(POLICY_CONFIG)
+					.as_deref()
+				{
+					Ok("block") => BranchProtection::Block,
+					_ => BranchProtection::Warn,
+				},
+			));
+		}
+	}
+
+	Ok(None)
+}
+
+/// how the branch `HEAD` points to is protected
+pub fn head_protection(
+	repo_path: &RepoPath,
+) -> Result<Option<BranchProtection>> {
+	let repo = repo(repo_path)?;
+
+	head_branch(&repo)
+		.map_or(Ok(None), |branch| protection_repo(&repo, &branch))
+}
+
+fn head_branch(repo: &Repository) -> Option<String> {
+	let head = repo.head().ok()?;
+
+	head.is_branch()
+		.then(|| head.shorthand().map(String::from))
+		.flatten()
+}
+
+/// fails if `action` on `branch` is blocked
+pub(crate) fn check_protection(
+	repo: &Repository,
+	branch: &str,
+	action: ProtectedAction,
+) -> Result<()> {
+	if protection_repo(repo, branch)? == Some(BranchProtection::Block)
+	{
+		return Err(Error::ProtectedBranch(
+			branch.to_string(),
+			action,
+		));
+	}
+
+	Ok(())
+}
+
+/// fails if committing on the branch `HEAD` points to is blocked
+pub(crate) fn check_head_protection(repo: &Repository) -> Result<()> {
+	head_branch(repo).map_or(Ok(()), |branch| {
+		check_protection(repo, &branch, ProtectedAction::Commit)
+	})
+}
+
+/// the protected ones of `branches`
+pub fn protected_branches<'a>(
+	repo_path: &RepoPath,
+	branches: impl IntoIterator<Item = &'a str>,
+) -> Result<Vec<String>> {
+	let patterns = get_config_strings(repo_path, PROTECTED_CONFIG)?;
+
+	Ok(branches
+		.into_iter()
+		.filter(|branch| {
+			patterns
+				.iter()
+				.any(|pattern| glob_match(pattern.trim(), branch))
+		})
+		.map(String::from)
+		.collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		commit, create_branch, delete_branch, stage_add_file,
+		tests::{repo_init, write_commit_file},
+	};
+	use std::{fs::File, io::Write, path::Path};
+
+	#[test]
+	fn test_protection() {
+		let (td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "a.txt", "a", "a");
+		assert_eq!(head_protection(repo_path).unwrap(), None);
+
+		let mut config = repo.config().unwrap();
+		config
+			.set_multivar(PROTECTED_CONFIG, "^$", "master")
+			.unwrap();
+		config
+			.set_multivar(PROTECTED_CONFIG, "^$", "release/*")
+			.unwrap();
+
+		assert_eq!(
+			head_protection(repo_path).unwrap(),
+			Some(BranchProtection::Warn)
+		);
+		assert_eq!(
+			protected_branches(
+				repo_path,
+				["master", "release/1", "feature"]
+			)
+			.unwrap(),
+			vec![String::from("master"), String::from("release/1")]
+		);
+
+		// warnings are up to the ui
+		File::create(td.path().join("a.txt"))
+			.unwrap()
+			.write_all(b"b")
+			.unwrap();
+		stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+		commit(repo_path, "on master").unwrap();
+
+		config.set_str(POLICY_CONFIG, "block").unwrap();
+
+		File::create(td.path().join("a.txt"))
+			.unwrap()
+			.write_all(b"c")
+			.unwrap();
+		stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+		assert!(matches!(
+			commit(repo_path, "blocked"),
+			Err(Error::ProtectedBranch(_, ProtectedAction::Commit))
+		));
+
+		create_branch(repo_path, "release/1").unwrap();
+		commit(repo_path, "on release").unwrap_err();
+		create_branch(repo_path, "feature").unwrap();
+		commit(repo_path, "feature").unwrap();
+
+		assert!(matches!(
+			delete_branch(repo_path, "refs/heads/release/1"),
+			Err(Error::ProtectedBranch(_, ProtectedAction::Delete))
+		));
+		delete_branch(repo_path, "refs/heads/master").unwrap_err();
+	}
+}
@@ -1,12 +1,13 @@
-//! renaming of branches
+//! renaming and copying of branches
 
 use crate::{
-	error::Result,
+	error::{Error, Result},
 	sync::{repository::repo, RepoPath},
 };
 use scopetime::scope_time;
 
-/// Rename the branch reference
+/// Rename the branch reference, its config (like the upstream) moves
+/// along
 pub fn rename_branch(
 	repo_path: &RepoPath,
 	branch_ref: &str,
@@ -22,11 +23,57 @@ pub fn rename_branch(
 	Ok(())
 }
 
+/// creates the branch `new_name` at the commit of the branch
+/// reference `branch_ref` with a copy of its config, like
+/// `git branch --copy`
+pub fn copy_branch(
+	repo_path: &RepoPath,
+	branch_ref: &str,
+	new_name: &str,
+) -> Result<()> {
+	scope_time!("copy_branch");
+
+	let repo = repo(repo_path)?;
+	let branch = git2::Branch::wrap(repo.find_reference(branch_ref)?);
+	let old_name = branch
+		.name()?
+		.ok_or_else(|| {
+			Error::Generic(format!("invalid branch: {branch_ref}"))
+		})?
+		.to_string();
+
+	repo.branch(new_name, &branch.get().peel_to_commit()?, false)?;
+
+	let mut config = repo.config()?;
+	let prefix = format!("branch.{old_name}.");
+
+	let mut copied = Vec::new();
+	let mut entries = config.entries(None)?;
+	while let Some(entry) = entries.next() {
+		let entry = entry?;
+		if let (Some(key), Some(value)) = (
+			entry.name().and_then(|name| name.strip_prefix(&prefix)),
+			entry.value(),
+		) {
+			copied.push((key.to_string(), value.to_string()));
+		}
+	}
+	drop(entries);
+
+	for (key, value) in copied {
+		config
+			.set_str(&format!("branch.{new_name}.{key}"), &value)?;
+	}
+
+	Ok(())
+}
+
 #[cfg(test)]
 mod test {
 	use super::super::{checkout_branch, create_branch, RepoPath};
-	use super::rename_branch;
+	use super::{copy_branch, rename_branch};
 	use crate::sync::tests::repo_init;
+	use git2::BranchType;
 
 	#[test]
 	fn test_rename_branch() {
@@ -68,4 +115,68 @@ mod test {
 			"AnotherName"
 		);
 	}
+
+	#[test]
+	fn test_rename_keeps_upstream() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		create_branch(repo_path, "branch1").unwrap();
+		let mut config = repo.config().unwrap();
+		config.set_str("branch.branch1.remote", "origin").unwrap();
+
+		rename_branch(repo_path, "refs/heads/branch1", "branch2")
+			.unwrap();
+
+		let config = repo.config().unwrap().snapshot().unwrap();
+		assert_eq!(
+			config.get_str("branch.branch2.remote").unwrap(),
+			"origin"
+		);
+		assert!(config.get_str("branch.branch1.remote").is_err());
+	}
+
+	#[test]
+	fn test_copy_branch() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		create_branch(repo_path, "branch1").unwrap();
+		let mut config = repo.config().unwrap();
+		config.set_str("branch.branch1.remote", "origin").unwrap();
+		config
+			.set_str("branch.branch1.merge", "refs/heads/branch1")
+			.unwrap();
+
+		copy_branch(repo_path, "refs/heads/branch1", "branch2")
+			.unwrap();
+
+		let commit = |name| {
+			repo.find_branch(name, BranchType::Local)
+				.unwrap()
+				.get()
+				.peel_to_commit()
+				.unwrap()
+				.id()
+		};
+		assert_eq!(commit("branch1"), commit("branch2"));
+
+		let config = repo.config().unwrap().snapshot().unwrap();
+		assert_eq!(
+			config.get_str("branch.branch2.remote").unwrap(),
+			"origin"
+		);
+		assert_eq!(
+			config.get_str("branch.branch2.merge").unwrap(),
+			"refs/heads/branch1"
+		);
+		assert_eq!(
+			config.get_str("branch.branch1.remote").unwrap(),
+			"origin"
+		);
+	}
 }
@@ -3,29 +3,45 @@ use super::{CommitId, RepoPath};
 use crate::sync::sign::{SignBuilder, SignError};
 use crate::{
 	error::{Error, Result},
-	sync::{repository::repo, utils::get_head_repo},
+	sync::{
+		branch::protection::check_head_protection, repository::repo,
+		utils::get_head_repo,
+	},
 };
 use git2::{
-	message_prettify, ErrorCode, ObjectType, Repository, Signature,
+	message_prettify, Commit, ErrorCode, Index, ObjectType,
+	Repository, Signature, Tree,
 };
 use scopetime::scope_time;
+use std::path::Path;
 
 ///
 pub fn amend(
 	repo_path: &RepoPath,
 	id: CommitId,
 	msg: &str,
+) -> Result<CommitId> {
+	amend_with_files(repo_path, id, msg, &[])
+}
+
+/// amends `id` with the staged changes except for the ones in
+/// `keep_staged`, those stay as they are in `id` and remain staged
+/// for a follow-up commit
+pub fn amend_with_files(
+	repo_path: &RepoPath,
+	id: CommitId,
+	msg: &str,
+	keep_staged: &[String],
 ) -> Result<CommitId> {
 	scope_time!("amend");
 
 	let repo = repo(repo_path)?;
+	check_head_protection(&repo)?;
 	let config = repo.config()?;
 
 	let commit = repo.find_commit(id.into())?;
 
-	let mut index = repo.index()?;
-	let tree_id = index.write_tree()?;
-	let tree = repo.find_tree(tree_id)?;
+	let tree = amend_tree(&repo, &commit, keep_staged)?;
 
 	if config.get_bool("commit.gpgsign").unwrap_or(false) {
 		// HACK: we undo the last commit and create a new one
@@ -34,7 +50,7 @@ pub fn amend(
 		let head = get_head_repo(&repo)?;
 		if head == commit.id().into() {
 			undo_last_commit(repo_path)?;
-			return self::commit(repo_path, msg);
+			return commit_tree(&repo, &tree, msg);
 		}
 
 		return Err(Error::SignAmendNonLastCommit);
@@ -54,6 +70,58 @@ pub fn amend(
 	Ok(CommitId::new(new_id))
 }
 
+/// builds the tree of the index, but with the paths in `keep_staged`
+/// taken from `commit`
+fn amend_tree<'a>(
+	repo: &'a Repository,
+	commit: &Commit,
+	keep_staged: &[String],
+) -> Result<Tree<'a>> {
+	let mut index = repo.index()?;
+
+	if keep_staged.is_empty() {
+		let tree_id = index.write_tree()?;
+		return Ok(repo.find_tree(tree_id)?);
+	}
+
+	let commit_tree = commit.tree()?;
+	let diff = repo.diff_tree_to_index(
+		Some(&commit_tree),
+		Some(&index),
+		None,
+	)?;
+
+	let mut tree_index = Index::new()?;
+	tree_index.read_tree(&commit_tree)?;
+
+	for delta in diff.deltas() {
+		let Some(path) = delta
+			.new_file()
+			.path()
+			.or_else(|| delta.old_file().path())
+		else {
+			continue;
+		};
+
+		if keep_staged
+			.iter()
+			.any(|keep| Path::new(keep.as_str()) == path)
+		{
+			continue;
+		}
+
+		if let Some(entry) = index.get_path(path, 0) {
+			tree_index.add(&entry)?;
+		} else {
+			tree_index.remove_path(path)?;
+		}
+	}
+
+	let tree_id = tree_index.write_tree_to(repo)?;
+
+	Ok(repo.find_tree(tree_id)?)
+}
+
 /// Wrap `Repository::signature` to allow unknown user.name.
 ///
 /// See <https://github.com/gitui-org/gitui/issues/79>.
@@ -85,13 +153,24 @@ pub fn commit(repo_path: &RepoPath, msg: &str) -> Result<CommitId> {
 	scope_time!("commit");
 
 	let repo = repo(repo_path)?;
-	let config = repo.config()?;
-	let signature = signature_allow_undefined_name(&repo)?;
+	check_head_protection(&repo)?;
 	let mut index = repo.index()?;
 	let tree_id = index.write_tree()?;
 	let tree = repo.find_tree(tree_id)?;
 
-	let parents = if let Ok(id) = get_head_repo(&repo) {
+	commit_tree(&repo, &tree, msg)
+}
+
+/// commits `tree` on top of HEAD
+pub(super) fn commit_tree(
+	repo: &Repository,
+	tree: &Tree,
+	msg: &str,
+) -> Result<CommitId> {
+	let config = repo.config()?;
+	let signature = signature_allow_undefined_name(repo)?;
+
+	let parents = if let Ok(id) = get_head_repo(repo) {
 		vec![repo.find_commit(id.into())?]
 	} else {
 		Vec::new()
@@ -107,7 +186,7 @@ pub fn commit(repo_path: &RepoPath, msg: &str) -> Result<CommitId> {
 			&signature,
 			&signature,
 			msg,
-			&tree,
+			tree,
 			parents.as_slice(),
 		)?;
 
@@ -115,7 +194,7 @@ pub fn commit(repo_path: &RepoPath, msg: &str) -> Result<CommitId> {
 			SignError::Shellout("utf8 conversion error".to_string())
 		})?;
 
-		let signer = SignBuilder::from_gitconfig(&repo, &config)?;
+		let signer = SignBuilder::from_gitconfig(repo, &config)?;
 		let (signature, signature_field) = signer.sign(&buffer)?;
 		let commit_id = repo.commit_signed(
 			commit,
@@ -147,7 +226,7 @@ pub fn commit(repo_path: &RepoPath, msg: &str) -> Result<CommitId> {
 			&signature,
 			&signature,
 			msg,
-			&tree,
+			tree,
 			parents.as_slice(),
 		)?
 	};
@@ -210,7 +289,9 @@ mod tests {
 		utils::get_head,
 		LogWalker,
 	};
-	use commit::{amend, commit_message_prettify, tag_commit};
+	use commit::{
+		amend, amend_with_files, commit_message_prettify, tag_commit,
+	};
 	use git2::Repository;
 	use std::{fs::File, io::Write, path::Path};
 
@@ -309,6 +390,46 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn test_amend_with_files() -> Result<()> {
+		let file_path1 = Path::new("foo");
+		let file_path2 = Path::new("foo2");
+		let (_td, repo) = repo_init_empty()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		File::create(root.join(file_path1))?.write_all(b"test1")?;
+
+		stage_add_file(repo_path, file_path1)?;
+		let id = commit(repo_path, "commit msg")?;
+
+		File::create(root.join(file_path1))?.write_all(b"test2")?;
+		File::create(root.join(file_path2))?.write_all(b"test2")?;
+
+		stage_add_file(repo_path, file_path1)?;
+		stage_add_file(repo_path, file_path2)?;
+
+		let new_id = amend_with_files(
+			repo_path,
+			id,
+			"amended",
+			&[String::from("foo2")],
+		)?;
+
+		assert_eq!(count_commits(&repo, 10), 1);
+
+		let files = get_commit_files(repo_path, new_id, None)?;
+
+		assert_eq!(files.len(), 1);
+		assert_eq!(files[0].path, "foo");
+
+		// the left out file is still staged
+		assert_eq!(get_statuses(repo_path), (0, 1));
+
+		Ok(())
+	}
+
 	#[test]
 	fn test_amend_with_different_user() {
 		let file_path1 = Path::new("foo");
@@ -0,0 +1,322 @@
+//! built-in checks of the staged lines before committing, run next to
+//! the pre-commit hook once `gitui.commitChecks` is set to true:
+//! * whitespace errors as configured in `core.whitespace` (like
+//!   `git diff --check`)
+//! * leftover conflict markers
+
+use super::{repository::repo, RepoPath};
+use crate::error::Result;
+use git2::{DiffOptions, Repository};
+use scopetime::scope_time;
+use std::fmt;
+
+const CHECKS_CONFIG: &str = "gitui.commitChecks";
+const CONFLICT_MARKERS: [&str; 4] =
+	["<<<<<<<", "|||||||", "=======", ">>>>>>>"];
+
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckKind {
+	/// `blank-at-eol`
+	TrailingWhitespace,
+	/// `space-before-tab`
+	SpaceBeforeTab,
+	/// `indent-with-non-tab`
+	IndentWithNonTab,
+	/// `tab-in-indent`
+	TabInIndent,
+	///
+	ConflictMarker,
+}
+
+impl fmt::Display for CheckKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::TrailingWhitespace => "trailing whitespace",
+			Self::SpaceBeforeTab => "space before tab in indent",
+			Self::IndentWithNonTab => "indent with spaces",
+			Self::TabInIndent => "tab in indent",
+			Self::ConflictMarker => "leftover conflict marker",
+		})
+	}
+}
+
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CheckViolation {
+	///
+	pub path: String,
+	/// 1-based line in the staged file
+	pub line: usize,
+	///
+	pub kind: CheckKind,
+}
+
+/// the whitespace errors `core.whitespace` asks for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+struct WhitespaceRules {
+	blank_at_eol: bool,
+	space_before_tab: bool,
+	indent_with_non_tab: bool,
+	tab_in_indent: bool,
+	cr_at_eol: bool,
+	tab_width: usize,
+}
+
+impl Default for WhitespaceRules {
+	fn default() -> Self {
+		Self {
+			blank_at_eol: true,
+			space_before_tab: true,
+			indent_with_non_tab: false,
+			tab_in_indent: false,
+			cr_at_eol: false,
+			tab_width: 8,
+		}
+	}
+}
+
+impl WhitespaceRules {
+	/// comma separated, a leading `-` turns a rule off
+	fn parse(config: &str) -> Self {
+		let mut rules = Self::default();
+
+		for rule in config.split(',').map(str::trim) {
+			let (enable, rule) = rule
+				.strip_prefix('-')
+				.map_or((true, rule), |rule| (false, rule));
+
+			match rule {
+				"trailing-space" | "blank-at-eol" => {
+					rules.blank_at_eol = enable;
+				}
+				"space-before-tab" => rules.space_before_tab = enable,
+				"indent-with-non-tab" => {
+					rules.indent_with_non_tab = enable;
+				}
+				"tab-in-indent" => rules.tab_in_indent = enable,
+				"cr-at-eol" => rules.cr_at_eol = enable,
+				_ => {
+					if let Some(width) = rule
+						.strip_prefix("tabwidth=")
+						.and_then(|width| width.parse().ok())
+					{
+						rules.tab_width = width;
+					}
+				}
+			}
+		}
+
+		rules
+	}
+
+	fn check(&self, line: &str) -> Option<CheckKind> {
+		let line = line.strip_suffix('\n').unwrap_or(line);
+		let line = if self.cr_at_eol {
+			line.strip_suffix('\r').unwrap_or(line)
+		} else {
+			line
+		};
+
+		let indent = &line[..line.len()
+			- line.trim_start_matches([' ', '\t']).len()];
+
+		if self.blank_at_eol
+			&& line.ends_with(|c: char| c.is_ascii_whitespace())
+		{
+			Some(CheckKind::TrailingWhitespace)
+		} else if self.space_before_tab && indent.contains(" \t") {
+			Some(CheckKind::SpaceBeforeTab)
+		} else if self.indent_with_non_tab
+			&& indent.contains(&" ".repeat(self.tab_width.max(1)))
+		{
+			Some(CheckKind::IndentWithNonTab)
+		} else if self.tab_in_indent && indent.contains('\t') {
+			Some(CheckKind::TabInIndent)
+		} else {
+			None
+		}
+	}
+}
+
+fn is_conflict_marker(line: &str) -> bool {
+	let line = line.trim_end_matches(['\n', '\r']);
+
+	CONFLICT_MARKERS.iter().any(|marker| {
+		line.strip_prefix(marker).is_some_and(|rest| {
+			if *marker == "=======" {
+				rest.is_empty()
+			} else {
+				rest.is_empty() || rest.starts_with(' ')
+			}
+		})
+	})
+}
+
+/// whether `gitui.commitChecks` is on
+pub fn commit_checks_enabled(repo_path: &RepoPath) -> Result<bool> {
+	let repo = repo(repo_path)?;
+	let enabled = repo.config()?.get_bool(CHECKS_CONFIG);
+
+	Ok(enabled.unwrap_or(false))
+}
+
+/// the problems in the lines staged for the next commit
+pub fn check_staged(
+	repo_path: &RepoPath,
+) -> Result<Vec<CheckViolation>> {
+	scope_time!("check_staged");
+
+	let repo = repo(repo_path)?;
+
+	check_staged_repo(&repo)
+}
+
+fn check_staged_repo(
+	repo: &Repository,
+) -> Result<Vec<CheckViolation>> {
+	let rules = WhitespaceRules::parse(
+		&repo
+			.config()?
+			.get_string("core.whitespace")
+			.unwrap_or_default(),
+	);
+
+	let head =
+		repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+	let diff = repo.diff_tree_to_index(
+		head.as_ref(),
+		None,
+		Some(DiffOptions::new().context_lines(0)),
+	)?;
+
+	let mut violations = Vec::new();
+
+	diff.foreach(
+		&mut |_, _| true,
+		None,
+		None,
+		Some(&mut |delta, _, line| {
+			let (Some(path), Some(lineno)) =
+				(delta.new_file().path(), line.new_lineno())
+			else {
+				return true;
+			};
+			if line.origin() != '+' {
+				return true;
+			}
+
+			let content = String::from_utf8_lossy(line.content());
+			let kind = if is_conflict_marker(&content) {
+				Some(CheckKind::ConflictMarker)
+			} else {
+				rules.check(&content)
+			};
+
+			if let Some(kind) = kind {
+				violations.push(CheckViolation {
+					path: path.to_string_lossy().to_string(),
+					line: lineno as usize,
+					kind,
+				});
+			}
+
+			true
+		}),
+	)?;
+
+	Ok(violations)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		stage_add_file,
+		tests::{repo_init, write_commit_file},
+	};
+	use std::{fs, path::Path};
+
+	#[test]
+	fn test_whitespace_rules() {
+		let rules = WhitespaceRules::default();
+		assert_eq!(
+			rules.check("a \n"),
+			Some(CheckKind::TrailingWhitespace)
+		);
+		assert_eq!(
+			rules.check("a\r\n"),
+			Some(CheckKind::TrailingWhitespace)
+		);
+		assert_eq!(
+			rules.check(" \ta\n"),
+			Some(CheckKind::SpaceBeforeTab)
+		);
+		assert_eq!(rules.check("\t        a\n"), None);
+
+		let rules = WhitespaceRules::parse(
+			"-trailing-space,cr-at-eol,indent-with-non-tab,tabwidth=4",
+		);
+		assert_eq!(rules.check("a \n"), None);
+		assert_eq!(
+			rules.check("    a\n"),
+			Some(CheckKind::IndentWithNonTab)
+		);
+		assert_eq!(rules.check("   a\r\n"), None);
+
+		let rules = WhitespaceRules::parse("tab-in-indent");
+		assert_eq!(
+			rules.check("\ta\n"),
+			Some(CheckKind::TabInIndent)
+		);
+	}
+
+	#[test]
+	fn test_conflict_markers() {
+		assert!(is_conflict_marker("<<<<<<< HEAD\n"));
+		assert!(is_conflict_marker("=======\n"));
+		assert!(is_conflict_marker(">>>>>>> feature\n"));
+		assert!(!is_conflict_marker("========\n"));
+		assert!(!is_conflict_marker("<<<<<<<<\n"));
+		assert!(!is_conflict_marker("a <<<<<<< b\n"));
+	}
+
+	#[test]
+	fn test_check_staged() {
+		let (td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		assert!(!commit_checks_enabled(repo_path).unwrap());
+
+		write_commit_file(&repo, "a.txt", "a \nb\n", "a");
+		fs::write(
+			td.path().join("a.txt"),
+			"a \nb\nc \n<<<<<<< HEAD\nd\n",
+		)
+		.unwrap();
+
+		// only staged lines count
+		assert!(check_staged(repo_path).unwrap().is_empty());
+
+		stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+
+		assert_eq!(
+			check_staged(repo_path).unwrap(),
+			vec![
+				CheckViolation {
+					path: String::from("a.txt"),
+					line: 3,
+					kind: CheckKind::TrailingWhitespace,
+				},
+				CheckViolation {
+					path: String::from("a.txt"),
+					line: 4,
+					kind: CheckKind::ConflictMarker,
+				},
+			]
+		);
+	}
+}
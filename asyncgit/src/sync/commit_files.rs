@@ -6,12 +6,27 @@ use crate::{
 	sync::{get_stashes, repository::repo},
 	StatusItem, StatusItemType,
 };
-use git2::{Diff, Repository};
+use git2::{Diff, Patch, Repository};
 use scopetime::scope_time;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+
+/// directories below this share of the changed lines are left out
+/// of the dirstat, like `git diff --dirstat` does
+const DIRSTAT_MIN_PERCENT: usize = 3;
+
+/// share of the changed lines of a diff in a single directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirStat {
+	/// directory with trailing slash, `./` for the root
+	pub dir: String,
+	/// added plus deleted lines
+	pub lines: usize,
+	///
+	pub percent: usize,
+}
 
 /// struct containing a new and an old version
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash, Debug)]
 pub struct OldNew<T> {
 	/// The old version
 	pub old: T,
@@ -40,6 +55,74 @@ pub fn sort_commits(
 	}
 }
 
+/// sorts two commits like `sort_commits`, opening the repo first
+pub fn sort_commit_ids(
+	repo_path: &RepoPath,
+	commits: (CommitId, CommitId),
+) -> Result<OldNew<CommitId>> {
+	sort_commits(&repo(repo_path)?, commits)
+}
+
+/// how the changed lines between two commits spread over the
+/// directories (tree to tree), biggest share first
+pub fn get_compare_dirstat(
+	repo_path: &RepoPath,
+	ids: OldNew<CommitId>,
+) -> Result<Vec<DirStat>> {
+	scope_time!("get_compare_dirstat");
+
+	let repo = repo(repo_path)?;
+	let diff = get_compare_commits_diff(&repo, ids, None, None)?;
+
+	let mut dirs: HashMap<String, usize> = HashMap::new();
+
+	for idx in 0..diff.deltas().len() {
+		let Some(file_patch) = Patch::from_diff(&diff, idx)? else {
+			continue;
+		};
+		let (_, additions, deletions) = file_patch.line_stats()?;
+
+		let delta = file_patch.delta();
+		let path = delta
+			.new_file()
+			.path()
+			.or_else(|| delta.old_file().path())
+			.map(|p| p.to_string_lossy().replace('\\', "/"))
+			.unwrap_or_default();
+		let dir = path.rsplit_once('/').map_or_else(
+			|| String::from("./"),
+			|(dir, _)| format!("{dir}/"),
+		);
+
+		*dirs.entry(dir).or_default() += additions + deletions;
+	}
+
+	Ok(dirstat(dirs))
+}
+
+fn dirstat(dirs: HashMap<String, usize>) -> Vec<DirStat> {
+	let total: usize = dirs.values().sum();
+	if total == 0 {
+		return Vec::new();
+	}
+
+	let mut res: Vec<DirStat> = dirs
+		.into_iter()
+		.map(|(dir, lines)| DirStat {
+			percent: lines * 100 / total,
+			dir,
+			lines,
+		})
+		.filter(|stat| stat.percent >= DIRSTAT_MIN_PERCENT)
+		.collect();
+
+	res.sort_by(|a, b| {
+		b.lines.cmp(&a.lines).then_with(|| a.dir.cmp(&b.dir))
+	});
+
+	res
+}
+
 /// get all files that are part of a commit
 pub fn get_commit_files(
 	repo_path: &RepoPath,
@@ -70,7 +153,7 @@ pub fn get_commit_files(
 	let res = diff
 		.deltas()
 		.map(|delta| {
-			let status = StatusItemType::from(delta.status());
+			let status = StatusItemType::from(&delta);
 
 			StatusItem {
 				path: delta
@@ -181,12 +264,15 @@ pub(crate) fn get_commit_diff<'a>(
 
 #[cfg(test)]
 mod tests {
-	use super::get_commit_files;
+	use super::{
+		get_commit_files, get_compare_dirstat, sort_commit_ids,
+		DirStat,
+	};
 	use crate::{
 		error::Result,
 		sync::{
 			commit, stage_add_file, stash_save,
-			tests::{get_statuses, repo_init},
+			tests::{get_statuses, repo_init, write_commit_file},
 			RepoPath,
 		},
 		StatusItemType,
@@ -266,4 +352,46 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[test]
+	fn test_compare_dirstat() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		std::fs::create_dir_all(root.join("src/ui"))?;
+
+		let c1 = write_commit_file(&repo, "root.txt", "a\n", "c1");
+		write_commit_file(&repo, "src/a.rs", "1\n2\n3\n", "c2");
+		write_commit_file(&repo, "src/ui/b.rs", "1\n", "c3");
+		let c4 = write_commit_file(&repo, "root.txt", "b\n", "c4");
+
+		// marked newest first, still diffed old to new
+		let ids = sort_commit_ids(repo_path, (c4, c1))?;
+		assert_eq!((ids.old, ids.new), (c1, c4));
+
+		assert_eq!(
+			get_compare_dirstat(repo_path, ids)?,
+			vec![
+				DirStat {
+					dir: String::from("src/"),
+					lines: 3,
+					percent: 50,
+				},
+				DirStat {
+					dir: String::from("./"),
+					lines: 2,
+					percent: 33,
+				},
+				DirStat {
+					dir: String::from("src/ui/"),
+					lines: 1,
+					percent: 16,
+				},
+			]
+		);
+
+		Ok(())
+	}
 }
@@ -5,8 +5,9 @@ use super::{
 use crate::error::Result;
 use bitflags::bitflags;
 use fuzzy_matcher::FuzzyMatcher;
-use git2::{Diff, Repository};
-use std::sync::Arc;
+use git2::{Diff, DiffLineType, Repository};
+use regex::{Regex, RegexBuilder};
+use std::{cell::Cell, ops::Range, sync::Arc};
 
 ///
 pub type SharedCommitFilterFn = Arc<
@@ -36,7 +37,7 @@ pub fn diff_contains_file(file_path: String) -> SharedCommitFilterFn {
 
 bitflags! {
 	///
-	#[derive(Debug, Clone, Copy)]
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 	pub struct SearchFields: u32 {
 		///
 		const MESSAGE_SUMMARY = 1 << 0;
@@ -46,12 +47,12 @@ bitflags! {
 		const FILENAMES = 1 << 2;
 		///
 		const AUTHORS = 1 << 3;
+		/// lines added or removed by the commit (pickaxe)
+		const DIFFS = 1 << 4;
 		//TODO:
-		// const COMMIT_HASHES = 1 << 3;
+		// const COMMIT_HASHES = 1 << 5;
 		// ///
-		// const DATES = 1 << 4;
-		// ///
-		// const DIFFS = 1 << 5;
+		// const DATES = 1 << 6;
 	}
 }
 
@@ -63,12 +64,14 @@ impl Default for SearchFields {
 
 bitflags! {
 	///
-	#[derive(Debug, Clone, Copy)]
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 	pub struct SearchOptions: u32 {
 		///
 		const CASE_SENSITIVE = 1 << 0;
 		///
 		const FUZZY_SEARCH = 1 << 1;
+		/// the pattern is a regular expression
+		const REGEX = 1 << 2;
 	}
 }
 
@@ -89,6 +92,32 @@ pub struct LogFilterSearchOptions {
 	pub options: SearchOptions,
 }
 
+impl LogFilterSearchOptions {
+	/// regex for the pattern unless this is a fuzzy search,
+	/// fails if the pattern is no valid regular expression
+	pub fn regex(&self) -> Result<Option<Regex>> {
+		if self.options.contains(SearchOptions::FUZZY_SEARCH) {
+			return Ok(None);
+		}
+
+		let pattern = if self.options.contains(SearchOptions::REGEX) {
+			self.search_pattern.clone()
+		} else {
+			regex::escape(&self.search_pattern)
+		};
+
+		Ok(Some(
+			RegexBuilder::new(&pattern)
+				.case_insensitive(
+					!self
+						.options
+						.contains(SearchOptions::CASE_SENSITIVE),
+				)
+				.build()?,
+		))
+	}
+}
+
 ///
 #[derive(Default)]
 pub struct LogFilterSearch {
@@ -96,11 +125,18 @@ pub struct LogFilterSearch {
 	pub matcher: fuzzy_matcher::skim::SkimMatcherV2,
 	///
 	pub options: LogFilterSearchOptions,
+	/// matches everything but fuzzy searches
+	regex: Option<Regex>,
 }
 
 impl LogFilterSearch {
-	///
+	/// an invalid regular expression matches nothing
 	pub fn new(options: LogFilterSearchOptions) -> Self {
+		let regex = options.regex().unwrap_or_else(|e| {
+			log::error!("invalid search pattern: {e}");
+			Regex::new("$^").ok()
+		});
+
 		let mut options = options;
 		if !options.options.contains(SearchOptions::CASE_SENSITIVE) {
 			options.search_pattern =
@@ -109,9 +145,46 @@ impl LogFilterSearch {
 		Self {
 			matcher: fuzzy_matcher::skim::SkimMatcherV2::default(),
 			options,
+			regex,
 		}
 	}
 
+	/// position of the first match in `text`, not available for fuzzy
+	/// searches
+	pub fn find(&self, text: &str) -> Option<Range<usize>> {
+		self.regex
+			.as_ref()
+			.and_then(|regex| regex.find(text))
+			.filter(|m| !m.is_empty())
+			.map(|m| m.range())
+	}
+
+	/// whether a line added or removed in `diff` matches
+	fn match_diff_content(&self, diff: &Diff<'_>) -> bool {
+		let found = Cell::new(false);
+
+		// stopping early makes `foreach` return an error
+		let _ = diff.foreach(
+			&mut |_, _| true,
+			None,
+			None,
+			Some(&mut |_, _, line| {
+				if matches!(
+					line.origin_value(),
+					DiffLineType::Addition | DiffLineType::Deletion
+				) && std::str::from_utf8(line.content())
+					.is_ok_and(|content| self.match_text(content))
+				{
+					found.set(true);
+				}
+
+				!found.get()
+			}),
+		);
+
+		found.get()
+	}
+
 	fn match_diff(&self, diff: &Diff<'_>) -> bool {
 		diff.deltas().any(|delta| {
 			if delta
@@ -133,24 +206,17 @@ impl LogFilterSearch {
 
 	///
 	pub fn match_text(&self, text: &str) -> bool {
-		if self.options.options.contains(SearchOptions::FUZZY_SEARCH)
-		{
-			self.matcher
-				.fuzzy_match(
-					text,
-					self.options.search_pattern.as_str(),
-				)
-				.is_some()
-		} else if self
-			.options
-			.options
-			.contains(SearchOptions::CASE_SENSITIVE)
-		{
-			text.contains(self.options.search_pattern.as_str())
-		} else {
-			text.to_lowercase()
-				.contains(self.options.search_pattern.as_str())
-		}
+		self.regex.as_ref().map_or_else(
+			|| {
+				self.matcher
+					.fuzzy_match(
+						text,
+						self.options.search_pattern.as_str(),
+					)
+					.is_some()
+			},
+			|regex| regex.is_match(text),
+		)
 	}
 }
 
@@ -185,18 +251,28 @@ pub fn filter_commit_by_search(
 				.flatten()
 				.unwrap_or_default();
 
-			let file_match = filter
-				.options
-				.fields
-				.contains(SearchFields::FILENAMES)
+			let fields = filter.options.fields;
+			let diff = fields
+				.intersects(
+					SearchFields::FILENAMES | SearchFields::DIFFS,
+				)
 				.then(|| {
 					get_commit_diff(
 						repo, *commit_id, None, None, None,
 					)
 					.ok()
 				})
-				.flatten()
-				.is_some_and(|diff| filter.match_diff(&diff));
+				.flatten();
+
+			let file_match = fields.contains(SearchFields::FILENAMES)
+				&& diff
+					.as_ref()
+					.is_some_and(|diff| filter.match_diff(diff));
+
+			let content_match = fields.contains(SearchFields::DIFFS)
+				&& diff.as_ref().is_some_and(|diff| {
+					filter.match_diff_content(diff)
+				});
 
 			let authors_match = if filter
 				.options
@@ -218,6 +294,7 @@ pub fn filter_commit_by_search(
 			Ok(msg_summary_match
 				|| msg_body_match
 				|| file_match
+				|| content_match
 				|| authors_match)
 		},
 	))
@@ -6,6 +6,7 @@ use crate::{
 	sync::{
 		commit_details::get_author_of_commit,
 		repository::{gix_repo, repo},
+		LogWalker,
 	},
 };
 use git2::{Commit, Error, Oid};
@@ -162,6 +163,26 @@ pub fn get_commits_info(
 	Ok(res)
 }
 
+/// the newest `limit` commits reachable from `HEAD`
+pub fn get_log(
+	repo_path: &RepoPath,
+	limit: usize,
+	message_length_limit: usize,
+) -> Result<Vec<CommitInfo>> {
+	scope_time!("get_log");
+
+	let ids = {
+		let repo = repo(repo_path)?;
+		let mut ids = Vec::new();
+		// `LogWalker::read` appends to the vec, unlike `io::Read`
+		#[allow(clippy::read_zero_byte_vec)]
+		LogWalker::new(&repo, limit)?.read(&mut ids)?;
+		ids
+	};
+
+	get_commits_info(repo_path, &ids, message_length_limit)
+}
+
 ///
 pub fn get_commit_info(
 	repo_path: &RepoPath,
@@ -192,6 +213,48 @@ pub fn get_commit_info(
 	})
 }
 
+/// parents of `commit_id`, the first parent first
+pub fn get_commit_parents(
+	repo_path: &RepoPath,
+	commit_id: CommitId,
+) -> Result<Vec<CommitId>> {
+	scope_time!("get_commit_parents");
+
+	let repo = repo(repo_path)?;
+	let commit = repo.find_commit(commit_id.get_oid())?;
+
+	Ok(commit.parent_ids().map(CommitId::new).collect())
+}
+
+/// commits reachable from any branch or `HEAD` that have
+/// `commit_id` as one of their parents
+pub fn get_commit_children(
+	repo_path: &RepoPath,
+	commit_id: CommitId,
+) -> Result<Vec<CommitId>> {
+	scope_time!("get_commit_children");
+
+	let repo = repo(repo_path)?;
+	let mut walk = repo.revwalk()?;
+	walk.push_glob("refs/heads")?;
+	walk.push_glob("refs/remotes")?;
+	if repo.head().is_ok() {
+		walk.push_head()?;
+	}
+	// only descendants (and unrelated commits) remain
+	walk.hide(commit_id.get_oid())?;
+
+	let mut children = Vec::new();
+	for id in walk {
+		let commit = repo.find_commit(id?)?;
+		if commit.parent_ids().any(|p| p == commit_id.get_oid()) {
+			children.push(CommitId::new(commit.id()));
+		}
+	}
+
+	Ok(children)
+}
+
 /// if `message_limit` is set the message will be
 /// limited to the first line and truncated to fit
 pub fn get_message(
@@ -230,12 +293,17 @@ pub fn gix_get_message(
 
 #[cfg(test)]
 mod tests {
-	use super::get_commits_info;
+	use super::{
+		get_commit_children, get_commit_parents, get_commits_info,
+		get_log,
+	};
 	use crate::{
 		error::Result,
 		sync::{
-			commit, stage_add_file, tests::repo_init_empty,
-			utils::get_head_repo, CommitId, RepoPath,
+			commit, stage_add_file,
+			tests::{repo_init, repo_init_empty, write_commit_file},
+			utils::get_head_repo,
+			CommitId, RepoPath,
 		},
 	};
 	use std::{fs::File, io::Write, path::Path};
@@ -271,6 +339,49 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn test_parents_and_children() -> Result<()> {
+		let file_path = Path::new("foo");
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		File::create(root.join(file_path))?.write_all(b"a")?;
+		stage_add_file(repo_path, file_path).unwrap();
+		let c1 = commit(repo_path, "commit1").unwrap();
+		File::create(root.join(file_path))?.write_all(b"b")?;
+		stage_add_file(repo_path, file_path).unwrap();
+		let c2 = commit(repo_path, "commit2").unwrap();
+
+		assert_eq!(get_commit_parents(repo_path, c1)?, vec![]);
+		assert_eq!(get_commit_parents(repo_path, c2)?, vec![c1]);
+		assert_eq!(get_commit_children(repo_path, c1)?, vec![c2]);
+		assert_eq!(get_commit_children(repo_path, c2)?, vec![]);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_get_log() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let c1 = write_commit_file(&repo, "a.txt", "a", "commit1");
+		let c2 = write_commit_file(&repo, "a.txt", "b", "commit2");
+
+		let res = get_log(repo_path, 2, 50).unwrap();
+
+		assert_eq!(
+			res.iter().map(|c| c.id).collect::<Vec<_>>(),
+			vec![c2, c1]
+		);
+		assert_eq!(res[0].message.as_str(), "commit2");
+		assert_eq!(get_log(repo_path, 10, 50).unwrap().len(), 3);
+	}
+
 	#[test]
 	fn test_log_first_msg_line() -> Result<()> {
 		let file_path = Path::new("foo");
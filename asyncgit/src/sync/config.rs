@@ -102,6 +102,42 @@ pub fn push_default_strategy_config_repo(
 	)
 }
 
+// see https://git-scm.com/docs/git-config#Documentation/git-config.txt-pushgpgSign
+/// represents `push.gpgSign` git config
+#[derive(Debug, PartialEq, Default, Eq, Clone, Copy)]
+pub enum PushSignConfig {
+	#[default]
+	No,
+	Yes,
+	/// sign if the server supports push certificates
+	IfAsked,
+}
+
+impl<'a> TryFrom<&'a str> for PushSignConfig {
+	type Error = crate::Error;
+	fn try_from(
+		value: &'a str,
+	) -> std::result::Result<Self, Self::Error> {
+		match value.to_lowercase().as_str() {
+			"false" | "no" | "off" | "0" | "" => Ok(Self::No),
+			"true" | "yes" | "on" | "1" => Ok(Self::Yes),
+			"if-asked" => Ok(Self::IfAsked),
+			_ => Err(crate::Error::GitConfig(format!(
+				"malformed value for push.gpgSign: {value}, must be a boolean or if-asked"
+			))),
+		}
+	}
+}
+
+pub fn push_sign_config_repo(
+	repo: &Repository,
+) -> Result<PushSignConfig> {
+	(get_config_string_repo(repo, "push.gpgSign")?).map_or_else(
+		|| Ok(PushSignConfig::default()),
+		|entry_str| PushSignConfig::try_from(entry_str.as_str()),
+	)
+}
+
 ///
 pub fn untracked_files_config(
 	repo_path: &RepoPath,
@@ -142,6 +178,37 @@ pub fn get_config_string_repo(
 	}
 }
 
+/// all values of a multi-valued key, in the order git reads them
+pub fn get_config_strings(
+	repo_path: &RepoPath,
+	key: &str,
+) -> Result<Vec<String>> {
+	let repo = repo(repo_path)?;
+	get_config_strings_repo(&repo, key)
+}
+
+pub fn get_config_strings_repo(
+	repo: &Repository,
+	key: &str,
+) -> Result<Vec<String>> {
+	scope_time!("get_config_strings_repo");
+
+	let cfg = repo.config()?;
+
+	let Ok(entries) = cfg.multivar(key, None) else {
+		return Ok(Vec::new());
+	};
+
+	let mut values = Vec::new();
+	entries.for_each(|entry| {
+		if let Some(value) = entry.value() {
+			values.push(value.to_string());
+		}
+	})?;
+
+	Ok(values)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -167,4 +234,50 @@ mod tests {
 		assert!(good_cfg.is_ok());
 		assert!(good_cfg.unwrap().is_some());
 	}
+
+	#[test]
+	fn test_get_config_strings() {
+		let (_td, repo) = repo_init().unwrap();
+		let rpath: &RepoPath =
+			&repo.path().as_os_str().to_str().unwrap().into();
+
+		assert!(get_config_strings(rpath, "gitui.test")
+			.unwrap()
+			.is_empty());
+
+		let mut config = repo.config().unwrap();
+		config.set_multivar("gitui.test", "^$", "a").unwrap();
+		config.set_multivar("gitui.test", "^$", "b").unwrap();
+
+		assert_eq!(
+			get_config_strings(rpath, "gitui.test").unwrap(),
+			vec![String::from("a"), String::from("b")]
+		);
+	}
+
+	#[test]
+	fn test_push_sign_config() {
+		let (_td, repo) = repo_init().unwrap();
+
+		assert_eq!(
+			push_sign_config_repo(&repo).unwrap(),
+			PushSignConfig::No
+		);
+
+		let mut config = repo.config().unwrap();
+		for (value, expected) in [
+			("true", PushSignConfig::Yes),
+			("If-Asked", PushSignConfig::IfAsked),
+			("off", PushSignConfig::No),
+		] {
+			config.set_str("push.gpgSign", value).unwrap();
+			assert_eq!(
+				push_sign_config_repo(&repo).unwrap(),
+				expected
+			);
+		}
+
+		config.set_str("push.gpgSign", "sometimes").unwrap();
+		assert!(push_sign_config_repo(&repo).is_err());
+	}
 }
@@ -10,7 +10,8 @@ use super::{
 	RepoPath,
 };
 use crate::error::{Error, Result};
-use git2::CredentialHelper;
+use git2::{Config, ConfigLevel, CredentialHelper, Repository};
+use std::process::{Command, Stdio};
 
 /// basic Authentication Credentials
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
@@ -95,26 +96,11 @@ pub fn extract_username_password(
 		.url()
 		.ok_or(Error::UnknownRemote)?
 		.to_owned();
-	let mut helper = CredentialHelper::new(&url);
 
-	//TODO: look at Cred::credential_helper,
-	//if the username is in the url we need to set it here,
-	//I dont think `config` will pick it up
-
-	if let Ok(config) = repo.config() {
-		helper.config(&config);
-	}
-
-	Ok(match helper.execute() {
-		Some((username, password)) => {
-			BasicAuthCredential::new(Some(username), Some(password))
-		}
-		None => extract_cred_from_url(&url),
-	})
+	Ok(extract_cred_for_url(&repo, &url))
 }
 
 /// extract username and password
-/// TODO: Very similar to `extract_username_password_for_fetch`. Can be refactored.
 pub fn extract_username_password_for_fetch(
 	repo_path: &RepoPath,
 ) -> Result<BasicAuthCredential> {
@@ -124,51 +110,145 @@ pub fn extract_username_password_for_fetch(
 		.url()
 		.ok_or(Error::UnknownRemote)?
 		.to_owned();
-	let mut helper = CredentialHelper::new(&url);
-
-	//TODO: look at Cred::credential_helper,
-	//if the username is in the url we need to set it here,
-	//I dont think `config` will pick it up
-
-	if let Ok(config) = repo.config() {
-		helper.config(&config);
-	}
 
-	Ok(match helper.execute() {
-		Some((username, password)) => {
-			BasicAuthCredential::new(Some(username), Some(password))
-		}
-		None => extract_cred_from_url(&url),
-	})
+	Ok(extract_cred_for_url(&repo, &url))
 }
 
 /// extract username and password
-/// TODO: Very similar to `extract_username_password_for_fetch`. Can be refactored.
 pub fn extract_username_password_for_push(
 	repo_path: &RepoPath,
 ) -> Result<BasicAuthCredential> {
 	let repo = repo(repo_path)?;
-	let url = repo
-		.find_remote(&get_default_remote_for_push_in_repo(&repo)?)?
-		.url()
+	let remote = repo
+		.find_remote(&get_default_remote_for_push_in_repo(&repo)?)?;
+	let url = remote
+		.pushurl()
+		.or_else(|| remote.url())
 		.ok_or(Error::UnknownRemote)?
 		.to_owned();
-	let mut helper = CredentialHelper::new(&url);
 
-	//TODO: look at Cred::credential_helper,
-	//if the username is in the url we need to set it here,
-	//I dont think `config` will pick it up
+	Ok(extract_cred_for_url(&repo, &url))
+}
 
-	if let Ok(config) = repo.config() {
-		helper.config(&config);
+/// asks the credential helpers first, then takes what the url has
+/// and the username remembered for it. whatever is still missing is
+/// asked from the askpass program, like git would
+fn extract_cred_for_url(
+	repo: &Repository,
+	url: &str,
+) -> BasicAuthCredential {
+	let mut helper = CredentialHelper::new(url);
+
+	let config = repo.config().ok();
+	if let Some(config) = &config {
+		// picks up `credential.<url>.username` as well
+		helper.config(config);
 	}
 
-	Ok(match helper.execute() {
-		Some((username, password)) => {
-			BasicAuthCredential::new(Some(username), Some(password))
+	if let Some((username, password)) = helper.execute() {
+		return BasicAuthCredential::new(
+			Some(username),
+			Some(password),
+		);
+	}
+
+	let mut cred = extract_cred_from_url(url);
+	if cred.username.is_none() {
+		cred.username = helper.username;
+	}
+
+	if !cred.is_complete() {
+		if let Some(program) = askpass_program(config.as_ref()) {
+			askpass(&program, url, &mut cred);
 		}
-		None => extract_cred_from_url(&url),
-	})
+	}
+
+	cred
+}
+
+/// `GIT_ASKPASS`, `core.askPass` or `SSH_ASKPASS`, in git's order
+fn askpass_program(config: Option<&Config>) -> Option<String> {
+	std::env::var("GIT_ASKPASS")
+		.ok()
+		.or_else(|| {
+			config.and_then(|c| c.get_string("core.askPass").ok())
+		})
+		.or_else(|| std::env::var("SSH_ASKPASS").ok())
+		.filter(|program| !program.is_empty())
+}
+
+fn askpass(program: &str, url: &str, cred: &mut BasicAuthCredential) {
+	let ask = |prompt: String| {
+		let output = Command::new(program)
+			.arg(prompt)
+			.stdin(Stdio::null())
+			.stderr(Stdio::null())
+			.output()
+			.map_err(|e| log::error!("askpass failed: {e}"))
+			.ok()
+			.filter(|output| output.status.success())?;
+
+		let answer = String::from_utf8_lossy(&output.stdout)
+			.trim_end_matches(['\r', '\n'])
+			.to_string();
+
+		Some(answer).filter(|answer| !answer.is_empty())
+	};
+
+	let display_url = url::Url::parse(url).map_or_else(
+		|_| url.to_string(),
+		|url| {
+			format!(
+				"{}://{}",
+				url.scheme(),
+				url.host_str().unwrap_or_default()
+			)
+		},
+	);
+
+	if cred.username.is_none() {
+		cred.username =
+			ask(format!("Username for '{display_url}': "));
+	}
+
+	if let Some(username) = &cred.username {
+		if cred.password.is_none() {
+			let display_url = display_url.replacen(
+				"://",
+				&format!("://{username}@"),
+				1,
+			);
+			cred.password =
+				ask(format!("Password for '{display_url}': "));
+		}
+	}
+}
+
+/// remembers the username that worked for `url` in the repo config as
+/// `credential.<url>.username`, which git reads as well
+pub(crate) fn remember_username(
+	repo: &Repository,
+	url: &str,
+	username: &str,
+) -> Result<()> {
+	if extract_cred_from_url(url).username.as_deref()
+		== Some(username)
+	{
+		return Ok(());
+	}
+
+	let mut helper = CredentialHelper::new(url);
+	let config = repo.config()?;
+	helper.config(&config);
+
+	if helper.username.as_deref() != Some(username) {
+		config.open_level(ConfigLevel::Local)?.set_str(
+			&format!("credential.{url}.username"),
+			username,
+		)?;
+	}
+
+	Ok(())
 }
 
 /// extract credentials from url
@@ -193,7 +273,8 @@ mod tests {
 	use crate::sync::{
 		cred::{
 			extract_cred_from_url, extract_username_password,
-			need_username_password, BasicAuthCredential,
+			need_username_password, remember_username,
+			BasicAuthCredential,
 		},
 		remotes::DEFAULT_REMOTE_NAME,
 		tests::repo_init,
@@ -364,4 +445,84 @@ mod tests {
 
 		extract_username_password(repo_path).unwrap();
 	}
+
+	#[test]
+	#[serial]
+	fn test_extract_remembered_username() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let url = "http://github.com/user/repo";
+		repo.remote(DEFAULT_REMOTE_NAME, url).unwrap();
+
+		remember_username(&repo, url, "user").unwrap();
+
+		assert_eq!(
+			repo.config()
+				.unwrap()
+				.get_string(&format!("credential.{url}.username"))
+				.unwrap(),
+			"user"
+		);
+		assert_eq!(
+			extract_username_password(repo_path).unwrap().username,
+			Some("user".to_owned())
+		);
+	}
+
+	#[test]
+	#[serial]
+	fn test_dont_remember_username_from_url() {
+		let (_td, repo) = repo_init().unwrap();
+
+		let url = "http://user@github.com/user/repo";
+		remember_username(&repo, url, "user").unwrap();
+
+		assert!(repo
+			.config()
+			.unwrap()
+			.get_string(&format!("credential.{url}.username"))
+			.is_err());
+	}
+
+	#[cfg(unix)]
+	#[test]
+	#[serial]
+	fn test_extract_username_password_from_askpass() {
+		use std::os::unix::fs::PermissionsExt;
+
+		let (td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let askpass = td.path().join("askpass.sh");
+		std::fs::write(
+			&askpass,
+			"#!/bin/sh\ncase \"$1\" in\n  Username*) echo user ;;\n  *) echo secret ;;\nesac\n",
+		)
+		.unwrap();
+		std::fs::set_permissions(
+			&askpass,
+			std::fs::Permissions::from_mode(0o755),
+		)
+		.unwrap();
+
+		repo.config()
+			.unwrap()
+			.set_str("core.askPass", askpass.to_str().unwrap())
+			.unwrap();
+		repo.remote(DEFAULT_REMOTE_NAME, "http://github.com")
+			.unwrap();
+
+		assert_eq!(
+			extract_username_password(repo_path).unwrap(),
+			BasicAuthCredential::new(
+				Some("user".to_owned()),
+				Some("secret".to_owned())
+			)
+		);
+	}
 }
@@ -4,7 +4,8 @@ use super::{
 	commit_files::{
 		get_commit_diff, get_compare_commits_diff, OldNew,
 	},
-	utils::{get_head_repo, work_dir},
+	text_format::{diff_text_formats, TextFormat},
+	utils::{get_head_repo, symlink_target, work_dir},
 	CommitId, RepoPath,
 };
 use crate::{
@@ -15,7 +16,8 @@ use crate::{
 };
 use easy_cast::Conv;
 use git2::{
-	Delta, Diff, DiffDelta, DiffFormat, DiffHunk, Patch, Repository,
+	AttrCheckFlags, Delta, Diff, DiffDelta, DiffFormat, DiffHunk,
+	FileMode, ObjectType, Oid, Patch, Repository,
 };
 use scopetime::scope_time;
 use serde::{Deserialize, Serialize};
@@ -125,6 +127,33 @@ pub struct FileDiff {
 	pub sizes: (u64, u64),
 	/// size delta in bytes
 	pub size_delta: i64,
+	/// old and new file mode (like `0o100644`), set if they differ
+	pub mode_change: Option<(u32, u32)>,
+	/// the file is a symlink on either side, its content is the
+	/// link target
+	pub symlink: bool,
+	/// set if the file is binary
+	pub binary: Option<BinaryFiles>,
+	/// line endings and encoding of a text file, `None` on the side
+	/// it does not exist
+	pub text_format: OldNew<Option<TextFormat>>,
+}
+
+/// old and new version of a binary file
+#[derive(Clone, Debug)]
+pub struct BinaryFiles {
+	/// blob ids, `None` on the side the file does not exist
+	pub ids: OldNew<Option<String>>,
+	/// content if the file is an image, `None` on the side it does
+	/// not exist
+	pub images: Option<OldNew<Option<Vec<u8>>>>,
+}
+
+impl std::hash::Hash for BinaryFiles {
+	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+		// the ids already identify the content
+		self.ids.hash(state);
+	}
 }
 
 /// see <https://libgit2.org/libgit2/#HEAD/type/git_diff_options>
@@ -189,6 +218,8 @@ pub(crate) fn get_diff_raw<'a>(
 	} else {
 		opt.include_untracked(true);
 		opt.recurse_untracked_dirs(true);
+		// only the ignored files shown in the status get here
+		opt.include_ignored(true);
 		repo.diff_index_to_workdir(None, Some(&mut opt))?
 	};
 
@@ -208,7 +239,7 @@ pub fn get_diff(
 	let work_dir = work_dir(&repo)?;
 	let diff = get_diff_raw(&repo, p, stage, false, options)?;
 
-	raw_diff_to_file_diff(&diff, work_dir)
+	raw_diff_to_file_diff(&repo, &diff, work_dir)
 }
 
 /// returns diff of a specific file inside a commit
@@ -231,7 +262,7 @@ pub fn get_diff_commit(
 		Some(&get_stashes(repo_path)?.into_iter().collect()),
 	)?;
 
-	raw_diff_to_file_diff(&diff, work_dir)
+	raw_diff_to_file_diff(&repo, &diff, work_dir)
 }
 
 /// get file changes of a diff between two commits
@@ -248,13 +279,145 @@ pub fn get_diff_commits(
 	let diff =
 		get_compare_commits_diff(&repo, ids, Some(p), options)?;
 
-	raw_diff_to_file_diff(&diff, work_dir)
+	raw_diff_to_file_diff(&repo, &diff, work_dir)
+}
+
+/// blob id and mode of one side of a diff, `None` if the file does
+/// not exist there
+pub(crate) type DiffSide = Option<(Oid, u32)>;
+
+/// identifies the content the diff of `p` from `get_diff` is computed
+/// from (`HEAD` and index when `stage`, index and workdir otherwise)
+/// so an unchanged file can reuse an earlier diff.
+/// `None` if that cannot be told cheaply, like with conflicts
+pub(crate) fn get_diff_snapshot(
+	repo_path: &RepoPath,
+	p: &str,
+	stage: bool,
+) -> Result<Option<[DiffSide; 2]>> {
+	scope_time!("get_diff_snapshot");
+
+	let repo = repo(repo_path)?;
+	let index = repo.index()?;
+	if index.has_conflicts() {
+		return Ok(None);
+	}
+
+	let path = Path::new(p);
+	let indexed = index.get_path(path, 0).map(|e| (e.id, e.mode));
+
+	if stage {
+		let head = match repo.head() {
+			Ok(head) => head.peel_to_tree()?.get_path(path).ok().map(
+				|entry| {
+					(
+						entry.id(),
+						u32::try_from(entry.filemode())
+							.unwrap_or_default(),
+					)
+				},
+			),
+			// unborn
+			Err(_) => None,
+		};
+
+		return Ok(Some([head, indexed]));
+	}
+
+	let file = work_dir(&repo)?.join(path);
+	let Ok(meta) = fs::symlink_metadata(&file) else {
+		return Ok(Some([indexed, None]));
+	};
+
+	let id = if meta.file_type().is_symlink() {
+		Oid::hash_object(
+			ObjectType::Blob,
+			symlink_target(&file)?.as_bytes(),
+		)?
+	} else if meta.is_file() {
+		Oid::hash_file(ObjectType::Blob, &file)?
+	} else {
+		return Ok(None);
+	};
+
+	#[cfg(unix)]
+	let mode =
+		std::os::unix::fs::PermissionsExt::mode(&meta.permissions());
+	#[cfg(not(unix))]
+	let mode = 0;
+
+	Ok(Some([indexed, Some((id, mode))]))
+}
+
+/// attributes changing how a file is diffed
+const DIFF_ATTRIBUTES: [&str; 7] = [
+	"diff",
+	"text",
+	"binary",
+	"eol",
+	"crlf",
+	"filter",
+	"working-tree-encoding",
+];
+
+/// identifies what besides the content goes into the diff of `p`:
+/// the `diff.*` and `core.*` config and the attributes applying to
+/// `p`, so a diff cached before either changed is not reused
+pub(crate) fn get_diff_settings(
+	repo_path: &RepoPath,
+	p: &str,
+) -> Result<u64> {
+	scope_time!("get_diff_settings");
+
+	let repo = repo(repo_path)?;
+	let config = repo.config()?.snapshot()?;
+
+	let mut entries = Vec::new();
+	config.entries(Some(r"^(diff|core)\."))?.for_each(|entry| {
+		entries.push((
+			entry.name_bytes().to_vec(),
+			entry.value_bytes().to_vec(),
+		));
+	})?;
+
+	let path = Path::new(p);
+	let attributes = DIFF_ATTRIBUTES
+		.iter()
+		.map(|name| {
+			repo.get_attr_bytes(
+				path,
+				name,
+				AttrCheckFlags::FILE_THEN_INDEX,
+			)
+			.map(|value| value.map(<[u8]>::to_vec))
+		})
+		.collect::<std::result::Result<Vec<_>, _>>()?;
+
+	Ok(hash(&(entries, attributes)))
+}
+
+/// content of our side of the conflicted file `path`, empty if it
+/// does not exist there
+fn conflict_ours(repo: &Repository, path: &Path) -> Result<Vec<u8>> {
+	let index = repo.index()?;
+	let ours = index.conflicts()?.find_map(|conflict| {
+		conflict.ok()?.our.filter(|our| {
+			std::str::from_utf8(&our.path)
+				.is_ok_and(|our_path| Path::new(our_path) == path)
+		})
+	});
+
+	Ok(match ours {
+		Some(entry) => repo.find_blob(entry.id)?.content().to_vec(),
+		None => Vec::new(),
+	})
 }
 
 ///
 //TODO: refactor into helper type with the inline closures as dedicated functions
 #[allow(clippy::too_many_lines)]
 fn raw_diff_to_file_diff(
+	repo: &Repository,
 	diff: &Diff,
 	work_dir: &Path,
 ) -> Result<FileDiff> {
@@ -315,9 +478,15 @@ fn raw_diff_to_file_diff(
 			}
 		};
 
-		let new_file_diff = if diff.deltas().len() == 1 {
+		// libgit2 prints no patch for these, so it is made from the
+		// content before (nothing or our side of a conflict)
+		let buffer_diff = if diff.deltas().len() == 1 {
 			if let Some(delta) = diff.deltas().next() {
-				if delta.status() == Delta::Untracked {
+				if matches!(
+					delta.status(),
+					Delta::Untracked
+						| Delta::Ignored | Delta::Conflicted
+				) {
 					let relative_path =
 						delta.new_file().path().ok_or_else(|| {
 							Error::Generic(
@@ -331,8 +500,15 @@ fn raw_diff_to_file_diff(
 					if let Some(newfile_content) =
 						new_file_content(&newfile_path)
 					{
+						let old_content =
+							if delta.status() == Delta::Conflicted {
+								conflict_ours(repo, relative_path)?
+							} else {
+								Vec::new()
+							};
+
 						let mut patch = Patch::from_buffers(
-							&[],
+							&old_content,
 							None,
 							newfile_content.as_slice(),
 							Some(&newfile_path),
@@ -362,7 +538,7 @@ fn raw_diff_to_file_diff(
 			false
 		};
 
-		if !new_file_diff {
+		if !buffer_diff {
 			diff.print(
 				DiffFormat::Patch,
 				move |delta, hunk, line: git2::DiffLine| {
@@ -382,13 +558,115 @@ fn raw_diff_to_file_diff(
 			);
 		}
 
-		if new_file_diff {
+		if buffer_diff
+			&& diff.deltas().next().is_some_and(|delta| {
+				delta.status() != Delta::Conflicted
+			}) {
 			res.borrow_mut().untracked = true;
 		}
 	}
-	let res = Rc::try_unwrap(res)
-		.map_err(|_| Error::Generic("rc unwrap error".to_owned()))?;
-	Ok(res.into_inner())
+	let mut res = Rc::try_unwrap(res)
+		.map_err(|_| Error::Generic("rc unwrap error".to_owned()))?
+		.into_inner();
+
+	if let Some(delta) = diff.deltas().next() {
+		let (old, new) =
+			(delta.old_file().mode(), delta.new_file().mode());
+
+		res.symlink = old == FileMode::Link || new == FileMode::Link;
+		if old != new
+			&& old != FileMode::Unreadable
+			&& new != FileMode::Unreadable
+		{
+			res.mode_change = Some((old.into(), new.into()));
+		}
+
+		// a collapsed untracked or ignored folder is no binary file
+		let is_folder = delta.new_file().mode() == FileMode::Tree;
+
+		if res.hunks.is_empty() && !res.symlink && !is_folder {
+			res.binary = Some(binary_files(repo, &delta, work_dir));
+		} else if !res.symlink && !is_folder {
+			res.text_format =
+				diff_text_formats(repo, &delta, work_dir);
+		}
+	}
+
+	Ok(res)
+}
+
+/// image types the ui can preview
+const IMAGE_EXTENSIONS: &[&str] =
+	&["png", "jpg", "jpeg", "gif", "bmp", "webp"];
+
+/// images larger than this are not loaded for a preview
+const MAX_IMAGE_SIZE: u64 = 16 * 1024 * 1024;
+
+fn binary_files(
+	repo: &Repository,
+	delta: &DiffDelta,
+	work_dir: &Path,
+) -> BinaryFiles {
+	let (old, new) = (delta.old_file(), delta.new_file());
+	let old_exists = !matches!(
+		delta.status(),
+		Delta::Added | Delta::Untracked | Delta::Ignored
+	);
+	let new_exists = delta.status() != Delta::Deleted;
+	let new_path = new
+		.path()
+		.filter(|_| new_exists)
+		.map(|path| work_dir.join(path));
+
+	// workdir files are not hashed by the diff
+	let new_id = if new.id().is_zero() || !new_exists {
+		new_path.as_ref().filter(|path| path.is_file()).and_then(
+			|path| Oid::hash_file(ObjectType::Blob, path).ok(),
+		)
+	} else {
+		Some(new.id())
+	};
+	let old_id = old_exists.then(|| old.id());
+
+	let is_image =
+		new.path().or_else(|| old.path()).is_some_and(|path| {
+			path.extension().and_then(|ext| ext.to_str()).is_some_and(
+				|ext| {
+					IMAGE_EXTENSIONS
+						.contains(&ext.to_lowercase().as_str())
+				},
+			)
+		});
+
+	let content = |id: Option<Oid>, size: u64| {
+		if size > MAX_IMAGE_SIZE {
+			return None;
+		}
+		let id = id?;
+		repo.find_blob(id).map(|blob| blob.content().to_vec()).ok()
+	};
+
+	let images = is_image.then(|| OldNew {
+		old: content(old_id, old.size()),
+		new: content(new_id, new.size()).or_else(|| {
+			new_path
+				.as_ref()
+				.filter(|path| {
+					fs::metadata(path).is_ok_and(|meta| {
+						meta.len() <= MAX_IMAGE_SIZE
+					})
+				})
+				.and_then(|path| fs::read(path).ok())
+		}),
+	});
+
+	BinaryFiles {
+		ids: OldNew {
+			old: old_id.map(|id| id.to_string()),
+			new: new_id.map(|id| id.to_string()),
+		},
+		images,
+	}
 }
 
 const fn is_newline(c: char) -> bool {
@@ -398,11 +676,7 @@ const fn is_newline(c: char) -> bool {
 fn new_file_content(path: &Path) -> Option<Vec<u8>> {
 	if let Ok(meta) = fs::symlink_metadata(path) {
 		if meta.file_type().is_symlink() {
-			if let Ok(path) = fs::read_link(path) {
-				return Some(
-					path.to_str()?.to_string().as_bytes().into(),
-				);
-			}
+			return symlink_target(path).ok().map(String::into_bytes);
 		} else if !meta.file_type().is_dir() {
 			if let Ok(content) = fs::read(path) {
 				return Some(content);
@@ -415,22 +689,134 @@ fn new_file_content(path: &Path) -> Option<Vec<u8>> {
 
 #[cfg(test)]
 mod tests {
-	use super::{get_diff, get_diff_commit};
+	use super::{
+		get_diff, get_diff_commit, get_diff_settings,
+		get_diff_snapshot, OldNew,
+	};
 	use crate::{
 		error::Result,
 		sync::{
-			commit, stage_add_file,
+			checkout_branch, commit, create_branch, is_workdir_clean,
+			rebase_branch, stage_add_file,
 			status::{get_status, StatusType},
-			tests::{get_statuses, repo_init, repo_init_empty},
+			tests::{
+				get_statuses, repo_init, repo_init_empty,
+				write_commit_file,
+			},
 			RepoPath,
 		},
 	};
+	use git2::BranchType;
 	use std::{
 		fs::{self, File},
 		io::Write,
 		path::Path,
 	};
 
+	#[test]
+	fn test_conflict_diff() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "test.txt", "test1\n", "commit1");
+		create_branch(repo_path, "foo").unwrap();
+		write_commit_file(&repo, "test.txt", "test2\n", "commit2");
+		checkout_branch(repo_path, "master").unwrap();
+		write_commit_file(&repo, "test.txt", "test3\n", "commit3");
+		checkout_branch(repo_path, "foo").unwrap();
+		rebase_branch(repo_path, "master", BranchType::Local)
+			.unwrap();
+
+		let diff =
+			get_diff(repo_path, "test.txt", false, None).unwrap();
+
+		assert!(!diff.untracked);
+		assert_eq!(diff.hunks.len(), 1);
+		assert!(diff.hunks[0]
+			.lines
+			.iter()
+			.any(|line| line.content.starts_with("<<<<<<<")));
+	}
+
+	#[test]
+	fn test_diff_snapshot() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		fs::write(root.join("foo.txt"), "a\n").unwrap();
+		let untracked =
+			get_diff_snapshot(repo_path, "foo.txt", false).unwrap();
+		assert_eq!(
+			get_diff_snapshot(repo_path, "foo.txt", false).unwrap(),
+			untracked
+		);
+
+		stage_add_file(repo_path, Path::new("foo.txt")).unwrap();
+		let workdir =
+			get_diff_snapshot(repo_path, "foo.txt", false).unwrap();
+		assert_ne!(workdir, untracked);
+		let staged =
+			get_diff_snapshot(repo_path, "foo.txt", true).unwrap();
+
+		fs::write(root.join("foo.txt"), "b\n").unwrap();
+		assert_ne!(
+			get_diff_snapshot(repo_path, "foo.txt", false).unwrap(),
+			workdir
+		);
+		// the index did not change
+		assert_eq!(
+			get_diff_snapshot(repo_path, "foo.txt", true).unwrap(),
+			staged
+		);
+
+		fs::remove_file(root.join("foo.txt")).unwrap();
+		assert_eq!(
+			get_diff_snapshot(repo_path, "foo.txt", false)
+				.unwrap()
+				.map(|[_, new]| new),
+			Some(None)
+		);
+	}
+
+	#[test]
+	fn test_diff_settings() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let settings =
+			get_diff_settings(repo_path, "foo.txt").unwrap();
+		assert_eq!(
+			get_diff_settings(repo_path, "foo.txt").unwrap(),
+			settings
+		);
+
+		fs::write(root.join(".gitattributes"), "*.txt -diff\n")
+			.unwrap();
+		let attributes =
+			get_diff_settings(repo_path, "foo.txt").unwrap();
+		assert_ne!(attributes, settings);
+		// not affected by the attribute
+		assert_eq!(
+			get_diff_settings(repo_path, "foo.rs").unwrap(),
+			settings
+		);
+
+		repo.config()
+			.unwrap()
+			.set_str("diff.algorithm", "histogram")
+			.unwrap();
+		assert_ne!(
+			get_diff_settings(repo_path, "foo.txt").unwrap(),
+			attributes
+		);
+	}
+
 	#[test]
 	fn test_untracked_subfolder() {
 		let (_td, repo) = repo_init().unwrap();
@@ -636,6 +1022,51 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn test_binary_image_diff() -> Result<()> {
+		let file_path = Path::new("a.png");
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		File::create(root.join(file_path))?.write_all(b"\x00old")?;
+		stage_add_file(repo_path, file_path).unwrap();
+		commit(repo_path, "").unwrap();
+
+		File::create(root.join(file_path))?.write_all(b"\x00new!")?;
+
+		let diff = get_diff(
+			repo_path,
+			file_path.to_str().unwrap(),
+			false,
+			None,
+		)
+		.unwrap();
+
+		let binary = diff.binary.unwrap();
+		assert_eq!(
+			binary.ids.new,
+			Some(
+				git2::Oid::hash_object(
+					git2::ObjectType::Blob,
+					b"\x00new!"
+				)?
+				.to_string()
+			)
+		);
+		assert_ne!(binary.ids.old, binary.ids.new);
+		assert_eq!(
+			binary.images,
+			Some(OldNew {
+				old: Some(b"\x00old".to_vec()),
+				new: Some(b"\x00new!".to_vec())
+			})
+		);
+
+		Ok(())
+	}
+
 	#[test]
 	fn test_diff_delta_size_commit() -> Result<()> {
 		let file_path = Path::new("bar");
@@ -664,6 +1095,124 @@ mod tests {
 		assert_eq!(diff.sizes, (1, 2));
 		assert_eq!(diff.size_delta, 1);
 
+		Ok(())
+	}
+	#[cfg(unix)]
+	#[test]
+	fn test_mode_change() -> Result<()> {
+		use std::os::unix::fs::PermissionsExt;
+
+		let file_path = Path::new("run.sh");
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		File::create(root.join(file_path))?.write_all(b"echo\n")?;
+		stage_add_file(repo_path, file_path).unwrap();
+		commit(repo_path, "").unwrap();
+
+		fs::set_permissions(
+			root.join(file_path),
+			fs::Permissions::from_mode(0o755),
+		)?;
+
+		let diff = get_diff(
+			repo_path,
+			file_path.to_str().unwrap(),
+			false,
+			None,
+		)
+		.unwrap();
+
+		assert!(diff.hunks.is_empty());
+		assert_eq!(diff.mode_change, Some((0o100_644, 0o100_755)));
+		assert!(!diff.symlink);
+
+		Ok(())
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn test_symlink_target_change() -> Result<()> {
+		let file_path = Path::new("link");
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		std::os::unix::fs::symlink("a", root.join(file_path))?;
+		stage_add_file(repo_path, file_path).unwrap();
+		commit(repo_path, "").unwrap();
+
+		fs::remove_file(root.join(file_path))?;
+		std::os::unix::fs::symlink("b", root.join(file_path))?;
+
+		let diff = get_diff(
+			repo_path,
+			file_path.to_str().unwrap(),
+			false,
+			None,
+		)
+		.unwrap();
+
+		assert!(diff.symlink);
+		assert_eq!(diff.mode_change, None);
+		assert_eq!(diff.hunks.len(), 1);
+
+		Ok(())
+	}
+
+	#[cfg(unix)]
+	#[test]
+	/// what windows checks out with `core.symlinks` off
+	fn test_symlink_pointer_file() -> Result<()> {
+		let file_path = Path::new("link");
+		let (_td, repo) = repo_init_empty().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		std::os::unix::fs::symlink("a", root.join(file_path))?;
+		stage_add_file(repo_path, file_path).unwrap();
+		commit(repo_path, "").unwrap();
+
+		repo.config()?.set_bool("core.symlinks", false)?;
+		fs::remove_file(root.join(file_path))?;
+		fs::write(root.join(file_path), "a")?;
+
+		// a pointer file with the target is no change
+		assert!(is_workdir_clean(repo_path, None)?);
+
+		fs::write(root.join(file_path), "b")?;
+
+		let diff = get_diff(
+			repo_path,
+			file_path.to_str().unwrap(),
+			false,
+			None,
+		)
+		.unwrap();
+		assert!(diff.symlink);
+		assert_eq!(diff.mode_change, None);
+
+		// staging keeps it a symlink
+		stage_add_file(repo_path, file_path).unwrap();
+		assert_eq!(
+			repo.index()?.get_path(file_path, 0).unwrap().mode,
+			0o120_000
+		);
+		let diff = get_diff(
+			repo_path,
+			file_path.to_str().unwrap(),
+			true,
+			None,
+		)
+		.unwrap();
+		assert!(diff.symlink);
+		assert_eq!(diff.mode_change, None);
+		assert_eq!(diff.hunks.len(), 1);
+
 		Ok(())
 	}
 }
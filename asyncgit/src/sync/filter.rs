@@ -0,0 +1,261 @@
+//! converts between the content git stores and the one of the files
+//! in the workdir like the git cli does, following `.gitattributes`:
+//! eol, `ident`, `working-tree-encoding` and the clean/smudge
+//! commands of `filter` drivers (libgit2 knows the builtin ones only)
+
+use super::{repository::gix_repo, RepoPath};
+use crate::error::{Error, Result};
+use git2::{ObjectType, Oid, Repository, Tree, TreeWalkResult};
+use gix::filter::plumbing::{
+	driver::apply::Delay, pipeline::convert::ToGitOutcome,
+};
+use scopetime::scope_time;
+use std::{fs, io::Read, path::Path};
+
+/// the content of the file `path` in the workdir for `content` in git
+pub fn to_workdir(
+	repo_path: &RepoPath,
+	path: &str,
+	content: &[u8],
+) -> Result<Vec<u8>> {
+	let repo = gix_repo(repo_path)?;
+	let (mut pipeline, _) = repo.filter_pipeline(None)?;
+
+	let mut converted = Vec::new();
+	pipeline
+		.convert_to_worktree(content, path.into(), Delay::Forbid)?
+		.read_to_end(&mut converted)?;
+
+	Ok(converted)
+}
+
+/// the content git stores for `content` of the file `path` in the
+/// workdir
+pub fn to_git(
+	repo_path: &RepoPath,
+	path: &str,
+	content: &[u8],
+) -> Result<Vec<u8>> {
+	let repo = gix_repo(repo_path)?;
+	let (mut pipeline, index) = repo.filter_pipeline(None)?;
+
+	let mut converted = Vec::new();
+	match pipeline.convert_to_git(content, Path::new(path), &index)? {
+		ToGitOutcome::Unchanged(mut data) => {
+			data.read_to_end(&mut converted)?;
+		}
+		ToGitOutcome::Process(mut data) => {
+			data.read_to_end(&mut converted)?;
+		}
+		ToGitOutcome::Buffer(data) => {
+			converted.extend_from_slice(data);
+		}
+	}
+
+	Ok(converted)
+}
+
+/// rewrites the checked out files of `blobs` (path, id) whose workdir
+/// content differs from what the filter pipeline makes of the blob.
+///
+/// meant to follow a libgit2 checkout, which skips `filter` drivers
+pub fn refilter_workdir(
+	repo_path: &RepoPath,
+	repo: &Repository,
+	blobs: &[(String, Oid)],
+) -> Result<()> {
+	scope_time!("refilter_workdir");
+
+	if blobs.is_empty() {
+		return Ok(());
+	}
+
+	let work_dir = repo
+		.workdir()
+		.ok_or_else(|| Error::Generic(String::from("no workdir")))?;
+	let gix_repo = gix_repo(repo_path)?;
+	let (mut pipeline, _) = gix_repo.filter_pipeline(None)?;
+
+	for (path, id) in blobs {
+		let blob = repo.find_blob(*id)?;
+
+		let mut converted = Vec::new();
+		pipeline
+			.convert_to_worktree(
+				blob.content(),
+				path.as_str().into(),
+				Delay::Forbid,
+			)?
+			.read_to_end(&mut converted)?;
+
+		let file = work_dir.join(path);
+		if fs::read(&file).is_ok_and(|current| current != converted) {
+			fs::write(&file, converted)?;
+		}
+	}
+
+	Ok(())
+}
+
+/// the regular files (path, id) at `path` in `tree`, the file itself
+/// or the ones in the folder
+pub fn tree_blobs(
+	repo: &Repository,
+	tree: &Tree,
+	path: &str,
+) -> Result<Vec<(String, Oid)>> {
+	let path = path.trim_end_matches('/');
+	let Ok(entry) = tree.get_path(Path::new(path)) else {
+		return Ok(Vec::new());
+	};
+
+	let mut blobs = Vec::new();
+	match entry.kind() {
+		Some(ObjectType::Blob) if is_regular(entry.filemode()) => {
+			blobs.push((path.to_string(), entry.id()));
+		}
+		Some(ObjectType::Tree) => {
+			let folder = entry.to_object(repo)?.peel_to_tree()?;
+
+			folder.walk(
+				git2::TreeWalkMode::PreOrder,
+				|root, entry| {
+					if entry.kind() == Some(ObjectType::Blob)
+						&& is_regular(entry.filemode())
+					{
+						if let Some(name) = entry.name() {
+							blobs.push((
+								format!("{path}/{root}{name}"),
+								entry.id(),
+							));
+						}
+					}
+					TreeWalkResult::Ok
+				},
+			)?;
+		}
+		_ => (),
+	}
+
+	Ok(blobs)
+}
+
+/// the regular files (path, id) at `path` in the index, the file
+/// itself or the ones in the folder
+pub fn index_blobs(
+	repo: &Repository,
+	path: &str,
+) -> Result<Vec<(String, Oid)>> {
+	let path = path.trim_end_matches('/');
+	let folder = format!("{path}/");
+
+	Ok(repo
+		.index()?
+		.iter()
+		.filter(|entry| {
+			entry.flags & INDEX_STAGE_MASK == 0
+				&& is_regular(
+					i32::try_from(entry.mode).unwrap_or_default(),
+				)
+		})
+		.filter_map(|entry| {
+			let entry_path = String::from_utf8(entry.path).ok()?;
+			(entry_path == path || entry_path.starts_with(&folder))
+				.then_some((entry_path, entry.id))
+		})
+		.collect())
+}
+
+/// the conflict stage bits of the index entry flags
+const INDEX_STAGE_MASK: u16 = 0x3000;
+
+/// not a symlink or submodule
+const fn is_regular(filemode: i32) -> bool {
+	filemode == 0o100_644 || filemode == 0o100_755
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		checkout_file_at_commit, reset_workdir,
+		tests::{repo_init, write_commit_file},
+		utils::repo_write_file,
+	};
+
+	/// upper cases `*.txt` files in the workdir
+	fn setup_upper_filter(repo: &Repository) {
+		let mut config = repo.config().unwrap();
+		config.set_str("filter.upper.smudge", "tr a-z A-Z").unwrap();
+		config.set_str("filter.upper.clean", "tr A-Z a-z").unwrap();
+		write_commit_file(
+			repo,
+			".gitattributes",
+			"*.txt filter=upper\n",
+			"attributes",
+		);
+	}
+
+	#[test]
+	fn test_convert() {
+		let (td, repo) = repo_init().unwrap();
+		let path: &RepoPath = &td.path().to_str().unwrap().into();
+		setup_upper_filter(&repo);
+
+		assert_eq!(
+			to_workdir(path, "a.txt", b"abc\n").unwrap(),
+			b"ABC\n"
+		);
+		assert_eq!(
+			to_git(path, "a.txt", b"ABC\n").unwrap(),
+			b"abc\n"
+		);
+		assert_eq!(
+			to_workdir(path, "a.md", b"abc\n").unwrap(),
+			b"abc\n"
+		);
+	}
+
+	#[test]
+	fn test_checkout_file_smudges() {
+		let (td, repo) = repo_init().unwrap();
+		let path: &RepoPath = &td.path().to_str().unwrap().into();
+		let root = td.path();
+
+		// written before the filter, so the blob is lower case
+		let commit = write_commit_file(&repo, "a.txt", "abc\n", "c1");
+		setup_upper_filter(&repo);
+
+		repo_write_file(&repo, "a.txt", "changed\n").unwrap();
+		checkout_file_at_commit(path, commit, "a.txt").unwrap();
+		assert_eq!(fs::read(root.join("a.txt")).unwrap(), b"ABC\n");
+
+		repo_write_file(&repo, "a.txt", "changed\n").unwrap();
+		reset_workdir(path, "a.txt").unwrap();
+		assert_eq!(fs::read(root.join("a.txt")).unwrap(), b"ABC\n");
+	}
+
+	#[test]
+	fn test_checkout_folder_eol() {
+		let (td, repo) = repo_init().unwrap();
+		let path: &RepoPath = &td.path().to_str().unwrap().into();
+		let root = td.path();
+
+		write_commit_file(
+			&repo,
+			".gitattributes",
+			"*.txt text eol=crlf\n",
+			"attributes",
+		);
+		fs::create_dir(root.join("dir")).unwrap();
+		let commit =
+			write_commit_file(&repo, "dir/a.txt", "a\nb\n", "c1");
+
+		repo_write_file(&repo, "dir/a.txt", "changed\n").unwrap();
+		checkout_file_at_commit(path, commit, "dir").unwrap();
+		assert_eq!(
+			fs::read(root.join("dir/a.txt")).unwrap(),
+			b"a\r\nb\r\n"
+		);
+	}
+}
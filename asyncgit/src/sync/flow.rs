@@ -0,0 +1,380 @@
+//! git-flow style branching: feature, release and hotfix branches
+//! started from and merged back into the long lived branches named
+//! in the `gitflow.*` config the `git flow` tools write
+
+use super::{
+	checkout_branch, config::get_config_string_repo, delete_branch,
+	merge_branch, merge_commit, repository::repo, tag_commit,
+	utils::get_head_repo, validate_branch_name, CommitId, RepoPath,
+};
+use crate::error::{Error, Result};
+use git2::{BranchType, Repository, StatusOptions};
+use scopetime::scope_time;
+
+/// type of a short lived branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowKind {
+	/// started from and merged into develop
+	Feature,
+	/// started from develop, merged into main (tagged) and develop
+	Release,
+	/// started from main, merged into main (tagged) and develop
+	Hotfix,
+}
+
+impl FlowKind {
+	///
+	pub const ALL: [Self; 3] =
+		[Self::Feature, Self::Release, Self::Hotfix];
+
+	///
+	pub const fn label(self) -> &'static str {
+		match self {
+			Self::Feature => "feature",
+			Self::Release => "release",
+			Self::Hotfix => "hotfix",
+		}
+	}
+
+	const fn tagged(self) -> bool {
+		!matches!(self, Self::Feature)
+	}
+}
+
+/// branch names and prefixes in use
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlowConfig {
+	/// `gitflow.branch.master`, else `main` if it exists or `master`
+	pub main: String,
+	/// `gitflow.branch.develop`, defaults to `develop`
+	pub develop: String,
+	/// `gitflow.prefix.feature`, defaults to `feature/`
+	pub feature: String,
+	/// `gitflow.prefix.release`, defaults to `release/`
+	pub release: String,
+	/// `gitflow.prefix.hotfix`, defaults to `hotfix/`
+	pub hotfix: String,
+	/// `gitflow.prefix.versiontag`, empty by default
+	pub version_tag: String,
+}
+
+impl FlowConfig {
+	///
+	pub fn prefix(&self, kind: FlowKind) -> &str {
+		match kind {
+			FlowKind::Feature => &self.feature,
+			FlowKind::Release => &self.release,
+			FlowKind::Hotfix => &self.hotfix,
+		}
+	}
+
+	/// branch a new `kind` branch starts from
+	pub fn base(&self, kind: FlowKind) -> &str {
+		match kind {
+			FlowKind::Feature | FlowKind::Release => &self.develop,
+			FlowKind::Hotfix => &self.main,
+		}
+	}
+
+	/// kind and name (without prefix) of a flow branch
+	pub fn parse_branch<'a>(
+		&self,
+		branch: &'a str,
+	) -> Option<(FlowKind, &'a str)> {
+		FlowKind::ALL.into_iter().find_map(|kind| {
+			branch
+				.strip_prefix(self.prefix(kind))
+				.filter(|name| !name.is_empty())
+				.map(|name| (kind, name))
+		})
+	}
+
+	/// branches a `kind` branch is merged into when finished, in
+	/// that order
+	pub fn targets(&self, kind: FlowKind) -> Vec<&str> {
+		if kind.tagged() {
+			vec![&self.main, &self.develop]
+		} else {
+			vec![&self.develop]
+		}
+	}
+}
+
+///
+pub fn flow_config(repo_path: &RepoPath) -> Result<FlowConfig> {
+	let repo = repo(repo_path)?;
+
+	let get = |key: &str, default: &str| -> Result<String> {
+		Ok(get_config_string_repo(&repo, &format!("gitflow.{key}"))?
+			.unwrap_or_else(|| default.to_string()))
+	};
+
+	let main =
+		get_config_string_repo(&repo, "gitflow.branch.master")?
+			.unwrap_or_else(|| {
+				if repo.find_branch("main", BranchType::Local).is_ok()
+				{
+					String::from("main")
+				} else {
+					String::from("master")
+				}
+			});
+
+	Ok(FlowConfig {
+		main,
+		develop: get("branch.develop", "develop")?,
+		feature: get("prefix.feature", "feature/")?,
+		release: get("prefix.release", "release/")?,
+		hotfix: get("prefix.hotfix", "hotfix/")?,
+		version_tag: get("prefix.versiontag", "")?,
+	})
+}
+
+/// creates the `kind` branch `name` from its base branch and checks
+/// it out, returns the full branch name
+pub fn flow_start(
+	repo_path: &RepoPath,
+	kind: FlowKind,
+	name: &str,
+) -> Result<String> {
+	scope_time!("flow_start");
+
+	let config = flow_config(repo_path)?;
+	let branch = format!("{}{name}", config.prefix(kind));
+
+	if name.is_empty() || !validate_branch_name(&branch)? {
+		return Err(Error::Generic(format!(
+			"invalid branch name: {branch}"
+		)));
+	}
+
+	let repo = repo(repo_path)?;
+	let base = repo
+		.find_branch(config.base(kind), BranchType::Local)
+		.map_err(|_| {
+			Error::Generic(format!(
+				"base branch '{}' not found",
+				config.base(kind)
+			))
+		})?;
+	repo.branch(&branch, &base.get().peel_to_commit()?, false)?;
+
+	checkout_branch(repo_path, &branch)?;
+
+	Ok(branch)
+}
+
+/// merges the flow branch `branch` into its targets, tags releases
+/// and hotfixes and deletes it. stops at the first merge with
+/// conflicts, which can be finished again once they are resolved and
+/// committed
+pub fn flow_finish(repo_path: &RepoPath, branch: &str) -> Result<()> {
+	scope_time!("flow_finish");
+
+	let config = flow_config(repo_path)?;
+	let (kind, name) =
+		config.parse_branch(branch).ok_or_else(|| {
+			Error::Generic(format!("{branch} is no flow branch"))
+		})?;
+
+	let repo = repo(repo_path)?;
+	ensure_clean(&repo)?;
+
+	let head: CommitId = repo
+		.find_branch(branch, BranchType::Local)?
+		.get()
+		.peel_to_commit()?
+		.id()
+		.into();
+
+	for target in config.targets(kind) {
+		merge_into(repo_path, &repo, branch, head, target)?;
+
+		if kind.tagged() && target == config.main {
+			let tag = format!("{}{name}", config.version_tag);
+			if repo
+				.find_reference(&format!("refs/tags/{tag}"))
+				.is_err()
+			{
+				tag_commit(
+					repo_path,
+					&get_head_repo(&repo)?,
+					&tag,
+					Some(&tag),
+				)?;
+			}
+		}
+	}
+
+	delete_branch(repo_path, &format!("refs/heads/{branch}"))?;
+
+	Ok(())
+}
+
+fn ensure_clean(repo: &Repository) -> Result<()> {
+	let statuses = repo.statuses(Some(
+		StatusOptions::new()
+			.include_untracked(false)
+			.include_ignored(false),
+	))?;
+
+	if statuses.is_empty() {
+		Ok(())
+	} else {
+		Err(Error::UncommittedChanges)
+	}
+}
+
+/// checks out `target` and creates a merge commit of `branch`
+/// (pointing to `head`) unless it is merged already
+fn merge_into(
+	repo_path: &RepoPath,
+	repo: &Repository,
+	branch: &str,
+	head: CommitId,
+	target: &str,
+) -> Result<()> {
+	checkout_branch(repo_path, target)?;
+
+	let target_head = get_head_repo(repo)?;
+	if target_head == head
+		|| repo
+			.graph_descendant_of(target_head.into(), head.into())?
+	{
+		return Ok(());
+	}
+
+	merge_branch(repo_path, branch, BranchType::Local)?;
+
+	if repo.index()?.has_conflicts() {
+		return Err(Error::Generic(format!(
+			"conflicts merging {branch} into {target}, resolve and commit them, then finish again"
+		)));
+	}
+
+	merge_commit(
+		repo_path,
+		&format!("Merge branch '{branch}' into {target}"),
+		&[head],
+	)?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		branch::get_branch_name,
+		create_branch,
+		tests::{repo_init, write_commit_file},
+	};
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_parse_branch() {
+		let config = FlowConfig {
+			main: "main".into(),
+			develop: "develop".into(),
+			feature: "feature/".into(),
+			release: "release/".into(),
+			hotfix: "hotfix/".into(),
+			version_tag: "v".into(),
+		};
+
+		assert_eq!(
+			config.parse_branch("feature/login"),
+			Some((FlowKind::Feature, "login"))
+		);
+		assert_eq!(
+			config.parse_branch("hotfix/1.0.1"),
+			Some((FlowKind::Hotfix, "1.0.1"))
+		);
+		assert_eq!(config.parse_branch("feature/"), None);
+		assert_eq!(config.parse_branch("develop"), None);
+	}
+
+	#[test]
+	fn test_feature() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		create_branch(repo_path, "develop").unwrap();
+
+		assert_eq!(
+			flow_start(repo_path, FlowKind::Feature, "login")
+				.unwrap(),
+			"feature/login"
+		);
+		let feature =
+			write_commit_file(&repo, "login.txt", "login", "login");
+
+		flow_finish(repo_path, "feature/login").unwrap();
+
+		assert_eq!(get_branch_name(repo_path).unwrap(), "develop");
+		assert!(repo
+			.find_branch("feature/login", BranchType::Local)
+			.is_err());
+
+		let merge = repo.head().unwrap().peel_to_commit().unwrap();
+		assert_eq!(merge.parent_count(), 2);
+		assert_eq!(
+			CommitId::from(merge.parent_id(1).unwrap()),
+			feature
+		);
+	}
+
+	#[test]
+	fn test_release() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		repo.config()
+			.unwrap()
+			.set_str("gitflow.prefix.versiontag", "v")
+			.unwrap();
+		create_branch(repo_path, "develop").unwrap();
+
+		flow_start(repo_path, FlowKind::Release, "1.0").unwrap();
+		write_commit_file(&repo, "version.txt", "1.0", "bump");
+
+		flow_finish(repo_path, "release/1.0").unwrap();
+
+		let tag = repo
+			.revparse_single("v1.0^{commit}")
+			.unwrap()
+			.peel_to_commit()
+			.unwrap();
+		let master = repo
+			.find_branch("master", BranchType::Local)
+			.unwrap()
+			.get()
+			.peel_to_commit()
+			.unwrap();
+		assert_eq!(tag.id(), master.id());
+		assert_eq!(master.parent_count(), 2);
+		assert_eq!(get_branch_name(repo_path).unwrap(), "develop");
+	}
+
+	#[test]
+	fn test_start_needs_base() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		assert!(
+			flow_start(repo_path, FlowKind::Feature, "x").is_err()
+		);
+		assert!(flow_start(repo_path, FlowKind::Hotfix, "").is_err());
+		flow_start(repo_path, FlowKind::Hotfix, "1.0.1").unwrap();
+		assert_eq!(
+			get_branch_name(repo_path).unwrap(),
+			"hotfix/1.0.1"
+		);
+	}
+}
@@ -0,0 +1,190 @@
+//! exporting commits as patches
+
+use super::{CommitId, RepoPath};
+use crate::{
+	error::{Error, Result},
+	sync::repository::repo,
+};
+use git2::{DiffFormat, Email, EmailCreateOptions, Repository};
+use scopetime::scope_time;
+use std::path::{Path, PathBuf};
+
+/// longest subject part of a patch file name, same as `git format-patch`
+const MAX_FILE_NAME_SUBJECT: usize = 52;
+
+/// writes `commits` (oldest first) as numbered patch files into
+/// `out_dir`, like `git format-patch` does
+pub fn format_patches(
+	repo_path: &RepoPath,
+	commits: &[CommitId],
+	out_dir: &Path,
+) -> Result<Vec<PathBuf>> {
+	scope_time!("format_patches");
+
+	let repo = repo(repo_path)?;
+	let count = commits.len();
+
+	commits
+		.iter()
+		.enumerate()
+		.map(|(idx, id)| {
+			let commit = repo.find_commit((*id).into())?;
+			if commit.parent_count() > 1 {
+				return Err(Error::Generic(format!(
+					"cannot create a patch of merge commit {}",
+					id.get_short_string()
+				)));
+			}
+
+			let diff = commit_diff(&repo, *id)?;
+			let summary = commit.summary().unwrap_or_default();
+			let email = Email::from_diff(
+				&diff,
+				idx + 1,
+				count,
+				&commit.id(),
+				summary,
+				commit.body().unwrap_or_default(),
+				&commit.author(),
+				&mut EmailCreateOptions::new(),
+			)?;
+
+			let path =
+				out_dir.join(patch_file_name(idx + 1, summary));
+			std::fs::write(&path, email.as_slice())?;
+
+			Ok(path)
+		})
+		.collect()
+}
+
+/// diff of the whole range from the parent of `oldest` to `newest`
+pub fn get_range_diff(
+	repo_path: &RepoPath,
+	oldest: CommitId,
+	newest: CommitId,
+) -> Result<String> {
+	scope_time!("get_range_diff");
+
+	let repo = repo(repo_path)?;
+
+	let oldest = repo.find_commit(oldest.into())?;
+	let base = if oldest.parent_count() > 0 {
+		Some(oldest.parent(0)?.tree()?)
+	} else {
+		None
+	};
+	let newest = repo.find_commit(newest.into())?.tree()?;
+
+	let diff =
+		repo.diff_tree_to_tree(base.as_ref(), Some(&newest), None)?;
+
+	diff_to_string(&diff)
+}
+
+fn commit_diff(
+	repo: &Repository,
+	id: CommitId,
+) -> Result<git2::Diff<'_>> {
+	let commit = repo.find_commit(id.into())?;
+	let parent = if commit.parent_count() > 0 {
+		Some(commit.parent(0)?.tree()?)
+	} else {
+		None
+	};
+
+	Ok(repo.diff_tree_to_tree(
+		parent.as_ref(),
+		Some(&commit.tree()?),
+		None,
+	)?)
+}
+
+fn diff_to_string(diff: &git2::Diff) -> Result<String> {
+	let mut out = Vec::new();
+	diff.print(DiffFormat::Patch, |_, _, line| {
+		if matches!(line.origin(), '+' | '-' | ' ') {
+			out.push(line.origin() as u8);
+		}
+		out.extend_from_slice(line.content());
+		true
+	})?;
+
+	Ok(String::from_utf8_lossy(&out).into_owned())
+}
+
+/// `0001-fix-the-thing.patch`
+fn patch_file_name(number: usize, summary: &str) -> String {
+	let mut subject = String::new();
+	for c in summary.chars() {
+		if c.is_ascii_alphanumeric() || c == '.' || c == '_' {
+			subject.push(c);
+		} else if !subject.is_empty() && !subject.ends_with('-') {
+			subject.push('-');
+		}
+	}
+
+	let subject: String =
+		subject.chars().take(MAX_FILE_NAME_SUBJECT).collect();
+	let subject = subject.trim_end_matches(['-', '.']);
+
+	format!("{number:04}-{subject}.patch")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::tests::{repo_init, write_commit_file};
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_patch_file_name() {
+		assert_eq!(
+			patch_file_name(1, "Fix: the thing (again)"),
+			"0001-Fix-the-thing-again.patch"
+		);
+		assert_eq!(patch_file_name(12, "..."), "0012-.patch");
+	}
+
+	#[test]
+	fn test_format_patches() {
+		let (td, repo) = repo_init().unwrap();
+		let repo_path: &RepoPath =
+			&td.path().to_str().unwrap().into();
+
+		let c1 = write_commit_file(&repo, "a.txt", "a\n", "add a");
+		let c2 = write_commit_file(&repo, "a.txt", "b\n", "change a");
+
+		let out = tempfile::TempDir::new().unwrap();
+		let files =
+			format_patches(repo_path, &[c1, c2], out.path()).unwrap();
+
+		assert_eq!(
+			files,
+			vec![
+				out.path().join("0001-add-a.patch"),
+				out.path().join("0002-change-a.patch"),
+			]
+		);
+
+		let second = std::fs::read_to_string(&files[1]).unwrap();
+		assert!(second.contains("Subject: [PATCH 2/2] change a"));
+		assert!(second.contains("-a\n+b\n"));
+	}
+
+	#[test]
+	fn test_range_diff() {
+		let (td, repo) = repo_init().unwrap();
+		let repo_path: &RepoPath =
+			&td.path().to_str().unwrap().into();
+
+		let c1 = write_commit_file(&repo, "a.txt", "a\n", "add a");
+		write_commit_file(&repo, "b.txt", "b\n", "add b");
+		let c3 = write_commit_file(&repo, "a.txt", "c\n", "change a");
+
+		let diff = get_range_diff(repo_path, c1, c3).unwrap();
+
+		assert!(diff.contains("+++ b/a.txt\n@@ -0,0 +1 @@\n+c\n"));
+		assert!(diff.contains("+++ b/b.txt\n@@ -0,0 +1 @@\n+b\n"));
+	}
+}
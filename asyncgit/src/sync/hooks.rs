@@ -1,4 +1,4 @@
-use super::{repository::repo, RepoPath};
+use super::{repository::repo, CommitId, RepoPath};
 use crate::{
 	error::Result,
 	sync::{
@@ -7,11 +7,17 @@ use crate::{
 			push_default_strategy_config_repo,
 			PushDefaultStrategyConfig,
 		},
-		remotes::{proxy_auto, tags::tags_missing_remote, Callbacks},
+		remotes::{
+			advertised::advertised_refs, tags::tags_missing_remote,
+		},
 	},
 };
-use git2::{BranchType, Direction, Oid};
-pub use git2_hooks::{PrePushRef, PrepareCommitMsgSource};
+use git2::{BranchType, ConfigLevel, Direction, Oid, Repository};
+pub use git2_hooks::{
+	HookOutput, PrePushRef, PrepareCommitMsgSource, HOOK_COMMIT_MSG,
+	HOOK_POST_CHECKOUT, HOOK_POST_COMMIT, HOOK_POST_MERGE,
+	HOOK_PREPARE_COMMIT_MSG, HOOK_PRE_COMMIT, HOOK_PRE_PUSH,
+};
 use scopetime::scope_time;
 use std::collections::HashMap;
 
@@ -22,6 +28,8 @@ pub enum HookResult {
 	Ok,
 	/// Hook returned error
 	NotOk(String),
+	/// Hook returned error but its policy only warns about it
+	Warning(String),
 }
 
 impl From<git2_hooks::HookResult> for HookResult {
@@ -48,33 +56,128 @@ impl From<git2_hooks::HookResult> for HookResult {
 	}
 }
 
-/// Retrieve advertised refs from the remote for the upcoming push.
-fn advertised_remote_refs(
+/// how a hook affects the operation it belongs to, set per hook with
+/// `gitui.hook.<name>` to `block`, `warn` or `skip`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum HookPolicy {
+	/// a failing hook stops the operation
+	#[default]
+	Block,
+	/// a failing hook is only reported
+	Warn,
+	/// the hook does not run at all
+	Skip,
+}
+
+impl HookPolicy {
+	///
+	pub const fn as_str(self) -> &'static str {
+		match self {
+			Self::Block => "block",
+			Self::Warn => "warn",
+			Self::Skip => "skip",
+		}
+	}
+
+	///
+	#[must_use]
+	pub const fn next(self) -> Self {
+		match self {
+			Self::Block => Self::Warn,
+			Self::Warn => Self::Skip,
+			Self::Skip => Self::Block,
+		}
+	}
+
+	///
+	#[must_use]
+	pub const fn prev(self) -> Self {
+		match self {
+			Self::Block => Self::Skip,
+			Self::Warn => Self::Block,
+			Self::Skip => Self::Warn,
+		}
+	}
+
+	fn parse(value: &str) -> Option<Self> {
+		match value.to_lowercase().as_str() {
+			"block" => Some(Self::Block),
+			"warn" => Some(Self::Warn),
+			"skip" => Some(Self::Skip),
+			_ => None,
+		}
+	}
+}
+
+fn hook_policy_key(hook: &str) -> String {
+	format!("gitui.hook.{hook}")
+}
+
+fn hook_policy_repo(repo: &Repository, hook: &str) -> HookPolicy {
+	let Ok(value) = repo
+		.config()
+		.and_then(|c| c.get_string(&hook_policy_key(hook)))
+	else {
+		return HookPolicy::default();
+	};
+
+	HookPolicy::parse(&value).unwrap_or_else(|| {
+		log::warn!("unknown policy '{value}' for the {hook} hook");
+		HookPolicy::default()
+	})
+}
+
+/// the policy of `hook` (one of the `HOOK_*` names)
+pub fn hook_policy(
 	repo_path: &RepoPath,
-	remote: Option<&str>,
-	url: &str,
-	basic_credential: Option<crate::sync::cred::BasicAuthCredential>,
-) -> Result<HashMap<String, Oid>> {
+	hook: &str,
+) -> Result<HookPolicy> {
 	let repo = repo(repo_path)?;
-	let mut remote_handle = if let Some(name) = remote {
-		repo.find_remote(name)?
-	} else {
-		repo.remote_anonymous(url)?
-	};
 
-	let callbacks = Callbacks::new(None, basic_credential);
-	let conn = remote_handle.connect_auth(
-		Direction::Push,
-		Some(callbacks.callbacks()),
-		Some(proxy_auto()),
-	)?;
+	Ok(hook_policy_repo(&repo, hook))
+}
 
-	let mut map = HashMap::new();
-	for head in conn.list()? {
-		map.insert(head.name().to_string(), head.oid());
-	}
+/// `true` if `hook` exists and its policy does not skip it
+pub fn hook_runs(repo_path: &RepoPath, hook: &str) -> Result<bool> {
+	let repo = repo(repo_path)?;
+
+	Ok(hook_policy_repo(&repo, hook) != HookPolicy::Skip
+		&& git2_hooks::hook_available(&repo, None, hook)?)
+}
+
+/// sets the policy of `hook` in the config of this repository
+pub fn set_hook_policy(
+	repo_path: &RepoPath,
+	hook: &str,
+	policy: HookPolicy,
+) -> Result<()> {
+	let repo = repo(repo_path)?;
 
-	Ok(map)
+	repo.config()?
+		.open_level(ConfigLevel::Local)?
+		.set_str(&hook_policy_key(hook), policy.as_str())?;
+
+	Ok(())
+}
+
+/// runs `hook` through `run` unless its policy skips it, failures of
+/// warn-only hooks come back as `HookResult::Warning`
+fn with_policy(
+	repo: &Repository,
+	hook: &str,
+	run: impl FnOnce() -> Result<HookResult>,
+) -> Result<HookResult> {
+	match hook_policy_repo(repo, hook) {
+		HookPolicy::Block => run(),
+		HookPolicy::Warn => Ok(match run()? {
+			HookResult::NotOk(e) => HookResult::Warning(e),
+			res => res,
+		}),
+		HookPolicy::Skip => {
+			log::info!("{hook} hook skipped by its policy");
+			Ok(HookResult::Ok)
+		}
+	}
 }
 
 /// Determine the remote ref name for a branch push.
@@ -121,7 +224,9 @@ pub fn hooks_commit_msg(
 
 	let repo = repo(repo_path)?;
 
-	Ok(git2_hooks::hooks_commit_msg(&repo, None, msg)?.into())
+	with_policy(&repo, HOOK_COMMIT_MSG, || {
+		Ok(git2_hooks::hooks_commit_msg(&repo, None, msg)?.into())
+	})
 }
 
 /// see `git2_hooks::hooks_pre_commit`
@@ -130,7 +235,9 @@ pub fn hooks_pre_commit(repo_path: &RepoPath) -> Result<HookResult> {
 
 	let repo = repo(repo_path)?;
 
-	Ok(git2_hooks::hooks_pre_commit(&repo, None)?.into())
+	with_policy(&repo, HOOK_PRE_COMMIT, || {
+		Ok(git2_hooks::hooks_pre_commit(&repo, None)?.into())
+	})
 }
 
 /// see `git2_hooks::hooks_post_commit`
@@ -139,7 +246,46 @@ pub fn hooks_post_commit(repo_path: &RepoPath) -> Result<HookResult> {
 
 	let repo = repo(repo_path)?;
 
-	Ok(git2_hooks::hooks_post_commit(&repo, None)?.into())
+	with_policy(&repo, HOOK_POST_COMMIT, || {
+		Ok(git2_hooks::hooks_post_commit(&repo, None)?.into())
+	})
+}
+
+/// see `git2_hooks::hooks_post_checkout`
+pub fn hooks_post_checkout(
+	repo_path: &RepoPath,
+	old_head: Option<CommitId>,
+	new_head: CommitId,
+	branch_checkout: bool,
+) -> Result<HookResult> {
+	scope_time!("hooks_post_checkout");
+
+	let repo = repo(repo_path)?;
+
+	with_policy(&repo, HOOK_POST_CHECKOUT, || {
+		Ok(git2_hooks::hooks_post_checkout(
+			&repo,
+			None,
+			old_head.map(CommitId::get_oid),
+			new_head.get_oid(),
+			branch_checkout,
+		)?
+		.into())
+	})
+}
+
+/// see `git2_hooks::hooks_post_merge`
+pub fn hooks_post_merge(
+	repo_path: &RepoPath,
+	squash: bool,
+) -> Result<HookResult> {
+	scope_time!("hooks_post_merge");
+
+	let repo = repo(repo_path)?;
+
+	with_policy(&repo, HOOK_POST_MERGE, || {
+		Ok(git2_hooks::hooks_post_merge(&repo, None, squash)?.into())
+	})
 }
 
 /// see `git2_hooks::hooks_prepare_commit_msg`
@@ -152,10 +298,12 @@ pub fn hooks_prepare_commit_msg(
 
 	let repo = repo(repo_path)?;
 
-	Ok(git2_hooks::hooks_prepare_commit_msg(
-		&repo, None, source, msg,
-	)?
-	.into())
+	with_policy(&repo, HOOK_PREPARE_COMMIT_MSG, || {
+		Ok(git2_hooks::hooks_prepare_commit_msg(
+			&repo, None, source, msg,
+		)?
+		.into())
+	})
 }
 
 /// see `git2_hooks::hooks_pre_push`
@@ -164,18 +312,52 @@ pub fn hooks_pre_push(
 	remote: &str,
 	push: &PrePushTarget<'_>,
 	basic_credential: Option<crate::sync::cred::BasicAuthCredential>,
+) -> Result<HookResult> {
+	hooks_pre_push_streamed(
+		repo_path,
+		remote,
+		push,
+		basic_credential,
+		&mut |_| (),
+	)
+}
+
+/// like `hooks_pre_push`, handing each line the hook prints to
+/// `on_output` while it runs
+pub fn hooks_pre_push_streamed(
+	repo_path: &RepoPath,
+	remote: &str,
+	push: &PrePushTarget<'_>,
+	basic_credential: Option<crate::sync::cred::BasicAuthCredential>,
+	on_output: &mut dyn FnMut(HookOutput),
 ) -> Result<HookResult> {
 	scope_time!("hooks_pre_push");
 
 	let repo = repo(repo_path)?;
-	if !git2_hooks::hook_available(
-		&repo,
-		None,
-		git2_hooks::HOOK_PRE_PUSH,
-	)? {
+	if !git2_hooks::hook_available(&repo, None, HOOK_PRE_PUSH)? {
 		return Ok(HookResult::Ok);
 	}
 
+	with_policy(&repo, HOOK_PRE_PUSH, || {
+		run_pre_push(
+			&repo,
+			repo_path,
+			remote,
+			push,
+			basic_credential,
+			on_output,
+		)
+	})
+}
+
+fn run_pre_push(
+	repo: &Repository,
+	repo_path: &RepoPath,
+	remote: &str,
+	push: &PrePushTarget<'_>,
+	basic_credential: Option<crate::sync::cred::BasicAuthCredential>,
+	on_output: &mut dyn FnMut(HookOutput),
+) -> Result<HookResult> {
 	let git_remote = repo.find_remote(remote)?;
 	let url = git_remote
 		.pushurl()
@@ -187,11 +369,12 @@ pub fn hooks_pre_push(
 		})?
 		.to_string();
 
-	let advertised = advertised_remote_refs(
-		repo_path,
+	let advertised = advertised_refs(
+		repo,
 		Some(remote),
 		&url,
-		basic_credential,
+		Direction::Push,
+		basic_credential.as_ref(),
 	)?;
 	let updates = match push {
 		PrePushTarget::Branch { branch, delete } => {
@@ -205,17 +388,21 @@ pub fn hooks_pre_push(
 				&advertised,
 			)?]
 		}
-		PrePushTarget::Tags => {
-			pre_push_tag_updates(repo_path, remote, &advertised)?
-		}
+		PrePushTarget::Tags => pre_push_tag_updates(
+			repo_path,
+			remote,
+			&advertised,
+			basic_credential,
+		)?,
 	};
 
-	Ok(git2_hooks::hooks_pre_push(
-		&repo,
+	Ok(git2_hooks::hooks_pre_push_streamed(
+		repo,
 		None,
 		Some(remote),
 		&url,
 		&updates,
+		on_output,
 	)?
 	.into())
 }
@@ -251,9 +438,12 @@ fn pre_push_tag_updates(
 	repo_path: &RepoPath,
 	remote: &str,
 	advertised: &HashMap<String, Oid>,
+	basic_credential: Option<crate::sync::cred::BasicAuthCredential>,
 ) -> Result<Vec<PrePushRef>> {
 	let repo = repo(repo_path)?;
-	let tags = tags_missing_remote(repo_path, remote, None)?;
+	// served from the refs just advertised for the push
+	let tags =
+		tags_missing_remote(repo_path, remote, basic_credential)?;
 	let mut updates = Vec::with_capacity(tags.len());
 
 	for tag_ref in tags {
@@ -363,6 +553,94 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_hook_policy() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+		let repo_path: &RepoPath = &root.to_path_buf().into();
+
+		let hook = b"#!/bin/sh
+	echo 'rejected'
+	exit 1
+			";
+
+		git2_hooks::create_hook(&repo, HOOK_PRE_COMMIT, hook);
+
+		assert_eq!(
+			hook_policy(repo_path, HOOK_PRE_COMMIT).unwrap(),
+			HookPolicy::Block
+		);
+		assert_eq!(
+			hooks_pre_commit(repo_path).unwrap(),
+			HookResult::NotOk(String::from("rejected\n"))
+		);
+
+		set_hook_policy(repo_path, HOOK_PRE_COMMIT, HookPolicy::Warn)
+			.unwrap();
+		assert_eq!(
+			hooks_pre_commit(repo_path).unwrap(),
+			HookResult::Warning(String::from("rejected\n"))
+		);
+
+		set_hook_policy(repo_path, HOOK_PRE_COMMIT, HookPolicy::Skip)
+			.unwrap();
+		assert_eq!(
+			hooks_pre_commit(repo_path).unwrap(),
+			HookResult::Ok
+		);
+
+		// other hooks keep blocking
+		git2_hooks::create_hook(&repo, HOOK_POST_COMMIT, hook);
+		assert!(matches!(
+			hooks_post_commit(repo_path).unwrap(),
+			HookResult::NotOk(_)
+		));
+
+		// unknown policies block
+		repo.config()
+			.unwrap()
+			.set_str("gitui.hook.pre-commit", "maybe")
+			.unwrap();
+		assert_eq!(
+			hook_policy(repo_path, HOOK_PRE_COMMIT).unwrap(),
+			HookPolicy::Block
+		);
+	}
+
+	#[test]
+	fn test_post_checkout_hook_reports_heads() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.workdir().unwrap();
+		let repo_path: &RepoPath = &root.to_path_buf().into();
+
+		let hook = b"#!/bin/sh
+	echo \"$1 $2 $3\"
+	exit 1
+			";
+
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_POST_CHECKOUT,
+			hook,
+		);
+
+		let head: CommitId =
+			repo.head().unwrap().target().unwrap().into();
+
+		let res =
+			hooks_post_checkout(repo_path, Some(head), head, false)
+				.unwrap();
+
+		assert_eq!(
+			res,
+			HookResult::NotOk(format!("{head} {head} 0\n"))
+		);
+		assert_eq!(
+			hooks_post_merge(repo_path, false).unwrap(),
+			HookResult::Ok
+		);
+	}
+
 	// make sure we run the hooks with the correct pwd.
 	// for non-bare repos this is the dir of the worktree
 	// unfortunately does not work on windows
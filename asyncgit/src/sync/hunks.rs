@@ -1,5 +1,8 @@
 use super::{
-	diff::{get_diff_raw, DiffOptions, HunkHeader},
+	diff::{
+		get_diff_raw, DiffLinePosition, DiffLineType, DiffOptions,
+		Hunk, HunkHeader,
+	},
 	RepoPath,
 };
 use crate::{
@@ -7,8 +10,9 @@ use crate::{
 	hash,
 	sync::repository::repo,
 };
-use git2::{ApplyLocation, ApplyOptions, Diff};
+use git2::{ApplyLocation, ApplyOptions, Diff, DiffFormat};
 use scopetime::scope_time;
+use std::collections::HashSet;
 
 ///
 pub fn stage_hunk(
@@ -148,12 +152,183 @@ pub fn unstage_hunk(
 	Ok(count == 1)
 }
 
+/// `hunk` of the unstaged diff in patch format (header included) to
+/// be edited before [`apply_edited_hunk`], lines are taken from the
+/// raw diff so that line endings survive
+pub fn hunk_patch(
+	repo_path: &RepoPath,
+	file_path: &str,
+	hunk: &Hunk,
+	options: Option<DiffOptions>,
+) -> Result<String> {
+	scope_time!("hunk_patch");
+
+	let repo = repo(repo_path)?;
+
+	let header = hunk
+		.lines
+		.first()
+		.filter(|line| line.line_type == DiffLineType::Header)
+		.ok_or_else(|| {
+			Error::Generic("hunk not found".to_string())
+		})?;
+	let positions: HashSet<DiffLinePosition> = hunk
+		.lines
+		.iter()
+		.filter(|line| line.line_type != DiffLineType::Header)
+		.map(|line| line.position)
+		.collect();
+
+	let mut patch = format!("{}\n", header.content).into_bytes();
+	let mut changes = false;
+	let mut included = false;
+
+	let diff = get_diff_raw(&repo, file_path, false, false, options)?;
+	diff.print(DiffFormat::Patch, |_, _, line| {
+		match line.origin() {
+			' ' | '+' | '-' => {
+				included = positions
+					.contains(&DiffLinePosition::from(&line));
+				if included {
+					changes |= line.origin() != ' ';
+					patch.push(line.origin() as u8);
+					patch.extend_from_slice(line.content());
+				}
+			}
+			// "\ No newline at end of file" of the previous line
+			'=' | '>' | '<' if included => {
+				patch.extend_from_slice(line.content());
+			}
+			_ => (),
+		}
+		true
+	})?;
+
+	if !changes {
+		return Err(Error::Generic("hunk not found".to_string()));
+	}
+
+	Ok(String::from_utf8_lossy(&patch).into_owned())
+}
+
+/// stages a hunk edited like in `git add -p` edit mode: lines
+/// starting with `#` are dropped, the line counts of the header get
+/// recounted and the patch has to apply to the index as it is
+pub fn apply_edited_hunk(
+	repo_path: &RepoPath,
+	file_path: &str,
+	patch: &str,
+) -> Result<()> {
+	scope_time!("apply_edited_hunk");
+
+	let mut start = None;
+	let mut body = String::with_capacity(patch.len());
+	let (mut old_lines, mut new_lines) = (0, 0);
+	let mut changes = false;
+
+	for line in patch.split_inclusive('\n') {
+		if line.starts_with('#') {
+			continue;
+		}
+
+		if start.is_none() {
+			if line.starts_with("@@") {
+				start = Some(parse_hunk_start(line).ok_or_else(
+					|| {
+						Error::Generic(format!(
+							"invalid hunk header: {}",
+							line.trim_end()
+						))
+					},
+				)?);
+			} else if !line.trim().is_empty() {
+				return Err(Error::Generic(
+					"edited hunk has no header".to_string(),
+				));
+			}
+			continue;
+		}
+
+		match line.chars().next() {
+			// editors tend to strip the space of empty context
+			Some('\n' | '\r') => {
+				old_lines += 1;
+				new_lines += 1;
+				body.push(' ');
+			}
+			Some(' ') => {
+				old_lines += 1;
+				new_lines += 1;
+			}
+			Some('-') => {
+				old_lines += 1;
+				changes = true;
+			}
+			Some('+') => {
+				new_lines += 1;
+				changes = true;
+			}
+			Some('\\') => (),
+			_ => {
+				return Err(Error::Generic(format!(
+					"invalid line in edited hunk: {}",
+					line.trim_end()
+				)));
+			}
+		}
+
+		body.push_str(line);
+		if !line.ends_with('\n') {
+			body.push('\n');
+		}
+	}
+
+	let Some((old_start, new_start)) = start else {
+		return Err(Error::Generic(
+			"edited hunk has no header".to_string(),
+		));
+	};
+
+	if !changes {
+		return Ok(());
+	}
+
+	let patch = format!(
+		"diff --git a/{file_path} b/{file_path}\n--- a/{file_path}\n+++ b/{file_path}\n@@ -{old_start},{old_lines} +{new_start},{new_lines} @@\n{body}"
+	);
+
+	let repo = repo(repo_path)?;
+	let diff = Diff::from_buffer(patch.as_bytes())?;
+
+	repo.apply(&diff, ApplyLocation::Index, None).map_err(|e| {
+		Error::Generic(format!("edited hunk does not apply: {e}"))
+	})?;
+
+	Ok(())
+}
+
+/// old and new start line of a `@@ -a,b +c,d @@` header
+fn parse_hunk_start(header: &str) -> Option<(u32, u32)> {
+	let mut ranges = header
+		.strip_prefix("@@ ")?
+		.split(' ')
+		.take(2)
+		.map(|range| range.split(',').next().unwrap_or(range));
+
+	let old = ranges.next()?.strip_prefix('-')?.parse().ok()?;
+	let new = ranges.next()?.strip_prefix('+')?.parse().ok()?;
+
+	Some((old, new))
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use crate::{
 		error::Result,
-		sync::{diff::get_diff, tests::repo_init_empty},
+		sync::{
+			diff::get_diff, stage_add_file, tests::repo_init_empty,
+		},
 	};
 	use std::{
 		fs::{self, File},
@@ -191,4 +366,61 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[test]
+	fn test_edit_hunk() -> Result<()> {
+		let (_td, repo) = repo_init_empty()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		fs::write(root.join("foo.txt"), "a\nb\nc\n")?;
+		stage_add_file(repo_path, Path::new("foo.txt"))?;
+		fs::write(root.join("foo.txt"), "a\nB\nc\nd\n")?;
+
+		let diff = get_diff(repo_path, "foo.txt", false, None)?;
+		let patch =
+			hunk_patch(repo_path, "foo.txt", &diff.hunks[0], None)?;
+		assert_eq!(patch, "@@ -1,3 +1,4 @@\n a\n-b\n+B\n c\n+d\n");
+
+		// keep `b`, stage only the new last line with another text
+		let edited = "# comment\n@@ -1,3 +1,4 @@\n a\n b\n c\n+e\n";
+		apply_edited_hunk(repo_path, "foo.txt", edited)?;
+
+		let mut index = repo.index()?;
+		index.read(true)?;
+		let entry = index.get_path(Path::new("foo.txt"), 0).unwrap();
+		assert_eq!(
+			repo.find_blob(entry.id)?.content(),
+			b"a\nb\nc\ne\n"
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_edit_hunk_invalid() -> Result<()> {
+		let (_td, repo) = repo_init_empty()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		fs::write(root.join("foo.txt"), "a\nb\n")?;
+		stage_add_file(repo_path, Path::new("foo.txt"))?;
+
+		assert!(apply_edited_hunk(
+			repo_path,
+			"foo.txt",
+			"@@ -1,2 +1,2 @@\n a\nx\n"
+		)
+		.is_err());
+		assert!(apply_edited_hunk(
+			repo_path,
+			"foo.txt",
+			"@@ -1,2 +1,2 @@\n a\n-c\n+d\n"
+		)
+		.is_err());
+
+		Ok(())
+	}
 }
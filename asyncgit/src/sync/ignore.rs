@@ -2,26 +2,93 @@ use super::{utils::work_dir, RepoPath};
 use crate::{
 	error::{Error, Result},
 	sync::repository::repo,
+	StatusItem, StatusItemType,
 };
+use git2::{IndexAddOption, Repository, StatusOptions, StatusShow};
 use scopetime::scope_time;
 use std::{
-	fs::{File, OpenOptions},
+	fs::{self, File, OpenOptions},
 	io::{Read, Seek, SeekFrom, Write},
-	path::Path,
+	path::{Path, PathBuf},
 };
 
 static GITIGNORE: &str = ".gitignore";
 
+/// file to add ignore patterns to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IgnoreFile {
+	/// `.gitignore` in the root of the workdir, shared via commits
+	GitIgnore,
+	/// `info/exclude` in the git dir, local to this repository
+	InfoExclude,
+}
+
+/// answers whether paths are ignored, keeping the repository open for
+/// repeated lookups. the ignore rules are read once, a new checker is
+/// needed after a gitignore file changed
+pub struct IgnoreChecker {
+	repo: Repository,
+	work_dir: PathBuf,
+}
+
+impl IgnoreChecker {
+	///
+	pub fn new(repo_path: &RepoPath) -> Result<Self> {
+		let repo = repo(repo_path)?;
+		let work_dir = work_dir(&repo)?.to_path_buf();
+
+		Ok(Self { repo, work_dir })
+	}
+
+	/// `true` if the file or folder at the absolute `path` is ignored,
+	/// paths outside of the workdir or inside the git dir never are
+	pub fn is_ignored(&self, path: &Path) -> bool {
+		if path.starts_with(self.repo.path()) {
+			return false;
+		}
+
+		let Ok(relative) = path.strip_prefix(&self.work_dir) else {
+			return false;
+		};
+
+		if relative.as_os_str().is_empty() {
+			return false;
+		}
+
+		let mut relative = relative.to_string_lossy().to_string();
+		// `folder/` patterns only match with the trailing slash
+		if path.is_dir() {
+			relative.push('/');
+		}
+
+		self.repo.is_path_ignored(relative).unwrap_or_default()
+	}
+}
+
 /// add file or path to root ignore file
 pub fn add_to_ignore(
 	repo_path: &RepoPath,
 	path_to_ignore: &str,
 ) -> Result<()> {
-	scope_time!("add_to_ignore");
+	add_ignore_pattern(
+		repo_path,
+		path_to_ignore,
+		IgnoreFile::GitIgnore,
+	)
+}
+
+/// add an ignore pattern (like `/foo.txt`, `*.log` or `build/`) to
+/// the root ignore file or the local exclude file
+pub fn add_ignore_pattern(
+	repo_path: &RepoPath,
+	pattern: &str,
+	file: IgnoreFile,
+) -> Result<()> {
+	scope_time!("add_ignore_pattern");
 
 	let repo = repo(repo_path)?;
 
-	if Path::new(path_to_ignore).file_name()
+	if Path::new(pattern.trim_matches('/')).file_name()
 		== Path::new(GITIGNORE).file_name()
 	{
 		return Err(Error::Generic(String::from(
@@ -29,21 +96,149 @@ pub fn add_to_ignore(
 		)));
 	}
 
-	let ignore_file = work_dir(&repo)?.join(GITIGNORE);
+	let ignore_file = match file {
+		IgnoreFile::GitIgnore => work_dir(&repo)?.join(GITIGNORE),
+		IgnoreFile::InfoExclude => {
+			let info = repo.commondir().join("info");
+			fs::create_dir_all(&info)?;
+			info.join("exclude")
+		}
+	};
+
+	append_line(&ignore_file, pattern)
+}
+
+/// lists the files and folders in the workdir that are ignored by
+/// any gitignore file, `info/exclude` or `core.excludesFile`.
+/// ignored folders are listed as a whole instead of their content.
+pub fn get_ignored_files(
+	repo_path: &RepoPath,
+) -> Result<Vec<StatusItem>> {
+	scope_time!("get_ignored_files");
+
+	let repo = repo(repo_path)?;
+
+	let mut options = StatusOptions::default();
+	options
+		.show(StatusShow::Workdir)
+		.include_untracked(false)
+		.include_ignored(true)
+		.recurse_ignored_dirs(false);
+
+	let statuses = repo.statuses(Some(&mut options))?;
+
+	let mut res: Vec<StatusItem> = statuses
+		.iter()
+		.filter(|entry| entry.status().is_ignored())
+		.filter_map(|entry| {
+			entry.path().map(|path| StatusItem {
+				path: path.trim_end_matches('/').to_string(),
+				status: StatusItemType::Ignored,
+			})
+		})
+		.collect();
+
+	res.sort_by(|a, b| Path::new(&a.path).cmp(Path::new(&b.path)));
+
+	Ok(res)
+}
+
+/// stages an ignored file or everything inside an ignored folder
+pub fn stage_add_ignored(
+	repo_path: &RepoPath,
+	path: &str,
+) -> Result<()> {
+	scope_time!("stage_add_ignored");
+
+	let repo = repo(repo_path)?;
+	let mut index = repo.index()?;
+
+	index.add_all([path], IndexAddOption::FORCE, None)?;
+	index.write()?;
+
+	Ok(())
+}
+
+/// stops ignoring a file or folder by removing its own entry from the
+/// root ignore file or, if a broader pattern matches it, by adding a
+/// negated entry for it
+pub fn remove_from_ignore(
+	repo_path: &RepoPath,
+	path_to_unignore: &str,
+) -> Result<()> {
+	scope_time!("remove_from_ignore");
+
+	let repo = repo(repo_path)?;
+
+	// git never looks into an excluded folder, so no pattern can bring
+	// back a file in there
+	if let Some(parent) = Path::new(path_to_unignore)
+		.ancestors()
+		.skip(1)
+		.filter_map(Path::to_str)
+		.filter(|parent| !parent.is_empty())
+		.find(|parent| {
+			repo.is_path_ignored(format!("{parent}/"))
+				.unwrap_or_default()
+		}) {
+		return Err(Error::Generic(format!(
+			"the folder '{parent}' is ignored, force add instead"
+		)));
+	}
+
+	let work_dir = work_dir(&repo)?;
+	let ignore_file = work_dir.join(GITIGNORE);
+	let is_dir = work_dir.join(path_to_unignore).is_dir();
+
+	if ignore_file.exists() {
+		let content = fs::read_to_string(&ignore_file)?;
+		let lines: Vec<&str> = content
+			.lines()
+			.filter(|line| {
+				line.trim()
+					.trim_start_matches('/')
+					.trim_end_matches('/')
+					!= path_to_unignore
+			})
+			.collect();
+
+		if lines.len() != content.lines().count() {
+			let mut content = lines.join("\n");
+			if !content.is_empty() {
+				content.push('\n');
+			}
+			fs::write(&ignore_file, content)?;
+		}
+	}
 
-	let optional_newline = ignore_file.exists()
-		&& !file_ends_with_newline(&ignore_file)?;
+	// the ignore rules are cached per repository
+	if super::repository::repo(repo_path)?
+		.is_path_ignored(path_to_unignore)?
+	{
+		append_line(
+			&ignore_file,
+			&format!(
+				"!/{path_to_unignore}{}",
+				if is_dir { "/" } else { "" }
+			),
+		)?;
+	}
 
-	let mut file = OpenOptions::new()
-		.append(true)
-		.create(true)
-		.open(ignore_file)?;
+	Ok(())
+}
+
+fn append_line(file: &Path, line: &str) -> Result<()> {
+	let optional_newline =
+		file.exists() && !file_ends_with_newline(file)?;
+
+	let mut file =
+		OpenOptions::new().append(true).create(true).open(file)?;
 
 	writeln!(
 		file,
 		"{}{}",
 		if optional_newline { "\n" } else { "" },
-		path_to_ignore
+		line
 	)?;
 
 	Ok(())
@@ -63,7 +258,11 @@ fn file_ends_with_newline(file: &Path) -> Result<bool> {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::sync::{tests::repo_init, utils::repo_write_file};
+	use crate::sync::{
+		status::{get_status, StatusType},
+		tests::repo_init,
+		utils::repo_write_file,
+	};
 	use io::BufRead;
 	use pretty_assertions::assert_eq;
 	use std::{fs::File, io, path::Path};
@@ -140,6 +339,145 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn test_add_pattern_to_exclude() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		repo_write_file(&repo, "a.log", "test")?;
+		repo_write_file(&repo, "b.txt", "test")?;
+
+		add_ignore_pattern(
+			repo_path,
+			"*.log",
+			IgnoreFile::InfoExclude,
+		)?;
+
+		assert!(!root.join(GITIGNORE).exists());
+		assert!(fs::read_to_string(
+			repo.path().join("info").join("exclude")
+		)?
+		.ends_with("*.log\n"));
+
+		let status =
+			get_status(repo_path, StatusType::WorkingDir, None)?;
+		assert_eq!(status.len(), 1);
+		assert_eq!(status[0].path, "b.txt");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_get_ignored_files() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		repo_write_file(&repo, ".gitignore", "*.log\ntarget/\n")?;
+		repo_write_file(&repo, "a.log", "test")?;
+		repo_write_file(&repo, "a.txt", "test")?;
+		fs::create_dir(root.join("target"))?;
+		repo_write_file(&repo, "target/b.txt", "test")?;
+		repo_write_file(&repo, "target/c.txt", "test")?;
+
+		let ignored: Vec<_> = get_ignored_files(repo_path)?
+			.into_iter()
+			.map(|item| item.path)
+			.collect();
+
+		assert_eq!(ignored, vec!["a.log", "target"]);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_ignore_checker() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		repo_write_file(&repo, ".gitignore", "*.log\ntarget/\n")?;
+		fs::create_dir_all(root.join("target/debug"))?;
+		fs::create_dir(root.join("src"))?;
+
+		let checker = IgnoreChecker::new(repo_path)?;
+
+		assert!(checker.is_ignored(&root.join("a.log")));
+		assert!(checker.is_ignored(&root.join("target")));
+		assert!(checker.is_ignored(&root.join("target/debug/gitui")));
+		assert!(!checker.is_ignored(&root.join("src")));
+		assert!(!checker.is_ignored(&root.join("src/main.rs")));
+		assert!(!checker.is_ignored(root));
+		assert!(!checker.is_ignored(&repo.path().join("index")));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_stage_add_ignored() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		repo_write_file(&repo, ".gitignore", "target/\n")?;
+		fs::create_dir(root.join("target"))?;
+		repo_write_file(&repo, "target/b.txt", "test")?;
+
+		stage_add_ignored(repo_path, "target")?;
+
+		let staged = get_status(repo_path, StatusType::Stage, None)?;
+		assert_eq!(staged.len(), 1);
+		assert_eq!(staged[0].path, "target/b.txt");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_remove_from_ignore() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		repo_write_file(&repo, ".gitignore", "/a.txt\n*.log\n")?;
+		repo_write_file(&repo, "a.txt", "test")?;
+		repo_write_file(&repo, "b.log", "test")?;
+
+		remove_from_ignore(repo_path, "a.txt")?;
+		remove_from_ignore(repo_path, "b.log")?;
+
+		assert_eq!(
+			fs::read_to_string(root.join(GITIGNORE))?,
+			"*.log\n!/b.log\n"
+		);
+		assert!(get_ignored_files(repo_path)?.is_empty());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_remove_from_ignore_in_ignored_folder() -> Result<()> {
+		let (_td, repo) = repo_init()?;
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		repo_write_file(&repo, ".gitignore", "target/\n")?;
+		fs::create_dir(root.join("target"))?;
+		repo_write_file(&repo, "target/b.txt", "test")?;
+
+		assert!(
+			remove_from_ignore(repo_path, "target/b.txt").is_err()
+		);
+
+		Ok(())
+	}
+
 	#[test]
 	fn test_ignore_ignore() {
 		let ignore_file_path = Path::new(".gitignore");
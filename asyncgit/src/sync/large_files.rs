@@ -0,0 +1,206 @@
+//! files that should not end up in the index as they are: bigger
+//! than `gitui.largeFileSize` (bytes, `k`/`m`/`g` suffixes work, `0`
+//! turns the check off, 10m by default) or tracked by git-lfs, whose
+//! filter libgit2 does not run
+
+use super::{
+	config::untracked_files_config_repo, repository::repo,
+	utils::work_dir, RepoPath, ShowUntrackedFilesConfig,
+};
+use crate::error::{Error, Result};
+use git2::{AttrCheckFlags, Repository, Status, StatusOptions};
+use scopetime::scope_time;
+use std::{path::Path, process::Command};
+
+const SIZE_CONFIG: &str = "gitui.largeFileSize";
+const DEFAULT_SIZE: u64 = 10 * 1024 * 1024;
+
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LargeFile {
+	///
+	pub path: String,
+	/// in bytes
+	pub size: u64,
+	/// matches a git-lfs pattern in `.gitattributes`
+	pub lfs: bool,
+}
+
+fn size_limit(repo: &Repository) -> Result<u64> {
+	Ok(repo
+		.config()?
+		.get_i64(SIZE_CONFIG)
+		.map_or(DEFAULT_SIZE, |size| size.try_into().unwrap_or(0)))
+}
+
+fn is_lfs(repo: &Repository, path: &str) -> bool {
+	repo.get_attr(
+		Path::new(path),
+		"filter",
+		AttrCheckFlags::FILE_THEN_INDEX,
+	)
+	.ok()
+	.flatten()
+		== Some("lfs")
+}
+
+/// the large files among the changes `stage_add_all` would stage for
+/// `pattern`
+pub fn large_files(
+	repo_path: &RepoPath,
+	pattern: &str,
+	stage_untracked: Option<ShowUntrackedFilesConfig>,
+) -> Result<Vec<LargeFile>> {
+	scope_time!("large_files");
+
+	let repo = repo(repo_path)?;
+	let limit = size_limit(&repo)?;
+	let workdir = work_dir(&repo)?;
+
+	let stage_untracked = match stage_untracked {
+		Some(config) => config,
+		None => untracked_files_config_repo(&repo)?,
+	};
+
+	let statuses = repo.statuses(Some(
+		StatusOptions::new()
+			.pathspec(pattern)
+			.include_untracked(stage_untracked.include_untracked())
+			.recurse_untracked_dirs(true)
+			.include_ignored(false),
+	))?;
+
+	let changed =
+		Status::WT_NEW | Status::WT_MODIFIED | Status::WT_TYPECHANGE;
+
+	Ok(statuses
+		.iter()
+		.filter(|entry| entry.status().intersects(changed))
+		.filter_map(|entry| {
+			let path = entry.path()?.to_string();
+			// a symlink is stored as its target path
+			let size = workdir
+				.join(&path)
+				.symlink_metadata()
+				.ok()
+				.filter(std::fs::Metadata::is_file)?
+				.len();
+			let lfs = is_lfs(&repo, &path);
+
+			((limit > 0 && size >= limit) || lfs)
+				.then_some(LargeFile { path, size, lfs })
+		})
+		.collect())
+}
+
+/// tracks `path` with git-lfs and stages it through the git cli,
+/// which runs the lfs filter
+pub fn stage_lfs(repo_path: &RepoPath, path: &str) -> Result<()> {
+	scope_time!("stage_lfs");
+
+	let repo = repo(repo_path)?;
+	let workdir = work_dir(&repo)?;
+
+	if !is_lfs(&repo, path) {
+		git(workdir, &["lfs", "track", "--filename", "--", path])?;
+	}
+
+	git(workdir, &["add", "--", ".gitattributes", path])
+}
+
+fn git(workdir: &Path, args: &[&str]) -> Result<()> {
+	let output = Command::new("git")
+		.args(args)
+		.current_dir(workdir)
+		.output()
+		.map_err(|e| {
+			Error::Generic(format!("git-lfs needs git: {e}"))
+		})?;
+
+	if !output.status.success() {
+		return Err(Error::Generic(format!(
+			"git {} failed: {}",
+			args.first().unwrap_or(&""),
+			String::from_utf8_lossy(&output.stderr).trim()
+		)));
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		stage_add_all_except,
+		status::{get_status, StatusType},
+		tests::{get_statuses, repo_init},
+	};
+	use std::fs;
+
+	#[test]
+	fn test_large_files() {
+		let (td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		repo.config().unwrap().set_str(SIZE_CONFIG, "1k").unwrap();
+
+		fs::write(
+			td.path().join(".gitattributes"),
+			"*.bin filter=lfs diff=lfs merge=lfs -text\n",
+		)
+		.unwrap();
+		fs::create_dir(td.path().join("dir")).unwrap();
+		fs::write(td.path().join("dir/big.txt"), vec![b'a'; 2048])
+			.unwrap();
+		fs::write(td.path().join("small.txt"), "a").unwrap();
+		fs::write(td.path().join("small.bin"), "a").unwrap();
+
+		let mut large = large_files(
+			repo_path,
+			"*",
+			Some(ShowUntrackedFilesConfig::All),
+		)
+		.unwrap();
+		large.sort_by(|a, b| a.path.cmp(&b.path));
+
+		assert_eq!(
+			large,
+			vec![
+				LargeFile {
+					path: String::from("dir/big.txt"),
+					size: 2048,
+					lfs: false,
+				},
+				LargeFile {
+					path: String::from("small.bin"),
+					size: 1,
+					lfs: true,
+				},
+			]
+		);
+		assert!(large_files(
+			repo_path,
+			"small.txt",
+			Some(ShowUntrackedFilesConfig::All)
+		)
+		.unwrap()
+		.is_empty());
+
+		stage_add_all_except(
+			repo_path,
+			"*",
+			Some(ShowUntrackedFilesConfig::All),
+			&[String::from("dir/big.txt"), String::from("small.bin")],
+		)
+		.unwrap();
+
+		// .gitattributes and small.txt
+		assert_eq!(get_statuses(repo_path), (2, 2));
+		let staged =
+			get_status(repo_path, StatusType::Stage, None).unwrap();
+		assert!(staged.iter().all(|item| item.path != "small.bin"));
+	}
+}
@@ -383,4 +383,70 @@ mod tests {
 
 		assert_eq!(items.len(), 2);
 	}
+
+	#[test]
+	fn test_logwalker_with_filter_regex_and_content() {
+		let (_td, repo) = repo_init_empty().unwrap();
+
+		write_commit_file(&repo, "foo", "a", "fix #12");
+		let second_commit_id =
+			write_commit_file(&repo, "foo", "needle", "commit2");
+		let third_commit_id =
+			write_commit_file(&repo, "foo", "b", "Fix #3");
+
+		let search = |fields, options, pattern: &str| {
+			let mut items = Vec::new();
+			LogWalker::new(&repo, 100)
+				.unwrap()
+				.filter(Some(filter_commit_by_search(
+					LogFilterSearch::new(LogFilterSearchOptions {
+						fields,
+						options,
+						search_pattern: pattern.into(),
+					}),
+				)))
+				.read(&mut items)
+				.unwrap();
+			items
+		};
+
+		assert_eq!(
+			search(
+				SearchFields::MESSAGE_SUMMARY,
+				SearchOptions::REGEX | SearchOptions::CASE_SENSITIVE,
+				r"^Fix #\d$",
+			),
+			vec![third_commit_id]
+		);
+
+		// added in the second and removed in the third commit
+		assert_eq!(
+			search(
+				SearchFields::DIFFS,
+				SearchOptions::empty(),
+				"NEEDLE"
+			),
+			vec![third_commit_id, second_commit_id]
+		);
+
+		// an invalid regex matches nothing
+		assert!(search(
+			SearchFields::MESSAGE_SUMMARY,
+			SearchOptions::REGEX,
+			"fix (",
+		)
+		.is_empty());
+	}
+
+	#[test]
+	fn test_search_find() {
+		let search = LogFilterSearch::new(LogFilterSearchOptions {
+			fields: SearchFields::MESSAGE_SUMMARY,
+			options: SearchOptions::empty(),
+			search_pattern: "MSG".into(),
+		});
+
+		assert_eq!(search.find("my msg (#2)"), Some(3..6));
+		assert_eq!(search.find("other"), None);
+	}
 }
@@ -1,10 +1,17 @@
 use crate::{
 	error::{Error, Result},
 	sync::{
-		branch::merge_commit::commit_merge_with_head,
+		branch::{
+			merge_commit::commit_merge_with_head,
+			protection::check_head_protection,
+		},
 		rebase::{
 			abort_rebase, continue_rebase, get_rebase_progress,
 		},
+		rebase_interactive::{
+			abort_rebase_interactive, continue_rebase_interactive,
+			get_rebase_interactive_progress, is_rebase_interactive,
+		},
 		repository::repo,
 		reset_stage, reset_workdir, CommitId,
 	},
@@ -72,6 +79,10 @@ pub fn rebase_progress(
 
 	let repo = repo(repo_path)?;
 
+	if is_rebase_interactive(&repo) {
+		return get_rebase_interactive_progress(&repo);
+	}
+
 	get_rebase_progress(&repo)
 }
 
@@ -83,6 +94,10 @@ pub fn continue_pending_rebase(
 
 	let repo = repo(repo_path)?;
 
+	if is_rebase_interactive(&repo) {
+		return continue_rebase_interactive(&repo);
+	}
+
 	continue_rebase(&repo)
 }
 
@@ -92,6 +107,10 @@ pub fn abort_pending_rebase(repo_path: &RepoPath) -> Result<()> {
 
 	let repo = repo(repo_path)?;
 
+	if is_rebase_interactive(&repo) {
+		return abort_rebase_interactive(&repo);
+	}
+
 	abort_rebase(&repo)
 }
 
@@ -139,6 +158,7 @@ pub fn merge_commit(
 	scope_time!("merge_commit");
 
 	let repo = repo(repo_path)?;
+	check_head_protection(&repo)?;
 
 	let mut commits: Vec<Commit> = Vec::new();
 
@@ -3,9 +3,13 @@
 //TODO: remove once we have this activated on the toplevel
 #![deny(clippy::expect_used)]
 
+mod absorb;
+mod apply_patch;
+mod bisect;
 pub mod blame;
 pub mod branch;
 pub mod commit;
+mod commit_checks;
 mod commit_details;
 pub mod commit_files;
 mod commit_filter;
@@ -14,42 +18,81 @@ mod commits_info;
 mod config;
 pub mod cred;
 pub mod diff;
+mod filter;
+mod flow;
+mod format_patch;
 mod hooks;
 mod hunks;
 mod ignore;
+mod large_files;
 mod logwalker;
 mod merge;
 mod patches;
 mod rebase;
+pub mod rebase_interactive;
 pub mod remotes;
 mod repository;
 mod reset;
 mod reword;
 pub mod sign;
+mod snapshot;
+mod special_refs;
+mod stack;
 mod staging;
 mod stash;
 mod state;
 pub mod status;
 mod submodules;
 mod tags;
+mod text_format;
+mod trash;
 mod tree;
 pub mod utils;
+mod worktrees;
 
+pub use absorb::{absorb, AbsorbResult};
+pub use apply_patch::{
+	apply_patch, ApplyLocation, ApplyPatchResult, PatchConflict,
+};
+pub use bisect::{
+	bisect_mark, bisect_reset, bisect_run_step, bisect_state,
+	is_bisecting, BisectMark, BisectState,
+};
 pub use blame::{blame_file, BlameHunk, FileBlame};
 pub use branch::{
-	branch_compare_upstream, checkout_branch, checkout_commit,
+	autostash::checkout_branch_autostash,
+	branch_compare_upstream, branches_compare_upstream,
+	checkout_branch, checkout_commit,
+	checkout_conflicts::{
+		checkout_branch_resolved, checkout_conflicts,
+		ConflictResolution,
+	},
+	cleanup::{
+		cleanup_candidates, default_branch, delete_branches,
+		CleanupCandidate, CleanupReason,
+	},
 	config_is_pull_rebase, create_branch, delete_branch,
 	get_branch_remote, get_branch_upstream_merge, get_branches_info,
 	merge_commit::merge_upstream_commit,
 	merge_ff::branch_merge_upstream_fastforward,
-	merge_rebase::merge_upstream_rebase, rename::rename_branch,
+	merge_rebase::merge_upstream_rebase,
+	protection::{
+		head_protection, protected_branches, BranchProtection,
+		ProtectedAction,
+	},
+	rename::{copy_branch, rename_branch},
 	validate_branch_name, BranchCompare, BranchDetails, BranchInfo,
 };
-pub use commit::{amend, commit, tag_commit};
+pub use commit::{amend, amend_with_files, commit, tag_commit};
+pub use commit_checks::{
+	check_staged, commit_checks_enabled, CheckKind, CheckViolation,
+};
 pub use commit_details::{
 	get_commit_details, CommitDetails, CommitMessage, CommitSignature,
 };
-pub use commit_files::get_commit_files;
+pub use commit_files::{
+	get_commit_files, get_compare_dirstat, sort_commit_ids, DirStat,
+};
 pub use commit_filter::{
 	diff_contains_file, filter_commit_by_search, LogFilterSearch,
 	LogFilterSearchOptions, SearchFields, SearchOptions,
@@ -57,56 +100,109 @@ pub use commit_filter::{
 };
 pub use commit_revert::{commit_revert, revert_commit, revert_head};
 pub use commits_info::{
-	get_commit_info, get_commits_info, CommitId, CommitInfo,
+	get_commit_children, get_commit_info, get_commit_parents,
+	get_commits_info, get_log, CommitId, CommitInfo,
 };
 pub use config::{
-	get_config_string, untracked_files_config,
+	get_config_string, get_config_strings, untracked_files_config,
 	ShowUntrackedFilesConfig,
 };
 pub use diff::get_diff_commit;
+pub use flow::{
+	flow_config, flow_finish, flow_start, FlowConfig, FlowKind,
+};
+pub use format_patch::{format_patches, get_range_diff};
 pub use git2::BranchType;
 pub use hooks::{
-	hooks_commit_msg, hooks_post_commit, hooks_pre_commit,
-	hooks_pre_push, hooks_prepare_commit_msg, HookResult,
-	PrePushTarget, PrepareCommitMsgSource,
+	hook_policy, hook_runs, hooks_commit_msg, hooks_post_checkout,
+	hooks_post_commit, hooks_post_merge, hooks_pre_commit,
+	hooks_pre_push, hooks_pre_push_streamed,
+	hooks_prepare_commit_msg, set_hook_policy, HookOutput,
+	HookPolicy, HookResult, PrePushTarget, PrepareCommitMsgSource,
+	HOOK_COMMIT_MSG, HOOK_POST_CHECKOUT, HOOK_POST_COMMIT,
+	HOOK_POST_MERGE, HOOK_PREPARE_COMMIT_MSG, HOOK_PRE_COMMIT,
+	HOOK_PRE_PUSH,
+};
+pub use hunks::{
+	apply_edited_hunk, hunk_patch, reset_hunk, stage_hunk,
+	unstage_hunk,
 };
-pub use hunks::{reset_hunk, stage_hunk, unstage_hunk};
-pub use ignore::add_to_ignore;
+pub use ignore::{
+	add_ignore_pattern, add_to_ignore, get_ignored_files,
+	remove_from_ignore, stage_add_ignored, IgnoreChecker, IgnoreFile,
+};
+pub use large_files::{large_files, stage_lfs, LargeFile};
 pub use logwalker::{LogWalker, LogWalkerWithoutFilter};
 pub use merge::{
 	abort_pending_rebase, abort_pending_state,
 	continue_pending_rebase, merge_branch, merge_commit, merge_msg,
 	mergehead_ids, rebase_progress,
 };
+pub use patches::split_hunk;
 pub use rebase::rebase_branch;
+pub use rebase_interactive::{
+	rebase_interactive, rebase_interactive_todo, RebaseAction,
+	RebaseTodo,
+};
 pub use remotes::{
 	add_remote, delete_remote, get_default_remote,
 	get_default_remote_for_fetch, get_default_remote_for_push,
-	get_remote_url, get_remotes, push::AsyncProgress, rename_remote,
-	tags::PushTagsProgress, update_remote_url, validate_remote_name,
+	get_remote_url, get_remotes, is_shallow, push::AsyncProgress,
+	rename_remote, tags::PushTagsProgress, update_remote_url,
+	validate_remote_name,
 };
 pub(crate) use repository::{gix_repo, repo};
 pub use repository::{RepoPath, RepoPathRef};
-pub use reset::{reset_repo, reset_stage, reset_workdir};
+pub use reset::{
+	checkout_file_at_commit, reset_preview, reset_repo, reset_stage,
+	reset_workdir, ResetPreview,
+};
 pub use reword::reword;
-pub use staging::{discard_lines, stage_lines};
+pub use snapshot::{repo_snapshot, RepoChanges, RepoSnapshot};
+pub use special_refs::{get_special_refs, SpecialRef};
+pub use stack::{
+	get_patch_stack, stack_autosquash_todo, stack_fixup,
+	stack_rewrite, PatchStack, StackAction, StackTodo,
+};
+pub use staging::{discard_lines, stage_lines, unstage_lines};
 pub use stash::{
-	get_stashes, stash_apply, stash_drop, stash_pop, stash_save,
+	get_stashes, stash_apply, stash_drop, stash_pop,
+	stash_push_paths, stash_save, StashPushOptions,
+};
+pub use state::{
+	abort_apply_mailbox, apply_mailbox_progress, cherrypick_head,
+	repo_state, ApplyMailboxProgress, RepoState,
 };
-pub use state::{repo_state, RepoState};
 pub use status::is_workdir_clean;
 pub use submodules::{
-	get_submodules, submodule_parent_info, update_submodule,
-	SubmoduleInfo, SubmoduleParentInfo, SubmoduleStatus,
+	add_submodule, get_submodules, get_submodules_recursive,
+	init_submodule, remove_submodule, submodule_parent_info,
+	sync_submodule, update_submodule, SubmoduleInfo,
+	SubmoduleParentInfo, SubmoduleStatus,
 };
 pub use tags::{
-	delete_tag, get_tags, get_tags_with_metadata, CommitTags, Tag,
-	TagWithMetadata, Tags,
+	delete_tag, get_tag_sort, get_tags, get_tags_with_metadata,
+	CommitTags, Tag, TagSort, TagSortKey, TagWithMetadata, Tags,
+};
+pub use text_format::{
+	normalize_staged_eol, LineEnding, TextEncoding, TextFormat,
+};
+pub use trash::{
+	trash_drop, trash_list, trash_paths, trash_restore, TrashEntry,
+};
+pub use tree::{
+	search_tree, tree_file_content, tree_files, TreeFile,
+	TreeSearchMatch,
 };
-pub use tree::{tree_file_content, tree_files, TreeFile};
 pub use utils::{
-	get_head, get_head_tuple, repo_dir, repo_open_error,
-	stage_add_all, stage_add_file, stage_addremoved, Head,
+	get_head, get_head_tuple, glob_match, repo_common_dir, repo_dir,
+	repo_open_error, stage_add_all, stage_add_all_except,
+	stage_add_file, stage_addremoved, Head,
+};
+pub use worktrees::{
+	add_worktree, current_worktree_name, get_worktrees,
+	lock_worktree, prune_worktrees, remove_worktree, unlock_worktree,
+	WorktreeInfo,
 };
 
 pub use git2::ResetType;
@@ -1,5 +1,11 @@
-use super::diff::{get_diff_raw, DiffOptions, HunkHeader};
-use crate::error::{Error, Result};
+use super::diff::{
+	get_diff_raw, DiffLine as HunkLine, DiffLinePosition,
+	DiffLineType, DiffOptions, Hunk, HunkHeader,
+};
+use crate::{
+	error::{Error, Result},
+	hash,
+};
 use git2::{Diff, DiffLine, Patch, Repository};
 
 pub struct HunkLines<'a> {
@@ -62,6 +68,102 @@ pub fn patch_get_hunklines<'a>(
 	Ok(res)
 }
 
+/// splits `hunk` into one hunk per block of changes separated by
+/// context lines, like `git add -p` does. the context between two
+/// blocks ends the first and starts the second hunk.
+///
+/// a hunk with a single block of changes stays as it is
+pub fn split_hunk(hunk: &Hunk) -> Vec<Hunk> {
+	let lines: Vec<&HunkLine> = hunk
+		.lines
+		.iter()
+		.filter(|line| line.line_type != DiffLineType::Header)
+		.collect();
+	let is_change = |line: &&HunkLine| {
+		matches!(
+			line.line_type,
+			DiffLineType::Add | DiffLineType::Delete
+		)
+	};
+
+	// (first, last) index of each block of changes
+	let mut blocks: Vec<(usize, usize)> = Vec::new();
+	for (idx, line) in lines.iter().enumerate() {
+		if !is_change(line) {
+			continue;
+		}
+		match blocks.last_mut() {
+			Some((_, last)) if *last + 1 == idx => *last = idx,
+			_ => blocks.push((idx, idx)),
+		}
+	}
+
+	if blocks.len() < 2 {
+		return vec![hunk.clone()];
+	}
+
+	(0..blocks.len())
+		.map(|block_idx| {
+			let start = block_idx
+				.checked_sub(1)
+				.map_or(0, |prev| blocks[prev].1 + 1);
+			let end = blocks
+				.get(block_idx + 1)
+				.map_or(lines.len(), |(next, _)| *next);
+
+			sub_hunk(&lines[start..end])
+		})
+		.collect()
+}
+
+/// a hunk of `lines`, which always have context on both sides
+fn sub_hunk(lines: &[&HunkLine]) -> Hunk {
+	let start = |lineno: fn(&HunkLine) -> Option<u32>| {
+		lines
+			.iter()
+			.find_map(|line| lineno(line))
+			.unwrap_or_default()
+	};
+	let count = |lineno: fn(&HunkLine) -> Option<u32>| {
+		u32::try_from(
+			lines
+				.iter()
+				.filter(|line| lineno(line).is_some())
+				.count(),
+		)
+		.unwrap_or(u32::MAX)
+	};
+
+	let old = |line: &HunkLine| line.position.old_lineno;
+	let new = |line: &HunkLine| line.position.new_lineno;
+	let header = HunkHeader {
+		old_start: start(old),
+		old_lines: count(old),
+		new_start: start(new),
+		new_lines: count(new),
+	};
+
+	let mut hunk_lines = Vec::with_capacity(lines.len() + 1);
+	hunk_lines.push(HunkLine {
+		content: format!(
+			"@@ -{},{} +{},{} @@",
+			header.old_start,
+			header.old_lines,
+			header.new_start,
+			header.new_lines
+		)
+		.into(),
+		line_type: DiffLineType::Header,
+		position: DiffLinePosition::default(),
+	});
+	hunk_lines.extend(lines.iter().map(|line| (*line).clone()));
+
+	Hunk {
+		header_hash: hash(&header),
+		lines: hunk_lines,
+	}
+}
+
 //
 fn get_patches<'a>(diff: &Diff<'a>) -> Result<Vec<Patch<'a>>> {
 	let count = diff.deltas().len();
@@ -76,3 +178,73 @@ fn get_patches<'a>(diff: &Diff<'a>) -> Result<Vec<Patch<'a>>> {
 
 	Ok(res)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		diff::get_diff,
+		stage_lines,
+		tests::{repo_init, write_commit_file},
+		utils::repo_write_file,
+		RepoPath,
+	};
+
+	#[test]
+	fn test_split_hunk() {
+		let (td, repo) = repo_init().unwrap();
+		let path: &RepoPath = &td.path().to_str().unwrap().into();
+
+		write_commit_file(&repo, "a.txt", "1\n2\n3\n4\n5\n6\n", "c1");
+		repo_write_file(&repo, "a.txt", "1\nb\n3\n4\n5\ne\nf\n")
+			.unwrap();
+
+		let diff = get_diff(path, "a.txt", false, None).unwrap();
+		assert_eq!(diff.hunks.len(), 1);
+
+		let hunks = split_hunk(&diff.hunks[0]);
+		assert_eq!(hunks.len(), 2);
+		assert_eq!(&*hunks[0].lines[0].content, "@@ -1,5 +1,5 @@");
+		assert_eq!(&*hunks[1].lines[0].content, "@@ -3,4 +3,5 @@");
+		// the context between the changes is in both
+		assert_eq!(&*hunks[0].lines.last().unwrap().content, "5");
+		assert_eq!(&*hunks[1].lines[1].content, "3");
+
+		let changes: Vec<DiffLinePosition> = hunks[1]
+			.lines
+			.iter()
+			.filter(|line| {
+				matches!(
+					line.line_type,
+					DiffLineType::Add | DiffLineType::Delete
+				)
+			})
+			.map(|line| line.position)
+			.collect();
+		stage_lines(path, "a.txt", &changes).unwrap();
+
+		let staged = get_diff(path, "a.txt", true, None).unwrap();
+		let staged_changes: Vec<_> = staged.hunks[0]
+			.lines
+			.iter()
+			.filter(|line| line.line_type != DiffLineType::None)
+			.skip(1)
+			.map(|line| line.content.to_string())
+			.collect();
+		assert_eq!(staged_changes, vec!["6", "e", "f"]);
+	}
+
+	#[test]
+	fn test_split_single_block() {
+		let (td, repo) = repo_init().unwrap();
+		let path: &RepoPath = &td.path().to_str().unwrap().into();
+
+		write_commit_file(&repo, "a.txt", "1\n2\n3\n", "c1");
+		repo_write_file(&repo, "a.txt", "1\nb\n3\n").unwrap();
+
+		let diff = get_diff(path, "a.txt", false, None).unwrap();
+		let hunks = split_hunk(&diff.hunks[0]);
+		assert_eq!(hunks.len(), 1);
+		assert_eq!(hunks[0].header_hash, diff.hunks[0].header_hash);
+	}
+}
@@ -3,6 +3,7 @@ use scopetime::scope_time;
 
 use crate::{
 	error::{Error, Result},
+	progress::{Progress, ProgressPhase, ReportProgress},
 	sync::repository::repo,
 };
 
@@ -75,6 +76,8 @@ pub enum RebaseState {
 	Finished,
 	///
 	Conflicted,
+	/// an interactive rebase stopped to edit a commit
+	Edit,
 }
 
 /// rebase
@@ -154,6 +157,16 @@ pub struct RebaseProgress {
 	pub current_commit: Option<CommitId>,
 }
 
+impl ReportProgress for RebaseProgress {
+	fn report(&self) -> Progress {
+		Progress::new(
+			ProgressPhase::Rebasing,
+			self.current,
+			self.steps,
+		)
+	}
+}
+
 ///
 pub fn get_rebase_progress(
 	repo: &git2::Repository,
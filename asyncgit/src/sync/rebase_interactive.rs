@@ -0,0 +1,1033 @@
+//! interactive rebase of the commits on top of a base commit.
+//!
+//! libgit2 has no interactive rebase, so the commits are applied in
+//! memory and the work tree is only touched when the rebase stops
+//! (to edit a commit or on conflicts) or finishes. the state is kept
+//! in `rebase-merge/` the way git keeps it, so `git rebase --abort`
+//! works on it as well
+
+use super::{
+	commit::signature_allow_undefined_name,
+	rebase::{RebaseProgress, RebaseState},
+	repository::repo,
+	stack::{autosquash, squash_message, StackAction},
+	utils::read_file,
+	CommitId, RepoPath,
+};
+use crate::error::{Error, Result};
+use git2::{
+	build::CheckoutBuilder, Commit, Oid, Repository, RepositoryState,
+	ResetType, Sort, StatusOptions, StatusShow,
+};
+use scopetime::scope_time;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+const REBASE_MERGE_DIR: &str = "rebase-merge";
+const TODO_FILE: &str = "git-rebase-todo";
+const DONE_FILE: &str = "done";
+const HEAD_NAME_FILE: &str = "head-name";
+const ORIG_HEAD_FILE: &str = "orig-head";
+const ONTO_FILE: &str = "onto";
+const INTERACTIVE_FILE: &str = "interactive";
+const MSGNUM_FILE: &str = "msgnum";
+const END_FILE: &str = "end";
+const STOPPED_SHA_FILE: &str = "stopped-sha";
+const AMEND_FILE: &str = "amend";
+const CHERRY_PICK_HEAD_FILE: &str = "CHERRY_PICK_HEAD";
+/// new messages of reworded commits, by id
+const REWORD_DIR: &str = "gitui-reword";
+
+/// what to do with a commit of an interactive rebase
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebaseAction {
+	///
+	Pick,
+	/// pick with a new message
+	Reword,
+	/// pick and stop to amend it
+	Edit,
+	/// fold into the commit before, joining both messages
+	Squash,
+	/// fold into the commit before, keeping its message
+	Fixup,
+	/// leave the commit out
+	Drop,
+}
+
+impl RebaseAction {
+	/// the command of a git todo list
+	pub const fn command(self) -> &'static str {
+		match self {
+			Self::Pick => "pick",
+			Self::Reword => "reword",
+			Self::Edit => "edit",
+			Self::Squash => "squash",
+			Self::Fixup => "fixup",
+			Self::Drop => "drop",
+		}
+	}
+
+	fn parse(command: &str) -> Option<Self> {
+		match command {
+			"pick" | "p" => Some(Self::Pick),
+			"reword" | "r" => Some(Self::Reword),
+			"edit" | "e" => Some(Self::Edit),
+			"squash" | "s" => Some(Self::Squash),
+			"fixup" | "f" => Some(Self::Fixup),
+			"drop" | "d" => Some(Self::Drop),
+			_ => None,
+		}
+	}
+
+	const fn folds(self) -> bool {
+		matches!(self, Self::Squash | Self::Fixup)
+	}
+}
+
+impl From<StackAction> for RebaseAction {
+	fn from(action: StackAction) -> Self {
+		match action {
+			StackAction::Pick => Self::Pick,
+			StackAction::Fixup => Self::Fixup,
+			StackAction::Squash => Self::Squash,
+		}
+	}
+}
+
+/// a line of the todo list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RebaseTodo {
+	///
+	pub id: CommitId,
+	///
+	pub action: RebaseAction,
+	/// new message of a `Reword`, without one the old one is kept
+	pub message: Option<String>,
+}
+
+impl RebaseTodo {
+	///
+	pub const fn pick(id: CommitId) -> Self {
+		Self {
+			id,
+			action: RebaseAction::Pick,
+			message: None,
+		}
+	}
+}
+
+/// the commits after `base` up to HEAD (oldest first) all picked, or
+/// arranged by `rebase.autoSquash`
+pub fn rebase_interactive_todo(
+	repo_path: &RepoPath,
+	base: CommitId,
+) -> Result<Vec<RebaseTodo>> {
+	scope_time!("rebase_interactive_todo");
+
+	let repo = repo(repo_path)?;
+	let commits = commits_after(&repo, base.get_oid())?;
+
+	if !repo
+		.config()?
+		.get_bool("rebase.autoSquash")
+		.unwrap_or(false)
+	{
+		return Ok(commits
+			.into_iter()
+			.map(RebaseTodo::pick)
+			.collect());
+	}
+
+	let commits = commits
+		.iter()
+		.map(|id| {
+			let commit = repo.find_commit(id.get_oid())?;
+			let summary = commit.summary().unwrap_or_default();
+			Ok((*id, summary.to_string()))
+		})
+		.collect::<Result<Vec<_>>>()?;
+
+	Ok(autosquash(&commits)
+		.into_iter()
+		.map(|t| RebaseTodo {
+			id: t.id,
+			action: t.action.into(),
+			message: None,
+		})
+		.collect())
+}
+
+/// rebases the commits after `base` as `todo` (oldest first) says,
+/// commits of them left out are dropped
+pub fn rebase_interactive(
+	repo_path: &RepoPath,
+	base: CommitId,
+	todo: &[RebaseTodo],
+) -> Result<RebaseState> {
+	scope_time!("rebase_interactive");
+
+	let repo = repo(repo_path)?;
+
+	if repo.config()?.get_bool("commit.gpgsign").unwrap_or(false) {
+		return Err(Error::SignRebaseInteractive);
+	}
+
+	start(&repo, base.get_oid(), todo, false)
+}
+
+/// starts the rebase of the commits after `base`, the checks of
+/// signing are up to the caller.
+///
+/// with `allow_changes` uncommitted changes are kept as they are, the
+/// rebase cannot stop with them around: conflicts fail it without
+/// changing anything and the files of the branch have to stay the
+/// same
+pub(super) fn start(
+	repo: &Repository,
+	base: Oid,
+	todo: &[RebaseTodo],
+	allow_changes: bool,
+) -> Result<RebaseState> {
+	if repo.state() != RepositoryState::Clean {
+		return Err(Error::Generic(String::from(
+			"another operation is in progress",
+		)));
+	}
+
+	let head = repo.head()?;
+	if !head.is_branch() {
+		return Err(Error::Generic(String::from(
+			"HEAD is not on a branch",
+		)));
+	}
+	let keep_tree = if has_changes(repo, StatusShow::IndexAndWorkdir)?
+	{
+		if !allow_changes {
+			return Err(Error::Generic(String::from(
+				"cannot rebase with uncommitted changes",
+			)));
+		}
+		Some(head.peel_to_tree()?.id())
+	} else {
+		None
+	};
+
+	validate_todo(&commits_after(repo, base)?, todo)?;
+
+	let orig_head = head.peel_to_commit()?.id();
+	let head_name = head.name().unwrap_or_default().to_string();
+
+	let dir = state_dir(repo);
+	fs::create_dir_all(dir.join(REWORD_DIR))?;
+	fs::write(dir.join(INTERACTIVE_FILE), "")?;
+	fs::write(dir.join(HEAD_NAME_FILE), format!("{head_name}\n"))?;
+	fs::write(dir.join(ORIG_HEAD_FILE), format!("{orig_head}\n"))?;
+	fs::write(dir.join(ONTO_FILE), format!("{base}\n"))?;
+	fs::write(dir.join(MSGNUM_FILE), "0\n")?;
+	fs::write(dir.join(END_FILE), format!("{}\n", todo.len()))?;
+	fs::write(dir.join(DONE_FILE), "")?;
+
+	let mut lines = Vec::new();
+	for t in todo {
+		let commit = repo.find_commit(t.id.get_oid())?;
+		lines.push(format!(
+			"{} {} {}\n",
+			t.action.command(),
+			t.id,
+			commit.summary().unwrap_or_default()
+		));
+
+		if let (RebaseAction::Reword, Some(message)) =
+			(t.action, &t.message)
+		{
+			fs::write(
+				dir.join(REWORD_DIR).join(t.id.to_string()),
+				message,
+			)?;
+		}
+	}
+	fs::write(dir.join(TODO_FILE), lines.concat())?;
+
+	repo.reference(
+		"ORIG_HEAD",
+		orig_head,
+		true,
+		"gitui: rebase interactive",
+	)?;
+
+	run(repo, &dir, base, keep_tree).inspect_err(|_| {
+		// nothing happened yet
+		let _ = fs::remove_dir_all(&dir);
+	})
+}
+
+/// whether the pending rebase is an interactive one
+pub fn is_rebase_interactive(repo: &Repository) -> bool {
+	state_dir(repo).join(INTERACTIVE_FILE).exists()
+}
+
+/// commits what got resolved or edited and goes on with the todo
+/// list
+pub fn continue_rebase_interactive(
+	repo: &Repository,
+) -> Result<RebaseState> {
+	if repo.config()?.get_bool("commit.gpgsign").unwrap_or(false) {
+		return Err(Error::SignRebaseInteractive);
+	}
+
+	let dir = state_dir(repo);
+
+	let mut index = repo.index()?;
+	if index.has_conflicts() {
+		return Ok(RebaseState::Conflicted);
+	}
+	if has_changes(repo, StatusShow::Workdir)? {
+		return Err(Error::Generic(String::from(
+			"cannot continue with unstaged changes",
+		)));
+	}
+
+	let head = repo.head()?.peel_to_commit()?;
+	let staged = repo.find_tree(index.write_tree()?)?;
+	let committer = signature_allow_undefined_name(repo)?;
+
+	// without staged changes it got committed already (or nothing is
+	// left of the commit)
+	if staged.id() != head.tree_id() {
+		let current = if dir.join(AMEND_FILE).exists() {
+			head.amend(
+				None,
+				None,
+				Some(&committer),
+				None,
+				None,
+				Some(&staged),
+			)?
+		} else if let Some(stopped) = read_id(&dir, STOPPED_SHA_FILE)?
+		{
+			let commit = repo.find_commit(stopped)?;
+			let action = last_done(repo, &dir)?
+				.filter(|(_, id)| *id == stopped)
+				.map_or(RebaseAction::Pick, |(action, _)| action);
+
+			if action.folds() {
+				fold(repo, &head, &commit, staged.id(), action)?
+			} else {
+				repo.commit(
+					None,
+					&commit.author(),
+					&committer,
+					&message(&dir, &commit, action)?,
+					&staged,
+					&[&head],
+				)?
+			}
+		} else {
+			return Err(Error::Generic(String::from(
+				"nothing to continue with staged changes",
+			)));
+		};
+
+		repo.set_head_detached(current)?;
+	}
+
+	remove_file(&dir.join(AMEND_FILE))?;
+	remove_file(&dir.join(STOPPED_SHA_FILE))?;
+
+	run(repo, &dir, repo.head()?.peel_to_commit()?.id(), None)
+}
+
+/// goes back to where the rebase started, discarding all uncommitted
+/// changes
+pub fn abort_rebase_interactive(repo: &Repository) -> Result<()> {
+	let dir = state_dir(repo);
+
+	let head_name = read_file(&dir.join(HEAD_NAME_FILE))?;
+	let orig_head =
+		read_id(&dir, ORIG_HEAD_FILE)?.ok_or_else(|| {
+			Error::Generic(format!(
+				"{REBASE_MERGE_DIR} has no orig-head"
+			))
+		})?;
+
+	repo.set_head(head_name.trim())?;
+	repo.reset(
+		&repo.find_object(orig_head, None)?,
+		ResetType::Hard,
+		None,
+	)?;
+
+	// a hard reset cleans up the state on its own
+	if dir.exists() {
+		fs::remove_dir_all(dir)?;
+	}
+
+	Ok(())
+}
+
+///
+pub fn get_rebase_interactive_progress(
+	repo: &Repository,
+) -> Result<RebaseProgress> {
+	let dir = state_dir(repo);
+
+	let number = |file: &str| -> Result<usize> {
+		read_file(&dir.join(file))?.trim().parse().map_err(|_| {
+			Error::Generic(format!(
+				"invalid {REBASE_MERGE_DIR}/{file}"
+			))
+		})
+	};
+
+	Ok(RebaseProgress {
+		steps: number(END_FILE)?,
+		current: number(MSGNUM_FILE)?.saturating_sub(1),
+		current_commit: last_done(repo, &dir)?
+			.map(|(_, id)| id.into()),
+	})
+}
+
+fn state_dir(repo: &Repository) -> PathBuf {
+	repo.path().join(REBASE_MERGE_DIR)
+}
+
+/// the first parent line after `base` up to HEAD, oldest first
+fn commits_after(
+	repo: &Repository,
+	base: Oid,
+) -> Result<Vec<CommitId>> {
+	let head = repo.head()?.peel_to_commit()?.id();
+	if head == base || !repo.graph_descendant_of(head, base)? {
+		return Err(Error::Generic(String::from(
+			"the base is not an ancestor of HEAD",
+		)));
+	}
+
+	let mut walk = repo.revwalk()?;
+	walk.push(head)?;
+	walk.hide(base)?;
+	walk.simplify_first_parent()?;
+	walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+
+	let mut commits = Vec::new();
+	for id in walk {
+		let id = id?;
+		if repo.find_commit(id)?.parent_count() > 1 {
+			return Err(Error::Generic(String::from(
+				"cannot rebase merge commits",
+			)));
+		}
+		commits.push(id.into());
+	}
+
+	Ok(commits)
+}
+
+fn validate_todo(
+	commits: &[CommitId],
+	todo: &[RebaseTodo],
+) -> Result<()> {
+	for (idx, t) in todo.iter().enumerate() {
+		if !commits.contains(&t.id) {
+			return Err(Error::Generic(format!(
+				"{} is not one of the rebased commits",
+				t.id.get_short_string()
+			)));
+		}
+		if todo[..idx].iter().any(|other| other.id == t.id) {
+			return Err(Error::Generic(format!(
+				"{} is in the todo list twice",
+				t.id.get_short_string()
+			)));
+		}
+	}
+
+	if todo
+		.iter()
+		.find(|t| t.action != RebaseAction::Drop)
+		.is_some_and(|t| t.action.folds())
+	{
+		return Err(Error::Generic(String::from(
+			"the first commit has nothing to be folded into",
+		)));
+	}
+
+	Ok(())
+}
+
+/// changes to tracked files
+fn has_changes(repo: &Repository, show: StatusShow) -> Result<bool> {
+	let mut options = StatusOptions::new();
+	options
+		.show(show)
+		.include_untracked(false)
+		.include_ignored(false);
+
+	Ok(!repo.statuses(Some(&mut options))?.is_empty())
+}
+
+/// works off the todo list on top of `current`. HEAD only moves when
+/// the rebase stops or finishes, so on errors the list is put back
+/// and nothing got applied. with `keep_tree` it never stops and has
+/// to end up with that tree
+fn run(
+	repo: &Repository,
+	dir: &Path,
+	current: Oid,
+	keep_tree: Option<Oid>,
+) -> Result<RebaseState> {
+	let files = [TODO_FILE, DONE_FILE, MSGNUM_FILE];
+	let saved = files
+		.iter()
+		.map(|file| read_file(&dir.join(file)))
+		.collect::<Result<Vec<_>>>()?;
+
+	run_todo(repo, dir, current, keep_tree).inspect_err(|_| {
+		for (file, content) in files.iter().zip(&saved) {
+			let _ = fs::write(dir.join(file), content);
+		}
+	})
+}
+
+fn run_todo(
+	repo: &Repository,
+	dir: &Path,
+	mut current: Oid,
+	keep_tree: Option<Oid>,
+) -> Result<RebaseState> {
+	let committer = signature_allow_undefined_name(repo)?;
+
+	while let Some((action, id)) = next_todo(repo, dir)? {
+		if action == RebaseAction::Drop {
+			continue;
+		}
+
+		let commit = repo.find_commit(id)?;
+
+		// untouched commits keep their ids
+		if matches!(action, RebaseAction::Pick | RebaseAction::Edit)
+			&& commit.parent_id(0).ok() == Some(current)
+		{
+			current = id;
+		} else {
+			let onto = repo.find_commit(current)?;
+			let mut index =
+				repo.cherrypick_commit(&commit, &onto, 0, None)?;
+
+			if index.has_conflicts() {
+				if keep_tree.is_some() {
+					return Err(Error::RebaseConflict);
+				}
+
+				stop_conflicted(repo, dir, current, &commit)?;
+
+				return Ok(RebaseState::Conflicted);
+			}
+
+			let tree = index.write_tree_to(repo)?;
+
+			current = if action.folds() {
+				fold(repo, &onto, &commit, tree, action)?
+			} else {
+				repo.commit(
+					None,
+					&commit.author(),
+					&committer,
+					&message(dir, &commit, action)?,
+					&repo.find_tree(tree)?,
+					&[&onto],
+				)?
+			};
+		}
+
+		if action == RebaseAction::Edit {
+			detach_at(repo, current)?;
+			fs::write(dir.join(AMEND_FILE), format!("{current}\n"))?;
+			fs::write(dir.join(STOPPED_SHA_FILE), format!("{id}\n"))?;
+
+			return Ok(RebaseState::Edit);
+		}
+	}
+
+	if keep_tree.is_some_and(|tree| {
+		repo.find_commit(current)
+			.is_ok_and(|commit| commit.tree_id() != tree)
+	}) {
+		return Err(Error::Generic(String::from(
+			"the rebase would change the files under the uncommitted changes",
+		)));
+	}
+
+	finish(repo, dir, current)
+}
+
+/// takes the first command off the todo list and adds it to the done
+/// ones
+fn next_todo(
+	repo: &Repository,
+	dir: &Path,
+) -> Result<Option<(RebaseAction, Oid)>> {
+	let todo = read_file(&dir.join(TODO_FILE))?;
+
+	let mut lines = todo.lines();
+	let Some(line) = lines.by_ref().find(|line| {
+		let line = line.trim();
+		!line.is_empty() && !line.starts_with('#')
+	}) else {
+		return Ok(None);
+	};
+
+	let parsed = parse_line(repo, line)?;
+
+	let rest: String = lines.flat_map(|line| [line, "\n"]).collect();
+	let mut done = read_file(&dir.join(DONE_FILE))?;
+	done.push_str(line);
+	done.push('\n');
+	let msgnum = read_file(&dir.join(MSGNUM_FILE))?
+		.trim()
+		.parse::<usize>()
+		.unwrap_or_default();
+
+	fs::write(dir.join(TODO_FILE), rest)?;
+	fs::write(dir.join(DONE_FILE), done)?;
+	fs::write(dir.join(MSGNUM_FILE), format!("{}\n", msgnum + 1))?;
+
+	Ok(Some(parsed))
+}
+
+/// the last command taken off the todo list
+fn last_done(
+	repo: &Repository,
+	dir: &Path,
+) -> Result<Option<(RebaseAction, Oid)>> {
+	let Ok(done) = read_file(&dir.join(DONE_FILE)) else {
+		return Ok(None);
+	};
+
+	done.lines()
+		.rev()
+		.find(|line| !line.trim().is_empty())
+		.map(|line| parse_line(repo, line))
+		.transpose()
+}
+
+fn parse_line(
+	repo: &Repository,
+	line: &str,
+) -> Result<(RebaseAction, Oid)> {
+	let mut parts = line.split_whitespace();
+
+	let action = parts.next().and_then(RebaseAction::parse);
+	let commit = parts
+		.next()
+		.and_then(|id| repo.revparse_single(id).ok())
+		.and_then(|object| object.peel_to_commit().ok());
+
+	match (action, commit) {
+		(Some(action), Some(commit)) => Ok((action, commit.id())),
+		_ => Err(Error::Generic(format!(
+			"unsupported rebase command: {line}"
+		))),
+	}
+}
+
+fn read_id(dir: &Path, file: &str) -> Result<Option<Oid>> {
+	let path = dir.join(file);
+	if !path.exists() {
+		return Ok(None);
+	}
+
+	Ok(Some(Oid::from_str(read_file(&path)?.trim())?))
+}
+
+fn remove_file(path: &Path) -> Result<()> {
+	if path.exists() {
+		fs::remove_file(path)?;
+	}
+
+	Ok(())
+}
+
+/// message of the picked `commit`, a new one if it gets reworded
+fn message(
+	dir: &Path,
+	commit: &Commit,
+	action: RebaseAction,
+) -> Result<String> {
+	let reworded = dir.join(REWORD_DIR).join(commit.id().to_string());
+	if action == RebaseAction::Reword && reworded.exists() {
+		return read_file(&reworded);
+	}
+
+	Ok(String::from_utf8_lossy(commit.message_raw_bytes())
+		.to_string())
+}
+
+/// replaces `onto` by a commit with `tree`, adding the message of
+/// `commit` for a squash
+fn fold(
+	repo: &Repository,
+	onto: &Commit,
+	commit: &Commit,
+	tree: Oid,
+	action: RebaseAction,
+) -> Result<Oid> {
+	let committer = signature_allow_undefined_name(repo)?;
+	let parent = onto.parent(0)?;
+
+	let mut message =
+		String::from_utf8_lossy(onto.message_raw_bytes()).to_string();
+	if action == RebaseAction::Squash {
+		message = squash_message(
+			&message,
+			&String::from_utf8_lossy(commit.message_raw_bytes()),
+		);
+	}
+
+	Ok(repo.commit(
+		None,
+		&onto.author(),
+		&committer,
+		&message,
+		&repo.find_tree(tree)?,
+		&[&parent],
+	)?)
+}
+
+/// checks out `id` with a detached HEAD
+fn detach_at(repo: &Repository, id: Oid) -> Result<()> {
+	let commit = repo.find_commit(id)?;
+
+	repo.checkout_tree(
+		commit.as_object(),
+		Some(CheckoutBuilder::new().safe()),
+	)?;
+	repo.set_head_detached(id)?;
+
+	Ok(())
+}
+
+/// leaves the conflicts of picking `commit` on `current` in the
+/// index and work tree
+fn stop_conflicted(
+	repo: &Repository,
+	dir: &Path,
+	current: Oid,
+	commit: &Commit,
+) -> Result<()> {
+	detach_at(repo, current)?;
+	repo.cherrypick(commit, None)?;
+
+	// the rebase is what is in progress, not a cherry-pick
+	remove_file(&repo.path().join(CHERRY_PICK_HEAD_FILE))?;
+
+	fs::write(
+		dir.join(STOPPED_SHA_FILE),
+		format!("{}\n", commit.id()),
+	)?;
+
+	Ok(())
+}
+
+/// moves the branch to `current` and checks it out
+fn finish(
+	repo: &Repository,
+	dir: &Path,
+	current: Oid,
+) -> Result<RebaseState> {
+	let head_name = read_file(&dir.join(HEAD_NAME_FILE))?;
+	let head_name = head_name.trim();
+
+	repo.checkout_tree(
+		repo.find_commit(current)?.as_object(),
+		Some(CheckoutBuilder::new().safe()),
+	)?;
+	repo.reference(
+		head_name,
+		current,
+		true,
+		"gitui: rebase interactive finished",
+	)?;
+	repo.set_head(head_name)?;
+
+	fs::remove_dir_all(dir)?;
+
+	Ok(RebaseState::Finished)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{
+		abort_rebase_interactive, continue_rebase_interactive,
+		get_rebase_interactive_progress, rebase_interactive,
+		rebase_interactive_todo, RebaseAction, RebaseTodo,
+	};
+	use crate::sync::{
+		rebase::RebaseState,
+		repo_state, stage_add_file,
+		tests::{repo_init, write_commit_file},
+		utils::{repo_read_file, repo_write_file},
+		CommitId, RepoPath, RepoState,
+	};
+	use git2::Repository;
+	use std::path::Path;
+
+	fn todo(items: &[(CommitId, RebaseAction)]) -> Vec<RebaseTodo> {
+		items
+			.iter()
+			.map(|(id, action)| RebaseTodo {
+				id: *id,
+				action: *action,
+				message: None,
+			})
+			.collect()
+	}
+
+	fn summaries(repo: &Repository) -> Vec<String> {
+		let mut walk = repo.revwalk().unwrap();
+		walk.push_head().unwrap();
+		walk.map(|id| {
+			repo.find_commit(id.unwrap())
+				.unwrap()
+				.summary()
+				.unwrap()
+				.to_string()
+		})
+		.collect()
+	}
+
+	#[test]
+	fn test_reorder_squash_drop() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let base =
+			write_commit_file(&repo, "base.txt", "base", "base");
+		let a = write_commit_file(&repo, "a.txt", "a", "add a");
+		let b = write_commit_file(&repo, "b.txt", "b", "add b");
+		let c = write_commit_file(&repo, "c.txt", "c", "add c");
+		let d = write_commit_file(&repo, "d.txt", "d", "add d");
+
+		assert_eq!(
+			rebase_interactive_todo(repo_path, base).unwrap(),
+			vec![
+				RebaseTodo::pick(a),
+				RebaseTodo::pick(b),
+				RebaseTodo::pick(c),
+				RebaseTodo::pick(d),
+			]
+		);
+
+		let mut items = todo(&[
+			(c, RebaseAction::Pick),
+			(a, RebaseAction::Fixup),
+			(b, RebaseAction::Drop),
+			(d, RebaseAction::Reword),
+		]);
+		items[3].message = Some(String::from("reworded d"));
+
+		let res =
+			rebase_interactive(repo_path, base, &items).unwrap();
+
+		assert_eq!(res, RebaseState::Finished);
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+		assert_eq!(
+			summaries(&repo)[..3],
+			["reworded d", "add c", "base"]
+		);
+		assert!(root.join("a.txt").exists());
+		assert!(!root.join("b.txt").exists());
+		assert!(repo.head().unwrap().is_branch());
+	}
+
+	#[test]
+	fn test_edit_continue() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let base =
+			write_commit_file(&repo, "base.txt", "base", "base");
+		let a = write_commit_file(&repo, "a.txt", "a", "add a");
+		let b = write_commit_file(&repo, "b.txt", "b", "add b");
+
+		let res = rebase_interactive(
+			repo_path,
+			base,
+			&todo(&[
+				(a, RebaseAction::Edit),
+				(b, RebaseAction::Pick),
+			]),
+		)
+		.unwrap();
+
+		assert_eq!(res, RebaseState::Edit);
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Rebase);
+
+		let repo = crate::sync::repository::repo(repo_path).unwrap();
+		assert_eq!(
+			get_rebase_interactive_progress(&repo).unwrap().current,
+			0
+		);
+		assert!(!root.join("b.txt").exists());
+
+		repo_write_file(&repo, "a.txt", "edited").unwrap();
+		assert!(continue_rebase_interactive(&repo).is_err());
+
+		stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+		let repo = crate::sync::repository::repo(repo_path).unwrap();
+
+		let res = continue_rebase_interactive(&repo).unwrap();
+
+		assert_eq!(res, RebaseState::Finished);
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+		assert_eq!(summaries(&repo)[..3], ["add b", "add a", "base"]);
+		assert_eq!(repo_read_file(&repo, "a.txt").unwrap(), "edited");
+		assert!(root.join("b.txt").exists());
+	}
+
+	#[test]
+	fn test_conflict_continue() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let base = write_commit_file(&repo, "f.txt", "1\n", "base");
+		let a = write_commit_file(&repo, "f.txt", "2\n", "two");
+		let b = write_commit_file(&repo, "f.txt", "3\n", "three");
+
+		// `b` does not apply without `a`
+		let res = rebase_interactive(
+			repo_path,
+			base,
+			&todo(&[
+				(b, RebaseAction::Pick),
+				(a, RebaseAction::Drop),
+			]),
+		)
+		.unwrap();
+
+		assert_eq!(res, RebaseState::Conflicted);
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Rebase);
+
+		// a fresh handle, the one above caches the index
+		let repo = crate::sync::repository::repo(repo_path).unwrap();
+		assert!(repo.index().unwrap().has_conflicts());
+		assert!(repo_read_file(&repo, "f.txt")
+			.unwrap()
+			.contains("<<<<<<<"));
+
+		assert_eq!(
+			continue_rebase_interactive(&repo).unwrap(),
+			RebaseState::Conflicted
+		);
+
+		repo_write_file(&repo, "f.txt", "3\n").unwrap();
+		stage_add_file(repo_path, Path::new("f.txt")).unwrap();
+
+		let repo = crate::sync::repository::repo(repo_path).unwrap();
+		let res = continue_rebase_interactive(&repo).unwrap();
+
+		assert_eq!(res, RebaseState::Finished);
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+		assert_eq!(summaries(&repo)[..2], ["three", "base"]);
+	}
+
+	#[test]
+	fn test_conflict_abort() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let base = write_commit_file(&repo, "f.txt", "1\n", "base");
+		let a = write_commit_file(&repo, "f.txt", "2\n", "two");
+		let b = write_commit_file(&repo, "f.txt", "3\n", "three");
+
+		let res = rebase_interactive(
+			repo_path,
+			base,
+			&todo(&[
+				(b, RebaseAction::Pick),
+				(a, RebaseAction::Pick),
+			]),
+		)
+		.unwrap();
+		assert_eq!(res, RebaseState::Conflicted);
+
+		let repo = crate::sync::repository::repo(repo_path).unwrap();
+		abort_rebase_interactive(&repo).unwrap();
+
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+		assert_eq!(
+			repo.head().unwrap().peel_to_commit().unwrap().id(),
+			b.get_oid()
+		);
+		assert!(repo.head().unwrap().is_branch());
+		assert_eq!(repo_read_file(&repo, "f.txt").unwrap(), "3\n");
+	}
+
+	#[test]
+	fn test_failed_step_keeps_todo() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let base =
+			write_commit_file(&repo, "base.txt", "base", "base");
+		let a = write_commit_file(&repo, "a.txt", "a", "add a");
+		let b = write_commit_file(&repo, "b.txt", "b", "add b");
+
+		let res = rebase_interactive(
+			repo_path,
+			base,
+			&todo(&[
+				(a, RebaseAction::Edit),
+				(b, RebaseAction::Pick),
+			]),
+		)
+		.unwrap();
+		assert_eq!(res, RebaseState::Edit);
+
+		// checking out `b` would overwrite it
+		repo_write_file(&repo, "b.txt", "untracked").unwrap();
+
+		let repo = crate::sync::repository::repo(repo_path).unwrap();
+		assert!(continue_rebase_interactive(&repo).is_err());
+
+		std::fs::remove_file(root.join("b.txt")).unwrap();
+
+		let res = continue_rebase_interactive(&repo).unwrap();
+
+		assert_eq!(res, RebaseState::Finished);
+		assert_eq!(summaries(&repo)[..3], ["add b", "add a", "base"]);
+	}
+
+	#[test]
+	fn test_first_cannot_fold() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let base =
+			write_commit_file(&repo, "base.txt", "base", "base");
+		let a = write_commit_file(&repo, "a.txt", "a", "add a");
+
+		assert!(rebase_interactive(
+			repo_path,
+			base,
+			&todo(&[(a, RebaseAction::Squash)]),
+		)
+		.is_err());
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+	}
+}
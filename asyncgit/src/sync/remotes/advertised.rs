@@ -0,0 +1,200 @@
+//! the refs remotes advertise, kept for a short while since listing
+//! them costs a connection every time
+
+use super::{
+	proxy_auto,
+	retry::{retry_network, RetryConfig},
+	Callbacks,
+};
+use crate::{error::Result, sync::cred::BasicAuthCredential};
+use git2::{Direction, Oid, Repository};
+use std::{
+	collections::HashMap,
+	path::PathBuf,
+	sync::{LazyLock, Mutex},
+	time::{Duration, Instant},
+};
+
+/// how long listed refs are reused
+const ADVERTISED_REFS_TTL: Duration = Duration::from_secs(30);
+
+struct AdvertisedRefs {
+	listed: Instant,
+	refs: HashMap<String, Oid>,
+}
+
+/// by repository and remote url
+static ADVERTISED: LazyLock<
+	Mutex<HashMap<(PathBuf, String), AdvertisedRefs>>,
+> = LazyLock::new(Mutex::default);
+
+fn cache_key(repo: &Repository, url: &str) -> (PathBuf, String) {
+	(repo.path().to_path_buf(), url.to_string())
+}
+
+/// the refs advertised at `url` (through `remote` if it has a name),
+/// only listed again once the cached ones expired
+pub fn advertised_refs(
+	repo: &Repository,
+	remote: Option<&str>,
+	url: &str,
+	direction: Direction,
+	basic_credential: Option<&BasicAuthCredential>,
+) -> Result<HashMap<String, Oid>> {
+	if let Some(refs) = cached_advertised_refs(repo, url) {
+		log::debug!("advertised refs of '{url}' from cache");
+		return Ok(refs);
+	}
+
+	let mut remote_handle = if let Some(name) = remote {
+		repo.find_remote(name)?
+	} else {
+		repo.remote_anonymous(url)?
+	};
+
+	let refs = retry_network(
+		RetryConfig::from_repo(repo),
+		|_| (),
+		|| {
+			let callbacks =
+				Callbacks::new(None, basic_credential.cloned());
+			let conn = callbacks.check_result(
+				repo,
+				remote_handle.connect_auth(
+					direction,
+					Some(callbacks.callbacks()),
+					Some(proxy_auto()),
+				),
+			)?;
+
+			Ok(conn
+				.list()?
+				.iter()
+				.map(|head| (head.name().to_string(), head.oid()))
+				.collect::<HashMap<_, _>>())
+		},
+	)?;
+
+	if let Ok(mut cache) = ADVERTISED.lock() {
+		cache.insert(
+			cache_key(repo, url),
+			AdvertisedRefs {
+				listed: Instant::now(),
+				refs: refs.clone(),
+			},
+		);
+	}
+
+	Ok(refs)
+}
+
+/// the refs advertised at `url` if they were listed recently, without
+/// connecting to the remote
+pub fn cached_advertised_refs(
+	repo: &Repository,
+	url: &str,
+) -> Option<HashMap<String, Oid>> {
+	let cache = ADVERTISED.lock().ok()?;
+
+	cache
+		.get(&cache_key(repo, url))
+		.filter(|cached| {
+			cached.listed.elapsed() < ADVERTISED_REFS_TTL
+		})
+		.map(|cached| cached.refs.clone())
+}
+
+/// where `remote_ref` is at `url` if that was listed recently
+pub fn cached_advertised_ref(
+	repo: &Repository,
+	url: &str,
+	remote_ref: &str,
+) -> Option<Oid> {
+	let cache = ADVERTISED.lock().ok()?;
+
+	cache
+		.get(&cache_key(repo, url))
+		.filter(|cached| {
+			cached.listed.elapsed() < ADVERTISED_REFS_TTL
+		})
+		.and_then(|cached| cached.refs.get(remote_ref).copied())
+}
+
+/// keeps the cached refs of `url` in line with a push that went
+/// through, `oid` is `None` for a deleted ref
+pub fn update_advertised_ref(
+	repo: &Repository,
+	url: &str,
+	remote_ref: &str,
+	oid: Option<Oid>,
+) {
+	let Ok(mut cache) = ADVERTISED.lock() else {
+		return;
+	};
+
+	if let Some(cached) = cache.get_mut(&cache_key(repo, url)) {
+		if let Some(oid) = oid {
+			cached.refs.insert(remote_ref.to_string(), oid);
+		} else {
+			cached.refs.remove(remote_ref);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::tests::{
+		repo_clone, repo_init, write_commit_file,
+	};
+
+	#[test]
+	fn test_advertised_refs_cached() {
+		let (upstream_dir, upstream) = repo_init().unwrap();
+		let commit = write_commit_file(&upstream, "a", "a", "a");
+
+		let url = upstream_dir.path().to_str().unwrap();
+		let (_td, repo) = repo_clone(url).unwrap();
+
+		assert!(cached_advertised_refs(&repo, url).is_none());
+
+		let refs = advertised_refs(
+			&repo,
+			Some("origin"),
+			url,
+			Direction::Fetch,
+			None,
+		)
+		.unwrap();
+		assert_eq!(
+			refs.get("refs/heads/master"),
+			Some(&commit.get_oid())
+		);
+
+		// the remote moves on, the cache does not notice
+		upstream
+			.reference("refs/heads/other", commit.get_oid(), true, "")
+			.unwrap();
+		let refs = advertised_refs(
+			&repo,
+			Some("origin"),
+			url,
+			Direction::Fetch,
+			None,
+		)
+		.unwrap();
+		assert!(!refs.contains_key("refs/heads/other"));
+
+		// but knows about our own pushes
+		update_advertised_ref(
+			&repo,
+			url,
+			"refs/heads/pushed",
+			Some(commit.get_oid()),
+		);
+		update_advertised_ref(&repo, url, "refs/heads/master", None);
+		let refs = cached_advertised_refs(&repo, url).unwrap();
+		assert!(refs.contains_key("refs/heads/pushed"));
+		assert!(!refs.contains_key("refs/heads/master"));
+	}
+}
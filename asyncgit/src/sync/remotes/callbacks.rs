@@ -1,7 +1,14 @@
 use super::push::ProgressNotification;
-use crate::{error::Result, sync::cred::BasicAuthCredential};
+use crate::{
+	asyncjob::CancellationToken,
+	error::{Error, Result},
+	sync::cred::{remember_username, BasicAuthCredential},
+};
 use crossbeam_channel::Sender;
-use git2::{Cred, Error as GitError, RemoteCallbacks};
+use git2::{
+	Cred, Error as GitError, ErrorClass, ErrorCode, RemoteCallbacks,
+	Repository,
+};
 use std::sync::{
 	atomic::{AtomicBool, Ordering},
 	Arc, Mutex,
@@ -11,6 +18,10 @@ use std::sync::{
 #[derive(Default, Clone)]
 pub struct CallbackStats {
 	pub push_rejected_msg: Option<(String, String)>,
+	/// the url and username a password was handed out for
+	pub used_username: Option<(String, String)>,
+	/// no credentials or the ones given were rejected
+	pub credentials_rejected: bool,
 }
 
 ///
@@ -20,6 +31,7 @@ pub struct Callbacks {
 	basic_credential: Option<BasicAuthCredential>,
 	stats: Arc<Mutex<CallbackStats>>,
 	first_call_to_credentials: Arc<AtomicBool>,
+	cancellation: Option<CancellationToken>,
 }
 
 impl Callbacks {
@@ -37,15 +49,76 @@ impl Callbacks {
 			first_call_to_credentials: Arc::new(AtomicBool::new(
 				true,
 			)),
+			cancellation: None,
 		}
 	}
 
+	/// aborts the transfer once `cancellation` is cancelled
+	#[must_use]
+	pub fn with_cancellation(
+		mut self,
+		cancellation: Option<&CancellationToken>,
+	) -> Self {
+		self.cancellation = cancellation.cloned();
+		self
+	}
+
+	///
+	pub fn is_cancelled(&self) -> bool {
+		self.cancellation
+			.as_ref()
+			.is_some_and(CancellationToken::is_cancelled)
+	}
+
 	///
 	pub fn get_stats(&self) -> Result<CallbackStats> {
 		let stats = self.stats.lock()?;
 		Ok(stats.clone())
 	}
 
+	/// tells authentication and network failures of a remote
+	/// operation apart. on success the username that worked is
+	/// remembered for the url
+	pub fn check_result<T>(
+		&self,
+		repo: &Repository,
+		res: std::result::Result<T, GitError>,
+	) -> Result<T> {
+		let stats = self.get_stats()?;
+
+		match res {
+			Err(_) if self.is_cancelled() => Err(Error::Cancelled),
+			Ok(value) => {
+				if let Some((url, username)) = stats.used_username {
+					if let Err(e) =
+						remember_username(repo, &url, &username)
+					{
+						log::error!("remember username failed: {e}");
+					}
+				}
+
+				Ok(value)
+			}
+			Err(e)
+				if stats.credentials_rejected
+					|| e.code() == ErrorCode::Auth =>
+			{
+				Err(Error::Authentication(e.message().to_string()))
+			}
+			Err(e)
+				if matches!(
+					e.class(),
+					ErrorClass::Net
+						| ErrorClass::Http | ErrorClass::Ssh
+						| ErrorClass::Ssl | ErrorClass::Os
+				) =>
+			{
+				Err(Error::Network(e.message().to_string()))
+			}
+			Err(e) => Err(e.into()),
+		}
+	}
+
 	///
 	pub fn callbacks<'a>(&self) -> RemoteCallbacks<'a> {
 		let mut callbacks = RemoteCallbacks::new();
@@ -66,7 +139,7 @@ impl Callbacks {
 		let this = self.clone();
 		callbacks.transfer_progress(move |p| {
 			this.transfer_progress(&p);
-			true
+			!this.is_cancelled()
 		});
 
 		let this = self.clone();
@@ -91,12 +164,22 @@ impl Callbacks {
 			},
 		);
 
+		let this = self.clone();
 		callbacks.sideband_progress(move |data| {
 			log::debug!(
 				"sideband transfer: '{}'",
 				String::from_utf8_lossy(data).trim()
 			);
-			true
+			!this.is_cancelled()
+		});
+
+		let this = self.clone();
+		callbacks.push_negotiation(move |_| {
+			if this.is_cancelled() {
+				Err(GitError::from_str("cancelled"))
+			} else {
+				Ok(())
+			}
 		});
 
 		callbacks
@@ -107,7 +190,7 @@ impl Callbacks {
 		reference: &str,
 		msg: Option<&str>,
 	) {
-		log::debug!("push_update_reference: '{reference}' {msg:?}",);
+		log::debug!("push_update_reference: '{reference}' {msg:?}");
 
 		if let Ok(mut stats) = self.stats.lock() {
 			stats.push_rejected_msg = msg
@@ -141,6 +224,7 @@ impl Callbacks {
 			sender.send(ProgressNotification::Transfer {
 				objects: p.received_objects(),
 				total_objects: p.total_objects(),
+				bytes: p.received_bytes(),
 			})
 		});
 	}
@@ -162,7 +246,7 @@ impl Callbacks {
 		total: usize,
 		bytes: usize,
 	) {
-		log::debug!("progress: {current}/{total} ({bytes} B)",);
+		log::debug!("progress: {current}/{total} ({bytes} B)");
 		self.sender.clone().map(|sender| {
 			sender.send(ProgressNotification::PushTransfer {
 				current,
@@ -191,10 +275,11 @@ impl Callbacks {
 			self.first_call_to_credentials
 				.store(false, Ordering::Relaxed);
 		} else {
+			self.set_credentials_rejected();
 			return Err(GitError::from_str("Bad credentials."));
 		}
 
-		match &self.basic_credential {
+		let cred = match &self.basic_credential {
 			_ if allowed_types.is_ssh_key() => username_from_url
 				.map_or_else(
 					|| {
@@ -208,6 +293,10 @@ impl Callbacks {
 				username: Some(user),
 				password: Some(pwd),
 			}) if allowed_types.is_user_pass_plaintext() => {
+				if let Ok(mut stats) = self.stats.lock() {
+					stats.used_username =
+						Some((url.to_string(), user.clone()));
+				}
 				Cred::userpass_plaintext(user, pwd)
 			}
 			Some(BasicAuthCredential {
@@ -216,6 +305,84 @@ impl Callbacks {
 			}) if allowed_types.is_username() => Cred::username(user),
 			_ if allowed_types.is_default() => Cred::default(),
 			_ => Err(GitError::from_str("Couldn't find credentials")),
+		};
+
+		if cred.is_err() {
+			self.set_credentials_rejected();
+		}
+
+		cred
+	}
+
+	fn set_credentials_rejected(&self) {
+		if let Ok(mut stats) = self.stats.lock() {
+			stats.credentials_rejected = true;
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::tests::repo_init;
+
+	#[test]
+	fn test_check_result_classifies_errors() {
+		let (_td, repo) = repo_init().unwrap();
+		let callbacks = Callbacks::new(None, None);
+
+		let auth: std::result::Result<(), _> = Err(GitError::new(
+			ErrorCode::Auth,
+			ErrorClass::Http,
+			"unexpected http status code: 401",
+		));
+		assert!(matches!(
+			callbacks.check_result(&repo, auth),
+			Err(Error::Authentication(_))
+		));
+
+		let net: std::result::Result<(), _> = Err(GitError::new(
+			ErrorCode::GenericError,
+			ErrorClass::Net,
+			"failed to resolve address",
+		));
+		assert!(matches!(
+			callbacks.check_result(&repo, net),
+			Err(Error::Network(_))
+		));
+
+		callbacks.set_credentials_rejected();
+		let rejected: std::result::Result<(), _> =
+			Err(GitError::new(
+				ErrorCode::GenericError,
+				ErrorClass::Callback,
+				"Bad credentials.",
+			));
+		assert!(matches!(
+			callbacks.check_result(&repo, rejected),
+			Err(Error::Authentication(_))
+		));
+	}
+
+	#[test]
+	fn test_check_result_cancelled() {
+		let (_td, repo) = repo_init().unwrap();
+		let cancellation = CancellationToken::default();
+		let callbacks = Callbacks::new(None, None)
+			.with_cancellation(Some(&cancellation));
+
+		assert!(!callbacks.is_cancelled());
+		cancellation.cancel();
+		assert!(callbacks.is_cancelled());
+
+		let aborted: std::result::Result<(), _> = Err(GitError::new(
+			ErrorCode::User,
+			ErrorClass::Callback,
+			"transfer cancelled",
+		));
+		assert!(matches!(
+			callbacks.check_result(&repo, aborted),
+			Err(Error::Cancelled)
+		));
+	}
+}
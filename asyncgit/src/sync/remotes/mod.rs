@@ -1,21 +1,25 @@
 //!
 
+pub(crate) mod advertised;
 mod callbacks;
 pub(crate) mod push;
+mod retry;
 pub(crate) mod tags;
 
 use crate::{
+	asyncjob::CancellationToken,
 	error::{Error, Result},
 	sync::{
 		cred::BasicAuthCredential,
 		remotes::push::ProgressNotification, repository::repo, utils,
 	},
-	ProgressPercent,
+	Progress, ProgressPhase,
 };
 use crossbeam_channel::Sender;
 use git2::{
 	BranchType, FetchOptions, ProxyOptions, Remote, Repository,
 };
+use retry::{retry_network, RetryConfig};
 use scopetime::scope_time;
 use utils::bytes2string;
 
@@ -27,6 +31,10 @@ use super::RepoPath;
 /// origin
 pub const DEFAULT_REMOTE_NAME: &str = "origin";
 
+/// fetch depth that makes libgit2 fetch the history a shallow clone
+/// is missing (`GIT_FETCH_DEPTH_UNSHALLOW`)
+const UNSHALLOW_DEPTH: i32 = i32::MAX;
+
 ///
 pub fn proxy_auto<'a>() -> ProxyOptions<'a> {
 	let mut proxy = ProxyOptions::new();
@@ -266,29 +274,46 @@ pub(crate) fn get_default_remote_in_repo(
 	Err(Error::NoDefaultRemoteFound)
 }
 
+/// whether the repo is a shallow clone missing older history
+pub fn is_shallow(repo_path: &RepoPath) -> Result<bool> {
+	Ok(repo(repo_path)?.is_shallow())
+}
+
 ///
 fn fetch_from_remote(
 	repo_path: &RepoPath,
 	remote: &str,
 	basic_credential: Option<BasicAuthCredential>,
 	progress_sender: Option<Sender<ProgressNotification>>,
+	unshallow: bool,
+	cancellation: Option<&CancellationToken>,
 ) -> Result<()> {
 	let repo = repo(repo_path)?;
 
 	let mut remote = repo.find_remote(remote)?;
 
 	let mut options = FetchOptions::new();
-	let callbacks = Callbacks::new(progress_sender, basic_credential);
+	let callbacks = Callbacks::new(progress_sender, basic_credential)
+		.with_cancellation(cancellation);
 	options.prune(git2::FetchPrune::On);
+	if unshallow {
+		options.depth(UNSHALLOW_DEPTH);
+	}
 	options.proxy_options(proxy_auto());
 	options.download_tags(git2::AutotagOption::All);
 	options.remote_callbacks(callbacks.callbacks());
-	remote.fetch(&[] as &[&str], Some(&mut options), None)?;
+	callbacks.check_result(
+		&repo,
+		remote.fetch(&[] as &[&str], Some(&mut options), None),
+	)?;
 	// fetch tags (also removing remotely deleted ones)
-	remote.fetch(
-		&["refs/tags/*:refs/tags/*"],
-		Some(&mut options),
-		None,
+	callbacks.check_result(
+		&repo,
+		remote.fetch(
+			&["refs/tags/*:refs/tags/*"],
+			Some(&mut options),
+			None,
+		),
 	)?;
 
 	Ok(())
@@ -298,11 +323,48 @@ fn fetch_from_remote(
 pub fn fetch_all(
 	repo_path: &RepoPath,
 	basic_credential: &Option<BasicAuthCredential>,
-	progress_sender: &Option<Sender<ProgressPercent>>,
+	progress_sender: &Option<Sender<Progress>>,
+	cancellation: Option<&CancellationToken>,
 ) -> Result<()> {
 	scope_time!("fetch_all");
 
+	fetch_remotes(
+		repo_path,
+		basic_credential.as_ref(),
+		progress_sender.as_ref(),
+		false,
+		cancellation,
+	)
+}
+
+/// like [`fetch_all`] but also fetches the history a shallow clone
+/// is missing, turning it into a complete one
+pub fn unshallow(
+	repo_path: &RepoPath,
+	basic_credential: &Option<BasicAuthCredential>,
+	progress_sender: &Option<Sender<Progress>>,
+	cancellation: Option<&CancellationToken>,
+) -> Result<()> {
+	scope_time!("unshallow");
+
+	fetch_remotes(
+		repo_path,
+		basic_credential.as_ref(),
+		progress_sender.as_ref(),
+		true,
+		cancellation,
+	)
+}
+
+fn fetch_remotes(
+	repo_path: &RepoPath,
+	basic_credential: Option<&BasicAuthCredential>,
+	progress_sender: Option<&Sender<Progress>>,
+	unshallow: bool,
+	cancellation: Option<&CancellationToken>,
+) -> Result<()> {
 	let repo = repo(repo_path)?;
+	let retry = RetryConfig::from_repo(&repo);
 	let remotes = repo
 		.remotes()?
 		.iter()
@@ -312,15 +374,39 @@ pub fn fetch_all(
 	let remotes_count = remotes.len();
 
 	for (idx, remote) in remotes.into_iter().enumerate() {
-		fetch_from_remote(
-			repo_path,
-			&remote,
-			basic_credential.clone(),
-			None,
+		if cancellation.is_some_and(CancellationToken::is_cancelled) {
+			return Err(Error::Cancelled);
+		}
+
+		retry_network(
+			retry,
+			|attempt| {
+				progress_sender.map(|sender| {
+					sender.send(Progress::new(
+						ProgressPhase::Retrying,
+						attempt,
+						retry.retries,
+					))
+				});
+			},
+			|| {
+				fetch_from_remote(
+					repo_path,
+					&remote,
+					basic_credential.cloned(),
+					None,
+					unshallow,
+					cancellation,
+				)
+			},
 		)?;
 
 		if let Some(sender) = progress_sender {
-			let progress = ProgressPercent::new(idx, remotes_count);
+			let progress = Progress::new(
+				ProgressPhase::Receiving,
+				idx + 1,
+				remotes_count,
+			);
 			sender.send(progress)?;
 		}
 	}
@@ -329,11 +415,13 @@ pub fn fetch_all(
 }
 
 /// fetches from upstream/remote for local `branch`
+#[allow(clippy::needless_pass_by_value)]
 pub(crate) fn fetch(
 	repo_path: &RepoPath,
 	branch: &str,
 	basic_credential: Option<BasicAuthCredential>,
 	progress_sender: Option<Sender<ProgressNotification>>,
+	cancellation: Option<&CancellationToken>,
 ) -> Result<usize> {
 	scope_time!("fetch");
 
@@ -346,13 +434,34 @@ pub(crate) fn fetch(
 	let remote_name = bytes2string(&remote_name)?;
 	let mut remote = repo.find_remote(&remote_name)?;
 
-	let mut options = FetchOptions::new();
-	options.download_tags(git2::AutotagOption::All);
-	let callbacks = Callbacks::new(progress_sender, basic_credential);
-	options.remote_callbacks(callbacks.callbacks());
-	options.proxy_options(proxy_auto());
-
-	remote.fetch(&[branch], Some(&mut options), None)?;
+	let retry = RetryConfig::from_repo(&repo);
+	retry_network(
+		retry,
+		|attempt| {
+			progress_sender.as_ref().map(|sender| {
+				sender.send(ProgressNotification::Retry {
+					attempt,
+					retries: retry.retries,
+				})
+			});
+		},
+		|| {
+			let mut options = FetchOptions::new();
+			options.download_tags(git2::AutotagOption::All);
+			let callbacks = Callbacks::new(
+				progress_sender.clone(),
+				basic_credential.clone(),
+			)
+			.with_cancellation(cancellation);
+			options.remote_callbacks(callbacks.callbacks());
+			options.proxy_options(proxy_auto());
+
+			callbacks.check_result(
+				&repo,
+				remote.fetch(&[branch], Some(&mut options), None),
+			)
+		},
+	)?;
 
 	Ok(remote.stats().received_bytes())
 }
@@ -361,8 +470,9 @@ pub(crate) fn fetch(
 mod tests {
 	use super::*;
 	use crate::sync::tests::{
-		debug_cmd_print, repo_clone, repo_init,
+		debug_cmd_print, repo_clone, repo_init, write_commit_file,
 	};
+	use tempfile::TempDir;
 
 	#[test]
 	fn test_smoke() {
@@ -376,7 +486,29 @@ mod tests {
 
 		assert_eq!(remotes, vec![String::from("origin")]);
 
-		fetch(repo_path, "master", None, None).unwrap();
+		fetch(repo_path, "master", None, None, None).unwrap();
+	}
+
+	#[test]
+	fn test_is_shallow() {
+		let (remote_dir, remote) = repo_init().unwrap();
+		let remote_path = remote_dir.path().to_str().unwrap();
+		write_commit_file(&remote, "a.txt", "a", "first");
+		write_commit_file(&remote, "a.txt", "b", "second");
+
+		let (repo_dir, _repo) = repo_clone(remote_path).unwrap();
+		let repo_path: &RepoPath =
+			&repo_dir.path().to_str().unwrap().into();
+		assert!(!is_shallow(repo_path).unwrap());
+
+		let shallow_dir = TempDir::new().unwrap();
+		let shallow_path: &RepoPath =
+			&shallow_dir.path().to_str().unwrap().into();
+		debug_cmd_print(
+			shallow_path,
+			&format!("git clone --depth 1 file://{remote_path} .")[..],
+		);
+		assert!(is_shallow(shallow_path).unwrap());
 	}
 
 	#[test]
@@ -1,30 +1,44 @@
 use crate::{
+	asyncjob::CancellationToken,
 	error::{Error, Result},
-	progress::ProgressPercent,
+	progress::ReportProgress,
 	sync::{
-		branch::branch_set_upstream_after_push,
+		branch::{
+			branch_set_upstream_after_push,
+			protection::{check_protection, ProtectedAction},
+		},
 		config::{
-			push_default_strategy_config_repo,
-			PushDefaultStrategyConfig,
+			push_default_strategy_config_repo, push_sign_config_repo,
+			PushDefaultStrategyConfig, PushSignConfig,
 		},
 		cred::BasicAuthCredential,
 		get_branch_upstream_merge,
-		remotes::{proxy_auto, Callbacks},
+		remotes::{
+			advertised::update_advertised_ref,
+			proxy_auto,
+			retry::{retry_network, RetryConfig},
+			Callbacks,
+		},
 		repository::repo,
+		sign::SignBuilder,
 		CommitId, RepoPath,
 	},
 };
 use crossbeam_channel::Sender;
-use git2::{PackBuilderStage, PushOptions};
+use git2::{PackBuilderStage, PushOptions, Remote, Repository};
 use scopetime::scope_time;
-use std::fmt::Write as _;
+use std::{
+	fmt::Write as _,
+	io::{BufRead, BufReader},
+	process::{Command, Stdio},
+};
 
 ///
-pub trait AsyncProgress: Clone + Send + Sync {
+pub trait AsyncProgress:
+	ReportProgress + Clone + Send + Sync
+{
 	///
 	fn is_done(&self) -> bool;
-	///
-	fn progress(&self) -> ProgressPercent;
 }
 
 ///
@@ -45,6 +59,8 @@ pub enum ProgressNotification {
 		objects: usize,
 		///
 		total_objects: usize,
+		/// received so far
+		bytes: usize,
 	},
 	///
 	PushTransfer {
@@ -64,6 +80,13 @@ pub enum ProgressNotification {
 		///
 		current: usize,
 	},
+	/// the last attempt failed on the network
+	Retry {
+		///
+		attempt: usize,
+		///
+		retries: usize,
+	},
 	///
 	Done,
 }
@@ -72,29 +95,6 @@ impl AsyncProgress for ProgressNotification {
 	fn is_done(&self) -> bool {
 		*self == Self::Done
 	}
-	fn progress(&self) -> ProgressPercent {
-		match *self {
-			Self::Packing {
-				stage,
-				current,
-				total,
-			} => match stage {
-				PackBuilderStage::AddingObjects
-				| PackBuilderStage::Deltafication => {
-					ProgressPercent::new(current, total)
-				}
-			},
-			Self::PushTransfer { current, total, .. } => {
-				ProgressPercent::new(current, total)
-			}
-			Self::Transfer {
-				objects,
-				total_objects,
-				..
-			} => ProgressPercent::new(objects, total_objects),
-			_ => ProgressPercent::full(),
-		}
-	}
 }
 
 ///
@@ -126,11 +126,12 @@ pub fn push_branch(
 		delete,
 		basic_credential,
 		progress_sender,
+		None,
 	)
 }
 
 //TODO: cleanup
-#[allow(clippy::too_many_arguments)]
+#[allow(clippy::too_many_arguments, clippy::needless_pass_by_value)]
 pub fn push_raw(
 	repo_path: &RepoPath,
 	remote: &str,
@@ -140,22 +141,28 @@ pub fn push_raw(
 	delete: bool,
 	basic_credential: Option<BasicAuthCredential>,
 	progress_sender: Option<Sender<ProgressNotification>>,
+	cancellation: Option<&CancellationToken>,
 ) -> Result<()> {
 	scope_time!("push");
 
 	let repo = repo(repo_path)?;
 	let mut remote = repo.find_remote(remote)?;
 
+	if ref_type == PushType::Branch {
+		if delete {
+			check_protection(&repo, branch, ProtectedAction::Delete)?;
+		} else if force {
+			check_protection(
+				&repo,
+				branch,
+				ProtectedAction::ForcePush,
+			)?;
+		}
+	}
+
 	let push_default_strategy =
 		push_default_strategy_config_repo(&repo)?;
 
-	let mut options = PushOptions::new();
-	options.proxy_options(proxy_auto());
-
-	let callbacks = Callbacks::new(progress_sender, basic_credential);
-	options.remote_callbacks(callbacks.callbacks());
-	options.packbuilder_parallelism(0);
-
 	let branch_modifier = match (force, delete) {
 		(true, true) => "+:",
 		(false, true) => ":",
@@ -167,8 +174,9 @@ pub fn push_raw(
 		PushType::Tag => "tags",
 	};
 
-	let mut push_ref =
-		format!("{branch_modifier}refs/{git_ref_type}/{branch}");
+	let local_ref = format!("refs/{git_ref_type}/{branch}");
+	let mut push_ref = format!("{branch_modifier}{local_ref}");
+	let mut remote_ref = local_ref.clone();
 
 	if !delete
 		&& ref_type == PushType::Branch
@@ -179,11 +187,84 @@ pub fn push_raw(
 			get_branch_upstream_merge(repo_path, branch)
 		{
 			let _ = write!(push_ref, ":{branch_upstream_merge}");
+			remote_ref = branch_upstream_merge;
 		}
 	}
 
 	log::debug!("push to: {push_ref}");
-	remote.push(&[push_ref], Some(&mut options))?;
+
+	let sign = push_sign_config_repo(&repo)?;
+	let retry = RetryConfig::from_repo(&repo);
+	retry_network(
+		retry,
+		|attempt| {
+			progress_sender.as_ref().map(|sender| {
+				sender.send(ProgressNotification::Retry {
+					attempt,
+					retries: retry.retries,
+				})
+			});
+		},
+		|| {
+			if sign == PushSignConfig::No {
+				push_once(
+					&repo,
+					&mut remote,
+					&push_ref,
+					basic_credential.clone(),
+					progress_sender.clone(),
+					cancellation,
+				)
+			} else {
+				push_signed(
+					&repo,
+					remote.name().unwrap_or_default(),
+					&push_ref,
+					sign,
+					basic_credential.as_ref(),
+					progress_sender.as_ref(),
+					cancellation,
+				)
+			}
+		},
+	)?;
+
+	if let Some(url) = remote.pushurl().or_else(|| remote.url()) {
+		let oid = if delete {
+			None
+		} else {
+			repo.refname_to_id(&local_ref).ok()
+		};
+		update_advertised_ref(&repo, url, &remote_ref, oid);
+	}
+
+	if !delete {
+		branch_set_upstream_after_push(&repo, branch)?;
+	}
+
+	Ok(())
+}
+
+fn push_once(
+	repo: &Repository,
+	remote: &mut Remote,
+	push_ref: &str,
+	basic_credential: Option<BasicAuthCredential>,
+	progress_sender: Option<Sender<ProgressNotification>>,
+	cancellation: Option<&CancellationToken>,
+) -> Result<()> {
+	let mut options = PushOptions::new();
+	options.proxy_options(proxy_auto());
+
+	let callbacks = Callbacks::new(progress_sender, basic_credential)
+		.with_cancellation(cancellation);
+	options.remote_callbacks(callbacks.callbacks());
+	options.packbuilder_parallelism(0);
+
+	callbacks.check_result(
+		repo,
+		remote.push(&[push_ref], Some(&mut options)),
+	)?;
 
 	if let Some((reference, msg)) =
 		callbacks.get_stats()?.push_rejected_msg
@@ -193,13 +274,173 @@ pub fn push_raw(
 		)));
 	}
 
-	if !delete {
-		branch_set_upstream_after_push(&repo, branch)?;
+	Ok(())
+}
+
+/// answers `get` with the credentials from the environment, so they
+/// do not show up in the arguments of the process
+const CREDENTIAL_HELPER: &str = r#"!f() { test "$1" = get && echo "username=$GITUI_PUSH_USERNAME" && echo "password=$GITUI_PUSH_PASSWORD"; }; f"#;
+
+/// libgit2 cannot send push certificates, so signed pushes go through
+/// the git cli, after the signing setup passed the same checks as for
+/// signed commits.
+///
+/// like the libgit2 push it never runs the pre-push hook itself
+/// (`--no-verify`), gitui runs it before pushing either way. git
+/// cannot prompt for anything: credentials entered in gitui are
+/// handed over by a credential helper and stdin is closed. git gets
+/// killed once `cancellation` is cancelled
+fn push_signed(
+	repo: &Repository,
+	remote: &str,
+	push_ref: &str,
+	sign: PushSignConfig,
+	basic_credential: Option<&BasicAuthCredential>,
+	progress_sender: Option<&Sender<ProgressNotification>>,
+	cancellation: Option<&CancellationToken>,
+) -> Result<()> {
+	scope_time!("push_signed");
+
+	let config = repo.config()?;
+	SignBuilder::from_gitconfig(repo, &config)?;
+
+	let signed = if sign == PushSignConfig::IfAsked {
+		"--signed=if-asked"
+	} else {
+		"--signed=yes"
+	};
+
+	let mut child = git_with_credentials(basic_credential)
+		.args([
+			"push",
+			"--no-verify",
+			"--porcelain",
+			"--progress",
+			signed,
+		])
+		.arg(remote)
+		.arg(push_ref)
+		.current_dir(repo.workdir().unwrap_or_else(|| repo.path()))
+		.env("GIT_TERMINAL_PROMPT", "0")
+		.stdin(Stdio::null())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+		.map_err(|e| {
+			Error::Generic(format!("signed push needs git: {e}"))
+		})?;
+
+	let mut messages = Vec::new();
+	if let Some(stderr) = child.stderr.take() {
+		for line in BufReader::new(stderr).split(b'\r') {
+			if cancellation
+				.is_some_and(CancellationToken::is_cancelled)
+			{
+				child.kill()?;
+				break;
+			}
+
+			for line in String::from_utf8_lossy(&line?).lines() {
+				if let Some(progress) = parse_progress(line) {
+					progress_sender
+						.map(|sender| sender.send(progress));
+				} else {
+					messages.push(line.to_string());
+				}
+			}
+		}
+	}
+
+	let output = child.wait_with_output()?;
+
+	if cancellation.is_some_and(CancellationToken::is_cancelled) {
+		return Err(Error::Cancelled);
+	}
+
+	if !output.status.success() {
+		return Err(signed_push_error(messages.join("\n").trim()));
 	}
 
 	Ok(())
 }
 
+/// the git cli only tells network failures apart in its messages
+const NETWORK_FAILURES: &[&str] = &[
+	"Could not resolve host",
+	"Could not resolve hostname",
+	"Failed to connect",
+	"Connection refused",
+	"Connection timed out",
+	"Connection reset",
+	"Operation timed out",
+	"early EOF",
+];
+
+/// an `Error::Network` for `messages` of a failed signed push that
+/// name a network failure, so it gets retried like a libgit2 push
+fn signed_push_error(messages: &str) -> Error {
+	if NETWORK_FAILURES
+		.iter()
+		.any(|failure| messages.contains(failure))
+	{
+		Error::Network(messages.to_string())
+	} else {
+		Error::Generic(format!("signed push failed: {messages}"))
+	}
+}
+
+/// the git cli using `credential` instead of the configured helpers
+fn git_with_credentials(
+	credential: Option<&BasicAuthCredential>,
+) -> Command {
+	let mut cmd = Command::new("git");
+
+	if let Some(BasicAuthCredential {
+		username: Some(username),
+		password: Some(password),
+	}) = credential
+	{
+		// an empty helper drops the configured ones
+		cmd.args(["-c", "credential.helper="])
+			.arg("-c")
+			.arg(format!("credential.helper={CREDENTIAL_HELPER}"))
+			.env("GITUI_PUSH_USERNAME", username)
+			.env("GITUI_PUSH_PASSWORD", password);
+	}
+
+	cmd
+}
+
+/// progress of the git cli like `Writing objects:  50% (1/2)`
+fn parse_progress(line: &str) -> Option<ProgressNotification> {
+	let (stage, rest) = line.trim().split_once(':')?;
+	let (current, total) =
+		rest.split_once('(')?.1.split_once(')')?.0.split_once('/')?;
+	let (current, total) =
+		(current.parse().ok()?, total.parse().ok()?);
+
+	Some(match stage {
+		"Enumerating objects" | "Counting objects" => {
+			ProgressNotification::Packing {
+				stage: PackBuilderStage::AddingObjects,
+				total,
+				current,
+			}
+		}
+		"Compressing objects" => ProgressNotification::Packing {
+			stage: PackBuilderStage::Deltafication,
+			total,
+			current,
+		},
+		"Writing objects" => ProgressNotification::PushTransfer {
+			current,
+			total,
+			bytes: 0,
+		},
+		_ => return None,
+	})
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -210,8 +451,8 @@ mod tests {
 			write_commit_file,
 		},
 	};
-	use git2::Repository;
-	use std::{fs::File, io::Write, path::Path};
+	use git2::{PackBuilderStage, Repository};
+	use std::{fs::File, io::Write, path::Path, process::Stdio};
 
 	#[test]
 	fn test_force_push() {
@@ -519,4 +760,207 @@ mod tests {
 			.map(|(i, _)| i.name().unwrap().unwrap().to_string())
 			.any(|i| &i == "test_branch"));
 	}
+
+	#[test]
+	fn test_push_signed_if_asked() {
+		let (upstream_dir, upstream_repo) = repo_init_bare().unwrap();
+
+		let (tmp_repo_dir, repo) =
+			repo_clone(upstream_dir.path().to_str().unwrap())
+				.unwrap();
+
+		let commit = write_commit_file(
+			&repo,
+			"temp_file.txt",
+			"SomeContent",
+			"Initial commit",
+		);
+
+		repo.config()
+			.unwrap()
+			.set_str("push.gpgSign", "if-asked")
+			.unwrap();
+
+		// a local remote does not ask for a push certificate
+		push_branch(
+			&tmp_repo_dir.path().to_str().unwrap().into(),
+			"origin",
+			"master",
+			false,
+			false,
+			None,
+			None,
+		)
+		.unwrap();
+
+		let upstream_head = upstream_repo
+			.find_branch("master", git2::BranchType::Local)
+			.unwrap()
+			.get()
+			.peel_to_commit()
+			.unwrap()
+			.id();
+		assert_eq!(CommitId::new(upstream_head), commit);
+	}
+
+	#[test]
+	fn test_push_signed_skips_hooks_like_libgit2() {
+		let (upstream_dir, _upstream_repo) =
+			repo_init_bare().unwrap();
+		let (tmp_repo_dir, repo) =
+			repo_clone(upstream_dir.path().to_str().unwrap())
+				.unwrap();
+		let repo_path: &RepoPath =
+			&tmp_repo_dir.path().to_str().unwrap().into();
+
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_PUSH,
+			b"#!/bin/sh\nexit 1",
+		);
+
+		write_commit_file(&repo, "a.txt", "a", "libgit2");
+		push_branch(
+			repo_path, "origin", "master", false, false, None, None,
+		)
+		.unwrap();
+
+		repo.config()
+			.unwrap()
+			.set_str("push.gpgSign", "if-asked")
+			.unwrap();
+		write_commit_file(&repo, "a.txt", "b", "cli");
+		push_branch(
+			repo_path, "origin", "master", false, false, None, None,
+		)
+		.unwrap();
+	}
+
+	#[test]
+	fn test_push_signed_progress() {
+		let (upstream_dir, _upstream_repo) =
+			repo_init_bare().unwrap();
+		let (tmp_repo_dir, repo) =
+			repo_clone(upstream_dir.path().to_str().unwrap())
+				.unwrap();
+
+		repo.config()
+			.unwrap()
+			.set_str("push.gpgSign", "if-asked")
+			.unwrap();
+		write_commit_file(&repo, "a.txt", "a", "c1");
+
+		let (sender, receiver) = crossbeam_channel::unbounded();
+		push_branch(
+			&tmp_repo_dir.path().to_str().unwrap().into(),
+			"origin",
+			"master",
+			false,
+			false,
+			None,
+			Some(sender),
+		)
+		.unwrap();
+
+		assert!(receiver.try_iter().any(|progress| matches!(
+			progress,
+			ProgressNotification::PushTransfer { current, total, .. }
+				if current == total
+		)));
+	}
+
+	#[test]
+	fn test_push_signed_retries() {
+		let (tmp_repo_dir, repo) = repo_init().unwrap();
+
+		let mut config = repo.config().unwrap();
+		config.set_str("push.gpgSign", "if-asked").unwrap();
+		config.set_i64("gitui.networkRetries", 1).unwrap();
+		config.set_i64("gitui.networkRetryDelay", 0).unwrap();
+		repo.remote("origin", "https://host.invalid/repo.git")
+			.unwrap();
+
+		let (sender, receiver) = crossbeam_channel::unbounded();
+		let res = push_branch(
+			&tmp_repo_dir.path().to_str().unwrap().into(),
+			"origin",
+			"master",
+			false,
+			false,
+			None,
+			Some(sender),
+		);
+
+		assert!(matches!(res, Err(Error::Network(_))), "{res:?}");
+		assert!(receiver.try_iter().any(|progress| progress
+			== ProgressNotification::Retry {
+				attempt: 1,
+				retries: 1,
+			}));
+	}
+
+	#[test]
+	fn test_signed_push_error() {
+		assert!(matches!(
+			signed_push_error(
+				"fatal: unable to access 'https://host.invalid/': Could not resolve host: host.invalid"
+			),
+			Error::Network(_)
+		));
+		assert!(matches!(
+			signed_push_error("! [rejected] master -> master"),
+			Error::Generic(_)
+		));
+	}
+
+	#[test]
+	fn test_parse_progress() {
+		assert_eq!(
+			parse_progress("Writing objects:  50% (1/2)"),
+			Some(ProgressNotification::PushTransfer {
+				current: 1,
+				total: 2,
+				bytes: 0,
+			})
+		);
+		assert_eq!(
+			parse_progress("Compressing objects: 100% (3/3), done."),
+			Some(ProgressNotification::Packing {
+				stage: PackBuilderStage::Deltafication,
+				total: 3,
+				current: 3,
+			})
+		);
+		assert_eq!(parse_progress("remote: Resolving (1/2)"), None);
+		assert_eq!(parse_progress("To ../upstream"), None);
+	}
+
+	#[test]
+	fn test_git_with_credentials() {
+		use std::io::Write as _;
+
+		let credential = BasicAuthCredential::new(
+			Some(String::from("user")),
+			Some(String::from("secret")),
+		);
+
+		let mut child = git_with_credentials(Some(&credential))
+			.args(["credential", "fill"])
+			.env("GIT_TERMINAL_PROMPT", "0")
+			.stdin(Stdio::piped())
+			.stdout(Stdio::piped())
+			.spawn()
+			.unwrap();
+		child
+			.stdin
+			.take()
+			.unwrap()
+			.write_all(b"protocol=https\nhost=example.com\n\n")
+			.unwrap();
+		let output = child.wait_with_output().unwrap();
+		let output = String::from_utf8(output.stdout).unwrap();
+
+		assert!(output.contains("username=user\n"));
+		assert!(output.contains("password=secret\n"));
+	}
 }
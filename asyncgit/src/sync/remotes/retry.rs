@@ -0,0 +1,172 @@
+//! retrying remote operations that failed on the network
+
+use crate::error::{Error, Result};
+use git2::Repository;
+use std::{thread, time::Duration};
+
+const RETRIES_CONFIG: &str = "gitui.networkRetries";
+const DELAY_CONFIG: &str = "gitui.networkRetryDelay";
+
+/// the wait between two attempts never grows beyond this
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// how often remote operations are retried after network failures
+/// and how long to wait before the first retry. the wait doubles with
+/// every further attempt
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryConfig {
+	///
+	pub retries: usize,
+	///
+	pub delay: Duration,
+}
+
+impl Default for RetryConfig {
+	fn default() -> Self {
+		Self {
+			retries: 3,
+			delay: Duration::from_secs(1),
+		}
+	}
+}
+
+impl RetryConfig {
+	/// reads `gitui.networkRetries` (`0` disables retrying) and
+	/// `gitui.networkRetryDelay` in milliseconds
+	pub fn from_repo(repo: &Repository) -> Self {
+		let default = Self::default();
+		let Ok(config) = repo.config() else {
+			return default;
+		};
+
+		let retries = config
+			.get_i64(RETRIES_CONFIG)
+			.ok()
+			.and_then(|retries| usize::try_from(retries).ok())
+			.unwrap_or(default.retries);
+		let delay = config
+			.get_i64(DELAY_CONFIG)
+			.ok()
+			.and_then(|ms| u64::try_from(ms).ok())
+			.map_or(default.delay, Duration::from_millis);
+
+		Self { retries, delay }
+	}
+
+	/// to wait before `attempt` (starting at 1)
+	pub fn delay_for(&self, attempt: usize) -> Duration {
+		let exponent = u32::try_from(attempt.saturating_sub(1))
+			.unwrap_or(u32::MAX);
+
+		self.delay
+			.saturating_mul(2_u32.saturating_pow(exponent))
+			.min(MAX_DELAY)
+	}
+}
+
+/// runs `op` until it succeeds, fails for another reason than the
+/// network or ran out of retries. `on_retry` is told about every
+/// retry before waiting for it
+pub fn retry_network<T>(
+	config: RetryConfig,
+	mut on_retry: impl FnMut(usize),
+	mut op: impl FnMut() -> Result<T>,
+) -> Result<T> {
+	let mut attempt = 0;
+
+	loop {
+		match op() {
+			Err(Error::Network(msg)) if attempt < config.retries => {
+				attempt += 1;
+				let delay = config.delay_for(attempt);
+
+				log::warn!(
+					"network error, retry {attempt}/{} in {delay:?}: {msg}",
+					config.retries
+				);
+
+				on_retry(attempt);
+				thread::sleep(delay);
+			}
+			res => return res,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::tests::repo_init;
+	use std::cell::Cell;
+
+	#[test]
+	fn test_retry_config() {
+		let (_td, repo) = repo_init().unwrap();
+
+		assert_eq!(
+			RetryConfig::from_repo(&repo),
+			RetryConfig::default()
+		);
+
+		let mut config = repo.config().unwrap();
+		config.set_i64(RETRIES_CONFIG, 5).unwrap();
+		config.set_i64(DELAY_CONFIG, 200).unwrap();
+
+		let retry = RetryConfig::from_repo(&repo);
+		assert_eq!(retry.retries, 5);
+		assert_eq!(retry.delay_for(1), Duration::from_millis(200));
+		assert_eq!(retry.delay_for(3), Duration::from_millis(800));
+		assert_eq!(retry.delay_for(20), MAX_DELAY);
+	}
+
+	#[test]
+	fn test_retry_network() {
+		let config = RetryConfig {
+			retries: 2,
+			delay: Duration::ZERO,
+		};
+
+		let calls = Cell::new(0);
+		let mut retries = Vec::new();
+		let res = retry_network(
+			config,
+			|attempt| retries.push(attempt),
+			|| {
+				calls.set(calls.get() + 1);
+				if calls.get() < 3 {
+					Err(Error::Network("timeout".into()))
+				} else {
+					Ok(calls.get())
+				}
+			},
+		);
+		assert_eq!(res.unwrap(), 3);
+		assert_eq!(retries, vec![1, 2]);
+
+		// gives up after the configured retries
+		calls.set(0);
+		let res: Result<()> = retry_network(
+			config,
+			|_| (),
+			|| {
+				calls.set(calls.get() + 1);
+				Err(Error::Network("timeout".into()))
+			},
+		);
+		assert!(matches!(res, Err(Error::Network(_))));
+		assert_eq!(calls.get(), 3);
+
+		// anything but the network is not retried
+		calls.set(0);
+		let res: Result<()> = retry_network(
+			config,
+			|_| (),
+			|| {
+				calls.set(calls.get() + 1);
+				Err(Error::Authentication("denied".into()))
+			},
+		);
+		assert!(res.is_err());
+		assert_eq!(calls.get(), 1);
+	}
+}
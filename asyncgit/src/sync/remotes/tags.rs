@@ -2,17 +2,23 @@
 
 use super::push::AsyncProgress;
 use crate::{
-	error::Result,
-	progress::ProgressPercent,
+	asyncjob::CancellationToken,
+	error::{Error, Result},
+	progress::{Progress, ProgressPhase, ReportProgress},
 	sync::{
 		cred::BasicAuthCredential,
-		remotes::{proxy_auto, Callbacks},
+		remotes::{
+			advertised::{advertised_refs, update_advertised_ref},
+			proxy_auto,
+			retry::{retry_network, RetryConfig},
+			Callbacks,
+		},
 		repository::repo,
 		RepoPath,
 	},
 };
 use crossbeam_channel::Sender;
-use git2::{Direction, PushOptions};
+use git2::{Direction, PushOptions, Repository};
 use scopetime::scope_time;
 use std::collections::HashSet;
 
@@ -28,26 +34,44 @@ pub enum PushTagsProgress {
 		///
 		total: usize,
 	},
+	/// the last attempt failed on the network
+	Retry {
+		///
+		attempt: usize,
+		///
+		retries: usize,
+	},
 	/// done
 	Done,
 }
 
-impl AsyncProgress for PushTagsProgress {
-	fn progress(&self) -> ProgressPercent {
-		match self {
-			Self::CheckRemote => ProgressPercent::empty(),
+impl ReportProgress for PushTagsProgress {
+	fn report(&self) -> Progress {
+		match *self {
+			Self::CheckRemote => {
+				Progress::new(ProgressPhase::CheckingRemote, 0, 1)
+			}
 			Self::Push { pushed, total } => {
-				ProgressPercent::new(*pushed, *total)
+				Progress::new(ProgressPhase::Sending, pushed, total)
 			}
-			Self::Done => ProgressPercent::full(),
+			Self::Retry { attempt, retries } => Progress::new(
+				ProgressPhase::Retrying,
+				attempt,
+				retries,
+			),
+			Self::Done => Progress::new(ProgressPhase::Done, 1, 1),
 		}
 	}
+}
+
+impl AsyncProgress for PushTagsProgress {
 	fn is_done(&self) -> bool {
 		*self == Self::Done
 	}
 }
 
 /// lists the remotes tags
+#[allow(clippy::needless_pass_by_value)]
 fn remote_tag_refs(
 	repo_path: &RepoPath,
 	remote: &str,
@@ -56,26 +80,39 @@ fn remote_tag_refs(
 	scope_time!("remote_tags");
 
 	let repo = repo(repo_path)?;
-	let mut remote = repo.find_remote(remote)?;
-	let callbacks = Callbacks::new(None, basic_credential);
-	let conn = remote.connect_auth(
-		Direction::Fetch,
-		Some(callbacks.callbacks()),
-		Some(proxy_auto()),
-	)?;
+	let url = remote_push_url(&repo, remote)?;
 
-	let remote_heads = conn.list()?;
-	let remote_tags = remote_heads
-		.iter()
-		.map(|s| s.name().to_string())
-		.filter(|name| {
-			name.starts_with("refs/tags/") && !name.ends_with("^{}")
-		})
-		.collect::<Vec<_>>();
+	let remote_tags = advertised_refs(
+		&repo,
+		Some(remote),
+		&url,
+		Direction::Fetch,
+		basic_credential.as_ref(),
+	)?
+	.into_keys()
+	.filter(|name| {
+		name.starts_with("refs/tags/") && !name.ends_with("^{}")
+	})
+	.collect::<Vec<_>>();
 
 	Ok(remote_tags)
 }
 
+/// the url pushes to `remote` go to, which is where its refs are
+/// listed from as well
+fn remote_push_url(
+	repo: &Repository,
+	remote: &str,
+) -> Result<String> {
+	let remote = repo.find_remote(remote)?;
+
+	Ok(remote
+		.pushurl()
+		.or_else(|| remote.url())
+		.ok_or(Error::UnknownRemote)?
+		.to_string())
+}
+
 /// lists the remotes tags missing
 pub fn tags_missing_remote(
 	repo_path: &RepoPath,
@@ -107,6 +144,7 @@ pub fn push_tags(
 	remote: &str,
 	basic_credential: Option<BasicAuthCredential>,
 	progress_sender: Option<Sender<PushTagsProgress>>,
+	cancellation: Option<&CancellationToken>,
 ) -> Result<()> {
 	scope_time!("push_tags");
 
@@ -121,7 +159,9 @@ pub fn push_tags(
 	)?;
 
 	let repo = repo(repo_path)?;
+	let url = remote_push_url(&repo, remote)?;
 	let mut remote = repo.find_remote(remote)?;
+	let retry = RetryConfig::from_repo(&repo);
 
 	let total = tags_missing.len();
 
@@ -130,13 +170,41 @@ pub fn push_tags(
 	});
 
 	for (idx, tag) in tags_missing.into_iter().enumerate() {
-		let mut options = PushOptions::new();
-		let callbacks =
-			Callbacks::new(None, basic_credential.clone());
-		options.remote_callbacks(callbacks.callbacks());
-		options.packbuilder_parallelism(0);
-		options.proxy_options(proxy_auto());
-		remote.push(&[tag.as_str()], Some(&mut options))?;
+		if cancellation.is_some_and(CancellationToken::is_cancelled) {
+			return Err(Error::Cancelled);
+		}
+
+		retry_network(
+			retry,
+			|attempt| {
+				progress_sender.as_ref().map(|sender| {
+					sender.send(PushTagsProgress::Retry {
+						attempt,
+						retries: retry.retries,
+					})
+				});
+			},
+			|| {
+				let mut options = PushOptions::new();
+				let callbacks =
+					Callbacks::new(None, basic_credential.clone())
+						.with_cancellation(cancellation);
+				options.remote_callbacks(callbacks.callbacks());
+				options.packbuilder_parallelism(0);
+				options.proxy_options(proxy_auto());
+				callbacks.check_result(
+					&repo,
+					remote.push(&[tag.as_str()], Some(&mut options)),
+				)
+			},
+		)?;
+
+		update_advertised_ref(
+			&repo,
+			&url,
+			&tag,
+			repo.refname_to_id(&tag).ok(),
+		);
 
 		progress_sender.as_ref().map(|sender| {
 			sender.send(PushTagsProgress::Push {
@@ -196,7 +264,7 @@ mod tests {
 			clone1_dir, "origin", "master", false, false, None, None,
 		)
 		.unwrap();
-		push_tags(clone1_dir, "origin", None, None).unwrap();
+		push_tags(clone1_dir, "origin", None, None, None).unwrap();
 
 		// clone2
 
@@ -210,7 +278,8 @@ mod tests {
 		assert_eq!(sync::get_tags(clone2_dir).unwrap().len(), 0);
 
 		//lets fetch from origin
-		let bytes = fetch(clone2_dir, "master", None, None).unwrap();
+		let bytes =
+			fetch(clone2_dir, "master", None, None, None).unwrap();
 		assert!(bytes > 0);
 
 		sync::merge_upstream_commit(clone2_dir, "master").unwrap();
@@ -244,7 +313,7 @@ mod tests {
 			clone1_dir, "origin", "master", false, false, None, None,
 		)
 		.unwrap();
-		push_tags(clone1_dir, "origin", None, None).unwrap();
+		push_tags(clone1_dir, "origin", None, None, None).unwrap();
 
 		// clone2
 
@@ -286,7 +355,7 @@ mod tests {
 			tags_missing.as_slice(),
 			&[String::from("refs/tags/tag1")]
 		);
-		push_tags(clone1_dir, "origin", None, None).unwrap();
+		push_tags(clone1_dir, "origin", None, None, None).unwrap();
 		let tags_missing =
 			tags_missing_remote(clone1_dir, "origin", None).unwrap();
 		assert!(tags_missing.is_empty());
@@ -318,20 +387,63 @@ mod tests {
 
 		let tags1 = sync::get_tags(clone1_dir).unwrap();
 
-		push_tags(clone1_dir, "origin", None, None).unwrap();
+		push_tags(clone1_dir, "origin", None, None, None).unwrap();
 		let tags_missing =
 			tags_missing_remote(clone1_dir, "origin", None).unwrap();
 		assert!(tags_missing.is_empty());
 
 		// clone 2 - pull
 
-		fetch(clone2_dir, "master", None, None).unwrap();
+		fetch(clone2_dir, "master", None, None, None).unwrap();
 
 		let tags2 = sync::get_tags(clone2_dir).unwrap();
 
 		assert_eq!(tags1, tags2);
 	}
 
+	#[test]
+	fn test_push_tags_cancelled() {
+		let (r1_dir, _repo) = repo_init_bare().unwrap();
+		let r1_dir = r1_dir.path().to_str().unwrap();
+
+		let (clone1_dir, clone1) = repo_clone(r1_dir).unwrap();
+		let clone1_dir: &RepoPath =
+			&clone1_dir.path().to_str().unwrap().into();
+
+		let commit1 =
+			write_commit_file(&clone1, "test.txt", "test", "commit1");
+		push_branch(
+			clone1_dir, "origin", "master", false, false, None, None,
+		)
+		.unwrap();
+		sync::tag_commit(clone1_dir, &commit1, "tag1", None).unwrap();
+
+		let cancellation = CancellationToken::default();
+		cancellation.cancel();
+
+		assert!(matches!(
+			push_tags(
+				clone1_dir,
+				"origin",
+				None,
+				None,
+				Some(&cancellation)
+			),
+			Err(Error::Cancelled)
+		));
+		assert_eq!(
+			tags_missing_remote(clone1_dir, "origin", None)
+				.unwrap()
+				.len(),
+			1
+		);
+
+		assert!(matches!(
+			fetch_all(clone1_dir, &None, &None, Some(&cancellation)),
+			Err(Error::Cancelled)
+		));
+	}
+
 	#[test]
 	fn test_tags_fetch_all() {
 		let (r1_dir, _repo) = repo_init_bare().unwrap();
@@ -358,14 +470,14 @@ mod tests {
 
 		let tags1 = sync::get_tags(clone1_dir).unwrap();
 
-		push_tags(clone1_dir, "origin", None, None).unwrap();
+		push_tags(clone1_dir, "origin", None, None, None).unwrap();
 		let tags_missing =
 			tags_missing_remote(clone1_dir, "origin", None).unwrap();
 		assert!(tags_missing.is_empty());
 
 		// clone 2 - pull
 
-		fetch_all(clone2_dir, &None, &None).unwrap();
+		fetch_all(clone2_dir, &None, &None, None).unwrap();
 
 		let tags2 = sync::get_tags(clone2_dir).unwrap();
 
@@ -395,11 +507,11 @@ mod tests {
 		// clone1 - creates tag
 
 		sync::tag_commit(clone1_dir, &commit1, "tag1", None).unwrap();
-		push_tags(clone1_dir, "origin", None, None).unwrap();
+		push_tags(clone1_dir, "origin", None, None, None).unwrap();
 
 		// clone 2 - pull
 
-		fetch_all(clone2_dir, &None, &None).unwrap();
+		fetch_all(clone2_dir, &None, &None, None).unwrap();
 		assert_eq!(sync::get_tags(clone2_dir).unwrap().len(), 1);
 
 		// delete on clone 1
@@ -415,14 +527,15 @@ mod tests {
 			true,
 			None,
 			None,
+			None,
 		)
 		.unwrap();
 
-		push_tags(clone1_dir, "origin", None, None).unwrap();
+		push_tags(clone1_dir, "origin", None, None, None).unwrap();
 
 		// clone 2
 
-		fetch_all(clone2_dir, &None, &None).unwrap();
+		fetch_all(clone2_dir, &None, &None, None).unwrap();
 		assert_eq!(sync::get_tags(clone2_dir).unwrap().len(), 0);
 	}
 }
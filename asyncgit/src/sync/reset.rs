@@ -1,7 +1,16 @@
 use super::{utils::get_head_repo, CommitId, RepoPath};
-use crate::{error::Result, sync::repository::repo};
-use git2::{build::CheckoutBuilder, ObjectType, ResetType};
+use crate::{
+	error::{Error, Result},
+	sync::{
+		filter::{index_blobs, refilter_workdir, tree_blobs},
+		repository::repo,
+	},
+};
+use git2::{
+	build::CheckoutBuilder, ObjectType, ResetType, StatusOptions,
+};
 use scopetime::scope_time;
+use std::path::Path;
 
 ///
 pub fn reset_stage(repo_path: &RepoPath, path: &str) -> Result<()> {
@@ -35,6 +44,47 @@ pub fn reset_workdir(repo_path: &RepoPath, path: &str) -> Result<()> {
 		.path(path);
 
 	repo.checkout_index(None, Some(&mut checkout_opts))?;
+
+	refilter_workdir(repo_path, &repo, &index_blobs(&repo, path)?)?;
+
+	Ok(())
+}
+
+/// overwrites `path` in the workdir with its version in `commit`,
+/// leaving the index alone
+pub fn checkout_file_at_commit(
+	repo_path: &RepoPath,
+	commit: CommitId,
+	path: &str,
+) -> Result<()> {
+	scope_time!("checkout_file_at_commit");
+
+	let repo = repo(repo_path)?;
+
+	let commit = repo.find_commit(commit.into())?;
+	let tree = commit.tree()?;
+	if tree.get_path(Path::new(path)).is_err() {
+		return Err(Error::Generic(format!(
+			"{path} does not exist in {}",
+			CommitId::from(commit.id()).get_short_string()
+		)));
+	}
+
+	let mut checkout_opts = CheckoutBuilder::new();
+	checkout_opts
+		.update_index(false)
+		.disable_pathspec_match(true)
+		.force()
+		.path(path);
+
+	repo.checkout_tree(commit.as_object(), Some(&mut checkout_opts))?;
+
+	refilter_workdir(
+		repo_path,
+		&repo,
+		&tree_blobs(&repo, &tree, path)?,
+	)?;
+
 	Ok(())
 }
 
@@ -55,15 +105,67 @@ pub fn reset_repo(
 	Ok(())
 }
 
+/// what a `reset_repo` of the current branch to a commit affects
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ResetPreview {
+	/// commits no longer on the branch afterwards
+	pub commits_removed: usize,
+	/// commits on the branch afterwards that were not before
+	pub commits_added: usize,
+	/// files that differ between `HEAD` and the commit
+	pub files_changed: usize,
+	/// uncommitted changes of tracked files, lost in a hard reset
+	pub local_changes: usize,
+}
+
+///
+pub fn reset_preview(
+	repo_path: &RepoPath,
+	commit: CommitId,
+) -> Result<ResetPreview> {
+	scope_time!("reset_preview");
+
+	let repo = repo(repo_path)?;
+
+	let head = get_head_repo(&repo)?;
+	let (commits_removed, commits_added) =
+		repo.graph_ahead_behind(head.into(), commit.into())?;
+
+	let diff = repo.diff_tree_to_tree(
+		Some(&repo.find_commit(head.into())?.tree()?),
+		Some(&repo.find_commit(commit.into())?.tree()?),
+		None,
+	)?;
+
+	let local_changes = repo
+		.statuses(Some(
+			StatusOptions::new()
+				.include_untracked(false)
+				.include_ignored(false),
+		))?
+		.len();
+
+	Ok(ResetPreview {
+		commits_removed,
+		commits_added,
+		files_changed: diff.deltas().len(),
+		local_changes,
+	})
+}
+
 #[cfg(test)]
 mod tests {
-	use super::{reset_stage, reset_workdir};
+	use super::{
+		checkout_file_at_commit, reset_preview, reset_stage,
+		reset_workdir, ResetPreview,
+	};
 	use crate::error::Result;
 	use crate::sync::{
 		commit,
 		status::{get_status, StatusType},
 		tests::{
-			debug_cmd_print, get_statuses, repo_init, repo_init_empty,
+			debug_cmd_print, get_statuses, repo_init,
+			repo_init_empty, write_commit_file,
 		},
 		utils::{stage_add_all, stage_add_file},
 		RepoPath,
@@ -100,6 +202,55 @@ mod tests {
 0   newb
 1   end";
 
+	#[test]
+	fn test_checkout_file() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let old = write_commit_file(&repo, "foo.txt", "old", "c1");
+		write_commit_file(&repo, "foo.txt", "new", "c2");
+		let other = write_commit_file(&repo, "bar.txt", "bar", "c3");
+
+		checkout_file_at_commit(repo_path, old, "foo.txt").unwrap();
+
+		assert_eq!(
+			fs::read_to_string(root.join("foo.txt")).unwrap(),
+			"old"
+		);
+		// unstaged only
+		assert_eq!(get_statuses(repo_path), (1, 0));
+
+		assert!(checkout_file_at_commit(repo_path, old, "bar.txt")
+			.is_err());
+		checkout_file_at_commit(repo_path, other, "bar.txt").unwrap();
+		assert_eq!(get_statuses(repo_path), (1, 0));
+	}
+
+	#[test]
+	fn test_reset_preview() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let base = write_commit_file(&repo, "foo.txt", "a", "c1");
+		write_commit_file(&repo, "foo.txt", "b", "c2");
+		write_commit_file(&repo, "bar.txt", "c", "c3");
+		fs::write(root.join("foo.txt"), "local").unwrap();
+
+		assert_eq!(
+			reset_preview(repo_path, base).unwrap(),
+			ResetPreview {
+				commits_removed: 2,
+				commits_added: 0,
+				files_changed: 2,
+				local_changes: 1,
+			}
+		);
+	}
+
 	#[test]
 	fn test_reset_only_unstaged() {
 		let (_td, repo) = repo_init().unwrap();
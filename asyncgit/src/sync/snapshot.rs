@@ -0,0 +1,120 @@
+//! a cheap fingerprint of `HEAD`, the index and the refs to notice
+//! when git commands outside of gitui change them
+
+use super::RepoPath;
+use crate::{error::Result, hash, sync::repository::repo};
+use bitflags::bitflags;
+use scopetime::scope_time;
+use std::{fs, time::SystemTime};
+
+bitflags! {
+	/// what differs between two `RepoSnapshot`s
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+	pub struct RepoChanges: u8 {
+		/// checked out branch or commit
+		const HEAD = 1 << 0;
+		///
+		const INDEX = 1 << 1;
+		/// branches, tags, remote branches, stashes, ...
+		const REFS = 1 << 2;
+	}
+}
+
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoSnapshot {
+	/// name `HEAD` points to and the commit it resolves to
+	head: (Option<String>, Option<String>),
+	/// modification time and size of the index file
+	index: Option<(SystemTime, u64)>,
+	/// hash of all ref names and their targets
+	refs: u64,
+}
+
+impl RepoSnapshot {
+	/// what changed from `self` to `newer`
+	pub fn changes(&self, newer: &Self) -> RepoChanges {
+		let mut changes = RepoChanges::empty();
+
+		changes.set(RepoChanges::HEAD, self.head != newer.head);
+		changes.set(RepoChanges::INDEX, self.index != newer.index);
+		changes.set(RepoChanges::REFS, self.refs != newer.refs);
+
+		changes
+	}
+}
+
+///
+pub fn repo_snapshot(repo_path: &RepoPath) -> Result<RepoSnapshot> {
+	scope_time!("repo_snapshot");
+
+	let repo = repo(repo_path)?;
+
+	let head_ref = repo.find_reference("HEAD")?;
+	let head = (
+		head_ref.symbolic_target().map(String::from),
+		repo.refname_to_id("HEAD").ok().map(|id| id.to_string()),
+	);
+
+	let index = fs::metadata(repo.path().join("index"))
+		.ok()
+		.and_then(|meta| Some((meta.modified().ok()?, meta.len())));
+
+	let mut refs = Vec::new();
+	for reference in repo.references()?.flatten() {
+		refs.push((
+			reference.name_bytes().to_vec(),
+			reference.target().map(|id| id.to_string()),
+			reference.symbolic_target_bytes().map(<[u8]>::to_vec),
+		));
+	}
+
+	Ok(RepoSnapshot {
+		head,
+		index,
+		refs: hash(&refs),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		checkout_branch, stage_add_file,
+		tests::{repo_init, write_commit_file},
+		utils::repo_write_file,
+	};
+	use std::path::Path;
+
+	#[test]
+	fn test_snapshot_changes() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "foo.txt", "a", "c1");
+
+		let first = repo_snapshot(repo_path).unwrap();
+		assert_eq!(
+			first.changes(&repo_snapshot(repo_path).unwrap()),
+			RepoChanges::empty()
+		);
+
+		let head = repo.head().unwrap().peel_to_commit().unwrap();
+		repo.branch("other", &head, false).unwrap();
+		let branched = repo_snapshot(repo_path).unwrap();
+		assert_eq!(first.changes(&branched), RepoChanges::REFS);
+
+		checkout_branch(repo_path, "other").unwrap();
+		let checked_out = repo_snapshot(repo_path).unwrap();
+		assert!(branched
+			.changes(&checked_out)
+			.contains(RepoChanges::HEAD));
+
+		repo_write_file(&repo, "bar.txt", "some more").unwrap();
+		stage_add_file(repo_path, Path::new("bar.txt")).unwrap();
+		let staged = repo_snapshot(repo_path).unwrap();
+		assert_eq!(checked_out.changes(&staged), RepoChanges::INDEX);
+	}
+}
@@ -0,0 +1,150 @@
+//! the pseudo refs git leaves next to `HEAD` while or after moving
+//! it, like `ORIG_HEAD`
+
+use super::{repository::repo, CommitId, RepoPath};
+use crate::error::Result;
+use git2::Repository;
+use scopetime::scope_time;
+use std::fmt;
+
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpecialRef {
+	/// where `HEAD` was before a reset, merge or rebase
+	OrigHead,
+	/// the commits being merged
+	MergeHead,
+	/// the commit being cherry-picked
+	CherryPickHead,
+	/// what the last fetch fetched for merging
+	FetchHead,
+}
+
+impl SpecialRef {
+	///
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::OrigHead => "ORIG_HEAD",
+			Self::MergeHead => "MERGE_HEAD",
+			Self::CherryPickHead => "CHERRY_PICK_HEAD",
+			Self::FetchHead => "FETCH_HEAD",
+		}
+	}
+}
+
+impl fmt::Display for SpecialRef {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+/// the special refs that exist, `MERGE_HEAD` once per merged commit
+pub fn get_special_refs(
+	repo_path: &RepoPath,
+) -> Result<Vec<(SpecialRef, CommitId)>> {
+	scope_time!("get_special_refs");
+
+	let mut repo = repo(repo_path)?;
+
+	let mut refs = Vec::new();
+
+	if let Some(id) = direct_ref(&repo, SpecialRef::OrigHead) {
+		refs.push((SpecialRef::OrigHead, id));
+	}
+
+	repo.mergehead_foreach(|id| {
+		refs.push((SpecialRef::MergeHead, (*id).into()));
+		true
+	})
+	.ok();
+
+	if let Some(id) = direct_ref(&repo, SpecialRef::CherryPickHead) {
+		refs.push((SpecialRef::CherryPickHead, id));
+	}
+
+	if let Some(id) = fetch_head(&repo) {
+		refs.push((SpecialRef::FetchHead, id));
+	}
+
+	Ok(refs)
+}
+
+fn direct_ref(
+	repo: &Repository,
+	special: SpecialRef,
+) -> Option<CommitId> {
+	let reference = repo.find_reference(special.name()).ok()?;
+	let commit = reference.peel_to_commit().ok()?;
+
+	Some(commit.id().into())
+}
+
+/// like git, the first fetched head that was not marked
+/// `not-for-merge`, or the first one if all are
+fn fetch_head(repo: &Repository) -> Option<CommitId> {
+	let mut first = None;
+	let mut for_merge = None;
+
+	repo.fetchhead_foreach(|_, _, id, is_merge| {
+		first.get_or_insert(*id);
+		if is_merge {
+			for_merge = Some(*id);
+			return false;
+		}
+		true
+	})
+	.ok();
+
+	let id = for_merge.or(first)?;
+
+	// fetched tags can point to anything
+	repo.find_commit(id).ok().map(|commit| commit.id().into())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::tests::{repo_init, write_commit_file};
+	use std::fs;
+
+	#[test]
+	fn test_special_refs() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let first = write_commit_file(&repo, "a.txt", "a", "a");
+		let second = write_commit_file(&repo, "a.txt", "b", "b");
+
+		assert!(get_special_refs(repo_path).unwrap().is_empty());
+
+		fs::write(
+			repo.path().join("ORIG_HEAD"),
+			format!("{first}\n"),
+		)
+		.unwrap();
+		fs::write(
+			repo.path().join("MERGE_HEAD"),
+			format!("{first}\n{second}\n"),
+		)
+		.unwrap();
+		fs::write(
+			repo.path().join("FETCH_HEAD"),
+			format!(
+				"{first}\tnot-for-merge\tbranch 'a' of ../r\n{second}\t\tbranch 'b' of ../r\n"
+			),
+		)
+		.unwrap();
+
+		assert_eq!(
+			get_special_refs(repo_path).unwrap(),
+			vec![
+				(SpecialRef::OrigHead, first),
+				(SpecialRef::MergeHead, first),
+				(SpecialRef::MergeHead, second),
+				(SpecialRef::FetchHead, second),
+			]
+		);
+	}
+}
@@ -0,0 +1,706 @@
+//! the commits of the current branch on top of its upstream as a
+//! stack of patches that can be reordered and fixed up.
+//!
+//! the stack is rewritten by the interactive rebase, which stops on
+//! conflicts to be continued or aborted like any other rebase. with
+//! uncommitted changes around it cannot stop, conflicts change
+//! nothing then
+
+use super::{
+	commit::signature_allow_undefined_name,
+	rebase::RebaseState,
+	rebase_interactive::{self, RebaseAction, RebaseTodo},
+	repository::repo,
+	CommitId, RepoPath,
+};
+use crate::error::{Error, Result};
+use git2::{BranchType, Oid, Repository, Sort};
+use scopetime::scope_time;
+
+/// the current branch as a stack over its upstream
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchStack {
+	/// the current branch
+	pub branch: String,
+	/// upstream of `branch`
+	pub upstream: String,
+	///
+	pub upstream_type: BranchType,
+	/// commits the upstream has that the stack is not based on yet
+	pub behind: usize,
+	/// merge base of the branch and its upstream
+	pub base: CommitId,
+	/// oldest first
+	pub commits: Vec<CommitId>,
+	/// `rebase.autoSquash`, arrange `fixup!` and `squash!` commits
+	/// right away
+	pub autosquash: bool,
+}
+
+/// what to do with a commit of the stack
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackAction {
+	///
+	Pick,
+	/// fold into the commit before, keeping its message
+	Fixup,
+	/// fold into the commit before, joining both messages
+	Squash,
+}
+
+/// a commit of the stack and what to do with it when rewriting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StackTodo {
+	///
+	pub id: CommitId,
+	///
+	pub action: StackAction,
+}
+
+/// the commits of the current branch not in its upstream
+pub fn get_patch_stack(repo_path: &RepoPath) -> Result<PatchStack> {
+	scope_time!("get_patch_stack");
+
+	let repo = repo(repo_path)?;
+
+	patch_stack(&repo)
+}
+
+/// the stack with `fixup!` and `squash!` commits moved right after
+/// the commit they refer to, like `git rebase --autosquash`
+pub fn stack_autosquash_todo(
+	repo_path: &RepoPath,
+	stack: &PatchStack,
+) -> Result<Vec<StackTodo>> {
+	scope_time!("stack_autosquash_todo");
+
+	let repo = repo(repo_path)?;
+
+	let commits = stack
+		.commits
+		.iter()
+		.map(|id| {
+			let commit = repo.find_commit(id.get_oid())?;
+			let summary = commit.summary().unwrap_or_default();
+			Ok((*id, summary.to_string()))
+		})
+		.collect::<Result<Vec<_>>>()?;
+
+	Ok(autosquash(&commits))
+}
+
+/// rewrites the stack as `todo` (oldest first) says, which has to
+/// hold exactly the commits of the stack
+pub fn stack_rewrite(
+	repo_path: &RepoPath,
+	todo: &[StackTodo],
+) -> Result<RebaseState> {
+	scope_time!("stack_rewrite");
+
+	let repo = repo(repo_path)?;
+	let stack = patch_stack(&repo)?;
+
+	let mut sorted_todo: Vec<_> = todo.iter().map(|t| t.id).collect();
+	let mut sorted_stack = stack.commits.clone();
+	sorted_todo.sort();
+	sorted_stack.sort();
+	if sorted_todo != sorted_stack {
+		return Err(Error::Generic(String::from(
+			"new order does not match the commits of the stack",
+		)));
+	}
+	if todo.first().is_some_and(|t| t.action != StackAction::Pick) {
+		return Err(Error::Generic(String::from(
+			"the first commit has nothing to be folded into",
+		)));
+	}
+
+	let todo: Vec<_> = todo
+		.iter()
+		.map(|t| RebaseTodo {
+			id: t.id,
+			action: t.action.into(),
+			message: None,
+		})
+		.collect();
+
+	rewrite(&repo, stack.base.get_oid(), &todo)
+}
+
+/// folds the staged changes into `target` of the stack (autofixup),
+/// the commits after it are rebased on top.
+///
+/// the staged changes are committed as `fixup!` commit first, which
+/// is what is left on top when a stopped rewrite gets aborted
+pub fn stack_fixup(
+	repo_path: &RepoPath,
+	target: CommitId,
+) -> Result<RebaseState> {
+	scope_time!("stack_fixup");
+
+	let repo = repo(repo_path)?;
+	let stack = patch_stack(&repo)?;
+
+	if !stack.commits.contains(&target) {
+		return Err(Error::Generic(String::from(
+			"commit is not part of the stack",
+		)));
+	}
+
+	let mut index = repo.index()?;
+	if index.has_conflicts() {
+		return Err(Error::Generic(String::from(
+			"cannot fixup with conflicts in the index",
+		)));
+	}
+
+	let head = repo.head()?.peel_to_commit()?;
+	let staged_tree = repo.find_tree(index.write_tree()?)?;
+	if staged_tree.id() == head.tree_id() {
+		return Err(Error::Generic(String::from(
+			"nothing staged to fixup",
+		)));
+	}
+
+	if repo.config()?.get_bool("commit.gpgsign").unwrap_or(false) {
+		return Err(Error::SignStackRewrite);
+	}
+
+	let summary = repo
+		.find_commit(target.get_oid())?
+		.summary()
+		.unwrap_or_default()
+		.to_string();
+	let signature = signature_allow_undefined_name(&repo)?;
+	let fixup = repo.commit(
+		Some("HEAD"),
+		&signature,
+		&signature,
+		&format!("fixup! {summary}"),
+		&staged_tree,
+		&[&head],
+	)?;
+
+	let mut todo = Vec::new();
+	for id in &stack.commits {
+		todo.push(RebaseTodo::pick(*id));
+		if *id == target {
+			todo.push(RebaseTodo {
+				id: fixup.into(),
+				action: RebaseAction::Fixup,
+				message: None,
+			});
+		}
+	}
+
+	rewrite(&repo, stack.base.get_oid(), &todo).inspect_err(|_| {
+		// the staged changes are still staged
+		let _ = repo.head().and_then(|branch| {
+			repo.reference(
+				branch.name().unwrap_or_default(),
+				head.id(),
+				true,
+				"gitui: stack fixup failed",
+			)
+		});
+	})
+}
+
+pub(super) fn patch_stack(repo: &Repository) -> Result<PatchStack> {
+	let head = repo.head()?;
+	if !head.is_branch() {
+		return Err(Error::Generic(String::from(
+			"HEAD is not on a branch",
+		)));
+	}
+
+	let branch_name =
+		head.shorthand().unwrap_or_default().to_string();
+	let branch = repo.find_branch(&branch_name, BranchType::Local)?;
+	let upstream = branch.upstream()?;
+	let upstream_type = if upstream.get().is_remote() {
+		BranchType::Remote
+	} else {
+		BranchType::Local
+	};
+	let upstream_name =
+		upstream.name()?.unwrap_or_default().to_string();
+
+	let head_id = head.peel_to_commit()?.id();
+	let upstream_id = upstream.get().peel_to_commit()?.id();
+	let base = repo.merge_base(head_id, upstream_id)?;
+	let (_, behind) =
+		repo.graph_ahead_behind(head_id, upstream_id)?;
+
+	let mut walk = repo.revwalk()?;
+	walk.push(head_id)?;
+	walk.hide(base)?;
+	walk.simplify_first_parent()?;
+	walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+
+	let autosquash = repo
+		.config()?
+		.get_bool("rebase.autoSquash")
+		.unwrap_or(false);
+
+	let mut commits = Vec::new();
+	for id in walk {
+		let id = id?;
+		if repo.find_commit(id)?.parent_count() > 1 {
+			return Err(Error::Generic(String::from(
+				"the stack contains a merge commit",
+			)));
+		}
+		commits.push(id.into());
+	}
+
+	Ok(PatchStack {
+		branch: branch_name,
+		upstream: upstream_name,
+		upstream_type,
+		behind,
+		base: base.into(),
+		commits,
+		autosquash,
+	})
+}
+
+/// the subject a `fixup!` or `squash!` commit refers to
+fn autosquash_target(summary: &str) -> Option<(StackAction, &str)> {
+	let (action, mut target) = if let Some(target) =
+		summary.strip_prefix("fixup! ")
+	{
+		(StackAction::Fixup, target)
+	} else if let Some(target) = summary.strip_prefix("squash! ") {
+		(StackAction::Squash, target)
+	} else {
+		return None;
+	};
+
+	// `fixup! fixup! x` refers to `x` as well
+	while let Some(inner) = target
+		.strip_prefix("fixup! ")
+		.or_else(|| target.strip_prefix("squash! "))
+	{
+		target = inner;
+	}
+
+	Some((action, target))
+}
+
+/// `commits` (oldest first, with their summaries) in autosquash order.
+/// targets are matched by subject, then by id prefix, then by subject
+/// prefix, like git does
+pub(super) fn autosquash(
+	commits: &[(CommitId, String)],
+) -> Vec<StackTodo> {
+	let mut todo: Vec<StackTodo> = Vec::new();
+	// fixups that found their target, by position in `commits`
+	let mut moved: Vec<Vec<StackTodo>> =
+		vec![Vec::new(); commits.len()];
+	let mut is_moved = vec![false; commits.len()];
+
+	for (idx, (id, summary)) in commits.iter().enumerate() {
+		let Some((action, target)) = autosquash_target(summary)
+		else {
+			continue;
+		};
+
+		let earlier = &commits[..idx];
+		let found = earlier
+			.iter()
+			.position(|(_, s)| s == target)
+			.or_else(|| {
+				earlier.iter().position(|(c, _)| {
+					target.len() >= 4
+						&& c.to_string().starts_with(target)
+				})
+			})
+			.or_else(|| {
+				earlier
+					.iter()
+					.position(|(_, s)| s.starts_with(target))
+			});
+
+		if let Some(found) = found {
+			// a fixup of a fixup goes after the target of that one
+			let mut found = found;
+			while let Some(outer) = moved.iter().position(|m| {
+				m.iter().any(|t| t.id == commits[found].0)
+			}) {
+				found = outer;
+			}
+
+			moved[found].push(StackTodo { id: *id, action });
+			is_moved[idx] = true;
+		}
+	}
+
+	for (idx, (id, _)) in commits.iter().enumerate() {
+		if is_moved[idx] {
+			continue;
+		}
+
+		todo.push(StackTodo {
+			id: *id,
+			action: StackAction::Pick,
+		});
+		todo.extend(moved[idx].iter().copied());
+	}
+
+	todo
+}
+
+/// rebases the commits after `base` as `todo` says, uncommitted
+/// changes are kept as they are
+pub(super) fn rewrite(
+	repo: &Repository,
+	base: Oid,
+	todo: &[RebaseTodo],
+) -> Result<RebaseState> {
+	if repo.config()?.get_bool("commit.gpgsign").unwrap_or(false) {
+		return Err(Error::SignStackRewrite);
+	}
+
+	rebase_interactive::start(repo, base, todo, true)
+}
+
+/// `message` with the body of the squashed commit added, its
+/// `squash!` subject left out
+pub(super) fn squash_message(
+	message: &str,
+	squashed: &str,
+) -> String {
+	let body = squashed
+		.strip_prefix("squash! ")
+		.map_or(squashed, |rest| {
+			rest.split_once('\n').map_or("", |(_, body)| body)
+		})
+		.trim();
+
+	if body.is_empty() {
+		message.to_string()
+	} else {
+		format!("{}\n\n{body}\n", message.trim_end())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{
+		autosquash, get_patch_stack, stack_autosquash_todo,
+		stack_fixup, stack_rewrite, StackAction, StackTodo,
+	};
+	use crate::sync::diff::get_diff;
+	use crate::{
+		error::Error,
+		sync::{
+			abort_pending_rebase, checkout_branch,
+			continue_pending_rebase, create_branch, get_head,
+			rebase::RebaseState,
+			repo_state, stage_add_file,
+			tests::{repo_init, write_commit_file},
+			utils::{repo_read_file, repo_write_file},
+			CommitId, RepoPath, RepoState,
+		},
+	};
+	use git2::{BranchType, Repository};
+	use std::path::Path;
+
+	fn picks(ids: &[CommitId]) -> Vec<StackTodo> {
+		ids.iter()
+			.map(|id| StackTodo {
+				id: *id,
+				action: StackAction::Pick,
+			})
+			.collect()
+	}
+
+	/// `feature` with `a.txt`, `b.txt` and `c.txt` committed on top
+	/// of its upstream `master`
+	fn stack_repo(
+		repo: &Repository,
+		repo_path: &RepoPath,
+	) -> Vec<crate::sync::CommitId> {
+		write_commit_file(repo, "base.txt", "base", "base");
+		create_branch(repo_path, "feature").unwrap();
+		checkout_branch(repo_path, "feature").unwrap();
+		repo.find_branch("feature", BranchType::Local)
+			.unwrap()
+			.set_upstream(Some("master"))
+			.unwrap();
+
+		vec![
+			write_commit_file(repo, "a.txt", "a\n", "add a"),
+			write_commit_file(repo, "b.txt", "b\n", "add b"),
+			write_commit_file(repo, "c.txt", "c\n", "add c"),
+		]
+	}
+
+	fn summaries(repo: &Repository) -> Vec<String> {
+		let mut walk = repo.revwalk().unwrap();
+		walk.push_head().unwrap();
+		walk.map(|id| {
+			repo.find_commit(id.unwrap())
+				.unwrap()
+				.summary()
+				.unwrap()
+				.to_string()
+		})
+		.collect()
+	}
+
+	#[test]
+	fn test_get_and_reorder() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let ids = stack_repo(&repo, repo_path);
+
+		let stack = get_patch_stack(repo_path).unwrap();
+		assert_eq!(stack.branch, "feature");
+		assert_eq!(stack.upstream, "master");
+		assert_eq!(stack.behind, 0);
+		assert_eq!(stack.commits, ids);
+
+		assert_eq!(
+			stack_rewrite(
+				repo_path,
+				&picks(&[ids[0], ids[2], ids[1]]),
+			)
+			.unwrap(),
+			RebaseState::Finished
+		);
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+		assert_eq!(
+			summaries(&repo)[..3],
+			["add b", "add c", "add a"]
+		);
+
+		// the bottom of the stack kept its id
+		let stack = get_patch_stack(repo_path).unwrap();
+		assert_eq!(stack.commits[0], ids[0]);
+
+		assert!(stack_rewrite(repo_path, &picks(&ids[..2])).is_err());
+	}
+
+	#[test]
+	fn test_fixup() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let ids = stack_repo(&repo, repo_path);
+
+		assert!(stack_fixup(repo_path, ids[0]).is_err());
+
+		repo_write_file(&repo, "a.txt", "a\nfixed\n").unwrap();
+		stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+
+		stack_fixup(repo_path, ids[0]).unwrap();
+
+		let stack = get_patch_stack(repo_path).unwrap();
+		assert_eq!(stack.commits.len(), 3);
+		assert_eq!(
+			summaries(&repo)[..3],
+			["add c", "add b", "add a"]
+		);
+
+		let fixed =
+			repo.find_commit(stack.commits[0].into()).unwrap();
+		let blob = fixed
+			.tree()
+			.unwrap()
+			.get_name("a.txt")
+			.unwrap()
+			.to_object(&repo)
+			.unwrap()
+			.peel_to_blob()
+			.unwrap();
+		assert_eq!(blob.content(), b"a\nfixed\n");
+
+		// nothing left staged
+		assert!(matches!(
+			stack_fixup(repo_path, ids[0]),
+			Err(Error::Generic(_))
+		));
+	}
+
+	#[test]
+	fn test_fixup_keeps_unstaged() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let ids = stack_repo(&repo, repo_path);
+
+		repo_write_file(&repo, "a.txt", "a\nfixed\n").unwrap();
+		stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+		repo_write_file(&repo, "a.txt", "a\nfixed\nunstaged\n")
+			.unwrap();
+
+		assert_eq!(
+			stack_fixup(repo_path, ids[0]).unwrap(),
+			RebaseState::Finished
+		);
+
+		assert_eq!(
+			summaries(&repo)[..3],
+			["add c", "add b", "add a"]
+		);
+		assert_eq!(
+			repo_read_file(&repo, "a.txt").unwrap(),
+			"a\nfixed\nunstaged\n"
+		);
+		assert!(get_diff(repo_path, "a.txt", true, None)
+			.unwrap()
+			.hunks
+			.is_empty());
+	}
+
+	#[test]
+	fn test_conflict_stops() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let mut ids = stack_repo(&repo, repo_path);
+		ids.push(write_commit_file(&repo, "a.txt", "a2\n", "edit a"));
+		let head = get_head(repo_path).unwrap();
+
+		assert_eq!(
+			stack_rewrite(
+				repo_path,
+				&picks(&[ids[3], ids[1], ids[2], ids[0]])
+			)
+			.unwrap(),
+			RebaseState::Conflicted
+		);
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Rebase);
+
+		// resolved by taking the version of the stack
+		repo_write_file(&repo, "a.txt", "a2\n").unwrap();
+		stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+		assert_eq!(
+			continue_pending_rebase(repo_path).unwrap(),
+			RebaseState::Conflicted
+		);
+
+		abort_pending_rebase(repo_path).unwrap();
+		assert_eq!(get_head(repo_path).unwrap(), head);
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+	}
+
+	#[test]
+	fn test_conflict_with_changes_changes_nothing() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let mut ids = stack_repo(&repo, repo_path);
+		ids.push(write_commit_file(&repo, "a.txt", "a2\n", "edit a"));
+		let head = get_head(repo_path).unwrap();
+		repo_write_file(&repo, "b.txt", "b2\n").unwrap();
+
+		assert!(matches!(
+			stack_rewrite(
+				repo_path,
+				&picks(&[ids[3], ids[1], ids[2], ids[0]])
+			),
+			Err(Error::RebaseConflict)
+		));
+		assert_eq!(get_head(repo_path).unwrap(), head);
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+		assert_eq!(repo_read_file(&repo, "b.txt").unwrap(), "b2\n");
+	}
+
+	#[test]
+	fn test_autosquash_order() {
+		let commits: Vec<(CommitId, String)> = [
+			"a",
+			"b",
+			"fixup! a",
+			"squash! b",
+			"fixup! fixup! a",
+			"c",
+		]
+		.iter()
+		.enumerate()
+		.map(|(idx, summary)| {
+			let mut bytes = [0_u8; 20];
+			bytes[0] = u8::try_from(idx).unwrap() + 1;
+			(
+				CommitId::new(git2::Oid::from_bytes(&bytes).unwrap()),
+				(*summary).to_string(),
+			)
+		})
+		.collect();
+
+		let todo: Vec<_> = autosquash(&commits)
+			.into_iter()
+			.map(|t| {
+				let idx =
+					commits.iter().position(|(id, _)| *id == t.id);
+				(idx.unwrap(), t.action)
+			})
+			.collect();
+
+		assert_eq!(
+			todo,
+			vec![
+				(0, StackAction::Pick),
+				(2, StackAction::Fixup),
+				(4, StackAction::Fixup),
+				(1, StackAction::Pick),
+				(3, StackAction::Squash),
+				(5, StackAction::Pick),
+			]
+		);
+	}
+
+	#[test]
+	fn test_autosquash_rewrite() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		stack_repo(&repo, repo_path);
+		write_commit_file(&repo, "a.txt", "a2\n", "fixup! add a");
+		write_commit_file(
+			&repo,
+			"b.txt",
+			"b2\n",
+			"squash! add b\n\nmore b",
+		);
+		repo.config()
+			.unwrap()
+			.set_bool("rebase.autoSquash", true)
+			.unwrap();
+
+		let stack = get_patch_stack(repo_path).unwrap();
+		assert!(stack.autosquash);
+
+		let todo = stack_autosquash_todo(repo_path, &stack).unwrap();
+		stack_rewrite(repo_path, &todo).unwrap();
+
+		let stack = get_patch_stack(repo_path).unwrap();
+		assert_eq!(stack.commits.len(), 3);
+
+		let b = repo.find_commit(stack.commits[1].into()).unwrap();
+		assert_eq!(b.message().unwrap(), "add b\n\nmore b\n");
+		assert_eq!(
+			repo.find_commit(stack.commits[0].into())
+				.unwrap()
+				.message()
+				.unwrap(),
+			"add a"
+		);
+	}
+}
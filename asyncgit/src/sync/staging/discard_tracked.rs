@@ -1,13 +1,18 @@
-use super::{apply_selection, load_file};
+use super::apply_selection;
 use crate::{
 	error::Result,
 	sync::{
-		diff::DiffLinePosition, patches::get_file_diff_patch,
-		patches::patch_get_hunklines, repository::repo,
-		utils::repo_write_file, RepoPath,
+		diff::DiffLinePosition,
+		filter::{to_git, to_workdir},
+		patches::get_file_diff_patch,
+		patches::patch_get_hunklines,
+		repository::repo,
+		utils::work_dir,
+		RepoPath,
 	},
 };
 use scopetime::scope_time;
+use std::fs;
 
 /// discards specific lines in an unstaged hunk of a diff
 pub fn discard_lines(
@@ -31,13 +36,22 @@ pub fn discard_lines(
 			get_file_diff_patch(&repo, file_path, false, false)?;
 		let hunks = patch_get_hunklines(&patch)?;
 
-		let working_content = load_file(&repo, file_path)?;
+		// the hunks are in git's form, so is the result
+		let file = work_dir(&repo)?.join(file_path);
+		let working_content = String::from_utf8(to_git(
+			repo_path,
+			file_path,
+			&fs::read(file)?,
+		)?)?;
 		let old_lines = working_content.lines().collect::<Vec<_>>();
 
 		apply_selection(lines, &hunks, &old_lines, false, true)?
 	};
 
-	repo_write_file(&repo, file_path, new_content.as_str())?;
+	fs::write(
+		work_dir(&repo)?.join(file_path),
+		to_workdir(repo_path, file_path, new_content.as_bytes())?,
+	)?;
 
 	Ok(())
 }
@@ -45,7 +59,11 @@ pub fn discard_lines(
 #[cfg(test)]
 mod test {
 	use super::*;
-	use crate::sync::tests::{repo_init, write_commit_file};
+	use crate::sync::{
+		staging::load_file,
+		tests::{repo_init, write_commit_file},
+		utils::repo_write_file,
+	};
 
 	#[test]
 	fn test_discard() {
@@ -98,6 +116,37 @@ mod test {
 		assert_eq!(result_file.as_str(), FILE_3);
 	}
 
+	#[test]
+	fn test_discard_keeps_eol() {
+		let (path, repo) = repo_init().unwrap();
+		let path: &RepoPath = &path.path().to_str().unwrap().into();
+
+		write_commit_file(
+			&repo,
+			".gitattributes",
+			"*.txt text eol=crlf\n",
+			"attributes",
+		);
+		write_commit_file(&repo, "test.txt", "0\r\n1\r\n", "c1");
+
+		repo_write_file(&repo, "test.txt", "0\r\nx\r\n1\r\n")
+			.unwrap();
+
+		discard_lines(
+			path,
+			"test.txt",
+			&[DiffLinePosition {
+				old_lineno: None,
+				new_lineno: Some(2),
+			}],
+		)
+		.unwrap();
+
+		let result_file = load_file(&repo, "test.txt").unwrap();
+
+		assert_eq!(result_file.as_str(), "0\r\n1\r\n");
+	}
+
 	#[test]
 	fn test_discard2() {
 		static FILE_1: &str = r"start
@@ -2,14 +2,12 @@ mod discard_tracked;
 mod stage_tracked;
 
 pub use discard_tracked::discard_lines;
-pub use stage_tracked::stage_lines;
+pub use stage_tracked::{stage_lines, unstage_lines};
 
-use super::{
-	diff::DiffLinePosition, patches::HunkLines, utils::work_dir,
-};
+use super::{diff::DiffLinePosition, patches::HunkLines};
 use crate::error::Result;
-use git2::{DiffLine, DiffLineType, Repository};
-use std::{collections::HashSet, fs::File, io::Read};
+use git2::{DiffLine, DiffLineType};
+use std::collections::HashSet;
 
 const NEWLINE: char = '\n';
 
@@ -171,11 +169,14 @@ pub fn apply_selection(
 	Ok(new_content.finish(old_lines))
 }
 
+#[cfg(test)]
 pub fn load_file(
-	repo: &Repository,
+	repo: &git2::Repository,
 	file_path: &str,
 ) -> Result<String> {
-	let repo_path = work_dir(repo)?;
+	use std::{fs::File, io::Read};
+
+	let repo_path = super::utils::work_dir(repo)?;
 	let mut file = File::open(repo_path.join(file_path).as_path())?;
 	let mut res = String::new();
 	file.read_to_string(&mut res)?;
@@ -7,33 +7,58 @@ use crate::{
 	},
 };
 use easy_cast::Conv;
+use git2::{Index, IndexEntry, Repository};
 use scopetime::scope_time;
 use std::path::Path;
 
-///
+/// stages the selected `lines` of the workdir diff of `file_path`,
+/// an untracked file gets added with just these lines
 pub fn stage_lines(
 	repo_path: &RepoPath,
 	file_path: &str,
-	is_stage: bool,
 	lines: &[DiffLinePosition],
 ) -> Result<()> {
 	scope_time!("stage_lines");
 
+	apply_lines(repo_path, file_path, false, lines)
+}
+
+/// unstages the selected `lines` of the staged diff of `file_path`
+pub fn unstage_lines(
+	repo_path: &RepoPath,
+	file_path: &str,
+	lines: &[DiffLinePosition],
+) -> Result<()> {
+	scope_time!("unstage_lines");
+
+	apply_lines(repo_path, file_path, true, lines)
+}
+
+fn apply_lines(
+	repo_path: &RepoPath,
+	file_path: &str,
+	is_stage: bool,
+	lines: &[DiffLinePosition],
+) -> Result<()> {
 	if lines.is_empty() {
 		return Ok(());
 	}
 
 	let repo = repo(repo_path)?;
-	// log::debug!("stage_lines: {:?}", lines);
 
 	let mut index = repo.index()?;
 	index.read(true)?;
-	let mut idx =
-		index.get_path(Path::new(file_path), 0).ok_or_else(|| {
-			Error::Generic(String::from(
-				"only non new files supported",
-			))
-		})?;
+	let mut idx = match index.get_path(Path::new(file_path), 0) {
+		Some(idx) => idx,
+		None if !is_stage => {
+			add_empty_entry(&repo, &mut index, file_path)?
+		}
+		None => {
+			return Err(Error::Generic(format!(
+				"'{file_path}' is not staged"
+			)))
+		}
+	};
 	let blob = repo.find_blob(idx.id)?;
 	let indexed_content = String::from_utf8(blob.content().into())?;
 
@@ -59,6 +84,26 @@ pub fn stage_lines(
 	Ok(())
 }
 
+/// adds an untracked file to the (not yet written) index without any
+/// content, so all of its lines show up in the workdir diff
+fn add_empty_entry(
+	repo: &Repository,
+	index: &mut Index,
+	file_path: &str,
+) -> Result<IndexEntry> {
+	let path = Path::new(file_path);
+	index.add_path(path)?;
+
+	let mut idx = index.get_path(path, 0).ok_or_else(|| {
+		Error::Generic(format!("failed to add '{file_path}'"))
+	})?;
+	idx.id = repo.blob(&[])?;
+	idx.file_size = 0;
+	index.add(&idx)?;
+
+	Ok(idx)
+}
+
 #[cfg(test)]
 mod test {
 	use super::*;
@@ -89,7 +134,6 @@ mod test {
 		stage_lines(
 			path,
 			"test.txt",
-			false,
 			&[DiffLinePosition {
 				old_lineno: None,
 				new_lineno: Some(2),
@@ -103,6 +147,38 @@ mod test {
 		assert_eq!(&*diff.hunks[0].lines[0].content, "@@ -1 +1,2 @@");
 	}
 
+	#[test]
+	fn test_stage_untracked() {
+		static FILE: &str = r"0
+1
+2
+";
+
+		let (path, repo) = repo_init().unwrap();
+		let path: &RepoPath = &path.path().to_str().unwrap().into();
+
+		repo_write_file(&repo, "test.txt", FILE).unwrap();
+
+		assert_eq!(get_statuses(path), (1, 0));
+
+		stage_lines(
+			path,
+			"test.txt",
+			&[DiffLinePosition {
+				old_lineno: None,
+				new_lineno: Some(2),
+			}],
+		)
+		.unwrap();
+
+		assert_eq!(get_statuses(path), (1, 1));
+
+		let diff = get_diff(path, "test.txt", true, None).unwrap();
+
+		assert_eq!(diff.lines, 2);
+		assert_eq!(&*diff.hunks[0].lines[1].content, "1");
+	}
+
 	#[test]
 	fn test_panic_stage_no_newline() {
 		static FILE_1: &str = r"a = 1
@@ -122,7 +198,6 @@ c = 4";
 		stage_lines(
 			path,
 			"test.txt",
-			false,
 			&[
 				DiffLinePosition {
 					old_lineno: Some(1),
@@ -171,10 +246,9 @@ c = 4";
 
 		assert_eq!(diff_before.lines, 5);
 
-		stage_lines(
+		unstage_lines(
 			path,
 			"test.txt",
-			true,
 			&[DiffLinePosition {
 				old_lineno: None,
 				new_lineno: Some(2),
@@ -1,13 +1,17 @@
 use super::{CommitId, RepoPath};
 use crate::{
 	error::{Error, Result},
-	sync::repository::repo,
+	sync::{
+		commit::signature_allow_undefined_name, repository::repo,
+	},
 };
 use git2::{
-	build::CheckoutBuilder, Oid, Repository, StashApplyOptions,
-	StashFlags,
+	build::CheckoutBuilder, Commit, Index, IndexEntry, IndexTime,
+	Oid, Repository, StashApplyOptions, StashFlags, StashSaveOptions,
+	StatusOptions,
 };
 use scopetime::scope_time;
+use std::{fs, path::Path};
 
 ///
 pub fn get_stashes(repo_path: &RepoPath) -> Result<Vec<CommitId>> {
@@ -63,6 +67,26 @@ pub fn stash_apply(
 ) -> Result<()> {
 	scope_time!("stash_apply");
 
+	apply(repo_path, stash_id, allow_conflicts, false)
+}
+
+/// like `stash_apply` but also restores what was staged when the
+/// stash was saved, failing if the staged changes conflict
+pub fn stash_apply_index(
+	repo_path: &RepoPath,
+	stash_id: CommitId,
+) -> Result<()> {
+	scope_time!("stash_apply_index");
+
+	apply(repo_path, stash_id, false, true)
+}
+
+fn apply(
+	repo_path: &RepoPath,
+	stash_id: CommitId,
+	allow_conflicts: bool,
+	reinstate_index: bool,
+) -> Result<()> {
 	let mut repo = repo(repo_path)?;
 
 	let index = get_stash_index(&mut repo, stash_id.get_oid())?;
@@ -72,6 +96,9 @@ pub fn stash_apply(
 
 	let mut opt = StashApplyOptions::default();
 	opt.checkout_options(checkout);
+	if reinstate_index {
+		opt.reinstantiate_index();
+	}
 	repo.stash_apply(index, Some(&mut opt))?;
 
 	Ok(())
@@ -124,6 +151,291 @@ pub fn stash_save(
 	Ok(CommitId::new(id))
 }
 
+/// options of `stash_push_paths`
+#[derive(Default, Clone, Debug)]
+pub struct StashPushOptions {
+	/// stash message, libgit2 picks one if `None`
+	pub message: Option<String>,
+	/// leave the staged changes of the paths in the index
+	pub keep_index: bool,
+	/// only stash untracked files and leave tracked changes alone
+	pub untracked_only: bool,
+}
+
+/// like `stash_save` but only stashes the changes of `paths`,
+/// untracked files in `paths` get added to the index to be part of
+/// the stash unless `keep_index` is set.
+///
+/// with `untracked_only` the untracked files in `paths` (or all of
+/// them if `paths` is empty) are stashed as untracked files.
+pub fn stash_push_paths(
+	repo_path: &RepoPath,
+	paths: &[String],
+	opts: &StashPushOptions,
+) -> Result<CommitId> {
+	scope_time!("stash_push_paths");
+
+	if opts.untracked_only {
+		return stash_untracked(repo_path, paths, opts);
+	}
+
+	if paths.is_empty() {
+		return stash_save(
+			repo_path,
+			opts.message.as_deref(),
+			true,
+			opts.keep_index,
+		);
+	}
+
+	let keep_index = opts.keep_index;
+	let mut repo = repo(repo_path)?;
+
+	// libgit2 stashes all untracked files regardless of the paths
+	if !keep_index {
+		let mut index = repo.index()?;
+		for path in paths {
+			if repo.status_file(Path::new(path))?.is_wt_new() {
+				index.add_path(Path::new(path))?;
+			}
+		}
+		index.write()?;
+	}
+
+	// libgit2 resets the whole workdir after stashing paths, so we
+	// keep everything and reset only `paths` ourselves
+	let mut options = StashSaveOptions::new(repo.signature()?);
+	options.flags(Some(StashFlags::KEEP_ALL));
+	for path in paths {
+		options.pathspec(path.as_str());
+	}
+
+	let mut id = repo.stash_save_ext(Some(&mut options))?;
+
+	let mut checkout = CheckoutBuilder::new();
+	checkout.force();
+	for path in paths {
+		checkout.path(path);
+	}
+
+	if keep_index {
+		repo.checkout_index(None, Some(&mut checkout))?;
+	} else {
+		let head = repo.head()?.peel_to_tree()?;
+		repo.checkout_tree(head.as_object(), Some(&mut checkout))?;
+	}
+
+	if let Some(message) = &opts.message {
+		id = set_stash_message(&mut repo, id, message)?;
+	}
+
+	Ok(CommitId::new(id))
+}
+
+/// libgit2 always stashes all untracked files, so the stash commits
+/// are built here: index and worktree commit stay at `HEAD` and only
+/// the untracked files end up in the third parent
+fn stash_untracked(
+	repo_path: &RepoPath,
+	paths: &[String],
+	opts: &StashPushOptions,
+) -> Result<CommitId> {
+	let repo = repo(repo_path)?;
+	let workdir = repo.workdir().ok_or_else(|| {
+		Error::Generic("stash: repo has no workdir".to_string())
+	})?;
+
+	let files = untracked_files(&repo, paths)?;
+	if files.is_empty() {
+		return Err(Error::Generic(
+			"no untracked files to stash".to_string(),
+		));
+	}
+
+	let head = repo.head()?;
+	let branch = if head.is_branch() {
+		head.shorthand().unwrap_or_default().to_string()
+	} else {
+		String::from("(no branch)")
+	};
+	let head = head.peel_to_commit()?;
+	let tree = head.tree()?;
+	let sig = signature_allow_undefined_name(&repo)?;
+
+	let on_head = format!(
+		"{branch}: {} {}",
+		head.as_object().short_id()?.as_str().unwrap_or_default(),
+		head.summary().unwrap_or_default()
+	);
+
+	let mut index = Index::new()?;
+	for file in &files {
+		index.add(&untracked_entry(&repo, workdir, file)?)?;
+	}
+	let untracked_tree =
+		repo.find_tree(index.write_tree_to(&repo)?)?;
+
+	let index_commit = repo.commit(
+		None,
+		&sig,
+		&sig,
+		&format!("index on {on_head}"),
+		&tree,
+		&[&head],
+	)?;
+	let untracked_commit = repo.commit(
+		None,
+		&sig,
+		&sig,
+		&format!("untracked files on {on_head}"),
+		&untracked_tree,
+		&[],
+	)?;
+
+	let msg = opts.message.as_ref().map_or_else(
+		|| format!("WIP on {on_head}"),
+		|message| format!("On {branch}: {message}"),
+	);
+	let parents: [&Commit; 3] = [
+		&head,
+		&repo.find_commit(index_commit)?,
+		&repo.find_commit(untracked_commit)?,
+	];
+	let id = repo.commit(None, &sig, &sig, &msg, &tree, &parents)?;
+
+	repo.reference_ensure_log("refs/stash")?;
+	repo.reference("refs/stash", id, true, &msg)?;
+
+	for file in &files {
+		fs::remove_file(workdir.join(file))?;
+
+		// clean up folders that only held stashed files
+		let mut dir = Path::new(file).parent();
+		while let Some(parent) =
+			dir.filter(|d| !d.as_os_str().is_empty())
+		{
+			if fs::remove_dir(workdir.join(parent)).is_err() {
+				break;
+			}
+			dir = parent.parent();
+		}
+	}
+
+	Ok(CommitId::new(id))
+}
+
+/// untracked files that are in `paths` or in a folder of `paths`
+fn untracked_files(
+	repo: &Repository,
+	paths: &[String],
+) -> Result<Vec<String>> {
+	let mut options = StatusOptions::new();
+	options
+		.include_untracked(true)
+		.recurse_untracked_dirs(true)
+		.include_ignored(false);
+
+	let in_paths = |file: &str| {
+		paths.is_empty()
+			|| paths.iter().any(|path| {
+				file == path
+					|| file
+						.strip_prefix(path.trim_end_matches('/'))
+						.is_some_and(|rest| rest.starts_with('/'))
+			})
+	};
+
+	Ok(repo
+		.statuses(Some(&mut options))?
+		.iter()
+		.filter(|entry| entry.status().is_wt_new())
+		.filter_map(|entry| entry.path().map(String::from))
+		.filter(|file| in_paths(file))
+		.collect())
+}
+
+fn untracked_entry(
+	repo: &Repository,
+	workdir: &Path,
+	file: &str,
+) -> Result<IndexEntry> {
+	let full_path = workdir.join(file);
+	let metadata = fs::symlink_metadata(&full_path)?;
+
+	// like git a symlink is stored as its target, not what it points to
+	let (mode, id) = if metadata.is_symlink() {
+		let target = fs::read_link(&full_path)?;
+		(0o120_000, repo.blob(target.as_os_str().as_encoded_bytes())?)
+	} else {
+		#[cfg(unix)]
+		let executable = {
+			use std::os::unix::fs::PermissionsExt;
+			metadata.permissions().mode() & 0o111 != 0
+		};
+		#[cfg(not(unix))]
+		let executable = false;
+
+		(
+			if executable { 0o100_755 } else { 0o100_644 },
+			repo.blob_path(&full_path)?,
+		)
+	};
+
+	Ok(IndexEntry {
+		ctime: IndexTime::new(0, 0),
+		mtime: IndexTime::new(0, 0),
+		dev: 0,
+		ino: 0,
+		mode,
+		uid: 0,
+		gid: 0,
+		file_size: 0,
+		id,
+		flags: 0,
+		flags_extended: 0,
+		path: file.as_bytes().to_vec(),
+	})
+}
+
+/// replaces the latest stash `id` with a copy using `message`, the
+/// libgit2 options to save a stash of paths take no message
+fn set_stash_message(
+	repo: &mut Repository,
+	id: Oid,
+	message: &str,
+) -> Result<Oid> {
+	let (new_id, msg) = {
+		let stash = repo.find_commit(id)?;
+
+		// libgit2 names it "WIP on <branch>: <id> <summary>"
+		let branch = stash
+			.summary()
+			.and_then(|summary| summary.strip_prefix("WIP on "))
+			.and_then(|summary| summary.split_once(": "))
+			.map(|(branch, _)| branch.to_string())
+			.unwrap_or_default();
+		let msg = format!("On {branch}: {message}");
+
+		let parents = stash.parents().collect::<Vec<_>>();
+		let new_id = repo.commit(
+			None,
+			&stash.author(),
+			&stash.committer(),
+			&msg,
+			&stash.tree()?,
+			&parents.iter().collect::<Vec<_>>(),
+		)?;
+
+		(new_id, msg)
+	};
+
+	repo.stash_drop(0)?;
+	repo.reference_ensure_log("refs/stash")?;
+	repo.reference("refs/stash", new_id, true, &msg)?;
+
+	Ok(new_id)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -135,6 +447,7 @@ mod tests {
 		},
 		utils::{repo_read_file, repo_write_file},
 	};
+	use std::fs;
 	use std::{fs::File, io::Write, path::Path};
 
 	#[test]
@@ -168,6 +481,188 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn test_stashing_paths() -> Result<()> {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "foo.txt", "test", "commit foo");
+		write_commit_file(&repo, "bar.txt", "test", "commit bar");
+
+		repo_write_file(&repo, "foo.txt", "test\nfoo")?;
+		repo_write_file(&repo, "bar.txt", "test\nbar")?;
+		repo_write_file(&repo, "baz.txt", "untracked")?;
+		repo_write_file(&repo, "new.txt", "untracked")?;
+
+		assert_eq!(get_statuses(repo_path), (4, 0));
+
+		let stash = stash_push_paths(
+			repo_path,
+			&[String::from("foo.txt"), String::from("new.txt")],
+			&StashPushOptions {
+				message: Some(String::from("foo only")),
+				..StashPushOptions::default()
+			},
+		)?;
+
+		assert_eq!(get_statuses(repo_path), (2, 0));
+		assert_eq!(repo_read_file(&repo, "foo.txt")?, "test");
+		assert_eq!(repo_read_file(&repo, "bar.txt")?, "test\nbar");
+		assert_eq!(repo_read_file(&repo, "baz.txt")?, "untracked");
+
+		assert!(!root.join("new.txt").exists());
+
+		let files = get_commit_files(repo_path, stash, None)?;
+		assert_eq!(files.len(), 2);
+		assert_eq!(files[0].path, "foo.txt");
+		assert_eq!(files[1].path, "new.txt");
+
+		assert_eq!(get_stashes(repo_path)?, vec![stash]);
+		let info = get_commits_info(repo_path, &[stash], 100)?;
+		assert!(info[0].message.ends_with(": foo only"));
+
+		stash_pop(repo_path, stash)?;
+
+		assert_eq!(repo_read_file(&repo, "foo.txt")?, "test\nfoo");
+		assert_eq!(repo_read_file(&repo, "new.txt")?, "untracked");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_stashing_untracked_only() -> Result<()> {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "foo.txt", "test", "commit foo");
+
+		repo_write_file(&repo, "foo.txt", "test\nfoo")?;
+		repo_write_file(&repo, "new.txt", "untracked")?;
+		repo_write_file(&repo, "other.txt", "untracked")?;
+		fs::create_dir(root.join("dir"))?;
+		repo_write_file(&repo, "dir/new.txt", "nested")?;
+
+		let opts = StashPushOptions {
+			message: Some(String::from("new files")),
+			untracked_only: true,
+			..StashPushOptions::default()
+		};
+		let stash = stash_push_paths(
+			repo_path,
+			&[String::from("new.txt"), String::from("dir")],
+			&opts,
+		)?;
+
+		assert_eq!(repo_read_file(&repo, "foo.txt")?, "test\nfoo");
+		assert_eq!(repo_read_file(&repo, "other.txt")?, "untracked");
+		assert!(!root.join("new.txt").exists());
+		assert!(!root.join("dir").exists());
+
+		assert_eq!(get_stashes(repo_path)?, vec![stash]);
+		let info = get_commits_info(repo_path, &[stash], 100)?;
+		assert_eq!(info[0].message, "On master: new files");
+		assert_eq!(repo.find_commit(stash.into())?.parent_count(), 3);
+
+		stash_pop(repo_path, stash)?;
+
+		assert_eq!(repo_read_file(&repo, "new.txt")?, "untracked");
+		assert_eq!(repo_read_file(&repo, "dir/new.txt")?, "nested");
+		assert!(repo.status_file(Path::new("new.txt"))?.is_wt_new());
+		assert_eq!(get_statuses(repo_path), (4, 0));
+
+		Ok(())
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_stashing_untracked_symlink() -> Result<()> {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		repo_write_file(&repo, "target.txt", "content")?;
+		std::os::unix::fs::symlink("target.txt", root.join("link"))?;
+
+		let opts = StashPushOptions {
+			untracked_only: true,
+			..StashPushOptions::default()
+		};
+		let stash = stash_push_paths(
+			repo_path,
+			&[String::from("link")],
+			&opts,
+		)?;
+
+		assert!(fs::symlink_metadata(root.join("link")).is_err());
+
+		let untracked =
+			repo.find_commit(stash.into())?.parent(2)?.tree()?;
+		let entry = untracked.get_name("link").unwrap();
+		assert_eq!(entry.filemode(), 0o120_000);
+		assert_eq!(
+			repo.find_blob(entry.id())?.content(),
+			b"target.txt"
+		);
+
+		stash_pop(repo_path, stash)?;
+
+		assert!(fs::symlink_metadata(root.join("link"))?.is_symlink());
+		assert_eq!(
+			fs::read_link(root.join("link"))?,
+			Path::new("target.txt")
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_stashing_untracked_without_user_name() -> Result<()> {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		repo.config()?.remove("user.name")?;
+		repo_write_file(&repo, "new.txt", "untracked")?;
+
+		let opts = StashPushOptions {
+			untracked_only: true,
+			..StashPushOptions::default()
+		};
+		let stash = stash_push_paths(repo_path, &[], &opts)?;
+
+		assert_eq!(
+			repo.find_commit(stash.into())?.author().name(),
+			Some("unknown")
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_stashing_untracked_only_nothing() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "foo.txt", "test", "commit foo");
+		repo_write_file(&repo, "foo.txt", "test\nfoo").unwrap();
+
+		let opts = StashPushOptions {
+			untracked_only: true,
+			..StashPushOptions::default()
+		};
+
+		assert!(stash_push_paths(repo_path, &[], &opts).is_err());
+		assert!(get_stashes(repo_path).unwrap().is_empty());
+	}
+
 	#[test]
 	fn test_stashes() -> Result<()> {
 		let (_td, repo) = repo_init().unwrap();
@@ -1,7 +1,19 @@
-use super::RepoPath;
-use crate::{error::Result, sync::repository::repo};
-use git2::RepositoryState;
+//! the operation in progress, detected by libgit2 from the state files
+//! in the git dir (`MERGE_HEAD`, `rebase-merge/`, `rebase-apply/`,
+//! `CHERRY_PICK_HEAD`, `REVERT_HEAD`, `BISECT_LOG`, ...)
+
+use super::{CommitId, RepoPath};
+use crate::{
+	error::{Error, Result},
+	sync::{repository::repo, utils::read_file},
+};
+use git2::{RepositoryState, ResetType};
 use scopetime::scope_time;
+use std::fmt;
+
+const CHERRY_PICK_HEAD_FILE: &str = "CHERRY_PICK_HEAD";
+const ORIG_HEAD_FILE: &str = "ORIG_HEAD";
+const REBASE_APPLY_DIR: &str = "rebase-apply";
 
 ///
 #[derive(Debug, PartialEq, Eq)]
@@ -10,12 +22,16 @@ pub enum RepoState {
 	Clean,
 	///
 	Merge,
-	///
+	/// interactive or not
 	Rebase,
 	///
+	CherryPick,
+	///
 	Revert,
 	///
-	Other,
+	Bisect,
+	/// `git am`
+	ApplyMailbox,
 }
 
 impl From<RepositoryState> for RepoState {
@@ -23,16 +39,36 @@ impl From<RepositoryState> for RepoState {
 		match state {
 			RepositoryState::Clean => Self::Clean,
 			RepositoryState::Merge => Self::Merge,
-			RepositoryState::Revert => Self::Revert,
-			RepositoryState::RebaseMerge => Self::Rebase,
-			_ => {
-				log::warn!("state not supported yet: {state:?}");
-				Self::Other
-			}
+			RepositoryState::Revert
+			| RepositoryState::RevertSequence => Self::Revert,
+			RepositoryState::CherryPick
+			| RepositoryState::CherryPickSequence => Self::CherryPick,
+			// `rebase-apply/` without `applying` in it is left by
+			// `git rebase --apply`
+			RepositoryState::RebaseMerge
+			| RepositoryState::RebaseInteractive
+			| RepositoryState::Rebase
+			| RepositoryState::ApplyMailboxOrRebase => Self::Rebase,
+			RepositoryState::ApplyMailbox => Self::ApplyMailbox,
+			RepositoryState::Bisect => Self::Bisect,
 		}
 	}
 }
 
+impl fmt::Display for RepoState {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::Clean => "Clean",
+			Self::Merge => "Merge",
+			Self::Rebase => "Rebase",
+			Self::CherryPick => "Cherry-pick",
+			Self::Revert => "Revert",
+			Self::Bisect => "Bisect",
+			Self::ApplyMailbox => "Apply mailbox",
+		})
+	}
+}
+
 ///
 pub fn repo_state(repo_path: &RepoPath) -> Result<RepoState> {
 	scope_time!("repo_state");
@@ -43,3 +79,149 @@ pub fn repo_state(repo_path: &RepoPath) -> Result<RepoState> {
 
 	Ok(state.into())
 }
+
+/// the commit a pending cherry-pick picks
+pub fn cherrypick_head(repo_path: &RepoPath) -> Result<CommitId> {
+	scope_time!("cherrypick_head");
+
+	let path = repo(repo_path)?.path().join(CHERRY_PICK_HEAD_FILE);
+
+	let id = git2::Oid::from_str(read_file(&path)?.trim())?;
+
+	Ok(id.into())
+}
+
+/// progress of a pending `git am`
+#[derive(Debug, PartialEq, Eq)]
+pub struct ApplyMailboxProgress {
+	/// 1-based patch that stopped
+	pub current: usize,
+	///
+	pub patches: usize,
+}
+
+///
+pub fn apply_mailbox_progress(
+	repo_path: &RepoPath,
+) -> Result<ApplyMailboxProgress> {
+	scope_time!("apply_mailbox_progress");
+
+	let dir = repo(repo_path)?.path().join(REBASE_APPLY_DIR);
+
+	let number = |file: &str| -> Result<usize> {
+		read_file(&dir.join(file))?.trim().parse().map_err(|_| {
+			Error::Generic(format!(
+				"invalid {REBASE_APPLY_DIR}/{file}"
+			))
+		})
+	};
+
+	Ok(ApplyMailboxProgress {
+		current: number("next")?,
+		patches: number("last")?,
+	})
+}
+
+/// like `git am --abort`: goes back to where `git am` started and
+/// discards all uncommitted changes
+pub fn abort_apply_mailbox(repo_path: &RepoPath) -> Result<()> {
+	scope_time!("abort_apply_mailbox");
+
+	let repo = repo(repo_path)?;
+
+	let orig_head = read_file(&repo.path().join(ORIG_HEAD_FILE))?;
+	let orig_head = repo
+		.find_object(git2::Oid::from_str(orig_head.trim())?, None)?;
+
+	repo.reset(&orig_head, ResetType::Hard, None)?;
+	repo.cleanup_state()?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::tests::{repo_init, write_commit_file};
+	use std::fs;
+
+	#[test]
+	fn test_repo_state() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let id = write_commit_file(&repo, "a.txt", "a", "a");
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+
+		fs::write(
+			repo.path().join(CHERRY_PICK_HEAD_FILE),
+			format!("{id}\n"),
+		)
+		.unwrap();
+		assert_eq!(
+			repo_state(repo_path).unwrap(),
+			RepoState::CherryPick
+		);
+		assert_eq!(cherrypick_head(repo_path).unwrap(), id);
+		repo.cleanup_state().unwrap();
+
+		let rebase_merge = repo.path().join("rebase-merge");
+		fs::create_dir(&rebase_merge).unwrap();
+		fs::write(rebase_merge.join("interactive"), "").unwrap();
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Rebase);
+		repo.cleanup_state().unwrap();
+
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+	}
+
+	#[test]
+	fn test_apply_mailbox() {
+		let (td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let start = write_commit_file(&repo, "a.txt", "a", "a");
+
+		// what `git am` leaves after applying the first of three
+		// patches
+		fs::write(
+			repo.path().join(ORIG_HEAD_FILE),
+			format!("{start}\n"),
+		)
+		.unwrap();
+		write_commit_file(&repo, "a.txt", "b", "patch 1");
+		let dir = repo.path().join(REBASE_APPLY_DIR);
+		fs::create_dir(&dir).unwrap();
+		fs::write(dir.join("applying"), "").unwrap();
+		fs::write(dir.join("next"), "2\n").unwrap();
+		fs::write(dir.join("last"), "3\n").unwrap();
+		fs::write(td.path().join("a.txt"), "c").unwrap();
+
+		assert_eq!(
+			repo_state(repo_path).unwrap(),
+			RepoState::ApplyMailbox
+		);
+		assert_eq!(
+			apply_mailbox_progress(repo_path).unwrap(),
+			ApplyMailboxProgress {
+				current: 2,
+				patches: 3,
+			}
+		);
+
+		abort_apply_mailbox(repo_path).unwrap();
+
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+		assert_eq!(
+			repo.head().unwrap().target().unwrap(),
+			start.into()
+		);
+		assert_eq!(
+			fs::read_to_string(td.path().join("a.txt")).unwrap(),
+			"a"
+		);
+	}
+}
@@ -2,14 +2,22 @@
 
 use crate::{
 	error::Result,
+	progress::{Progress, ProgressPhase, ReportProgress},
 	sync::{
 		config::untracked_files_config_repo,
 		repository::{gix_repo, repo},
 	},
 };
-use git2::{Delta, Status, StatusOptions, StatusShow};
+use git2::{Delta, DiffDelta, Status, StatusOptions, StatusShow};
+use gix::progress::{Id, MessageLevel, Step, StepShared, Unit};
 use scopetime::scope_time;
-use std::path::Path;
+use std::{
+	path::Path,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+};
 
 use super::{RepoPath, ShowUntrackedFilesConfig};
 
@@ -26,8 +34,12 @@ pub enum StatusItemType {
 	Renamed,
 	///
 	Typechange,
+	/// only the file mode (like the executable bit) changed
+	ModeChange,
 	///
 	Conflicted,
+	/// excluded by an ignore rule, only listed on request
+	Ignored,
 }
 
 impl From<gix::status::index_worktree::iter::Summary>
@@ -58,6 +70,17 @@ impl From<gix::diff::index::ChangeRef<'_, '_>> for StatusItemType {
 		match change_ref {
 			ChangeRef::Addition { .. } => Self::New,
 			ChangeRef::Deletion { .. } => Self::Deleted,
+			ChangeRef::Modification {
+				previous_entry_mode,
+				previous_id,
+				entry_mode,
+				id,
+				..
+			} if previous_id == id
+				&& previous_entry_mode != entry_mode =>
+			{
+				Self::ModeChange
+			}
 			ChangeRef::Modification { .. }
 			| ChangeRef::Rewrite { .. } => Self::Modified,
 		}
@@ -82,6 +105,43 @@ impl From<Status> for StatusItemType {
 	}
 }
 
+impl From<&gix::status::index_worktree::Item> for StatusItemType {
+	fn from(item: &gix::status::index_worktree::Item) -> Self {
+		use gix::status::{
+			index_worktree::Item,
+			plumbing::index_as_worktree::{Change, EntryStatus},
+		};
+
+		match item {
+			Item::Modification {
+				status:
+					EntryStatus::Change(Change::Modification {
+						executable_bit_changed: true,
+						content_change: None,
+						..
+					}),
+				..
+			} => Self::ModeChange,
+			_ => item.summary().map_or(Self::Modified, Into::into),
+		}
+	}
+}
+
+impl From<&DiffDelta<'_>> for StatusItemType {
+	fn from(delta: &DiffDelta) -> Self {
+		let (old, new) = (delta.old_file(), delta.new_file());
+
+		if delta.status() == Delta::Modified
+			&& old.id() == new.id()
+			&& old.mode() != new.mode()
+		{
+			Self::ModeChange
+		} else {
+			delta.status().into()
+		}
+	}
+}
+
 impl From<Delta> for StatusItemType {
 	fn from(d: Delta) -> Self {
 		match d {
@@ -167,11 +227,83 @@ impl From<ShowUntrackedFilesConfig> for gix::status::UntrackedFiles {
 	}
 }
 
+/// how many files of the workdir a running `get_status_with_progress`
+/// compared with the index so far, clones share the counts
+#[derive(Clone, Debug, Default)]
+pub struct ScanProgress {
+	checked: StepShared,
+	total: Arc<AtomicUsize>,
+}
+
+impl ReportProgress for ScanProgress {
+	fn report(&self) -> Progress {
+		Progress::new(
+			ProgressPhase::Scanning,
+			self.checked.load(Ordering::Relaxed),
+			self.total.load(Ordering::Relaxed),
+		)
+	}
+}
+
+impl gix::progress::Count for ScanProgress {
+	fn set(&self, step: Step) {
+		self.checked.store(step, Ordering::Relaxed);
+	}
+
+	fn step(&self) -> Step {
+		self.checked.load(Ordering::Relaxed)
+	}
+
+	fn inc_by(&self, step: Step) {
+		self.checked.fetch_add(step, Ordering::Relaxed);
+	}
+
+	/// gix counts the checked files on it from several threads
+	fn counter(&self) -> StepShared {
+		Arc::clone(&self.checked)
+	}
+}
+
+impl gix::progress::Progress for ScanProgress {
+	fn init(&mut self, max: Option<Step>, _unit: Option<Unit>) {
+		self.checked.store(0, Ordering::Relaxed);
+		self.total.store(max.unwrap_or_default(), Ordering::Relaxed);
+	}
+
+	fn set_name(&mut self, _name: String) {}
+
+	fn name(&self) -> Option<String> {
+		None
+	}
+
+	fn id(&self) -> Id {
+		gix::progress::UNKNOWN
+	}
+
+	fn message(&self, _level: MessageLevel, _message: String) {}
+}
+
 /// guarantees sorting
 pub fn get_status(
 	repo_path: &RepoPath,
 	status_type: StatusType,
 	show_untracked: Option<ShowUntrackedFilesConfig>,
+) -> Result<Vec<StatusItem>> {
+	get_status_with_progress(
+		repo_path,
+		status_type,
+		show_untracked,
+		&ScanProgress::default(),
+	)
+}
+
+/// `get_status` counting the files it checked in the workdir on
+/// `progress`, the staged changes are not counted
+pub fn get_status_with_progress(
+	repo_path: &RepoPath,
+	status_type: StatusType,
+	show_untracked: Option<ShowUntrackedFilesConfig>,
+	progress: &ScanProgress,
 ) -> Result<Vec<StatusItem>> {
 	scope_time!("get_status");
 
@@ -192,7 +324,7 @@ pub fn get_status(
 	};
 
 	let status = repo
-		.status(gix::progress::Discard)?
+		.status(progress.clone())?
 		.untracked_files(show_untracked.into());
 
 	let mut res = Vec::new();
@@ -208,7 +340,9 @@ pub fn get_status(
 					continue;
 				};
 
-				let status = item.summary().map(Into::into);
+				let status = item
+					.summary()
+					.map(|_| StatusItemType::from(&item));
 
 				if let Some(status) = status {
 					let path = item.rela_path().to_string();
@@ -263,9 +397,9 @@ pub fn get_status(
 				let path = item.location().to_string();
 
 				let status = match item {
-					gix::status::Item::IndexWorktree(item) => {
-						item.summary().map(Into::into)
-					}
+					gix::status::Item::IndexWorktree(item) => item
+						.summary()
+						.map(|_| StatusItemType::from(&item)),
 					gix::status::Item::TreeIndex(change_ref) => {
 						Some(change_ref.into())
 					}
@@ -285,6 +419,61 @@ pub fn get_status(
 	Ok(res)
 }
 
+/// workdir status of only the tracked files `paths` (relative to
+/// the workdir)
+///
+/// much cheaper than a full `get_status` in big repos. `None` if one
+/// of them is not tracked, whether it shows up as
+/// untracked or inside an untracked folder takes a full scan
+pub fn get_status_of_tracked(
+	repo_path: &RepoPath,
+	paths: &[String],
+) -> Result<Option<Vec<StatusItem>>> {
+	scope_time!("get_status_of_tracked");
+
+	let repo: gix::Repository = gix_repo(repo_path)?;
+
+	let index = repo.index_or_empty()?;
+	if paths.iter().any(|path| {
+		index.entry_by_path(path.as_str().into()).is_none()
+	}) {
+		return Ok(None);
+	}
+
+	let patterns: Vec<gix::bstr::BString> = paths
+		.iter()
+		.map(|path| format!(":(literal){path}").into())
+		.collect();
+
+	let iter = repo
+		.status(gix::progress::Discard)?
+		.untracked_files(gix::status::UntrackedFiles::None)
+		.into_index_worktree_iter(patterns)?;
+
+	let mut res = Vec::new();
+	for item in iter {
+		let Ok(item) = item else {
+			log::warn!("[status] the status iter returned an error for an item: {item:?}");
+
+			continue;
+		};
+
+		if let Some(status) =
+			item.summary().map(|_| StatusItemType::from(&item))
+		{
+			let path = item.rela_path().to_string();
+
+			res.push(StatusItem { path, status });
+		}
+	}
+
+	res.sort_by(|a, b| {
+		Path::new(a.path.as_str()).cmp(Path::new(b.path.as_str()))
+	});
+
+	Ok(Some(res))
+}
+
 /// discard all changes in the working directory
 pub fn discard_status(repo_path: &RepoPath) -> Result<bool> {
 	let repo = repo(repo_path)?;
@@ -310,6 +499,84 @@ mod tests {
 	use std::{fs::File, io::Write, path::Path};
 	use tempfile::TempDir;
 
+	#[test]
+	fn test_status_of_tracked() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		for file in ["a.txt", "b.txt", "sub/c.txt"] {
+			std::fs::create_dir_all(root.join("sub")).unwrap();
+			std::fs::write(root.join(file), "1").unwrap();
+			stage_add_file(repo_path, Path::new(file)).unwrap();
+		}
+		commit(repo_path, "files").unwrap();
+
+		std::fs::write(root.join("a.txt"), "2").unwrap();
+		std::fs::write(root.join("b.txt"), "2").unwrap();
+		std::fs::remove_file(root.join("sub/c.txt")).unwrap();
+
+		let status = get_status_of_tracked(
+			repo_path,
+			&[String::from("a.txt"), String::from("sub/c.txt")],
+		)
+		.unwrap()
+		.unwrap();
+
+		assert_eq!(
+			status,
+			vec![
+				StatusItem {
+					path: String::from("a.txt"),
+					status: StatusItemType::Modified,
+				},
+				StatusItem {
+					path: String::from("sub/c.txt"),
+					status: StatusItemType::Deleted,
+				},
+			]
+		);
+
+		std::fs::write(root.join("new.txt"), "1").unwrap();
+		assert!(get_status_of_tracked(
+			repo_path,
+			&[String::from("new.txt")]
+		)
+		.unwrap()
+		.is_none());
+	}
+
+	#[test]
+	fn test_status_progress() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		for file in ["a.txt", "b.txt", "c.txt"] {
+			std::fs::write(root.join(file), "1").unwrap();
+			stage_add_file(repo_path, Path::new(file)).unwrap();
+		}
+		commit(repo_path, "files").unwrap();
+		std::fs::write(root.join("b.txt"), "2").unwrap();
+
+		let progress = ScanProgress::default();
+		let status = get_status_with_progress(
+			repo_path,
+			StatusType::WorkingDir,
+			None,
+			&progress,
+		)
+		.unwrap();
+
+		assert_eq!(status.len(), 1);
+		assert_eq!(
+			progress.report(),
+			Progress::new(ProgressPhase::Scanning, 3, 3)
+		);
+	}
+
 	#[test]
 	fn test_discard_status() {
 		let file_path = Path::new("README.md");
@@ -368,4 +635,39 @@ mod tests {
 			}]
 		);
 	}
+	#[cfg(unix)]
+	#[test]
+	fn test_mode_change() {
+		use std::os::unix::fs::PermissionsExt;
+
+		let file_path = Path::new("run.sh");
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		File::create(root.join(file_path))
+			.unwrap()
+			.write_all(b"echo")
+			.unwrap();
+		stage_add_file(repo_path, file_path).unwrap();
+		commit(repo_path, "commit msg").unwrap();
+
+		std::fs::set_permissions(
+			root.join(file_path),
+			std::fs::Permissions::from_mode(0o755),
+		)
+		.unwrap();
+
+		let status =
+			get_status(repo_path, StatusType::WorkingDir, None)
+				.unwrap();
+		assert_eq!(status[0].status, StatusItemType::ModeChange);
+
+		stage_add_file(repo_path, file_path).unwrap();
+
+		let status =
+			get_status(repo_path, StatusType::Stage, None).unwrap();
+		assert_eq!(status[0].status, StatusItemType::ModeChange);
+	}
 }
@@ -1,13 +1,23 @@
-use std::path::{Path, PathBuf};
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
 
+use crossbeam_channel::Sender;
 use git2::{
-	Repository, RepositoryOpenFlags, Submodule,
-	SubmoduleUpdateOptions,
+	ConfigLevel, FetchOptions, Repository, RepositoryOpenFlags,
+	Submodule, SubmoduleUpdateOptions,
 };
 use scopetime::scope_time;
 
-use super::{repo, CommitId, RepoPath};
-use crate::{error::Result, sync::utils::work_dir, Error};
+use super::{
+	remotes::{proxy_auto, push::ProgressNotification, Callbacks},
+	repo, CommitId, RepoPath,
+};
+use crate::{
+	asyncjob::CancellationToken, error::Result,
+	sync::utils::work_dir, Error,
+};
 
 pub use git2::SubmoduleStatus;
 
@@ -26,6 +36,9 @@ pub struct SubmoduleInfo {
 	pub head_id: Option<CommitId>,
 	///
 	pub status: SubmoduleStatus,
+	/// path of the submodule containing this one (relative to the
+	/// top level workdir) or `None` for direct submodules
+	pub parent: Option<PathBuf>,
 }
 
 ///
@@ -50,6 +63,22 @@ impl SubmoduleInfo {
 
 		Ok(RepoPath::Path(wd.join(self.path.clone())))
 	}
+
+	/// path of the repo this submodule is registered in
+	pub fn get_parent_repo_path(
+		&self,
+		repo_path: &RepoPath,
+	) -> Result<RepoPath> {
+		match &self.parent {
+			Some(parent) => {
+				let repo = repo(repo_path)?;
+				let wd = repo.workdir().ok_or(Error::NoWorkDir)?;
+
+				Ok(RepoPath::Path(wd.join(parent)))
+			}
+			None => Ok(repo_path.clone()),
+		}
+	}
 }
 
 fn submodule_to_info(s: &Submodule, r: &Repository) -> SubmoduleInfo {
@@ -67,6 +96,7 @@ fn submodule_to_info(s: &Submodule, r: &Repository) -> SubmoduleInfo {
 		head_id: s.head_id().map(CommitId::from),
 		url: s.url().map(String::from),
 		status,
+		parent: None,
 	}
 }
 
@@ -87,10 +117,61 @@ pub fn get_submodules(
 	Ok(res)
 }
 
-///
+/// lists the submodules including the ones nested inside of
+/// checked out submodules, paths are relative to the top level
+pub fn get_submodules_recursive(
+	repo_path: &RepoPath,
+) -> Result<Vec<SubmoduleInfo>> {
+	scope_time!("get_submodules_recursive");
+
+	let mut res = Vec::new();
+	collect_submodules(&repo(repo_path)?, None, &mut res)?;
+
+	Ok(res)
+}
+
+fn collect_submodules(
+	repo: &Repository,
+	prefix: Option<&Path>,
+	res: &mut Vec<SubmoduleInfo>,
+) -> Result<()> {
+	for s in repo.submodules()? {
+		let mut info = submodule_to_info(&s, repo);
+
+		if let Some(prefix) = prefix {
+			info.path = prefix.join(&info.path);
+			info.parent = Some(prefix.to_path_buf());
+		}
+
+		let nested = if info
+			.status
+			.contains(SubmoduleStatus::WD_UNINITIALIZED)
+		{
+			None
+		} else {
+			s.open().ok()
+		};
+		let path = info.path.clone();
+
+		res.push(info);
+
+		if let Some(nested) = nested {
+			collect_submodules(&nested, Some(&path), res)?;
+		}
+	}
+
+	Ok(())
+}
+
+/// checks out the commit recorded in the parent (cloning the
+/// submodule first if needed), with `recursive` nested submodules
+/// are updated as well
 pub fn update_submodule(
 	repo_path: &RepoPath,
 	name: &str,
+	recursive: bool,
+	progress_sender: &Option<Sender<ProgressNotification>>,
+	cancellation: Option<&CancellationToken>,
 ) -> Result<()> {
 	scope_time!("update_submodule");
 
@@ -98,10 +179,224 @@ pub fn update_submodule(
 
 	let mut submodule = repo.find_submodule(name)?;
 
+	update(
+		&repo,
+		&mut submodule,
+		recursive,
+		progress_sender.as_ref(),
+		cancellation,
+	)
+}
+
+fn update(
+	repo: &Repository,
+	submodule: &mut Submodule,
+	recursive: bool,
+	progress_sender: Option<&Sender<ProgressNotification>>,
+	cancellation: Option<&CancellationToken>,
+) -> Result<()> {
+	let callbacks = Callbacks::new(progress_sender.cloned(), None)
+		.with_cancellation(cancellation);
 	let mut options = SubmoduleUpdateOptions::new();
 	options.allow_fetch(true);
+	options.fetch(fetch_options(&callbacks));
+
+	callbacks.check_result(
+		repo,
+		submodule.update(true, Some(&mut options)),
+	)?;
+
+	if recursive {
+		let repo = submodule.open()?;
+		for mut nested in repo.submodules()? {
+			update(
+				&repo,
+				&mut nested,
+				true,
+				progress_sender,
+				cancellation,
+			)?;
+		}
+	}
+
+	Ok(())
+}
+
+/// reporting the transfer of the clone or fetch to the sender of
+/// `callbacks`
+fn fetch_options(callbacks: &Callbacks) -> FetchOptions<'static> {
+	let mut options = FetchOptions::new();
+	options.remote_callbacks(callbacks.callbacks());
+	options.proxy_options(proxy_auto());
+	options
+}
+
+/// copies the submodule url from `.gitmodules` into `.git/config`
+pub fn init_submodule(
+	repo_path: &RepoPath,
+	name: &str,
+) -> Result<()> {
+	scope_time!("init_submodule");
+
+	let repo = repo(repo_path)?;
+
+	repo.find_submodule(name)?.init(false)?;
+
+	Ok(())
+}
+
+/// updates the url in `.git/config` and in the remote of the
+/// checked out submodule to the one in `.gitmodules`
+pub fn sync_submodule(
+	repo_path: &RepoPath,
+	name: &str,
+) -> Result<()> {
+	scope_time!("sync_submodule");
+
+	let repo = repo(repo_path)?;
+
+	repo.find_submodule(name)?.sync()?;
+
+	Ok(())
+}
+
+/// clones `url` into `path` (defaults to the name of the remote
+/// repo) and registers it as a submodule
+pub fn add_submodule(
+	repo_path: &RepoPath,
+	url: &str,
+	path: Option<&str>,
+	progress_sender: &Option<Sender<ProgressNotification>>,
+	cancellation: Option<&CancellationToken>,
+) -> Result<()> {
+	scope_time!("add_submodule");
+
+	let repo = repo(repo_path)?;
+
+	let path = path.map_or_else(
+		|| {
+			url.trim_end_matches('/')
+				.rsplit(['/', ':'])
+				.next()
+				.unwrap_or_default()
+				.trim_end_matches(".git")
+				.to_string()
+		},
+		String::from,
+	);
+
+	if path.is_empty() {
+		return Err(Error::Generic(String::from(
+			"no submodule path given",
+		)));
+	}
+
+	let mut submodule =
+		repo.submodule(url, Path::new(&path), true)?;
+
+	let callbacks = Callbacks::new(progress_sender.clone(), None)
+		.with_cancellation(cancellation);
+	let mut options = SubmoduleUpdateOptions::new();
+	options.fetch(fetch_options(&callbacks));
+	callbacks
+		.check_result(&repo, submodule.clone(Some(&mut options)))?;
+	submodule.add_finalize()?;
+
+	Ok(())
+}
+
+/// unregisters the submodule and deletes its checkout, the cloned
+/// repo in `.git/modules` is kept just like `git rm` does
+pub fn remove_submodule(
+	repo_path: &RepoPath,
+	name: &str,
+) -> Result<()> {
+	scope_time!("remove_submodule");
+
+	let repo = repo(repo_path)?;
+	let wd = work_dir(&repo)?.to_path_buf();
+
+	let path = repo.find_submodule(name)?.path().to_path_buf();
+
+	let status =
+		repo.submodule_status(name, git2::SubmoduleIgnore::None)?;
+	if status.intersects(
+		SubmoduleStatus::WD_INDEX_MODIFIED
+			| SubmoduleStatus::WD_WD_MODIFIED
+			| SubmoduleStatus::WD_UNTRACKED,
+	) {
+		return Err(Error::Generic(format!(
+			"submodule '{name}' contains local changes"
+		)));
+	}
+
+	let section = format!("submodule.{name}.");
+	let header = format!("[submodule \"{name}\"]");
+
+	let gitmodules = wd.join(".gitmodules");
+	remove_config_section(
+		&mut git2::Config::open(&gitmodules)?,
+		&section,
+	)?;
+	remove_empty_section(&gitmodules, &header)?;
+
+	let mut config = repo.config()?.open_level(ConfigLevel::Local)?;
+	remove_config_section(&mut config, &section)?;
+	remove_empty_section(&repo.path().join("config"), &header)?;
+
+	let mut index = repo.index()?;
+	index.remove_path(&path)?;
+	index.add_path(Path::new(".gitmodules"))?;
+	index.write()?;
+
+	let checkout = wd.join(&path);
+	if checkout.exists() {
+		fs::remove_dir_all(checkout)?;
+	}
+
+	Ok(())
+}
+
+fn remove_config_section(
+	config: &mut git2::Config,
+	section: &str,
+) -> Result<()> {
+	let mut keys = Vec::new();
+	config.entries(None)?.for_each(|entry| {
+		if let Some(name) = entry.name() {
+			if name.starts_with(section) {
+				keys.push(name.to_string());
+			}
+		}
+	})?;
+
+	for key in keys {
+		config.remove(&key)?;
+	}
+
+	Ok(())
+}
 
-	submodule.update(true, Some(&mut options))?;
+/// libgit2 keeps the section header once all its keys are removed
+fn remove_empty_section(path: &Path, header: &str) -> Result<()> {
+	let content = fs::read_to_string(path)?;
+	let lines: Vec<&str> = content.lines().collect();
+
+	let mut res = String::with_capacity(content.len());
+	for (i, line) in lines.iter().enumerate() {
+		let empty = line.trim() == header
+			&& lines[i + 1..]
+				.iter()
+				.find(|l| !l.trim().is_empty())
+				.is_none_or(|l| l.trim_start().starts_with('['));
+
+		if !empty {
+			res.push_str(line);
+			res.push('\n');
+		}
+	}
+
+	fs::write(path, res)?;
 
 	Ok(())
 }
@@ -155,14 +450,144 @@ pub fn submodule_parent_info(
 
 #[cfg(test)]
 mod tests {
-	use super::get_submodules;
+	use super::{
+		add_submodule, get_submodules, get_submodules_recursive,
+		remove_submodule, update_submodule,
+	};
 	use crate::sync::{
-		submodules::submodule_parent_info, tests::repo_init, RepoPath,
+		remotes::push::ProgressNotification,
+		submodules::submodule_parent_info, tests::repo_init,
+		RepoPath,
 	};
+	use crossbeam_channel::unbounded;
 	use git2::Repository;
 	use pretty_assertions::assert_eq;
 	use std::path::Path;
 
+	fn url(path: &Path) -> String {
+		path.to_str().unwrap().to_string()
+	}
+
+	#[test]
+	fn test_add_remove_submodule() {
+		let (remote, _) = repo_init().unwrap();
+		let (dir, repo) = repo_init().unwrap();
+		let repo_path: RepoPath = dir.path().to_str().unwrap().into();
+
+		let (sender, receiver) = unbounded();
+		add_submodule(
+			&repo_path,
+			&url(remote.path()),
+			Some("sub"),
+			&Some(sender),
+			None,
+		)
+		.unwrap();
+
+		// a local clone transfers nothing, but still updates the
+		// remote branches
+		assert!(receiver.try_iter().any(|notification| matches!(
+			notification,
+			ProgressNotification::UpdateTips { .. }
+		)));
+
+		let subs = get_submodules(&repo_path).unwrap();
+		assert_eq!(subs.len(), 1);
+		assert_eq!(subs[0].path, Path::new("sub"));
+		assert!(dir.path().join("sub/.git").exists());
+
+		remove_submodule(&repo_path, "sub").unwrap();
+
+		assert!(get_submodules(&repo_path).unwrap().is_empty());
+		assert_eq!(
+			std::fs::read_to_string(dir.path().join(".gitmodules"))
+				.unwrap(),
+			""
+		);
+		assert!(!dir.path().join("sub").exists());
+		assert!(repo
+			.index()
+			.unwrap()
+			.get_path(Path::new("sub"), 0)
+			.is_none());
+	}
+
+	#[test]
+	fn test_nested_submodules() {
+		let (inner, _) = repo_init().unwrap();
+		let (outer, outer_repo) = repo_init().unwrap();
+		let outer_path: RepoPath =
+			outer.path().to_str().unwrap().into();
+
+		add_submodule(
+			&outer_path,
+			&url(inner.path()),
+			Some("inner"),
+			&None,
+			None,
+		)
+		.unwrap();
+		{
+			let mut index = outer_repo.index().unwrap();
+			index.read(true).unwrap();
+			let tree =
+				outer_repo.find_tree(index.write_tree().unwrap());
+			let head = outer_repo.head().unwrap().peel_to_commit();
+			let sig = outer_repo.signature().unwrap();
+			outer_repo
+				.commit(
+					Some("HEAD"),
+					&sig,
+					&sig,
+					"add inner",
+					&tree.unwrap(),
+					&[&head.unwrap()],
+				)
+				.unwrap();
+		}
+
+		let (dir, _) = repo_init().unwrap();
+		let repo_path: RepoPath = dir.path().to_str().unwrap().into();
+
+		add_submodule(
+			&repo_path,
+			&url(outer.path()),
+			None,
+			&None,
+			None,
+		)
+		.unwrap();
+
+		let subs = get_submodules_recursive(&repo_path).unwrap();
+		assert_eq!(subs.len(), 2);
+
+		let outer_name = subs[0].name.clone();
+		assert!(!dir
+			.path()
+			.join(&outer_name)
+			.join("inner/.git")
+			.exists());
+
+		update_submodule(&repo_path, &outer_name, true, &None, None)
+			.unwrap();
+
+		let subs = get_submodules_recursive(&repo_path).unwrap();
+		assert_eq!(subs.len(), 2);
+		assert_eq!(
+			subs[1].path,
+			Path::new(&outer_name).join("inner")
+		);
+		assert_eq!(
+			subs[1].parent.as_deref(),
+			Some(Path::new(&outer_name))
+		);
+		assert!(dir
+			.path()
+			.join(&outer_name)
+			.join("inner/.git")
+			.exists());
+	}
+
 	#[test]
 	fn test_smoke() {
 		let (dir, _r) = repo_init().unwrap();
@@ -1,10 +1,15 @@
 use super::{get_commits_info, CommitId, RepoPath};
 use crate::{
 	error::Result,
-	sync::{gix_repo, repository::repo},
+	sync::{
+		config::get_config_string_repo, gix_repo, repository::repo,
+	},
 };
 use scopetime::scope_time;
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::{
+	cmp::Ordering,
+	collections::{BTreeMap, HashMap},
+};
 
 ///
 #[derive(Clone, Hash, PartialEq, Eq, Debug)]
@@ -13,6 +18,12 @@ pub struct Tag {
 	pub name: String,
 	/// tag annotation
 	pub annotation: Option<String>,
+	/// name of the tagger of annotated tags
+	pub tagger: Option<String>,
+	/// tagger time of annotated tags
+	pub tagger_time: Option<i64>,
+	/// annotated tag with a gpg, ssh or x509 signature
+	pub signed: bool,
 }
 
 impl Tag {
@@ -21,6 +32,9 @@ impl Tag {
 		Self {
 			name: name.into(),
 			annotation: None,
+			tagger: None,
+			tagger_time: None,
+			signed: false,
 		}
 	}
 }
@@ -36,18 +50,172 @@ pub struct TagWithMetadata {
 	pub name: String,
 	///
 	pub author: String,
-	///
+	/// commit time
 	pub time: i64,
+	/// tagger time of annotated tags, commit time otherwise
+	pub creator_time: i64,
 	///
 	pub message: String,
 	///
 	pub commit_id: CommitId,
 	///
 	pub annotation: Option<String>,
+	/// name of the tagger of annotated tags
+	pub tagger: Option<String>,
+	///
+	pub signed: bool,
+}
+
+/// what the tag list is ordered by
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TagSortKey {
+	///
+	#[default]
+	CommitDate,
+	/// like git's `creatordate`
+	CreatorDate,
+	/// like git's `version:refname`, `v10.0` after `v2.0`
+	Version,
+	/// like git's `refname`
+	Name,
+}
+
+/// order of the tag list, see git's `tag.sort`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TagSort {
+	///
+	pub key: TagSortKey,
+	///
+	pub descending: bool,
+}
+
+impl Default for TagSort {
+	fn default() -> Self {
+		Self {
+			key: TagSortKey::CommitDate,
+			descending: true,
+		}
+	}
+}
+
+impl TagSort {
+	/// parses a `tag.sort` value like `-version:refname`
+	pub fn from_config(value: &str) -> Option<Self> {
+		let (descending, key) = value
+			.strip_prefix('-')
+			.map_or((false, value), |key| (true, key));
+
+		let key = match key {
+			"refname" => TagSortKey::Name,
+			"version:refname" | "v:refname" => TagSortKey::Version,
+			"creatordate" | "taggerdate" => TagSortKey::CreatorDate,
+			"committerdate" => TagSortKey::CommitDate,
+			_ => return None,
+		};
+
+		Some(Self { key, descending })
+	}
+
+	///
+	pub fn sort(self, tags: &mut [TagWithMetadata]) {
+		tags.sort_by(|a, b| {
+			let ord = match self.key {
+				TagSortKey::CommitDate => a.time.cmp(&b.time),
+				TagSortKey::CreatorDate => {
+					a.creator_time.cmp(&b.creator_time)
+				}
+				TagSortKey::Version => version_cmp(&a.name, &b.name),
+				TagSortKey::Name => a.name.cmp(&b.name),
+			};
+
+			if self.descending {
+				ord.reverse()
+			} else {
+				ord
+			}
+		});
+	}
+}
+
+/// compares names chunk by chunk, numbers by their value
+fn version_cmp(a: &str, b: &str) -> Ordering {
+	fn chunks(s: &str) -> Vec<&str> {
+		let mut res = Vec::new();
+		let mut start = 0;
+		let mut digits = None;
+		for (idx, c) in s.char_indices() {
+			let is_digit = c.is_ascii_digit();
+			if digits.is_some_and(|digits| digits != is_digit) {
+				res.push(&s[start..idx]);
+				start = idx;
+			}
+			digits = Some(is_digit);
+		}
+		res.push(&s[start..]);
+		res
+	}
+
+	for (a, b) in chunks(a).into_iter().zip(chunks(b)) {
+		let ord = match (a.parse::<u64>(), b.parse::<u64>()) {
+			(Ok(a), Ok(b)) => a.cmp(&b),
+			_ => a.cmp(b),
+		};
+		if ord != Ordering::Equal {
+			return ord;
+		}
+	}
+
+	a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+/// returns the `tag.sort` config of the repo, or the default order
+pub fn get_tag_sort(repo_path: &RepoPath) -> Result<TagSort> {
+	let repo = repo(repo_path)?;
+
+	Ok(get_config_string_repo(&repo, "tag.sort")?
+		.as_deref()
+		.and_then(TagSort::from_config)
+		.unwrap_or_default())
 }
 
 static MAX_MESSAGE_WIDTH: usize = 100;
 
+/// gix only splits off gpg signatures
+const SIGNATURE_STARTS: [&str; 2] = [
+	"-----BEGIN SSH SIGNATURE-----",
+	"-----BEGIN SIGNED MESSAGE-----",
+];
+
+/// the tag message without a trailing ssh or x509 signature, and
+/// whether it had one
+fn split_signature(message: &str) -> (&str, bool) {
+	SIGNATURE_STARTS
+		.iter()
+		.filter_map(|start| {
+			if message.starts_with(start) {
+				Some(0)
+			} else {
+				message.find(&format!("\n{start}")).map(|idx| idx + 1)
+			}
+		})
+		.min()
+		.map_or((message, false), |idx| (&message[..idx], true))
+}
+
+fn annotated_tag(tag: &gix::objs::TagRef) -> Tag {
+	let message = tag.message.to_string();
+	let (message, ssh_signed) = split_signature(&message);
+	let tagger = tag.tagger().ok().flatten();
+
+	Tag {
+		name: tag.name.to_string(),
+		annotation: Some(message.to_string()),
+		tagger: tagger.map(|tagger| tagger.name.to_string()),
+		tagger_time: tagger.map(|tagger| tagger.seconds()),
+		signed: ssh_signed || tag.pgp_signature.is_some(),
+	}
+}
+
 /// returns `Tags` type filled with all tags found in repo
 pub fn get_tags(repo_path: &RepoPath) -> Result<Tags> {
 	scope_time!("get_tags");
@@ -68,18 +236,14 @@ pub fn get_tags(repo_path: &RepoPath) -> Result<Tags> {
 		let tag = reference.peel_to_tag();
 
 		if let Ok(commit) = commit {
-			let tag_ref = tag.as_ref().map(gix::Tag::decode);
-
-			let name = match tag_ref {
-				Ok(Ok(tag)) => tag.name.to_string(),
-				_ => reference.name().shorten().to_string(),
-			};
-			let annotation = match tag_ref {
-				Ok(Ok(tag)) => Some(tag.message.to_string()),
-				_ => None,
+			let tag = match tag.as_ref().map(gix::Tag::decode) {
+				Ok(Ok(tag)) => annotated_tag(&tag),
+				_ => {
+					Tag::new(&reference.name().shorten().to_string())
+				}
 			};
 
-			adder(commit.into(), Tag { name, annotation });
+			adder(commit.into(), tag);
 		}
 	}
 
@@ -94,29 +258,8 @@ pub fn get_tags_with_metadata(
 
 	let tags_grouped_by_commit_id = get_tags(repo_path)?;
 
-	let tags_with_commit_id: Vec<(&str, Option<&str>, &CommitId)> =
-		tags_grouped_by_commit_id
-			.iter()
-			.flat_map(|(commit_id, tags)| {
-				tags.iter()
-					.map(|tag| {
-						(
-							tag.name.as_ref(),
-							tag.annotation.as_deref(),
-							commit_id,
-						)
-					})
-					.collect::<Vec<_>>()
-			})
-			.collect();
-
-	let unique_commit_ids: HashSet<_> = tags_with_commit_id
-		.iter()
-		.copied()
-		.map(|(_, _, &commit_id)| commit_id)
-		.collect();
-	let mut commit_ids = Vec::with_capacity(unique_commit_ids.len());
-	commit_ids.extend(unique_commit_ids);
+	let commit_ids: Vec<CommitId> =
+		tags_grouped_by_commit_id.keys().copied().collect();
 
 	let commit_infos =
 		get_commits_info(repo_path, &commit_ids, MAX_MESSAGE_WIDTH)?;
@@ -125,23 +268,28 @@ pub fn get_tags_with_metadata(
 		.map(|commit_info| (commit_info.id, commit_info))
 		.collect();
 
-	let mut tags: Vec<TagWithMetadata> = tags_with_commit_id
+	let mut tags: Vec<TagWithMetadata> = tags_grouped_by_commit_id
 		.into_iter()
-		.filter_map(|(tag, annotation, commit_id)| {
-			unique_commit_infos.get(commit_id).map(|commit_info| {
-				TagWithMetadata {
-					name: String::from(tag),
-					author: commit_info.author.clone(),
-					time: commit_info.time,
-					message: commit_info.message.clone(),
-					commit_id: *commit_id,
-					annotation: annotation.map(String::from),
-				}
-			})
+		.filter_map(|(commit_id, tags)| {
+			let commit_info = unique_commit_infos.get(&commit_id)?;
+
+			Some(tags.into_iter().map(move |tag| TagWithMetadata {
+				author: commit_info.author.clone(),
+				time: commit_info.time,
+				creator_time:
+					tag.tagger_time.unwrap_or(commit_info.time),
+				message: commit_info.message.clone(),
+				commit_id,
+				name: tag.name,
+				annotation: tag.annotation,
+				tagger: tag.tagger,
+				signed: tag.signed,
+			}))
 		})
+		.flatten()
 		.collect();
 
-	tags.sort_unstable_by_key(|b| std::cmp::Reverse(b.time));
+	TagSort::default().sort(&mut tags);
 
 	Ok(tags)
 }
@@ -175,6 +323,68 @@ mod tests {
 		assert!(get_tags(repo_path).unwrap().is_empty());
 	}
 
+	#[test]
+	fn test_version_cmp() {
+		assert_eq!(version_cmp("v2.0", "v10.0"), Ordering::Less);
+		assert_eq!(
+			version_cmp("v1.2.10", "v1.2.9"),
+			Ordering::Greater
+		);
+		assert_eq!(version_cmp("v1.0", "v1.0"), Ordering::Equal);
+		assert_eq!(version_cmp("v1.0", "v1.0.1"), Ordering::Less);
+		assert_eq!(version_cmp("alpha", "beta"), Ordering::Less);
+	}
+
+	#[test]
+	fn test_sort_config() {
+		assert_eq!(
+			TagSort::from_config("-v:refname"),
+			Some(TagSort {
+				key: TagSortKey::Version,
+				descending: true
+			})
+		);
+		assert_eq!(
+			TagSort::from_config("creatordate"),
+			Some(TagSort {
+				key: TagSortKey::CreatorDate,
+				descending: false
+			})
+		);
+		assert_eq!(TagSort::from_config("objectsize"), None);
+	}
+
+	#[test]
+	fn test_sort_by_version() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let target = repo
+			.find_object(
+				repo.head().unwrap().target().unwrap(),
+				Some(ObjectType::Commit),
+			)
+			.unwrap();
+		for name in ["v10.0", "v2.0", "v1.5"] {
+			repo.tag_lightweight(name, &target, false).unwrap();
+		}
+
+		repo.config()
+			.unwrap()
+			.set_str("tag.sort", "v:refname")
+			.unwrap();
+
+		let mut tags = get_tags_with_metadata(repo_path).unwrap();
+		get_tag_sort(repo_path).unwrap().sort(&mut tags);
+
+		assert_eq!(
+			tags.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+			vec!["v1.5", "v2.0", "v10.0"]
+		);
+	}
+
 	#[test]
 	fn test_multitags() {
 		let (_td, repo) = repo_init().unwrap();
@@ -223,4 +433,78 @@ mod tests {
 
 		assert_eq!(tags.len(), 0);
 	}
+
+	#[test]
+	fn test_split_signature() {
+		assert_eq!(split_signature("msg\n"), ("msg\n", false));
+		assert_eq!(
+			split_signature(
+				"msg\n-----BEGIN SSH SIGNATURE-----\nabc\n-----END SSH SIGNATURE-----\n"
+			),
+			("msg\n", true)
+		);
+		assert_eq!(
+			split_signature("-----BEGIN SIGNED MESSAGE-----\n"),
+			("", true)
+		);
+	}
+
+	#[test]
+	fn test_tag_metadata() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let target = repo
+			.find_object(
+				repo.head().unwrap().target().unwrap(),
+				Some(ObjectType::Commit),
+			)
+			.unwrap();
+		let tagger = git2::Signature::new(
+			"tagger",
+			"tagger@example.com",
+			&git2::Time::new(1_700_000_000, 0),
+		)
+		.unwrap();
+
+		repo.tag_lightweight("light", &target, false).unwrap();
+		repo.tag("annotated", &target, &tagger, "release\n", false)
+			.unwrap();
+		repo.tag(
+			"signed",
+			&target,
+			&tagger,
+			"release\n-----BEGIN SSH SIGNATURE-----\nabc\n-----END SSH SIGNATURE-----\n",
+			false,
+		)
+		.unwrap();
+
+		let tags = get_tags_with_metadata(repo_path).unwrap();
+		let tag = |name: &str| {
+			tags.iter().find(|tag| tag.name == name).unwrap()
+		};
+
+		assert_eq!(tag("light").tagger, None);
+		assert_eq!(tag("light").creator_time, tag("light").time);
+		assert!(!tag("light").signed);
+
+		assert_eq!(
+			tag("annotated").tagger.as_deref(),
+			Some("tagger")
+		);
+		assert_eq!(tag("annotated").creator_time, 1_700_000_000);
+		assert_eq!(
+			tag("annotated").annotation.as_deref(),
+			Some("release\n")
+		);
+		assert!(!tag("annotated").signed);
+
+		assert!(tag("signed").signed);
+		assert_eq!(
+			tag("signed").annotation.as_deref(),
+			Some("release\n")
+		);
+	}
 }
@@ -0,0 +1,304 @@
+//! line endings and encoding of text files
+
+use super::{commit_files::OldNew, repository::repo, RepoPath};
+use crate::error::{Error, Result};
+use git2::{Delta, DiffDelta, Oid, Repository};
+use scopetime::scope_time;
+use std::{fmt, fs, path::Path};
+
+/// larger files are not looked at
+const MAX_SIZE: usize = 16 * 1024 * 1024;
+
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LineEnding {
+	/// no line break at all
+	None,
+	///
+	Lf,
+	///
+	CrLf,
+	/// both `LF` and `CRLF`
+	Mixed,
+}
+
+impl fmt::Display for LineEnding {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::None => "no EOL",
+			Self::Lf => "LF",
+			Self::CrLf => "CRLF",
+			Self::Mixed => "mixed EOL",
+		})
+	}
+}
+
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TextEncoding {
+	///
+	Utf8,
+	/// with byte order mark
+	Utf8Bom,
+	///
+	Utf16Le,
+	///
+	Utf16Be,
+	/// not valid UTF-8, like latin-1
+	Other,
+}
+
+impl fmt::Display for TextEncoding {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::Utf8 => "UTF-8",
+			Self::Utf8Bom => "UTF-8 BOM",
+			Self::Utf16Le => "UTF-16LE",
+			Self::Utf16Be => "UTF-16BE",
+			Self::Other => "non-UTF-8",
+		})
+	}
+}
+
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TextFormat {
+	///
+	pub line_ending: LineEnding,
+	///
+	pub encoding: TextEncoding,
+}
+
+impl TextFormat {
+	/// `None` for files too large to look at
+	pub fn detect(content: &[u8]) -> Option<Self> {
+		if content.len() > MAX_SIZE {
+			return None;
+		}
+
+		let encoding = if content.starts_with(b"\xEF\xBB\xBF") {
+			TextEncoding::Utf8Bom
+		} else if content.starts_with(b"\xFF\xFE") {
+			TextEncoding::Utf16Le
+		} else if content.starts_with(b"\xFE\xFF") {
+			TextEncoding::Utf16Be
+		} else if std::str::from_utf8(content).is_ok() {
+			TextEncoding::Utf8
+		} else {
+			TextEncoding::Other
+		};
+
+		// the line breaks of UTF-16 are two bytes wide
+		let line_ending = match encoding {
+			TextEncoding::Utf16Le => {
+				line_ending(content, b"\r\0\n\0", b"\n\0")
+			}
+			TextEncoding::Utf16Be => {
+				line_ending(content, b"\0\r\0\n", b"\0\n")
+			}
+			_ => line_ending(content, b"\r\n", b"\n"),
+		};
+
+		Some(Self {
+			line_ending,
+			encoding,
+		})
+	}
+}
+
+/// the formats of both sides of a text file diff
+pub fn diff_text_formats(
+	repo: &Repository,
+	delta: &DiffDelta,
+	work_dir: &Path,
+) -> OldNew<Option<TextFormat>> {
+	let (old, new) = (delta.old_file(), delta.new_file());
+	let old_exists = !matches!(
+		delta.status(),
+		Delta::Added | Delta::Untracked | Delta::Ignored
+	);
+	let new_exists = delta.status() != Delta::Deleted;
+
+	let blob = |id: Oid| {
+		repo.find_blob(id)
+			.ok()
+			.and_then(|blob| TextFormat::detect(blob.content()))
+	};
+	// workdir files are not hashed by the diff
+	let file = || {
+		let path = work_dir.join(new.path()?);
+		let size = fs::metadata(&path).ok()?.len();
+		if usize::try_from(size).ok()? > MAX_SIZE {
+			return None;
+		}
+
+		TextFormat::detect(&fs::read(path).ok()?)
+	};
+
+	OldNew {
+		old: old_exists.then(|| blob(old.id())).flatten(),
+		new: new_exists
+			.then(|| {
+				if new.id().is_zero() {
+					file()
+				} else {
+					blob(new.id()).or_else(file)
+				}
+			})
+			.flatten(),
+	}
+}
+
+fn line_ending(content: &[u8], crlf: &[u8], lf: &[u8]) -> LineEnding {
+	let count = |pattern: &[u8]| {
+		content
+			.windows(pattern.len())
+			.filter(|window| *window == pattern)
+			.count()
+	};
+	let (crlfs, lfs) = (count(crlf), count(lf));
+
+	match (crlfs, lfs) {
+		(0, 0) => LineEnding::None,
+		(0, _) => LineEnding::Lf,
+		(crlfs, lfs) if crlfs == lfs => LineEnding::CrLf,
+		_ => LineEnding::Mixed,
+	}
+}
+
+/// replaces the `CRLF` line endings of the staged version of `path`
+/// with `LF`, leaving the workdir alone
+pub fn normalize_staged_eol(
+	repo_path: &RepoPath,
+	path: &str,
+) -> Result<()> {
+	scope_time!("normalize_staged_eol");
+
+	let repo = repo(repo_path)?;
+	let mut index = repo.index()?;
+
+	let mut entry =
+		index.get_path(Path::new(path), 0).ok_or_else(|| {
+			Error::Generic(format!("'{path}' is not staged"))
+		})?;
+
+	let blob = repo.find_blob(entry.id)?;
+	if blob.is_binary() {
+		return Err(Error::Generic(format!("'{path}' is binary")));
+	}
+
+	let content = blob.content();
+	let mut normalized = Vec::with_capacity(content.len());
+	let mut bytes = content.iter().peekable();
+	while let Some(byte) = bytes.next() {
+		if *byte != b'\r' || bytes.peek() != Some(&&b'\n') {
+			normalized.push(*byte);
+		}
+	}
+
+	if normalized.len() == content.len() {
+		return Ok(());
+	}
+
+	entry.id = repo.blob(&normalized)?;
+	entry.file_size = u32::try_from(normalized.len())
+		.map_err(|_| Error::Generic(format!("'{path}' too large")))?;
+	index.add(&entry)?;
+	index.write()?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		diff::{get_diff, DiffLineType},
+		stage_add_file,
+		tests::{repo_init, write_commit_file},
+	};
+	use std::fs;
+
+	#[test]
+	fn test_detect() {
+		let detect = |content: &[u8]| TextFormat::detect(content);
+
+		assert_eq!(
+			detect(b"a\nb\n"),
+			Some(TextFormat {
+				line_ending: LineEnding::Lf,
+				encoding: TextEncoding::Utf8,
+			})
+		);
+		assert_eq!(
+			detect(b"a\r\nb\r\n").unwrap().line_ending,
+			LineEnding::CrLf
+		);
+		assert_eq!(
+			detect(b"a\r\nb\n").unwrap().line_ending,
+			LineEnding::Mixed
+		);
+		assert_eq!(
+			detect(b"a").unwrap().line_ending,
+			LineEnding::None
+		);
+		assert_eq!(
+			detect(b"\xEF\xBB\xBFa\n").unwrap().encoding,
+			TextEncoding::Utf8Bom
+		);
+		assert_eq!(
+			detect(b"caf\xE9\n").unwrap().encoding,
+			TextEncoding::Other
+		);
+		assert_eq!(
+			detect(b"\xFF\xFEa\0\r\0\n\0"),
+			Some(TextFormat {
+				line_ending: LineEnding::CrLf,
+				encoding: TextEncoding::Utf16Le,
+			})
+		);
+	}
+
+	#[test]
+	fn test_normalize_staged_eol() {
+		let (td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "a.txt", "a\nb\n", "a");
+		fs::write(td.path().join("a.txt"), "a\r\nb\r\nc\r\n")
+			.unwrap();
+		stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+
+		let diff = get_diff(repo_path, "a.txt", true, None).unwrap();
+		assert_eq!(
+			diff.text_format.new.map(|format| format.line_ending),
+			Some(LineEnding::CrLf)
+		);
+
+		normalize_staged_eol(repo_path, "a.txt").unwrap();
+
+		let diff = get_diff(repo_path, "a.txt", true, None).unwrap();
+		// only the added line is left
+		assert_eq!(
+			diff.hunks[0]
+				.lines
+				.iter()
+				.filter(|line| line.line_type != DiffLineType::None)
+				.map(|line| line.content.as_ref())
+				.collect::<Vec<_>>(),
+			vec!["@@ -1,2 +1,3 @@", "c"]
+		);
+		assert_eq!(
+			diff.text_format.new.map(|format| format.line_ending),
+			Some(LineEnding::Lf)
+		);
+
+		// the workdir keeps its line endings
+		assert_eq!(
+			fs::read(td.path().join("a.txt")).unwrap(),
+			b"a\r\nb\r\nc\r\n"
+		);
+	}
+}
@@ -0,0 +1,454 @@
+//! workdir content saved before it is discarded, kept in
+//! `gitui-trash` in the git dir until it is restored or dropped.
+//! entries older than `MAX_AGE_DAYS` and the oldest ones beyond
+//! `MAX_SIZE` in total are pruned whenever a new one is added
+
+use super::{
+	repository::repo,
+	status::{get_status, StatusItemType, StatusType},
+	utils::{repo_dir, work_dir},
+	RepoPath, ShowUntrackedFilesConfig,
+};
+use crate::error::{Error, Result};
+use scopetime::scope_time;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+const TRASH_DIR: &str = "gitui-trash";
+const MAX_AGE_DAYS: i64 = 30;
+const MAX_SIZE: u64 = 256 * 1024 * 1024;
+
+/// the files of a single discard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrashEntry {
+	/// name of the folder of the entry
+	pub id: String,
+	/// unix time of the discard in seconds
+	pub time: i64,
+	/// saved files relative to the workdir
+	pub files: Vec<String>,
+}
+
+/// copies the changed and untracked files in `paths` (files or
+/// folders) into a new trash entry, `None` if there was nothing to
+/// save
+pub fn trash_paths(
+	repo_path: &RepoPath,
+	paths: &[String],
+) -> Result<Option<TrashEntry>> {
+	scope_time!("trash_paths");
+
+	let workdir = work_dir(&repo(repo_path)?)?.to_owned();
+
+	let files: Vec<String> = get_status(
+		repo_path,
+		StatusType::WorkingDir,
+		Some(ShowUntrackedFilesConfig::All),
+	)?
+	.into_iter()
+	.filter(|item| {
+		item.status != StatusItemType::Deleted
+			&& paths.iter().any(|path| contains(path, &item.path))
+			&& workdir.join(&item.path).symlink_metadata().is_ok_and(
+				|meta| {
+					meta.is_file() || meta.file_type().is_symlink()
+				},
+			)
+	})
+	.map(|item| item.path)
+	.collect();
+
+	if files.is_empty() {
+		return Ok(None);
+	}
+
+	let (id, dir) = new_entry_dir(repo_path)?;
+
+	for file in &files {
+		copy_file(&workdir.join(file), &dir.join(file))?;
+	}
+
+	if let Err(e) = prune(
+		&trash_dir(repo_path)?,
+		entry_time(&id) - MAX_AGE_DAYS * 24 * 60 * 60,
+		MAX_SIZE,
+	) {
+		log::error!("trash prune error: {e}");
+	}
+
+	Ok(Some(TrashEntry {
+		time: entry_time(&id),
+		id,
+		files,
+	}))
+}
+
+/// all trash entries, newest first
+pub fn trash_list(repo_path: &RepoPath) -> Result<Vec<TrashEntry>> {
+	scope_time!("trash_list");
+
+	let mut entries = read_entries(&trash_dir(repo_path)?)?;
+
+	entries.sort_by(|a, b| {
+		b.time.cmp(&a.time).then_with(|| b.id.cmp(&a.id))
+	});
+
+	Ok(entries)
+}
+
+fn read_entries(trash: &Path) -> Result<Vec<TrashEntry>> {
+	if !trash.exists() {
+		return Ok(Vec::new());
+	}
+
+	let mut entries = Vec::new();
+
+	for dir in fs::read_dir(trash)? {
+		let dir = dir?;
+
+		if !dir.file_type()?.is_dir() {
+			continue;
+		}
+
+		let id = dir.file_name().to_string_lossy().to_string();
+		let mut files = Vec::new();
+		list_files(&dir.path(), &dir.path(), &mut files)?;
+		files.sort();
+
+		entries.push(TrashEntry {
+			time: entry_time(&id),
+			id,
+			files,
+		});
+	}
+
+	Ok(entries)
+}
+
+/// removes the entries from before `oldest` (unix time in seconds)
+/// and the oldest ones once the newer ones take up `max_size` bytes,
+/// the newest entry is always kept
+fn prune(trash: &Path, oldest: i64, max_size: u64) -> Result<()> {
+	let mut entries = read_entries(trash)?;
+	entries.sort_by(|a, b| {
+		b.time.cmp(&a.time).then_with(|| b.id.cmp(&a.id))
+	});
+
+	let mut size = 0_u64;
+
+	for (idx, entry) in entries.iter().enumerate() {
+		let dir = trash.join(&entry.id);
+
+		size = size.saturating_add(
+			entry
+				.files
+				.iter()
+				.filter_map(|file| {
+					dir.join(file).symlink_metadata().ok()
+				})
+				.map(|meta| meta.len())
+				.sum(),
+		);
+
+		if idx > 0 && (entry.time < oldest || size > max_size) {
+			fs::remove_dir_all(dir)?;
+		}
+	}
+
+	Ok(())
+}
+
+/// writes the files of the entry back into the workdir, overwriting
+/// what is there, and removes the entry
+pub fn trash_restore(repo_path: &RepoPath, id: &str) -> Result<()> {
+	scope_time!("trash_restore");
+
+	let workdir = work_dir(&repo(repo_path)?)?.to_owned();
+	let dir = entry_dir(repo_path, id)?;
+
+	let mut files = Vec::new();
+	list_files(&dir, &dir, &mut files)?;
+
+	for file in &files {
+		copy_file(&dir.join(file), &workdir.join(file))?;
+	}
+
+	fs::remove_dir_all(dir)?;
+
+	Ok(())
+}
+
+/// deletes the entry for good
+pub fn trash_drop(repo_path: &RepoPath, id: &str) -> Result<()> {
+	scope_time!("trash_drop");
+
+	fs::remove_dir_all(entry_dir(repo_path, id)?)?;
+
+	Ok(())
+}
+
+fn trash_dir(repo_path: &RepoPath) -> Result<PathBuf> {
+	Ok(repo_dir(repo_path)?.join(TRASH_DIR))
+}
+
+/// the folder of an existing entry, refusing ids that leave the trash
+fn entry_dir(repo_path: &RepoPath, id: &str) -> Result<PathBuf> {
+	if id.is_empty() || Path::new(id).file_name() != Some(id.as_ref())
+	{
+		return Err(Error::Generic(format!(
+			"invalid trash entry: {id}"
+		)));
+	}
+
+	let dir = trash_dir(repo_path)?.join(id);
+
+	if !dir.is_dir() {
+		return Err(Error::Generic(format!(
+			"trash entry not found: {id}"
+		)));
+	}
+
+	Ok(dir)
+}
+
+/// ids are the creation time in milliseconds, with a counter for
+/// entries created within the same millisecond
+fn new_entry_dir(repo_path: &RepoPath) -> Result<(String, PathBuf)> {
+	let trash = trash_dir(repo_path)?;
+	let millis = SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map_err(|e| Error::Generic(e.to_string()))?
+		.as_millis();
+
+	let mut id = millis.to_string();
+	let mut counter = 0;
+	while trash.join(&id).exists() {
+		counter += 1;
+		id = format!("{millis}-{counter}");
+	}
+
+	let dir = trash.join(&id);
+	fs::create_dir_all(&dir)?;
+
+	Ok((id, dir))
+}
+
+fn entry_time(id: &str) -> i64 {
+	id.split('-')
+		.next()
+		.and_then(|millis| millis.parse::<i64>().ok())
+		.map_or(0, |millis| millis / 1000)
+}
+
+/// whether `file` is `path` or inside the folder `path`
+fn contains(path: &str, file: &str) -> bool {
+	let path = path.trim_end_matches('/');
+
+	file == path
+		|| file
+			.strip_prefix(path)
+			.is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// copies symlinks as links instead of their target
+fn copy_file(from: &Path, to: &Path) -> Result<()> {
+	if let Some(parent) = to.parent() {
+		fs::create_dir_all(parent)?;
+	}
+
+	let link = from.symlink_metadata()?.is_symlink();
+
+	// neither writing through a link nor failing on an existing file
+	if to
+		.symlink_metadata()
+		.is_ok_and(|meta| link || meta.is_symlink())
+	{
+		fs::remove_file(to)?;
+	}
+
+	if link {
+		symlink(&fs::read_link(from)?, to)?;
+	} else {
+		fs::copy(from, to)?;
+	}
+
+	Ok(())
+}
+
+#[cfg(unix)]
+fn symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+	std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+	std::os::windows::fs::symlink_file(target, link)
+}
+
+fn list_files(
+	root: &Path,
+	dir: &Path,
+	files: &mut Vec<String>,
+) -> Result<()> {
+	for entry in fs::read_dir(dir)? {
+		let entry = entry?;
+		let path = entry.path();
+
+		// not following trashed symlinks
+		if entry.file_type()?.is_dir() {
+			list_files(root, &path, files)?;
+		} else if let Ok(file) = path.strip_prefix(root) {
+			files.push(file.to_string_lossy().replace('\\', "/"));
+		}
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{
+		prune, trash_dir, trash_drop, trash_list, trash_paths,
+		trash_restore,
+	};
+	use crate::sync::{
+		reset_workdir,
+		tests::{repo_init, write_commit_file},
+		RepoPath,
+	};
+	use std::fs;
+
+	#[test]
+	fn test_trash_and_restore() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "foo.txt", "old", "c1");
+		fs::write(root.join("foo.txt"), "changed").unwrap();
+		fs::create_dir(root.join("dir")).unwrap();
+		fs::write(root.join("dir/new.txt"), "new").unwrap();
+		fs::write(root.join("other.txt"), "other").unwrap();
+
+		let paths = [String::from("foo.txt"), String::from("dir")];
+		let entry = trash_paths(repo_path, &paths).unwrap().unwrap();
+		assert_eq!(entry.files.len(), 2);
+
+		for path in &paths {
+			reset_workdir(repo_path, path).unwrap();
+		}
+		assert_eq!(
+			fs::read_to_string(root.join("foo.txt")).unwrap(),
+			"old"
+		);
+		assert!(!root.join("dir/new.txt").exists());
+
+		let list = trash_list(repo_path).unwrap();
+		assert_eq!(list.len(), 1);
+		assert_eq!(list[0].id, entry.id);
+		assert_eq!(list[0].files, vec!["dir/new.txt", "foo.txt"]);
+
+		trash_restore(repo_path, &entry.id).unwrap();
+		assert_eq!(
+			fs::read_to_string(root.join("foo.txt")).unwrap(),
+			"changed"
+		);
+		assert_eq!(
+			fs::read_to_string(root.join("dir/new.txt")).unwrap(),
+			"new"
+		);
+		assert!(trash_list(repo_path).unwrap().is_empty());
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn test_trash_symlink() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "foo.txt", "old", "c1");
+		std::os::unix::fs::symlink("foo.txt", root.join("link"))
+			.unwrap();
+
+		let entry = trash_paths(repo_path, &[String::from("link")])
+			.unwrap()
+			.unwrap();
+		assert_eq!(entry.files, vec!["link"]);
+
+		fs::remove_file(root.join("link")).unwrap();
+		trash_restore(repo_path, &entry.id).unwrap();
+
+		assert_eq!(
+			fs::read_link(root.join("link")).unwrap(),
+			std::path::Path::new("foo.txt")
+		);
+		assert_eq!(
+			fs::read_to_string(root.join("foo.txt")).unwrap(),
+			"old"
+		);
+	}
+
+	#[test]
+	fn test_prune() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		let trash = trash_dir(repo_path).unwrap();
+		for (id, size) in
+			[("1000", 1), ("50000", 10), ("60000", 10), ("70000", 10)]
+		{
+			fs::create_dir_all(trash.join(id)).unwrap();
+			fs::write(trash.join(id).join("f"), "x".repeat(size))
+				.unwrap();
+		}
+
+		// too old
+		prune(&trash, 10, 100).unwrap();
+		assert_eq!(trash_list(repo_path).unwrap().len(), 3);
+
+		// too big
+		prune(&trash, 10, 15).unwrap();
+		let ids: Vec<_> = trash_list(repo_path)
+			.unwrap()
+			.into_iter()
+			.map(|entry| entry.id)
+			.collect();
+		assert_eq!(ids, vec!["70000"]);
+
+		// the newest one stays regardless
+		prune(&trash, 100, 0).unwrap();
+		assert_eq!(trash_list(repo_path).unwrap().len(), 1);
+	}
+
+	#[test]
+	fn test_trash_nothing_and_drop() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "foo.txt", "old", "c1");
+
+		assert!(trash_paths(repo_path, &[String::from("foo.txt")])
+			.unwrap()
+			.is_none());
+
+		fs::write(root.join("foo.txt"), "changed").unwrap();
+		let entry =
+			trash_paths(repo_path, &[String::from("foo.txt")])
+				.unwrap()
+				.unwrap();
+
+		assert!(trash_drop(repo_path, "../..").is_err());
+		trash_drop(repo_path, &entry.id).unwrap();
+		assert!(trash_list(repo_path).unwrap().is_empty());
+	}
+}
@@ -91,6 +91,69 @@ pub fn tree_file_content(
 	Ok(content)
 }
 
+/// a line containing the text searched by `search_tree`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TreeSearchMatch {
+	/// relative to the workdir
+	pub path: String,
+	/// 1-based
+	pub line: usize,
+	///
+	pub content: String,
+}
+
+/// case insensitive search for `pattern` in the text files of
+/// `commit`, in tree order and stopping after `limit` matches
+pub fn search_tree(
+	repo_path: &RepoPath,
+	commit: CommitId,
+	pattern: &str,
+	limit: usize,
+) -> Result<Vec<TreeSearchMatch>> {
+	scope_time!("search_tree");
+
+	let pattern = pattern.to_lowercase();
+	let files = tree_files(repo_path, commit)?;
+
+	let repo = repo(repo_path)?;
+
+	let mut res = Vec::new();
+
+	for file in files {
+		// submodules and symlinks
+		if file.filemode != i32::from(git2::FileMode::Blob)
+			&& file.filemode
+				!= i32::from(git2::FileMode::BlobExecutable)
+		{
+			continue;
+		}
+
+		let blob = repo.find_blob(file.id)?;
+		if blob.is_binary() {
+			continue;
+		}
+
+		let path = file.path.strip_prefix("./").unwrap_or(&file.path);
+		let content = String::from_utf8_lossy(blob.content());
+
+		for (idx, line) in content.lines().enumerate() {
+			if line.to_lowercase().contains(&pattern) {
+				res.push(TreeSearchMatch {
+					path: path.to_string_lossy().into_owned(),
+					line: idx + 1,
+					content: line.to_string(),
+				});
+
+				if res.len() >= limit {
+					return Ok(res);
+				}
+			}
+		}
+	}
+
+	Ok(res)
+}
+
 ///
 fn tree_recurse(
 	repo: &Repository,
@@ -154,6 +217,41 @@ mod tests {
 		assert_ne!(files_c2[0], files[0]);
 	}
 
+	#[test]
+	fn test_search_tree() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "a.txt", "foo\nbar\nFoo bar", "c1");
+		let c2 = write_commit_file(&repo, "c.txt", "nope", "c2");
+		write_commit_file(&repo, "a.txt", "", "c3");
+
+		let matches = search_tree(repo_path, c2, "foo", 10).unwrap();
+
+		assert_eq!(
+			matches,
+			vec![
+				TreeSearchMatch {
+					path: String::from("a.txt"),
+					line: 1,
+					content: String::from("foo"),
+				},
+				TreeSearchMatch {
+					path: String::from("a.txt"),
+					line: 3,
+					content: String::from("Foo bar"),
+				},
+			]
+		);
+
+		assert_eq!(
+			search_tree(repo_path, c2, "o", 2).unwrap().len(),
+			2
+		);
+	}
+
 	#[test]
 	fn test_sorting() {
 		let mut list = ["file", "folder/file", "folder/afile"]
@@ -7,11 +7,13 @@ use crate::{
 	error::{Error, Result},
 	sync::config::untracked_files_config_repo,
 };
-use git2::{IndexAddOption, Repository, RepositoryOpenFlags};
+use git2::{
+	FileMode, Index, IndexAddOption, IndexEntry, IndexTime, Oid,
+	Repository, RepositoryOpenFlags,
+};
 use scopetime::scope_time;
 use std::{
-	fs::File,
-	io::Write,
+	fs::{self, File},
 	path::{Path, PathBuf},
 };
 
@@ -45,12 +47,34 @@ pub(crate) fn work_dir(repo: &Repository) -> Result<&Path> {
 	repo.workdir().ok_or(Error::NoWorkDir)
 }
 
+/// the target of the symlink at `path` the way git records it, with
+/// `/` separators on windows as well
+pub(crate) fn symlink_target(path: &Path) -> Result<String> {
+	let target = fs::read_link(path)?;
+	let target = target.to_str().ok_or_else(|| {
+		Error::Generic(format!("invalid symlink target: {target:?}"))
+	})?;
+
+	Ok(if cfg!(windows) {
+		target.replace('\\', "/")
+	} else {
+		target.to_string()
+	})
+}
+
 /// path to .git folder
 pub fn repo_dir(repo_path: &RepoPath) -> Result<PathBuf> {
 	let repo = repo(repo_path)?;
 	Ok(repo.path().to_owned())
 }
 
+/// path to the .git folder shared by all worktrees, the same as
+/// `repo_dir` outside of linked worktrees
+pub fn repo_common_dir(repo_path: &RepoPath) -> Result<PathBuf> {
+	let repo = repo(repo_path)?;
+	Ok(repo.commondir().to_owned())
+}
+
 ///
 pub fn repo_work_dir(repo_path: &RepoPath) -> Result<String> {
 	let repo = repo(repo_path)?;
@@ -103,12 +127,53 @@ pub fn stage_add_file(
 
 	let mut index = repo.index()?;
 
-	index.add_path(path)?;
+	if let Err(e) = index.add_path(path) {
+		// libgit2 follows symlinks to folders on windows
+		let link = work_dir(&repo)?.join(path);
+		if !fs::symlink_metadata(&link)
+			.is_ok_and(|meta| meta.file_type().is_symlink())
+		{
+			return Err(e.into());
+		}
+
+		add_symlink(&mut index, &link, path)?;
+	}
 	index.write()?;
 
 	Ok(())
 }
 
+/// stages the symlink `link` at `path` the way git does, as a blob of
+/// its target
+fn add_symlink(
+	index: &mut Index,
+	link: &Path,
+	path: &Path,
+) -> Result<()> {
+	let path = path.to_str().ok_or_else(|| {
+		Error::Generic(format!("invalid path: {path:?}"))
+	})?;
+	let target = symlink_target(link)?;
+
+	let entry = IndexEntry {
+		ctime: IndexTime::new(0, 0),
+		mtime: IndexTime::new(0, 0),
+		dev: 0,
+		ino: 0,
+		mode: u32::from(FileMode::Link),
+		uid: 0,
+		gid: 0,
+		file_size: u32::try_from(target.len()).unwrap_or_default(),
+		id: Oid::zero(),
+		flags: 0,
+		flags_extended: 0,
+		path: path.replace('\\', "/").into_bytes(),
+	};
+	index.add_frombuffer(&entry, target.as_bytes())?;
+
+	Ok(())
+}
+
 /// like `stage_add_file` but uses a pattern to match/glob multiple files/folders
 pub fn stage_add_all(
 	repo_path: &RepoPath,
@@ -117,6 +182,18 @@ pub fn stage_add_all(
 ) -> Result<()> {
 	scope_time!("stage_add_all");
 
+	stage_add_all_except(repo_path, pattern, stage_untracked, &[])
+}
+
+/// like `stage_add_all` but leaves the paths in `except` alone
+pub fn stage_add_all_except(
+	repo_path: &RepoPath,
+	pattern: &str,
+	stage_untracked: Option<ShowUntrackedFilesConfig>,
+	except: &[String],
+) -> Result<()> {
+	scope_time!("stage_add_all_except");
+
 	let repo = repo(repo_path)?;
 
 	let mut index = repo.index()?;
@@ -127,14 +204,21 @@ pub fn stage_add_all(
 		untracked_files_config_repo(&repo)?
 	};
 
+	// a positive return value skips the path
+	let mut skip = |path: &Path, _: &[u8]| {
+		i32::from(
+			except.iter().any(|except| Path::new(except) == path),
+		)
+	};
+
 	if stage_untracked.include_untracked() {
 		index.add_all(
 			vec![pattern],
 			IndexAddOption::DEFAULT,
-			None,
+			Some(&mut skip),
 		)?;
 	} else {
-		index.update_all(vec![pattern], None)?;
+		index.update_all(vec![pattern], Some(&mut skip))?;
 	}
 
 	index.write()?;
@@ -174,22 +258,52 @@ pub fn stage_addremoved(
 	Ok(())
 }
 
+/// matches `text` against a glob `pattern` where `*` stands for any
+/// number of characters (including `/`) and `?` for exactly one
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+	let pattern: Vec<char> = pattern.chars().collect();
+	let text: Vec<char> = text.chars().collect();
+
+	let (mut p, mut t) = (0, 0);
+	// position of the last `*` and the text position it matched up to
+	let mut backtrack = None;
+
+	while t < text.len() {
+		match pattern.get(p) {
+			Some('*') => {
+				backtrack = Some((p, t));
+				p += 1;
+			}
+			Some(c) if *c == '?' || *c == text[t] => {
+				p += 1;
+				t += 1;
+			}
+			_ => {
+				let Some((star, matched)) = backtrack else {
+					return false;
+				};
+				p = star + 1;
+				t = matched + 1;
+				backtrack = Some((star, matched + 1));
+			}
+		}
+	}
+
+	pattern[p..].iter().all(|c| *c == '*')
+}
+
 pub(crate) fn bytes2string(bytes: &[u8]) -> Result<String> {
 	Ok(String::from_utf8(bytes.to_vec())?)
 }
 
 /// write a file in repo
+#[cfg(test)]
 pub(crate) fn repo_write_file(
 	repo: &Repository,
 	file: &str,
 	content: &str,
 ) -> Result<()> {
-	let dir = work_dir(repo)?.join(file);
-	let file_path = dir.to_str().ok_or_else(|| {
-		Error::Generic(String::from("invalid file path"))
-	})?;
-	let mut file = File::create(file_path)?;
-	file.write_all(content.as_bytes())?;
+	fs::write(work_dir(repo)?.join(file), content)?;
 	Ok(())
 }
 
@@ -229,6 +343,7 @@ mod tests {
 	use crate::sync::{
 		commit,
 		diff::get_diff,
+		reset_stage,
 		status::{get_status, StatusType},
 		tests::{
 			debug_cmd_print, get_statuses, repo_init,
@@ -242,6 +357,53 @@ mod tests {
 		path::Path,
 	};
 
+	#[test]
+	fn test_glob_match() {
+		assert!(glob_match("dependabot/*", "dependabot/npm/foo"));
+		assert!(glob_match("*/dependabot/*", "origin/dependabot/x"));
+		assert!(glob_match("feat?", "feat1"));
+		assert!(glob_match("*", ""));
+		assert!(glob_match("a*b*c", "aXbYbZc"));
+		assert!(!glob_match("dependabot/*", "origin/dependabot/x"));
+		assert!(!glob_match("feat?", "feat"));
+		assert!(!glob_match("a*b", "aXbY"));
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn test_add_symlink() {
+		let (_td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		fs::create_dir(root.join("dir")).unwrap();
+		std::os::unix::fs::symlink("dir", root.join("link")).unwrap();
+
+		// what libgit2 does is what git does
+		stage_add_file(repo_path, Path::new("link")).unwrap();
+		let mut index = repo.index().unwrap();
+		index.read(true).unwrap();
+		let libgit2_entry = index.get_path(Path::new("link"), 0);
+
+		reset_stage(repo_path, "link").unwrap();
+		index.read(true).unwrap();
+		assert!(index.get_path(Path::new("link"), 0).is_none());
+
+		add_symlink(
+			&mut index,
+			&root.join("link"),
+			Path::new("link"),
+		)
+		.unwrap();
+		let entry = index.get_path(Path::new("link"), 0).unwrap();
+		let libgit2_entry = libgit2_entry.unwrap();
+
+		assert_eq!(entry.id, libgit2_entry.id);
+		assert_eq!(entry.mode, libgit2_entry.mode);
+		assert_eq!(entry.file_size, libgit2_entry.file_size);
+	}
+
 	#[test]
 	fn test_stage_add_smoke() {
 		let file_path = Path::new("foo");
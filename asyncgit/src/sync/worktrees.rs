@@ -0,0 +1,457 @@
+//! linked worktrees sharing the repository
+
+use super::{repository::repo, utils::work_dir, RepoPath};
+use crate::error::{Error, Result};
+use git2::{
+	BranchType, Repository, StatusOptions, Worktree,
+	WorktreeAddOptions, WorktreeLockStatus, WorktreePruneOptions,
+};
+use scopetime::scope_time;
+use std::path::{Path, PathBuf};
+
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorktreeInfo {
+	/// name of a linked worktree, `None` for the main one
+	pub name: Option<String>,
+	///
+	pub path: PathBuf,
+	/// checked out branch, `None` if detached
+	pub branch: Option<String>,
+	/// worktree `repo_path` points into
+	pub is_current: bool,
+	/// locked against pruning
+	pub is_locked: bool,
+	/// its folder got moved or deleted
+	pub is_prunable: bool,
+}
+
+/// name of the linked worktree `repo_path` points into, `None` for
+/// the main worktree
+pub fn current_worktree_name(
+	repo_path: &RepoPath,
+) -> Result<Option<String>> {
+	let repo = repo(repo_path)?;
+
+	if !repo.is_worktree() {
+		return Ok(None);
+	}
+
+	Ok(Worktree::open_from_repository(&repo)?
+		.name()
+		.map(String::from))
+}
+
+/// main worktree first, followed by the linked ones by name
+pub fn get_worktrees(
+	repo_path: &RepoPath,
+) -> Result<Vec<WorktreeInfo>> {
+	scope_time!("get_worktrees");
+
+	let repo = repo(repo_path)?;
+	let current = repo.workdir().map(canonicalize);
+	let main = Repository::open(repo.commondir())?;
+
+	let mut res = Vec::new();
+
+	if let Some(path) = main.workdir() {
+		res.push(WorktreeInfo {
+			name: None,
+			path: path.to_path_buf(),
+			branch: head_branch(&main),
+			is_current: current.as_ref() == Some(&canonicalize(path)),
+			is_locked: false,
+			is_prunable: false,
+		});
+	}
+
+	let mut names: Vec<_> = main
+		.worktrees()?
+		.iter()
+		.flatten()
+		.map(String::from)
+		.collect();
+	names.sort();
+
+	for name in names {
+		let worktree = main.find_worktree(&name)?;
+		let path = worktree.path().to_path_buf();
+		let is_prunable = worktree.validate().is_err();
+
+		res.push(WorktreeInfo {
+			branch: if is_prunable {
+				None
+			} else {
+				Repository::open_from_worktree(&worktree)
+					.ok()
+					.and_then(|repo| head_branch(&repo))
+			},
+			is_current: current.as_ref()
+				== Some(&canonicalize(&path)),
+			is_locked: !matches!(
+				worktree.is_locked(),
+				Ok(WorktreeLockStatus::Unlocked)
+			),
+			name: Some(name),
+			path,
+			is_prunable,
+		});
+	}
+
+	Ok(res)
+}
+
+/// adds a linked worktree at `path` (relative to the workdir) named
+/// after its folder.
+///
+/// like the git cli it checks out `branch` or else the branch named
+/// after the folder, both created at `HEAD` if they do not exist
+pub fn add_worktree(
+	repo_path: &RepoPath,
+	path: &str,
+	branch: Option<&str>,
+) -> Result<()> {
+	scope_time!("add_worktree");
+
+	let repo = repo(repo_path)?;
+	let main = Repository::open(repo.commondir())?;
+	let path = work_dir(&repo)?.join(path);
+
+	let name = path
+		.file_name()
+		.and_then(|name| name.to_str())
+		.ok_or_else(|| {
+			Error::Generic(format!(
+				"invalid worktree path: {}",
+				path.display()
+			))
+		})?;
+	if main.find_worktree(name).is_ok() {
+		return Err(Error::Generic(format!(
+			"worktree '{name}' already exists"
+		)));
+	}
+
+	let branch_name = branch.unwrap_or(name);
+	let branch =
+		match repo.find_branch(branch_name, BranchType::Local) {
+			Ok(branch) => branch,
+			Err(_) => repo.branch(
+				branch_name,
+				&repo.head()?.peel_to_commit()?,
+				false,
+			)?,
+		};
+
+	main.worktree(
+		name,
+		&path,
+		Some(WorktreeAddOptions::new().reference(Some(branch.get()))),
+	)?;
+
+	Ok(())
+}
+
+/// deletes the linked worktree `name` with its folder, refuses to
+/// if it is locked, the current one or has changes
+pub fn remove_worktree(
+	repo_path: &RepoPath,
+	name: &str,
+) -> Result<()> {
+	scope_time!("remove_worktree");
+
+	let repo = repo(repo_path)?;
+	let main = Repository::open(repo.commondir())?;
+	let worktree = main.find_worktree(name)?;
+
+	if !matches!(worktree.is_locked()?, WorktreeLockStatus::Unlocked)
+	{
+		return Err(Error::Generic(format!(
+			"worktree '{name}' is locked"
+		)));
+	}
+
+	if worktree.validate().is_ok() {
+		if repo.workdir().map(canonicalize)
+			== Some(canonicalize(worktree.path()))
+		{
+			return Err(Error::Generic(format!(
+				"worktree '{name}' is the current one"
+			)));
+		}
+
+		let worktree_repo =
+			Repository::open_from_worktree(&worktree)?;
+		let mut options = StatusOptions::new();
+		options.include_untracked(true).include_ignored(false);
+		if !worktree_repo.statuses(Some(&mut options))?.is_empty() {
+			return Err(Error::Generic(format!(
+				"worktree '{name}' has changes"
+			)));
+		}
+	}
+
+	worktree.prune(Some(
+		WorktreePruneOptions::new().valid(true).working_tree(true),
+	))?;
+
+	Ok(())
+}
+
+/// forgets the linked worktrees whose folder is gone and that are
+/// not locked, returns how many
+pub fn prune_worktrees(repo_path: &RepoPath) -> Result<usize> {
+	scope_time!("prune_worktrees");
+
+	let repo = repo(repo_path)?;
+	let main = Repository::open(repo.commondir())?;
+
+	let mut pruned = 0;
+	for name in main.worktrees()?.iter().flatten() {
+		let worktree = main.find_worktree(name)?;
+		if worktree.is_prunable(None)? {
+			worktree.prune(None)?;
+			pruned += 1;
+		}
+	}
+
+	Ok(pruned)
+}
+
+/// locks the linked worktree `name` against pruning and removal
+pub fn lock_worktree(
+	repo_path: &RepoPath,
+	name: &str,
+	reason: Option<&str>,
+) -> Result<()> {
+	let repo = repo(repo_path)?;
+	let main = Repository::open(repo.commondir())?;
+	main.find_worktree(name)?.lock(reason)?;
+
+	Ok(())
+}
+
+///
+pub fn unlock_worktree(
+	repo_path: &RepoPath,
+	name: &str,
+) -> Result<()> {
+	let repo = repo(repo_path)?;
+	let main = Repository::open(repo.commondir())?;
+	main.find_worktree(name)?.unlock()?;
+
+	Ok(())
+}
+
+fn head_branch(repo: &Repository) -> Option<String> {
+	let head = repo.head().ok()?;
+	head.is_branch()
+		.then(|| head.shorthand().map(String::from))
+		.flatten()
+}
+
+fn canonicalize(path: &Path) -> PathBuf {
+	path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sync::{
+		get_stashes, hooks_pre_commit, repo_common_dir, repo_dir,
+		repo_state, stash_save,
+		tests::{repo_init, write_commit_file},
+		HookResult, RepoState,
+	};
+	use git2::WorktreeAddOptions;
+	use std::fs;
+
+	#[test]
+	fn test_worktrees() {
+		let (td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "a.txt", "a", "commit");
+
+		assert_eq!(current_worktree_name(repo_path).unwrap(), None);
+		assert_eq!(get_worktrees(repo_path).unwrap().len(), 1);
+
+		let wt_path = td.path().join("feature-wt");
+		let branch = repo
+			.branch(
+				"feature",
+				&repo.head().unwrap().peel_to_commit().unwrap(),
+				false,
+			)
+			.unwrap();
+		repo.worktree(
+			"feature-wt",
+			&wt_path,
+			Some(
+				WorktreeAddOptions::new()
+					.reference(Some(branch.get())),
+			),
+		)
+		.unwrap();
+
+		let worktrees = get_worktrees(repo_path).unwrap();
+		assert_eq!(worktrees.len(), 2);
+		assert_eq!(worktrees[0].name, None);
+		assert_eq!(worktrees[0].branch.as_deref(), Some("master"));
+		assert!(worktrees[0].is_current);
+		assert_eq!(worktrees[1].name.as_deref(), Some("feature-wt"));
+		assert_eq!(worktrees[1].branch.as_deref(), Some("feature"));
+		assert!(!worktrees[1].is_current);
+		assert!(!worktrees[1].is_prunable);
+
+		let wt_repo_path: &RepoPath =
+			&wt_path.as_os_str().to_str().unwrap().into();
+		assert_eq!(
+			current_worktree_name(wt_repo_path).unwrap().as_deref(),
+			Some("feature-wt")
+		);
+
+		let worktrees = get_worktrees(wt_repo_path).unwrap();
+		assert!(!worktrees[0].is_current);
+		assert!(worktrees[1].is_current);
+	}
+
+	#[test]
+	fn test_add_remove_worktree() {
+		let (td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "a.txt", "a", "commit");
+		repo.branch(
+			"existing",
+			&repo.head().unwrap().peel_to_commit().unwrap(),
+			false,
+		)
+		.unwrap();
+
+		let new_path = td.path().join("new");
+		add_worktree(repo_path, new_path.to_str().unwrap(), None)
+			.unwrap();
+		let existing_path = td.path().join("existing");
+		add_worktree(
+			repo_path,
+			existing_path.to_str().unwrap(),
+			None,
+		)
+		.unwrap();
+
+		let worktrees = get_worktrees(repo_path).unwrap();
+		assert_eq!(worktrees.len(), 3);
+		assert_eq!(worktrees[1].name.as_deref(), Some("existing"));
+		assert_eq!(worktrees[1].branch.as_deref(), Some("existing"));
+		assert_eq!(worktrees[2].name.as_deref(), Some("new"));
+		assert_eq!(worktrees[2].branch.as_deref(), Some("new"));
+		assert!(new_path.join("a.txt").exists());
+
+		// the branch is checked out already
+		assert!(add_worktree(
+			repo_path,
+			td.path().join("other").to_str().unwrap(),
+			Some("new"),
+		)
+		.is_err());
+
+		lock_worktree(repo_path, "new", Some("busy")).unwrap();
+		assert!(get_worktrees(repo_path).unwrap()[2].is_locked);
+		assert!(remove_worktree(repo_path, "new").is_err());
+		unlock_worktree(repo_path, "new").unwrap();
+
+		fs::write(new_path.join("b.txt"), "b").unwrap();
+		assert!(remove_worktree(repo_path, "new").is_err());
+		fs::remove_file(new_path.join("b.txt")).unwrap();
+
+		remove_worktree(repo_path, "new").unwrap();
+		assert!(!new_path.exists());
+		assert_eq!(get_worktrees(repo_path).unwrap().len(), 2);
+	}
+
+	#[test]
+	fn test_prune_worktrees() {
+		let (td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "a.txt", "a", "commit");
+
+		let gone_path = td.path().join("gone");
+		add_worktree(repo_path, gone_path.to_str().unwrap(), None)
+			.unwrap();
+		let locked_path = td.path().join("locked");
+		add_worktree(repo_path, locked_path.to_str().unwrap(), None)
+			.unwrap();
+		lock_worktree(repo_path, "locked", None).unwrap();
+
+		fs::remove_dir_all(&gone_path).unwrap();
+		fs::remove_dir_all(&locked_path).unwrap();
+
+		assert_eq!(prune_worktrees(repo_path).unwrap(), 1);
+
+		let worktrees = get_worktrees(repo_path).unwrap();
+		assert_eq!(worktrees.len(), 2);
+		assert_eq!(worktrees[1].name.as_deref(), Some("locked"));
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_linked_worktree_shares_repo_files() {
+		let (td, repo) = repo_init().unwrap();
+		let root = repo.path().parent().unwrap();
+		let repo_path: &RepoPath =
+			&root.as_os_str().to_str().unwrap().into();
+
+		write_commit_file(&repo, "a.txt", "a", "commit");
+
+		let wt_path = td.path().join("wt");
+		repo.worktree("wt", &wt_path, None).unwrap();
+		let wt_repo_path: &RepoPath =
+			&wt_path.as_os_str().to_str().unwrap().into();
+
+		assert_eq!(
+			repo_common_dir(wt_repo_path).unwrap(),
+			repo_dir(repo_path).unwrap()
+		);
+		assert_ne!(
+			repo_dir(wt_repo_path).unwrap(),
+			repo_dir(repo_path).unwrap()
+		);
+
+		// hooks of the main repository
+		git2_hooks::create_hook(
+			&repo,
+			git2_hooks::HOOK_PRE_COMMIT,
+			b"#!/bin/sh\nexit 1\n",
+		);
+		assert!(matches!(
+			hooks_pre_commit(wt_repo_path).unwrap(),
+			HookResult::NotOk(_)
+		));
+
+		// the stash is shared
+		fs::write(wt_path.join("a.txt"), "b").unwrap();
+		let stash =
+			stash_save(wt_repo_path, None, true, false).unwrap();
+		assert_eq!(get_stashes(repo_path).unwrap(), vec![stash]);
+
+		// the repo state is not
+		fs::write(
+			repo_dir(wt_repo_path).unwrap().join("MERGE_HEAD"),
+			format!("{stash}\n"),
+		)
+		.unwrap();
+		assert_eq!(
+			repo_state(wt_repo_path).unwrap(),
+			RepoState::Merge
+		);
+		assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+	}
+}
@@ -2,7 +2,7 @@ use crate::{
 	asyncjob::{AsyncJob, AsyncSingleJob, RunParams},
 	error::Result,
 	hash,
-	sync::{self, RepoPath},
+	sync::{self, RepoPath, TagWithMetadata},
 	AsyncGitNotification,
 };
 use crossbeam_channel::Sender;
@@ -169,3 +169,66 @@ impl AsyncJob for AsyncTagsJob {
 		Ok(notification)
 	}
 }
+
+enum TagListState {
+	Request(RepoPath),
+	Response(Result<Vec<TagWithMetadata>>),
+}
+
+/// all tags with their commit, tagger and signature in one pass for
+/// the tag list
+#[derive(Clone)]
+pub struct AsyncTagListJob {
+	state: Arc<Mutex<Option<TagListState>>>,
+}
+
+///
+impl AsyncTagListJob {
+	///
+	pub fn new(repo: RepoPath) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(TagListState::Request(
+				repo,
+			)))),
+		}
+	}
+
+	///
+	pub fn result(&self) -> Option<Result<Vec<TagWithMetadata>>> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					TagListState::Request(_) => None,
+					TagListState::Response(result) => Some(result),
+				};
+			}
+		}
+
+		None
+	}
+}
+
+impl AsyncJob for AsyncTagListJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		if let Ok(mut state) = self.state.lock() {
+			*state = state.take().map(|state| match state {
+				TagListState::Request(repo) => {
+					TagListState::Response(
+						sync::get_tags_with_metadata(&repo),
+					)
+				}
+				TagListState::Response(result) => {
+					TagListState::Response(result)
+				}
+			});
+		}
+
+		Ok(AsyncGitNotification::TagList)
+	}
+}
@@ -0,0 +1,105 @@
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	error::Result,
+	sync::{search_tree, CommitId, RepoPath, TreeSearchMatch},
+	AsyncGitNotification,
+};
+use std::sync::{Arc, Mutex};
+
+/// more would not help finding anything
+const MAX_MATCHES: usize = 1000;
+
+///
+pub struct TreeSearchResult {
+	///
+	pub commit: CommitId,
+	///
+	pub pattern: String,
+	///
+	pub result: Result<Vec<TreeSearchMatch>>,
+}
+
+enum JobState {
+	Request {
+		commit: CommitId,
+		pattern: String,
+		repo: RepoPath,
+	},
+	Response(TreeSearchResult),
+}
+
+/// searches the file contents of a commit
+#[derive(Clone, Default)]
+pub struct AsyncTreeSearchJob {
+	state: Arc<Mutex<Option<JobState>>>,
+}
+
+///
+impl AsyncTreeSearchJob {
+	///
+	pub fn new(
+		repo: RepoPath,
+		commit: CommitId,
+		pattern: String,
+	) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request {
+				repo,
+				commit,
+				pattern,
+			}))),
+		}
+	}
+
+	///
+	pub fn result(&self) -> Option<TreeSearchResult> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request { .. } => None,
+					JobState::Response(result) => Some(result),
+				};
+			}
+		}
+
+		None
+	}
+}
+
+impl AsyncJob for AsyncTreeSearchJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		if let Ok(mut state) = self.state.lock() {
+			*state = state.take().map(|state| match state {
+				JobState::Request {
+					commit,
+					pattern,
+					repo,
+				} => {
+					let result = search_tree(
+						&repo,
+						commit,
+						&pattern,
+						MAX_MATCHES,
+					);
+
+					JobState::Response(TreeSearchResult {
+						commit,
+						pattern,
+						result,
+					})
+				}
+				JobState::Response(result) => {
+					JobState::Response(result)
+				}
+			});
+		}
+
+		Ok(AsyncGitNotification::TreeSearch)
+	}
+}
@@ -0,0 +1,67 @@
+use crate::{
+	asyncjob::{AsyncJob, RunParams},
+	error::Result,
+	sync::{get_worktrees, RepoPath, WorktreeInfo},
+	AsyncGitNotification,
+};
+use std::sync::{Arc, Mutex};
+
+enum JobState {
+	Request(RepoPath),
+	Response(Result<Vec<WorktreeInfo>>),
+}
+
+/// lists the worktrees of a repository
+#[derive(Clone, Default)]
+pub struct AsyncWorktreesJob {
+	state: Arc<Mutex<Option<JobState>>>,
+}
+
+///
+impl AsyncWorktreesJob {
+	///
+	pub fn new(repo: RepoPath) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request(
+				repo,
+			)))),
+		}
+	}
+
+	///
+	pub fn result(&self) -> Option<Result<Vec<WorktreeInfo>>> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(state) = state.take() {
+				return match state {
+					JobState::Request(_) => None,
+					JobState::Response(result) => Some(result),
+				};
+			}
+		}
+
+		None
+	}
+}
+
+impl AsyncJob for AsyncWorktreesJob {
+	type Notification = AsyncGitNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> Result<Self::Notification> {
+		if let Ok(mut state) = self.state.lock() {
+			*state = state.take().map(|state| match state {
+				JobState::Request(repo) => {
+					JobState::Response(get_worktrees(&repo))
+				}
+				JobState::Response(result) => {
+					JobState::Response(result)
+				}
+			});
+		}
+
+		Ok(AsyncGitNotification::Worktrees)
+	}
+}
@@ -1,12 +1,15 @@
 use git2::Repository;
 
-use crate::{error::Result, HookResult, HooksError};
+use crate::{error::Result, HookOutput, HookResult, HooksError};
 
 use std::{
 	ffi::{OsStr, OsString},
+	io::{self, BufRead, BufReader, Read},
 	path::{Path, PathBuf},
-	process::Command,
+	process::{ChildStdin, Command},
 	str::FromStr,
+	sync::mpsc::{self, Sender},
+	thread::{self, JoinHandle},
 };
 
 pub struct HookPaths {
@@ -99,7 +102,10 @@ impl HookPaths {
 	}
 
 	/// check default hook path first and then followed by `other_paths`.
-	/// if no hook is found we return the default hook path
+	/// if no hook is found we return the default hook path.
+	///
+	/// both are relative to the common git dir, so linked worktrees
+	/// share the hooks of the main repository like they do in git.
 	fn find_hook(
 		repo: &Repository,
 		other_paths: Option<&[&str]>,
@@ -115,16 +121,13 @@ impl HookPaths {
 		}
 
 		for p in paths {
-			let p = repo.path().to_path_buf().join(p).join(hook);
+			let p = repo.commondir().join(p).join(hook);
 			if p.exists() {
 				return p;
 			}
 		}
 
-		repo.path()
-			.to_path_buf()
-			.join(DEFAULT_HOOKS_PATH)
-			.join(hook)
+		repo.commondir().join(DEFAULT_HOOKS_PATH).join(hook)
 	}
 
 	/// was a hook file found and is it executable
@@ -155,6 +158,21 @@ impl HookPaths {
 		args: I,
 		stdin: Option<&[u8]>,
 	) -> Result<HookResult>
+	where
+		I: IntoIterator<Item = S> + Copy,
+		S: AsRef<OsStr>,
+	{
+		self.run_hook_streamed(args, stdin, &mut |_| ())
+	}
+
+	/// like `run_hook_os_str_with_stdin`, handing each line the hook
+	/// prints to `on_output` while it runs
+	pub fn run_hook_streamed<I, S>(
+		&self,
+		args: I,
+		stdin: Option<&[u8]>,
+		on_output: &mut dyn FnMut(HookOutput),
+	) -> Result<HookResult>
 	where
 		I: IntoIterator<Item = S> + Copy,
 		S: AsRef<OsStr>,
@@ -166,7 +184,7 @@ impl HookPaths {
 			self.pwd.display()
 		);
 
-		let run_command = |command: &mut Command| {
+		let mut run_command = |command: &mut Command| {
 			let mut child = command
 				.args(args)
 				.current_dir(&self.pwd)
@@ -180,29 +198,33 @@ impl HookPaths {
 				.stderr(std::process::Stdio::piped())
 				.spawn()?;
 
-			if let (Some(mut stdin_handle), Some(input)) =
+			if let (Some(stdin_handle), Some(input)) =
 				(child.stdin.take(), stdin)
 			{
-				use std::io::{ErrorKind, Write};
-
-				// Write stdin to hook process
-				// Ignore broken pipe - hook may exit early without reading all input
-				let _ =
-					stdin_handle.write_all(input).inspect_err(|e| {
-						match e.kind() {
-							ErrorKind::BrokenPipe => {
-								log::debug!(
-									"Hook closed stdin early"
-								);
-							}
-							_ => log::warn!(
-								"Failed to write stdin to hook: {e}"
-							),
-						}
-					});
+				write_stdin(stdin_handle, input);
+			}
+
+			let (tx, rx) = mpsc::channel();
+			let stdout = child.stdout.take().map(|pipe| {
+				read_lines(pipe, tx.clone(), HookOutput::Stdout)
+			});
+			let stderr = child.stderr.take().map(|pipe| {
+				read_lines(pipe, tx.clone(), HookOutput::Stderr)
+			});
+			drop(tx);
+
+			// ends once both pipes are closed
+			for output in rx {
+				on_output(output);
 			}
 
-			child.wait_with_output()
+			let status = child.wait()?;
+
+			io::Result::Ok((
+				status,
+				join_reader(stdout)?,
+				join_reader(stderr)?,
+			))
 		};
 
 		let output = if cfg!(windows) {
@@ -255,14 +277,13 @@ impl HookPaths {
 			}
 		}?;
 
-		let stderr =
-			String::from_utf8_lossy(&output.stderr).to_string();
-		let stdout =
-			String::from_utf8_lossy(&output.stdout).to_string();
+		let (status, stdout, stderr) = output;
+
+		let stderr = String::from_utf8_lossy(&stderr).to_string();
+		let stdout = String::from_utf8_lossy(&stdout).to_string();
 
 		// Get exit code, or fail if process was killed by signal
-		let code =
-			output.status.code().ok_or(HooksError::NoExitCode)?;
+		let code = status.code().ok_or(HooksError::NoExitCode)?;
 
 		Ok(HookResult::Run(crate::HookRunResponse {
 			hook,
@@ -273,6 +294,68 @@ impl HookPaths {
 	}
 }
 
+/// writes `input` to the hook and closes its stdin by dropping the
+/// handle
+fn write_stdin(mut stdin_handle: ChildStdin, input: &[u8]) {
+	use std::io::{ErrorKind, Write};
+
+	// Write stdin to hook process
+	// Ignore broken pipe - hook may exit early without reading all input
+	let _ = stdin_handle.write_all(input).inspect_err(|e| {
+		match e.kind() {
+			ErrorKind::BrokenPipe => {
+				log::debug!("Hook closed stdin early");
+			}
+			_ => log::warn!("Failed to write stdin to hook: {e}"),
+		}
+	});
+}
+
+/// sends each line of `pipe` as it comes in, the thread returns
+/// everything read
+fn read_lines<R: Read + Send + 'static>(
+	pipe: R,
+	tx: Sender<HookOutput>,
+	output: fn(String) -> HookOutput,
+) -> JoinHandle<io::Result<Vec<u8>>> {
+	thread::spawn(move || {
+		let mut reader = BufReader::new(pipe);
+		let mut all = Vec::new();
+		let mut line = Vec::new();
+
+		loop {
+			line.clear();
+			if reader.read_until(b'\n', &mut line)? == 0 {
+				break;
+			}
+
+			all.extend_from_slice(&line);
+
+			let text = String::from_utf8_lossy(&line);
+			// nobody listening is fine
+			tx.send(output(
+				text.trim_end_matches(['\n', '\r']).to_string(),
+			))
+			.ok();
+		}
+
+		Ok(all)
+	})
+}
+
+fn join_reader(
+	reader: Option<JoinHandle<io::Result<Vec<u8>>>>,
+) -> io::Result<Vec<u8>> {
+	reader.map_or_else(
+		|| Ok(Vec::new()),
+		|reader| {
+			reader.join().map_err(|_| {
+				io::Error::other("hook output reader panicked")
+			})?
+		},
+	)
+}
+
 fn sh_command() -> Command {
 	let mut command = Command::new(gix_path::env::shell());
 
@@ -45,6 +45,8 @@ pub const HOOK_PRE_COMMIT: &str = "pre-commit";
 pub const HOOK_COMMIT_MSG: &str = "commit-msg";
 pub const HOOK_PREPARE_COMMIT_MSG: &str = "prepare-commit-msg";
 pub const HOOK_PRE_PUSH: &str = "pre-push";
+pub const HOOK_POST_CHECKOUT: &str = "post-checkout";
+pub const HOOK_POST_MERGE: &str = "post-merge";
 
 const HOOK_COMMIT_MSG_TEMP_FILE: &str = "COMMIT_EDITMSG";
 
@@ -121,6 +123,15 @@ pub struct HookRunResponse {
 	pub code: i32,
 }
 
+/// a line printed by a running hook, without its line break
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookOutput {
+	/// a line on stdout
+	Stdout(String),
+	/// a line on stderr
+	Stderr(String),
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum HookResult {
 	/// No hook found
@@ -232,6 +243,52 @@ pub fn hooks_post_commit(
 	hook.run_hook(&[])
 }
 
+/// this hook is documented here <https://git-scm.com/docs/githooks#_post_checkout>
+///
+/// It receives the previous HEAD (all-zeroes if there was none), the new HEAD
+/// and a flag that is `1` for a branch checkout and `0` for a checkout of files.
+/// Its result cannot affect the checkout that already happened.
+pub fn hooks_post_checkout(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	old_head: Option<Oid>,
+	new_head: Oid,
+	branch_checkout: bool,
+) -> Result<HookResult> {
+	let hook = HookPaths::new(repo, other_paths, HOOK_POST_CHECKOUT)?;
+
+	if !hook.found() {
+		return Ok(HookResult::NoHookFound);
+	}
+
+	let old_head = old_head.unwrap_or_else(Oid::zero).to_string();
+	let new_head = new_head.to_string();
+
+	hook.run_hook(&[
+		old_head.as_str(),
+		new_head.as_str(),
+		if branch_checkout { "1" } else { "0" },
+	])
+}
+
+/// this hook is documented here <https://git-scm.com/docs/githooks#_post_merge>
+///
+/// It receives a flag that is `1` if the merge was a squash merge.
+/// Its result cannot affect the merge that already happened.
+pub fn hooks_post_merge(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	squash: bool,
+) -> Result<HookResult> {
+	let hook = HookPaths::new(repo, other_paths, HOOK_POST_MERGE)?;
+
+	if !hook.found() {
+		return Ok(HookResult::NoHookFound);
+	}
+
+	hook.run_hook(&[if squash { "1" } else { "0" }])
+}
+
 /// this hook is documented here <https://git-scm.com/docs/githooks#_pre_push>
 ///
 /// According to git documentation, pre-push hook receives:
@@ -250,6 +307,26 @@ pub fn hooks_pre_push(
 	remote: Option<&str>,
 	url: &str,
 	updates: &[PrePushRef],
+) -> Result<HookResult> {
+	hooks_pre_push_streamed(
+		repo,
+		other_paths,
+		remote,
+		url,
+		updates,
+		&mut |_| (),
+	)
+}
+
+/// like [`hooks_pre_push`], handing each line the hook prints to
+/// `on_output` while it runs
+pub fn hooks_pre_push_streamed(
+	repo: &Repository,
+	other_paths: Option<&[&str]>,
+	remote: Option<&str>,
+	url: &str,
+	updates: &[PrePushRef],
+	on_output: &mut dyn FnMut(HookOutput),
 ) -> Result<HookResult> {
 	let hook = HookPaths::new(repo, other_paths, HOOK_PRE_PUSH)?;
 
@@ -265,9 +342,10 @@ pub fn hooks_pre_push(
 
 	let stdin_data = PrePushRef::to_stdin(updates);
 
-	hook.run_hook_os_str_with_stdin(
+	hook.run_hook_streamed(
 		[remote_name, url],
 		Some(stdin_data.as_bytes()),
+		on_output,
 	)
 }
 
@@ -461,6 +539,53 @@ exit 0
 		assert!(res.is_successful());
 	}
 
+	#[test]
+	fn test_post_checkout_args() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/bin/sh
+echo \"$1 $2 $3\"
+exit 1
+        ";
+
+		create_hook(&repo, HOOK_POST_CHECKOUT, hook);
+
+		let head = repo.head().unwrap().target().unwrap();
+		let res = hooks_post_checkout(&repo, None, None, head, true)
+			.unwrap();
+
+		let HookResult::Run(response) = res else {
+			unreachable!()
+		};
+		assert!(!response.is_successful());
+		assert_eq!(
+			response.stdout.trim(),
+			format!("{} {head} 1", "0".repeat(40))
+		);
+	}
+
+	#[test]
+	fn test_post_merge_args() {
+		let (_td, repo) = repo_init();
+
+		let res = hooks_post_merge(&repo, None, false).unwrap();
+		assert_eq!(res, HookResult::NoHookFound);
+
+		let hook = b"#!/bin/sh
+echo \"squash=$1\"
+        ";
+
+		create_hook(&repo, HOOK_POST_MERGE, hook);
+
+		let res = hooks_post_merge(&repo, None, true).unwrap();
+
+		let HookResult::Run(response) = res else {
+			unreachable!()
+		};
+		assert!(response.is_successful());
+		assert_eq!(response.stdout.trim(), "squash=1");
+	}
+
 	#[test]
 	fn test_hooks_commit_msg_ok() {
 		let (_td, repo) = repo_init();
@@ -797,6 +922,39 @@ exit 0
 		assert_eq!(hook.pwd, git_root.parent().unwrap());
 	}
 
+	// linked worktrees run the hooks of the main repository in their
+	// own workdir
+	#[test]
+	#[cfg(unix)]
+	fn test_hooks_in_worktree() {
+		let (td, repo) = repo_init();
+
+		let hook = b"#!/bin/sh
+echo \"$(pwd)\" > \"$1\"
+exit 1
+		";
+		create_hook(&repo, HOOK_COMMIT_MSG, hook);
+
+		let wt_path = td.path().join("wt");
+		repo.worktree("wt", &wt_path, None).unwrap();
+		let wt_repo = Repository::open(&wt_path).unwrap();
+
+		let hook =
+			HookPaths::new(&wt_repo, None, HOOK_COMMIT_MSG).unwrap();
+		assert_eq!(hook.git, wt_repo.path());
+		assert!(hook.hook.starts_with(repo.path()));
+		assert!(hook.found());
+
+		let mut msg = String::from("msg");
+		let res = hooks_commit_msg(&wt_repo, None, &mut msg).unwrap();
+
+		assert!(!res.is_successful());
+		assert_eq!(
+			msg.trim_end(),
+			wt_path.canonicalize().unwrap().to_str().unwrap()
+		);
+	}
+
 	#[test]
 	fn test_hooks_prep_commit_msg_success() {
 		let (_td, repo) = repo_init();
@@ -922,6 +1080,52 @@ exit 3
 		assert_eq!(&response.stdout, "failed\n");
 	}
 
+	#[test]
+	fn test_pre_push_streamed() {
+		let (_td, repo) = repo_init();
+
+		let hook = b"#!/bin/sh
+echo 'one'
+echo 'two' >&2
+printf 'three'
+exit 1
+	";
+		create_hook(&repo, HOOK_PRE_PUSH, hook);
+
+		let mut lines = Vec::new();
+		let res = hooks_pre_push_streamed(
+			&repo,
+			None,
+			Some("origin"),
+			"https://example.com/repo.git",
+			&[],
+			&mut |output| lines.push(output),
+		)
+		.unwrap();
+
+		let HookResult::Run(response) = res else {
+			unreachable!()
+		};
+		assert_eq!(response.code, 1);
+		assert_eq!(&response.stdout, "one\nthree");
+		assert_eq!(&response.stderr, "two\n");
+
+		// the order across stdout and stderr is up to the os
+		lines.sort_by_key(|output| match output {
+			HookOutput::Stdout(line) | HookOutput::Stderr(line) => {
+				line.clone()
+			}
+		});
+		assert_eq!(
+			lines,
+			vec![
+				HookOutput::Stdout(String::from("one")),
+				HookOutput::Stdout(String::from("three")),
+				HookOutput::Stderr(String::from("two")),
+			]
+		);
+	}
+
 	#[test]
 	fn test_pre_push_no_remote_name() {
 		let (_td, repo) = repo_init();
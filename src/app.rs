@@ -1,49 +1,72 @@
 use crate::{
 	accessors,
-	args::CliArgs,
+	args::{CliArgs, FileView},
+	automation::{self, AsyncShellJob, Automation, Step, Trigger},
 	cmdbar::CommandBar,
 	components::{
-		command_pump, event_pump, CommandInfo, Component,
+		command_pump, event_pump, tab_at, CommandInfo, Component,
 		DrawableComponent, FuzzyFinderTarget,
 	},
+	event_log::{self, Event as LogEvent},
 	input::{Input, InputEvent, InputState},
-	keys::{key_match, KeyConfig, SharedKeyConfig},
-	options::{Options, SharedOptions},
+	ipc::IpcCommand,
+	keys::{
+		key_match, KeyConfig, KeyMacro, SequenceMatcher,
+		SharedKeyConfig,
+	},
+	options::{Options, SharedOptions, SplitTab},
 	popup_stack::PopupStack,
 	popups::{
-		AppOption, BlameFilePopup, BranchListPopup,
-		CheckoutOptionPopup, CommitPopup, CompareCommitsPopup,
-		ConfirmPopup, CreateBranchPopup, CreateRemotePopup,
-		ExternalEditorPopup, FetchPopup, FileRevlogPopup,
-		FuzzyFindPopup, GotoLinePopup, HelpPopup, InspectCommitPopup,
-		LogSearchPopupPopup, MsgPopup, OptionsPopup, PullPopup,
-		PushPopup, PushTagsPopup, RemoteListPopup, RenameBranchPopup,
-		RenameRemotePopup, ResetPopup, RevisionFilesPopup,
-		StashMsgPopup, SubmodulesListPopup, TagCommitPopup,
-		TagListPopup, UpdateRemoteUrlPopup,
+		AddSubmodulePopup, AddWorktreePopup, AmendFilesPopup,
+		AppOption, ApplyPatchPopup, BisectPopup, BlameFileOpen,
+		BlameFilePopup, BranchCleanupPopup, BranchListPopup,
+		CheckoutConflictsPopup, CheckoutOptionPopup,
+		CommitChecksPopup, CommitPickerPopup, CommitPopup,
+		CompareCommitsPopup, ConfirmPopup, CopyPopup,
+		CreateBranchPopup, CreateRemotePopup, ExternalEditorPopup,
+		FetchPopup, FileRevOpen, FileRevlogPopup, FlowPopup,
+		FuzzyFindPopup, GotoLinePopup, HelpPopup, IgnorePatternPopup,
+		InspectCommitOpen, InspectCommitPopup, KeyBindingsPopup,
+		LargeFilesPopup, LineActionsPopup, LogSearchPopupPopup,
+		MacroReplayPopup, MsgPopup, OptionsPopup, PatchSeriesPopup,
+		PullPopup, PushPopup, PushTagsPopup, RebaseInteractivePopup,
+		RemoteListPopup, RenameBranchPopup, RenameRemotePopup,
+		ResetPopup, RevisionFilesPopup, StackPopup, StashMsgPopup,
+		SubmodulesListPopup, TagCommitPopup, TagListPopup, TourPane,
+		TourPopup, TrashPopup, TreeSearchPopup, UpdateRemoteUrlPopup,
+		WorktreesPopup,
 	},
 	queue::{
 		Action, AppTabs, InternalEvent, NeedsUpdate, Queue,
-		StackablePopupOpen,
+		ResetItem, StackablePopupOpen,
 	},
+	refresh::{ExternalChanges, RefreshReport},
 	setup_popups,
 	strings::{self, ellipsis_trim_start, order},
 	tabs::{FilesTab, Revlog, StashList, Stashing, Status},
 	try_or_popup,
-	ui::style::{SharedTheme, Theme},
+	ui::{
+		style::{SharedTheme, Theme},
+		Graphics, SharedGraphics,
+	},
 	AsyncAppNotification, AsyncNotification,
 };
 use anyhow::{bail, Result};
 use asyncgit::{
+	asyncjob::AsyncSingleJob,
+	cached,
 	sync::{
 		self,
+		diff::Hunk,
 		utils::{repo_work_dir, undo_last_commit},
-		RepoPath, RepoPathRef,
+		CommitId, HookResult, RepoChanges, RepoPath, RepoPathRef,
+		ResetType,
 	},
-	AsyncGitNotification, PushType,
+	AsyncCheckoutJob, AsyncGitNotification, AsyncPostHookJob,
+	PushType, SubmoduleOperation,
 };
 use crossbeam_channel::Sender;
-use crossterm::event::{Event, KeyEvent};
+use crossterm::event::{Event, KeyEvent, MouseEvent};
 use ratatui::{
 	layout::{
 		Alignment, Constraint, Direction, Layout, Margin, Rect,
@@ -54,6 +77,7 @@ use ratatui::{
 };
 use std::{
 	cell::{Cell, RefCell},
+	collections::VecDeque,
 	path::{Path, PathBuf},
 	rc::Rc,
 };
@@ -71,9 +95,11 @@ pub struct App {
 	repo: RepoPathRef,
 	do_quit: QuitState,
 	help_popup: HelpPopup,
+	tour_popup: TourPopup,
 	msg_popup: MsgPopup,
 	confirm_popup: ConfirmPopup,
 	commit_popup: CommitPopup,
+	amend_files_popup: AmendFilesPopup,
 	blame_file_popup: BlameFilePopup,
 	file_revlog_popup: FileRevlogPopup,
 	stashmsg_popup: StashMsgPopup,
@@ -81,6 +107,7 @@ pub struct App {
 	compare_commits_popup: CompareCommitsPopup,
 	external_editor_popup: ExternalEditorPopup,
 	revision_files_popup: RevisionFilesPopup,
+	tree_search_popup: TreeSearchPopup,
 	fuzzy_find_popup: FuzzyFindPopup,
 	log_search_popup: LogSearchPopupPopup,
 	push_popup: PushPopup,
@@ -89,6 +116,11 @@ pub struct App {
 	fetch_popup: FetchPopup,
 	tag_commit_popup: TagCommitPopup,
 	create_branch_popup: CreateBranchPopup,
+	flow_popup: FlowPopup,
+	branch_cleanup_popup: BranchCleanupPopup,
+	checkout_conflicts_popup: CheckoutConflictsPopup,
+	large_files_popup: LargeFilesPopup,
+	commit_checks_popup: CommitChecksPopup,
 	create_remote_popup: CreateRemotePopup,
 	rename_remote_popup: RenameRemotePopup,
 	update_remote_url_popup: UpdateRemoteUrlPopup,
@@ -96,9 +128,23 @@ pub struct App {
 	rename_branch_popup: RenameBranchPopup,
 	select_branch_popup: BranchListPopup,
 	options_popup: OptionsPopup,
+	key_bindings_popup: KeyBindingsPopup,
 	submodule_popup: SubmodulesListPopup,
+	add_submodule_popup: AddSubmodulePopup,
+	worktrees_popup: WorktreesPopup,
+	add_worktree_popup: AddWorktreePopup,
+	trash_popup: TrashPopup,
+	apply_patch_popup: ApplyPatchPopup,
+	stack_popup: StackPopup,
+	rebase_interactive_popup: RebaseInteractivePopup,
 	tags_popup: TagListPopup,
 	reset_popup: ResetPopup,
+	copy_popup: CopyPopup,
+	line_actions_popup: LineActionsPopup,
+	ignore_pattern_popup: IgnorePatternPopup,
+	bisect_popup: BisectPopup,
+	commit_picker_popup: CommitPickerPopup,
+	patch_series_popup: PatchSeriesPopup,
 	checkout_option_popup: CheckoutOptionPopup,
 	cmdbar: RefCell<CommandBar>,
 	tab: usize,
@@ -113,12 +159,41 @@ pub struct App {
 	input: Input,
 	popup_stack: PopupStack,
 	options: SharedOptions,
+	graphics: SharedGraphics,
 	repo_path_text: String,
+	/// name of the linked worktree the repo was opened in
+	worktree_name: Option<String>,
+	repo_tabs: Option<(Vec<String>, usize)>,
+	repo_switch: Option<bool>,
+	config_reload: bool,
+	watcher_reload: bool,
+	/// where the tab headers were drawn, for mouse clicks
+	tabs_area: Cell<Rect>,
+	key_sequences: SequenceMatcher,
+	key_macro: KeyMacro,
+	macro_replay: Option<usize>,
 	goto_line_popup: GotoLinePopup,
+	macro_replay_popup: MacroReplayPopup,
+	automation: Automation,
+	/// steps of triggered automations left to run
+	automation_steps: VecDeque<Step>,
+	shell_job: AsyncSingleJob<AsyncShellJob>,
+	/// from spawning a `Step::Shell` until its result came in, the
+	/// job itself only is pending once it started
+	waiting_for_shell: bool,
+	/// to tell when the branch was switched
+	branch_name: cached::BranchName,
+	post_hook_job: AsyncSingleJob<AsyncPostHookJob>,
+	checkout_job: AsyncSingleJob<AsyncCheckoutJob>,
+	external_changes: ExternalChanges,
 
 	// "Flags"
 	requires_redraw: Cell<bool>,
 	file_to_open: Option<String>,
+	line_to_open: Option<usize>,
+	hunk_to_edit: Option<(String, Hunk)>,
+	refresh_report: Option<RefreshReport>,
+	external_note: Option<RepoChanges>,
 }
 
 pub struct Environment {
@@ -127,6 +202,7 @@ pub struct Environment {
 	pub key_config: SharedKeyConfig,
 	pub repo: RepoPathRef,
 	pub options: SharedOptions,
+	pub graphics: SharedGraphics,
 	pub sender_git: Sender<AsyncGitNotification>,
 	pub sender_app: Sender<AsyncAppNotification>,
 }
@@ -142,6 +218,7 @@ impl Environment {
 			key_config: Default::default(),
 			repo: RefCell::new(RepoPath::Path(Default::default())),
 			options: Rc::new(RefCell::new(Options::test_env())),
+			graphics: Default::default(),
 			sender_git: unbounded().0,
 			sender_app: unbounded().0,
 		}
@@ -165,17 +242,26 @@ impl App {
 
 		let repo_path_text =
 			repo_work_dir(&repo.borrow()).unwrap_or_default();
+		let worktree_name =
+			sync::current_worktree_name(&repo.borrow())
+				.ok()
+				.flatten();
 
 		let env = Environment {
 			queue: Queue::new(),
 			theme: Rc::new(theme),
 			key_config: Rc::new(key_config),
 			options: Options::new(repo.clone()),
+			graphics: Graphics::new(),
 			repo,
 			sender_git,
 			sender_app,
 		};
 
+		let start_view = cliargs.has_start_view();
+		let file_view = cliargs.file_view;
+		let commit = cliargs.commit;
+
 		let mut select_file: Option<PathBuf> = None;
 		let tab = if let Some(file) = cliargs.select_file {
 			// convert to relative git path
@@ -190,17 +276,24 @@ impl App {
 		} else {
 			env.options.borrow().current_tab()
 		};
+		let tab = cliargs.tab.unwrap_or(tab);
+
+		let view_file = (file_view != FileView::Tree)
+			.then(|| select_file.take())
+			.flatten();
 
 		let mut app = Self {
 			input,
 			confirm_popup: ConfirmPopup::new(&env),
 			commit_popup: CommitPopup::new(&env),
+			amend_files_popup: AmendFilesPopup::new(&env),
 			blame_file_popup: BlameFilePopup::new(
 				&env,
 				&strings::blame_title(&env.key_config),
 			),
 			file_revlog_popup: FileRevlogPopup::new(&env),
 			revision_files_popup: RevisionFilesPopup::new(&env),
+			tree_search_popup: TreeSearchPopup::new(&env),
 			stashmsg_popup: StashMsgPopup::new(&env),
 			inspect_commit_popup: InspectCommitPopup::new(&env),
 			compare_commits_popup: CompareCommitsPopup::new(&env),
@@ -208,10 +301,23 @@ impl App {
 			push_popup: PushPopup::new(&env),
 			push_tags_popup: PushTagsPopup::new(&env),
 			reset_popup: ResetPopup::new(&env),
+			copy_popup: CopyPopup::new(&env),
+			line_actions_popup: LineActionsPopup::new(&env),
+			ignore_pattern_popup: IgnorePatternPopup::new(&env),
+			bisect_popup: BisectPopup::new(&env),
+			commit_picker_popup: CommitPickerPopup::new(&env),
+			patch_series_popup: PatchSeriesPopup::new(&env),
 			pull_popup: PullPopup::new(&env),
 			fetch_popup: FetchPopup::new(&env),
 			tag_commit_popup: TagCommitPopup::new(&env),
 			create_branch_popup: CreateBranchPopup::new(&env),
+			flow_popup: FlowPopup::new(&env),
+			branch_cleanup_popup: BranchCleanupPopup::new(&env),
+			checkout_conflicts_popup: CheckoutConflictsPopup::new(
+				&env,
+			),
+			large_files_popup: LargeFilesPopup::new(&env),
+			commit_checks_popup: CommitChecksPopup::new(&env),
 			create_remote_popup: CreateRemotePopup::new(&env),
 			rename_remote_popup: RenameRemotePopup::new(&env),
 			update_remote_url_popup: UpdateRemoteUrlPopup::new(&env),
@@ -220,7 +326,17 @@ impl App {
 			select_branch_popup: BranchListPopup::new(&env),
 			tags_popup: TagListPopup::new(&env),
 			options_popup: OptionsPopup::new(&env),
+			key_bindings_popup: KeyBindingsPopup::new(&env),
 			submodule_popup: SubmodulesListPopup::new(&env),
+			add_submodule_popup: AddSubmodulePopup::new(&env),
+			worktrees_popup: WorktreesPopup::new(&env),
+			add_worktree_popup: AddWorktreePopup::new(&env),
+			trash_popup: TrashPopup::new(&env),
+			apply_patch_popup: ApplyPatchPopup::new(&env),
+			stack_popup: StackPopup::new(&env),
+			rebase_interactive_popup: RebaseInteractivePopup::new(
+				&env,
+			),
 			log_search_popup: LogSearchPopupPopup::new(&env),
 			fuzzy_find_popup: FuzzyFindPopup::new(&env),
 			do_quit: QuitState::None,
@@ -229,6 +345,7 @@ impl App {
 				env.key_config.clone(),
 			)),
 			help_popup: HelpPopup::new(&env),
+			tour_popup: TourPopup::new(&env),
 			msg_popup: MsgPopup::new(&env),
 			revlog: Revlog::new(&env),
 			status_tab: Status::new(&env),
@@ -237,20 +354,55 @@ impl App {
 			files_tab: FilesTab::new(&env, select_file),
 			checkout_option_popup: CheckoutOptionPopup::new(&env),
 			goto_line_popup: GotoLinePopup::new(&env),
+			macro_replay_popup: MacroReplayPopup::new(&env),
+			automation: Automation::init(),
+			automation_steps: VecDeque::new(),
+			shell_job: AsyncSingleJob::new(env.sender_app.clone()),
+			waiting_for_shell: false,
+			branch_name: cached::BranchName::new(env.repo.clone()),
+			post_hook_job: AsyncSingleJob::new(
+				env.sender_git.clone(),
+			),
+			checkout_job: AsyncSingleJob::new(env.sender_git.clone()),
+			external_changes: ExternalChanges::default(),
 			tab: 0,
 			queue: env.queue,
 			theme: env.theme,
 			options: env.options,
+			graphics: env.graphics,
 			key_config: env.key_config,
 			requires_redraw: Cell::new(false),
 			file_to_open: None,
+			line_to_open: None,
+			hunk_to_edit: None,
+			refresh_report: None,
+			external_note: None,
 			repo: env.repo,
 			repo_path_text,
+			worktree_name,
+			repo_tabs: None,
+			repo_switch: None,
+			config_reload: false,
+			watcher_reload: false,
+			tabs_area: Cell::new(Rect::default()),
+			key_sequences: SequenceMatcher::default(),
+			key_macro: KeyMacro::default(),
+			macro_replay: None,
 			popup_stack: PopupStack::default(),
 		};
 
 		app.set_tab(tab)?;
 
+		if start_view {
+			app.open_start_view(
+				commit.as_deref(),
+				view_file,
+				file_view,
+			)?;
+		} else {
+			app.tour_popup.open_on_first_run()?;
+		}
+
 		Ok(app)
 	}
 
@@ -297,15 +449,171 @@ impl App {
 			}
 		}
 
+		self.tour_popup
+			.set_highlight(self.tour_highlight(chunks_main[2]));
+
 		self.draw_popups(f)?;
 
+		// a screen reader follows the cursor, so it must not jump
+		// around with every redraw
+		if self.theme.accessible() {
+			f.set_cursor_position(chunks_main[2].as_position());
+		}
+
 		Ok(())
 	}
 
+	/// opens the commit or file passed on the command line
+	fn open_start_view(
+		&mut self,
+		revision: Option<&str>,
+		file: Option<PathBuf>,
+		file_view: FileView,
+	) -> Result<()> {
+		let commit = match revision
+			.map(|rev| {
+				CommitId::from_revision(&self.repo.borrow(), rev)
+			})
+			.transpose()
+		{
+			Ok(commit) => commit,
+			Err(e) => {
+				self.msg_popup.show_error(&format!(
+					"{}: {e}",
+					strings::UNKNOWN_REVISION
+				))?;
+				return Ok(());
+			}
+		};
+
+		let file_path = file.map(|file| {
+			file.strip_prefix(".")
+				.unwrap_or(&file)
+				.to_string_lossy()
+				.to_string()
+		});
+
+		let popup = match (file_path, commit) {
+			(Some(file_path), commit)
+				if file_view == FileView::Blame =>
+			{
+				StackablePopupOpen::BlameFile(BlameFileOpen {
+					file_path,
+					commit_id: commit,
+					selection: None,
+				})
+			}
+			(Some(file_path), _) => StackablePopupOpen::FileRevlog(
+				FileRevOpen::new(file_path),
+			),
+			(None, Some(commit)) => {
+				StackablePopupOpen::InspectCommit(
+					InspectCommitOpen::new(commit),
+				)
+			}
+			(None, None) => return Ok(()),
+		};
+
+		self.open_popup(popup)
+	}
+
+	/// area of the pane the tour currently points at
+	fn tour_highlight(&self, cmdbar: Rect) -> Option<Rect> {
+		self.tour_popup.pane().map(|pane| match pane {
+			TourPane::Tabs => self.tabs_area.get(),
+			TourPane::Unstaged => self.status_tab.pane_areas()[0],
+			TourPane::Staged => self.status_tab.pane_areas()[1],
+			TourPane::CommandBar => cmdbar,
+		})
+	}
+
 	///
 	pub fn event(&mut self, ev: InputEvent) -> Result<()> {
 		log::trace!("event: {ev:?}");
 
+		match ev {
+			InputEvent::Input(Event::FocusGained) => {
+				if self.options.borrow().watcher().refresh_on_focus {
+					self.update_external(None)?;
+				}
+				return Ok(());
+			}
+			InputEvent::Input(Event::FocusLost) => return Ok(()),
+			InputEvent::Input(Event::Key(key)) => {
+				self.refresh_report = None;
+				self.external_note = None;
+
+				if self.check_macro_keys(&key) {
+					return Ok(());
+				}
+
+				self.key_macro.record(key);
+			}
+			_ => (),
+		}
+
+		self.sequence_event(ev)?;
+
+		if let Some(times) = self.macro_replay.take() {
+			for key in self.key_macro.replay(times) {
+				self.sequence_event(InputEvent::Input(Event::Key(
+					key,
+				)))?;
+			}
+		}
+
+		Ok(())
+	}
+
+	/// record and replay keys outside of popups, `true` if consumed
+	fn check_macro_keys(&mut self, key: &KeyEvent) -> bool {
+		if self.any_popup_visible() {
+			return false;
+		}
+
+		if key_match(key, self.key_config.keys.macro_record) {
+			self.key_macro.toggle_recording();
+			self.update_commands();
+			return true;
+		}
+
+		if key_match(key, self.key_config.keys.macro_replay)
+			&& !self.key_macro.is_recording()
+			&& self.key_macro.has_recording()
+		{
+			self.macro_replay_popup.open();
+			self.update_commands();
+			return true;
+		}
+
+		false
+	}
+
+	/// expands key sequences before processing the event
+	fn sequence_event(&mut self, ev: InputEvent) -> Result<()> {
+		if let InputEvent::Input(Event::Key(key)) = ev {
+			// popups are left alone to not swallow text input
+			if !self.any_popup_visible() {
+				let keys = self
+					.key_sequences
+					.feed(&self.key_config.sequences, key);
+				for key in keys {
+					self.process_event(InputEvent::Input(
+						Event::Key(key),
+					))?;
+				}
+
+				return Ok(());
+			}
+
+			self.key_sequences.reset();
+		}
+
+		self.process_event(ev)
+	}
+
+	#[allow(clippy::too_many_lines)]
+	fn process_event(&mut self, ev: InputEvent) -> Result<()> {
 		if let InputEvent::Input(ev) = ev {
 			if self.check_hard_exit(&ev) || self.check_quit(&ev) {
 				return Ok(());
@@ -360,28 +668,67 @@ impl App {
 				) {
 					self.options_popup.show()?;
 					NeedsUpdate::ALL
+				} else if key_match(
+					k,
+					self.key_config.keys.view_worktrees,
+				) {
+					self.worktrees_popup.open()?;
+					NeedsUpdate::ALL
+				} else if key_match(
+					k,
+					self.key_config.keys.view_trash,
+				) {
+					self.trash_popup.open()?;
+					NeedsUpdate::ALL
+				} else if key_match(
+					k,
+					self.key_config.keys.view_stack,
+				) {
+					self.stack_popup.open()?;
+					NeedsUpdate::ALL
+				} else if key_match(
+					k,
+					self.key_config.keys.reload_config,
+				) {
+					self.config_reload = true;
+					NeedsUpdate::empty()
+				} else if key_match(k, self.key_config.keys.refresh) {
+					self.update_external(None)?;
+					self.refresh_report = Some(RefreshReport::new(
+						&self.refresh_jobs(),
+					));
+					NeedsUpdate::empty()
 				} else {
+					self.layout_keys(k);
 					NeedsUpdate::empty()
 				};
 
 				flags.insert(new_flags);
+			} else if let Event::Mouse(m) = &ev {
+				if self.click_tab(*m)? {
+					flags.insert(NeedsUpdate::COMMANDS);
+				}
 			}
 
 			self.process_queue(flags)?;
 		} else if let InputEvent::State(polling_state) = ev {
 			self.external_editor_popup.hide();
 			if matches!(polling_state, InputState::Paused) {
-				let result =
-					if let Some(path) = self.file_to_open.take() {
-						ExternalEditorPopup::open_file_in_editor(
-							&self.repo.borrow(),
-							Path::new(&path),
-						)
-					} else {
-						let changes =
-							self.status_tab.get_files_changes()?;
-						self.commit_popup.show_editor(changes)
-					};
+				let result = if let Some((path, hunk)) =
+					self.hunk_to_edit.take()
+				{
+					self.edit_hunk(&path, &hunk)
+				} else if let Some(path) = self.file_to_open.take() {
+					ExternalEditorPopup::open_file_in_editor(
+						&self.repo.borrow(),
+						Path::new(&path),
+						self.line_to_open.take(),
+					)
+				} else {
+					let changes =
+						self.status_tab.get_files_changes()?;
+					self.commit_popup.show_editor(changes)
+				};
 
 				if let Err(e) = result {
 					let msg =
@@ -390,6 +737,9 @@ impl App {
 					self.msg_popup.show_error(msg.as_str())?;
 				}
 
+				// e.g. the result of staging an edited hunk
+				self.process_queue(NeedsUpdate::empty())?;
+
 				self.requires_redraw.set(true);
 				self.input.set_polling(true);
 			}
@@ -401,16 +751,60 @@ impl App {
 	//TODO: do we need this?
 	/// forward ticking to components that require it
 	pub fn update(&mut self) -> Result<()> {
+		self.update_with(None)
+	}
+
+	/// update after git outside of gitui may have changed the repo,
+	/// `changed` are the workdir relative paths the watcher saw
+	/// change. a changed `HEAD`, index or refs reloads everything
+	/// including the branches and gets noted in the top bar,
+	/// otherwise only `changed` gets looked at if it can.
+	pub fn update_external(
+		&mut self,
+		changed: Option<&[String]>,
+	) -> Result<()> {
+		// our own jobs change refs until they finish
+		if !self.own_git_job_pending() {
+			let external =
+				self.external_changes.check(&self.repo.borrow());
+
+			if !external.is_empty() {
+				log::info!("changed outside gitui: {external:?}");
+				self.external_note = Some(external);
+				self.update_with(None)?;
+
+				return self.process_queue(
+					NeedsUpdate::BRANCHES
+						| NeedsUpdate::WORKTREES
+						| NeedsUpdate::COMMANDS,
+				);
+			}
+		}
+
+		self.update_with(changed)
+	}
+
+	fn update_with(
+		&mut self,
+		changed: Option<&[String]>,
+	) -> Result<()> {
 		log::trace!("update");
 
 		self.commit_popup.update();
-		self.status_tab.update()?;
+		match changed {
+			Some(paths) => self.status_tab.update_changed(paths)?,
+			None => self.status_tab.update()?,
+		}
 		self.revlog.update()?;
 		self.files_tab.update()?;
 		self.stashing_tab.update()?;
 		self.stashlist_tab.update()?;
 		self.reset_popup.update()?;
 
+		if self.check_branch_switch() {
+			// nothing else processes the queue after a tick
+			self.process_queue(NeedsUpdate::empty())?;
+		}
 		self.update_commands();
 
 		Ok(())
@@ -435,13 +829,78 @@ impl App {
 			self.pull_popup.update_git(ev);
 			self.fetch_popup.update_git(ev);
 			self.select_branch_popup.update_git(ev)?;
+			self.branch_cleanup_popup.update_git(ev)?;
+			self.worktrees_popup.update_git(ev);
+			self.submodule_popup.update_git(ev)?;
+
+			if ev == AsyncGitNotification::PostHook {
+				self.update_post_hook();
+			}
+			if ev == AsyncGitNotification::Checkout {
+				self.update_checkout();
+			}
+			if matches!(
+				ev,
+				AsyncGitNotification::Push
+					| AsyncGitNotification::PushTags
+					| AsyncGitNotification::Pull
+					| AsyncGitNotification::Fetch
+					| AsyncGitNotification::DeleteBranches
+					| AsyncGitNotification::PostHook
+					| AsyncGitNotification::Checkout
+					| AsyncGitNotification::Worktrees
+					| AsyncGitNotification::Submodules
+			) {
+				self.external_changes
+					.acknowledge(&self.repo.borrow());
+			}
+		}
+
+		if ev
+			== AsyncNotification::App(
+				AsyncAppNotification::CommitLint,
+			) {
+			self.commit_popup.update_lint();
+		}
+
+		if ev
+			== AsyncNotification::App(
+				AsyncAppNotification::Automation,
+			) {
+			self.update_shell();
+		}
+
+		#[cfg(feature = "forge-api")]
+		if ev
+			== AsyncNotification::App(
+				AsyncAppNotification::ForgeReview,
+			) {
+			self.status_tab.update_review();
+			self.select_branch_popup.set_pull_request(
+				self.status_tab.pull_request().cloned(),
+			);
+		}
+		#[cfg(feature = "forge-api")]
+		if ev
+			== AsyncNotification::App(
+				AsyncAppNotification::ForgeChecks,
+			) {
+			self.revlog.update_checks();
 		}
 
 		self.files_tab.update_async(ev)?;
-		self.blame_file_popup.update_async(ev)?;
+		self.blame_file_popup.update_async(ev);
 		self.revision_files_popup.update(ev)?;
+		self.tree_search_popup.update(ev);
 		self.tags_popup.update(ev);
 
+		if self.refresh_report.is_some() {
+			let jobs = self.refresh_jobs();
+			if let Some(report) = &mut self.refresh_report {
+				report.update(&jobs);
+			}
+		}
+
 		//TODO: better system for this
 		// can we simply process the queue here and everyone just uses the queue to schedule a cmd update?
 		self.process_queue(NeedsUpdate::COMMANDS)?;
@@ -449,6 +908,69 @@ impl App {
 		Ok(())
 	}
 
+	/// runs a command received on the control socket
+	pub fn ipc_command(&mut self, cmd: IpcCommand) -> Result<()> {
+		log::info!("ipc command: {cmd:?}");
+
+		match cmd {
+			IpcCommand::Refresh => {}
+			IpcCommand::OpenCommit(revision) => {
+				match CommitId::from_revision(
+					&self.repo.borrow(),
+					&revision,
+				) {
+					Ok(id) => {
+						self.queue.push(InternalEvent::OpenPopup(
+							StackablePopupOpen::InspectCommit(
+								InspectCommitOpen::new(id),
+							),
+						));
+					}
+					Err(e) => {
+						self.queue.push(InternalEvent::ShowErrorMsg(
+							format!(
+								"{}: {e}",
+								strings::UNKNOWN_REVISION
+							),
+						));
+					}
+				}
+			}
+			IpcCommand::SwitchRepo(path) => {
+				let path =
+					Path::new(&repo_work_dir(&self.repo.borrow())?)
+						.join(path);
+
+				if let Some(e) = sync::repo_open_error(
+					&RepoPath::Path(path.clone()),
+				) {
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("invalid repo path: {e}"),
+					));
+				} else {
+					self.queue.push(InternalEvent::OpenRepo { path });
+				}
+			}
+			IpcCommand::Run(script) => {
+				let branch = self.branch_name.lookup()?;
+				match automation::eval(
+					&script,
+					&self.repo.borrow(),
+					&branch,
+				) {
+					Ok(steps) => self.automation_steps.extend(steps),
+					Err(e) => {
+						self.queue.push(InternalEvent::ShowErrorMsg(
+							format!("automation failed:\n{e}"),
+						));
+					}
+				}
+			}
+		}
+
+		self.process_queue(NeedsUpdate::ALL)
+	}
+
 	///
 	pub fn is_quit(&self) -> bool {
 		!matches!(self.do_quit, QuitState::None)
@@ -460,6 +982,64 @@ impl App {
 		self.do_quit.clone()
 	}
 
+	/// names of all opened repositories and the index of this one,
+	/// only shown if there is more than one
+	pub fn set_repo_tabs(
+		&mut self,
+		names: Vec<String>,
+		active: usize,
+	) {
+		self.repo_tabs = (names.len() > 1).then_some((names, active));
+	}
+
+	/// `Some(reverse)` if the user asked to switch the repository
+	pub fn take_repo_switch(&mut self) -> Option<bool> {
+		self.repo_switch.take()
+	}
+
+	/// `true` if the user asked to re-read theme and key config
+	pub fn take_config_reload(&mut self) -> bool {
+		std::mem::take(&mut self.config_reload)
+	}
+
+	/// `true` if the watcher settings changed and the watcher has to
+	/// be recreated
+	pub fn take_watcher_reload(&mut self) -> bool {
+		std::mem::take(&mut self.watcher_reload)
+	}
+
+	/// persists the view state to be restored on the next start
+	pub fn store_ui_state(&self) {
+		let mut state = self.options.borrow().ui_state().clone();
+
+		self.revlog.store_state(&mut state);
+		self.status_tab.store_state(&mut state);
+
+		self.options.borrow_mut().set_ui_state(state);
+	}
+
+	/// the jobs a refresh can re-run and whether they are running
+	fn refresh_jobs(&self) -> [(&'static str, bool); 4] {
+		[
+			("status", self.status_tab.anything_pending()),
+			("log", self.revlog.any_work_pending()),
+			("files", self.files_tab.anything_pending()),
+			("stashing", self.stashing_tab.anything_pending()),
+		]
+	}
+
+	/// jobs of gitui that change `HEAD`, the index or refs
+	fn own_git_job_pending(&self) -> bool {
+		self.push_popup.any_work_pending()
+			|| self.push_tags_popup.any_work_pending()
+			|| self.pull_popup.any_work_pending()
+			|| self.fetch_popup.any_work_pending()
+			|| self.branch_cleanup_popup.any_work_pending()
+			|| self.post_hook_job.is_pending()
+			|| self.checkout_job.is_pending()
+			|| self.shell_job.is_pending()
+	}
+
 	///
 	pub fn any_work_pending(&self) -> bool {
 		self.status_tab.anything_pending()
@@ -476,7 +1056,20 @@ impl App {
 			|| self.pull_popup.any_work_pending()
 			|| self.fetch_popup.any_work_pending()
 			|| self.revision_files_popup.any_work_pending()
+			|| self.tree_search_popup.any_work_pending()
 			|| self.tags_popup.any_work_pending()
+			|| self.branch_cleanup_popup.any_work_pending()
+			|| self.post_hook_job.is_pending()
+			|| self.checkout_job.is_pending()
+			|| self.shell_job.is_pending()
+	}
+
+	/// advances the spinners of running hooks, `true` if they need a
+	/// redraw
+	pub fn tick_spinner(&mut self) -> bool {
+		// both have to tick
+		let push = self.push_popup.tick_spinner();
+		self.push_tags_popup.tick_spinner() || push
 	}
 
 	///
@@ -488,6 +1081,16 @@ impl App {
 			false
 		}
 	}
+
+	/// writes the image previews of the frame drawn into `buffer`
+	pub fn flush_graphics(
+		&self,
+		out: &mut impl std::io::Write,
+		buffer: &ratatui::buffer::Buffer,
+		force: bool,
+	) -> std::io::Result<()> {
+		self.graphics.borrow_mut().flush(out, buffer, force)
+	}
 }
 
 // private impls
@@ -499,11 +1102,16 @@ impl App {
 			fuzzy_find_popup,
 			msg_popup,
 			confirm_popup,
+			amend_files_popup,
+			commit_checks_popup,
 			commit_popup,
 			goto_line_popup,
+			macro_replay_popup,
 			blame_file_popup,
 			file_revlog_popup,
 			stashmsg_popup,
+			commit_picker_popup,
+			tree_search_popup,
 			inspect_commit_popup,
 			compare_commits_popup,
 			external_editor_popup,
@@ -513,8 +1121,17 @@ impl App {
 			fetch_popup,
 			tag_commit_popup,
 			reset_popup,
+			copy_popup,
+			line_actions_popup,
+			ignore_pattern_popup,
+			bisect_popup,
+			patch_series_popup,
 			checkout_option_popup,
 			create_branch_popup,
+			flow_popup,
+			branch_cleanup_popup,
+			checkout_conflicts_popup,
+			large_files_popup,
 			create_remote_popup,
 			rename_remote_popup,
 			update_remote_url_popup,
@@ -522,10 +1139,19 @@ impl App {
 			rename_branch_popup,
 			select_branch_popup,
 			revision_files_popup,
+			add_submodule_popup,
 			submodule_popup,
+			add_worktree_popup,
+			worktrees_popup,
+			trash_popup,
+			apply_patch_popup,
+			stack_popup,
+			rebase_interactive_popup,
 			tags_popup,
+			key_bindings_popup,
 			options_popup,
 			help_popup,
+			tour_popup,
 			revlog,
 			status_tab,
 			files_tab,
@@ -538,12 +1164,16 @@ impl App {
 		self,
 		[
 			commit_popup,
+			commit_checks_popup,
+			amend_files_popup,
 			stashmsg_popup,
 			help_popup,
+			tour_popup,
 			inspect_commit_popup,
 			compare_commits_popup,
 			blame_file_popup,
 			file_revlog_popup,
+			tree_search_popup,
 			external_editor_popup,
 			tag_commit_popup,
 			select_branch_popup,
@@ -552,10 +1182,26 @@ impl App {
 			rename_remote_popup,
 			update_remote_url_popup,
 			submodule_popup,
+			add_submodule_popup,
+			worktrees_popup,
+			add_worktree_popup,
+			trash_popup,
+			apply_patch_popup,
+			stack_popup,
+			rebase_interactive_popup,
 			tags_popup,
 			reset_popup,
+			copy_popup,
+			line_actions_popup,
+			ignore_pattern_popup,
+			bisect_popup,
+			commit_picker_popup,
 			checkout_option_popup,
 			create_branch_popup,
+			flow_popup,
+			branch_cleanup_popup,
+			checkout_conflicts_popup,
+			large_files_popup,
 			rename_branch_popup,
 			revision_files_popup,
 			fuzzy_find_popup,
@@ -565,9 +1211,11 @@ impl App {
 			pull_popup,
 			fetch_popup,
 			options_popup,
+			key_bindings_popup,
 			confirm_popup,
 			msg_popup,
-			goto_line_popup
+			goto_line_popup,
+			macro_replay_popup
 		]
 	);
 
@@ -615,6 +1263,36 @@ impl App {
 		self.set_tab(new_tab)
 	}
 
+	/// keys to switch the repository and to resize the split
+	fn layout_keys(&mut self, k: &KeyEvent) {
+		let keys = &self.key_config.keys;
+
+		if self.repo_tabs.is_some()
+			&& (key_match(k, keys.repo_next)
+				|| key_match(k, keys.repo_prev))
+		{
+			self.repo_switch = Some(key_match(k, keys.repo_prev));
+		} else if let Some(tab) = self.split_tab() {
+			if key_match(k, keys.split_left)
+				|| key_match(k, keys.split_right)
+			{
+				self.options
+					.borrow_mut()
+					.split_move(tab, key_match(k, keys.split_right));
+			}
+		}
+	}
+
+	/// resizable split of the current tab
+	const fn split_tab(&self) -> Option<SplitTab> {
+		match self.tab {
+			0 => Some(SplitTab::Status),
+			1 => Some(SplitTab::Log),
+			2 => Some(SplitTab::Files),
+			_ => None,
+		}
+	}
+
 	fn switch_tab(&mut self, k: &KeyEvent) -> Result<()> {
 		if key_match(k, self.key_config.keys.tab_status) {
 			self.switch_to_tab(&AppTabs::Status)?;
@@ -631,6 +1309,22 @@ impl App {
 		Ok(())
 	}
 
+	/// switches to the tab clicked on in the top bar
+	fn click_tab(&mut self, ev: MouseEvent) -> Result<bool> {
+		let widths: Vec<usize> =
+			self.tab_labels().iter().map(Span::width).collect();
+		let divider = strings::tab_divider(&self.key_config).width();
+
+		if let Some(tab) =
+			tab_at(ev, self.tabs_area.get(), &widths, divider)
+		{
+			self.set_tab(tab)?;
+			return Ok(true);
+		}
+
+		Ok(false)
+	}
+
 	fn set_tab(&mut self, tab: usize) -> Result<()> {
 		let tabs = self.get_tabs();
 		for (i, t) in tabs.into_iter().enumerate() {
@@ -671,6 +1365,8 @@ impl App {
 		flags.insert(new_flags);
 
 		if flags.contains(NeedsUpdate::ALL) {
+			// whatever changed the repo was done by gitui itself
+			self.external_changes.acknowledge(&self.repo.borrow());
 			self.update()?;
 		}
 		//TODO: make this a queue event?
@@ -690,6 +1386,9 @@ impl App {
 		if flags.contains(NeedsUpdate::REMOTES) {
 			self.remotes_popup.update_remotes()?;
 		}
+		if flags.contains(NeedsUpdate::WORKTREES) {
+			self.worktrees_popup.update_worktrees();
+		}
 
 		Ok(())
 	}
@@ -708,6 +1407,9 @@ impl App {
 			StackablePopupOpen::FileTree(param) => {
 				self.revision_files_popup.open(param)?;
 			}
+			StackablePopupOpen::TreeSearch(param) => {
+				self.tree_search_popup.open(param)?;
+			}
 			StackablePopupOpen::InspectCommit(param) => {
 				self.inspect_commit_popup.open(param)?;
 			}
@@ -719,6 +1421,173 @@ impl App {
 		Ok(())
 	}
 
+	/// triggers the branch switch automation if `HEAD` moved to
+	/// another branch since the last update
+	fn check_branch_switch(&mut self) -> bool {
+		let last = self.branch_name.last();
+		let current = self.branch_name.lookup().ok();
+
+		let switched =
+			last.is_some() && current.is_some() && last != current;
+		if switched {
+			self.queue.push(InternalEvent::RunAutomation(
+				Trigger::BranchSwitch,
+			));
+		}
+
+		switched
+	}
+
+	fn update_checkout(&self) {
+		let Some(result) = self
+			.checkout_job
+			.take_last()
+			.and_then(|job| job.result())
+		else {
+			return;
+		};
+
+		match result {
+			Ok(hook) => {
+				self.queue.push(InternalEvent::RunPostHook(hook));
+			}
+			Err(e) => {
+				log::error!("checkout failed: {e}");
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("checkout failed:\n{e}"),
+				));
+			}
+		}
+
+		self.queue.push(InternalEvent::Update(
+			NeedsUpdate::ALL | NeedsUpdate::BRANCHES,
+		));
+	}
+
+	fn update_post_hook(&self) {
+		let Some((hook, result)) = self
+			.post_hook_job
+			.take_last()
+			.and_then(|job| job.result())
+		else {
+			return;
+		};
+
+		let msg = match result {
+			Ok(HookResult::Ok) => return,
+			Ok(
+				HookResult::NotOk(output)
+				| HookResult::Warning(output),
+			) => output,
+			Err(e) => e.to_string(),
+		};
+
+		self.queue.push(InternalEvent::ShowErrorMsg(format!(
+			"{} hook failed:\n{msg}",
+			hook.name()
+		)));
+	}
+
+	/// queues the steps the script of `trigger` asks for
+	fn run_automation(&mut self, trigger: Trigger) -> Result<()> {
+		let branch = self.branch_name.lookup()?;
+
+		match self.automation.steps(
+			trigger,
+			&self.repo.borrow(),
+			&branch,
+		) {
+			Ok(steps) => self.automation_steps.extend(steps),
+			Err(e) => {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("automation failed:\n{e}"),
+				));
+			}
+		}
+
+		Ok(())
+	}
+
+	fn update_shell(&mut self) {
+		self.waiting_for_shell = false;
+
+		let Some(result) =
+			self.shell_job.take_last().and_then(|job| job.result())
+		else {
+			return;
+		};
+
+		if let Err(e) = result {
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"automation failed:\n{e}"
+			)));
+		}
+
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+	}
+
+	/// runs the next automation step unless a push, fetch or command
+	/// of an earlier one is still going, `false` if nothing was run
+	fn run_automation_step(&mut self) -> bool {
+		if self.push_popup.is_visible()
+			|| self.fetch_popup.is_visible()
+			|| self.waiting_for_shell
+		{
+			return false;
+		}
+
+		let Some(step) = self.automation_steps.pop_front() else {
+			return false;
+		};
+
+		log::info!("automation step: {step:?}");
+
+		if let Err(e) = self.run_step(step) {
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"automation failed:\n{e}"
+			)));
+		}
+
+		true
+	}
+
+	fn run_step(&mut self, step: Step) -> Result<()> {
+		let branch = self.branch_name.lookup()?;
+
+		match step {
+			Step::Push => {
+				// the push popup expects a remote to exist
+				sync::get_default_remote_for_push(
+					&self.repo.borrow(),
+				)?;
+				self.queue.push(InternalEvent::Push(
+					branch,
+					PushType::Branch,
+					false,
+					false,
+				));
+			}
+			Step::Fetch => {
+				sync::get_default_remote_for_fetch(
+					&self.repo.borrow(),
+				)?;
+				self.queue.push(InternalEvent::FetchRemotes);
+			}
+			Step::OpenUrl(url) => {
+				crate::forge::open_in_browser(&url)?;
+			}
+			Step::Shell(command) => {
+				self.shell_job.spawn(AsyncShellJob::new(
+					command,
+					self.repo.borrow().clone(),
+				));
+				self.waiting_for_shell = true;
+			}
+		}
+
+		Ok(())
+	}
+
 	fn process_internal_events(&mut self) -> Result<NeedsUpdate> {
 		let mut flags = NeedsUpdate::empty();
 
@@ -726,7 +1595,7 @@ impl App {
 			let front = self.queue.pop();
 			if let Some(e) = front {
 				flags.insert(self.process_internal_event(e)?);
-			} else {
+			} else if !self.run_automation_step() {
 				break;
 			}
 		}
@@ -741,15 +1610,41 @@ impl App {
 		ev: InternalEvent,
 	) -> Result<NeedsUpdate> {
 		let mut flags = NeedsUpdate::empty();
+
+		// updates follow nearly every action and would drown them
+		if !matches!(ev, InternalEvent::Update(_)) {
+			event_log::record(&LogEvent::Action {
+				name: (&ev).into(),
+			});
+		}
+
 		match ev {
 			InternalEvent::ConfirmedAction(action) => {
 				self.process_confirmed_action(action, &mut flags)?;
 			}
 			InternalEvent::ConfirmAction(action) => {
-				self.confirm_popup.open(action)?;
-				flags.insert(NeedsUpdate::COMMANDS);
+				let protected = ConfirmPopup::protected_branches(
+					&self.repo.borrow(),
+					&action,
+				);
+				if !protected.is_empty()
+					|| self
+						.options
+						.borrow()
+						.confirm()
+						.requires(&action)
+				{
+					self.confirm_popup.open(action, protected)?;
+					flags.insert(NeedsUpdate::COMMANDS);
+				} else {
+					self.process_confirmed_action(
+						action, &mut flags,
+					)?;
+				}
 			}
 			InternalEvent::ShowErrorMsg(msg) => {
+				event_log::record(&LogEvent::Error { message: &msg });
+				self.automation_steps.clear();
 				self.msg_popup.show_error(msg.as_str())?;
 				flags
 					.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS);
@@ -761,6 +1656,12 @@ impl App {
 			}
 			InternalEvent::Update(u) => flags.insert(u),
 			InternalEvent::OpenCommit => self.commit_popup.show()?,
+			InternalEvent::OpenAmendFiles(keep_staged) => {
+				self.amend_files_popup.open(&keep_staged)?;
+			}
+			InternalEvent::AmendKeepStaged(keep_staged) => {
+				self.commit_popup.set_keep_staged(keep_staged);
+			}
 			InternalEvent::RewordCommit(id) => {
 				self.commit_popup.open(Some(id))?;
 			}
@@ -771,9 +1672,26 @@ impl App {
 			InternalEvent::TagCommit(id) => {
 				self.tag_commit_popup.open(id)?;
 			}
+			InternalEvent::RebaseInteractive(id) => {
+				if let Err(e) = self.rebase_interactive_popup.open(id)
+				{
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("rebase error:\n{e}"),
+					));
+				}
+			}
 			InternalEvent::CreateRemote => {
 				self.create_remote_popup.open()?;
 			}
+			InternalEvent::AddWorktree => {
+				self.add_worktree_popup.open()?;
+			}
+			InternalEvent::AddSubmodule => {
+				self.add_submodule_popup.open()?;
+			}
+			InternalEvent::SubmoduleOperation(operation) => {
+				self.submodule_popup.run_operation(operation);
+			}
 			InternalEvent::RenameRemote(cur_name) => {
 				self.rename_remote_popup.open(cur_name)?;
 			}
@@ -787,16 +1705,32 @@ impl App {
 			InternalEvent::CreateBranch => {
 				self.create_branch_popup.open()?;
 			}
+			InternalEvent::OpenFlow => {
+				self.flow_popup.open()?;
+			}
+			InternalEvent::OpenBranchCleanup => {
+				self.branch_cleanup_popup.open()?;
+			}
 			InternalEvent::RenameBranch(branch_ref, cur_name) => {
 				self.rename_branch_popup
 					.open(branch_ref, cur_name)?;
 			}
+			InternalEvent::CopyBranch(branch_ref, cur_name) => {
+				self.rename_branch_popup
+					.open_copy(branch_ref, cur_name)?;
+			}
 			InternalEvent::SelectBranch => {
 				self.select_branch_popup.open()?;
 			}
+			InternalEvent::OpenTour => {
+				self.tour_popup.open()?;
+			}
 			InternalEvent::ViewSubmodules => {
 				self.submodule_popup.open()?;
 			}
+			InternalEvent::ApplyPatch => {
+				self.apply_patch_popup.open()?;
+			}
 			InternalEvent::Tags => {
 				self.tags_popup.open()?;
 			}
@@ -815,12 +1749,36 @@ impl App {
 					flags.insert(NeedsUpdate::ALL);
 				}
 			}
+			InternalEvent::SelectOrInspectCommit(id) => {
+				if self.revlog.select_commit(id).is_ok() {
+					flags.insert(NeedsUpdate::ALL);
+				} else {
+					self.queue.push(InternalEvent::OpenPopup(
+						StackablePopupOpen::InspectCommit(
+							InspectCommitOpen::new(id),
+						),
+					));
+				}
+			}
 			InternalEvent::OpenExternalEditor(path) => {
 				self.input.set_polling(false);
 				self.external_editor_popup.show()?;
 				self.file_to_open = path;
 				flags.insert(NeedsUpdate::COMMANDS);
 			}
+			InternalEvent::OpenExternalEditorAt(path, line) => {
+				self.input.set_polling(false);
+				self.external_editor_popup.show()?;
+				self.file_to_open = Some(path);
+				self.line_to_open = Some(line);
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
+			InternalEvent::EditHunk(path, hunk) => {
+				self.input.set_polling(false);
+				self.external_editor_popup.show()?;
+				self.hunk_to_edit = Some((path, hunk));
+				flags.insert(NeedsUpdate::COMMANDS);
+			}
 			InternalEvent::Push(branch, push_type, force, delete) => {
 				self.push_popup
 					.push(branch, push_type, force, delete)?;
@@ -842,6 +1800,14 @@ impl App {
 				}
 				flags.insert(NeedsUpdate::ALL);
 			}
+			InternalEvent::Unshallow => {
+				if let Err(error) = self.fetch_popup.unshallow() {
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						error.to_string(),
+					));
+				}
+				flags.insert(NeedsUpdate::ALL);
+			}
 			InternalEvent::PushTags => {
 				self.push_tags_popup.push_tags()?;
 				flags.insert(NeedsUpdate::ALL);
@@ -869,6 +1835,35 @@ impl App {
 					| AppOption::DiffInterhunkLines => {
 						self.status_tab.update_diff()?;
 					}
+					AppOption::BranchAheadBehind
+					| AppOption::BranchDate
+					| AppOption::BranchAuthor
+					| AppOption::BranchUpstream
+					| AppOption::BranchSort
+					| AppOption::BranchHideRemote => {
+						self.select_branch_popup.update_branches()?;
+					}
+					AppOption::WatcherStrategy
+					| AppOption::WatcherDebounce
+					| AppOption::WatcherTick => {
+						self.watcher_reload = true;
+					}
+					AppOption::WatcherFocus
+					| AppOption::StatusDiscardToTrash
+					| AppOption::StatusAbsorbAutosquash
+					| AppOption::BranchAutostash
+					| AppOption::ConfirmDiscard
+					| AppOption::ConfirmForcePush
+					| AppOption::ConfirmBranchDelete
+					| AppOption::ConfirmResetHard
+					| AppOption::ConfirmStashDrop
+					| AppOption::LogDecorations
+					| AppOption::LogRemoteBranches
+					| AppOption::LogTags
+					| AppOption::HookPreCommit
+					| AppOption::HookCommitMsg
+					| AppOption::HookPrePush
+					| AppOption::KeyBindings => (),
 				}
 
 				flags.insert(NeedsUpdate::ALL);
@@ -925,9 +1920,39 @@ impl App {
 			InternalEvent::OpenResetPopup(id) => {
 				self.reset_popup.open(id)?;
 			}
+			InternalEvent::OpenCopyPopup(context) => {
+				self.copy_popup.open(context)?;
+			}
+			InternalEvent::OpenLineActionsPopup(context) => {
+				self.line_actions_popup.open(context)?;
+			}
+			InternalEvent::OpenIgnorePatternPopup(context) => {
+				self.ignore_pattern_popup.open(&context)?;
+			}
+			InternalEvent::OpenBisectPopup(id) => {
+				self.bisect_popup.open(id)?;
+			}
+			InternalEvent::BisectRun(command) => {
+				self.revlog.bisect_run(command);
+			}
+			InternalEvent::OpenCommitPicker(context) => {
+				self.commit_picker_popup.open(context)?;
+			}
+			InternalEvent::OpenPatchSeriesPopup(context) => {
+				self.patch_series_popup.open(context)?;
+			}
 			InternalEvent::CommitSearch(options) => {
 				self.revlog.search(options);
 			}
+			InternalEvent::OpenKeyBindingsPopup => {
+				self.key_bindings_popup.open()?;
+			}
+			InternalEvent::RunAutomation(trigger) => {
+				self.run_automation(trigger)?;
+			}
+			InternalEvent::ReplayMacro(times) => {
+				self.macro_replay = Some(times);
+			}
 			InternalEvent::OpenGotoLinePopup(max_line) => {
 				self.goto_line_popup.open(max_line);
 			}
@@ -939,20 +1964,57 @@ impl App {
 			InternalEvent::CheckoutOption(branch) => {
 				self.checkout_option_popup.open(branch)?;
 			}
+			InternalEvent::OpenCheckoutConflicts(branch, files) => {
+				self.checkout_conflicts_popup.open(branch, files)?;
+			}
+			InternalEvent::OpenLargeFiles(files) => {
+				self.large_files_popup.open(files)?;
+			}
+			InternalEvent::OpenCommitChecks(violations) => {
+				self.commit_checks_popup.open(violations)?;
+			}
+			InternalEvent::CheckoutAutostash(branch) => {
+				self.checkout_job.spawn(AsyncCheckoutJob::new(
+					self.repo.borrow().clone(),
+					branch,
+				));
+			}
+			InternalEvent::RunPostHook(hook) => {
+				self.post_hook_job.spawn(AsyncPostHookJob::new(
+					self.repo.borrow().clone(),
+					hook,
+				));
+			}
 		}
 
 		Ok(flags)
 	}
 
+	#[allow(clippy::too_many_lines)]
 	fn process_confirmed_action(
 		&mut self,
 		action: Action,
 		flags: &mut NeedsUpdate,
 	) -> Result<()> {
+		event_log::record(&LogEvent::Action {
+			name: (&action).into(),
+		});
+
+		if !self.trash_before_discard(&action) {
+			return Ok(());
+		}
+
 		match action {
 			Action::Reset(r) => {
 				self.status_tab.reset(&r);
 			}
+			Action::ResetItems(paths) => {
+				for path in paths {
+					if !self.status_tab.reset(&ResetItem { path }) {
+						break;
+					}
+				}
+			}
 			Action::StashDrop(_) | Action::StashPop(_) => {
 				if let Err(e) = self
 					.stashlist_tab
@@ -993,9 +2055,19 @@ impl App {
 			Action::DeleteRemoteBranch(branch_ref) => {
 				self.delete_remote_branch(&branch_ref)?;
 			}
+			Action::DeleteBranches(branch_refs) => {
+				self.branch_cleanup_popup.delete(branch_refs);
+			}
 			Action::DeleteRemote(remote_name) => {
 				self.delete_remote(&remote_name);
 			}
+			Action::RemoveWorktree(name) => {
+				self.remove_worktree(&name);
+			}
+			Action::RemoveSubmodule(name) => {
+				self.submodule_popup
+					.run_operation(SubmoduleOperation::Remove(name));
+			}
 			Action::DeleteTag(tag_name) => {
 				self.delete_tag(tag_name)?;
 			}
@@ -1015,15 +2087,40 @@ impl App {
 					false,
 				));
 			}
+			Action::ResetHard(id) => {
+				try_or_popup!(
+					self,
+					"reset:",
+					sync::reset_repo(
+						&self.repo.borrow(),
+						id,
+						ResetType::Hard
+					)
+				);
+			}
 			Action::PullMerge { rebase, .. } => {
 				self.pull_popup.try_conflict_free_merge(rebase);
 			}
-			Action::AbortRevert | Action::AbortMerge => {
+			Action::AbortRevert
+			| Action::AbortMerge
+			| Action::AbortCherryPick => {
 				self.status_tab.revert_pending_state();
 			}
 			Action::AbortRebase => {
 				self.status_tab.abort_rebase();
 			}
+			Action::AbortApplyMailbox => {
+				self.status_tab.abort_apply_mailbox();
+			}
+			Action::FinishFlowBranch { branch, .. } => {
+				try_or_popup!(
+					self,
+					"finish branch failed:",
+					sync::flow_finish(&self.repo.borrow(), &branch)
+				);
+
+				self.select_branch_popup.update_branches()?;
+			}
 			Action::UndoCommit => {
 				try_or_popup!(
 					self,
@@ -1031,6 +2128,17 @@ impl App {
 					undo_last_commit(&self.repo.borrow())
 				);
 			}
+			Action::CheckoutFile { commit, path } => {
+				try_or_popup!(
+					self,
+					"checkout file failed:",
+					sync::checkout_file_at_commit(
+						&self.repo.borrow(),
+						commit,
+						&path
+					)
+				);
+			}
 		}
 
 		flags.insert(NeedsUpdate::ALL);
@@ -1038,7 +2146,33 @@ impl App {
 		Ok(())
 	}
 
-	fn delete_tag(&mut self, tag_name: String) -> Result<()> {
+	/// saves what a discard loses to the trash if enabled, `false` if
+	/// that failed and the discard has to be skipped
+	fn trash_before_discard(&self, action: &Action) -> bool {
+		if !self.options.borrow().discard_to_trash() {
+			return true;
+		}
+
+		let paths = match action {
+			Action::Reset(item) => vec![item.path.clone()],
+			Action::ResetItems(paths) => paths.clone(),
+			Action::ResetHunk(path, _)
+			| Action::ResetLines(path, _) => vec![path.clone()],
+			_ => return true,
+		};
+
+		if let Err(e) = sync::trash_paths(&self.repo.borrow(), &paths)
+		{
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"moving to trash failed, nothing discarded:\n{e}"
+			)));
+			return false;
+		}
+
+		true
+	}
+
+	fn delete_tag(&self, tag_name: String) -> Result<()> {
 		if let Err(error) =
 			sync::delete_tag(&self.repo.borrow(), &tag_name)
 		{
@@ -1052,7 +2186,7 @@ impl App {
 				Action::DeleteRemoteTag(tag_name, remote),
 			));
 
-			self.tags_popup.update_tags()?;
+			self.tags_popup.update_tags();
 		}
 		Ok(())
 	}
@@ -1097,12 +2231,78 @@ impl App {
 			Err(e) => {
 				log::error!("delete remote: {e:?}");
 				self.queue.push(InternalEvent::ShowErrorMsg(
-					format!("delete remote error:\n{e}",),
+					format!("delete remote error:\n{e}"),
 				));
 			}
 		}
 	}
 
+	/// stages the hunk as edited in the external editor, errors
+	/// are only returned if the editor could not be run
+	fn edit_hunk(&self, path: &str, hunk: &Hunk) -> Result<()> {
+		let repo = self.repo.borrow();
+
+		let text = match sync::hunk_patch(
+			&repo,
+			path,
+			hunk,
+			Some(self.options.borrow().diff_options()),
+		) {
+			Ok(text) => text,
+			Err(e) => {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("edit hunk error:\n{e}"),
+				));
+				return Ok(());
+			}
+		};
+
+		let file_path = sync::repo_dir(&repo)?.join("HUNK_EDIT.diff");
+		std::fs::write(
+			&file_path,
+			text + strings::edit_hunk_editor_msg(&self.key_config)
+				.as_str(),
+		)?;
+
+		let edited = ExternalEditorPopup::open_file_in_editor(
+			&repo, &file_path, None,
+		)
+		.and_then(|()| Ok(std::fs::read_to_string(&file_path)?));
+		std::fs::remove_file(&file_path)?;
+
+		match sync::apply_edited_hunk(&repo, path, &edited?) {
+			Ok(()) => {
+				self.queue
+					.push(InternalEvent::Update(NeedsUpdate::ALL));
+			}
+			Err(e) => {
+				log::error!("edit hunk: {e}");
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("edit hunk error:\n{e}"),
+				));
+			}
+		}
+
+		Ok(())
+	}
+
+	fn remove_worktree(&self, name: &str) {
+		match sync::remove_worktree(&self.repo.borrow(), name) {
+			Ok(()) => {
+				self.queue.push(InternalEvent::Update(
+					NeedsUpdate::ALL | NeedsUpdate::WORKTREES,
+				));
+			}
+			Err(e) => {
+				log::error!("remove worktree: {e:?}");
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("remove worktree error:\n{e}"),
+				));
+			}
+		}
+	}
+
+	#[allow(clippy::too_many_lines)]
 	fn commands(&self, force_all: bool) -> Vec<CommandInfo> {
 		let mut res = Vec::new();
 
@@ -1135,6 +2335,83 @@ impl App {
 			)
 			.order(order::NAV),
 		);
+		res.push(
+			CommandInfo::new(
+				strings::commands::macro_record(
+					&self.key_config,
+					self.key_macro.is_recording(),
+				),
+				true,
+				!self.any_popup_visible(),
+			)
+			.order(order::NAV),
+		);
+		res.push(
+			CommandInfo::new(
+				strings::commands::macro_replay(&self.key_config),
+				!self.key_macro.is_recording()
+					&& self.key_macro.has_recording(),
+				!self.any_popup_visible(),
+			)
+			.order(order::NAV),
+		);
+		res.push(
+			CommandInfo::new(
+				strings::commands::view_worktrees(&self.key_config),
+				true,
+				!self.any_popup_visible(),
+			)
+			.order(order::NAV),
+		);
+		res.push(
+			CommandInfo::new(
+				strings::commands::view_trash(&self.key_config),
+				true,
+				!self.any_popup_visible(),
+			)
+			.order(order::NAV),
+		);
+		res.push(
+			CommandInfo::new(
+				strings::commands::view_stack(&self.key_config),
+				true,
+				!self.any_popup_visible(),
+			)
+			.order(order::NAV),
+		);
+		res.push(
+			CommandInfo::new(
+				strings::commands::reload_config(&self.key_config),
+				true,
+				!self.any_popup_visible(),
+			)
+			.order(order::NAV),
+		);
+		res.push(
+			CommandInfo::new(
+				strings::commands::refresh(&self.key_config),
+				true,
+				!self.any_popup_visible(),
+			)
+			.order(order::NAV),
+		);
+		res.push(
+			CommandInfo::new(
+				strings::commands::switch_repo(&self.key_config),
+				true,
+				!self.any_popup_visible() && self.repo_tabs.is_some(),
+			)
+			.order(order::NAV),
+		);
+		res.push(
+			CommandInfo::new(
+				strings::commands::resize_split(&self.key_config),
+				true,
+				!self.any_popup_visible()
+					&& self.split_tab().is_some(),
+			)
+			.order(order::NAV),
+		);
 		res.push(
 			CommandInfo::new(
 				strings::commands::options_popup(&self.key_config),
@@ -1157,6 +2434,16 @@ impl App {
 	}
 
 	//TODO: make this dynamic
+	fn tab_labels(&self) -> [Span<'static>; 5] {
+		[
+			Span::raw(strings::tab_status(&self.key_config)),
+			Span::raw(strings::tab_log(&self.key_config)),
+			Span::raw(strings::tab_files(&self.key_config)),
+			Span::raw(strings::tab_stashing(&self.key_config)),
+			Span::raw(strings::tab_stashes(&self.key_config)),
+		]
+	}
+
 	fn draw_top_bar(&self, f: &mut Frame, r: Rect) {
 		const DIVIDER_PAD_SPACES: usize = 2;
 		const SIDE_PADS: usize = 2;
@@ -1167,13 +2454,9 @@ impl App {
 			horizontal: 1,
 		});
 
-		let tab_labels = [
-			Span::raw(strings::tab_status(&self.key_config)),
-			Span::raw(strings::tab_log(&self.key_config)),
-			Span::raw(strings::tab_files(&self.key_config)),
-			Span::raw(strings::tab_stashing(&self.key_config)),
-			Span::raw(strings::tab_stashes(&self.key_config)),
-		];
+		self.tabs_area.set(Rect { height: 1, ..r });
+
+		let tab_labels = self.tab_labels();
 		let divider = strings::tab_divider(&self.key_config);
 
 		// heuristic, since tui doesn't provide a way to know
@@ -1214,16 +2497,92 @@ impl App {
 			table_area,
 		);
 
-		f.render_widget(
-			Paragraph::new(Line::from(vec![Span::styled(
-				ellipsis_trim_start(
-					&self.repo_path_text,
-					text_area.width as usize,
+		let mut repo_text = self.repo_tabs.as_ref().map_or_else(
+			|| {
+				Line::from(vec![Span::styled(
+					ellipsis_trim_start(
+						&self.repo_path_text,
+						text_area.width as usize,
+					),
+					self.theme.title(false),
+				)])
+			},
+			|(names, active)| self.repo_tabs_line(names, *active),
+		);
+
+		if let Some(name) = &self.worktree_name {
+			repo_text.spans.insert(
+				0,
+				Span::styled(
+					format!(
+						"{} {name} ",
+						strings::WORKTREE_INDICATOR
+					),
+					self.theme.branch(false, true),
+				),
+			);
+		}
+
+		if let Some(changes) = self.external_note {
+			repo_text.spans.insert(
+				0,
+				Span::styled(
+					format!(
+						"{} ",
+						strings::external_changes(changes)
+					),
+					self.theme.text(true, false),
+				),
+			);
+		}
+
+		if let Some(report) = &self.refresh_report {
+			repo_text.spans.insert(
+				0,
+				Span::styled(
+					format!("{} ", report.text()),
+					self.theme.text(true, false),
 				),
-				self.theme.title(false),
-			)]))
-			.alignment(Alignment::Right),
+			);
+		}
+
+		if self.key_macro.is_recording() {
+			repo_text.spans.insert(
+				0,
+				Span::styled(
+					format!("{} ", strings::MACRO_RECORDING),
+					self.theme.text_danger(),
+				),
+			);
+		}
+
+		f.render_widget(
+			Paragraph::new(repo_text).alignment(Alignment::Right),
 			text_area,
 		);
 	}
+
+	fn repo_tabs_line<'a>(
+		&self,
+		names: &'a [String],
+		active: usize,
+	) -> Line<'a> {
+		let divider = strings::tab_divider(&self.key_config);
+
+		let mut spans = Vec::with_capacity(names.len() * 2);
+		for (idx, name) in names.iter().enumerate() {
+			if idx > 0 {
+				spans.push(Span::styled(
+					divider.clone(),
+					self.theme.tab(false),
+				));
+			}
+			spans.push(Span::styled(
+				name.as_str(),
+				self.theme.tab(idx == active),
+			));
+		}
+
+		Line::from(spans)
+	}
 }
@@ -1,9 +1,9 @@
-use crate::bug_report;
+use crate::{bug_report, headless::HeadlessCommand};
 use anyhow::{anyhow, Context, Result};
 use asyncgit::sync::RepoPath;
 use clap::{
 	builder::ArgPredicate, crate_authors, crate_description,
-	crate_name, Arg, Command as ClapApp,
+	crate_name, value_parser, Arg, ArgMatches, Command as ClapApp,
 };
 use simplelog::{Config, LevelFilter, WriteLogger};
 use std::{
@@ -18,23 +18,94 @@ const LOGGING_FLAG_ID: &str = "logging";
 const THEME_FLAG_ID: &str = "theme";
 const WORKDIR_FLAG_ID: &str = "workdir";
 const FILE_FLAG_ID: &str = "file";
+const BLAME_FLAG_ID: &str = "blame";
+const HISTORY_FLAG_ID: &str = "history";
+const TAB_FLAG_ID: &str = "tab";
+const COMMIT_FLAG_ID: &str = "commit";
+const JSON_FLAG_ID: &str = "json";
+const COUNT_FLAG_ID: &str = "count";
+const STATUS_COMMAND: &str = "status";
+const LOG_COMMAND: &str = "log";
 const GIT_DIR_FLAG_ID: &str = "directory";
+const REPO_FLAG_ID: &str = "repo";
 const WATCHER_FLAG_ID: &str = "watcher";
+const MOUSE_FLAG_ID: &str = "mouse";
+const OSC52_FLAG_ID: &str = "osc52";
+const SOCKET_FLAG_ID: &str = "socket";
+const EVENT_LOG_FLAG_ID: &str = "event-log";
+const ACCESSIBLE_FLAG_ID: &str = "accessible";
+const AMBIGUOUS_WIDE_FLAG_ID: &str = "ambiguous-wide";
 const KEY_BINDINGS_FLAG_ID: &str = "key_bindings";
 const KEY_SYMBOLS_FLAG_ID: &str = "key_symbols";
 const DEFAULT_THEME: &str = "theme.ron";
 const DEFAULT_GIT_DIR: &str = ".";
+/// values of `--tab` in the order of the tabs
+const TABS: [&str; 5] =
+	["status", "log", "files", "stashing", "stashes"];
+
+/// how the file passed with `--file` is opened
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum FileView {
+	/// selected in the files tab
+	#[default]
+	Tree,
+	Blame,
+	History,
+}
 
 #[derive(Clone)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct CliArgs {
 	pub theme: PathBuf,
 	pub select_file: Option<PathBuf>,
+	pub file_view: FileView,
+	/// index of the tab to start in
+	pub tab: Option<usize>,
+	/// revision to inspect on start
+	pub commit: Option<String>,
+	/// prints the result instead of starting the ui
+	pub headless: Option<HeadlessCommand>,
 	pub repo_path: RepoPath,
+	/// opened in additional repo tabs
+	pub extra_repos: Vec<RepoPath>,
+	/// control socket to listen on
+	pub socket: Option<PathBuf>,
+	/// json lines file recording the session
+	pub event_log: Option<PathBuf>,
 	pub notify_watcher: bool,
+	pub mouse: bool,
+	/// copy through the terminal instead of a clipboard tool
+	pub osc52: bool,
+	/// no animations, high contrast and textual markers
+	pub accessible: bool,
+	/// east asian ambiguous characters take two columns
+	pub ambiguous_wide: bool,
 	pub key_bindings_path: Option<PathBuf>,
 	pub key_symbols_path: Option<PathBuf>,
 }
 
+impl CliArgs {
+	/// same settings for another repo, without the view to start in
+	pub fn for_repo(&self, repo_path: RepoPath) -> Self {
+		Self {
+			repo_path,
+			select_file: None,
+			file_view: FileView::default(),
+			tab: None,
+			commit: None,
+			headless: None,
+			..self.clone()
+		}
+	}
+
+	/// whether a specific view was asked for on the command line
+	pub const fn has_start_view(&self) -> bool {
+		self.select_file.is_some()
+			|| self.tab.is_some()
+			|| self.commit.is_some()
+	}
+}
+
 pub fn process_cmdline() -> Result<CliArgs> {
 	let app = app();
 
@@ -61,6 +132,19 @@ pub fn process_cmdline() -> Result<CliArgs> {
 	let select_file = arg_matches
 		.get_one::<String>(FILE_FLAG_ID)
 		.map(PathBuf::from);
+	let file_view = if arg_matches.get_flag(BLAME_FLAG_ID) {
+		FileView::Blame
+	} else if arg_matches.get_flag(HISTORY_FLAG_ID) {
+		FileView::History
+	} else {
+		FileView::Tree
+	};
+
+	let tab = arg_matches
+		.get_one::<String>(TAB_FLAG_ID)
+		.and_then(|tab| TABS.iter().position(|name| name == tab));
+	let commit =
+		arg_matches.get_one::<String>(COMMIT_FLAG_ID).cloned();
 
 	let repo_path = if let Some(w) = workdir {
 		RepoPath::Workdir { gitdir, workdir: w }
@@ -68,6 +152,13 @@ pub fn process_cmdline() -> Result<CliArgs> {
 		RepoPath::Path(gitdir)
 	};
 
+	let extra_repos = arg_matches
+		.get_many::<String>(REPO_FLAG_ID)
+		.into_iter()
+		.flatten()
+		.map(|path| RepoPath::Path(PathBuf::from(path)))
+		.collect();
+
 	let arg_theme = arg_matches
 		.get_one::<String>(THEME_FLAG_ID)
 		.map_or_else(|| PathBuf::from(DEFAULT_THEME), PathBuf::from);
@@ -84,6 +175,19 @@ pub fn process_cmdline() -> Result<CliArgs> {
 	let notify_watcher: bool =
 		*arg_matches.get_one(WATCHER_FLAG_ID).unwrap_or(&false);
 
+	let mouse = arg_matches.get_flag(MOUSE_FLAG_ID);
+	let osc52 = arg_matches.get_flag(OSC52_FLAG_ID);
+	let accessible = arg_matches.get_flag(ACCESSIBLE_FLAG_ID);
+	let ambiguous_wide = arg_matches.get_flag(AMBIGUOUS_WIDE_FLAG_ID);
+
+	let socket = arg_matches
+		.get_one::<String>(SOCKET_FLAG_ID)
+		.map(PathBuf::from);
+
+	let event_log = arg_matches
+		.get_one::<String>(EVENT_LOG_FLAG_ID)
+		.map(PathBuf::from);
+
 	let key_bindings_path = arg_matches
 		.get_one::<String>(KEY_BINDINGS_FLAG_ID)
 		.map(PathBuf::from);
@@ -95,15 +199,26 @@ pub fn process_cmdline() -> Result<CliArgs> {
 	Ok(CliArgs {
 		theme,
 		select_file,
+		file_view,
+		tab,
+		commit,
+		headless: headless_command(&arg_matches),
 		repo_path,
+		extra_repos,
+		socket,
+		event_log,
 		notify_watcher,
+		mouse,
+		osc52,
+		accessible,
+		ambiguous_wide,
 		key_bindings_path,
 		key_symbols_path,
 	})
 }
 
 fn app() -> ClapApp {
-	ClapApp::new(crate_name!())
+	let app = ClapApp::new(crate_name!())
 		.author(crate_authors!())
 		.version(env!("GITUI_BUILD_NAME"))
 		.about(crate_description!())
@@ -167,13 +282,6 @@ fn app() -> ClapApp {
 				.long("bugreport")
 				.action(clap::ArgAction::SetTrue),
 		)
-		.arg(
-			Arg::new(FILE_FLAG_ID)
-				.help("Select the file in the file tab")
-				.short('f')
-				.long("file")
-				.num_args(1),
-		)
 		.arg(
 			Arg::new(GIT_DIR_FLAG_ID)
 				.help("Set the git directory")
@@ -182,6 +290,15 @@ fn app() -> ClapApp {
 				.env("GIT_DIR")
 				.num_args(1),
 		)
+		.arg(
+			Arg::new(REPO_FLAG_ID)
+				.help("Open another repository in an additional repo tab (can be repeated)")
+				.short('r')
+				.long("repo")
+				.value_name("PATH")
+				.action(clap::ArgAction::Append)
+				.num_args(1),
+		)
 		.arg(
 			Arg::new(WORKDIR_FLAG_ID)
 				.help("Set the working directory")
@@ -189,6 +306,154 @@ fn app() -> ClapApp {
 				.long("workdir")
 				.env("GIT_WORK_TREE")
 				.num_args(1),
+		);
+
+	headless_commands(start_view_args(display_args(
+		integration_args(app),
+	)))
+}
+
+fn headless_command(
+	arg_matches: &ArgMatches,
+) -> Option<HeadlessCommand> {
+	match arg_matches.subcommand()? {
+		(STATUS_COMMAND, args) => Some(HeadlessCommand::Status {
+			json: args.get_flag(JSON_FLAG_ID),
+		}),
+		(LOG_COMMAND, args) => Some(HeadlessCommand::Log {
+			json: args.get_flag(JSON_FLAG_ID),
+			count: args
+				.get_one::<usize>(COUNT_FLAG_ID)
+				.copied()
+				.unwrap_or_default(),
+		}),
+		_ => None,
+	}
+}
+
+/// commands printing their result instead of starting the ui
+fn headless_commands(app: ClapApp) -> ClapApp {
+	let json = Arg::new(JSON_FLAG_ID)
+		.help("Print json")
+		.long("json")
+		.action(clap::ArgAction::SetTrue);
+
+	app.subcommand(
+		ClapApp::new(STATUS_COMMAND)
+			.about(
+				"Print the changes in the working dir and the stage",
+			)
+			.arg(json.clone()),
+	)
+	.subcommand(
+		ClapApp::new(LOG_COMMAND)
+			.about("Print the newest commits of HEAD")
+			.arg(json)
+			.arg(
+				Arg::new(COUNT_FLAG_ID)
+					.help("Number of commits to print")
+					.short('n')
+					.long("max-count")
+					.value_name("NUMBER")
+					.value_parser(value_parser!(usize))
+					.default_value("20"),
+			),
+	)
+}
+
+/// flags letting other programs drive or observe gitui
+fn integration_args(app: ClapApp) -> ClapApp {
+	app
+		.arg(
+			Arg::new(SOCKET_FLAG_ID)
+				.help("Listen for commands from other programs on a unix socket. See https://github.com/gitui-org/gitui/blob/master/FAQ.md#socket for details.")
+				.long("socket")
+				.value_name("PATH")
+				.env("GITUI_SOCKET")
+				.num_args(1),
+		)
+		.arg(
+			Arg::new(EVENT_LOG_FLAG_ID)
+				.help("Append a json line for every action, job duration and error to the file. See https://github.com/gitui-org/gitui/blob/master/FAQ.md#eventlog for details.")
+				.long("event-log")
+				.value_name("FILE")
+				.env("GITUI_EVENT_LOG")
+				.num_args(1),
+		)
+}
+
+/// flags changing how the ui is drawn and talks to the terminal
+fn display_args(app: ClapApp) -> ClapApp {
+	app
+		.arg(
+			Arg::new(MOUSE_FLAG_ID)
+				.help("Enable mouse support (clicking, scrolling and selecting diff lines). Hold shift to select text in the terminal.")
+				.long("mouse")
+				.action(clap::ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new(OSC52_FLAG_ID)
+				.help("Copy to the clipboard with the OSC 52 escape sequence of the terminal instead of a clipboard tool. Used automatically in ssh sessions without a forwarded display. See https://github.com/gitui-org/gitui/blob/master/FAQ.md#clipboard for details.")
+				.long("osc52")
+				.env("GITUI_OSC52")
+				.value_parser(clap::builder::FalseyValueParser::new())
+				.action(clap::ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new(ACCESSIBLE_FLAG_ID)
+				.help("Screen reader friendly mode: no spinner, high contrast colors, +/- markers on diff lines and a fixed cursor position. Can also be enabled in the theme file. See https://github.com/gitui-org/gitui/blob/master/FAQ.md#accessibility for details.")
+				.long("accessible")
+				.action(clap::ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new(AMBIGUOUS_WIDE_FLAG_ID)
+				.help("Treat east asian ambiguous width characters as two columns wide, like terminals in a CJK locale draw them. See https://github.com/gitui-org/gitui/blob/master/FAQ.md#unicode for details.")
+				.long("ambiguous-wide")
+				.env("GITUI_AMBIGUOUS_WIDE")
+				.value_parser(clap::builder::FalseyValueParser::new())
+				.action(clap::ArgAction::SetTrue),
+		)
+}
+
+/// flags opening a specific view on start
+fn start_view_args(app: ClapApp) -> ClapApp {
+	app
+		.arg(
+			Arg::new(FILE_FLAG_ID)
+				.help("Select the file in the file tab")
+				.short('f')
+				.long("file")
+				.num_args(1),
+		)
+		.arg(
+			Arg::new(BLAME_FLAG_ID)
+				.help("Open the blame of the file passed with --file (at --commit if given)")
+				.long("blame")
+				.requires(FILE_FLAG_ID)
+				.conflicts_with(HISTORY_FLAG_ID)
+				.action(clap::ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new(HISTORY_FLAG_ID)
+				.help("Open the history of the file passed with --file, showing the diff of each revision")
+				.long("history")
+				.requires(FILE_FLAG_ID)
+				.action(clap::ArgAction::SetTrue),
+		)
+		.arg(
+			Arg::new(TAB_FLAG_ID)
+				.help("Start in the given tab")
+				.long("tab")
+				.value_name("TAB")
+				.value_parser(TABS)
+				.num_args(1),
+		)
+		.arg(
+			Arg::new(COMMIT_FLAG_ID)
+				.help("Inspect the given commit (any revision like a sha, branch or tag)")
+				.long("commit")
+				.value_name("REVISION")
+				.num_args(1),
 		)
 }
 
@@ -0,0 +1,373 @@
+//! user scripts gitui runs when something happens in the repo,
+//! written in [rhai](https://rhai.rs) in `automation.rhai` in the
+//! config directory. every event calls the function of its name:
+//!
+//! ```rhai
+//! fn post_commit() {
+//!     push();
+//!     open_url(`${base_url()}/compare/${branch()}?expand=1`);
+//! }
+//!
+//! fn branch_switch() {
+//!     if changes() == 0 {
+//!         shell("cargo fetch");
+//!     }
+//! }
+//! ```
+//!
+//! scripts only look at the repo and queue up steps, the steps run
+//! one after another once the script returned: a `push()`, `fetch()`
+//! or `shell(..)` is waited for and the remaining steps are dropped
+//! once an error is shown
+
+use crate::{args::get_app_config_path, forge, AsyncAppNotification};
+use anyhow::{anyhow, Result};
+use asyncgit::{
+	asyncjob::{AsyncJob, RunParams},
+	sync::{self, status::StatusType, RepoPath},
+};
+use rhai::{Dynamic, Engine, EvalAltResult, Scope, AST};
+use std::{
+	cell::RefCell,
+	fs,
+	path::{Path, PathBuf},
+	process::Command,
+	rc::Rc,
+	sync::{Arc, Mutex},
+};
+
+const AUTOMATION_FILE: &str = "automation.rhai";
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// keeps an endless loop in a script from freezing the ui
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// what happened in the repo
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trigger {
+	/// a commit was created (or amended, reworded…) in gitui
+	PostCommit,
+	/// `HEAD` moved to another branch, inside gitui or outside
+	BranchSwitch,
+}
+
+impl Trigger {
+	/// of the script function handling it
+	const fn function(self) -> &'static str {
+		match self {
+			Self::PostCommit => "post_commit",
+			Self::BranchSwitch => "branch_switch",
+		}
+	}
+}
+
+/// a single thing a script asked for
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Step {
+	/// pushes the current branch
+	Push,
+	/// fetches all remotes
+	Fetch,
+	/// opens the url in the browser
+	OpenUrl(String),
+	/// runs the command with `sh -c` (`cmd /C` on windows) in the
+	/// workdir
+	Shell(String),
+}
+
+#[derive(Default)]
+pub struct Automation {
+	script: Option<AST>,
+}
+
+impl Automation {
+	/// compiles `automation.rhai`, nothing happens automatically
+	/// without one
+	pub fn init() -> Self {
+		let Ok(path) = get_app_config_path() else {
+			return Self::default();
+		};
+		let path = path.join(AUTOMATION_FILE);
+
+		if !path.exists() {
+			return Self::default();
+		}
+
+		Self::read_file(&path).unwrap_or_else(|e| {
+			log::error!("automation loading error: {e}");
+			Self::default()
+		})
+	}
+
+	fn read_file(path: &Path) -> Result<Self> {
+		let script = Engine::new()
+			.compile(fs::read_to_string(path)?)
+			.map_err(|e| anyhow!("{}: {e}", path.display()))?;
+
+		Ok(Self {
+			script: Some(script),
+		})
+	}
+
+	/// runs the function of `trigger`, returns the steps it queued
+	pub fn steps(
+		&self,
+		trigger: Trigger,
+		repo: &RepoPath,
+		branch: &str,
+	) -> Result<Vec<Step>> {
+		let Some(script) = &self.script else {
+			return Ok(Vec::new());
+		};
+
+		let name = trigger.function();
+		if !script
+			.iter_functions()
+			.any(|f| f.name == name && f.params.is_empty())
+		{
+			return Ok(Vec::new());
+		}
+
+		run(repo, branch, |engine| {
+			engine
+				.call_fn::<Dynamic>(
+					&mut Scope::new(),
+					script,
+					name,
+					(),
+				)
+				.map(|_| ())
+		})
+	}
+}
+
+/// runs `script`, e.g. `push()` sent through the control socket,
+/// returns the steps it queued
+pub fn eval(
+	script: &str,
+	repo: &RepoPath,
+	branch: &str,
+) -> Result<Vec<Step>> {
+	run(repo, branch, |engine| engine.run(script))
+}
+
+fn run(
+	repo: &RepoPath,
+	branch: &str,
+	f: impl FnOnce(&Engine) -> ScriptResult<()>,
+) -> Result<Vec<Step>> {
+	let steps = Rc::new(RefCell::new(Vec::new()));
+
+	f(&engine(repo, branch, &steps))
+		.map_err(|e| anyhow!("script error: {e}"))?;
+
+	Ok(steps.take())
+}
+
+/// the functions scripts can call
+fn engine(
+	repo: &RepoPath,
+	branch: &str,
+	steps: &Rc<RefCell<Vec<Step>>>,
+) -> Engine {
+	let mut engine = Engine::new();
+	engine.set_max_operations(MAX_OPERATIONS);
+
+	let queue = |step: Step| {
+		let steps = Rc::clone(steps);
+		move || steps.borrow_mut().push(step.clone())
+	};
+	engine.register_fn("push", queue(Step::Push));
+	engine.register_fn("fetch", queue(Step::Fetch));
+
+	let queue_with = |step: fn(String) -> Step| {
+		let steps = Rc::clone(steps);
+		move |arg: &str| steps.borrow_mut().push(step(arg.into()))
+	};
+	engine.register_fn("open_url", queue_with(Step::OpenUrl));
+	engine.register_fn("shell", queue_with(Step::Shell));
+
+	let branch = branch.to_string();
+	engine.register_fn("branch", move || branch.clone());
+
+	let repo_commit = repo.clone();
+	engine.register_fn("commit", move || -> ScriptResult<String> {
+		sync::get_head(&repo_commit)
+			.map(|id| id.to_string())
+			.map_err(|e| e.to_string().into())
+	});
+
+	let repo_base = repo.clone();
+	engine.register_fn(
+		"base_url",
+		move || -> ScriptResult<String> {
+			forge::web_base(&repo_base)
+				.map_err(|e| e.to_string().into())
+		},
+	);
+
+	let repo_changes = repo.clone();
+	engine.register_fn("changes", move || -> ScriptResult<i64> {
+		sync::status::get_status(
+			&repo_changes,
+			StatusType::WorkingDir,
+			None,
+		)
+		.map(|items| i64::try_from(items.len()).unwrap_or(i64::MAX))
+		.map_err(|e| e.to_string().into())
+	});
+
+	engine
+}
+
+/// runs a `Step::Shell` in the background, the ui gets told through
+/// `AsyncAppNotification::Automation`
+#[derive(Clone)]
+pub struct AsyncShellJob {
+	command: String,
+	repo: RepoPath,
+	result: Arc<Mutex<Option<Result<()>>>>,
+}
+
+impl AsyncShellJob {
+	///
+	pub fn new(command: String, repo: RepoPath) -> Self {
+		Self {
+			command,
+			repo,
+			result: Arc::default(),
+		}
+	}
+
+	///
+	pub fn result(&self) -> Option<Result<()>> {
+		self.result.lock().ok().and_then(|mut r| r.take())
+	}
+}
+
+impl AsyncJob for AsyncShellJob {
+	type Notification = AsyncAppNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> asyncgit::Result<Self::Notification> {
+		let res = sync::utils::repo_work_dir(&self.repo)
+			.map_err(anyhow::Error::from)
+			.and_then(|dir| {
+				run_in(&self.command, &PathBuf::from(dir))
+			});
+
+		if let Ok(mut result) = self.result.lock() {
+			*result = Some(res);
+		}
+
+		Ok(AsyncAppNotification::Automation)
+	}
+}
+
+/// runs `command` in `dir`, failing with its output if it does not
+/// exit successfully
+fn run_in(command: &str, dir: &Path) -> Result<()> {
+	let mut cmd = if cfg!(windows) {
+		let mut cmd = Command::new("cmd");
+		cmd.arg("/C");
+		cmd
+	} else {
+		let mut cmd = Command::new("sh");
+		cmd.arg("-c");
+		cmd
+	};
+
+	let out = cmd
+		.arg(command)
+		.current_dir(dir)
+		.output()
+		.map_err(|e| anyhow!("`{command}`: {e}"))?;
+
+	if out.status.success() {
+		Ok(())
+	} else {
+		let msg = if out.stderr.is_empty() {
+			String::from_utf8_lossy(&out.stdout)
+		} else {
+			String::from_utf8_lossy(&out.stderr)
+		};
+		Err(anyhow!("`{command}` failed ({}):\n{msg}", out.status))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{eval, run_in, Automation, Step, Trigger};
+	use asyncgit::sync::RepoPath;
+	use pretty_assertions::assert_eq;
+	use rhai::Engine;
+	use tempfile::TempDir;
+
+	fn automation(script: &str) -> Automation {
+		Automation {
+			script: Some(Engine::new().compile(script).unwrap()),
+		}
+	}
+
+	#[test]
+	fn test_steps() {
+		let td = TempDir::new().unwrap();
+		let repo: RepoPath = td.path().to_str().unwrap().into();
+
+		let automation = automation(
+			r#"
+			fn post_commit() {
+				push();
+				open_url(`https://example.com/${branch()}`);
+			}
+			"#,
+		);
+
+		assert_eq!(
+			automation
+				.steps(Trigger::PostCommit, &repo, "main")
+				.unwrap(),
+			vec![
+				Step::Push,
+				Step::OpenUrl("https://example.com/main".into())
+			]
+		);
+		assert!(automation
+			.steps(Trigger::BranchSwitch, &repo, "main")
+			.unwrap()
+			.is_empty());
+	}
+
+	#[test]
+	fn test_eval() {
+		let td = TempDir::new().unwrap();
+		let repo: RepoPath = td.path().to_str().unwrap().into();
+
+		assert_eq!(
+			eval(r#"fetch(); shell("make")"#, &repo, "main").unwrap(),
+			vec![Step::Fetch, Step::Shell("make".into())]
+		);
+		// not a repo, so neither a head nor a remote
+		assert!(eval("commit()", &repo, "main").is_err());
+		assert!(eval("base_url()", &repo, "main").is_err());
+		assert!(eval("loop {}", &repo, "main").is_err());
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_run_in() {
+		let td = TempDir::new().unwrap();
+
+		run_in("touch created", td.path()).unwrap();
+		assert!(td.path().join("created").exists());
+
+		let err = run_in("echo oops >&2; exit 3", td.path())
+			.unwrap_err()
+			.to_string();
+		assert!(err.contains("oops"));
+	}
+}
@@ -1,9 +1,47 @@
 use anyhow::{anyhow, Result};
+use std::env;
 use std::io::Write;
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
 use which::which;
 
+static FORCE_OSC52: AtomicBool = AtomicBool::new(false);
+
+/// always copy through the terminal using OSC 52 instead of a
+/// clipboard tool
+pub fn set_osc52(enabled: bool) {
+	FORCE_OSC52.store(enabled, Ordering::Relaxed);
+}
+
+/// the clipboard tools would copy on the remote machine when running
+/// over ssh without a forwarded display
+fn is_remote_session() -> bool {
+	let ssh = env::var_os("SSH_TTY").is_some()
+		|| env::var_os("SSH_CONNECTION").is_some();
+
+	ssh && env::var_os("DISPLAY").is_none()
+		&& env::var_os("WAYLAND_DISPLAY").is_none()
+}
+
+fn in_tmux() -> bool {
+	env::var_os("TMUX").is_some()
+}
+
+/// copies `text` into the clipboard, through the terminal if forced or
+/// in a remote session
+pub fn copy_string(text: &str) -> Result<()> {
+	if FORCE_OSC52.load(Ordering::Relaxed) || is_remote_session() {
+		return copy_string_osc52(
+			text,
+			&mut std::io::stdout(),
+			in_tmux(),
+		);
+	}
+
+	copy_string_native(text)
+}
+
 fn exec_copy_with_args(
 	command: &str,
 	args: &[&str],
@@ -67,19 +105,30 @@ fn is_wsl() -> bool {
 // This enables copying even if there is no Wayland or X socket available,
 // e.g. via SSH, as long as it supported by the terminal.
 // See https://invisible-island.net/xterm/ctlseqs/ctlseqs.html#h3-Operating-System-Commands
-#[cfg(any(
-	all(target_family = "unix", not(target_os = "macos")),
-	test
-))]
-fn copy_string_osc52(text: &str, out: &mut impl Write) -> Result<()> {
+// Inside tmux the sequence is sent a second time wrapped for
+// passthrough, so it reaches the outer terminal with either
+// `set-clipboard on` or `allow-passthrough on`.
+fn copy_string_osc52(
+	text: &str,
+	out: &mut impl Write,
+	tmux: bool,
+) -> Result<()> {
 	use base64::prelude::{Engine, BASE64_STANDARD};
 	const OSC52_DESTINATION_CLIPBOARD: char = 'c';
-	write!(
-		out,
+	let sequence = format!(
 		"\x1b]52;{destination};{encoded_text}\x07",
 		destination = OSC52_DESTINATION_CLIPBOARD,
 		encoded_text = BASE64_STANDARD.encode(text)
-	)?;
+	);
+	out.write_all(sequence.as_bytes())?;
+	if tmux {
+		write!(
+			out,
+			"\x1bPtmux;{}\x1b\\",
+			sequence.replace('\x1b', "\x1b\x1b")
+		)?;
+	}
+	out.flush()?;
 	Ok(())
 }
 
@@ -89,7 +138,7 @@ fn copy_string_wayland(text: &str) -> Result<()> {
 		return Ok(());
 	}
 
-	copy_string_osc52(text, &mut std::io::stdout())
+	copy_string_osc52(text, &mut std::io::stdout(), in_tmux())
 }
 
 #[cfg(all(target_family = "unix", not(target_os = "macos")))]
@@ -111,11 +160,11 @@ fn copy_string_x(text: &str) -> Result<()> {
 		return Ok(());
 	}
 
-	copy_string_osc52(text, &mut std::io::stdout())
+	copy_string_osc52(text, &mut std::io::stdout(), in_tmux())
 }
 
 #[cfg(all(target_family = "unix", not(target_os = "macos")))]
-pub fn copy_string(text: &str) -> Result<()> {
+fn copy_string_native(text: &str) -> Result<()> {
 	if std::env::var("WAYLAND_DISPLAY").is_ok() {
 		return copy_string_wayland(text);
 	}
@@ -133,12 +182,12 @@ fn exec_copy(command: &str, text: &str) -> Result<()> {
 }
 
 #[cfg(target_os = "macos")]
-pub fn copy_string(text: &str) -> Result<()> {
+fn copy_string_native(text: &str) -> Result<()> {
 	exec_copy("pbcopy", text)
 }
 
 #[cfg(windows)]
-pub fn copy_string(text: &str) -> Result<()> {
+fn copy_string_native(text: &str) -> Result<()> {
 	exec_copy("clip", text)
 }
 
@@ -149,9 +198,21 @@ mod tests {
 		let mut buffer = Vec::<u8>::new();
 		{
 			let mut cursor = std::io::Cursor::new(&mut buffer);
-			super::copy_string_osc52("foo", &mut cursor).unwrap();
+			super::copy_string_osc52("foo", &mut cursor, false)
+				.unwrap();
 		}
 		let output = String::from_utf8(buffer).unwrap();
 		assert_eq!(output, "\x1b]52;c;Zm9v\x07");
 	}
+
+	#[test]
+	fn test_copy_string_osc52_tmux() {
+		let mut buffer = Vec::<u8>::new();
+		super::copy_string_osc52("foo", &mut buffer, true).unwrap();
+		let output = String::from_utf8(buffer).unwrap();
+		assert_eq!(
+			output,
+			"\x1b]52;c;Zm9v\x07\x1bPtmux;\x1b\x1b]52;c;Zm9v\x07\x1b\\"
+		);
+	}
 }
@@ -0,0 +1,307 @@
+//! checks the commit message while it is typed, configured per repo
+//! in `gitui.commitLint`: `conventional` for the built-in
+//! conventional commit rules or a command that gets the message on
+//! stdin and fails with the reason on stdout or stderr
+
+use crate::AsyncAppNotification;
+use asyncgit::{
+	asyncjob::{AsyncJob, AsyncSingleJob, RunParams},
+	sync::{get_config_string, utils::repo_work_dir, RepoPath},
+};
+use crossbeam_channel::Sender;
+use std::{
+	io::Write,
+	path::{Path, PathBuf},
+	process::{Command, Stdio},
+	sync::{Arc, Mutex},
+};
+
+const COMMIT_LINT_CONFIG: &str = "gitui.commitLint";
+
+const CONVENTIONAL_TYPES: &[&str] = &[
+	"build", "chore", "ci", "docs", "feat", "fix", "perf",
+	"refactor", "revert", "style", "test",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Linter {
+	Conventional,
+	Command(String),
+}
+
+impl Linter {
+	fn from_config(repo: &RepoPath) -> Option<Self> {
+		let value = get_config_string(repo, COMMIT_LINT_CONFIG)
+			.map_err(|e| log::error!("commit lint config: {e}"))
+			.ok()
+			.flatten()?;
+
+		match value.trim() {
+			"" => None,
+			"conventional" => Some(Self::Conventional),
+			command => Some(Self::Command(command.to_string())),
+		}
+	}
+}
+
+/// first problem of `msg` according to the conventional commit
+/// rules, lines starting with `#` are ignored
+pub fn conventional(msg: &str) -> Option<String> {
+	let mut lines = msg.lines().filter(|line| !line.starts_with('#'));
+	let header = lines.next().unwrap_or_default();
+
+	let Some((prefix, subject)) = header.split_once(':') else {
+		return Some(String::from("missing `type: subject`"));
+	};
+
+	let prefix = prefix.strip_suffix('!').unwrap_or(prefix);
+	let kind = match prefix.split_once('(') {
+		Some((kind, scope)) => {
+			if !scope.ends_with(')') || scope.len() < 2 {
+				return Some(String::from("invalid `(scope)`"));
+			}
+			kind
+		}
+		None => prefix,
+	};
+
+	if !CONVENTIONAL_TYPES.contains(&kind) {
+		return Some(format!("unknown type `{kind}`"));
+	}
+
+	let subject = subject.strip_prefix(' ').unwrap_or(subject);
+	if subject.trim().is_empty() {
+		return Some(String::from("empty subject"));
+	}
+	if subject.ends_with('.') {
+		return Some(String::from("subject ends with `.`"));
+	}
+
+	if lines.next().is_some_and(|line| !line.trim().is_empty()) {
+		return Some(String::from("no blank line after the header"));
+	}
+
+	None
+}
+
+/// runs `command` with `msg` on stdin, `None` if it succeeds
+fn run_command(
+	command: &str,
+	msg: &str,
+	dir: &Path,
+) -> Option<String> {
+	let mut cmd = if cfg!(windows) {
+		let mut cmd = Command::new("cmd");
+		cmd.arg("/C");
+		cmd
+	} else {
+		let mut cmd = Command::new("sh");
+		cmd.arg("-c");
+		cmd
+	};
+
+	let child = cmd
+		.arg(command)
+		.current_dir(dir)
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn();
+	let mut child = match child {
+		Ok(child) => child,
+		Err(e) => return Some(format!("`{command}`: {e}")),
+	};
+
+	if let Some(mut stdin) = child.stdin.take() {
+		// a linter not reading its input is fine
+		let _ = stdin.write_all(msg.as_bytes());
+	}
+
+	let out = match child.wait_with_output() {
+		Ok(out) => out,
+		Err(e) => return Some(format!("`{command}`: {e}")),
+	};
+
+	if out.status.success() {
+		return None;
+	}
+
+	let text = String::from_utf8_lossy(&out.stdout).into_owned()
+		+ String::from_utf8_lossy(&out.stderr).as_ref();
+
+	Some(
+		text.lines()
+			.map(str::trim)
+			.find(|line| !line.is_empty())
+			.map_or_else(
+				|| format!("lint failed ({})", out.status),
+				String::from,
+			),
+	)
+}
+
+#[derive(Clone)]
+struct AsyncLintJob {
+	command: String,
+	msg: String,
+	dir: PathBuf,
+	warning: Arc<Mutex<Option<String>>>,
+}
+
+impl AsyncJob for AsyncLintJob {
+	type Notification = AsyncAppNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> asyncgit::Result<Self::Notification> {
+		let warning =
+			run_command(&self.command, &self.msg, &self.dir);
+
+		if let Ok(mut result) = self.warning.lock() {
+			*result = warning;
+		}
+
+		Ok(AsyncAppNotification::CommitLint)
+	}
+}
+
+/// lints the message of the commit popup, external commands run in
+/// the background and only for the latest message
+pub struct CommitLint {
+	linter: Option<Linter>,
+	dir: PathBuf,
+	job: AsyncSingleJob<AsyncLintJob>,
+	linted: Option<String>,
+	warning: Option<String>,
+}
+
+impl CommitLint {
+	///
+	pub fn new(sender: &Sender<AsyncAppNotification>) -> Self {
+		Self {
+			linter: None,
+			dir: PathBuf::new(),
+			job: AsyncSingleJob::new(sender.clone()),
+			linted: None,
+			warning: None,
+		}
+	}
+
+	/// reads the config again, called whenever the popup opens
+	pub fn reset(&mut self, repo: &RepoPath) {
+		self.linter = Linter::from_config(repo);
+		self.dir = repo_work_dir(repo).unwrap_or_default().into();
+		self.linted = None;
+		self.warning = None;
+		self.job.cancel();
+	}
+
+	///
+	pub fn check(&mut self, msg: &str) {
+		if self.linted.as_deref() == Some(msg) {
+			return;
+		}
+		self.linted = Some(msg.to_string());
+
+		match &self.linter {
+			None => (),
+			// an empty message is no mistake yet
+			Some(_) if msg.trim().is_empty() => {
+				self.job.cancel();
+				self.warning = None;
+			}
+			Some(Linter::Conventional) => {
+				self.warning = conventional(msg);
+			}
+			Some(Linter::Command(command)) => {
+				self.job.spawn(AsyncLintJob {
+					command: command.clone(),
+					msg: msg.to_string(),
+					dir: self.dir.clone(),
+					warning: Arc::default(),
+				});
+			}
+		}
+	}
+
+	/// takes the result of a finished command unless the message
+	/// changed since
+	pub fn update(&mut self) {
+		if let Some(job) = self
+			.job
+			.take_last()
+			.filter(|job| self.linted.as_ref() == Some(&job.msg))
+		{
+			self.warning =
+				job.warning.lock().ok().and_then(|mut w| w.take());
+		}
+	}
+
+	///
+	pub fn warning(&self) -> Option<&str> {
+		self.warning.as_deref()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{conventional, run_command};
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_conventional() {
+		assert_eq!(conventional("feat: add flow popup"), None);
+		assert_eq!(conventional("fix(diff)!: wrap lines"), None);
+		assert_eq!(
+			conventional("# comment\nchore: bump\n\nbody"),
+			None
+		);
+
+		assert_eq!(
+			conventional("add flow popup").as_deref(),
+			Some("missing `type: subject`")
+		);
+		assert_eq!(
+			conventional("feature: x").as_deref(),
+			Some("unknown type `feature`")
+		);
+		assert_eq!(
+			conventional("fix(: x").as_deref(),
+			Some("invalid `(scope)`")
+		);
+		assert_eq!(
+			conventional("fix: ").as_deref(),
+			Some("empty subject")
+		);
+		assert_eq!(
+			conventional("fix: done.").as_deref(),
+			Some("subject ends with `.`")
+		);
+		assert_eq!(
+			conventional("fix: x\nbody").as_deref(),
+			Some("no blank line after the header")
+		);
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_run_command() {
+		let dir = std::env::temp_dir();
+
+		assert_eq!(run_command("grep -q ok", "ok", &dir), None);
+		assert_eq!(
+			run_command(
+				"echo; echo 'too short' >&2; exit 1",
+				"",
+				&dir
+			)
+			.as_deref(),
+			Some("too short")
+		);
+		assert!(run_command("exit 2", "", &dir)
+			.unwrap()
+			.starts_with("lint failed"));
+	}
+}
@@ -0,0 +1,131 @@
+//! commit message templates to pick from in the commit popup:
+//! `commit.template` followed by every `gitui.commitTemplate`, each
+//! the path of a template file (relative to the workdir unless
+//! absolute).
+//!
+//! `{branch}` and `{ticket}` in the `gitui.commitTemplate` files are
+//! replaced by the branch name and the ticket id found in it (see
+//! `tickets`).
+//! templates for some branches or directories only are set up with
+//! git's conditional includes (`[includeIf "onbranch:release/*"]`)
+
+use crate::tickets::Tickets;
+use asyncgit::sync::{
+	get_config_string, get_config_strings, utils::repo_work_dir,
+	RepoPath,
+};
+use std::{
+	fs::read_to_string,
+	path::{Path, PathBuf},
+};
+
+const TEMPLATE_CONFIG: &str = "gitui.commitTemplate";
+
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitTemplate {
+	/// file name of the template
+	pub name: String,
+	pub text: String,
+}
+
+impl CommitTemplate {
+	fn read(path: &str, workdir: &Path) -> Option<Self> {
+		let path = shellexpand::full(path)
+			.map_err(|e| log::error!("commit template path: {e}"))
+			.ok()?;
+		let path = workdir.join(PathBuf::from(path.as_ref()));
+
+		let text = read_to_string(&path)
+			.map_err(|e| {
+				log::error!(
+					"read commit template failed: {e} (path: '{path:?}')"
+				);
+			})
+			.ok()?;
+
+		Some(Self {
+			name: path.file_name().map_or_else(
+				|| path.to_string_lossy().to_string(),
+				|name| name.to_string_lossy().to_string(),
+			),
+			text,
+		})
+	}
+}
+
+/// the templates configured for `repo`, placeholders filled in for
+/// `branch` and with the `gitui.ticketPrefix` of its ticket id (the
+/// prefix alone if there are no templates)
+pub fn load(
+	repo: &RepoPath,
+	branch: Option<&str>,
+) -> Vec<CommitTemplate> {
+	let Ok(workdir) = repo_work_dir(repo) else {
+		return Vec::new();
+	};
+	let workdir = Path::new(&workdir);
+
+	let branch = branch.unwrap_or_default();
+	let tickets = Tickets::load(repo);
+	let ticket = tickets
+		.as_ref()
+		.and_then(|tickets| tickets.find(branch))
+		.unwrap_or_default();
+
+	let mut templates = get_config_string(repo, "commit.template")
+		.map_err(|e| log::error!("load git-config failed: {e}"))
+		.ok()
+		.flatten()
+		.and_then(|path| CommitTemplate::read(&path, workdir))
+		.into_iter()
+		.collect::<Vec<_>>();
+
+	let paths = get_config_strings(repo, TEMPLATE_CONFIG)
+		.map_err(|e| log::error!("load git-config failed: {e}"))
+		.unwrap_or_default();
+
+	templates.extend(paths.iter().filter_map(|path| {
+		let mut template = CommitTemplate::read(path, workdir)?;
+		template.text = render(&template.text, branch, &ticket);
+		Some(template)
+	}));
+
+	if let (Some(tickets), false) = (tickets, ticket.is_empty()) {
+		if templates.is_empty() {
+			templates.push(CommitTemplate {
+				name: ticket.clone(),
+				text: String::new(),
+			});
+		}
+
+		for template in &mut templates {
+			template.text = tickets.prefixed(&template.text, &ticket);
+		}
+
+		templates.retain(|template| !template.text.is_empty());
+	}
+
+	templates
+}
+
+#[allow(clippy::literal_string_with_formatting_args)]
+fn render(template: &str, branch: &str, ticket: &str) -> String {
+	template
+		.replace("{branch}", branch)
+		.replace("{ticket}", ticket)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::render;
+
+	#[test]
+	fn test_render() {
+		assert_eq!(
+			render("[{ticket}] \n\nbranch: {branch}", "a/X-1", "X-1"),
+			"[X-1] \n\nbranch: a/X-1"
+		);
+		assert_eq!(render("{ticket}: ", "master", ""), ": ");
+	}
+}
@@ -0,0 +1,171 @@
+//! Tab completion for text inputs, the candidates are looked up in
+//! the background when the input opens
+
+use crate::AsyncAppNotification;
+use asyncgit::{
+	asyncjob::{AsyncJob, AsyncSingleJob, RunParams},
+	sync::{get_branches_info, get_remotes, RepoPath, RepoPathRef},
+};
+use crossbeam_channel::Sender;
+use std::sync::{Arc, Mutex};
+
+/// what an input gets completed with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompletionSource {
+	/// local and remote branch names
+	Branches,
+	/// remote names
+	Remotes,
+}
+
+impl CompletionSource {
+	fn candidates(self, repo: &RepoPath) -> Vec<String> {
+		let res = match self {
+			Self::Branches => {
+				get_branches_info(repo, true).and_then(|local| {
+					let remote = get_branches_info(repo, false)?;
+					Ok(local
+						.into_iter()
+						.chain(remote)
+						.map(|branch| branch.name)
+						.collect())
+				})
+			}
+			Self::Remotes => get_remotes(repo),
+		};
+
+		let mut candidates = res
+			.map_err(|e| log::error!("completion lookup: {e}"))
+			.unwrap_or_default();
+		candidates.sort();
+		candidates.dedup();
+		candidates
+	}
+}
+
+#[derive(Clone)]
+struct AsyncCompletionJob {
+	repo: RepoPath,
+	source: CompletionSource,
+	candidates: Arc<Mutex<Option<Vec<String>>>>,
+}
+
+impl AsyncJob for AsyncCompletionJob {
+	type Notification = AsyncAppNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> asyncgit::Result<Self::Notification> {
+		let candidates = self.source.candidates(&self.repo);
+
+		if let Ok(mut result) = self.candidates.lock() {
+			*result = Some(candidates);
+		}
+
+		Ok(AsyncAppNotification::Completion)
+	}
+}
+
+/// cycles through the candidates starting with the typed text
+pub struct Completion {
+	repo: RepoPathRef,
+	source: CompletionSource,
+	job: AsyncSingleJob<AsyncCompletionJob>,
+	candidates: Vec<String>,
+	matches: Vec<String>,
+	selection: Option<usize>,
+}
+
+impl Completion {
+	///
+	pub fn new(
+		repo: RepoPathRef,
+		sender: &Sender<AsyncAppNotification>,
+		source: CompletionSource,
+	) -> Self {
+		Self {
+			repo,
+			source,
+			job: AsyncSingleJob::new(sender.clone()),
+			candidates: Vec::new(),
+			matches: Vec::new(),
+			selection: None,
+		}
+	}
+
+	/// looks up the candidates again
+	pub fn load(&mut self) {
+		self.reset();
+		self.job.spawn(AsyncCompletionJob {
+			repo: self.repo.borrow().clone(),
+			source: self.source,
+			candidates: Arc::default(),
+		});
+	}
+
+	/// stops cycling, the next cycle matches the text again
+	pub fn reset(&mut self) {
+		self.matches.clear();
+		self.selection = None;
+	}
+
+	fn fetch_candidates(&mut self) {
+		if let Some(candidates) = self
+			.job
+			.take_last()
+			.and_then(|job| job.candidates.lock().ok()?.take())
+		{
+			self.candidates = candidates;
+		}
+	}
+
+	/// the next (or previous) candidate for `text`
+	pub fn cycle(
+		&mut self,
+		text: &str,
+		reverse: bool,
+	) -> Option<&str> {
+		self.fetch_candidates();
+
+		let cycling = self
+			.selection
+			.and_then(|idx| self.matches.get(idx))
+			.is_some_and(|current| current == text);
+
+		if cycling {
+			let count = self.matches.len();
+			self.selection = self.selection.map(|idx| {
+				if reverse {
+					idx.checked_sub(1).unwrap_or(count - 1)
+				} else {
+					(idx + 1) % count
+				}
+			});
+		} else {
+			self.matches = self
+				.candidates
+				.iter()
+				.filter(|candidate| candidate.starts_with(text))
+				.cloned()
+				.collect();
+			self.selection = if self.matches.is_empty() {
+				None
+			} else if reverse {
+				Some(self.matches.len() - 1)
+			} else {
+				Some(0)
+			};
+		}
+
+		self.selection
+			.and_then(|idx| self.matches.get(idx))
+			.map(String::as_str)
+	}
+
+	/// the candidates currently cycled through and the selected one
+	pub fn matches(&self) -> Option<(&[String], usize)> {
+		self.selection.map(|idx| (self.matches.as_slice(), idx))
+	}
+}
@@ -8,15 +8,18 @@ use crate::{
 	components::{CommandInfo, Component, EventState},
 	keys::{key_match, SharedKeyConfig},
 	options::SharedOptions,
+	popups::IgnorePatternContext,
 	queue::{Action, InternalEvent, NeedsUpdate, Queue, ResetItem},
-	strings, try_or_popup,
+	strings,
+	tabs::StashingOptions,
+	try_or_popup,
 };
 use anyhow::Result;
 use asyncgit::{
-	sync::{self, RepoPathRef},
+	sync::{self, LargeFile, RepoPathRef},
 	StatusItem, StatusItemType,
 };
-use crossterm::event::Event;
+use crossterm::event::{Event, MouseEvent};
 use ratatui::{layout::Rect, Frame};
 use std::path::Path;
 
@@ -71,15 +74,50 @@ impl ChangesComponent {
 		self.files.is_empty()
 	}
 
+	/// selects the next (or previous) file matching `filter`
+	pub fn select_file(
+		&mut self,
+		forward: bool,
+		filter: impl Fn(&StatusItem) -> bool,
+	) -> bool {
+		self.files.select_file(forward, filter)
+	}
+
 	///
 	pub fn is_file_selected(&self) -> bool {
 		self.files.is_file_selected()
 	}
 
-	fn index_add_remove(&self) -> Result<bool> {
+	/// whether a mouse event happened on the list
+	pub fn contains_mouse(&self, ev: MouseEvent) -> bool {
+		self.files.contains_mouse(ev)
+	}
+
+	/// path of the selected entry if it is ignored
+	fn selected_ignored(&self) -> Option<String> {
+		self.files
+			.selection_file()
+			.filter(|item| item.status == StatusItemType::Ignored)
+			.map(|item| item.path)
+	}
+
+	fn index_add_remove(&mut self) -> Result<bool> {
+		let marked = self.files.marked_files();
+		if !marked.is_empty() {
+			self.index_add_remove_marked(&marked)?;
+			return Ok(true);
+		}
+
 		if let Some(tree_item) = self.selection() {
 			if self.is_working_dir {
-				if let FileTreeItemKind::File(i) = tree_item.kind {
+				if let Some(path) = self.selected_ignored() {
+					sync::stage_add_ignored(
+						&self.repo.borrow(),
+						&path,
+					)?;
+				} else if let FileTreeItemKind::File(i) =
+					tree_item.kind
+				{
 					let path = Path::new(i.path.as_str());
 					match i.status {
 						StatusItemType::Deleted => {
@@ -88,20 +126,18 @@ impl ChangesComponent {
 								path,
 							)?;
 						}
+						_ if self.warn_large_files(&i.path)? => {
+							return Ok(true);
+						}
 						_ => sync::stage_add_file(
 							&self.repo.borrow(),
 							path,
 						)?,
 					}
 				} else {
-					let config =
-						self.options.borrow().status_show_untracked();
-
 					//TODO: check if we can handle the one file case with it as well
-					sync::stage_add_all(
-						&self.repo.borrow(),
+					self.stage_add_all(
 						tree_item.info.full_path.as_str(),
-						config,
 					)?;
 				}
 
@@ -128,16 +164,105 @@ impl ChangesComponent {
 		Ok(false)
 	}
 
-	fn index_add_all(&self) -> Result<()> {
-		let config = self.options.borrow().status_show_untracked();
+	/// stages or unstages all `marked` files in one go
+	fn index_add_remove_marked(
+		&mut self,
+		marked: &[StatusItem],
+	) -> Result<()> {
+		let mut large_files = Vec::new();
+
+		for item in marked {
+			let path = Path::new(item.path.as_str());
+
+			if !self.is_working_dir {
+				sync::reset_stage(&self.repo.borrow(), &item.path)?;
+			} else if item.status == StatusItemType::Deleted {
+				sync::stage_addremoved(&self.repo.borrow(), path)?;
+			} else if item.status == StatusItemType::Ignored {
+				sync::stage_add_ignored(
+					&self.repo.borrow(),
+					&item.path,
+				)?;
+			} else {
+				let large = self.large_files(&item.path)?;
+				if large.is_empty() {
+					sync::stage_add_file(&self.repo.borrow(), path)?;
+				}
+				large_files.extend(large);
+			}
+		}
+
+		if !large_files.is_empty() {
+			self.queue
+				.push(InternalEvent::OpenLargeFiles(large_files));
+		}
 
-		sync::stage_add_all(&self.repo.borrow(), "*", config)?;
+		self.files.clear_marks();
+
+		if self.is_working_dir
+			&& sync::is_workdir_clean(
+				&self.repo.borrow(),
+				self.options.borrow().status_show_untracked(),
+			)? {
+			self.queue.push(InternalEvent::StatusLastFileMoved);
+		}
+
+		Ok(())
+	}
+
+	fn index_add_all(&self) -> Result<()> {
+		self.stage_add_all("*")?;
 
 		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
 
 		Ok(())
 	}
 
+	fn large_files(&self, pattern: &str) -> Result<Vec<LargeFile>> {
+		Ok(sync::large_files(
+			&self.repo.borrow(),
+			pattern,
+			self.options.borrow().status_show_untracked(),
+		)?)
+	}
+
+	/// `true` if the files of `pattern` were left unstaged for the
+	/// user to decide as some are large
+	fn warn_large_files(&self, pattern: &str) -> Result<bool> {
+		let large_files = self.large_files(pattern)?;
+		if large_files.is_empty() {
+			return Ok(false);
+		}
+
+		self.queue.push(InternalEvent::OpenLargeFiles(large_files));
+
+		Ok(true)
+	}
+
+	/// stages everything but large files, which are left for the
+	/// user to decide on
+	fn stage_add_all(&self, pattern: &str) -> Result<()> {
+		let large_files = self.large_files(pattern)?;
+		let config = self.options.borrow().status_show_untracked();
+
+		sync::stage_add_all_except(
+			&self.repo.borrow(),
+			pattern,
+			config,
+			&large_files
+				.iter()
+				.map(|file| file.path.clone())
+				.collect::<Vec<_>>(),
+		)?;
+
+		if !large_files.is_empty() {
+			self.queue
+				.push(InternalEvent::OpenLargeFiles(large_files));
+		}
+
+		Ok(())
+	}
+
 	fn stage_remove_all(&self) -> Result<()> {
 		sync::reset_stage(&self.repo.borrow(), "*")?;
 
@@ -147,6 +272,23 @@ impl ChangesComponent {
 	}
 
 	fn dispatch_reset_workdir(&self) -> bool {
+		let marked = self.files.marked_files();
+		if !marked.is_empty() {
+			self.queue.push(InternalEvent::ConfirmAction(
+				Action::ResetItems(
+					marked
+						.into_iter()
+						.filter(|item| {
+							item.status != StatusItemType::Ignored
+						})
+						.map(|item| item.path)
+						.collect(),
+				),
+			));
+
+			return true;
+		}
+
 		if let Some(tree_item) = self.selection() {
 			self.queue.push(InternalEvent::ConfirmAction(
 				Action::Reset(ResetItem {
@@ -159,24 +301,90 @@ impl ChangesComponent {
 		false
 	}
 
+	fn marked_paths(&self) -> Vec<String> {
+		self.files
+			.marked_files()
+			.into_iter()
+			.map(|item| item.path)
+			.collect()
+	}
+
+	/// stashes only the marked files or the selected file (folder)
+	/// if none are marked
+	fn stash_files(&self) -> bool {
+		let mut paths = self.marked_paths();
+		if paths.is_empty() {
+			paths = self.files.selected_files();
+		}
+
+		if paths.is_empty() {
+			return false;
+		}
+
+		self.queue.push(InternalEvent::PopupStashing(
+			StashingOptions {
+				stash_untracked: true,
+				keep_index: false,
+				paths,
+				untracked_only: false,
+			},
+		));
+
+		true
+	}
+
+	/// stashes the untracked files only, of the marked files if any
+	/// are marked
+	fn stash_untracked(&self) {
+		self.queue.push(InternalEvent::PopupStashing(
+			StashingOptions {
+				stash_untracked: true,
+				keep_index: false,
+				paths: self.marked_paths(),
+				untracked_only: true,
+			},
+		));
+	}
+
+	fn remove_from_ignore(&self, path: &str) -> bool {
+		if let Err(e) =
+			sync::remove_from_ignore(&self.repo.borrow(), path)
+		{
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"unignore error:\n{e}\nfile:\n{path:?}"
+			)));
+
+			return false;
+		}
+
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+
+		true
+	}
+
+	fn toggle_ignored(&self) {
+		self.options.borrow_mut().status_toggle_show_ignored();
+
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+	}
+
 	fn add_to_ignore(&self) -> bool {
+		if let Some(path) = self.selected_ignored() {
+			return self.remove_from_ignore(&path);
+		}
+
 		if let Some(tree_item) = self.selection() {
-			if let Err(e) = sync::add_to_ignore(
-				&self.repo.borrow(),
-				&tree_item.info.full_path,
-			) {
-				self.queue.push(InternalEvent::ShowErrorMsg(
-					format!(
-						"ignore error:\n{}\nfile:\n{:?}",
-						e, tree_item.info.full_path
+			self.queue.push(InternalEvent::OpenIgnorePatternPopup(
+				IgnorePatternContext {
+					path: tree_item.info.full_path,
+					is_folder: matches!(
+						tree_item.kind,
+						FileTreeItemKind::Path(_)
 					),
-				));
-			} else {
-				self.queue
-					.push(InternalEvent::Update(NeedsUpdate::ALL));
+				},
+			));
 
-				return true;
-			}
+			return true;
 		}
 
 		false
@@ -200,31 +408,87 @@ impl Component for ChangesComponent {
 		self.files.commands(out, force_all);
 
 		let some_selection = self.selection().is_some();
+		let marked = self.files.marked_count();
+
+		out.push(CommandInfo::new(
+			strings::commands::mark_file(&self.key_config),
+			true,
+			some_selection && self.focused(),
+		));
+		out.push(CommandInfo::new(
+			strings::commands::stash_files(&self.key_config, marked),
+			some_selection,
+			some_selection && self.focused(),
+		));
 
 		if self.is_working_dir {
+			let ignored = self.selected_ignored().is_some();
+
 			out.push(CommandInfo::new(
 				strings::commands::stage_all(&self.key_config),
 				true,
 				some_selection && self.focused(),
 			));
 			out.push(CommandInfo::new(
-				strings::commands::stage_item(&self.key_config),
+				strings::commands::stash_untracked(&self.key_config),
 				true,
 				some_selection && self.focused(),
 			));
 			out.push(CommandInfo::new(
-				strings::commands::reset_item(&self.key_config),
+				if marked > 0 {
+					strings::commands::stage_marked(
+						&self.key_config,
+						marked,
+					)
+				} else if ignored {
+					strings::commands::force_add_item(
+						&self.key_config,
+					)
+				} else {
+					strings::commands::stage_item(&self.key_config)
+				},
 				true,
 				some_selection && self.focused(),
 			));
 			out.push(CommandInfo::new(
-				strings::commands::ignore_item(&self.key_config),
+				if marked > 0 {
+					strings::commands::reset_marked(
+						&self.key_config,
+						marked,
+					)
+				} else {
+					strings::commands::reset_item(&self.key_config)
+				},
+				marked > 0 || !ignored,
+				some_selection && self.focused(),
+			));
+			out.push(CommandInfo::new(
+				if ignored {
+					strings::commands::unignore_item(&self.key_config)
+				} else {
+					strings::commands::ignore_item(&self.key_config)
+				},
 				true,
 				some_selection && self.focused(),
 			));
+			out.push(CommandInfo::new(
+				strings::commands::toggle_ignored(
+					&self.key_config,
+					self.options.borrow().status_show_ignored(),
+				),
+				true,
+				self.focused(),
+			));
 		} else {
 			out.push(CommandInfo::new(
-				strings::commands::unstage_item(&self.key_config),
+				if marked > 0 {
+					strings::commands::unstage_marked(
+						&self.key_config,
+						marked,
+					)
+				} else {
+					strings::commands::unstage_item(&self.key_config)
+				},
 				true,
 				some_selection && self.focused(),
 			));
@@ -276,10 +540,29 @@ impl Component for ChangesComponent {
 					self.queue
 						.push(InternalEvent::StatusLastFileMoved);
 					Ok(EventState::Consumed)
+				} else if key_match(
+					e,
+					self.key_config.keys.log_mark_commit,
+				) {
+					Ok(self.files.toggle_mark().into())
+				} else if key_match(
+					e,
+					self.key_config.keys.stashing_save,
+				) {
+					Ok(self.stash_files().into())
+				} else if key_match(
+					e,
+					self.key_config.keys.status_stash_untracked,
+				) && self.is_working_dir
+				{
+					self.stash_untracked();
+					Ok(EventState::Consumed)
 				} else if key_match(
 					e,
 					self.key_config.keys.status_reset_item,
 				) && self.is_working_dir
+					&& (self.files.marked_count() > 0
+						|| self.selected_ignored().is_none())
 				{
 					Ok(self.dispatch_reset_workdir().into())
 				} else if key_match(
@@ -289,6 +572,13 @@ impl Component for ChangesComponent {
 					&& !self.is_empty()
 				{
 					Ok(self.add_to_ignore().into())
+				} else if key_match(
+					e,
+					self.key_config.keys.status_toggle_ignored,
+				) && self.is_working_dir
+				{
+					self.toggle_ignored();
+					Ok(EventState::Consumed)
 				} else {
 					Ok(EventState::NotConsumed)
 				};
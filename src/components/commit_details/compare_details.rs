@@ -14,7 +14,8 @@ use crate::{
 };
 use anyhow::Result;
 use asyncgit::sync::{
-	self, commit_files::OldNew, CommitDetails, CommitId, RepoPathRef,
+	self, commit_files::OldNew, CommitDetails, CommitId, DirStat,
+	RepoPathRef,
 };
 use crossterm::event::Event;
 use ratatui::{
@@ -23,9 +24,14 @@ use ratatui::{
 	Frame,
 };
 
+/// most directories listed in the dirstat
+const DIRSTAT_MAX_LINES: usize = 5;
+
 pub struct CompareDetailsComponent {
 	repo: RepoPathRef,
+	ids: Option<OldNew<CommitId>>,
 	data: Option<OldNew<CommitDetails>>,
+	dirstat: Vec<DirStat>,
 	theme: SharedTheme,
 	focused: bool,
 }
@@ -34,7 +40,9 @@ impl CompareDetailsComponent {
 	///
 	pub fn new(env: &Environment, focused: bool) -> Self {
 		Self {
+			ids: None,
 			data: None,
+			dirstat: Vec::new(),
 			theme: env.theme.clone(),
 			focused,
 			repo: env.repo.clone(),
@@ -42,6 +50,19 @@ impl CompareDetailsComponent {
 	}
 
 	pub fn set_commits(&mut self, ids: Option<OldNew<CommitId>>) {
+		if ids == self.ids {
+			return;
+		}
+		self.ids = ids;
+
+		self.dirstat = ids
+			.and_then(|ids| {
+				sync::get_compare_dirstat(&self.repo.borrow(), ids)
+					.map_err(|e| log::error!("dirstat: {e}"))
+					.ok()
+			})
+			.unwrap_or_default();
+
 		self.data = ids.and_then(|ids| {
 			let old = sync::get_commit_details(
 				&self.repo.borrow(),
@@ -58,6 +79,36 @@ impl CompareDetailsComponent {
 		});
 	}
 
+	/// rows needed to draw the details
+	pub fn height(&self) -> u16 {
+		let dirstat = self.dirstat.len().min(DIRSTAT_MAX_LINES);
+
+		if dirstat == 0 {
+			10
+		} else {
+			12 + u16::try_from(dirstat).unwrap_or_default()
+		}
+	}
+
+	fn get_dirstat_text(&self) -> Vec<Line<'_>> {
+		self.dirstat
+			.iter()
+			.take(DIRSTAT_MAX_LINES)
+			.map(|stat| {
+				Line::from(vec![
+					Span::styled(
+						format!("{:>3}% ", stat.percent),
+						self.theme.text(false, false),
+					),
+					Span::styled(
+						Cow::from(stat.dir.as_str()),
+						self.theme.text(true, false),
+					),
+				])
+			})
+			.collect()
+	}
+
 	fn get_commit_text(&self, data: &CommitDetails) -> Vec<Line<'_>> {
 		let mut res = vec![
 			Line::from(vec![
@@ -104,8 +155,12 @@ impl DrawableComponent for CompareDetailsComponent {
 		let chunks = Layout::default()
 			.direction(Direction::Vertical)
 			.constraints(
-				[Constraint::Length(5), Constraint::Length(5)]
-					.as_ref(),
+				[
+					Constraint::Length(5),
+					Constraint::Length(5),
+					Constraint::Min(0),
+				]
+				.as_ref(),
 			)
 			.split(rect);
 
@@ -135,6 +190,18 @@ impl DrawableComponent for CompareDetailsComponent {
 				),
 				chunks[1],
 			);
+
+			if !self.dirstat.is_empty() {
+				f.render_widget(
+					dialog_paragraph(
+						strings::commit::COMPARE_DIRSTAT_TITLE,
+						Text::from(self.get_dirstat_text()),
+						&self.theme,
+						false,
+					),
+					chunks[2],
+				);
+			}
 		}
 
 		Ok(())
@@ -9,6 +9,7 @@ use crate::{
 	},
 	keys::{key_match, SharedKeyConfig},
 	strings::{self, order},
+	tickets::Tickets,
 	ui::style::SharedTheme,
 };
 use anyhow::Result;
@@ -31,6 +32,8 @@ pub struct DetailsComponent {
 	repo: RepoPathRef,
 	data: Option<CommitDetails>,
 	tags: Vec<Tag>,
+	/// to show the ticket ids in the message as links
+	tickets: Option<Tickets>,
 	theme: SharedTheme,
 	focused: bool,
 	current_width: Cell<u16>,
@@ -49,6 +52,7 @@ impl DetailsComponent {
 			repo: env.repo.clone(),
 			data: None,
 			tags: Vec::new(),
+			tickets: None,
 			theme: env.theme.clone(),
 			focused,
 			scroll_to_bottom_next_draw: Cell::new(false),
@@ -68,6 +72,8 @@ impl DetailsComponent {
 		self.data = id.and_then(|id| {
 			sync::get_commit_details(&self.repo.borrow(), id).ok()
 		});
+		self.tickets = Tickets::load(&self.repo.borrow())
+			.filter(Tickets::has_links);
 
 		self.scroll.reset();
 
@@ -147,14 +153,42 @@ impl DetailsComponent {
 			.skip(self.scroll.get_top())
 			.take(height)
 			.map(|(i, line)| {
-				Line::from(vec![Span::styled(
-					line.clone(),
+				self.styled_line(
+					line,
 					self.get_theme_for_line(i < wrapped_title.len()),
-				)])
+				)
 			})
 			.collect()
 	}
 
+	/// `line` with its ticket ids underlined
+	fn styled_line<'a>(
+		&self,
+		line: &Cow<'a, str>,
+		style: Style,
+	) -> Line<'a> {
+		let Some(tickets) = &self.tickets else {
+			return Line::from(Span::styled(line.clone(), style));
+		};
+
+		let mut spans = Vec::new();
+		let mut end = 0;
+		for range in tickets.find_all(line) {
+			spans.push(Span::styled(
+				line[end..range.start].to_string(),
+				style,
+			));
+			spans.push(Span::styled(
+				line[range.clone()].to_string(),
+				style.add_modifier(Modifier::UNDERLINED),
+			));
+			end = range.end;
+		}
+		spans.push(Span::styled(line[end..].to_string(), style));
+
+		Line::from(spans)
+	}
+
 	#[allow(clippy::too_many_lines)]
 	fn get_text_info(&self) -> Vec<Line<'_>> {
 		self.data.as_ref().map_or_else(Vec::new, |data| {
@@ -10,15 +10,16 @@ use crate::{
 	accessors,
 	app::Environment,
 	keys::{key_match, SharedKeyConfig},
+	string_utils::glob_match,
 	strings,
 };
 use anyhow::Result;
 use asyncgit::{
 	sync::{commit_files::OldNew, CommitTags},
-	AsyncCommitFiles, CommitFilesParams,
+	AsyncCommitFiles, CommitFilesParams, StatusItem,
 };
 use compare_details::CompareDetailsComponent;
-use crossterm::event::Event;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 use details::DetailsComponent;
 use ratatui::{
 	layout::{Constraint, Direction, Layout, Rect},
@@ -31,6 +32,11 @@ pub struct CommitDetailsComponent {
 	compare_details: CompareDetailsComponent,
 	file_tree: StatusTreeComponent,
 	git_commit_files: AsyncCommitFiles,
+	/// all changed files, `file_tree` only shows those passing
+	/// `filter`
+	files: Vec<StatusItem>,
+	filter: String,
+	filtering: bool,
 	visible: bool,
 	key_config: SharedKeyConfig,
 }
@@ -48,6 +54,9 @@ impl CommitDetailsComponent {
 				&env.sender_git,
 			),
 			file_tree: StatusTreeComponent::new(env, "", false),
+			files: Vec::new(),
+			filter: String::new(),
+			filtering: false,
 			visible: false,
 			commit: None,
 			key_config: env.key_config.clone(),
@@ -56,12 +65,18 @@ impl CommitDetailsComponent {
 
 	fn get_files_title(&self) -> String {
 		let files_count = self.file_tree.file_count();
-
-		format!(
-			"{} {}",
-			strings::commit::details_files_title(&self.key_config),
-			files_count
-		)
+		let title =
+			strings::commit::details_files_title(&self.key_config);
+
+		if self.filtering || !self.filter.is_empty() {
+			format!(
+				"{title} {files_count}/{} {}",
+				self.files.len(),
+				strings::filter_suffix(&self.filter, self.filtering)
+			)
+		} else {
+			format!("{title} {files_count}")
+		}
 	}
 
 	///
@@ -75,6 +90,11 @@ impl CommitDetailsComponent {
 			self.compare_details.set_commits(None);
 		}
 
+		if self.commit.map(|p| p.id) != params.map(|p| p.id) {
+			self.filter.clear();
+			self.filtering = false;
+		}
+
 		self.commit = params;
 
 		if let Some(id) = params {
@@ -94,13 +114,12 @@ impl CommitDetailsComponent {
 				self.git_commit_files.current()?
 			{
 				if fetched_id == id {
-					self.file_tree.update(res.as_slice())?;
-					self.file_tree.set_title(self.get_files_title());
-
-					return Ok(());
+					self.files = res;
+					return self.apply_filter();
 				}
 			}
 
+			self.files.clear();
 			self.file_tree.clear()?;
 			self.git_commit_files.fetch(id)?;
 		}
@@ -110,6 +129,69 @@ impl CommitDetailsComponent {
 		Ok(())
 	}
 
+	/// narrows the changed files down to the ones shown
+	fn apply_filter(&mut self) -> Result<()> {
+		let filter = self.filter.to_lowercase();
+
+		if filter.is_empty() {
+			self.file_tree.update(&self.files)?;
+		} else {
+			let files: Vec<StatusItem> = self
+				.files
+				.iter()
+				.filter(|file| {
+					let path = file.path.to_lowercase();
+					if filter.contains(['*', '?']) {
+						glob_match(&filter, &path)
+					} else {
+						path.contains(&filter)
+					}
+				})
+				.cloned()
+				.collect();
+			self.file_tree.update(&files)?;
+		}
+
+		self.file_tree.set_title(self.get_files_title());
+
+		Ok(())
+	}
+
+	/// handles keys while the filter is typed, `true` if consumed
+	fn filter_event(&mut self, e: &KeyEvent) -> Result<bool> {
+		if !self.filtering {
+			if key_match(e, self.key_config.keys.files_filter) {
+				self.filtering = true;
+				self.file_tree.set_title(self.get_files_title());
+				return Ok(true);
+			}
+			return Ok(false);
+		}
+
+		if key_match(e, self.key_config.keys.exit_popup) {
+			self.filtering = false;
+			self.filter.clear();
+		} else if key_match(e, self.key_config.keys.enter) {
+			self.filtering = false;
+		} else if e.code == KeyCode::Backspace {
+			self.filter.pop();
+		} else if let KeyCode::Char(c) = e.code {
+			if e.modifiers.difference(KeyModifiers::SHIFT).is_empty()
+			{
+				self.filter.push(c);
+			}
+		}
+
+		self.apply_filter()?;
+
+		Ok(true)
+	}
+
+	/// `true` while the user types a filter
+	pub const fn is_filtering(&self) -> bool {
+		self.filtering
+	}
+
 	///
 	pub fn any_work_pending(&self) -> bool {
 		self.git_commit_files.is_pending()
@@ -145,7 +227,10 @@ impl DrawableComponent for CommitDetailsComponent {
 		}
 
 		let constraints = if self.is_compare() {
-			[Constraint::Length(10), Constraint::Min(0)]
+			[
+				Constraint::Length(self.compare_details.height()),
+				Constraint::Min(0),
+			]
 		} else {
 			let details_focused = self.details_focused();
 			let percentages = if self.file_tree.focused() {
@@ -190,12 +275,24 @@ impl Component for CommitDetailsComponent {
 				force_all,
 				self.components().as_slice(),
 			);
+
+			out.push(CommandInfo::new(
+				strings::commands::filter_files(&self.key_config),
+				!self.files.is_empty(),
+				self.file_tree.focused() || force_all,
+			));
 		}
 
 		CommandBlocking::PassingOn
 	}
 
 	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if let Event::Key(e) = ev {
+			if self.file_tree.focused() && self.filter_event(e)? {
+				return Ok(EventState::Consumed);
+			}
+		}
+
 		if event_pump(ev, self.components_mut().as_mut_slice())?
 			.is_consumed()
 		{
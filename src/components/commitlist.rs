@@ -1,12 +1,17 @@
 use super::utils::logitems::{ItemBatch, LogEntry};
+#[cfg(feature = "forge-api")]
+use crate::forge::CiState;
 use crate::{
 	app::Environment,
 	components::{
-		utils::string_width_align, CommandBlocking, CommandInfo,
-		Component, DrawableComponent, EventState, ScrollType,
+		checkout_with_hook, list_mouse, utils::string_width_align,
+		CommandBlocking, CommandInfo, Component, DrawableComponent,
+		EventState, ListMouse, ScrollType,
 	},
 	keys::{key_match, SharedKeyConfig},
+	options::SharedOptions,
 	queue::{InternalEvent, Queue},
+	string_utils::{pad_width, str_width},
 	strings::{self, symbol},
 	try_or_popup,
 	ui::style::{SharedTheme, Theme},
@@ -15,14 +20,16 @@ use crate::{
 use anyhow::Result;
 use asyncgit::sync::{
 	self, checkout_commit, BranchDetails, BranchInfo, CommitId,
-	RepoPathRef, Tags,
+	LogFilterSearch, RepoPathRef, SearchFields, Tags,
 };
+#[cfg(feature = "forge-api")]
+use asyncgit::DiffLineType;
 use chrono::{DateTime, Local};
-use crossterm::event::Event;
+use crossterm::event::{Event, MouseEvent};
 use indexmap::IndexSet;
 use itertools::Itertools;
 use ratatui::{
-	layout::{Alignment, Rect},
+	layout::{Alignment, Margin, Rect},
 	style::Style,
 	text::{Line, Span},
 	widgets::{Block, Borders, Paragraph},
@@ -44,20 +51,28 @@ pub struct CommitList {
 	highlighted_selection: Option<usize>,
 	items: ItemBatch,
 	highlights: Option<Rc<IndexSet<CommitId>>>,
+	/// search of the highlights, used to mark the matching text
+	search: Option<LogFilterSearch>,
 	commits: IndexSet<CommitId>,
 	/// The marked commits.
 	/// `self.marked[].0` holds the commit index into `self.items.items` - used for ordering the list.
 	/// `self.marked[].1` is the commit id of the marked commit.
 	marked: Vec<(usize, CommitId)>,
+	/// last marked commit, a range mark extends from here
+	mark_anchor: Option<CommitId>,
 	scroll_state: (Instant, f32),
 	tags: Option<Tags>,
 	local_branches: BTreeMap<CommitId, Vec<BranchInfo>>,
 	remote_branches: BTreeMap<CommitId, Vec<BranchInfo>>,
 	current_size: Cell<Option<(u16, u16)>>,
 	scroll_top: Cell<usize>,
+	list_area: Cell<Rect>,
+	#[cfg(feature = "forge-api")]
+	ci_states: BTreeMap<CommitId, CiState>,
 	theme: SharedTheme,
 	queue: Queue,
 	key_config: SharedKeyConfig,
+	options: SharedOptions,
 }
 
 impl CommitList {
@@ -67,19 +82,25 @@ impl CommitList {
 			repo: env.repo.clone(),
 			items: ItemBatch::default(),
 			marked: Vec::with_capacity(2),
+			mark_anchor: None,
 			selection: 0,
 			highlighted_selection: None,
 			commits: IndexSet::new(),
 			highlights: None,
+			search: None,
 			scroll_state: (Instant::now(), 0_f32),
 			tags: None,
 			local_branches: BTreeMap::default(),
 			remote_branches: BTreeMap::default(),
 			current_size: Cell::new(None),
 			scroll_top: Cell::new(0),
+			list_area: Cell::new(Rect::default()),
+			#[cfg(feature = "forge-api")]
+			ci_states: BTreeMap::default(),
 			theme: env.theme.clone(),
 			queue: env.queue.clone(),
 			key_config: env.key_config.clone(),
+			options: env.options.clone(),
 			title: title.into(),
 		}
 	}
@@ -89,6 +110,36 @@ impl CommitList {
 		self.tags.as_ref()
 	}
 
+	/// commits currently scrolled into view
+	#[cfg(feature = "forge-api")]
+	pub fn visible_commits(&self) -> Vec<CommitId> {
+		let height = self
+			.current_size()
+			.map_or(0, |(_, height)| usize::from(height));
+
+		self.items
+			.iter()
+			.skip(self.scroll_top.get())
+			.take(height)
+			.map(|e| e.id)
+			.collect()
+	}
+
+	///
+	#[cfg(feature = "forge-api")]
+	pub fn set_ci_states(
+		&mut self,
+		states: Vec<(CommitId, Option<CiState>)>,
+	) {
+		for (id, state) in states {
+			if let Some(state) = state {
+				self.ci_states.insert(id, state);
+			} else {
+				self.ci_states.remove(&id);
+			}
+		}
+	}
+
 	///
 	pub fn clear(&mut self) {
 		self.items.clear();
@@ -100,6 +151,11 @@ impl CommitList {
 		self.commits.iter().copied().collect_vec()
 	}
 
+	///
+	pub fn set_title(&mut self, title: &str) {
+		self.title = title.into();
+	}
+
 	///
 	pub fn set_tags(&mut self, tags: Tags) {
 		self.tags = Some(tags);
@@ -122,6 +178,13 @@ impl CommitList {
 		self.marked.clear();
 	}
 
+	/// whether the marked commits are adjacent in the list
+	pub fn marked_contiguous(&self) -> bool {
+		self.marked
+			.windows(2)
+			.all(|pair| pair[1].0 == pair[0].0 + 1)
+	}
+
 	///
 	pub fn marked_commits(&self) -> Vec<CommitId> {
 		let (_, commits): (Vec<_>, Vec<CommitId>) =
@@ -170,7 +233,15 @@ impl CommitList {
 			try_or_popup!(
 				self,
 				"failed to checkout commit:",
-				checkout_commit(&self.repo.borrow(), commit_hash)
+				checkout_with_hook(
+					&self.queue,
+					&self.repo.borrow(),
+					true,
+					|| checkout_commit(
+						&self.repo.borrow(),
+						commit_hash
+					)
+				)
 			);
 		}
 	}
@@ -227,11 +298,20 @@ impl CommitList {
 		}
 	}
 
+	/// marks the text matching `search` in the highlighted commits
+	pub fn set_search(&mut self, search: Option<LogFilterSearch>) {
+		self.search = search;
+	}
+
 	///
 	pub fn set_highlighting(
 		&mut self,
 		highlighting: Option<Rc<IndexSet<CommitId>>>,
 	) {
+		if highlighting.is_none() {
+			self.search = None;
+		}
+
 		//note: set highlights to none if there is no highlight
 		self.highlights = if highlighting
 			.as_ref()
@@ -387,6 +467,30 @@ impl CommitList {
 		Ok(needs_update)
 	}
 
+	/// wheel scrolls the selection, a click selects the commit
+	fn mouse_event(&mut self, ev: MouseEvent) -> Result<bool> {
+		match list_mouse(ev, self.list_area.get()) {
+			Some(ListMouse::Scroll(scroll)) => {
+				self.move_selection(scroll)
+			}
+			Some(ListMouse::Click(row))
+				if !self.items.highlighting() =>
+			{
+				let selection = self.items.index_offset()
+					+ self.scroll_top.get()
+					+ row;
+				if selection > self.selection_max() {
+					return Ok(false);
+				}
+
+				let changed = selection != self.selection;
+				self.selection = selection;
+				Ok(changed)
+			}
+			_ => Ok(false),
+		}
+	}
+
 	fn mark(&mut self) {
 		if let Some(e) = self.selected_entry() {
 			let id = e.id;
@@ -395,8 +499,10 @@ impl CommitList {
 				.saturating_sub(self.items.index_offset());
 			if self.is_marked(&id).unwrap_or_default() {
 				self.marked.retain(|marked| marked.1 != id);
+				self.mark_anchor = None;
 			} else {
 				self.marked.push((selected, id));
+				self.mark_anchor = Some(id);
 
 				self.marked.sort_unstable_by(|first, second| {
 					first.0.cmp(&second.0)
@@ -405,6 +511,36 @@ impl CommitList {
 		}
 	}
 
+	/// marks all commits between the last marked one and the selection
+	fn mark_range(&mut self) {
+		let anchor = self.mark_anchor.and_then(|anchor| {
+			self.items.iter().position(|e| e.id == anchor)
+		});
+		let (Some(anchor), Some(id)) =
+			(anchor, self.selected_entry().map(|e| e.id))
+		else {
+			self.mark();
+			return;
+		};
+
+		let selected =
+			self.selection.saturating_sub(self.items.index_offset());
+		let (from, to) = (anchor.min(selected), anchor.max(selected));
+
+		let range: Vec<_> = self
+			.items
+			.iter()
+			.enumerate()
+			.skip(from)
+			.take(to - from + 1)
+			.map(|(idx, e)| (idx, e.id))
+			.filter(|(_, id)| !self.is_marked(id).unwrap_or_default())
+			.collect();
+		self.marked.extend(range);
+		self.marked.sort_unstable_by_key(|marked| marked.0);
+		self.mark_anchor = Some(id);
+	}
+
 	fn update_scroll_speed(&mut self) {
 		const REPEATED_SCROLL_THRESHOLD_MILLIS: u128 = 300;
 		const SCROLL_SPEED_START: f32 = 0.1_f32;
@@ -439,7 +575,32 @@ impl CommitList {
 		}
 	}
 
-	#[allow(clippy::too_many_arguments)]
+	#[cfg(feature = "forge-api")]
+	fn ci_span(
+		&self,
+		id: &CommitId,
+		theme: &Theme,
+		selected: bool,
+	) -> Span<'static> {
+		let (ci, style) = match self.ci_states.get(id) {
+			Some(CiState::Success) => (
+				symbol::CHECKMARK,
+				theme.diff_line(DiffLineType::Add, selected),
+			),
+			Some(CiState::Failure) => (
+				"\u{2717}",
+				theme.diff_line(DiffLineType::Delete, selected),
+			),
+			Some(CiState::Pending) => {
+				("\u{25cb}", theme.text(false, selected))
+			}
+			None => (symbol::EMPTY_SPACE, theme.text(true, selected)),
+		};
+
+		Span::styled(ci, style)
+	}
+
+	#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
 	fn get_entry_to_add<'a>(
 		&self,
 		e: &'a LogEntry,
@@ -518,6 +679,13 @@ impl CommitList {
 
 		txt.push(splitter.clone());
 
+		// ci state, only takes up space once any state is known
+		#[cfg(feature = "forge-api")]
+		if !self.ci_states.is_empty() {
+			txt.push(self.ci_span(&e.id, theme, selected));
+			txt.push(splitter.clone());
+		}
+
 		// commit timestamp
 		txt.push(Span::styled(
 			Cow::from(e.time_to_string(now)),
@@ -531,7 +699,13 @@ impl CommitList {
 		let author = string_width_align(&e.author, author_width);
 
 		// commit author
-		txt.push(Span::styled(author, style_author));
+		txt.extend(self.search_spans(
+			author,
+			SearchFields::AUTHORS,
+			normal && e.highlighted,
+			style_author,
+			selected,
+		));
 
 		txt.push(splitter.clone());
 
@@ -553,18 +727,53 @@ impl CommitList {
 		txt.push(splitter);
 
 		let message_width = width.saturating_sub(
-			txt.iter().map(|span| span.content.len()).sum(),
+			txt.iter().map(|span| str_width(&span.content)).sum(),
 		);
 
 		// commit msg
-		txt.push(Span::styled(
-			format!("{:message_width$}", &e.msg),
+		txt.extend(self.search_spans(
+			pad_width(&e.msg, message_width),
+			SearchFields::MESSAGE_SUMMARY,
+			normal && e.highlighted,
 			style_msg,
+			selected,
 		));
 
 		Line::from(txt)
 	}
 
+	/// splits `text` to mark the first search match if `field` is
+	/// searched
+	fn search_spans<'a>(
+		&self,
+		text: String,
+		field: SearchFields,
+		matching: bool,
+		style: Style,
+		selected: bool,
+	) -> Vec<Span<'a>> {
+		let range = self
+			.search
+			.as_ref()
+			.filter(|search| {
+				matching && search.options.fields.contains(field)
+			})
+			.and_then(|search| search.find(&text));
+
+		let Some(range) = range else {
+			return vec![Span::styled(text, style)];
+		};
+
+		vec![
+			Span::styled(text[..range.start].to_string(), style),
+			Span::styled(
+				text[range.clone()].to_string(),
+				self.theme.search_match(selected),
+			),
+			Span::styled(text[range.end..].to_string(), style),
+		]
+	}
+
 	fn get_text(&self, height: usize, width: usize) -> Vec<Line<'_>> {
 		let selection = self.relative_selection();
 
@@ -574,6 +783,8 @@ impl CommitList {
 
 		let any_marked = !self.marked.is_empty();
 
+		let decorations = self.options.borrow().log_decorations();
+
 		for (idx, e) in self
 			.items
 			.iter()
@@ -581,17 +792,22 @@ impl CommitList {
 			.take(height)
 			.enumerate()
 		{
-			let tags =
-				self.tags.as_ref().and_then(|t| t.get(&e.id)).map(
-					|tags| {
-						tags.iter()
-							.map(|t| format!("<{}>", t.name))
-							.join(" ")
-					},
-				);
+			let tags = self
+				.tags
+				.as_ref()
+				.filter(|_| decorations.show_tags())
+				.and_then(|t| t.get(&e.id))
+				.map(|tags| {
+					tags.iter()
+						.map(|t| format!("<{}>", t.name))
+						.join(" ")
+				});
 
-			let local_branches =
-				self.local_branches.get(&e.id).map(|local_branch| {
+			let local_branches = self
+				.local_branches
+				.get(&e.id)
+				.filter(|_| decorations.refs)
+				.map(|local_branch| {
 					local_branch
 						.iter()
 						.map(|local_branch| {
@@ -606,17 +822,22 @@ impl CommitList {
 				None
 			};
 
-			txt.push(self.get_entry_to_add(
-				e,
-				idx + self.scroll_top.get() == selection,
-				tags,
-				local_branches,
-				self.remote_branches_string(e),
-				&self.theme,
-				width,
-				now,
-				marked,
-			));
+			txt.push(
+				self.get_entry_to_add(
+					e,
+					idx + self.scroll_top.get() == selection,
+					tags,
+					local_branches,
+					decorations
+						.show_remote_branches()
+						.then(|| self.remote_branches_string(e))
+						.flatten(),
+					&self.theme,
+					width,
+					now,
+					marked,
+				),
+			);
 		}
 
 		txt
@@ -777,6 +998,7 @@ impl DrawableComponent for CommitList {
 			area.height.saturating_sub(2),
 		);
 		self.current_size.set(Some(current_size));
+		self.list_area.set(area.inner(Margin::new(1, 1)));
 
 		let height_in_lines = current_size.1 as usize;
 		let selection = self.relative_selection();
@@ -818,8 +1040,8 @@ impl DrawableComponent for CommitList {
 			f,
 			area,
 			&self.theme,
-			self.commits.len(),
-			self.selection,
+			self.commits.len().saturating_sub(height_in_lines),
+			self.items.index_offset() + self.scroll_top.get(),
 			Orientation::Vertical,
 		);
 
@@ -829,6 +1051,10 @@ impl DrawableComponent for CommitList {
 
 impl Component for CommitList {
 	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if let Event::Mouse(m) = ev {
+			return self.mouse_event(*m).map(Into::into);
+		}
+
 		if let Event::Key(k) = ev {
 			let selection_changed =
 				if key_match(k, self.key_config.keys.move_up) {
@@ -857,6 +1083,12 @@ impl Component for CommitList {
 				) {
 					self.mark();
 					true
+				} else if key_match(
+					k,
+					self.key_config.keys.log_mark_range,
+				) {
+					self.mark_range();
+					true
 				} else if key_match(
 					k,
 					self.key_config.keys.log_checkout_commit,
@@ -890,6 +1122,13 @@ impl Component for CommitList {
 			true,
 			true,
 		));
+		out.push(CommandInfo::new(
+			strings::commands::commit_list_mark_range(
+				&self.key_config,
+			),
+			self.selected_entry().is_some(),
+			true,
+		));
 		CommandBlocking::PassingOn
 	}
 }
@@ -899,6 +1138,8 @@ mod tests {
 	use asyncgit::sync::CommitInfo;
 
 	use super::*;
+	use crate::options::Options;
+	use std::cell::RefCell;
 
 	impl Default for CommitList {
 		fn default() -> Self {
@@ -907,15 +1148,21 @@ mod tests {
 				selection: 0,
 				highlighted_selection: Option::None,
 				highlights: Option::None,
+				search: Option::None,
 				tags: Option::None,
 				items: ItemBatch::default(),
 				commits: IndexSet::default(),
 				marked: Vec::default(),
+				mark_anchor: None,
 				scroll_top: Cell::default(),
+				list_area: Cell::default(),
+				#[cfg(feature = "forge-api")]
+				ci_states: BTreeMap::default(),
 				local_branches: BTreeMap::default(),
 				remote_branches: BTreeMap::default(),
 				theme: SharedTheme::default(),
 				key_config: SharedKeyConfig::default(),
+				options: Rc::new(RefCell::new(Options::test_env())),
 				scroll_state: (Instant::now(), 0.0),
 				current_size: Cell::default(),
 				repo: RepoPathRef::new(sync::RepoPath::Path(
@@ -1054,6 +1301,31 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_mark_range() {
+		let mut cl = build_commit_list_with_some_commits();
+		cl.mark();
+		cl.selection = 7;
+		cl.mark_range();
+
+		assert!(cl.marked_contiguous());
+		assert_eq!(
+			cl.concat_selected_commit_ids(),
+			Some(String::from(concat!(
+				"0000000000000000000000000000000000000002 ",
+				"0000000000000000000000000000000000000003 ",
+				"0000000000000000000000000000000000000004 ",
+				"0000000000000000000000000000000000000005"
+			)))
+		);
+
+		// continues from the end of the last range
+		cl.selection = 9;
+		cl.mark_range();
+		assert_eq!(cl.marked_count(), 6);
+		assert!(cl.marked_contiguous());
+	}
+
 	#[test]
 	fn test_copy_commit_random_marked() {
 		let cl = build_commit_list_with_some_commits();
@@ -16,12 +16,17 @@ use crate::{
 	strings,
 };
 
+/// hosts take any username along with an access token, this is the
+/// one github documents
+const TOKEN_USERNAME: &str = "x-access-token";
+
 ///
 pub struct CredComponent {
 	visible: bool,
 	key_config: SharedKeyConfig,
 	input_username: TextInputComponent,
 	input_password: TextInputComponent,
+	input_token: TextInputComponent,
 	cred: BasicAuthCredential,
 }
 
@@ -45,6 +50,13 @@ impl CredComponent {
 				false,
 			)
 			.with_input_type(InputType::Password),
+			input_token: TextInputComponent::new(
+				env,
+				&strings::token_popup_title(&key_config),
+				&strings::token_popup_msg(&key_config),
+				false,
+			)
+			.with_input_type(InputType::Password),
 			key_config,
 			cred: BasicAuthCredential::new(None, None),
 		}
@@ -57,6 +69,35 @@ impl CredComponent {
 	pub const fn get_cred(&self) -> &BasicAuthCredential {
 		&self.cred
 	}
+
+	/// switches between username and password and a single token
+	fn toggle_token(&mut self) -> Result<()> {
+		if self.input_token.is_visible() {
+			self.input_token.hide();
+			self.input_token.clear();
+			return self.show_username();
+		}
+
+		if self.input_username.is_visible() {
+			let username = self.input_username.get_text();
+			if !username.is_empty() {
+				self.cred.username = Some(username.to_string());
+			}
+		}
+
+		self.input_username.hide();
+		self.input_password.hide();
+		self.input_password.clear();
+		self.input_token.show()
+	}
+
+	/// the username is always offered for editing, prefilled with the
+	/// one from the url or the one remembered for it
+	fn show_username(&mut self) -> Result<()> {
+		self.input_username
+			.set_text(self.cred.username.clone().unwrap_or_default());
+		self.input_username.show()
+	}
 }
 
 impl DrawableComponent for CredComponent {
@@ -64,6 +105,7 @@ impl DrawableComponent for CredComponent {
 		if self.visible {
 			self.input_username.draw(f, rect)?;
 			self.input_password.draw(f, rect)?;
+			self.input_token.draw(f, rect)?;
 		}
 		Ok(())
 	}
@@ -85,6 +127,14 @@ impl Component for CredComponent {
 				true,
 				true,
 			));
+			out.push(CommandInfo::new(
+				strings::commands::cred_toggle_token(
+					&self.key_config,
+					self.input_token.is_visible(),
+				),
+				true,
+				true,
+			));
 			out.push(CommandInfo::new(
 				strings::commands::close_popup(&self.key_config),
 				true,
@@ -102,8 +152,13 @@ impl Component for CredComponent {
 					self.hide();
 					return Ok(EventState::Consumed);
 				}
+				if key_match(e, self.key_config.keys.tab_toggle) {
+					self.toggle_token()?;
+					return Ok(EventState::Consumed);
+				}
 				if self.input_username.event(ev)?.is_consumed()
 					|| self.input_password.event(ev)?.is_consumed()
+					|| self.input_token.event(ev)?.is_consumed()
 				{
 					return Ok(EventState::Consumed);
 				} else if key_match(e, self.key_config.keys.enter) {
@@ -130,6 +185,25 @@ impl Component for CredComponent {
 						self.input_password.hide();
 						self.input_password.clear();
 						return Ok(EventState::NotConsumed);
+					} else if self.input_token.is_visible() {
+						self.cred = BasicAuthCredential::new(
+							Some(
+								self.cred
+									.username
+									.clone()
+									.unwrap_or_else(|| {
+										TOKEN_USERNAME.to_string()
+									}),
+							),
+							Some(
+								self.input_token
+									.get_text()
+									.to_string(),
+							),
+						);
+						self.input_token.hide();
+						self.input_token.clear();
+						return Ok(EventState::NotConsumed);
 					} else {
 						self.hide();
 					}
@@ -146,15 +220,18 @@ impl Component for CredComponent {
 
 	fn hide(&mut self) {
 		self.cred = BasicAuthCredential::new(None, None);
+		self.input_username.hide();
+		self.input_password.hide();
+		self.input_password.clear();
+		self.input_token.hide();
+		self.input_token.clear();
 		self.visible = false;
 	}
 
 	fn show(&mut self) -> Result<()> {
 		self.visible = true;
-		if self.cred.username.is_none() {
-			self.input_username.show()
-		} else if self.cred.password.is_none() {
-			self.input_password.show()
+		if self.cred.password.is_none() {
+			self.show_username()
 		} else {
 			Ok(())
 		}
@@ -8,28 +8,40 @@ use crate::{
 	components::{CommandInfo, Component, EventState},
 	keys::{key_match, SharedKeyConfig},
 	options::SharedOptions,
+	popups::{CopyContext, LineActionsContext},
 	queue::{Action, InternalEvent, NeedsUpdate, Queue, ResetItem},
-	string_utils::tabs_to_spaces,
-	string_utils::trim_offset,
+	string_utils::{
+		pad_width, tabs_to_spaces, trim_offset, wrap_by_width,
+	},
 	strings, try_or_popup,
-	ui::style::SharedTheme,
+	ui::{style::SharedTheme, PreviewImage, SharedGraphics},
 };
 use anyhow::Result;
 use asyncgit::{
 	hash,
-	sync::{self, diff::DiffLinePosition, RepoPathRef},
+	sync::{
+		self,
+		commit_files::OldNew,
+		diff::{DiffLinePosition, Hunk},
+		LineEnding, RepoPathRef,
+	},
 	DiffLine, DiffLineType, FileDiff,
 };
 use bytesize::ByteSize;
-use crossterm::event::Event;
+use crossterm::event::{
+	Event, MouseButton, MouseEvent, MouseEventKind,
+};
 use ratatui::{
-	layout::Rect,
+	layout::{Constraint, Layout, Margin, Position, Rect},
 	symbols,
 	text::{Line, Span},
 	widgets::{Block, Borders, Paragraph},
 	Frame,
 };
-use std::{borrow::Cow, cell::Cell, cmp, path::Path};
+use std::{
+	borrow::Cow, cell::Cell, cmp, collections::HashSet, fmt::Write,
+	path::Path, rc::Rc,
+};
 
 #[derive(Default)]
 struct Current {
@@ -101,15 +113,43 @@ impl Selection {
 	}
 }
 
+/// lines of a diff shown before more have to be loaded explicitly
+const DIFF_CHUNK_LINES: usize = 5_000;
+
+/// a move of the hunk selection
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct HunkJump {
+	/// towards the end of the diff
+	pub forward: bool,
+	/// only to hunks with conflict markers
+	pub conflicts: bool,
+}
+
 ///
+#[allow(clippy::struct_excessive_bools)]
 pub struct DiffComponent {
 	repo: RepoPathRef,
+	/// the part of `full_diff` within `line_limit`
 	diff: Option<FileDiff>,
+	full_diff: Option<FileDiff>,
+	line_limit: usize,
 	longest_line: usize,
 	pending: bool,
 	selection: Selection,
 	selected_hunk: Option<usize>,
+	/// applied once the diff of the next file arrives
+	pending_jump: Option<HunkJump>,
+	/// header hashes of the hunks split into smaller ones
+	split_hunks: HashSet<u64>,
+	/// header hashes of the hunks these got split into
+	sub_hunks: HashSet<u64>,
+	/// whether the owner moves hunk jumps on to the next file
+	jumps_across_files: bool,
 	current_size: Cell<(u16, u16)>,
+	/// area the diff lines were drawn into, used for mouse hits
+	text_area: Cell<Rect>,
+	/// line a mouse drag selection started on
+	mouse_drag: Option<usize>,
 	focused: bool,
 	current: Current,
 	vertical_scroll: VerticalScroll,
@@ -119,6 +159,9 @@ pub struct DiffComponent {
 	key_config: SharedKeyConfig,
 	is_immutable: bool,
 	options: SharedOptions,
+	/// old and new version of a changed image
+	images: Option<OldNew<Option<Rc<PreviewImage>>>>,
+	graphics: SharedGraphics,
 }
 
 impl DiffComponent {
@@ -130,9 +173,17 @@ impl DiffComponent {
 			current: Current::default(),
 			pending: false,
 			selected_hunk: None,
+			pending_jump: None,
+			split_hunks: HashSet::new(),
+			sub_hunks: HashSet::new(),
+			jumps_across_files: false,
 			diff: None,
+			full_diff: None,
+			line_limit: DIFF_CHUNK_LINES,
 			longest_line: 0,
 			current_size: Cell::new((0, 0)),
+			text_area: Cell::new(Rect::default()),
+			mouse_drag: None,
 			selection: Selection::Single(0),
 			vertical_scroll: VerticalScroll::new(),
 			horizontal_scroll: HorizontalScroll::new(),
@@ -141,6 +192,8 @@ impl DiffComponent {
 			is_immutable,
 			repo: env.repo.clone(),
 			options: env.options.clone(),
+			images: None,
+			graphics: env.graphics.clone(),
 		}
 	}
 	///
@@ -155,11 +208,16 @@ impl DiffComponent {
 	pub fn clear(&mut self, pending: bool) {
 		self.current = Current::default();
 		self.diff = None;
+		self.full_diff = None;
+		self.images = None;
+		self.line_limit = DIFF_CHUNK_LINES;
 		self.longest_line = 0;
 		self.vertical_scroll.reset();
 		self.horizontal_scroll.reset();
 		self.selection = Selection::Single(0);
 		self.selected_hunk = None;
+		self.split_hunks.clear();
+		self.sub_hunks.clear();
 		self.pending = pending;
 	}
 	///
@@ -170,6 +228,7 @@ impl DiffComponent {
 		diff: FileDiff,
 	) {
 		self.pending = false;
+		let jump = self.pending_jump.take();
 
 		let hash = hash(&diff);
 
@@ -182,31 +241,34 @@ impl DiffComponent {
 				hash,
 			};
 
-			self.diff = Some(diff);
-
-			self.longest_line = self
-				.diff
-				.iter()
-				.flat_map(|diff| diff.hunks.iter())
-				.flat_map(|hunk| hunk.lines.iter())
-				.map(|line| {
-					let converted_content = tabs_to_spaces(
-						line.content.as_ref().to_string(),
-					);
-
-					converted_content.len()
-				})
-				.max()
-				.map_or(0, |len| {
-					// Each hunk uses a 1-character wide vertical bar to its left to indicate
-					// selection.
-					len + 1
+			if reset_selection {
+				self.line_limit = DIFF_CHUNK_LINES;
+				self.split_hunks.clear();
+			}
+			self.images = diff
+				.binary
+				.as_ref()
+				.and_then(|binary| binary.images.clone())
+				.map(|images| OldNew {
+					old: images
+						.old
+						.map(|data| Rc::new(PreviewImage::new(data))),
+					new: images
+						.new
+						.map(|data| Rc::new(PreviewImage::new(data))),
 				});
+			self.full_diff = Some(diff);
+			self.apply_splits();
+			self.apply_line_limit();
 
 			if reset_selection {
 				self.vertical_scroll.reset();
 				self.selection = Selection::Single(0);
 				self.update_selection(0);
+
+				if let Some(jump) = jump {
+					self.jump_hunk_from(jump, None);
+				}
 			} else {
 				let old_selection = match self.selection {
 					Selection::Single(line) => line,
@@ -217,6 +279,80 @@ impl DiffComponent {
 		}
 	}
 
+	/// shows the next chunk of a diff too large to show at once
+	fn load_more(&mut self) {
+		self.line_limit =
+			self.line_limit.saturating_add(DIFF_CHUNK_LINES);
+		self.apply_line_limit();
+	}
+
+	/// lines of the diff not loaded into the view yet
+	fn hidden_lines(&self) -> usize {
+		self.full_diff
+			.as_ref()
+			.map_or(0, |diff| diff.lines)
+			.saturating_sub(self.lines_count())
+	}
+
+	/// the first `limit` lines of `diff`
+	fn truncate_diff(diff: &FileDiff, limit: usize) -> FileDiff {
+		if diff.lines <= limit {
+			return diff.clone();
+		}
+
+		let mut hunks = Vec::new();
+		let mut lines = 0;
+		for hunk in &diff.hunks {
+			if lines >= limit {
+				break;
+			}
+
+			let take = hunk.lines.len().min(limit - lines);
+			hunks.push(Hunk {
+				header_hash: hunk.header_hash,
+				lines: hunk.lines[..take].to_vec(),
+			});
+			lines += take;
+		}
+
+		FileDiff {
+			hunks,
+			lines,
+			untracked: diff.untracked,
+			sizes: diff.sizes,
+			size_delta: diff.size_delta,
+			mode_change: diff.mode_change,
+			symlink: diff.symlink,
+			binary: diff.binary.clone(),
+			text_format: diff.text_format,
+		}
+	}
+
+	fn apply_line_limit(&mut self) {
+		self.diff = self
+			.full_diff
+			.as_ref()
+			.map(|diff| Self::truncate_diff(diff, self.line_limit));
+
+		self.longest_line = self
+			.diff
+			.iter()
+			.flat_map(|diff| diff.hunks.iter())
+			.flat_map(|hunk| hunk.lines.iter())
+			.map(|line| {
+				let converted_content =
+					tabs_to_spaces(line.content.as_ref().to_string());
+
+				converted_content.len()
+			})
+			.max()
+			.map_or(0, |len| {
+				// Each hunk uses a 1-character wide vertical bar to its left to indicate
+				// selection.
+				len + 1
+			});
+	}
+
 	fn move_selection(&mut self, move_type: ScrollType) {
 		if let Some(diff) = &self.diff {
 			let max = diff.lines.saturating_sub(1);
@@ -263,16 +399,287 @@ impl DiffComponent {
 	}
 
 	fn max_scroll_right(&self) -> usize {
+		if self.wrap_lines() {
+			return 0;
+		}
+
 		self.longest_line
 			.saturating_sub(self.current_size.get().0.into())
 	}
 
+	fn wrap_lines(&self) -> bool {
+		self.options.borrow().diff_wrap_lines()
+	}
+
+	fn toggle_wrap_lines(&self) {
+		self.options.borrow_mut().diff_toggle_wrap_lines();
+		self.vertical_scroll.reset();
+		self.horizontal_scroll.reset();
+	}
+
+	/// width available for line content when wrapping
+	/// (excluding the hunk marker column)
+	fn wrap_width(&self) -> usize {
+		usize::from(self.current_size.get().0.saturating_sub(1))
+	}
+
+	/// returns total amount of visual rows and the first and last
+	/// row of the line the selection ends on
+	fn wrapped_rows(&self) -> (usize, usize, usize) {
+		let Some(diff) = &self.diff else {
+			return (0, 0, 0);
+		};
+
+		let width = self.wrap_width();
+		let selected_line = self.selection.get_end();
+		let mut total = 0;
+		let mut selected = (0, 0);
+
+		for (i, line) in diff
+			.hunks
+			.iter()
+			.flat_map(|hunk| hunk.lines.iter())
+			.enumerate()
+		{
+			let rows = wrap_by_width(
+				&Self::line_content(line, &self.theme),
+				width,
+			)
+			.len();
+
+			if i == selected_line {
+				selected = (total, total + rows.saturating_sub(1));
+			}
+
+			total += rows;
+		}
+
+		(total, selected.0, selected.1)
+	}
+
+	/// whether a mouse event happened on the diff lines
+	pub fn contains_mouse(&self, ev: MouseEvent) -> bool {
+		self.text_area
+			.get()
+			.contains(Position::new(ev.column, ev.row))
+	}
+
+	/// diff line drawn in terminal `row`, rows above or below the
+	/// diff area select the lines just outside the visible ones
+	fn line_at_row(&self, row: u16) -> Option<usize> {
+		let diff = self.diff.as_ref()?;
+		let area = self.text_area.get();
+		if diff.lines == 0 || area.height == 0 {
+			return None;
+		}
+
+		let top = self.vertical_scroll.get_top();
+		let row = if row < area.y {
+			top.saturating_sub(1)
+		} else {
+			top + usize::from(row - area.y)
+		};
+
+		let line = if self.wrap_lines() {
+			let width = self.wrap_width();
+			let mut rows = 0;
+			diff.hunks
+				.iter()
+				.flat_map(|hunk| hunk.lines.iter())
+				.position(|line| {
+					rows += wrap_by_width(
+						&Self::line_content(line, &self.theme),
+						width,
+					)
+					.len();
+					rows > row
+				})
+				.unwrap_or(diff.lines)
+		} else {
+			row
+		};
+
+		Some(cmp::min(line, diff.lines - 1))
+	}
+
+	/// click selects a line, dragging extends the selection and a
+	/// right click offers to (un)stage or discard the selection
+	fn mouse_event(&mut self, ev: MouseEvent) -> bool {
+		let inside = self.contains_mouse(ev);
+
+		match ev.kind {
+			MouseEventKind::Down(MouseButton::Left) if inside => {
+				if let Some(line) = self.line_at_row(ev.row) {
+					self.update_selection(line);
+					self.mouse_drag = Some(line);
+				}
+			}
+			MouseEventKind::Drag(MouseButton::Left) => {
+				let (Some(start), Some(line)) =
+					(self.mouse_drag, self.line_at_row(ev.row))
+				else {
+					return false;
+				};
+				self.selection = if start == line {
+					Selection::Single(line)
+				} else {
+					Selection::Multiple(start, line)
+				};
+			}
+			MouseEventKind::Up(MouseButton::Left) => {
+				return self.mouse_drag.take().is_some();
+			}
+			MouseEventKind::Down(MouseButton::Right)
+				if inside && !self.is_immutable =>
+			{
+				if let Some(line) = self.line_at_row(ev.row) {
+					if !self.selection.contains(line) {
+						self.update_selection(line);
+					}
+				}
+				self.open_line_actions();
+			}
+			MouseEventKind::ScrollDown if inside => {
+				self.move_selection(ScrollType::Down);
+			}
+			MouseEventKind::ScrollUp if inside => {
+				self.move_selection(ScrollType::Up);
+			}
+			_ => return false,
+		}
+
+		true
+	}
+
+	fn open_line_actions(&self) {
+		if let Some(diff) = &self.diff {
+			self.queue.push(InternalEvent::OpenLineActionsPopup(
+				LineActionsContext {
+					path: self.current.path.clone(),
+					is_stage: self.is_stage(),
+					untracked: diff.untracked,
+					lines: self.selected_lines(),
+				},
+			));
+		}
+	}
+
 	fn modify_selection(&mut self, direction: Direction) {
 		if self.diff.is_some() {
 			self.selection.modify(direction, self.lines_count());
 		}
 	}
 
+	/// clipboard context of the current file and line selection
+	pub fn copy_context(&self) -> CopyContext {
+		CopyContext {
+			commit: None,
+			path: Some(self.current.path.clone()),
+			patch: self.selection_as_patch(),
+			lines: self.selected_new_lines(),
+		}
+	}
+
+	/// range of new line numbers covered by the selection
+	fn selected_new_lines(&self) -> Option<(usize, usize)> {
+		let diff = self.diff.as_ref()?;
+
+		let lines = diff
+			.hunks
+			.iter()
+			.flat_map(|hunk| hunk.lines.iter())
+			.enumerate()
+			.filter(|(i, _)| self.selection.contains(*i))
+			.filter_map(|(_, line)| line.position.new_lineno)
+			.map(|line| line as usize);
+
+		let (start, end) = lines.fold(None, |range, line| {
+			Some(range.map_or((line, line), |(start, end)| {
+				(cmp::min(start, line), cmp::max(end, line))
+			}))
+		})?;
+
+		Some((start, end))
+	}
+
+	/// selected lines as a patch, every hunk touched by the
+	/// selection gets its header recomputed to only span the selection
+	fn selection_as_patch(&self) -> Option<String> {
+		let diff = self.diff.as_ref()?;
+		let file = &self.current.path;
+
+		let mut patch = format!(
+			"diff --git a/{file} b/{file}\n--- a/{file}\n+++ b/{file}\n"
+		);
+		let mut any_selected = false;
+		let mut line_offset = 0;
+
+		for hunk in &diff.hunks {
+			let hunk_start = line_offset;
+			line_offset += hunk.lines.len();
+
+			let selected: Vec<(usize, &DiffLine)> = hunk
+				.lines
+				.iter()
+				.enumerate()
+				.filter(|(i, line)| {
+					line.line_type != DiffLineType::Header
+						&& self.selection.contains(hunk_start + i)
+				})
+				.collect();
+
+			let Some((first, _)) = selected.first().copied() else {
+				continue;
+			};
+			any_selected = true;
+
+			// an empty side of a hunk starts at the line before it
+			let start =
+				|get: fn(&DiffLinePosition) -> Option<u32>| {
+					selected
+						.iter()
+						.find_map(|(_, line)| get(&line.position))
+						.or_else(|| {
+							hunk.lines[..first]
+								.iter()
+								.rev()
+								.find_map(|line| get(&line.position))
+						})
+						.unwrap_or_default()
+				};
+			let old_count = selected
+				.iter()
+				.filter(|(_, l)| l.line_type != DiffLineType::Add)
+				.count();
+			let new_count = selected
+				.iter()
+				.filter(|(_, l)| l.line_type != DiffLineType::Delete)
+				.count();
+
+			let _ = writeln!(
+				patch,
+				"@@ -{},{old_count} +{},{new_count} @@",
+				start(|p| p.old_lineno),
+				start(|p| p.new_lineno),
+			);
+
+			for (_, line) in selected {
+				let prefix = match line.line_type {
+					DiffLineType::Add => '+',
+					DiffLineType::Delete => '-',
+					DiffLineType::None | DiffLineType::Header => ' ',
+				};
+				let _ = writeln!(
+					patch,
+					"{prefix}{}",
+					line.content.trim_end_matches(['\n', '\r'])
+				);
+			}
+		}
+
+		any_selected.then_some(patch)
+	}
+
 	fn copy_selection(&self) {
 		if let Some(diff) = &self.diff {
 			let lines_to_copy: Vec<&str> =
@@ -326,8 +733,16 @@ impl DiffComponent {
 
 	fn get_text(&self, width: u16, height: u16) -> Vec<Line<'_>> {
 		if let Some(diff) = &self.diff {
-			return if diff.hunks.is_empty() {
-				self.get_text_binary(diff)
+			return if diff.symlink {
+				self.get_text_symlink(diff)
+			} else if diff.hunks.is_empty() {
+				if diff.mode_change.is_some() {
+					self.get_text_mode_change(diff)
+				} else {
+					self.get_text_binary(diff)
+				}
+			} else if self.wrap_lines() {
+				self.get_text_wrapped(diff, height)
 			} else {
 				let mut res: Vec<Line> = Vec::new();
 
@@ -387,12 +802,112 @@ impl DiffComponent {
 		vec![]
 	}
 
+	fn get_text_wrapped(
+		&self,
+		diff: &FileDiff,
+		height: u16,
+	) -> Vec<Line<'_>> {
+		let top = self.vertical_scroll.get_top();
+		let height = usize::from(height);
+		let width = self.wrap_width();
+
+		let mut res: Vec<Line> = Vec::new();
+		let mut line_cursor = 0_usize;
+		let mut row = 0_usize;
+
+		for (i, hunk) in diff.hunks.iter().enumerate() {
+			let hunk_selected = self.focused()
+				&& self.selected_hunk.is_some_and(|s| s == i);
+			let hunk_len = hunk.lines.len();
+
+			for (i, line) in hunk.lines.iter().enumerate() {
+				if res.len() >= height {
+					return res;
+				}
+
+				for wrapped in Self::get_wrapped_lines_to_add(
+					width,
+					line,
+					self.focused()
+						&& self.selection.contains(line_cursor),
+					hunk_selected,
+					i == hunk_len - 1,
+					&self.theme,
+				) {
+					if row >= top && res.len() < height {
+						res.push(wrapped);
+					}
+					row += 1;
+				}
+
+				line_cursor += 1;
+			}
+		}
+
+		res
+	}
+
+	fn get_text_mode_change(&self, diff: &FileDiff) -> Vec<Line<'_>> {
+		diff.mode_change
+			.map(|(old, new)| {
+				Line::from(vec![
+					Span::raw(Cow::from("mode: ")),
+					Span::styled(
+						Cow::from(format!("{old:o}")),
+						self.theme
+							.diff_line(DiffLineType::Delete, false),
+					),
+					Span::raw(Cow::from(" -> ")),
+					Span::styled(
+						Cow::from(format!("{new:o}")),
+						self.theme
+							.diff_line(DiffLineType::Add, false),
+					),
+				])
+			})
+			.into_iter()
+			.collect()
+	}
+
+	/// the content of a symlink is its target, so show it as such
+	fn get_text_symlink<'a>(
+		&'a self,
+		diff: &'a FileDiff,
+	) -> Vec<Line<'a>> {
+		let target = |line_type: DiffLineType| {
+			diff.hunks
+				.iter()
+				.flat_map(|hunk| hunk.lines.iter())
+				.find(|line| {
+					// skip "\ No newline at end of file"
+					line.line_type == line_type
+						&& !line.content.starts_with('\\')
+				})
+				.map_or("", |line| line.content.as_ref())
+		};
+
+		let mut res = self.get_text_mode_change(diff);
+		res.push(Line::from(vec![
+			Span::raw(Cow::from("symlink: ")),
+			Span::styled(
+				Cow::from(target(DiffLineType::Delete)),
+				self.theme.diff_line(DiffLineType::Delete, false),
+			),
+			Span::raw(Cow::from(" -> ")),
+			Span::styled(
+				Cow::from(target(DiffLineType::Add)),
+				self.theme.diff_line(DiffLineType::Add, false),
+			),
+		]));
+		res
+	}
+
 	fn get_text_binary(&self, diff: &FileDiff) -> Vec<Line<'_>> {
 		let is_positive = diff.size_delta >= 0;
 		let delta_byte_size =
 			ByteSize::b(diff.size_delta.unsigned_abs());
 		let sign = if is_positive { "+" } else { "-" };
-		vec![Line::from(vec![
+		let mut res = vec![Line::from(vec![
 			Span::raw(Cow::from("size: ")),
 			Span::styled(
 				Cow::from(format!("{}", ByteSize::b(diff.sizes.0))),
@@ -416,7 +931,92 @@ impl DiffComponent {
 				),
 			),
 			Span::raw(Cow::from(")")),
-		])]
+		])];
+
+		if let Some(binary) = &diff.binary {
+			let short_id = |id: &Option<String>| {
+				id.as_ref().map_or_else(
+					|| "0000000".to_string(),
+					|id| id.chars().take(7).collect(),
+				)
+			};
+
+			res.push(Line::from(vec![
+				Span::raw(Cow::from("id: ")),
+				Span::styled(
+					Cow::from(short_id(&binary.ids.old)),
+					self.theme.commit_hash(false),
+				),
+				Span::raw(Cow::from(" -> ")),
+				Span::styled(
+					Cow::from(short_id(&binary.ids.new)),
+					self.theme.commit_hash(false),
+				),
+			]));
+		}
+
+		res
+	}
+
+	/// previews of the old and new image below the binary text
+	fn draw_images(&self, f: &mut Frame, area: Rect) {
+		let Some(images) = &self.images else {
+			return;
+		};
+
+		// below the size and id lines
+		let area = Rect {
+			y: area.y.saturating_add(3),
+			height: area.height.saturating_sub(3),
+			..area
+		};
+
+		let sides: Vec<_> =
+			[("old", &images.old), ("new", &images.new)]
+				.into_iter()
+				.filter_map(|(label, image)| {
+					Some((label, image.as_ref()?))
+				})
+				.collect();
+		let columns = Layout::horizontal(vec![
+			Constraint::Fill(1);
+			sides.len()
+		])
+		.spacing(1)
+		.split(area);
+
+		let mut graphics = self.graphics.borrow_mut();
+		for ((label, image), column) in
+			sides.into_iter().zip(columns.iter())
+		{
+			let preview = graphics.can_preview(image);
+			f.render_widget(
+				Paragraph::new(Span::styled(
+					if preview {
+						Cow::from(label)
+					} else {
+						Cow::from(format!(
+							"{label}: {}",
+							strings::IMAGE_NO_PREVIEW
+						))
+					},
+					self.theme.text(false, false),
+				)),
+				*column,
+			);
+
+			if preview {
+				graphics.draw(
+					f,
+					Rect {
+						y: column.y.saturating_add(1),
+						height: column.height.saturating_sub(1),
+						..*column
+					},
+					image,
+				);
+			}
+		}
 	}
 
 	fn get_line_to_add<'a>(
@@ -430,9 +1030,6 @@ impl DiffComponent {
 	) -> Line<'a> {
 		let style = theme.diff_hunk_marker(selected_hunk);
 
-		let is_content_line =
-			matches!(line.line_type, DiffLineType::None);
-
 		let left_side_of_line = if end_of_hunk {
 			Span::styled(Cow::from(symbols::line::BOTTOM_LEFT), style)
 		} else {
@@ -448,17 +1045,12 @@ impl DiffComponent {
 			}
 		};
 
-		let content =
-			if !is_content_line && line.content.as_ref().is_empty() {
-				theme.line_break()
-			} else {
-				tabs_to_spaces(line.content.as_ref().to_string())
-			};
+		let content = Self::line_content(line, theme);
 		let content = trim_offset(&content, scrolled_right);
 
 		let filled = if selected {
 			// selected line
-			format!("{content:w$}\n", w = width as usize)
+			format!("{}\n", pad_width(content, width as usize))
 		} else {
 			// weird eof missing eol line
 			format!("{content}\n")
@@ -473,6 +1065,76 @@ impl DiffComponent {
 		])
 	}
 
+	/// like `get_line_to_add` but soft wraps the content into as many
+	/// rows as needed to fit `width`, repeating the hunk marker
+	fn get_wrapped_lines_to_add(
+		width: usize,
+		line: &DiffLine,
+		selected: bool,
+		selected_hunk: bool,
+		end_of_hunk: bool,
+		theme: &SharedTheme,
+	) -> Vec<Line<'static>> {
+		let style = theme.diff_hunk_marker(selected_hunk);
+		let content = Self::line_content(line, theme);
+		let chunks = wrap_by_width(&content, width);
+		let last = chunks.len().saturating_sub(1);
+
+		chunks
+			.into_iter()
+			.enumerate()
+			.map(|(idx, chunk)| {
+				let marker = if end_of_hunk && idx == last {
+					symbols::line::BOTTOM_LEFT
+				} else if idx == 0
+					&& line.line_type == DiffLineType::Header
+				{
+					symbols::line::TOP_LEFT
+				} else {
+					symbols::line::VERTICAL
+				};
+
+				let filled = if selected {
+					format!("{}\n", pad_width(chunk, width))
+				} else {
+					format!("{chunk}\n")
+				};
+
+				Line::from(vec![
+					Span::styled(Cow::from(marker), style),
+					Span::styled(
+						Cow::from(filled),
+						theme.diff_line(line.line_type, selected),
+					),
+				])
+			})
+			.collect()
+	}
+
+	fn line_content(line: &DiffLine, theme: &SharedTheme) -> String {
+		let is_content_line =
+			matches!(line.line_type, DiffLineType::None);
+
+		let content =
+			if !is_content_line && line.content.as_ref().is_empty() {
+				theme.line_break()
+			} else {
+				tabs_to_spaces(line.content.as_ref().to_string())
+			};
+
+		if theme.accessible() {
+			let marker = match line.line_type {
+				DiffLineType::Add => '+',
+				DiffLineType::Delete => '-',
+				DiffLineType::None => ' ',
+				DiffLineType::Header => return content,
+			};
+			format!("{marker}{content}")
+		} else {
+			content
+		}
+	}
+
 	const fn hunk_visible(
 		hunk_min: usize,
 		hunk_max: usize,
@@ -494,9 +1156,130 @@ impl DiffComponent {
 		false
 	}
 
+	/// replaces the hunks in `split_hunks` with their parts
+	fn apply_splits(&mut self) {
+		self.sub_hunks.clear();
+
+		let Some(diff) = &mut self.full_diff else {
+			return;
+		};
+		if self.split_hunks.is_empty() {
+			return;
+		}
+
+		diff.hunks = std::mem::take(&mut diff.hunks)
+			.into_iter()
+			.flat_map(|hunk| {
+				if self.split_hunks.contains(&hunk.header_hash) {
+					let parts = sync::split_hunk(&hunk);
+					self.sub_hunks.extend(
+						parts.iter().map(|part| part.header_hash),
+					);
+					parts
+				} else {
+					vec![hunk]
+				}
+			})
+			.collect();
+		diff.lines =
+			diff.hunks.iter().map(|hunk| hunk.lines.len()).sum();
+	}
+
+	fn can_split_hunk(&self) -> bool {
+		!self.is_immutable
+			&& self
+				.selected_hunk
+				.zip(self.diff.as_ref())
+				.and_then(|(hunk, diff)| diff.hunks.get(hunk))
+				.is_some_and(|hunk| sync::split_hunk(hunk).len() > 1)
+	}
+
+	/// splits the selected hunk at the context between its changes
+	fn split_hunk(&mut self) {
+		if !self.can_split_hunk() {
+			return;
+		}
+		let Some(hunk_index) = self.selected_hunk else {
+			return;
+		};
+		let Some(hunk) =
+			self.diff.as_ref().map(|diff| &diff.hunks[hunk_index])
+		else {
+			return;
+		};
+
+		self.split_hunks.insert(hunk.header_hash);
+		self.apply_splits();
+		self.apply_line_limit();
+		self.jump_hunk_from(
+			HunkJump {
+				forward: true,
+				conflicts: false,
+			},
+			hunk_index.checked_sub(1),
+		);
+	}
+
+	/// an unstaged hunk of a tracked file without conflicts
+	fn selected_editable_hunk(&self) -> Option<&Hunk> {
+		if self.is_immutable || self.is_stage() {
+			return None;
+		}
+
+		let diff =
+			self.diff.as_ref().filter(|diff| !diff.untracked)?;
+
+		diff.hunks
+			.get(self.selected_hunk?)
+			.filter(|hunk| !is_conflict_hunk(hunk))
+	}
+
+	/// opens the selected hunk as a patch in the external editor,
+	/// like the edit mode of `git add -p`
+	fn edit_hunk(&self) {
+		if let Some(hunk) = self.selected_editable_hunk() {
+			self.queue.push(InternalEvent::EditHunk(
+				self.current.path.clone(),
+				hunk.clone(),
+			));
+		}
+	}
+
+	/// the changes of the hunk if it is part of a split one, these
+	/// get (un)staged as lines
+	fn sub_hunk_changes(
+		&self,
+		hunk: usize,
+	) -> Option<Vec<DiffLinePosition>> {
+		let hunk = self.diff.as_ref()?.hunks.get(hunk)?;
+
+		self.sub_hunks.contains(&hunk.header_hash).then(|| {
+			hunk.lines
+				.iter()
+				.filter(|line| {
+					matches!(
+						line.line_type,
+						DiffLineType::Add | DiffLineType::Delete
+					)
+				})
+				.map(|line| line.position)
+				.collect()
+		})
+	}
+
 	fn unstage_hunk(&self) -> Result<()> {
 		if let Some(diff) = &self.diff {
 			if let Some(hunk) = self.selected_hunk {
+				if let Some(changes) = self.sub_hunk_changes(hunk) {
+					sync::unstage_lines(
+						&self.repo.borrow(),
+						&self.current.path,
+						&changes,
+					)?;
+					self.queue_update();
+					return Ok(());
+				}
+
 				let hash = diff.hunks[hunk].header_hash;
 				sync::unstage_hunk(
 					&self.repo.borrow(),
@@ -515,9 +1298,31 @@ impl DiffComponent {
 		if let Some(diff) = &self.diff {
 			if let Some(hunk) = self.selected_hunk {
 				if diff.untracked {
-					sync::stage_add_file(
+					let large_files = sync::large_files(
+						&self.repo.borrow(),
+						&self.current.path,
+						self.options.borrow().status_show_untracked(),
+					)?;
+
+					if large_files.is_empty() {
+						sync::stage_add_file(
+							&self.repo.borrow(),
+							Path::new(&self.current.path),
+						)?;
+					} else {
+						self.queue.push(
+							InternalEvent::OpenLargeFiles(
+								large_files,
+							),
+						);
+					}
+				} else if let Some(changes) =
+					self.sub_hunk_changes(hunk)
+				{
+					sync::stage_lines(
 						&self.repo.borrow(),
-						Path::new(&self.current.path),
+						&self.current.path,
+						&changes,
 					)?;
 				} else {
 					let hash = diff.hunks[hunk].header_hash;
@@ -543,6 +1348,16 @@ impl DiffComponent {
 	fn reset_hunk(&self) {
 		if let Some(diff) = &self.diff {
 			if let Some(hunk) = self.selected_hunk {
+				if let Some(changes) = self.sub_hunk_changes(hunk) {
+					self.queue.push(InternalEvent::ConfirmAction(
+						Action::ResetLines(
+							self.current.path.clone(),
+							changes,
+						),
+					));
+					return;
+				}
+
 				let hash = diff.hunks[hunk].header_hash;
 
 				self.queue.push(InternalEvent::ConfirmAction(
@@ -565,24 +1380,29 @@ impl DiffComponent {
 	}
 
 	fn stage_lines(&self) {
-		if let Some(diff) = &self.diff {
-			//TODO: support untracked files as well
-			if !diff.untracked {
-				let selected_lines = self.selected_lines();
+		if self.diff.is_some() {
+			let selected_lines = self.selected_lines();
+			let repo = self.repo.borrow();
 
-				try_or_popup!(
-					self,
-					"(un)stage lines:",
+			try_or_popup!(
+				self,
+				"(un)stage lines:",
+				if self.is_stage() {
+					sync::unstage_lines(
+						&repo,
+						&self.current.path,
+						&selected_lines,
+					)
+				} else {
 					sync::stage_lines(
-						&self.repo.borrow(),
+						&repo,
 						&self.current.path,
-						self.is_stage(),
 						&selected_lines,
 					)
-				);
+				}
+			);
 
-				self.queue_update();
-			}
+			self.queue_update();
 		}
 	}
 
@@ -629,53 +1449,198 @@ impl DiffComponent {
 		Ok(())
 	}
 
-	fn calc_hunk_move_target(
+	/// the hunk `jump` leads to from hunk `from` (or from outside
+	/// the diff), including the ones not loaded yet
+	fn find_hunk(
 		&self,
-		direction: isize,
+		jump: HunkJump,
+		from: Option<usize>,
 	) -> Option<usize> {
-		let diff = self.diff.as_ref()?;
-		if diff.hunks.is_empty() {
-			return None;
+		let diff = self.full_diff.as_ref()?;
+		let is_target = |idx: &usize| {
+			!jump.conflicts || is_conflict_hunk(&diff.hunks[*idx])
+		};
+
+		if jump.forward {
+			let start = from.map_or(0, |idx| idx + 1);
+			(start..diff.hunks.len()).find(is_target)
+		} else {
+			let end = from.unwrap_or(diff.hunks.len());
+			(0..end).rev().find(is_target)
 		}
-		let max = diff.hunks.len() - 1;
-		let target_index = self.selected_hunk.map_or(0, |i| {
-			let target = if direction >= 0 {
-				i.saturating_add(direction.unsigned_abs())
-			} else {
-				i.saturating_sub(direction.unsigned_abs())
-			};
-			std::cmp::min(max, target)
-		});
-		Some(target_index)
 	}
 
-	fn diff_hunk_move_up_down(&mut self, direction: isize) {
-		let Some(diff) = &self.diff else { return };
-		let hunk_index = self.calc_hunk_move_target(direction);
-		// return if selected_hunk not change
-		if self.selected_hunk == hunk_index {
-			return;
+	/// whether `jump` stays within this diff
+	pub fn can_jump_hunk(&self, jump: HunkJump) -> bool {
+		self.find_hunk(jump, self.selected_hunk).is_some()
+	}
+
+	/// lets the hunk commands show up as possible at the ends of
+	/// the diff, for owners moving jumps on to the next file
+	#[must_use]
+	pub const fn with_jumps_across_files(mut self) -> Self {
+		self.jumps_across_files = true;
+		self
+	}
+
+	fn can_jump_hunk_cmd(&self, jump: HunkJump) -> bool {
+		if self.jumps_across_files {
+			!jump.conflicts || self.has_conflicts()
+		} else {
+			self.can_jump_hunk(jump)
 		}
-		if let Some(hunk_index) = hunk_index {
-			let line_index = diff
-				.hunks
-				.iter()
-				.take(hunk_index)
-				.fold(0, |sum, hunk| sum + hunk.lines.len());
-			let hunk = &diff.hunks[hunk_index];
-			self.selection = Selection::Single(line_index);
-			self.selected_hunk = Some(hunk_index);
-			self.vertical_scroll.move_area_to_visible(
-				self.current_size.get().1 as usize,
-				line_index,
-				line_index.saturating_add(hunk.lines.len()),
-			);
+	}
+
+	/// selects the hunk `jump` leads to, returns false if there is
+	/// none in this diff
+	pub fn jump_hunk(&mut self, jump: HunkJump) -> bool {
+		self.jump_hunk_from(jump, self.selected_hunk)
+	}
+
+	/// selects the first (or last) hunk `jump` leads to once the
+	/// diff of another file arrives
+	pub fn jump_hunk_on_update(&mut self, jump: HunkJump) {
+		self.pending_jump = Some(jump);
+	}
+
+	fn jump_hunk_from(
+		&mut self,
+		jump: HunkJump,
+		from: Option<usize>,
+	) -> bool {
+		let Some(hunk_index) = self.find_hunk(jump, from) else {
+			return false;
+		};
+
+		while self
+			.diff
+			.as_ref()
+			.is_some_and(|diff| diff.hunks.len() <= hunk_index)
+			&& self.hidden_lines() > 0
+		{
+			self.load_more();
 		}
+
+		let Some(diff) = &self.diff else { return false };
+		let Some(hunk) = diff.hunks.get(hunk_index) else {
+			return false;
+		};
+
+		let line_index = diff
+			.hunks
+			.iter()
+			.take(hunk_index)
+			.fold(0, |sum, hunk| sum + hunk.lines.len());
+		self.selection = Selection::Single(line_index);
+		self.selected_hunk = Some(hunk_index);
+		self.vertical_scroll.move_area_to_visible(
+			self.current_size.get().1 as usize,
+			line_index,
+			line_index.saturating_add(hunk.lines.len()),
+		);
+
+		true
+	}
+
+	fn commands_hunk_jumps(&self, out: &mut Vec<CommandInfo>) {
+		out.push(CommandInfo::new(
+			strings::commands::diff_hunk_next(&self.key_config),
+			self.can_jump_hunk_cmd(HunkJump {
+				forward: true,
+				conflicts: false,
+			}),
+			self.focused(),
+		));
+		out.push(CommandInfo::new(
+			strings::commands::diff_hunk_prev(&self.key_config),
+			self.can_jump_hunk_cmd(HunkJump {
+				forward: false,
+				conflicts: false,
+			}),
+			self.focused(),
+		));
+		if self.has_conflicts() {
+			out.push(CommandInfo::new(
+				strings::commands::diff_conflict_next(
+					&self.key_config,
+				),
+				self.can_jump_hunk_cmd(HunkJump {
+					forward: true,
+					conflicts: true,
+				}),
+				self.focused(),
+			));
+			out.push(CommandInfo::new(
+				strings::commands::diff_conflict_prev(
+					&self.key_config,
+				),
+				self.can_jump_hunk_cmd(HunkJump {
+					forward: false,
+					conflicts: true,
+				}),
+				self.focused(),
+			));
+		}
+	}
+
+	/// whether the diff has conflict markers in it
+	fn has_conflicts(&self) -> bool {
+		self.full_diff.as_ref().is_some_and(|diff| {
+			diff.hunks.iter().any(is_conflict_hunk)
+		})
 	}
 
 	const fn is_stage(&self) -> bool {
 		self.current.is_stage
 	}
+
+	/// line endings and encoding of the file, with the old ones if
+	/// they change
+	fn text_format_info(&self) -> Option<String> {
+		let format = self.diff.as_ref()?.text_format;
+		let new = format.new.or(format.old)?;
+
+		let changed = |old: String, new: String| match format.old {
+			Some(_) if old != new => format!("{old} → {new}"),
+			_ => new,
+		};
+		let old = format.old.unwrap_or(new);
+
+		Some(strings::diff_text_format(
+			&changed(
+				old.line_ending.to_string(),
+				new.line_ending.to_string(),
+			),
+			&changed(
+				old.encoding.to_string(),
+				new.encoding.to_string(),
+			),
+		))
+	}
+
+	/// the staged version has `CRLF` line endings to normalize
+	fn can_normalize_eol(&self) -> bool {
+		!self.is_immutable
+			&& self.is_stage()
+			&& self.diff.as_ref().is_some_and(|diff| {
+				diff.text_format.new.is_some_and(|format| {
+					matches!(
+						format.line_ending,
+						LineEnding::CrLf | LineEnding::Mixed
+					)
+				})
+			})
+	}
+
+	fn normalize_eol(&self) -> Result<()> {
+		sync::normalize_staged_eol(
+			&self.repo.borrow(),
+			&self.current.path,
+		)?;
+		self.queue_update();
+
+		Ok(())
+	}
 }
 
 impl DrawableComponent for DiffComponent {
@@ -684,26 +1649,57 @@ impl DrawableComponent for DiffComponent {
 			r.width.saturating_sub(2),
 			r.height.saturating_sub(2),
 		));
+		self.text_area.set(r.inner(Margin::new(1, 1)));
 
 		let current_width = self.current_size.get().0;
 		let current_height = self.current_size.get().1;
 
-		self.vertical_scroll.update(
-			self.selection.get_end(),
-			self.lines_count(),
-			usize::from(current_height),
-		);
+		if self.wrap_lines() {
+			let (rows, selected_first, selected_last) =
+				self.wrapped_rows();
+
+			self.vertical_scroll.update(
+				selected_last,
+				rows,
+				usize::from(current_height),
+			);
+			self.vertical_scroll.move_area_to_visible(
+				usize::from(current_height),
+				selected_first,
+				selected_last.saturating_add(1),
+			);
+		} else {
+			self.vertical_scroll.update(
+				self.selection.get_end(),
+				self.lines_count(),
+				usize::from(current_height),
+			);
+		}
 
 		self.horizontal_scroll.update_no_selection(
 			self.longest_line,
 			current_width.into(),
 		);
 
-		let title = format!(
+		let hidden = self.hidden_lines();
+		let mut title = format!(
 			"{}{}",
 			strings::title_diff(&self.key_config),
 			self.current.path
 		);
+		if let Some(info) = self.text_format_info() {
+			title = format!("{title} {info}");
+		}
+		if hidden > 0 {
+			title = format!(
+				"{title} {}",
+				strings::diff_lines_hidden(
+					&self.key_config,
+					self.lines_count(),
+					self.lines_count() + hidden,
+				)
+			);
+		}
 
 		let txt = if self.pending {
 			vec![Line::from(vec![Span::styled(
@@ -727,6 +1723,15 @@ impl DrawableComponent for DiffComponent {
 			r,
 		);
 
+		if !self.pending
+			&& self
+				.diff
+				.as_ref()
+				.is_some_and(|diff| diff.hunks.is_empty())
+		{
+			self.draw_images(f, r.inner(Margin::new(1, 1)));
+		}
+
 		if self.focused() {
 			self.vertical_scroll.draw(f, r, &self.theme);
 
@@ -739,6 +1744,13 @@ impl DrawableComponent for DiffComponent {
 	}
 }
 
+/// whether `hunk` has the start of a merge conflict in it
+fn is_conflict_hunk(hunk: &Hunk) -> bool {
+	hunk.lines
+		.iter()
+		.any(|line| line.content.starts_with("<<<<<<<"))
+}
+
 impl Component for DiffComponent {
 	fn commands(
 		&self,
@@ -750,16 +1762,27 @@ impl Component for DiffComponent {
 			self.can_scroll(),
 			self.focused(),
 		));
+		self.commands_hunk_jumps(out);
 		out.push(CommandInfo::new(
-			strings::commands::diff_hunk_next(&self.key_config),
-			self.calc_hunk_move_target(1) != self.selected_hunk,
-			self.focused(),
+			strings::commands::diff_split_hunk(&self.key_config),
+			self.can_split_hunk(),
+			self.focused() && !self.is_immutable,
 		));
 		out.push(CommandInfo::new(
-			strings::commands::diff_hunk_prev(&self.key_config),
-			self.calc_hunk_move_target(-1) != self.selected_hunk,
+			strings::commands::diff_edit_hunk(&self.key_config),
+			self.selected_editable_hunk().is_some(),
+			self.focused() && !self.is_immutable && !self.is_stage(),
+		));
+		out.push(CommandInfo::new(
+			strings::commands::diff_toggle_wrap(&self.key_config),
+			self.diff.is_some(),
 			self.focused(),
 		));
+		out.push(CommandInfo::new(
+			strings::commands::diff_load_more(&self.key_config),
+			true,
+			self.focused() && self.hidden_lines() > 0,
+		));
 		out.push(
 			CommandInfo::new(
 				strings::commands::diff_home_end(&self.key_config),
@@ -807,6 +1830,13 @@ impl Component for DiffComponent {
 				true,
 				self.focused() && self.is_stage(),
 			));
+			out.push(CommandInfo::new(
+				strings::commands::diff_normalize_eol(
+					&self.key_config,
+				),
+				self.can_normalize_eol(),
+				self.focused() && self.is_stage(),
+			));
 		}
 
 		out.push(CommandInfo::new(
@@ -815,12 +1845,22 @@ impl Component for DiffComponent {
 			self.focused(),
 		));
 
+		out.push(CommandInfo::new(
+			strings::commands::copy_menu(&self.key_config),
+			self.diff.is_some(),
+			self.focused(),
+		));
+
 		CommandBlocking::PassingOn
 	}
 
 	#[allow(clippy::cognitive_complexity, clippy::too_many_lines)]
 	fn event(&mut self, ev: &Event) -> Result<EventState> {
 		if self.focused() {
+			if let Event::Mouse(e) = ev {
+				return Ok(self.mouse_event(*e).into());
+			}
+
 			if let Event::Key(e) = ev {
 				return if key_match(e, self.key_config.keys.move_down)
 				{
@@ -868,13 +1908,51 @@ impl Component for DiffComponent {
 					e,
 					self.key_config.keys.diff_hunk_next,
 				) {
-					self.diff_hunk_move_up_down(1);
+					self.jump_hunk(HunkJump {
+						forward: true,
+						conflicts: false,
+					});
 					Ok(EventState::Consumed)
 				} else if key_match(
 					e,
 					self.key_config.keys.diff_hunk_prev,
 				) {
-					self.diff_hunk_move_up_down(-1);
+					self.jump_hunk(HunkJump {
+						forward: false,
+						conflicts: false,
+					});
+					Ok(EventState::Consumed)
+				} else if key_match(
+					e,
+					self.key_config.keys.diff_split_hunk,
+				) && !self.is_immutable
+				{
+					self.split_hunk();
+					Ok(EventState::Consumed)
+				} else if key_match(
+					e,
+					self.key_config.keys.diff_edit_hunk,
+				) && !self.is_immutable
+				{
+					self.edit_hunk();
+					Ok(EventState::Consumed)
+				} else if key_match(
+					e,
+					self.key_config.keys.diff_conflict_next,
+				) {
+					self.jump_hunk(HunkJump {
+						forward: true,
+						conflicts: true,
+					});
+					Ok(EventState::Consumed)
+				} else if key_match(
+					e,
+					self.key_config.keys.diff_conflict_prev,
+				) {
+					self.jump_hunk(HunkJump {
+						forward: false,
+						conflicts: true,
+					});
 					Ok(EventState::Consumed)
 				} else if key_match(
 					e,
@@ -922,9 +2000,43 @@ impl Component for DiffComponent {
 						}
 					}
 					Ok(EventState::Consumed)
+				} else if key_match(
+					e,
+					self.key_config.keys.diff_toggle_wrap,
+				) {
+					self.toggle_wrap_lines();
+					Ok(EventState::Consumed)
+				} else if key_match(
+					e,
+					self.key_config.keys.diff_load_more,
+				) && self.hidden_lines() > 0
+				{
+					self.load_more();
+					Ok(EventState::Consumed)
+				} else if key_match(
+					e,
+					self.key_config.keys.diff_normalize_eol,
+				) && self.can_normalize_eol()
+				{
+					try_or_popup!(
+						self,
+						"normalize error:",
+						self.normalize_eol()
+					);
+					Ok(EventState::Consumed)
 				} else if key_match(e, self.key_config.keys.copy) {
 					self.copy_selection();
 					Ok(EventState::Consumed)
+				} else if key_match(e, self.key_config.keys.copy_menu)
+				{
+					if self.diff.is_some() {
+						self.queue.push(
+							InternalEvent::OpenCopyPopup(
+								self.copy_context(),
+							),
+						);
+					}
+					Ok(EventState::Consumed)
 				} else {
 					Ok(EventState::NotConsumed)
 				};
@@ -950,6 +2062,30 @@ mod tests {
 	use std::rc::Rc;
 	use tempfile::NamedTempFile;
 
+	#[test]
+	fn test_truncate_diff() {
+		let hunk = |len: usize| Hunk {
+			header_hash: len as u64,
+			lines: vec![DiffLine::default(); len],
+		};
+		let diff = FileDiff {
+			hunks: vec![hunk(3), hunk(4)],
+			lines: 7,
+			..FileDiff::default()
+		};
+
+		let truncated = DiffComponent::truncate_diff(&diff, 5);
+		assert_eq!(truncated.lines, 5);
+		assert_eq!(truncated.hunks.len(), 2);
+		assert_eq!(truncated.hunks[1].lines.len(), 2);
+		assert_eq!(truncated.hunks[1].header_hash, 4);
+
+		let truncated = DiffComponent::truncate_diff(&diff, 3);
+		assert_eq!(truncated.hunks.len(), 1);
+
+		assert_eq!(DiffComponent::truncate_diff(&diff, 10).lines, 7);
+	}
+
 	#[test]
 	fn test_line_break() {
 		let diff_line = DiffLine {
@@ -995,8 +2131,10 @@ mod tests {
 			)
 			.unwrap();
 
-			let theme =
-				Rc::new(Theme::init(&file.path().to_path_buf()));
+			let theme = Rc::new(Theme::init(
+				&file.path().to_path_buf(),
+				false,
+			));
 
 			assert_eq!(
 				DiffComponent::get_line_to_add(
@@ -1012,4 +2150,106 @@ mod tests {
 			);
 		}
 	}
+
+	#[test]
+	fn test_accessible_markers() {
+		let theme = Rc::new(Theme::init(
+			&std::path::PathBuf::from("missing.ron"),
+			true,
+		));
+		let line = |line_type, content: &str| DiffLine {
+			line_type,
+			content: content.into(),
+			..DiffLine::default()
+		};
+
+		assert_eq!(
+			DiffComponent::line_content(
+				&line(DiffLineType::Add, "new"),
+				&theme
+			),
+			"+new"
+		);
+		assert_eq!(
+			DiffComponent::line_content(
+				&line(DiffLineType::Delete, "old"),
+				&theme
+			),
+			"-old"
+		);
+		assert_eq!(
+			DiffComponent::line_content(
+				&line(DiffLineType::None, "same"),
+				&theme
+			),
+			" same"
+		);
+	}
+
+	#[test]
+	fn test_wrapped_lines() {
+		let diff_line = DiffLine {
+			content: "abcdefg".into(),
+			line_type: DiffLineType::Add,
+			position: Default::default(),
+		};
+		let theme = Rc::new(Theme::default());
+
+		let lines = DiffComponent::get_wrapped_lines_to_add(
+			3, &diff_line, false, false, true, &theme,
+		);
+
+		assert_eq!(lines.len(), 3);
+		assert_eq!(
+			lines[0].spans[0].content,
+			Cow::from(symbols::line::VERTICAL)
+		);
+		assert_eq!(lines[1].spans[1].content, Cow::from("def\n"));
+		assert_eq!(
+			lines[2].spans[0].content,
+			Cow::from(symbols::line::BOTTOM_LEFT)
+		);
+	}
+
+	#[test]
+	fn test_selection_as_patch() {
+		let line = |content: &str, line_type, old, new| DiffLine {
+			content: content.into(),
+			line_type,
+			position: DiffLinePosition {
+				old_lineno: old,
+				new_lineno: new,
+			},
+		};
+
+		let env = Environment::test_env();
+		let mut diff = DiffComponent::new(&env, false);
+		diff.current.path = String::from("foo");
+		diff.diff = Some(FileDiff {
+			hunks: vec![sync::diff::Hunk {
+				header_hash: 0,
+				lines: vec![
+					line(
+						"@@ -1,3 +1,3 @@",
+						DiffLineType::Header,
+						None,
+						None,
+					),
+					line("a\n", DiffLineType::None, Some(1), Some(1)),
+					line("b\n", DiffLineType::Delete, Some(2), None),
+					line("c\n", DiffLineType::Add, None, Some(2)),
+					line("d\n", DiffLineType::None, Some(3), Some(3)),
+				],
+			}],
+			lines: 5,
+			..FileDiff::default()
+		});
+		diff.selection = Selection::Multiple(2, 3);
+
+		assert_eq!(
+			diff.selection_as_patch().unwrap(),
+			"diff --git a/foo b/foo\n--- a/foo\n+++ b/foo\n\
+			 @@ -2,1 +2,1 @@\n-b\n+c\n"
+		);
+	}
 }
@@ -45,6 +45,7 @@ mod revision_files;
 mod status_tree;
 mod syntax_text;
 mod textinput;
+mod untracked_notice;
 mod utils;
 
 pub use self::status_tree::StatusTreeComponent;
@@ -53,14 +54,18 @@ pub use command::{CommandInfo, CommandText};
 pub use commit_details::CommitDetailsComponent;
 pub use commitlist::CommitList;
 pub use cred::CredComponent;
-pub use diff::DiffComponent;
+pub use diff::{DiffComponent, HunkJump};
 pub use revision_files::RevisionFilesComponent;
 pub use syntax_text::SyntaxTextComponent;
 pub use textinput::{InputType, TextInputComponent};
+pub use untracked_notice::UntrackedNotice;
 pub use utils::{
-	filetree::FileTreeItemKind, logitems::ItemBatch,
-	scroll_vertical::VerticalScroll, string_width_align,
-	time_to_string,
+	checkout_with_hook,
+	filetree::FileTreeItemKind,
+	logitems::ItemBatch,
+	mouse::{list_mouse, tab_at, ListMouse},
+	scroll_vertical::VerticalScroll,
+	string_width_align, time_to_string,
 };
 
 use crate::ui::style::Theme;
@@ -6,8 +6,10 @@ use super::{
 use crate::{
 	app::Environment,
 	keys::{key_match, SharedKeyConfig},
-	popups::{BlameFileOpen, FileRevOpen},
+	options::{SharedOptions, SplitTab},
+	popups::{BlameFileOpen, CopyContext, FileRevOpen},
 	queue::{InternalEvent, Queue, StackablePopupOpen},
+	string_utils::{pad_width, str_width, truncate_width},
 	strings::{self, order, symbol},
 	try_or_popup,
 	ui::{self, common_nav, style::SharedTheme},
@@ -34,8 +36,6 @@ use std::{
 	collections::BTreeSet,
 	path::{Path, PathBuf},
 };
-use unicode_truncate::UnicodeTruncateStr;
-use unicode_width::UnicodeWidthStr;
 
 enum Focus {
 	Tree,
@@ -56,6 +56,7 @@ pub struct RevisionFilesComponent {
 	revision: Option<CommitInfo>,
 	focus: Focus,
 	key_config: SharedKeyConfig,
+	options: SharedOptions,
 	select_file: Option<PathBuf>,
 }
 
@@ -78,6 +79,7 @@ impl RevisionFilesComponent {
 			revision: None,
 			focus: Focus::Tree,
 			key_config: env.key_config.clone(),
+			options: env.options.clone(),
 			repo: env.repo.clone(),
 			select_file,
 			visible: false,
@@ -190,11 +192,12 @@ impl RevisionFilesComponent {
 			symbol::EMPTY_STR
 		};
 
-		let available_width =
-			width.saturating_sub(indent_str.len() + path_arrow.len());
+		let available_width = width
+			.saturating_sub(indent_str.len() + str_width(path_arrow));
 
 		let path = format!(
-			"{indent_str}{path_arrow}{path:available_width$}"
+			"{indent_str}{path_arrow}{}",
+			pad_width(path, available_width)
 		);
 
 		Span::styled(path, theme.file_tree_item(is_path, selected))
@@ -243,6 +246,15 @@ impl RevisionFilesComponent {
 		}
 	}
 
+	/// selects `file` now or once the files of the commit are loaded
+	pub fn select_file(&mut self, file: PathBuf) {
+		if self.files.is_some() {
+			self.find_file(&file);
+		} else {
+			self.select_file = Some(file);
+		}
+	}
+
 	pub fn find_file(&mut self, file: &Path) {
 		self.tree.collapse_but_root();
 		if self.tree.select_file(file) {
@@ -277,6 +289,7 @@ impl RevisionFilesComponent {
 						return self.current_file.load_file(
 							path.to_string_lossy().to_string(),
 							item,
+							self.revision.as_ref().map(|c| c.id),
 						);
 					}
 				}
@@ -364,18 +377,18 @@ impl RevisionFilesComponent {
 			const ELLIPSIS: char = '\u{2026}'; // …
 
 			let available = tree_width
-				.saturating_sub(title.width())
+				.saturating_sub(str_width(&title))
 				.saturating_sub(
 					2 /* frame end corners */ + 1 /* space */ + 2, /* square brackets */
 				);
 
-			if message.width() <= available {
+			if str_width(message) <= available {
 				write!(title, " [{message}]")?;
 			} else if available > 1 {
 				write!(
 					title,
 					" [{}{}]",
-					message.unicode_truncate(available - 1).0,
+					truncate_width(message, available - 1),
 					ELLIPSIS
 				)?;
 			} else {
@@ -397,12 +410,14 @@ impl RevisionFilesComponent {
 impl DrawableComponent for RevisionFilesComponent {
 	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
 		if self.is_visible() {
+			let ratio =
+				self.options.borrow().split_ratio(SplitTab::Files);
 			let chunks = Layout::default()
 				.direction(Direction::Horizontal)
 				.constraints(
 					[
-						Constraint::Percentage(40),
-						Constraint::Percentage(60),
+						Constraint::Percentage(ratio),
+						Constraint::Percentage(100 - ratio),
 					]
 					.as_ref(),
 				)
@@ -460,6 +475,14 @@ impl Component for RevisionFilesComponent {
 				)
 				.order(order::RARE_ACTION),
 			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::copy_menu(&self.key_config),
+					self.tree.selected_file().is_some(),
+					true,
+				)
+				.order(order::RARE_ACTION),
+			);
 			tree_nav_cmds(&self.tree, &self.key_config, out);
 		} else {
 			self.current_file.commands(out, force_all);
@@ -537,6 +560,20 @@ impl Component for RevisionFilesComponent {
 					);
 				}
 				return Ok(EventState::Consumed);
+			} else if key_match(key, self.key_config.keys.copy_menu) {
+				if let Some(file) = self.selected_file_path() {
+					self.queue.push(InternalEvent::OpenCopyPopup(
+						CopyContext {
+							commit: self
+								.revision
+								.as_ref()
+								.map(|c| c.id),
+							path: Some(file),
+							..CopyContext::default()
+						},
+					));
+				}
+				return Ok(EventState::Consumed);
 			} else if !is_tree_focused {
 				return self.current_file.event(event);
 			}
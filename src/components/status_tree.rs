@@ -1,24 +1,37 @@
 use super::{
+	list_mouse,
 	utils::{
 		filetree::{FileTreeItem, FileTreeItemKind},
 		statustree::{MoveSelection, StatusTree},
 	},
-	CommandBlocking, DrawableComponent,
+	CommandBlocking, DrawableComponent, ListMouse, ScrollType,
 };
 use crate::{
 	app::Environment,
 	components::{CommandInfo, Component, EventState},
 	keys::{key_match, SharedKeyConfig},
-	popups::{BlameFileOpen, FileRevOpen},
-	queue::{InternalEvent, NeedsUpdate, Queue, StackablePopupOpen},
-	strings::{self, order},
+	popups::{BlameFileOpen, CopyContext, FileRevOpen},
+	queue::{
+		Action, InternalEvent, NeedsUpdate, Queue, StackablePopupOpen,
+	},
+	string_utils::pad_width,
+	strings::{self, order, symbol},
 	ui::{self, style::SharedTheme},
 };
 use anyhow::Result;
 use asyncgit::{hash, sync::CommitId, StatusItem, StatusItemType};
-use crossterm::event::Event;
-use ratatui::{layout::Rect, text::Span, Frame};
-use std::{borrow::Cow, cell::Cell, path::Path};
+use crossterm::event::{Event, MouseEvent};
+use ratatui::{
+	layout::{Margin, Position, Rect},
+	text::Span,
+	Frame,
+};
+use std::{
+	borrow::Cow,
+	cell::Cell,
+	collections::{BTreeSet, HashSet},
+	path::Path,
+};
 
 //TODO: use new `filetreelist` crate
 
@@ -35,8 +48,10 @@ pub struct StatusTreeComponent {
 	theme: SharedTheme,
 	key_config: SharedKeyConfig,
 	scroll_top: Cell<usize>,
+	list_area: Cell<Rect>,
 	visible: bool,
 	revision: Option<CommitId>,
+	marked: BTreeSet<String>,
 }
 
 impl StatusTreeComponent {
@@ -52,9 +67,11 @@ impl StatusTreeComponent {
 			theme: env.theme.clone(),
 			key_config: env.key_config.clone(),
 			scroll_top: Cell::new(0),
+			list_area: Cell::new(Rect::default()),
 			pending: true,
 			visible: false,
 			revision: None,
+			marked: BTreeSet::new(),
 		}
 	}
 
@@ -70,6 +87,10 @@ impl StatusTreeComponent {
 		if self.current_hash != new_hash {
 			self.tree.update(list)?;
 			self.current_hash = new_hash;
+
+			let paths: HashSet<&str> =
+				list.iter().map(|item| item.path.as_str()).collect();
+			self.marked.retain(|path| paths.contains(path.as_str()));
 		}
 
 		Ok(())
@@ -91,6 +112,79 @@ impl StatusTreeComponent {
 		})
 	}
 
+	/// the selected file or all files in the selected folder
+	pub fn selected_files(&self) -> Vec<String> {
+		let Some(item) = self.selection() else {
+			return Vec::new();
+		};
+
+		match item.kind {
+			FileTreeItemKind::File(file) => vec![file.path],
+			FileTreeItemKind::Path(_) => {
+				let folder = format!("{}/", item.info.full_path);
+				self.tree
+					.tree
+					.items()
+					.iter()
+					.filter_map(|item| match &item.kind {
+						FileTreeItemKind::File(file)
+							if file.path.starts_with(&folder) =>
+						{
+							Some(file.path.clone())
+						}
+						_ => None,
+					})
+					.collect()
+			}
+		}
+	}
+
+	/// marks or unmarks the selected file or all files in the
+	/// selected folder
+	pub fn toggle_mark(&mut self) -> bool {
+		let paths = self.selected_files();
+		if paths.is_empty() {
+			return false;
+		}
+
+		if paths.iter().all(|path| self.marked.contains(path)) {
+			for path in &paths {
+				self.marked.remove(path);
+			}
+		} else {
+			self.marked.extend(paths);
+		}
+
+		true
+	}
+
+	///
+	pub fn marked_count(&self) -> usize {
+		self.marked.len()
+	}
+
+	/// the marked files in list order
+	pub fn marked_files(&self) -> Vec<StatusItem> {
+		self.tree
+			.tree
+			.items()
+			.iter()
+			.filter_map(|item| match &item.kind {
+				FileTreeItemKind::File(file)
+					if self.marked.contains(&file.path) =>
+				{
+					Some(file.clone())
+				}
+				_ => None,
+			})
+			.collect()
+	}
+
+	///
+	pub fn clear_marks(&mut self) {
+		self.marked.clear();
+	}
+
 	///
 	pub fn show_selection(&mut self, show: bool) {
 		self.show_selection = show;
@@ -128,6 +222,22 @@ impl StatusTreeComponent {
 		})
 	}
 
+	/// selects the next (or previous) visible file matching
+	/// `filter`, returns false if there is none
+	pub fn select_file(
+		&mut self,
+		forward: bool,
+		filter: impl Fn(&StatusItem) -> bool,
+	) -> bool {
+		let changed = self.tree.select_file(forward, filter);
+
+		if changed {
+			self.queue.push(InternalEvent::Update(NeedsUpdate::DIFF));
+		}
+
+		changed
+	}
+
 	fn move_selection(&mut self, dir: MoveSelection) -> bool {
 		let changed = self.tree.move_selection(dir);
 
@@ -138,40 +248,96 @@ impl StatusTreeComponent {
 		changed
 	}
 
+	/// whether a mouse event happened on the list
+	pub fn contains_mouse(&self, ev: MouseEvent) -> bool {
+		self.list_area
+			.get()
+			.contains(Position::new(ev.column, ev.row))
+	}
+
+	/// row the selection is drawn in, not counting the scroll offset
+	fn selected_row(&self) -> usize {
+		let (select, hidden) = self.selection_rows();
+
+		select.saturating_sub(hidden)
+	}
+
+	/// walks the selection to the item drawn in `row` since folded
+	/// and collapsed folders make rows and items differ
+	fn select_row(&mut self, row: usize) -> bool {
+		let target = self.scroll_top.get() + row;
+		let mut changed = false;
+
+		for _ in 0..self.tree.tree.len() {
+			let current = self.selected_row();
+			let dir = match current.cmp(&target) {
+				std::cmp::Ordering::Equal => break,
+				std::cmp::Ordering::Less => MoveSelection::Down,
+				std::cmp::Ordering::Greater => MoveSelection::Up,
+			};
+			if !self.tree.move_selection(dir) {
+				break;
+			}
+			changed = true;
+		}
+
+		if changed {
+			self.queue.push(InternalEvent::Update(NeedsUpdate::DIFF));
+		}
+
+		changed
+	}
+
+	fn mouse_event(&mut self, ev: MouseEvent) -> bool {
+		match list_mouse(ev, self.list_area.get()) {
+			Some(ListMouse::Scroll(scroll)) => {
+				self.move_selection(match scroll {
+					ScrollType::Up => MoveSelection::Up,
+					_ => MoveSelection::Down,
+				})
+			}
+			Some(ListMouse::Click(row)) => self.select_row(row),
+			None => false,
+		}
+	}
+
 	const fn item_status_char(item_type: StatusItemType) -> char {
 		match item_type {
 			StatusItemType::Modified => 'M',
 			StatusItemType::New => '+',
 			StatusItemType::Deleted => '-',
 			StatusItemType::Renamed => 'R',
-			StatusItemType::Typechange => ' ',
+			StatusItemType::Typechange => 'T',
+			StatusItemType::ModeChange => 'X',
 			StatusItemType::Conflicted => '!',
+			StatusItemType::Ignored => 'I',
 		}
 	}
 
 	fn item_to_text<'b>(
 		string: &str,
 		indent: usize,
-		visible: bool,
 		file_item_kind: &FileTreeItemKind,
 		width: u16,
 		selected: bool,
+		marked: bool,
 		theme: &'b SharedTheme,
-	) -> Option<Span<'b>> {
+	) -> Span<'b> {
 		let indent_str = if indent == 0 {
 			String::new()
 		} else {
 			format!("{:w$}", " ", w = indent * 2)
 		};
 
-		if !visible {
-			return None;
-		}
-
 		match file_item_kind {
 			FileTreeItemKind::File(status_item) => {
 				let status_char =
 					Self::item_status_char(status_item.status);
+				let mark = if marked {
+					symbol::CHECKMARK
+				} else {
+					symbol::EMPTY_SPACE
+				};
 				let file = Path::new(&status_item.path)
 					.file_name()
 					.and_then(std::ffi::OsStr::to_str)
@@ -179,20 +345,20 @@ impl StatusTreeComponent {
 
 				let txt = if selected {
 					format!(
-						"{} {}{:w$}",
+						"{}{}{}{}",
 						status_char,
+						mark,
 						indent_str,
-						file,
-						w = width as usize
+						pad_width(file, width as usize)
 					)
 				} else {
-					format!("{status_char} {indent_str}{file}")
+					format!("{status_char}{mark}{indent_str}{file}")
 				};
 
-				Some(Span::styled(
+				Span::styled(
 					Cow::from(txt),
 					theme.item(status_item.status, selected),
-				))
+				)
 			}
 
 			FileTreeItemKind::Path(path_collapsed) => {
@@ -201,99 +367,38 @@ impl StatusTreeComponent {
 
 				let txt = if selected {
 					format!(
-						"  {}{}{:w$}",
+						"  {}{}{}",
 						indent_str,
 						collapse_char,
-						string,
-						w = width as usize
+						pad_width(string, width as usize)
 					)
 				} else {
-					format!("  {indent_str}{collapse_char}{string}",)
+					format!("  {indent_str}{collapse_char}{string}")
 				};
 
-				Some(Span::styled(
+				Span::styled(
 					Cow::from(txt),
 					theme.text(true, selected),
-				))
+				)
 			}
 		}
 	}
 
-	/// Returns a `Vec<TextDrawInfo>` which is used to draw the `FileTreeComponent` correctly,
-	/// allowing folders to be folded up if they are alone in their directory
-	fn build_vec_text_draw_info_for_drawing(
-		&self,
-	) -> (Vec<TextDrawInfo<'_>>, usize, usize) {
-		let mut should_skip_over: usize = 0;
-		let mut selection_offset: usize = 0;
-		let mut selection_offset_visible: usize = 0;
-		let mut vec_draw_text_info: Vec<TextDrawInfo> = vec![];
-		let tree_items = self.tree.tree.items();
-
-		for (index, item) in tree_items.iter().enumerate() {
-			if should_skip_over > 0 {
-				should_skip_over -= 1;
-				continue;
-			}
-
-			let index_above_select =
-				index < self.tree.selection.unwrap_or(0);
-
-			if !item.info.visible && index_above_select {
-				selection_offset_visible += 1;
-			}
-
-			vec_draw_text_info.push(TextDrawInfo {
-				name: item.info.path.clone(),
-				indent: item.info.indent,
-				visible: item.info.visible,
-				item_kind: &item.kind,
-			});
-
-			let mut idx_temp = index;
-
-			while idx_temp < tree_items.len().saturating_sub(2)
-				&& tree_items[idx_temp].info.indent
-					< tree_items[idx_temp + 1].info.indent
-			{
-				// fold up the folder/file
-				idx_temp += 1;
-				should_skip_over += 1;
-
-				// don't fold files up
-				if let FileTreeItemKind::File(_) =
-					&tree_items[idx_temp].kind
-				{
-					should_skip_over -= 1;
-					break;
-				}
-				// don't fold up if more than one folder in folder
-				else if self
-					.tree
-					.tree
-					.multiple_items_at_path(idx_temp)
-				{
-					should_skip_over -= 1;
-					break;
-				}
-
-				// There is only one item at this level (i.e only one folder in the folder),
-				// so do fold up
+	/// Returns the row the selection is in and how many rows above
+	/// it are hidden inside collapsed folders
+	fn selection_rows(&self) -> (usize, usize) {
+		let selection = self.tree.selection.unwrap_or(0);
+		let select = self
+			.tree
+			.available_selections
+			.partition_point(|idx| *idx < selection);
+		let hidden = select
+			- self
+				.tree
+				.visible_rows
+				.partition_point(|row| *row < select);
 
-				let vec_draw_text_info_len = vec_draw_text_info.len();
-				vec_draw_text_info[vec_draw_text_info_len - 1]
-					.name += &(String::from("/")
-					+ &tree_items[idx_temp].info.path);
-				if index_above_select {
-					selection_offset += 1;
-				}
-			}
-		}
-		(
-			vec_draw_text_info,
-			selection_offset,
-			selection_offset_visible,
-		)
+		(select, hidden)
 	}
 
 	// Copy the real path of selected file to clickboard
@@ -309,6 +414,39 @@ impl StatusTreeComponent {
 		}
 	}
 
+	fn open_copy_menu(&self) {
+		if let Some(status_item) = self.selection_file() {
+			self.queue.push(InternalEvent::OpenCopyPopup(
+				CopyContext {
+					commit: self.revision,
+					path: Some(status_item.path),
+					..CopyContext::default()
+				},
+			));
+		}
+	}
+
+	/// asks to overwrite the selected file in the workdir with its
+	/// version in `revision`
+	fn restore_version(&self) {
+		match (self.revision, self.selection_file()) {
+			(Some(commit), Some(status_item))
+				if !matches!(
+					status_item.status,
+					StatusItemType::Deleted
+				) =>
+			{
+				self.queue.push(InternalEvent::ConfirmAction(
+					Action::CheckoutFile {
+						commit,
+						path: status_item.path,
+					},
+				));
+			}
+			_ => {}
+		}
+	}
+
 	fn open_history(&mut self) {
 		match self.selection_file() {
 			Some(status_item)
@@ -329,20 +467,14 @@ impl StatusTreeComponent {
 	}
 }
 
-/// Used for drawing the `FileTreeComponent`
-struct TextDrawInfo<'a> {
-	name: String,
-	indent: u8,
-	visible: bool,
-	item_kind: &'a FileTreeItemKind,
-}
-
 impl DrawableComponent for StatusTreeComponent {
 	fn draw(&self, f: &mut Frame, r: Rect) -> Result<()> {
 		if !self.is_visible() {
 			return Ok(());
 		}
 
+		self.list_area.set(r.inner(Margin::new(1, 1)));
+
 		if self.pending {
 			let items = vec![Span::styled(
 				Cow::from(strings::loading_text(&self.key_config)),
@@ -358,41 +490,40 @@ impl DrawableComponent for StatusTreeComponent {
 				&self.theme,
 			);
 		} else {
-			let (
-				vec_draw_text_info,
-				selection_offset,
-				selection_offset_visible,
-			) = self.build_vec_text_draw_info_for_drawing();
-
-			let select = self
-				.tree
-				.selection
-				.map(|idx| idx.saturating_sub(selection_offset))
-				.unwrap_or_default();
+			let (select, hidden) = self.selection_rows();
 			let tree_height = r.height.saturating_sub(2) as usize;
 			self.tree.window_height.set(Some(tree_height));
 
 			self.scroll_top.set(ui::calc_scroll_top(
 				self.scroll_top.get(),
 				tree_height,
-				select.saturating_sub(selection_offset_visible),
+				select.saturating_sub(hidden),
 			));
 
-			let items = vec_draw_text_info
+			// only the rows in view get laid out
+			let items = self
+				.tree
+				.visible_rows
 				.iter()
-				.enumerate()
-				.filter_map(|(index, draw_text_info)| {
+				.skip(self.scroll_top.get())
+				.take(tree_height)
+				.map(|&row| {
+					let item = &self.tree.tree
+						[self.tree.available_selections[row]];
 					Self::item_to_text(
-						&draw_text_info.name,
-						draw_text_info.indent as usize,
-						draw_text_info.visible,
-						draw_text_info.item_kind,
+						&self.tree.row_names[row],
+						item.info.indent as usize,
+						&item.kind,
 						r.width,
-						self.show_selection && select == index,
+						self.show_selection && select == row,
+						matches!(
+							&item.kind,
+							FileTreeItemKind::File(file)
+								if self.marked.contains(&file.path)
+						),
 						&self.theme,
 					)
-				})
-				.skip(self.scroll_top.get());
+				});
 
 			ui::draw_list(
 				f,
@@ -402,6 +533,18 @@ impl DrawableComponent for StatusTreeComponent {
 				self.focused,
 				&self.theme,
 			);
+
+			ui::draw_scrollbar(
+				f,
+				r,
+				&self.theme,
+				self.tree
+					.visible_rows
+					.len()
+					.saturating_sub(tree_height),
+				self.scroll_top.get(),
+				ui::Orientation::Vertical,
+			);
 		}
 
 		Ok(())
@@ -417,7 +560,7 @@ impl Component for StatusTreeComponent {
 		let available = self.focused || force_all;
 		let selection = self.selection_file();
 		let selected_is_file = selection.is_some();
-		let tracked = selection.is_some_and(|s| {
+		let tracked = selection.as_ref().is_some_and(|s| {
 			!matches!(s.status, StatusItemType::New)
 		});
 
@@ -468,11 +611,40 @@ impl Component for StatusTreeComponent {
 			.order(order::RARE_ACTION),
 		);
 
+		out.push(
+			CommandInfo::new(
+				strings::commands::copy_menu(&self.key_config),
+				selected_is_file,
+				available,
+			)
+			.order(order::RARE_ACTION),
+		);
+
+		if self.revision.is_some() {
+			out.push(
+				CommandInfo::new(
+					strings::commands::restore_version(
+						&self.key_config,
+					),
+					selection.is_some_and(|s| {
+						!matches!(s.status, StatusItemType::Deleted)
+					}),
+					available,
+				)
+				.order(order::RARE_ACTION),
+			);
+		}
+
 		CommandBlocking::PassingOn
 	}
 
+	#[allow(clippy::too_many_lines)]
 	fn event(&mut self, ev: &Event) -> Result<EventState> {
 		if self.focused {
+			if let Event::Mouse(m) = ev {
+				return Ok(self.mouse_event(*m).into());
+			}
+
 			if let Event::Key(e) = ev {
 				return if key_match(e, self.key_config.keys.blame) {
 					match self.selection_file() {
@@ -518,6 +690,17 @@ impl Component for StatusTreeComponent {
 				} else if key_match(e, self.key_config.keys.copy) {
 					self.copy_file_path();
 					Ok(EventState::Consumed)
+				} else if self.revision.is_some()
+					&& key_match(
+						e,
+						self.key_config.keys.restore_version,
+					) {
+					self.restore_version();
+					Ok(EventState::Consumed)
+				} else if key_match(e, self.key_config.keys.copy_menu)
+				{
+					self.open_copy_menu();
+					Ok(EventState::Consumed)
 				} else if key_match(e, self.key_config.keys.move_down)
 				{
 					Ok(self
@@ -1,14 +1,15 @@
 use super::{
-	CommandBlocking, CommandInfo, Component, DrawableComponent,
-	EventState,
+	string_width_align, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState,
 };
 use crate::{
 	app::Environment,
-	keys::SharedKeyConfig,
+	keys::{key_match, SharedKeyConfig},
 	string_utils::tabs_to_spaces,
 	strings,
 	ui::{
-		self, common_nav, style::SharedTheme, AsyncSyntaxJob,
+		self, age_short, common_nav, style::SharedTheme,
+		AsyncInlineBlameJob, AsyncSyntaxJob, InlineBlame,
 		ParagraphState, ScrollPos, StatefulParagraph,
 	},
 	AsyncAppNotification, AsyncNotification, SyntaxHighlightProgress,
@@ -16,7 +17,7 @@ use crate::{
 use anyhow::Result;
 use asyncgit::{
 	asyncjob::AsyncSingleJob,
-	sync::{self, RepoPathRef, TreeFile},
+	sync::{self, CommitId, RepoPathRef, TreeFile},
 	ProgressPercent,
 };
 use crossterm::event::Event;
@@ -24,17 +25,33 @@ use filetreelist::MoveSelection;
 use itertools::Either;
 use ratatui::{
 	layout::Rect,
-	text::Text,
+	text::{Line, Span, Text},
 	widgets::{Block, Borders, Wrap},
 	Frame,
 };
-use std::{cell::Cell, path::Path};
+use std::{
+	cell::Cell,
+	collections::HashMap,
+	path::Path,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+/// blamed files kept around to toggle and switch back quickly
+const BLAME_CACHE_SIZE: usize = 32;
+/// width of the summary in the blame gutter at most
+const BLAME_SUMMARY_WIDTH: usize = 30;
 
 pub struct SyntaxTextComponent {
 	repo: RepoPathRef,
 	current_file: Option<(String, Either<ui::SyntaxText, String>)>,
 	async_highlighting: AsyncSingleJob<AsyncSyntaxJob>,
 	syntax_progress: Option<ProgressPercent>,
+	/// revision the current file is shown at
+	current_commit: Option<CommitId>,
+	/// blame gutter next to the lines
+	show_blame: bool,
+	async_blame: AsyncSingleJob<AsyncInlineBlameJob>,
+	blames: HashMap<(String, CommitId), InlineBlame>,
 	key_config: SharedKeyConfig,
 	paragraph_state: Cell<ParagraphState>,
 	focused: bool,
@@ -49,6 +66,10 @@ impl SyntaxTextComponent {
 				env.sender_app.clone(),
 			),
 			syntax_progress: None,
+			current_commit: None,
+			show_blame: false,
+			async_blame: AsyncSingleJob::new(env.sender_app.clone()),
+			blames: HashMap::new(),
 			current_file: None,
 			paragraph_state: Cell::new(ParagraphState::default()),
 			focused: false,
@@ -60,6 +81,13 @@ impl SyntaxTextComponent {
 
 	///
 	pub fn update(&mut self, ev: AsyncNotification) {
+		if ev
+			== AsyncNotification::App(
+				AsyncAppNotification::InlineBlame,
+			) {
+			self.update_blame();
+		}
+
 		if let AsyncNotification::App(
 			AsyncAppNotification::SyntaxHighlighting(progress),
 		) = ev
@@ -89,9 +117,117 @@ impl SyntaxTextComponent {
 		}
 	}
 
+	fn update_blame(&mut self) {
+		if let Some(blame) =
+			self.async_blame.take_last().and_then(|job| job.result())
+		{
+			if self.blames.len() >= BLAME_CACHE_SIZE {
+				self.blames.clear();
+			}
+
+			self.blames
+				.insert((blame.path.clone(), blame.commit), blame);
+		}
+
+		// the file changed while the last one was blamed
+		self.request_blame();
+	}
+
+	fn current_blame(&self) -> Option<&InlineBlame> {
+		let (path, _) = self.current_file.as_ref()?;
+		let commit = self.current_commit?;
+
+		self.blames.get(&(path.clone(), commit))
+	}
+
+	/// blames the current file unless it is cached or being blamed
+	fn request_blame(&self) {
+		if !self.show_blame || self.async_blame.is_pending() {
+			return;
+		}
+
+		let (Some((path, _)), Some(commit)) =
+			(self.current_file.as_ref(), self.current_commit)
+		else {
+			return;
+		};
+
+		if !self.blames.contains_key(&(path.clone(), commit)) {
+			self.async_blame.spawn(AsyncInlineBlameJob::new(
+				self.repo.borrow().clone(),
+				path.clone(),
+				commit,
+			));
+		}
+	}
+
+	fn toggle_blame(&mut self) {
+		self.show_blame = !self.show_blame;
+		self.request_blame();
+	}
+
+	/// commit, age and summary of `line` on the first line of a hunk
+	fn blame_gutter(
+		blame: &InlineBlame,
+		line: usize,
+		now: i64,
+		summary_width: usize,
+	) -> String {
+		match blame.line(line) {
+			Some((commit, true)) => format!(
+				"{} {:>3} {} ",
+				commit.id.get_short_string(),
+				age_short(now - commit.time),
+				string_width_align(&commit.summary, summary_width)
+			),
+			_ => format!(
+				"{:w$}",
+				"",
+				w = CommitId::default().get_short_string().len()
+					+ summary_width + 6
+			),
+		}
+	}
+
+	fn with_blame<'a>(
+		&self,
+		text: Text<'a>,
+		blame: &InlineBlame,
+		width: u16,
+	) -> Text<'a> {
+		let now = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map_or(0, |d| i64::try_from(d.as_secs()).unwrap_or(0));
+		let summary_width =
+			BLAME_SUMMARY_WIDTH.min(usize::from(width / 4));
+
+		Text::from(
+			text.lines
+				.into_iter()
+				.enumerate()
+				.map(|(idx, mut line)| {
+					line.spans.insert(
+						0,
+						Span::styled(
+							Self::blame_gutter(
+								blame,
+								idx,
+								now,
+								summary_width,
+							),
+							self.theme.commit_time(false),
+						),
+					);
+					line
+				})
+				.collect::<Vec<Line>>(),
+		)
+	}
+
 	///
 	pub fn any_work_pending(&self) -> bool {
 		self.async_highlighting.is_pending()
+			|| self.async_blame.is_pending()
 	}
 
 	///
@@ -99,12 +235,19 @@ impl SyntaxTextComponent {
 		self.current_file = None;
 	}
 
-	///
-	pub fn load_file(&mut self, path: String, item: &TreeFile) {
-		let already_loaded = self
-			.current_file
-			.as_ref()
-			.is_some_and(|(current_file, _)| current_file == &path);
+	/// shows `item` at `path` as it is in `commit`
+	pub fn load_file(
+		&mut self,
+		path: String,
+		item: &TreeFile,
+		commit: Option<CommitId>,
+	) {
+		let already_loaded = self.current_commit == commit
+			&& self.current_file.as_ref().is_some_and(
+				|(current_file, _)| current_file == &path,
+			);
+
+		self.current_commit = commit;
 
 		if !already_loaded {
 			//TODO: fetch file content async as well
@@ -113,7 +256,7 @@ impl SyntaxTextComponent {
 					let content = tabs_to_spaces(content);
 					self.syntax_progress =
 						Some(ProgressPercent::empty());
-					self.async_highlighting.spawn(
+					self.async_highlighting.supersede(
 						AsyncSyntaxJob::new(
 							content.clone(),
 							path.clone(),
@@ -133,6 +276,8 @@ impl SyntaxTextComponent {
 					));
 				}
 			}
+
+			self.request_blame();
 		}
 	}
 
@@ -193,15 +338,27 @@ impl DrawableComponent for SyntaxTextComponent {
 			},
 		);
 
+		let text = match (self.show_blame, self.current_blame()) {
+			(true, Some(blame)) => {
+				self.with_blame(text, blame, area.width)
+			}
+			_ => text,
+		};
+
 		let title = format!(
-			"{}{}",
+			"{}{}{}",
 			self.current_file
 				.as_ref()
 				.map(|(name, _)| name.clone())
 				.unwrap_or_default(),
 			self.syntax_progress
 				.map(|p| format!(" ({}%)", p.progress))
-				.unwrap_or_default()
+				.unwrap_or_default(),
+			if self.show_blame && self.async_blame.is_pending() {
+				strings::INLINE_BLAME_PENDING
+			} else {
+				""
+			}
 		);
 
 		let content = StatefulParagraph::new(text)
@@ -253,6 +410,14 @@ impl Component for SyntaxTextComponent {
 				)
 				.order(strings::order::NAV),
 			);
+			out.push(CommandInfo::new(
+				strings::commands::toggle_inline_blame(
+					&self.key_config,
+					self.show_blame,
+				),
+				self.current_commit.is_some(),
+				true,
+			));
 		}
 		CommandBlocking::PassingOn
 	}
@@ -262,6 +427,15 @@ impl Component for SyntaxTextComponent {
 		event: &crossterm::event::Event,
 	) -> Result<EventState> {
 		if let Event::Key(key) = event {
+			if self.focused()
+				&& key_match(
+					key,
+					self.key_config.keys.toggle_inline_blame,
+				) {
+				self.toggle_blame();
+				return Ok(EventState::Consumed);
+			}
+
 			if let Some(nav) = common_nav(key, &self.key_config) {
 				return Ok(if self.scroll(nav) {
 					EventState::Consumed
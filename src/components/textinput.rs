@@ -1,4 +1,5 @@
 use crate::app::Environment;
+use crate::completion::{Completion, CompletionSource};
 use crate::keys::key_match;
 use crate::ui::Size;
 use crate::{
@@ -12,6 +13,7 @@ use crate::{
 };
 use anyhow::Result;
 use crossterm::event::Event;
+use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders};
 use ratatui::{
 	layout::{Alignment, Rect},
@@ -53,6 +55,7 @@ pub struct TextInputComponent {
 	embed: bool,
 	textarea: Option<TextAreaComponent>,
 	select_state: SelectionState,
+	completion: Option<Completion>,
 }
 
 impl TextInputComponent {
@@ -76,9 +79,24 @@ impl TextInputComponent {
 			embed: false,
 			textarea: None,
 			select_state: SelectionState::NotSelecting,
+			completion: None,
 		}
 	}
 
+	/// complete the text with candidates from `source` on tab
+	pub fn with_completion(
+		mut self,
+		env: &Environment,
+		source: CompletionSource,
+	) -> Self {
+		self.completion = Some(Completion::new(
+			env.repo.clone(),
+			&env.sender_app,
+			source,
+		));
+		self
+	}
+
 	///
 	pub const fn with_input_type(
 		mut self,
@@ -165,22 +183,22 @@ impl TextInputComponent {
 			);
 
 			if !self.embed {
-				text_area.set_block(
-					Block::default()
-						.borders(Borders::ALL)
-						.border_style(
-							ratatui::style::Style::default()
-								.add_modifier(
-									ratatui::style::Modifier::BOLD,
-								),
-						)
-						.title(self.title.clone()),
-				);
+				text_area.set_block(self.block());
 			}
 			text_area
 		});
 	}
 
+	fn block(&self) -> Block<'static> {
+		Block::default()
+			.borders(Borders::ALL)
+			.border_style(
+				ratatui::style::Style::default()
+					.add_modifier(ratatui::style::Modifier::BOLD),
+			)
+			.title(self.title.clone())
+	}
+
 	/// Set the `msg`.
 	pub fn set_text(&mut self, msg: String) {
 		self.msg = msg.into();
@@ -189,9 +207,23 @@ impl TextInputComponent {
 		}
 	}
 
+	/// moves the cursor to the end of its line
+	pub fn move_cursor_to_line_end(&mut self) {
+		if let Some(ta) = &mut self.textarea {
+			ta.move_cursor(CursorMove::End);
+		}
+	}
+
 	/// Set the `title`.
 	pub fn set_title(&mut self, t: String) {
 		self.title = t;
+
+		if !self.embed {
+			let block = self.block();
+			if let Some(ta) = &mut self.textarea {
+				ta.set_block(block);
+			}
+		}
 	}
 
 	///
@@ -224,6 +256,68 @@ impl TextInputComponent {
 		}
 	}
 
+	/// replaces the text with the next completion candidate,
+	/// `false` if there is none
+	fn complete(&mut self, reverse: bool) -> bool {
+		let text = self.get_text().to_string();
+		let Some(candidate) = self
+			.completion
+			.as_mut()
+			.and_then(|completion| completion.cycle(&text, reverse))
+			.map(ToString::to_string)
+		else {
+			return false;
+		};
+
+		self.set_text(candidate);
+		if let Some(ta) = &mut self.textarea {
+			ta.move_cursor(CursorMove::End);
+		}
+
+		true
+	}
+
+	fn draw_completion(&self, f: &mut Frame, area: Rect) {
+		const MAX_ITEMS: usize = 8;
+
+		let Some((matches, selection)) =
+			self.completion.as_ref().and_then(Completion::matches)
+		else {
+			return;
+		};
+
+		let offset = selection.saturating_sub(MAX_ITEMS - 1);
+		let lines: Vec<Line> = matches
+			.iter()
+			.enumerate()
+			.skip(offset)
+			.take(MAX_ITEMS)
+			.map(|(idx, candidate)| {
+				Line::from(Span::styled(
+					candidate.as_str(),
+					self.theme.text(true, idx == selection),
+				))
+			})
+			.collect();
+
+		let frame = f.area();
+		let y = area.y.saturating_add(area.height);
+		let height = u16::try_from(lines.len() + 2)
+			.unwrap_or_default()
+			.min(frame.height.saturating_sub(y));
+		let rect = Rect::new(area.x, y, area.width, height);
+
+		f.render_widget(Clear, rect);
+		f.render_widget(
+			Paragraph::new(lines).block(
+				Block::default()
+					.borders(Borders::ALL)
+					.border_style(self.theme.block(true)),
+			),
+			rect,
+		);
+	}
+
 	fn should_select(&mut self, input: &Input) {
 		if input.key == Key::Null {
 			return;
@@ -262,6 +356,20 @@ impl TextInputComponent {
 	#[allow(clippy::too_many_lines, clippy::unnested_or_patterns)]
 	fn process_inputs(ta: &mut TextArea<'_>, input: &Input) -> bool {
 		match input {
+			Input {
+				key: Key::MouseScrollDown,
+				..
+			} => {
+				ta.scroll((1, 0));
+				true
+			}
+			Input {
+				key: Key::MouseScrollUp,
+				..
+			} => {
+				ta.scroll((-1, 0));
+				true
+			}
 			Input {
 				key: Key::Char(c),
 				ctrl: false,
@@ -646,6 +754,7 @@ impl DrawableComponent for TextInputComponent {
 			if self.show_char_count {
 				self.draw_char_count(f, area);
 			}
+			self.draw_completion(f, area);
 
 			self.current_area.set(area);
 		}
@@ -668,12 +777,38 @@ impl Component for TextInputComponent {
 			.order(1),
 		);
 
+		if self.completion.is_some() {
+			out.push(
+				CommandInfo::new(
+					strings::commands::complete(&self.key_config),
+					true,
+					self.is_visible(),
+				)
+				.order(1),
+			);
+		}
+
 		//TODO: we might want to show the textarea specific commands here
 
 		visibility_blocking(self)
 	}
 
 	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if let Event::Key(e) = ev {
+			if self.completion.is_some() && self.is_visible() {
+				let reverse = key_match(
+					e,
+					self.key_config.keys.tab_toggle_reverse,
+				);
+				if (reverse
+					|| key_match(e, self.key_config.keys.tab_toggle))
+					&& self.complete(reverse)
+				{
+					return Ok(EventState::Consumed);
+				}
+			}
+		}
+
 		let input = Input::from(ev.clone());
 		self.should_select(&input);
 		if let Some(ta) = &mut self.textarea {
@@ -691,6 +826,8 @@ impl Component for TextInputComponent {
 				} else {
 					Self::process_inputs(ta, &input)
 				}
+			} else if let Event::Mouse(_) = ev {
+				Self::process_inputs(ta, &input)
 			} else {
 				false
 			};
@@ -704,6 +841,9 @@ impl Component for TextInputComponent {
 
 			if modified {
 				self.msg.take();
+				if let Some(completion) = &mut self.completion {
+					completion.reset();
+				}
 				return Ok(EventState::Consumed);
 			}
 		}
@@ -726,6 +866,9 @@ impl Component for TextInputComponent {
 
 	fn show(&mut self) -> Result<()> {
 		self.show_inner_textarea();
+		if let Some(completion) = &mut self.completion {
+			completion.load();
+		}
 		Ok(())
 	}
 }
@@ -0,0 +1,192 @@
+use crate::{keys::SharedKeyConfig, strings, ui::style::SharedTheme};
+use asyncgit::{StatusItem, StatusItemType};
+use ratatui::{
+	layout::Rect,
+	text::Span,
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+use std::{
+	collections::{HashMap, HashSet},
+	time::{Duration, Instant},
+};
+
+/// new untracked files in a single folder that make a flood
+const FLOOD_THRESHOLD: usize = 50;
+/// how long the notice stays up without an answer
+const NOTICE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// a folder that many untracked files appeared in at once
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UntrackedFlood {
+	/// relative to the workdir, without trailing slash
+	pub dir: String,
+	pub count: usize,
+}
+
+impl UntrackedFlood {
+	/// anchored gitignore pattern for the whole folder
+	pub fn ignore_pattern(&self) -> String {
+		format!("/{}/", self.dir)
+	}
+}
+
+/// watches the untracked files of the status for floods (like build
+/// output) and offers to ignore their folder for a while
+pub struct UntrackedNotice {
+	/// untracked files of the last status, `None` before the first
+	seen: Option<HashSet<String>>,
+	notice: Option<(UntrackedFlood, Instant)>,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl UntrackedNotice {
+	///
+	pub const fn new(
+		key_config: SharedKeyConfig,
+		theme: SharedTheme,
+	) -> Self {
+		Self {
+			seen: None,
+			notice: None,
+			key_config,
+			theme,
+		}
+	}
+
+	/// compares the untracked files of a new workdir status with the
+	/// last one, the first status only sets the baseline
+	pub fn update(&mut self, items: &[StatusItem]) {
+		let untracked: HashSet<String> = items
+			.iter()
+			.filter(|item| item.status == StatusItemType::New)
+			.map(|item| item.path.clone())
+			.collect();
+
+		if let Some(seen) = &self.seen {
+			if let Some(flood) = find_flood(seen, &untracked) {
+				self.notice = Some((flood, Instant::now()));
+			}
+		}
+
+		self.seen = Some(untracked);
+	}
+
+	/// the flood still waiting for an answer
+	pub fn current(&self) -> Option<&UntrackedFlood> {
+		self.notice
+			.as_ref()
+			.filter(|(_, shown)| shown.elapsed() < NOTICE_TIMEOUT)
+			.map(|(flood, _)| flood)
+	}
+
+	/// takes the flood to act on it, hiding the notice
+	pub fn take(&mut self) -> Option<UntrackedFlood> {
+		let flood = self.current().cloned();
+		self.notice = None;
+		flood
+	}
+
+	///
+	pub fn draw(&self, f: &mut Frame, area: Rect) {
+		let Some(flood) = self.current() else {
+			return;
+		};
+
+		let area = Rect {
+			y: area.bottom().saturating_sub(4),
+			height: area.height.min(3),
+			..area
+		};
+
+		f.render_widget(Clear, area);
+		f.render_widget(
+			Paragraph::new(Span::styled(
+				strings::untracked_flood_notice(
+					&self.key_config,
+					flood.count,
+					&flood.dir,
+				),
+				self.theme.text(true, false),
+			))
+			.block(
+				Block::default()
+					.borders(Borders::ALL)
+					.border_style(self.theme.block(true)),
+			),
+			area,
+		);
+	}
+}
+
+/// the folder with the most new untracked files if those are at least
+/// `FLOOD_THRESHOLD`, files in the root of the workdir have no folder
+/// to ignore
+fn find_flood(
+	before: &HashSet<String>,
+	now: &HashSet<String>,
+) -> Option<UntrackedFlood> {
+	let mut counts: HashMap<&str, usize> = HashMap::new();
+
+	for path in now.difference(before) {
+		let path = path.trim_end_matches('/');
+		if let Some((dir, _)) = path.rsplit_once('/') {
+			*counts.entry(dir).or_default() += 1;
+		}
+	}
+
+	counts
+		.into_iter()
+		.filter(|(_, count)| *count >= FLOOD_THRESHOLD)
+		.max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+		.map(|(dir, count)| UntrackedFlood {
+			dir: dir.to_string(),
+			count,
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{find_flood, UntrackedFlood, FLOOD_THRESHOLD};
+	use std::collections::HashSet;
+
+	fn files(dir: &str, count: usize) -> HashSet<String> {
+		(0..count).map(|i| format!("{dir}/f{i}.o")).collect()
+	}
+
+	#[test]
+	fn test_find_flood() {
+		let before = files("src", 3);
+		let mut now = before.clone();
+		now.extend(files("build/obj", FLOOD_THRESHOLD + 2));
+		now.extend(files("tmp", 4));
+
+		assert_eq!(
+			find_flood(&before, &now),
+			Some(UntrackedFlood {
+				dir: String::from("build/obj"),
+				count: FLOOD_THRESHOLD + 2,
+			})
+		);
+		assert_eq!(
+			find_flood(&before, &now).unwrap().ignore_pattern(),
+			"/build/obj/"
+		);
+
+		// already known files are no flood
+		assert_eq!(find_flood(&now, &now), None);
+	}
+
+	#[test]
+	fn test_no_flood_below_threshold_or_in_root() {
+		let before = HashSet::new();
+		let now = files("a", FLOOD_THRESHOLD - 1);
+		assert_eq!(find_flood(&before, &now), None);
+
+		let now: HashSet<String> = (0..FLOOD_THRESHOLD * 2)
+			.map(|i| format!("f{i}.o"))
+			.collect();
+		assert_eq!(find_flood(&before, &now), None);
+	}
+}
@@ -1,10 +1,18 @@
+use crate::{
+	queue::{InternalEvent, Queue},
+	string_utils::{pad_width, truncate_with},
+};
+use asyncgit::{
+	sync::{self, RepoPath},
+	PostHook,
+};
 use chrono::{DateTime, Local, Utc};
-use unicode_width::UnicodeWidthStr;
 
 #[cfg(feature = "ghemoji")]
 pub mod emoji;
 pub mod filetree;
 pub mod logitems;
+pub mod mouse;
 pub mod scroll_horizontal;
 pub mod scroll_vertical;
 pub mod statustree;
@@ -26,6 +34,29 @@ macro_rules! try_or_popup {
 	};
 }
 
+/// runs `checkout` and queues the post-checkout hook for the `HEAD`
+/// it moved, `branch` is `false` for a checkout of files
+pub fn checkout_with_hook<T>(
+	queue: &Queue,
+	repo: &RepoPath,
+	branch: bool,
+	checkout: impl FnOnce() -> asyncgit::Result<T>,
+) -> asyncgit::Result<T> {
+	let old = sync::get_head(repo).ok();
+
+	let res = checkout()?;
+
+	if let Ok(new) = sync::get_head(repo) {
+		queue.push(InternalEvent::RunPostHook(PostHook::Checkout {
+			old,
+			new,
+			branch,
+		}));
+	}
+
+	Ok(res)
+}
+
 /// helper func to convert unix time since epoch to formatted time string in local timezone
 pub fn time_to_string(secs: i64, short: bool) -> String {
 	let time = DateTime::<Local>::from(
@@ -49,21 +80,5 @@ pub fn time_to_string(secs: i64, short: bool) -> String {
 pub fn string_width_align(s: &str, width: usize) -> String {
 	static POSTFIX: &str = "..";
 
-	let len = UnicodeWidthStr::width(s);
-	let width_wo_postfix = width.saturating_sub(POSTFIX.len());
-
-	if (len >= width_wo_postfix && len <= width)
-		|| (len <= width_wo_postfix)
-	{
-		format!("{s:width$}")
-	} else {
-		let mut s = s.to_string();
-		s.truncate(find_truncate_point(&s, width_wo_postfix));
-		format!("{s}{POSTFIX}")
-	}
-}
-
-#[inline]
-fn find_truncate_point(s: &str, chars: usize) -> usize {
-	s.chars().take(chars).map(char::len_utf8).sum()
+	pad_width(&truncate_with(s, width, POSTFIX), width)
 }
@@ -0,0 +1,95 @@
+//! translates mouse events for lists and tab headers
+
+use crate::components::ScrollType;
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
+
+/// what a mouse event inside a list means
+#[derive(Clone, Copy)]
+pub enum ListMouse {
+	Scroll(ScrollType),
+	/// left click on the row, relative to the top of the list
+	Click(usize),
+}
+
+/// interprets `ev` for a list drawn into `area` (without borders)
+pub fn list_mouse(ev: MouseEvent, area: Rect) -> Option<ListMouse> {
+	if !area.contains(Position::new(ev.column, ev.row)) {
+		return None;
+	}
+
+	match ev.kind {
+		MouseEventKind::ScrollUp => {
+			Some(ListMouse::Scroll(ScrollType::Up))
+		}
+		MouseEventKind::ScrollDown => {
+			Some(ListMouse::Scroll(ScrollType::Down))
+		}
+		MouseEventKind::Down(MouseButton::Left) => {
+			Some(ListMouse::Click(usize::from(ev.row - area.y)))
+		}
+		_ => None,
+	}
+}
+
+/// index of the tab clicked on in a `Tabs` widget drawn into `area`
+/// with labels of `widths` separated by a divider of `divider_width`
+pub fn tab_at(
+	ev: MouseEvent,
+	area: Rect,
+	widths: &[usize],
+	divider_width: usize,
+) -> Option<usize> {
+	if ev.kind != MouseEventKind::Down(MouseButton::Left)
+		|| !area.contains(Position::new(ev.column, ev.row))
+	{
+		return None;
+	}
+
+	// every label is padded by a space on both sides
+	let column = usize::from(ev.column - area.x);
+	let mut start = 0;
+	for (idx, width) in widths.iter().enumerate() {
+		let end = start + width + 2;
+		if column < end {
+			return Some(idx);
+		}
+		start = end + divider_width;
+		if column < start {
+			return None;
+		}
+	}
+
+	None
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crossterm::event::KeyModifiers;
+	use pretty_assertions::assert_eq;
+
+	fn click(column: u16) -> MouseEvent {
+		MouseEvent {
+			kind: MouseEventKind::Down(MouseButton::Left),
+			column,
+			row: 0,
+			modifiers: KeyModifiers::NONE,
+		}
+	}
+
+	#[test]
+	fn test_tab_at() {
+		let area = Rect::new(1, 0, 40, 1);
+		let widths = [5, 3];
+
+		// ` Local | Abc `
+		assert_eq!(tab_at(click(0), area, &widths, 1), None);
+		assert_eq!(tab_at(click(1), area, &widths, 1), Some(0));
+		assert_eq!(tab_at(click(7), area, &widths, 1), Some(0));
+		assert_eq!(tab_at(click(8), area, &widths, 1), None);
+		assert_eq!(tab_at(click(9), area, &widths, 1), Some(1));
+		assert_eq!(tab_at(click(13), area, &widths, 1), Some(1));
+		assert_eq!(tab_at(click(14), area, &widths, 1), None);
+	}
+}
@@ -16,6 +16,13 @@ pub struct StatusTree {
 	// some folders may be folded up, this allows jumping
 	// over folders which are folded into their parent
 	pub available_selections: Vec<usize>,
+	// display name of each row in `available_selections`, with the
+	// folded up folders joined into it
+	pub row_names: Vec<String>,
+	// rows of `available_selections` outside of collapsed folders,
+	// rebuilt whenever the tree or a collapse state changes so
+	// drawing does not filter every frame
+	pub visible_rows: Vec<usize>,
 
 	pub window_height: Cell<Option<usize>>,
 }
@@ -66,7 +73,9 @@ impl StatusTree {
 		);
 
 		self.update_visibility(None, 0, true);
-		self.available_selections = self.setup_available_selections();
+		(self.available_selections, self.row_names) =
+			self.setup_available_selections();
+		self.setup_visible_rows();
 
 		//NOTE: now that visibility is set we can make sure selection is visible
 		if let Some(idx) = self.selection {
@@ -80,10 +89,15 @@ impl StatusTree {
 	/// some folders may be folded up into their parent
 	///
 	/// It should be impossible to select a folder which has been folded into its parent
-	fn setup_available_selections(&self) -> Vec<usize> {
-		// use the same algorithm as in filetree build_vec_text_for_drawing function
+	///
+	/// Also returns the name each of these rows is drawn with, so
+	/// drawing does not have to fold the tree up every frame
+	fn setup_available_selections(
+		&self,
+	) -> (Vec<usize>, Vec<String>) {
 		let mut should_skip_over: usize = 0;
 		let mut vec_available_selections: Vec<usize> = vec![];
+		let mut row_names: Vec<String> = vec![];
 		let tree_items = self.tree.items();
 		for index in 0..tree_items.len() {
 			if should_skip_over > 0 {
@@ -92,6 +106,7 @@ impl StatusTree {
 			}
 			let mut idx_temp = index;
 			vec_available_selections.push(index);
+			let mut name = tree_items[index].info.path.clone();
 
 			while idx_temp < tree_items.len().saturating_sub(2)
 				&& tree_items[idx_temp].info.indent
@@ -114,9 +129,23 @@ impl StatusTree {
 					should_skip_over -= 1;
 					break;
 				}
+
+				name.push('/');
+				name.push_str(&tree_items[idx_temp].info.path);
 			}
+			row_names.push(name);
 		}
-		vec_available_selections
+		(vec_available_selections, row_names)
+	}
+
+	fn setup_visible_rows(&mut self) {
+		self.visible_rows = self
+			.available_selections
+			.iter()
+			.enumerate()
+			.filter(|(_, idx)| self.tree[**idx].info.visible)
+			.map(|(row, _)| row)
+			.collect();
 	}
 
 	fn find_visible_idx(&self, mut idx: usize) -> usize {
@@ -167,6 +196,46 @@ impl StatusTree {
 		})
 	}
 
+	/// selects the next (or previous) visible file matching
+	/// `filter`, returns false if there is none
+	pub fn select_file(
+		&mut self,
+		forward: bool,
+		filter: impl Fn(&StatusItem) -> bool,
+	) -> bool {
+		let Some(selection) = self.selection else {
+			return false;
+		};
+
+		let is_target = |idx: &usize| {
+			self.is_visible_index(*idx)
+				&& matches!(
+					&self.tree[*idx].kind,
+					FileTreeItemKind::File(item) if filter(item)
+				)
+		};
+		let target = if forward {
+			self.available_selections
+				.iter()
+				.copied()
+				.filter(|idx| *idx > selection)
+				.find(is_target)
+		} else {
+			self.available_selections
+				.iter()
+				.rev()
+				.copied()
+				.filter(|idx| *idx < selection)
+				.find(is_target)
+		};
+
+		if target.is_some() {
+			self.selection = target;
+		}
+
+		target.is_some()
+	}
+
 	///
 	pub fn selected_item(&self) -> Option<FileTreeItem> {
 		self.selection.map(|i| self.tree[i].clone())
@@ -392,9 +461,11 @@ impl StatusTree {
 			if item_path.starts_with(&path) {
 				item.info.visible = false;
 			} else {
-				return;
+				break;
 			}
 		}
+
+		self.setup_visible_rows();
 	}
 
 	fn expand(&mut self, path: &str, current_index: usize) {
@@ -412,6 +483,7 @@ impl StatusTree {
 			current_index + 1,
 			false,
 		);
+		self.setup_visible_rows();
 	}
 
 	fn update_visibility(
@@ -637,6 +709,36 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_visible_rows() {
+		let items = string_vec_to_status(&[
+			"a/b/c", //
+			"a/b/d", //
+			"a/e",   //
+		]);
+
+		//0 a/
+		//1   b/
+		//2     c
+		//3     d
+		//4   e
+
+		let mut res = StatusTree::default();
+		res.update(&items).unwrap();
+		assert_eq!(res.available_selections, vec![0, 1, 2, 3, 4]);
+		assert_eq!(res.visible_rows, vec![0, 1, 2, 3, 4]);
+
+		res.collapse(&String::from("a/b"), 1);
+		assert_eq!(res.visible_rows, vec![0, 1, 4]);
+
+		// collapse states survive an update
+		res.update(&items).unwrap();
+		assert_eq!(res.visible_rows, vec![0, 1, 4]);
+
+		res.expand(&String::from("a/b"), 1);
+		assert_eq!(res.visible_rows, vec![0, 1, 2, 3, 4]);
+	}
+
 	#[test]
 	fn test_expand_bug() {
 		let items = string_vec_to_status(&[
@@ -791,6 +893,38 @@ mod tests {
 		assert_eq!(res.selection, Some(3));
 	}
 
+	#[test]
+	fn test_select_file() {
+		let mut items = string_vec_to_status(&[
+			"a/b", //
+			"a/c", //
+			"d",   //
+		]);
+		items[1].status = StatusItemType::Conflicted;
+
+		//0 a/
+		//1   b
+		//2   c
+		//3 d
+
+		let mut res = StatusTree::default();
+		res.update(&items).unwrap();
+
+		assert!(res.select_file(true, |_| true));
+		assert_eq!(res.selection, Some(1));
+
+		assert!(res.select_file(true, |item| {
+			item.status != StatusItemType::Conflicted
+		}));
+		assert_eq!(res.selection, Some(3));
+		assert!(!res.select_file(true, |_| true));
+
+		assert!(res.select_file(false, |item| {
+			item.status == StatusItemType::Conflicted
+		}));
+		assert_eq!(res.selection, Some(2));
+	}
+
 	#[test]
 	fn test_folders_fold_up_if_alone_in_directory() {
 		let items = string_vec_to_status(&[
@@ -865,6 +999,27 @@ mod tests {
 		assert_eq!(res.selection, Some(7));
 	}
 
+	#[test]
+	fn test_row_names_fold_up() {
+		let items = string_vec_to_status(&[
+			"a/b/c/d", //
+			"a/e",     //
+			"f",       //
+		]);
+
+		//0 a/
+		//1   b/c/
+		//3       d
+		//4   e
+		//5 f
+
+		let mut res = StatusTree::default();
+		res.update(&items).unwrap();
+
+		assert_eq!(res.available_selections, vec![0, 1, 3, 4, 5]);
+		assert_eq!(res.row_names, vec!["a", "b/c", "d", "e", "f"]);
+	}
+
 	#[test]
 	fn test_folders_fold_up_down_with_selection_left_right() {
 		let items = string_vec_to_status(&[
@@ -0,0 +1,133 @@
+//! opt-in (`--event-log`) record of a session as json lines: the
+//! actions performed, how long handling events and drawing took and
+//! the errors shown. it is only written locally, for debugging
+//! performance problems or building your own statistics
+
+use anyhow::Result;
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use std::{
+	fs::{File, OpenOptions},
+	io::{LineWriter, Write},
+	path::Path,
+	sync::Mutex,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+static EVENT_LOG: OnceCell<Mutex<LineWriter<File>>> = OnceCell::new();
+
+///
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Event<'a> {
+	/// gitui was started on `repo`
+	Start { version: &'a str, repo: &'a str },
+	/// an internal event like opening a popup or starting a push
+	Action { name: &'a str },
+	/// time spent handling an event or drawing a frame
+	Job { name: &'a str, duration_us: u128 },
+	/// an error shown to the user
+	Error { message: &'a str },
+	///
+	Exit,
+}
+
+#[derive(Serialize)]
+struct Record<'a> {
+	/// milliseconds since the unix epoch
+	time: u128,
+	#[serde(flatten)]
+	event: &'a Event<'a>,
+}
+
+/// appends all further events to the file at `path`
+pub fn init(path: &Path) -> Result<()> {
+	let file =
+		OpenOptions::new().create(true).append(true).open(path)?;
+
+	// a second call keeps writing to the first file
+	let _ = EVENT_LOG.set(Mutex::new(LineWriter::new(file)));
+
+	Ok(())
+}
+
+/// `false` unless `init` was called, to skip preparing events nobody
+/// records
+pub fn enabled() -> bool {
+	EVENT_LOG.get().is_some()
+}
+
+///
+pub fn record(event: &Event) {
+	let Some(log) = EVENT_LOG.get() else {
+		return;
+	};
+
+	let time = SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.unwrap_or_default()
+		.as_millis();
+
+	let result = log
+		.lock()
+		.map_err(|e| anyhow::anyhow!("{e}"))
+		.and_then(|mut out| write(&mut *out, time, event));
+
+	if let Err(e) = result {
+		log::error!("event log error: {e}");
+	}
+}
+
+/// records a `Job` that took `duration`
+pub fn record_job(name: &str, duration: Duration) {
+	record(&Event::Job {
+		name,
+		duration_us: duration.as_micros(),
+	});
+}
+
+fn write(
+	out: &mut impl Write,
+	time: u128,
+	event: &Event,
+) -> Result<()> {
+	serde_json::to_writer(&mut *out, &Record { time, event })?;
+	writeln!(out)?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{write, Event};
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_write() {
+		let mut out = Vec::new();
+
+		write(&mut out, 1, &Event::Action { name: "Push" }).unwrap();
+		write(
+			&mut out,
+			2,
+			&Event::Job {
+				name: "draw",
+				duration_us: 1500,
+			},
+		)
+		.unwrap();
+		write(&mut out, 3, &Event::Exit).unwrap();
+
+		assert_eq!(
+			String::from_utf8(out).unwrap(),
+			concat!(
+				r#"{"time":1,"kind":"action","name":"Push"}"#,
+				"\n",
+				r#"{"time":2,"kind":"job","name":"draw","duration_us":1500}"#,
+				"\n",
+				r#"{"time":3,"kind":"exit"}"#,
+				"\n",
+			)
+		);
+	}
+}
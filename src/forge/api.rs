@@ -0,0 +1,320 @@
+//! minimal GitHub/GitLab api client on top of `curl`.
+//!
+//! this is opt-in: it is only used with the `forge-api` feature and
+//! when a token is configured via `gitui.forgeToken` (git config) or
+//! the `GITHUB_TOKEN`/`GITLAB_TOKEN` environment variables. those are
+//! only handed to `github.com`/`gitlab.com` or a host whose forge is
+//! set explicitly in `gitui.forge`, never to a host that merely looks
+//! like one.
+
+use super::{split_remote_url, url_encode, ForgeKind};
+use anyhow::{anyhow, Result};
+use asyncgit::sync::{
+	get_config_string, get_default_remote, get_remote_url, CommitId,
+	RepoPath,
+};
+use serde_json::Value;
+use std::{
+	env,
+	io::Write,
+	process::{Command, Stdio},
+};
+
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CiState {
+	Pending,
+	Success,
+	Failure,
+}
+
+/// everything needed to query the api without touching the repo
+#[derive(Clone, Debug)]
+pub struct ApiClient {
+	kind: ForgeKind,
+	host: String,
+	project: String,
+	token: String,
+}
+
+impl ApiClient {
+	/// `None` if the remote is no supported forge or no token is set
+	pub fn new(repo: &RepoPath) -> Result<Option<Self>> {
+		let remote = get_default_remote(repo)?;
+		let Some(url) = get_remote_url(repo, &remote)? else {
+			return Ok(None);
+		};
+		let Some((host, project)) = split_remote_url(&url) else {
+			return Ok(None);
+		};
+
+		let configured = get_config_string(repo, "gitui.forge")?
+			.and_then(|name| ForgeKind::from_name(&name));
+		let Some(kind) =
+			configured.or_else(|| ForgeKind::from_host(host))
+		else {
+			return Ok(None);
+		};
+		if !matches!(kind, ForgeKind::GitHub | ForgeKind::GitLab) {
+			return Ok(None);
+		}
+
+		let Some(token) =
+			get_config_string(repo, "gitui.forgeToken")?.or_else(
+				|| {
+					token_env(kind, host, configured.is_some())
+						.and_then(|name| env::var(name).ok())
+				},
+			)
+		else {
+			return Ok(None);
+		};
+
+		Ok(Some(Self {
+			kind,
+			host: host.to_string(),
+			project: project.to_string(),
+			token,
+		}))
+	}
+
+	pub const fn kind(&self) -> ForgeKind {
+		self.kind
+	}
+
+	pub fn project(&self) -> &str {
+		&self.project
+	}
+
+	/// api url of the repository (GitHub) or project (GitLab)
+	pub fn repo_url(&self) -> String {
+		match self.kind {
+			ForgeKind::GitLab => format!(
+				"https://{}/api/v4/projects/{}",
+				self.host,
+				url_encode(&self.project)
+			),
+			_ if self.host == "github.com" => {
+				format!(
+					"https://api.github.com/repos/{}",
+					self.project
+				)
+			}
+			_ => format!(
+				"https://{}/api/v3/repos/{}",
+				self.host, self.project
+			),
+		}
+	}
+
+	pub fn get(&self, url: &str) -> Result<Value> {
+		let (auth, accept) = match self.kind {
+			ForgeKind::GitLab => {
+				(format!("PRIVATE-TOKEN: {}", self.token), "*/*")
+			}
+			_ => (
+				format!("Authorization: Bearer {}", self.token),
+				"application/vnd.github+json",
+			),
+		};
+
+		// the token is passed via stdin to keep it out of the process list
+		let mut child = Command::new("curl")
+			.args(["-sSfL", "--max-time", "10", "-H", "@-", "-H"])
+			.arg(format!("Accept: {accept}"))
+			.arg(url)
+			.stdin(Stdio::piped())
+			.stdout(Stdio::piped())
+			.stderr(Stdio::piped())
+			.spawn()
+			.map_err(|e| anyhow!("failed to run curl: {e}"))?;
+
+		if let Some(mut stdin) = child.stdin.take() {
+			stdin.write_all(auth.as_bytes())?;
+		}
+
+		let output = child.wait_with_output()?;
+		if !output.status.success() {
+			return Err(anyhow!(
+				"request failed: {}",
+				String::from_utf8_lossy(&output.stderr).trim()
+			));
+		}
+
+		Ok(serde_json::from_slice(&output.stdout)?)
+	}
+
+	/// combined ci state of all checks/pipelines of `commit`
+	pub fn ci_state(
+		&self,
+		commit: CommitId,
+	) -> Result<Option<CiState>> {
+		let repo = self.repo_url();
+
+		if self.kind == ForgeKind::GitLab {
+			let details = self.get(&format!(
+				"{repo}/repository/commits/{commit}"
+			))?;
+
+			return Ok(details["last_pipeline"]["status"]
+				.as_str()
+				.and_then(gitlab_ci_state));
+		}
+
+		let checks =
+			self.get(&format!("{repo}/commits/{commit}/check-runs"))?;
+		let status =
+			self.get(&format!("{repo}/commits/{commit}/status"))?;
+
+		Ok(github_ci_state(&checks, &status))
+	}
+}
+
+fn combine_ci(
+	states: impl Iterator<Item = CiState>,
+) -> Option<CiState> {
+	states.fold(None, |acc, state| match (acc, state) {
+		(Some(CiState::Failure), _) | (_, CiState::Failure) => {
+			Some(CiState::Failure)
+		}
+		(Some(CiState::Pending), _) | (_, CiState::Pending) => {
+			Some(CiState::Pending)
+		}
+		_ => Some(CiState::Success),
+	})
+}
+
+/// combines check runs (actions) and commit statuses of a commit
+fn github_ci_state(
+	checks: &Value,
+	status: &Value,
+) -> Option<CiState> {
+	let runs =
+		checks["check_runs"].as_array().into_iter().flatten().map(
+			|run| {
+				if run["status"].as_str() != Some("completed") {
+					CiState::Pending
+				} else if matches!(
+					run["conclusion"].as_str(),
+					Some("success" | "neutral" | "skipped")
+				) {
+					CiState::Success
+				} else {
+					CiState::Failure
+				}
+			},
+		);
+
+	// the combined status is `pending` if there are no statuses at all
+	let statuses =
+		(status["total_count"].as_u64().unwrap_or_default() > 0)
+			.then(|| match status["state"].as_str() {
+				Some("success") => CiState::Success,
+				Some("pending") => CiState::Pending,
+				_ => CiState::Failure,
+			});
+
+	combine_ci(runs.chain(statuses))
+}
+
+pub fn gitlab_ci_state(status: &str) -> Option<CiState> {
+	match status {
+		"success" => Some(CiState::Success),
+		"failed" | "canceled" => Some(CiState::Failure),
+		"skipped" => None,
+		_ => Some(CiState::Pending),
+	}
+}
+
+/// environment variable holding the token for `host`, only for the
+/// public forges or when the user picked the forge of `host`
+/// (`configured`) instead of it being guessed from the name
+fn token_env(
+	kind: ForgeKind,
+	host: &str,
+	configured: bool,
+) -> Option<&'static str> {
+	let host = host.to_lowercase();
+
+	match kind {
+		ForgeKind::GitHub if configured || host == "github.com" => {
+			Some("GITHUB_TOKEN")
+		}
+		ForgeKind::GitLab if configured || host == "gitlab.com" => {
+			Some("GITLAB_TOKEN")
+		}
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use serde_json::json;
+
+	#[test]
+	fn test_github_ci_state() {
+		let no_statuses =
+			json!({ "total_count": 0, "state": "pending" });
+
+		assert_eq!(
+			github_ci_state(
+				&json!({ "check_runs": [] }),
+				&no_statuses
+			),
+			None
+		);
+		assert_eq!(
+			github_ci_state(
+				&json!({ "check_runs": [
+					{ "status": "completed", "conclusion": "success" },
+					{ "status": "in_progress", "conclusion": null },
+				]}),
+				&no_statuses
+			),
+			Some(CiState::Pending)
+		);
+		assert_eq!(
+			github_ci_state(
+				&json!({ "check_runs": [
+					{ "status": "completed", "conclusion": "skipped" },
+				]}),
+				&json!({ "total_count": 1, "state": "failure" })
+			),
+			Some(CiState::Failure)
+		);
+	}
+
+	#[test]
+	fn test_token_env() {
+		assert_eq!(
+			token_env(ForgeKind::GitHub, "github.com", false),
+			Some("GITHUB_TOKEN")
+		);
+		assert_eq!(
+			token_env(ForgeKind::GitLab, "GitLab.com", false),
+			Some("GITLAB_TOKEN")
+		);
+		assert_eq!(
+			token_env(
+				ForgeKind::GitHub,
+				"github.evil.example",
+				false
+			),
+			None
+		);
+		assert_eq!(
+			token_env(ForgeKind::GitLab, "mygitlab.example", false),
+			None
+		);
+		assert_eq!(
+			token_env(ForgeKind::GitLab, "git.corp.example", true),
+			Some("GITLAB_TOKEN")
+		);
+		assert_eq!(
+			token_env(ForgeKind::Gitea, "codeberg.org", true),
+			None
+		);
+	}
+}
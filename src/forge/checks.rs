@@ -0,0 +1,167 @@
+//! ci state of the commits shown in the log
+
+use super::api::{ApiClient, CiState};
+use crate::AsyncAppNotification;
+use asyncgit::{
+	asyncjob::{AsyncJob, AsyncSingleJob, RunParams},
+	sync::{CommitId, RepoPathRef},
+};
+use crossbeam_channel::Sender;
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+	time::{Duration, Instant},
+};
+
+/// pending states are asked for again after this long
+const PENDING_REFRESH: Duration = Duration::from_secs(60);
+/// upper bound of commits queried by a single job
+const MAX_COMMITS_PER_JOB: usize = 20;
+
+enum JobState {
+	Request(ApiClient, Vec<CommitId>),
+	Response(Vec<(CommitId, Option<CiState>)>),
+}
+
+#[derive(Clone)]
+struct AsyncChecksJob {
+	state: Arc<Mutex<Option<JobState>>>,
+}
+
+impl AsyncChecksJob {
+	fn new(client: ApiClient, commits: Vec<CommitId>) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request(
+				client, commits,
+			)))),
+		}
+	}
+
+	fn result(&self) -> Option<Vec<(CommitId, Option<CiState>)>> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(JobState::Response(states)) = state.take() {
+				return Some(states);
+			}
+		}
+
+		None
+	}
+}
+
+impl AsyncJob for AsyncChecksJob {
+	type Notification = AsyncAppNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> asyncgit::Result<Self::Notification> {
+		if let Ok(mut state) = self.state.lock() {
+			*state = state.take().map(|state| match state {
+				JobState::Request(client, commits) => {
+					JobState::Response(
+						commits
+							.into_iter()
+							.map(|id| {
+								// unpushed commits are unknown to the
+								// forge, remember them as without ci
+								let ci = client
+									.ci_state(id)
+									.map_err(|e| {
+										log::debug!("forge api: {e}");
+									})
+									.ok()
+									.flatten();
+								(id, ci)
+							})
+							.collect(),
+					)
+				}
+				JobState::Response(states) => {
+					JobState::Response(states)
+				}
+			});
+		}
+
+		Ok(AsyncAppNotification::ForgeChecks)
+	}
+}
+
+/// fetches and caches the ci state of commits
+pub struct CommitChecks {
+	repo: RepoPathRef,
+	client: Option<ApiClient>,
+	client_resolved: bool,
+	job: AsyncSingleJob<AsyncChecksJob>,
+	fetched: HashMap<CommitId, (Option<CiState>, Instant)>,
+}
+
+impl CommitChecks {
+	///
+	pub fn new(
+		repo: RepoPathRef,
+		sender: &Sender<AsyncAppNotification>,
+	) -> Self {
+		Self {
+			repo,
+			client: None,
+			client_resolved: false,
+			job: AsyncSingleJob::new(sender.clone()),
+			fetched: HashMap::new(),
+		}
+	}
+
+	fn needs_fetch(&self, id: &CommitId) -> bool {
+		self.fetched.get(id).is_none_or(|(state, at)| {
+			*state == Some(CiState::Pending)
+				&& at.elapsed() > PENDING_REFRESH
+		})
+	}
+
+	/// requests the state of all `commits` not cached yet
+	pub fn request(&mut self, commits: &[CommitId]) {
+		if self.job.is_pending() {
+			return;
+		}
+
+		let missing: Vec<CommitId> = commits
+			.iter()
+			.filter(|id| self.needs_fetch(id))
+			.take(MAX_COMMITS_PER_JOB)
+			.copied()
+			.collect();
+		if missing.is_empty() {
+			return;
+		}
+
+		if !self.client_resolved {
+			self.client_resolved = true;
+			self.client = ApiClient::new(&self.repo.borrow())
+				.unwrap_or_else(|e| {
+					log::error!("forge api: {e}");
+					None
+				});
+		}
+
+		if let Some(client) = &self.client {
+			self.job
+				.spawn(AsyncChecksJob::new(client.clone(), missing));
+		}
+	}
+
+	/// takes the result of a finished request, returns the new states
+	pub fn update(&mut self) -> Vec<(CommitId, Option<CiState>)> {
+		let states = self
+			.job
+			.take_last()
+			.and_then(|job| job.result())
+			.unwrap_or_default();
+
+		let now = Instant::now();
+		for (id, state) in &states {
+			self.fetched.insert(*id, (*state, now));
+		}
+
+		states
+	}
+}
@@ -0,0 +1,415 @@
+//! helpers to derive web urls of a repository hosted on a forge
+//! (github, gitlab, gitea…) from its remote url
+//!
+//! the url layout is picked from the remote host and can be
+//! overridden per repository via git config:
+//! * `gitui.forge`: one of `github`, `gitlab`, `bitbucket`, `gitea`
+//!   or `sourcehut`, for self-hosted instances
+//! * `gitui.commitUrl`, `gitui.fileUrl`, `gitui.linesUrl`: templates
+//!   using `{base}`, `{commit}`, `{path}`, `{start}` and `{end}`
+
+#[cfg(feature = "forge-api")]
+mod api;
+#[cfg(feature = "forge-api")]
+mod checks;
+#[cfg(feature = "forge-api")]
+mod review;
+
+#[cfg(feature = "forge-api")]
+pub use api::CiState;
+#[cfg(feature = "forge-api")]
+pub use checks::CommitChecks;
+#[cfg(feature = "forge-api")]
+pub use review::{BranchReview, PullRequest};
+
+use anyhow::{anyhow, Result};
+use asyncgit::sync::{
+	get_config_string, get_default_remote, get_remote_url, CommitId,
+	RepoPath,
+};
+use std::{
+	env,
+	fmt::Write as _,
+	process::{Command, Stdio},
+};
+
+/// url layout of the known forges
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ForgeKind {
+	GitHub,
+	GitLab,
+	Bitbucket,
+	Gitea,
+	SourceHut,
+}
+
+impl ForgeKind {
+	fn from_name(name: &str) -> Option<Self> {
+		match name.trim().to_lowercase().as_str() {
+			"github" => Some(Self::GitHub),
+			"gitlab" => Some(Self::GitLab),
+			"bitbucket" => Some(Self::Bitbucket),
+			"gitea" | "forgejo" | "codeberg" => Some(Self::Gitea),
+			"sourcehut" | "srht" => Some(Self::SourceHut),
+			_ => None,
+		}
+	}
+
+	fn from_host(host: &str) -> Option<Self> {
+		let host = host.to_lowercase();
+
+		if host.contains("github") {
+			Some(Self::GitHub)
+		} else if host.contains("gitlab") {
+			Some(Self::GitLab)
+		} else if host.contains("bitbucket") {
+			Some(Self::Bitbucket)
+		} else if host.contains("codeberg")
+			|| host.contains("gitea")
+			|| host.contains("forgejo")
+		{
+			Some(Self::Gitea)
+		} else if host.ends_with("sr.ht") {
+			Some(Self::SourceHut)
+		} else {
+			None
+		}
+	}
+
+	const fn commit_template(self) -> &'static str {
+		match self {
+			Self::GitHub | Self::Gitea | Self::SourceHut => {
+				"{base}/commit/{commit}"
+			}
+			Self::GitLab => "{base}/-/commit/{commit}",
+			Self::Bitbucket => "{base}/commits/{commit}",
+		}
+	}
+
+	const fn file_template(self) -> &'static str {
+		match self {
+			Self::GitHub => "{base}/blob/{commit}/{path}",
+			Self::GitLab => "{base}/-/blob/{commit}/{path}",
+			Self::Bitbucket => "{base}/src/{commit}/{path}",
+			Self::Gitea => "{base}/src/commit/{commit}/{path}",
+			Self::SourceHut => "{base}/tree/{commit}/item/{path}",
+		}
+	}
+
+	const fn lines_template(self) -> &'static str {
+		match self {
+			Self::GitHub | Self::Gitea => "#L{start}-L{end}",
+			Self::GitLab | Self::SourceHut => "#L{start}-{end}",
+			Self::Bitbucket => "#lines-{start}:{end}",
+		}
+	}
+}
+
+/// templates used to build the web urls of a repository
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UrlTemplates {
+	pub commit: String,
+	pub file: String,
+	pub lines: String,
+}
+
+impl From<ForgeKind> for UrlTemplates {
+	fn from(kind: ForgeKind) -> Self {
+		Self {
+			commit: kind.commit_template().to_string(),
+			file: kind.file_template().to_string(),
+			lines: kind.lines_template().to_string(),
+		}
+	}
+}
+
+/// what a web url should point at
+#[derive(Clone, Copy, Debug)]
+pub struct WebTarget<'a> {
+	pub commit: CommitId,
+	pub path: Option<&'a str>,
+	/// 1-based, inclusive line range inside `path`
+	pub lines: Option<(usize, usize)>,
+}
+
+/// splits a remote url (`https://`, `ssh://` or scp-like
+/// `git@host:owner/repo.git`) into host and repository path
+fn split_remote_url(remote_url: &str) -> Option<(&str, &str)> {
+	let url = remote_url.trim();
+
+	let (host, path) = if let Some((_, rest)) = url.split_once("://")
+	{
+		let (authority, path) = rest.split_once('/')?;
+		let host = authority.rsplit('@').next()?;
+		// drop ssh ports, those are never part of the web url
+		let host = if url.starts_with("http") {
+			host
+		} else {
+			host.split(':').next()?
+		};
+		(host, path)
+	} else {
+		let (authority, path) = url.split_once(':')?;
+		(authority.rsplit('@').next()?, path)
+	};
+
+	let path = path.trim_matches('/');
+	let path = path.strip_suffix(".git").unwrap_or(path);
+
+	if host.is_empty() || path.is_empty() {
+		return None;
+	}
+
+	Some((host, path))
+}
+
+/// converts a remote url (`https://`, `ssh://` or scp-like
+/// `git@host:owner/repo.git`) into the https base url of the repository
+pub fn web_base_url(remote_url: &str) -> Option<String> {
+	split_remote_url(remote_url)
+		.map(|(host, path)| format!("https://{host}/{path}"))
+}
+
+/// fills the matching template with the values of `target`
+#[allow(clippy::literal_string_with_formatting_args)]
+pub fn format_web_url(
+	base: &str,
+	templates: &UrlTemplates,
+	target: &WebTarget,
+) -> String {
+	let commit = target.commit.to_string();
+
+	let Some(path) = target.path else {
+		return templates
+			.commit
+			.replace("{base}", base)
+			.replace("{commit}", &commit);
+	};
+
+	let path = path
+		.trim_start_matches("./")
+		.split('/')
+		.map(url_encode)
+		.collect::<Vec<_>>()
+		.join("/");
+
+	let mut url = templates
+		.file
+		.replace("{base}", base)
+		.replace("{commit}", &commit)
+		.replace("{path}", &path);
+
+	if let Some((start, end)) = target.lines {
+		url.push_str(
+			&templates
+				.lines
+				.replace("{start}", &start.to_string())
+				.replace("{end}", &end.to_string()),
+		);
+	}
+
+	url
+}
+
+/// percent-encodes everything but the unreserved characters of
+/// rfc 3986
+pub fn url_encode(s: &str) -> String {
+	s.bytes().fold(String::new(), |mut out, b| {
+		if b.is_ascii_alphanumeric() || b"-_.~".contains(&b) {
+			out.push(char::from(b));
+		} else {
+			let _ = write!(out, "%{b:02X}");
+		}
+		out
+	})
+}
+
+/// web url of the repository on the forge of its default remote
+pub fn web_base(repo: &RepoPath) -> Result<String> {
+	let url = remote_url(repo)?;
+
+	web_base_url(&url)
+		.ok_or_else(|| anyhow!("unsupported remote url: {url}"))
+}
+
+fn remote_url(repo: &RepoPath) -> Result<String> {
+	let remote = get_default_remote(repo)?;
+
+	get_remote_url(repo, &remote)?
+		.ok_or_else(|| anyhow!("remote '{remote}' has no url"))
+}
+
+fn url_templates(
+	repo: &RepoPath,
+	host: &str,
+) -> Result<UrlTemplates> {
+	let kind = get_config_string(repo, "gitui.forge")?
+		.and_then(|name| ForgeKind::from_name(&name))
+		.or_else(|| ForgeKind::from_host(host))
+		.unwrap_or(ForgeKind::GitHub);
+
+	let mut templates = UrlTemplates::from(kind);
+
+	for (key, template) in [
+		("gitui.commitUrl", &mut templates.commit),
+		("gitui.fileUrl", &mut templates.file),
+		("gitui.linesUrl", &mut templates.lines),
+	] {
+		if let Some(custom) = get_config_string(repo, key)? {
+			*template = custom;
+		}
+	}
+
+	Ok(templates)
+}
+
+/// permalink to a commit, a file or a line range at a commit
+pub fn permalink(
+	repo: &RepoPath,
+	target: &WebTarget,
+) -> Result<String> {
+	let url = remote_url(repo)?;
+	let base = web_base(repo)?;
+	let host = split_remote_url(&url).map_or("", |(host, _)| host);
+
+	Ok(format_web_url(&base, &url_templates(repo, host)?, target))
+}
+
+/// opens `url` in `$BROWSER` or the default browser of the platform
+pub fn open_in_browser(url: &str) -> Result<()> {
+	let mut cmd = env::var("BROWSER").map_or_else(
+		|_| {
+			if cfg!(target_os = "macos") {
+				Command::new("open")
+			} else if cfg!(windows) {
+				// not `cmd /C start`, cmd would interpret `&` and
+				// friends in the url
+				let mut cmd = Command::new("rundll32");
+				cmd.arg("url.dll,FileProtocolHandler");
+				cmd
+			} else {
+				Command::new("xdg-open")
+			}
+		},
+		Command::new,
+	);
+
+	cmd.arg(url)
+		.stdin(Stdio::null())
+		.stdout(Stdio::null())
+		.stderr(Stdio::null())
+		.spawn()
+		.map_err(|e| anyhow!("failed to open browser: {e}"))?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{
+		format_web_url, url_encode, web_base_url, ForgeKind,
+		UrlTemplates, WebTarget,
+	};
+	use asyncgit::sync::CommitId;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_web_base_url() {
+		let expected = Some(String::from("https://github.com/o/r"));
+
+		assert_eq!(web_base_url("git@github.com:o/r.git"), expected);
+		assert_eq!(
+			web_base_url("https://github.com/o/r.git"),
+			expected
+		);
+		assert_eq!(web_base_url("https://github.com/o/r/"), expected);
+		assert_eq!(
+			web_base_url("https://user@github.com/o/r"),
+			expected
+		);
+		assert_eq!(
+			web_base_url("ssh://git@github.com:22/o/r.git"),
+			expected
+		);
+		assert_eq!(web_base_url("/some/local/path"), None);
+	}
+
+	#[test]
+	fn test_format_web_url() {
+		let commit = CommitId::from_str_unchecked(
+			"d92f9ba0000000000000000000000000000000ff",
+		)
+		.unwrap();
+		let file = WebTarget {
+			commit,
+			path: Some("./src/main.rs"),
+			lines: Some((3, 7)),
+		};
+
+		assert_eq!(
+			format_web_url(
+				"https://gitlab.com/o/r",
+				&ForgeKind::GitLab.into(),
+				&file,
+			),
+			format!(
+				"https://gitlab.com/o/r/-/blob/{commit}/src/main.rs#L3-7"
+			)
+		);
+		assert_eq!(
+			format_web_url(
+				"https://bitbucket.org/o/r",
+				&ForgeKind::Bitbucket.into(),
+				&WebTarget {
+					path: None,
+					lines: None,
+					..file
+				},
+			),
+			format!("https://bitbucket.org/o/r/commits/{commit}")
+		);
+		assert_eq!(
+			format_web_url(
+				"https://example.com/o/r",
+				&UrlTemplates {
+					commit: String::new(),
+					file: String::from("{base}/view/{path}@{commit}"),
+					lines: String::from("?l={start}"),
+				},
+				&file,
+			),
+			format!(
+				"https://example.com/o/r/view/src/main.rs@{commit}?l=3"
+			)
+		);
+	}
+
+	#[test]
+	fn test_url_encode() {
+		assert_eq!(url_encode("o/r"), "o%2Fr");
+		assert_eq!(url_encode("me:feat/x-1"), "me%3Afeat%2Fx-1");
+	}
+
+	#[test]
+	fn test_format_web_url_encodes_path() {
+		let commit = CommitId::from_str_unchecked(
+			"d92f9ba0000000000000000000000000000000ff",
+		)
+		.unwrap();
+
+		assert_eq!(
+			format_web_url(
+				"https://github.com/o/r",
+				&ForgeKind::GitHub.into(),
+				&WebTarget {
+					commit,
+					path: Some("docs/a & b/#1?.md"),
+					lines: None,
+				},
+			),
+			format!(
+				"https://github.com/o/r/blob/{commit}/docs/a%20%26%20b/%231%3F.md"
+			)
+		);
+	}
+}
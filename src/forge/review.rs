@@ -0,0 +1,325 @@
+//! pull/merge request state of the current branch
+
+use super::{
+	api::{gitlab_ci_state, ApiClient, CiState},
+	url_encode, ForgeKind,
+};
+use crate::AsyncAppNotification;
+use anyhow::{anyhow, Result};
+use asyncgit::{
+	asyncjob::{AsyncJob, AsyncSingleJob, RunParams},
+	sync::{CommitId, RepoPathRef},
+};
+use crossbeam_channel::Sender;
+use serde_json::Value;
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+	time::{Duration, Instant},
+};
+
+/// how long a fetched state is considered fresh
+const REFRESH_INTERVAL: Duration = Duration::from_secs(120);
+
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReviewState {
+	Pending,
+	Approved,
+	ChangesRequested,
+}
+
+/// open pull/merge request of a branch
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PullRequest {
+	pub number: u64,
+	pub url: String,
+	pub draft: bool,
+	pub ci: Option<CiState>,
+	pub review: ReviewState,
+}
+
+impl PullRequest {
+	/// short summary like `#12 ✓ approved`
+	pub fn summary(&self) -> String {
+		let ci = match self.ci {
+			Some(CiState::Success) => " \u{2713}",
+			Some(CiState::Failure) => " \u{2717}",
+			Some(CiState::Pending) => " \u{25cb}",
+			None => "",
+		};
+		let review = match self.review {
+			ReviewState::Pending => "review pending",
+			ReviewState::Approved => "approved",
+			ReviewState::ChangesRequested => "changes requested",
+		};
+		let draft = if self.draft { " draft" } else { "" };
+
+		format!("#{}{draft}{ci} {review}", self.number)
+	}
+}
+
+#[derive(Clone, Debug)]
+struct ReviewRequest {
+	client: ApiClient,
+	branch: String,
+}
+
+impl ReviewRequest {
+	fn fetch(&self) -> Result<Option<PullRequest>> {
+		match self.client.kind() {
+			ForgeKind::GitLab => self.fetch_gitlab(),
+			_ => self.fetch_github(),
+		}
+	}
+
+	fn fetch_github(&self) -> Result<Option<PullRequest>> {
+		let repo = self.client.repo_url();
+		let owner = self
+			.client
+			.project()
+			.split('/')
+			.next()
+			.unwrap_or_default();
+
+		let pulls = self.client.get(&format!(
+			"{repo}/pulls?state=open&head={}",
+			url_encode(&format!("{owner}:{}", self.branch))
+		))?;
+		let Some(pr) = pulls.get(0) else {
+			return Ok(None);
+		};
+
+		let number = pr["number"]
+			.as_u64()
+			.ok_or_else(|| anyhow!("invalid pull request"))?;
+		let head = CommitId::from_str_unchecked(
+			pr["head"]["sha"].as_str().unwrap_or_default(),
+		)?;
+		let reviews = self
+			.client
+			.get(&format!("{repo}/pulls/{number}/reviews"))?;
+
+		Ok(Some(PullRequest {
+			number,
+			url: pr["html_url"].as_str().unwrap_or_default().into(),
+			draft: pr["draft"].as_bool().unwrap_or_default(),
+			ci: self.client.ci_state(head)?,
+			review: github_review_state(&reviews),
+		}))
+	}
+
+	fn fetch_gitlab(&self) -> Result<Option<PullRequest>> {
+		let project = self.client.repo_url();
+
+		let requests = self.client.get(&format!(
+			"{project}/merge_requests?state=opened&source_branch={}",
+			url_encode(&self.branch)
+		))?;
+		let Some(mr) = requests.get(0) else {
+			return Ok(None);
+		};
+
+		let iid = mr["iid"]
+			.as_u64()
+			.ok_or_else(|| anyhow!("invalid merge request"))?;
+		let details = self
+			.client
+			.get(&format!("{project}/merge_requests/{iid}"))?;
+		let approvals = self.client.get(&format!(
+			"{project}/merge_requests/{iid}/approvals"
+		))?;
+
+		Ok(Some(PullRequest {
+			number: iid,
+			url: mr["web_url"].as_str().unwrap_or_default().into(),
+			draft: mr["draft"].as_bool().unwrap_or_default(),
+			ci: details["head_pipeline"]["status"]
+				.as_str()
+				.and_then(gitlab_ci_state),
+			review: if approvals["approved"].as_bool() == Some(true) {
+				ReviewState::Approved
+			} else {
+				ReviewState::Pending
+			},
+		}))
+	}
+}
+
+/// latest review of every reviewer decides
+fn github_review_state(reviews: &Value) -> ReviewState {
+	let mut latest = HashMap::new();
+
+	for review in reviews.as_array().into_iter().flatten() {
+		let state = review["state"].as_str().unwrap_or_default();
+		if matches!(
+			state,
+			"APPROVED" | "CHANGES_REQUESTED" | "DISMISSED"
+		) {
+			latest.insert(
+				review["user"]["login"].as_str().unwrap_or_default(),
+				state,
+			);
+		}
+	}
+
+	if latest.values().any(|s| *s == "CHANGES_REQUESTED") {
+		ReviewState::ChangesRequested
+	} else if latest.values().any(|s| *s == "APPROVED") {
+		ReviewState::Approved
+	} else {
+		ReviewState::Pending
+	}
+}
+
+enum JobState {
+	Request(ReviewRequest),
+	Response(String, Option<PullRequest>),
+}
+
+#[derive(Clone)]
+struct AsyncReviewJob {
+	state: Arc<Mutex<Option<JobState>>>,
+}
+
+impl AsyncReviewJob {
+	fn new(request: ReviewRequest) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(Some(JobState::Request(
+				request,
+			)))),
+		}
+	}
+
+	fn result(&self) -> Option<(String, Option<PullRequest>)> {
+		if let Ok(mut state) = self.state.lock() {
+			if let Some(JobState::Response(branch, pr)) = state.take()
+			{
+				return Some((branch, pr));
+			}
+		}
+
+		None
+	}
+}
+
+impl AsyncJob for AsyncReviewJob {
+	type Notification = AsyncAppNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> asyncgit::Result<Self::Notification> {
+		if let Ok(mut state) = self.state.lock() {
+			*state = state.take().map(|state| match state {
+				JobState::Request(request) => {
+					let pr = request.fetch().unwrap_or_else(|e| {
+						log::error!("forge api: {e}");
+						None
+					});
+					JobState::Response(request.branch, pr)
+				}
+				JobState::Response(branch, pr) => {
+					JobState::Response(branch, pr)
+				}
+			});
+		}
+
+		Ok(AsyncAppNotification::ForgeReview)
+	}
+}
+
+/// keeps the pull request state of the current branch up to date
+pub struct BranchReview {
+	repo: RepoPathRef,
+	job: AsyncSingleJob<AsyncReviewJob>,
+	branch: Option<String>,
+	requested: Option<Instant>,
+	last: Option<PullRequest>,
+}
+
+impl BranchReview {
+	///
+	pub fn new(
+		repo: RepoPathRef,
+		sender: &Sender<AsyncAppNotification>,
+	) -> Self {
+		Self {
+			repo,
+			job: AsyncSingleJob::new(sender.clone()),
+			branch: None,
+			requested: None,
+			last: None,
+		}
+	}
+
+	/// requests the state of `branch` unless it is still fresh
+	pub fn request(&mut self, branch: &str) {
+		let fresh = self.branch.as_deref() == Some(branch)
+			&& self
+				.requested
+				.is_some_and(|at| at.elapsed() < REFRESH_INTERVAL);
+		if fresh || self.job.is_pending() {
+			return;
+		}
+
+		if self.branch.as_deref() != Some(branch) {
+			self.last = None;
+		}
+		self.branch = Some(branch.to_string());
+		self.requested = Some(Instant::now());
+
+		match ApiClient::new(&self.repo.borrow()) {
+			Ok(Some(client)) => {
+				self.job.spawn(AsyncReviewJob::new(ReviewRequest {
+					client,
+					branch: branch.to_string(),
+				}));
+			}
+			Ok(None) => (),
+			Err(e) => log::error!("forge api: {e}"),
+		}
+	}
+
+	/// takes the result of a finished request
+	pub fn update(&mut self) {
+		if let Some((branch, pr)) =
+			self.job.take_last().and_then(|job| job.result())
+		{
+			if self.branch.as_deref() == Some(branch.as_str()) {
+				self.last = pr;
+			}
+		}
+	}
+
+	///
+	pub const fn last(&self) -> Option<&PullRequest> {
+		self.last.as_ref()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use serde_json::json;
+
+	#[test]
+	fn test_github_review_state() {
+		let reviews = json!([
+			{ "user": { "login": "a" }, "state": "CHANGES_REQUESTED" },
+			{ "user": { "login": "b" }, "state": "COMMENTED" },
+			{ "user": { "login": "a" }, "state": "APPROVED" },
+		]);
+
+		assert_eq!(
+			github_review_state(&reviews),
+			ReviewState::Approved
+		);
+		assert_eq!(
+			github_review_state(&json!([])),
+			ReviewState::Pending
+		);
+	}
+}
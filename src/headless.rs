@@ -0,0 +1,180 @@
+//! non-interactive commands printing what the ui would show and
+//! exiting, for scripts and editor plugins
+
+use anyhow::Result;
+use asyncgit::sync::{
+	self,
+	status::{StatusItem, StatusItemType, StatusType},
+	CommitInfo, RepoPath,
+};
+use serde::Serialize;
+use std::io::{self, Write};
+
+/// longest commit summary printed
+const MESSAGE_LENGTH_LIMIT: usize = 500;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeadlessCommand {
+	Status { json: bool },
+	Log { json: bool, count: usize },
+}
+
+#[derive(Serialize)]
+struct FileJson<'a> {
+	path: &'a str,
+	status: &'static str,
+}
+
+#[derive(Serialize)]
+struct StatusJson<'a> {
+	branch: Option<String>,
+	head: Option<String>,
+	state: String,
+	workdir: Vec<FileJson<'a>>,
+	stage: Vec<FileJson<'a>>,
+}
+
+#[derive(Serialize)]
+struct CommitJson<'a> {
+	id: String,
+	author: &'a str,
+	time: i64,
+	message: &'a str,
+}
+
+///
+pub fn run(cmd: HeadlessCommand, repo_path: &RepoPath) -> Result<()> {
+	let mut out = io::stdout().lock();
+
+	match cmd {
+		HeadlessCommand::Status { json } => {
+			status(&mut out, repo_path, json)
+		}
+		HeadlessCommand::Log { json, count } => {
+			log(&mut out, repo_path, json, count)
+		}
+	}
+}
+
+fn status(
+	out: &mut impl Write,
+	repo_path: &RepoPath,
+	json: bool,
+) -> Result<()> {
+	let show_untracked = sync::untracked_files_config(repo_path)?;
+	let workdir = sync::status::get_status(
+		repo_path,
+		StatusType::WorkingDir,
+		Some(show_untracked),
+	)?;
+	let stage = sync::status::get_status(
+		repo_path,
+		StatusType::Stage,
+		Some(show_untracked),
+	)?;
+
+	// unborn branches have no head yet
+	let head = sync::get_head_tuple(repo_path).ok();
+	let branch = head.as_ref().and_then(|head| {
+		head.name.strip_prefix("refs/heads/").map(String::from)
+	});
+
+	if json {
+		serde_json::to_writer_pretty(
+			&mut *out,
+			&StatusJson {
+				branch,
+				head: head.map(|head| head.id.to_string()),
+				state: format!("{:?}", sync::repo_state(repo_path)?)
+					.to_lowercase(),
+				workdir: files_json(&workdir),
+				stage: files_json(&stage),
+			},
+		)?;
+		writeln!(out)?;
+	} else {
+		writeln!(
+			out,
+			"On branch {}",
+			branch.as_deref().unwrap_or("HEAD")
+		)?;
+
+		for (title, items) in
+			[("staged", &stage), ("not staged", &workdir)]
+		{
+			if !items.is_empty() {
+				writeln!(out, "{title}:")?;
+				for item in items {
+					writeln!(
+						out,
+						"  {:<10} {}",
+						status_name(item.status),
+						item.path
+					)?;
+				}
+			}
+		}
+	}
+
+	Ok(())
+}
+
+fn log(
+	out: &mut impl Write,
+	repo_path: &RepoPath,
+	json: bool,
+	count: usize,
+) -> Result<()> {
+	let commits =
+		sync::get_log(repo_path, count, MESSAGE_LENGTH_LIMIT)?;
+
+	if json {
+		let commits: Vec<_> = commits
+			.iter()
+			.map(|commit: &CommitInfo| CommitJson {
+				id: commit.id.to_string(),
+				author: &commit.author,
+				time: commit.time,
+				message: &commit.message,
+			})
+			.collect();
+
+		serde_json::to_writer_pretty(&mut *out, &commits)?;
+		writeln!(out)?;
+	} else {
+		for commit in &commits {
+			writeln!(
+				out,
+				"{} {} {}",
+				commit.id.get_short_string(),
+				commit.author,
+				commit.message
+			)?;
+		}
+	}
+
+	Ok(())
+}
+
+fn files_json(items: &[StatusItem]) -> Vec<FileJson<'_>> {
+	items
+		.iter()
+		.map(|item| FileJson {
+			path: &item.path,
+			status: status_name(item.status),
+		})
+		.collect()
+}
+
+const fn status_name(status: StatusItemType) -> &'static str {
+	match status {
+		StatusItemType::New => "new",
+		StatusItemType::Modified => "modified",
+		StatusItemType::Deleted => "deleted",
+		StatusItemType::Renamed => "renamed",
+		StatusItemType::Typechange => "typechange",
+		StatusItemType::ModeChange => "modechange",
+		StatusItemType::Conflicted => "conflicted",
+		StatusItemType::Ignored => "ignored",
+	}
+}
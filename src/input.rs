@@ -1,8 +1,15 @@
 use crate::notify_mutex::NotifiableMutex;
 use anyhow::Result;
 use crossbeam_channel::{unbounded, Receiver, Sender};
-use crossterm::event::{self, Event, Event::Key, KeyEventKind};
+use crossterm::{
+	event::{
+		self, DisableMouseCapture, EnableMouseCapture, Event,
+		Event::Key, KeyEventKind,
+	},
+	ExecutableCommand,
+};
 use std::{
+	io,
 	sync::{
 		atomic::{AtomicBool, Ordering},
 		Arc,
@@ -13,6 +20,24 @@ use std::{
 
 static FAST_POLL_DURATION: Duration = Duration::from_millis(100);
 static SLOW_POLL_DURATION: Duration = Duration::from_secs(10);
+static MOUSE_CAPTURE: AtomicBool = AtomicBool::new(false);
+
+/// turns reporting of mouse events on or off (opt-in via `--mouse`
+/// since it disables selecting text in the terminal)
+pub fn set_mouse_capture(enabled: bool) -> Result<()> {
+	if enabled {
+		io::stdout().execute(EnableMouseCapture)?;
+	} else {
+		io::stdout().execute(DisableMouseCapture)?;
+	}
+	MOUSE_CAPTURE.store(enabled, Ordering::Relaxed);
+	Ok(())
+}
+
+///
+pub fn mouse_capture() -> bool {
+	MOUSE_CAPTURE.load(Ordering::Relaxed)
+}
 
 ///
 #[derive(Clone, Copy, Debug)]
@@ -0,0 +1,286 @@
+//! optional control socket (`--socket`) letting editor plugins and
+//! scripts drive a running gitui, one command per line:
+//! * `refresh`
+//! * `open-commit <revision>`
+//! * `switch-repo <path>`
+//! * `run <script>` with a one line automation script, e.g.
+//!   `run push()` or `run shell("make")`
+//!
+//! each line is answered with `ok` once the command was queued or
+//! `error: <reason>` if it was not understood
+
+use anyhow::{anyhow, bail, Result};
+use crossbeam_channel::{unbounded, Receiver};
+use std::path::{Path, PathBuf};
+
+/// a command received on the control socket
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IpcCommand {
+	Refresh,
+	OpenCommit(String),
+	SwitchRepo(PathBuf),
+	Run(String),
+}
+
+impl IpcCommand {
+	fn parse(line: &str) -> Result<Self> {
+		let line = line.trim();
+		let (name, arg) = line
+			.split_once(char::is_whitespace)
+			.map_or((line, ""), |(name, arg)| (name, arg.trim()));
+
+		let required = |arg: &str| {
+			if arg.is_empty() {
+				Err(anyhow!("`{name}` needs an argument"))
+			} else {
+				Ok(arg.to_string())
+			}
+		};
+
+		Ok(match name {
+			"refresh" => Self::Refresh,
+			"open-commit" => Self::OpenCommit(required(arg)?),
+			"switch-repo" => Self::SwitchRepo(required(arg)?.into()),
+			"run" => {
+				let script = required(arg)?;
+				rhai::Engine::new()
+					.compile(&script)
+					.map_err(|e| anyhow!("invalid script: {e}"))?;
+				Self::Run(script)
+			}
+			_ => bail!("unknown command `{name}`"),
+		})
+	}
+}
+
+/// listens on the socket until dropped
+pub struct IpcServer {
+	path: PathBuf,
+	receiver: Receiver<IpcCommand>,
+}
+
+impl IpcServer {
+	/// binds the socket at `path`, replacing a stale one left by a
+	/// crashed instance. anything at `path` that is not a socket is
+	/// left alone. since `run` executes commands the socket is only
+	/// accessible to the current user.
+	#[cfg(unix)]
+	pub fn new(path: &Path) -> Result<Self> {
+		use std::os::unix::{fs::FileTypeExt, net::UnixStream};
+
+		if let Ok(meta) = std::fs::symlink_metadata(path) {
+			if !meta.file_type().is_socket() {
+				bail!(
+					"{} exists and is not a socket",
+					path.display()
+				);
+			}
+			if UnixStream::connect(path).is_ok() {
+				bail!("socket {} is in use", path.display());
+			}
+			std::fs::remove_file(path)?;
+		}
+
+		let listener = bind_private(path).map_err(|e| {
+			anyhow!("failed to bind {}: {e}", path.display())
+		})?;
+		let (sender, receiver) = unbounded();
+
+		std::thread::spawn(move || {
+			for stream in listener.incoming() {
+				match stream {
+					Ok(stream) => {
+						let sender = sender.clone();
+						std::thread::spawn(move || {
+							if let Err(e) = serve(&stream, &sender) {
+								log::error!("ipc client error: {e}");
+							}
+						});
+					}
+					Err(e) => log::error!("ipc accept error: {e}"),
+				}
+			}
+		});
+
+		Ok(Self {
+			path: path.to_path_buf(),
+			receiver,
+		})
+	}
+
+	#[cfg(not(unix))]
+	pub fn new(_path: &Path) -> Result<Self> {
+		bail!("the control socket is only supported on unix")
+	}
+
+	///
+	pub fn receiver(&self) -> Receiver<IpcCommand> {
+		self.receiver.clone()
+	}
+}
+
+/// binds the socket inside a directory only the current user can
+/// enter, restricts it to the user and only then moves it to `path`.
+/// bound at `path` directly it would be accessible with the
+/// permissions of the umask until it is restricted
+#[cfg(unix)]
+fn bind_private(
+	path: &Path,
+) -> std::io::Result<std::os::unix::net::UnixListener> {
+	use std::{
+		fs::{self, DirBuilder, Permissions},
+		os::unix::{
+			fs::{DirBuilderExt, PermissionsExt},
+			net::UnixListener,
+		},
+	};
+
+	let name = path.file_name().unwrap_or_default().to_string_lossy();
+	let private = path.with_file_name(format!(
+		".{name}.{}.tmp",
+		std::process::id()
+	));
+	DirBuilder::new().mode(0o700).create(&private)?;
+
+	let bound = private.join("socket");
+	let res = UnixListener::bind(&bound).and_then(|listener| {
+		fs::set_permissions(&bound, Permissions::from_mode(0o600))?;
+		fs::rename(&bound, path)?;
+		Ok(listener)
+	});
+
+	if let Err(e) = fs::remove_dir_all(&private) {
+		log::error!("failed to remove {}: {e}", private.display());
+	}
+
+	res
+}
+
+impl Drop for IpcServer {
+	fn drop(&mut self) {
+		if let Err(e) = std::fs::remove_file(&self.path) {
+			log::error!("failed to remove socket: {e}");
+		}
+	}
+}
+
+/// answers the commands of one client until it disconnects
+#[cfg(unix)]
+fn serve(
+	stream: &std::os::unix::net::UnixStream,
+	sender: &crossbeam_channel::Sender<IpcCommand>,
+) -> Result<()> {
+	use std::io::{BufRead, BufReader, Write};
+
+	let mut writer = stream;
+
+	for line in BufReader::new(stream).lines() {
+		let line = line?;
+		if line.trim().is_empty() {
+			continue;
+		}
+
+		let reply = match IpcCommand::parse(&line) {
+			Ok(cmd) => {
+				sender.send(cmd)?;
+				String::from("ok")
+			}
+			Err(e) => format!("error: {e}"),
+		};
+
+		writeln!(writer, "{reply}")?;
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{IpcCommand, IpcServer};
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_parse() {
+		assert_eq!(
+			IpcCommand::parse("refresh").unwrap(),
+			IpcCommand::Refresh
+		);
+		assert_eq!(
+			IpcCommand::parse("open-commit  HEAD~2 ").unwrap(),
+			IpcCommand::OpenCommit("HEAD~2".into())
+		);
+		assert_eq!(
+			IpcCommand::parse("switch-repo /tmp/my repo").unwrap(),
+			IpcCommand::SwitchRepo("/tmp/my repo".into())
+		);
+		assert_eq!(
+			IpcCommand::parse(r#"run shell("make")"#).unwrap(),
+			IpcCommand::Run(r#"shell("make")"#.into())
+		);
+
+		assert!(IpcCommand::parse("open-commit").is_err());
+		assert!(IpcCommand::parse("run shell(").is_err());
+		assert!(IpcCommand::parse("quit").is_err());
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_server() {
+		use std::io::{BufRead, BufReader, Write};
+		use std::os::unix::net::UnixStream;
+
+		let td = tempfile::TempDir::new().unwrap();
+		let path = td.path().join("gitui.sock");
+
+		let server = IpcServer::new(&path).unwrap();
+		assert!(IpcServer::new(&path).is_err());
+
+		let mut stream = UnixStream::connect(&path).unwrap();
+		stream.write_all(b"refresh\nbogus\n").unwrap();
+
+		let mut lines = BufReader::new(&stream).lines();
+		assert_eq!(lines.next().unwrap().unwrap(), "ok");
+		assert_eq!(
+			lines.next().unwrap().unwrap(),
+			"error: unknown command `bogus`"
+		);
+		assert_eq!(
+			server.receiver().recv().unwrap(),
+			IpcCommand::Refresh
+		);
+
+		drop(server);
+		assert!(!path.exists());
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_server_permissions() {
+		use std::os::unix::fs::PermissionsExt;
+
+		let td = tempfile::TempDir::new().unwrap();
+		let path = td.path().join("gitui.sock");
+
+		let _server = IpcServer::new(&path).unwrap();
+
+		let mode =
+			std::fs::metadata(&path).unwrap().permissions().mode();
+		assert_eq!(mode & 0o777, 0o600);
+		// nothing of the private directory is left behind
+		assert_eq!(std::fs::read_dir(td.path()).unwrap().count(), 1);
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_server_keeps_regular_file() {
+		let td = tempfile::TempDir::new().unwrap();
+		let path = td.path().join("notes.txt");
+		std::fs::write(&path, "keep me").unwrap();
+
+		assert!(IpcServer::new(&path).is_err());
+		assert_eq!(
+			std::fs::read_to_string(&path).unwrap(),
+			"keep me"
+		);
+	}
+}
@@ -1,11 +1,17 @@
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyModifiers};
-use std::{fs::canonicalize, path::PathBuf, rc::Rc};
+use std::{
+	fs::canonicalize,
+	path::{Path, PathBuf},
+	rc::Rc,
+};
+use struct_patch::traits::Patch;
 
 use crate::{args::get_app_config_path, strings::symbol};
 
 use super::{
-	key_list::{GituiKeyEvent, KeysList},
+	key_list::{GituiKeyEvent, KeysList, KeysListPatch},
+	sequences::KeySequence,
 	symbols::KeySymbols,
 };
 
@@ -16,7 +22,9 @@ const KEY_SYMBOLS_FILENAME: &str = "key_symbols.ron";
 #[derive(Default, Clone)]
 pub struct KeyConfig {
 	pub keys: KeysList,
+	pub sequences: Vec<KeySequence>,
 	symbols: KeySymbols,
+	bindings_file: PathBuf,
 }
 
 impl KeyConfig {
@@ -38,18 +46,41 @@ impl KeyConfig {
 		key_bindings_path: Option<&PathBuf>,
 		key_symbols_path: Option<&PathBuf>,
 	) -> Result<Self> {
-		let keys = KeysList::init(
-			key_bindings_path
-				.unwrap_or(&Self::get_config_file()?)
-				.clone(),
-		);
+		let bindings_file = key_bindings_path
+			.unwrap_or(&Self::get_config_file()?)
+			.clone();
+		let keys = KeysList::init(bindings_file.clone());
 		let symbols = KeySymbols::init(
 			key_symbols_path
 				.unwrap_or(&Self::get_symbols_file()?)
 				.clone(),
 		);
 
-		Ok(Self { keys, symbols })
+		let sequences = KeySequence::from_keys_list(&keys);
+
+		Ok(Self {
+			keys,
+			sequences,
+			symbols,
+			bindings_file,
+		})
+	}
+
+	/// `self` with the bindings of `patch` changed
+	pub fn patched(&self, patch: KeysListPatch) -> Self {
+		let mut keys = self.keys.clone();
+		keys.apply(patch);
+
+		Self {
+			sequences: KeySequence::from_keys_list(&keys),
+			keys,
+			..self.clone()
+		}
+	}
+
+	/// file the key bindings are read from and saved to
+	pub fn bindings_file(&self) -> &Path {
+		&self.bindings_file
 	}
 
 	fn get_key_symbol(&self, k: KeyCode) -> &str {
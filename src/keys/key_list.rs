@@ -1,6 +1,13 @@
+use super::sequences::KeySequenceConfig;
+use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ron::ser::PrettyConfig;
 use serde::{Deserialize, Serialize};
-use std::{fs::File, path::PathBuf};
+use std::{
+	fmt::Write as _,
+	fs::{self, File},
+	path::{Path, PathBuf},
+};
 use struct_patch::traits::Patch as PatchTrait;
 use struct_patch::Patch;
 
@@ -34,7 +41,7 @@ impl From<&GituiKeyEvent> for KeyEvent {
 	}
 }
 
-#[derive(Debug, Clone, Patch)]
+#[derive(Debug, Clone, Serialize, Patch)]
 #[patch(attribute(derive(Deserialize, Debug)))]
 pub struct KeysList {
 	pub tab_status: GituiKeyEvent,
@@ -51,6 +58,7 @@ pub struct KeysList {
 	pub open_commit: GituiKeyEvent,
 	pub open_commit_editor: GituiKeyEvent,
 	pub open_help: GituiKeyEvent,
+	pub open_tour: GituiKeyEvent,
 	pub open_options: GituiKeyEvent,
 	pub move_left: GituiKeyEvent,
 	pub move_right: GituiKeyEvent,
@@ -70,9 +78,12 @@ pub struct KeysList {
 	pub blame: GituiKeyEvent,
 	pub file_history: GituiKeyEvent,
 	pub edit_file: GituiKeyEvent,
+	pub restore_version: GituiKeyEvent,
 	pub status_stage_all: GituiKeyEvent,
 	pub status_reset_item: GituiKeyEvent,
 	pub status_ignore_file: GituiKeyEvent,
+	pub status_toggle_ignored: GituiKeyEvent,
+	pub status_stash_untracked: GituiKeyEvent,
 	pub diff_stage_lines: GituiKeyEvent,
 	pub diff_reset_lines: GituiKeyEvent,
 	pub stashing_save: GituiKeyEvent,
@@ -84,19 +95,46 @@ pub struct KeysList {
 	pub cmd_bar_toggle: GituiKeyEvent,
 	pub log_tag_commit: GituiKeyEvent,
 	pub log_mark_commit: GituiKeyEvent,
+	pub log_mark_range: GituiKeyEvent,
+	pub log_export_patches: GituiKeyEvent,
+	pub log_special_refs: GituiKeyEvent,
+	pub log_rebase_interactive: GituiKeyEvent,
+	pub log_unshallow: GituiKeyEvent,
 	pub log_checkout_commit: GituiKeyEvent,
 	pub log_reset_commit: GituiKeyEvent,
 	pub log_reword_commit: GituiKeyEvent,
 	pub log_find: GituiKeyEvent,
+	pub log_toggle_decorations: GituiKeyEvent,
+	pub log_bisect: GituiKeyEvent,
 	pub find_commit_sha: GituiKeyEvent,
 	pub commit_amend: GituiKeyEvent,
+	pub amend_files: GituiKeyEvent,
 	pub toggle_signoff: GituiKeyEvent,
 	pub toggle_verify: GituiKeyEvent,
 	pub copy: GituiKeyEvent,
+	pub copy_menu: GituiKeyEvent,
+	pub open_in_browser: GituiKeyEvent,
+	pub split_left: GituiKeyEvent,
+	pub split_right: GituiKeyEvent,
+	pub repo_next: GituiKeyEvent,
+	pub repo_prev: GituiKeyEvent,
+	pub leader: GituiKeyEvent,
+	pub macro_record: GituiKeyEvent,
+	pub macro_replay: GituiKeyEvent,
+	pub reload_config: GituiKeyEvent,
+	pub refresh: GituiKeyEvent,
+	pub sequences: Vec<KeySequenceConfig>,
 	pub create_branch: GituiKeyEvent,
 	pub rename_branch: GituiKeyEvent,
+	pub copy_branch: GituiKeyEvent,
 	pub select_branch: GituiKeyEvent,
 	pub delete_branch: GituiKeyEvent,
+	pub branch_sort: GituiKeyEvent,
+	pub goto_parent: GituiKeyEvent,
+	pub goto_child: GituiKeyEvent,
+	pub tag_sort: GituiKeyEvent,
+	pub branch_filter: GituiKeyEvent,
+	pub branch_hide_remotes: GituiKeyEvent,
 	pub merge_branch: GituiKeyEvent,
 	pub rebase_branch: GituiKeyEvent,
 	pub reset_branch: GituiKeyEvent,
@@ -107,7 +145,11 @@ pub struct KeysList {
 	pub push: GituiKeyEvent,
 	pub open_file_tree: GituiKeyEvent,
 	pub file_find: GituiKeyEvent,
+	pub files_filter: GituiKeyEvent,
+	pub search_contents: GituiKeyEvent,
 	pub branch_find: GituiKeyEvent,
+	pub branch_flow: GituiKeyEvent,
+	pub branch_cleanup: GituiKeyEvent,
 	pub force_push: GituiKeyEvent,
 	pub fetch: GituiKeyEvent,
 	pub pull: GituiKeyEvent,
@@ -115,17 +157,49 @@ pub struct KeysList {
 	pub undo_commit: GituiKeyEvent,
 	pub diff_hunk_next: GituiKeyEvent,
 	pub diff_hunk_prev: GituiKeyEvent,
+	pub diff_conflict_next: GituiKeyEvent,
+	pub diff_conflict_prev: GituiKeyEvent,
+	pub diff_split_hunk: GituiKeyEvent,
+	pub diff_edit_hunk: GituiKeyEvent,
+	pub diff_toggle_wrap: GituiKeyEvent,
+	pub diff_load_more: GituiKeyEvent,
+	pub diff_normalize_eol: GituiKeyEvent,
 	pub stage_unstage_item: GituiKeyEvent,
 	pub tag_annotate: GituiKeyEvent,
 	pub view_submodules: GituiKeyEvent,
 	pub view_remotes: GituiKeyEvent,
+	pub view_worktrees: GituiKeyEvent,
+	pub worktree_add: GituiKeyEvent,
+	pub worktree_remove: GituiKeyEvent,
+	pub worktree_lock: GituiKeyEvent,
+	pub worktree_prune: GituiKeyEvent,
+	pub view_trash: GituiKeyEvent,
+	pub apply_patch: GituiKeyEvent,
+	pub apply_patch_location: GituiKeyEvent,
+	pub view_stack: GituiKeyEvent,
+	pub stack_fixup: GituiKeyEvent,
+	pub stack_autosquash: GituiKeyEvent,
+	pub rebase_pick: GituiKeyEvent,
+	pub rebase_reword: GituiKeyEvent,
+	pub rebase_edit: GituiKeyEvent,
+	pub rebase_squash: GituiKeyEvent,
+	pub rebase_fixup: GituiKeyEvent,
+	pub rebase_drop: GituiKeyEvent,
+	pub ignore_untracked_flood: GituiKeyEvent,
+	pub absorb: GituiKeyEvent,
+	pub toggle_inline_blame: GituiKeyEvent,
 	pub update_remote_name: GituiKeyEvent,
 	pub update_remote_url: GituiKeyEvent,
 	pub add_remote: GituiKeyEvent,
 	pub delete_remote: GituiKeyEvent,
 	pub view_submodule_parent: GituiKeyEvent,
 	pub update_submodule: GituiKeyEvent,
+	pub submodule_init: GituiKeyEvent,
+	pub submodule_sync: GituiKeyEvent,
+	pub submodule_add: GituiKeyEvent,
+	pub submodule_remove: GituiKeyEvent,
 	pub commit_history_next: GituiKeyEvent,
+	pub commit_template_next: GituiKeyEvent,
 	pub commit: GituiKeyEvent,
 	pub newline: GituiKeyEvent,
 	pub goto_line: GituiKeyEvent,
@@ -133,6 +207,7 @@ pub struct KeysList {
 
 #[rustfmt::skip]
 impl Default for KeysList {
+	#[allow(clippy::too_many_lines)]
 	fn default() -> Self {
 		Self {
 			tab_status: GituiKeyEvent::new(KeyCode::Char('1'), KeyModifiers::empty()),
@@ -149,6 +224,7 @@ impl Default for KeysList {
 			open_commit: GituiKeyEvent::new(KeyCode::Char('c'),  KeyModifiers::empty()),
 			open_commit_editor: GituiKeyEvent::new(KeyCode::Char('e'), KeyModifiers::CONTROL),
 			open_help: GituiKeyEvent::new(KeyCode::Char('h'),  KeyModifiers::empty()),
+			open_tour: GituiKeyEvent::new(KeyCode::Char('t'),  KeyModifiers::empty()),
 			open_options: GituiKeyEvent::new(KeyCode::Char('o'),  KeyModifiers::empty()),
 			move_left: GituiKeyEvent::new(KeyCode::Left,  KeyModifiers::empty()),
 			move_right: GituiKeyEvent::new(KeyCode::Right,  KeyModifiers::empty()),
@@ -168,10 +244,13 @@ impl Default for KeysList {
 			blame: GituiKeyEvent::new(KeyCode::Char('B'),  KeyModifiers::SHIFT),
 			file_history: GituiKeyEvent::new(KeyCode::Char('H'),  KeyModifiers::SHIFT),
 			edit_file: GituiKeyEvent::new(KeyCode::Char('e'),  KeyModifiers::empty()),
+			restore_version: GituiKeyEvent::new(KeyCode::Char('S'),  KeyModifiers::SHIFT),
 			status_stage_all: GituiKeyEvent::new(KeyCode::Char('a'),  KeyModifiers::empty()),
 			status_reset_item: GituiKeyEvent::new(KeyCode::Char('D'),  KeyModifiers::SHIFT),
 			diff_reset_lines: GituiKeyEvent::new(KeyCode::Char('d'),  KeyModifiers::empty()),
 			status_ignore_file: GituiKeyEvent::new(KeyCode::Char('i'),  KeyModifiers::empty()),
+			status_toggle_ignored: GituiKeyEvent::new(KeyCode::Char('I'),  KeyModifiers::SHIFT),
+			status_stash_untracked: GituiKeyEvent::new(KeyCode::Char('u'),  KeyModifiers::empty()),
 			diff_stage_lines: GituiKeyEvent::new(KeyCode::Char('s'),  KeyModifiers::empty()),
 			stashing_save: GituiKeyEvent::new(KeyCode::Char('s'),  KeyModifiers::empty()),
 			stashing_toggle_untracked: GituiKeyEvent::new(KeyCode::Char('u'),  KeyModifiers::empty()),
@@ -182,19 +261,46 @@ impl Default for KeysList {
 			cmd_bar_toggle: GituiKeyEvent::new(KeyCode::Char('.'),  KeyModifiers::empty()),
 			log_tag_commit: GituiKeyEvent::new(KeyCode::Char('t'),  KeyModifiers::empty()),
 			log_mark_commit: GituiKeyEvent::new(KeyCode::Char(' '),  KeyModifiers::empty()),
+			log_mark_range: GituiKeyEvent::new(KeyCode::Char('V'),  KeyModifiers::SHIFT),
+			log_export_patches: GituiKeyEvent::new(KeyCode::Char('E'),  KeyModifiers::SHIFT),
+			log_special_refs: GituiKeyEvent::new(KeyCode::Char('O'),  KeyModifiers::SHIFT),
+			log_rebase_interactive: GituiKeyEvent::new(KeyCode::Char('i'),  KeyModifiers::empty()),
+			log_unshallow: GituiKeyEvent::new(KeyCode::Char('U'),  KeyModifiers::SHIFT),
 			log_checkout_commit: GituiKeyEvent { code: KeyCode::Char('S'), modifiers: KeyModifiers::SHIFT },
 			log_reset_commit: GituiKeyEvent { code: KeyCode::Char('R'), modifiers: KeyModifiers::SHIFT },
 			log_reword_commit: GituiKeyEvent { code: KeyCode::Char('r'), modifiers: KeyModifiers::empty() },
 			log_find: GituiKeyEvent { code: KeyCode::Char('f'), modifiers: KeyModifiers::empty() },
+			log_toggle_decorations: GituiKeyEvent { code: KeyCode::Char('d'), modifiers: KeyModifiers::empty() },
+			log_bisect: GituiKeyEvent { code: KeyCode::Char('X'), modifiers: KeyModifiers::SHIFT },
 			find_commit_sha: GituiKeyEvent::new(KeyCode::Char('j'), KeyModifiers::CONTROL),
 			commit_amend: GituiKeyEvent::new(KeyCode::Char('a'),  KeyModifiers::CONTROL),
+			amend_files: GituiKeyEvent::new(KeyCode::Char('t'),  KeyModifiers::CONTROL),
 			toggle_signoff: GituiKeyEvent::new(KeyCode::Char('s'),  KeyModifiers::CONTROL),
 			toggle_verify: GituiKeyEvent::new(KeyCode::Char('f'),  KeyModifiers::CONTROL),
 			copy: GituiKeyEvent::new(KeyCode::Char('y'),  KeyModifiers::empty()),
+			copy_menu: GituiKeyEvent::new(KeyCode::Char('Y'),  KeyModifiers::SHIFT),
+			open_in_browser: GituiKeyEvent::new(KeyCode::Char('o'),  KeyModifiers::empty()),
+			split_left: GituiKeyEvent::new(KeyCode::Char('['),  KeyModifiers::empty()),
+			split_right: GituiKeyEvent::new(KeyCode::Char(']'),  KeyModifiers::empty()),
+			repo_next: GituiKeyEvent::new(KeyCode::Right,  KeyModifiers::CONTROL),
+			repo_prev: GituiKeyEvent::new(KeyCode::Left,  KeyModifiers::CONTROL),
+			leader: GituiKeyEvent::new(KeyCode::Char('\\'),  KeyModifiers::empty()),
+			sequences: Vec::new(),
+			macro_record: GituiKeyEvent::new(KeyCode::Char('Q'),  KeyModifiers::SHIFT),
+			macro_replay: GituiKeyEvent::new(KeyCode::Char('@'),  KeyModifiers::empty()),
+			reload_config: GituiKeyEvent::new(KeyCode::F(5),  KeyModifiers::empty()),
+			refresh: GituiKeyEvent::new(KeyCode::Char('l'),  KeyModifiers::CONTROL),
 			create_branch: GituiKeyEvent::new(KeyCode::Char('c'),  KeyModifiers::empty()),
 			rename_branch: GituiKeyEvent::new(KeyCode::Char('r'),  KeyModifiers::empty()),
+			copy_branch: GituiKeyEvent::new(KeyCode::Char('y'),  KeyModifiers::empty()),
 			select_branch: GituiKeyEvent::new(KeyCode::Char('b'),  KeyModifiers::empty()),
 			delete_branch: GituiKeyEvent::new(KeyCode::Char('D'),  KeyModifiers::SHIFT),
+			branch_sort: GituiKeyEvent::new(KeyCode::Char('O'),  KeyModifiers::SHIFT),
+			goto_parent: GituiKeyEvent::new(KeyCode::Char('<'),  KeyModifiers::empty()),
+			goto_child: GituiKeyEvent::new(KeyCode::Char('>'),  KeyModifiers::empty()),
+			tag_sort: GituiKeyEvent::new(KeyCode::Char('O'),  KeyModifiers::SHIFT),
+			branch_filter: GituiKeyEvent::new(KeyCode::Char('/'),  KeyModifiers::empty()),
+			branch_hide_remotes: GituiKeyEvent::new(KeyCode::Char('x'),  KeyModifiers::empty()),
 			merge_branch: GituiKeyEvent::new(KeyCode::Char('m'),  KeyModifiers::empty()),
 			rebase_branch: GituiKeyEvent::new(KeyCode::Char('R'),  KeyModifiers::SHIFT),
 			reset_branch: GituiKeyEvent::new(KeyCode::Char('s'),  KeyModifiers::empty()),
@@ -210,20 +316,56 @@ impl Default for KeysList {
 			abort_merge: GituiKeyEvent::new(KeyCode::Char('A'),  KeyModifiers::SHIFT),
 			open_file_tree: GituiKeyEvent::new(KeyCode::Char('F'),  KeyModifiers::SHIFT),
 			file_find: GituiKeyEvent::new(KeyCode::Char('f'),  KeyModifiers::empty()),
+			files_filter: GituiKeyEvent::new(KeyCode::Char('/'),  KeyModifiers::empty()),
+			search_contents: GituiKeyEvent::new(KeyCode::Char('f'),  KeyModifiers::empty()),
 			branch_find: GituiKeyEvent::new(KeyCode::Char('f'),  KeyModifiers::empty()),
+			branch_flow: GituiKeyEvent::new(KeyCode::Char('g'),  KeyModifiers::empty()),
+			branch_cleanup: GituiKeyEvent { code: KeyCode::Char('X'), modifiers: KeyModifiers::SHIFT },
 			diff_hunk_next: GituiKeyEvent::new(KeyCode::Char('n'),  KeyModifiers::empty()),
 			diff_hunk_prev: GituiKeyEvent::new(KeyCode::Char('p'),  KeyModifiers::empty()),
+			diff_conflict_next: GituiKeyEvent::new(KeyCode::Char('n'),  KeyModifiers::CONTROL),
+			diff_conflict_prev: GituiKeyEvent::new(KeyCode::Char('p'),  KeyModifiers::CONTROL),
+			diff_split_hunk: GituiKeyEvent::new(KeyCode::Char('x'),  KeyModifiers::empty()),
+			diff_edit_hunk: GituiKeyEvent::new(KeyCode::Char('e'),  KeyModifiers::empty()),
+			diff_toggle_wrap: GituiKeyEvent::new(KeyCode::Char('W'),  KeyModifiers::SHIFT),
+			diff_load_more: GituiKeyEvent::new(KeyCode::Char('M'),  KeyModifiers::SHIFT),
+			diff_normalize_eol: GituiKeyEvent::new(KeyCode::Char('N'),  KeyModifiers::SHIFT),
 			stage_unstage_item: GituiKeyEvent::new(KeyCode::Enter,  KeyModifiers::empty()),
 			tag_annotate: GituiKeyEvent::new(KeyCode::Char('a'),  KeyModifiers::CONTROL),
 			view_submodules: GituiKeyEvent::new(KeyCode::Char('S'),  KeyModifiers::SHIFT),
 			view_remotes: GituiKeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL),
+			view_worktrees: GituiKeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL),
+			worktree_add: GituiKeyEvent::new(KeyCode::Char('a'), KeyModifiers::empty()),
+			worktree_remove: GituiKeyEvent::new(KeyCode::Char('D'), KeyModifiers::SHIFT),
+			worktree_lock: GituiKeyEvent::new(KeyCode::Char('l'), KeyModifiers::empty()),
+			worktree_prune: GituiKeyEvent::new(KeyCode::Char('P'), KeyModifiers::SHIFT),
+			view_trash: GituiKeyEvent::new(KeyCode::Char('z'), KeyModifiers::CONTROL),
+			apply_patch: GituiKeyEvent::new(KeyCode::Char('a'), KeyModifiers::CONTROL),
+			apply_patch_location: GituiKeyEvent::new(KeyCode::Char('i'), KeyModifiers::empty()),
+			view_stack: GituiKeyEvent::new(KeyCode::Char('k'), KeyModifiers::CONTROL),
+			stack_fixup: GituiKeyEvent::new(KeyCode::Char('f'), KeyModifiers::empty()),
+			stack_autosquash: GituiKeyEvent::new(KeyCode::Char('a'), KeyModifiers::empty()),
+			rebase_pick: GituiKeyEvent::new(KeyCode::Char('p'), KeyModifiers::empty()),
+			rebase_reword: GituiKeyEvent::new(KeyCode::Char('r'), KeyModifiers::empty()),
+			rebase_edit: GituiKeyEvent::new(KeyCode::Char('e'), KeyModifiers::empty()),
+			rebase_squash: GituiKeyEvent::new(KeyCode::Char('s'), KeyModifiers::empty()),
+			rebase_fixup: GituiKeyEvent::new(KeyCode::Char('f'), KeyModifiers::empty()),
+			rebase_drop: GituiKeyEvent::new(KeyCode::Char('d'), KeyModifiers::empty()),
+			ignore_untracked_flood: GituiKeyEvent::new(KeyCode::Char('g'), KeyModifiers::CONTROL),
+			absorb: GituiKeyEvent::new(KeyCode::Char('o'), KeyModifiers::CONTROL),
+			toggle_inline_blame: GituiKeyEvent::new(KeyCode::Char('b'), KeyModifiers::CONTROL),
 			update_remote_name: GituiKeyEvent::new(KeyCode::Char('n'),KeyModifiers::NONE),
 			update_remote_url: GituiKeyEvent::new(KeyCode::Char('u'),KeyModifiers::NONE),
 			add_remote: GituiKeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE),
 			delete_remote: GituiKeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE),
 			view_submodule_parent: GituiKeyEvent::new(KeyCode::Char('p'),  KeyModifiers::empty()),
 			update_submodule: GituiKeyEvent::new(KeyCode::Char('u'),  KeyModifiers::empty()),
+			submodule_init: GituiKeyEvent::new(KeyCode::Char('i'),  KeyModifiers::empty()),
+			submodule_sync: GituiKeyEvent::new(KeyCode::Char('s'),  KeyModifiers::empty()),
+			submodule_add: GituiKeyEvent::new(KeyCode::Char('a'),  KeyModifiers::empty()),
+			submodule_remove: GituiKeyEvent::new(KeyCode::Char('D'),  KeyModifiers::SHIFT),
 			commit_history_next: GituiKeyEvent::new(KeyCode::Char('n'),  KeyModifiers::CONTROL),
+			commit_template_next: GituiKeyEvent::new(KeyCode::Char('l'),  KeyModifiers::CONTROL),
 			commit: GituiKeyEvent::new(KeyCode::Char('d'),  KeyModifiers::CONTROL),
 			newline: GituiKeyEvent::new(KeyCode::Enter,  KeyModifiers::empty()),
 			goto_line: GituiKeyEvent::new(KeyCode::Char('L'),  KeyModifiers::SHIFT),
@@ -244,6 +386,75 @@ impl KeysList {
 		}
 		keys_list
 	}
+
+	/// every field as `(name, ron value)` in declaration order,
+	/// relying on ron printing one field per line when limited to a
+	/// depth of one
+	fn fields(&self) -> Result<Vec<(String, String)>> {
+		let text = ron::ser::to_string_pretty(
+			self,
+			PrettyConfig::new().depth_limit(1),
+		)?;
+
+		Ok(text
+			.lines()
+			.filter_map(|line| {
+				let (name, value) = line.trim().split_once(':')?;
+				Some((
+					name.to_string(),
+					value.trim().trim_end_matches(',').to_string(),
+				))
+			})
+			.collect())
+	}
+
+	/// all key bindings by their field name in declaration order
+	pub fn bindings(&self) -> Vec<(String, GituiKeyEvent)> {
+		self.fields()
+			.map_err(|e| log::error!("KeysList fields: {e}"))
+			.unwrap_or_default()
+			.into_iter()
+			.filter_map(|(name, value)| {
+				ron::from_str::<GituiKeyEvent>(&value)
+					.ok()
+					.map(|binding| (name, binding))
+			})
+			.collect()
+	}
+
+	/// changes the binding called `name` to `key`
+	pub fn set_binding(
+		&mut self,
+		name: &str,
+		key: GituiKeyEvent,
+	) -> Result<()> {
+		let patch: KeysListPatch = ron::from_str(&format!(
+			"({name}: Some({}))",
+			ron::to_string(&key)?
+		))?;
+		self.apply(patch);
+		Ok(())
+	}
+
+	/// writes everything differing from the defaults to `file`
+	pub fn save(&self, file: &Path) -> Result<()> {
+		let defaults = Self::default().fields()?;
+		let mut out = String::from("(\n");
+		for ((name, value), (_, default)) in
+			self.fields()?.iter().zip(&defaults)
+		{
+			if value != default {
+				writeln!(out, "\t{name}: Some({value}),")?;
+			}
+		}
+		out.push_str(")\n");
+
+		if let Some(dir) = file.parent() {
+			fs::create_dir_all(dir)?;
+		}
+		fs::write(file, out)?;
+		Ok(())
+	}
 }
 
 #[cfg(test)]
@@ -296,4 +507,24 @@ mod tests {
 			)
 		);
 	}
+
+	#[test]
+	fn test_save_changed_bindings() {
+		let file = NamedTempFile::new().unwrap();
+		let ctrl_j = GituiKeyEvent::new(
+			KeyCode::Char('j'),
+			KeyModifiers::CONTROL,
+		);
+
+		let mut keys = KeysList::default();
+		keys.set_binding("move_down", ctrl_j).unwrap();
+		keys.save(file.path()).unwrap();
+
+		let text = std::fs::read_to_string(file.path()).unwrap();
+		assert_eq!(text.lines().count(), 3);
+
+		let loaded = KeysList::init(file.path().to_path_buf());
+		assert_eq!(loaded.move_down, ctrl_j);
+		assert_eq!(loaded.move_up, KeysList::default().move_up);
+	}
 }
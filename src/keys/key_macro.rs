@@ -0,0 +1,66 @@
+use crossterm::event::KeyEvent;
+
+/// records a sequence of keys to replay it later
+#[derive(Default)]
+pub struct KeyMacro {
+	recording: Option<Vec<KeyEvent>>,
+	recorded: Vec<KeyEvent>,
+}
+
+impl KeyMacro {
+	/// starts or finishes recording, returns whether it is recording now
+	pub fn toggle_recording(&mut self) -> bool {
+		if let Some(keys) = self.recording.take() {
+			self.recorded = keys;
+			false
+		} else {
+			self.recording = Some(Vec::new());
+			true
+		}
+	}
+
+	///
+	pub const fn is_recording(&self) -> bool {
+		self.recording.is_some()
+	}
+
+	///
+	pub fn has_recording(&self) -> bool {
+		!self.recorded.is_empty()
+	}
+
+	/// adds `key` to the macro if recording
+	pub fn record(&mut self, key: KeyEvent) {
+		if let Some(keys) = &mut self.recording {
+			keys.push(key);
+		}
+	}
+
+	/// the recorded keys repeated `times` times
+	pub fn replay(&self, times: usize) -> Vec<KeyEvent> {
+		self.recorded.repeat(times)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crossterm::event::{KeyCode, KeyModifiers};
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_record_replay() {
+		let a = KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE);
+		let b = KeyEvent::new(KeyCode::Down, KeyModifiers::NONE);
+		let mut key_macro = KeyMacro::default();
+
+		key_macro.record(a);
+		assert!(key_macro.toggle_recording());
+		key_macro.record(a);
+		key_macro.record(b);
+		assert!(!key_macro.toggle_recording());
+		key_macro.record(b);
+
+		assert_eq!(key_macro.replay(2), vec![a, b, a, b]);
+	}
+}
@@ -1,6 +1,12 @@
 mod key_config;
 mod key_list;
+mod key_macro;
+mod sequences;
 mod symbols;
 
 pub use key_config::{KeyConfig, SharedKeyConfig};
-pub use key_list::key_match;
+pub use key_list::{
+	key_match, GituiKeyEvent, KeysList, KeysListPatch,
+};
+pub use key_macro::KeyMacro;
+pub use sequences::SequenceMatcher;
@@ -0,0 +1,305 @@
+use super::key_list::{key_match, GituiKeyEvent, KeysList};
+use anyhow::{anyhow, bail, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// a sequence like `g g` or `<leader> p f` as written in the config
+/// triggering the binding named `action` (e.g. `home`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeySequenceConfig {
+	pub keys: String,
+	pub action: String,
+}
+
+/// parsed sequence emitting the key bound to its action
+#[derive(Debug, Clone)]
+pub struct KeySequence {
+	pub keys: Vec<GituiKeyEvent>,
+	pub action: GituiKeyEvent,
+}
+
+impl KeySequence {
+	/// parses all sequences of `list`, logging and skipping invalid ones
+	pub fn from_keys_list(list: &KeysList) -> Vec<Self> {
+		if list.sequences.is_empty() {
+			return Vec::new();
+		}
+
+		let bindings: HashMap<String, GituiKeyEvent> =
+			list.bindings().into_iter().collect();
+
+		list.sequences
+			.iter()
+			.filter_map(|seq| {
+				let parsed = parse_keys(&seq.keys, list.leader)
+					.and_then(|keys| {
+						let action = bindings
+							.get(seq.action.as_str())
+							.copied()
+							.ok_or_else(|| {
+								anyhow!(
+									"unknown action '{}'",
+									seq.action
+								)
+							})?;
+						Ok(Self { keys, action })
+					});
+
+				parsed
+					.map_err(|e| {
+						log::error!(
+							"key sequence '{}': {e}",
+							seq.keys
+						);
+					})
+					.ok()
+			})
+			.collect()
+	}
+}
+
+/// parses whitespace separated keys like `g`, `G`, `<leader>`,
+/// `<C-r>` or `<Enter>`
+pub fn parse_keys(
+	s: &str,
+	leader: GituiKeyEvent,
+) -> Result<Vec<GituiKeyEvent>> {
+	let keys = s
+		.split_whitespace()
+		.map(|token| parse_key(token, leader))
+		.collect::<Result<Vec<_>>>()?;
+
+	if keys.is_empty() {
+		bail!("empty sequence");
+	}
+
+	Ok(keys)
+}
+
+fn parse_key(
+	token: &str,
+	leader: GituiKeyEvent,
+) -> Result<GituiKeyEvent> {
+	let mut chars = token.chars();
+	if let (Some(c), None) = (chars.next(), chars.next()) {
+		return Ok(char_key(c, KeyModifiers::NONE));
+	}
+
+	let name = token
+		.strip_prefix('<')
+		.and_then(|t| t.strip_suffix('>'))
+		.ok_or_else(|| anyhow!("invalid key '{token}'"))?;
+
+	if name.eq_ignore_ascii_case("leader") {
+		return Ok(leader);
+	}
+
+	let mut modifiers = KeyModifiers::NONE;
+	let mut name = name;
+	while let Some((prefix, rest)) = name.split_once('-') {
+		if rest.is_empty() {
+			break;
+		}
+		modifiers |= match prefix {
+			"C" => KeyModifiers::CONTROL,
+			"A" | "M" => KeyModifiers::ALT,
+			"S" => KeyModifiers::SHIFT,
+			_ => bail!("invalid modifier in '{token}'"),
+		};
+		name = rest;
+	}
+
+	let mut chars = name.chars();
+	if let (Some(c), None) = (chars.next(), chars.next()) {
+		return Ok(char_key(c, modifiers));
+	}
+
+	let code = match name.to_ascii_lowercase().as_str() {
+		"enter" | "cr" => KeyCode::Enter,
+		"esc" => KeyCode::Esc,
+		"tab" => KeyCode::Tab,
+		"backtab" => KeyCode::BackTab,
+		"space" => KeyCode::Char(' '),
+		"bs" | "backspace" => KeyCode::Backspace,
+		"del" | "delete" => KeyCode::Delete,
+		"insert" => KeyCode::Insert,
+		"left" => KeyCode::Left,
+		"right" => KeyCode::Right,
+		"up" => KeyCode::Up,
+		"down" => KeyCode::Down,
+		"home" => KeyCode::Home,
+		"end" => KeyCode::End,
+		"pageup" => KeyCode::PageUp,
+		"pagedown" => KeyCode::PageDown,
+		"lt" => KeyCode::Char('<'),
+		f => f
+			.strip_prefix('f')
+			.and_then(|n| n.parse().ok())
+			.map(KeyCode::F)
+			.ok_or_else(|| anyhow!("unknown key '{token}'"))?,
+	};
+
+	Ok(GituiKeyEvent::new(code, modifiers))
+}
+
+/// upper case letters come with `SHIFT` like in the key list
+fn char_key(c: char, modifiers: KeyModifiers) -> GituiKeyEvent {
+	if c.is_uppercase() {
+		GituiKeyEvent::new(
+			KeyCode::Char(c),
+			modifiers | KeyModifiers::SHIFT,
+		)
+	} else {
+		GituiKeyEvent::new(KeyCode::Char(c), modifiers)
+	}
+}
+
+/// collects keys until they complete a sequence or can't anymore
+#[derive(Default)]
+pub struct SequenceMatcher {
+	pending: Vec<KeyEvent>,
+}
+
+impl SequenceMatcher {
+	/// returns the keys to process now: nothing while a sequence is
+	/// incomplete, its action once complete, otherwise the collected
+	/// keys as they were pressed
+	pub fn feed(
+		&mut self,
+		sequences: &[KeySequence],
+		key: KeyEvent,
+	) -> Vec<KeyEvent> {
+		if sequences.is_empty() {
+			return vec![key];
+		}
+
+		self.pending.push(key);
+
+		let matches_pending = |seq: &&KeySequence| {
+			seq.keys.len() >= self.pending.len()
+				&& self
+					.pending
+					.iter()
+					.zip(&seq.keys)
+					.all(|(ev, binding)| key_match(ev, *binding))
+		};
+
+		let candidates: Vec<&KeySequence> =
+			sequences.iter().filter(matches_pending).collect();
+
+		if candidates.is_empty() {
+			return std::mem::take(&mut self.pending);
+		}
+
+		// a complete sequence wins over longer ones sharing its prefix
+		if let Some(seq) = candidates
+			.iter()
+			.find(|seq| seq.keys.len() == self.pending.len())
+		{
+			self.pending.clear();
+			return vec![KeyEvent::from(&seq.action)];
+		}
+
+		Vec::new()
+	}
+
+	/// drops an incomplete sequence
+	pub fn reset(&mut self) {
+		self.pending.clear();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	fn key(c: char) -> KeyEvent {
+		KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+	}
+
+	#[test]
+	fn test_parse_keys() {
+		let leader = GituiKeyEvent::new(
+			KeyCode::Char(' '),
+			KeyModifiers::NONE,
+		);
+
+		assert_eq!(
+			parse_keys("<leader> G <C-r> <S-Down> <F5>", leader)
+				.unwrap(),
+			vec![
+				leader,
+				GituiKeyEvent::new(
+					KeyCode::Char('G'),
+					KeyModifiers::SHIFT
+				),
+				GituiKeyEvent::new(
+					KeyCode::Char('r'),
+					KeyModifiers::CONTROL
+				),
+				GituiKeyEvent::new(
+					KeyCode::Down,
+					KeyModifiers::SHIFT
+				),
+				GituiKeyEvent::new(KeyCode::F(5), KeyModifiers::NONE),
+			]
+		);
+		assert!(parse_keys("", leader).is_err());
+		assert!(parse_keys("<nope>", leader).is_err());
+	}
+
+	#[test]
+	fn test_from_keys_list() {
+		let mut list = KeysList::default();
+		list.sequences = vec![
+			KeySequenceConfig {
+				keys: "<leader> p".into(),
+				action: "push".into(),
+			},
+			KeySequenceConfig {
+				keys: "g g".into(),
+				action: "nope".into(),
+			},
+		];
+
+		let sequences = KeySequence::from_keys_list(&list);
+
+		assert_eq!(sequences.len(), 1);
+		assert_eq!(sequences[0].keys, vec![list.leader, key_p()]);
+		assert_eq!(sequences[0].action, list.push);
+	}
+
+	fn key_p() -> GituiKeyEvent {
+		GituiKeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE)
+	}
+
+	#[test]
+	fn test_matcher() {
+		let home =
+			GituiKeyEvent::new(KeyCode::Home, KeyModifiers::NONE);
+		let sequences = vec![KeySequence {
+			keys: parse_keys("g g", home).unwrap(),
+			action: home,
+		}];
+		let mut matcher = SequenceMatcher::default();
+
+		assert_eq!(matcher.feed(&sequences, key('g')), vec![]);
+		assert_eq!(
+			matcher.feed(&sequences, key('g')),
+			vec![KeyEvent::from(&home)]
+		);
+
+		assert_eq!(matcher.feed(&sequences, key('g')), vec![]);
+		assert_eq!(
+			matcher.feed(&sequences, key('j')),
+			vec![key('g'), key('j')]
+		);
+
+		assert_eq!(
+			matcher.feed(&sequences, key('j')),
+			vec![key('j')]
+		);
+	}
+}
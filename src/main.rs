@@ -61,37 +61,50 @@
 
 mod app;
 mod args;
+mod automation;
 mod bug_report;
 mod clipboard;
 mod cmdbar;
+mod commit_lint;
+mod commit_templates;
+mod completion;
 mod components;
+mod event_log;
+mod forge;
+mod headless;
 mod input;
+mod ipc;
 mod keys;
 mod notify_mutex;
 mod options;
 mod popup_stack;
 mod popups;
 mod queue;
+mod refresh;
+mod repo_config;
 mod spinner;
 mod string_utils;
 mod strings;
 mod tabs;
+mod tickets;
 mod ui;
 mod watcher;
 
 use crate::{
 	app::App,
 	args::{process_cmdline, CliArgs},
+	repo_config::RepoConfig,
 };
 use anyhow::{anyhow, bail, Result};
 use app::QuitState;
 use asyncgit::{
-	sync::{utils::repo_work_dir, RepoPath},
+	sync::{get_config_string, utils::repo_work_dir, RepoPath},
 	AsyncGitNotification,
 };
 use backtrace::Backtrace;
 use crossbeam_channel::{never, tick, unbounded, Receiver, Select};
 use crossterm::{
+	event::{DisableFocusChange, EnableFocusChange},
 	terminal::{
 		disable_raw_mode, enable_raw_mode, EnterAlternateScreen,
 		LeaveAlternateScreen,
@@ -99,7 +112,9 @@ use crossterm::{
 	ExecutableCommand,
 };
 use input::{Input, InputEvent, InputState};
+use ipc::{IpcCommand, IpcServer};
 use keys::KeyConfig;
+use options::{Options, WatcherStrategy};
 use ratatui::backend::CrosstermBackend;
 use scopeguard::defer;
 use scopetime::scope_time;
@@ -115,7 +130,8 @@ use watcher::RepoWatcher;
 
 type Terminal = ratatui::Terminal<CrosstermBackend<io::Stdout>>;
 
-static TICK_INTERVAL: Duration = Duration::from_secs(5);
+/// whitespace separated globs of paths the watcher ignores
+const WATCHER_IGNORE_CONFIG: &str = "gitui.watcherIgnore";
 static SPINNER_INTERVAL: Duration = Duration::from_millis(80);
 
 ///
@@ -123,9 +139,12 @@ static SPINNER_INTERVAL: Duration = Duration::from_millis(80);
 pub enum QueueEvent {
 	Tick,
 	Notify,
+	/// the watcher saw these paths (relative to the workdir) change
+	Changed(Vec<String>),
 	SpinnerUpdate,
 	AsyncEvent(AsyncNotification),
 	InputEvent(InputEvent),
+	Ipc(IpcCommand),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -138,6 +157,20 @@ pub enum SyntaxHighlightProgress {
 pub enum AsyncAppNotification {
 	///
 	SyntaxHighlighting(SyntaxHighlightProgress),
+	///
+	CommitLint,
+	///
+	Completion,
+	///
+	InlineBlame,
+	///
+	Automation,
+	///
+	#[cfg(feature = "forge-api")]
+	ForgeReview,
+	///
+	#[cfg(feature = "forge-api")]
+	ForgeChecks,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -148,12 +181,6 @@ pub enum AsyncNotification {
 	Git(AsyncGitNotification),
 }
 
-#[derive(Clone, Copy, PartialEq)]
-enum Updater {
-	Ticker,
-	NotifyWatcher,
-}
-
 /// Do `log::error!` and `eprintln!` in one line.
 macro_rules! log_eprintln {
 	( $($arg:tt)* ) => {{
@@ -170,15 +197,34 @@ fn main() -> Result<()> {
 	asyncgit::register_tracing_logging();
 	ensure_valid_path(&cliargs.repo_path)?;
 
-	let key_config = KeyConfig::init(
-		cliargs.key_bindings_path.as_ref(),
-		cliargs.key_symbols_path.as_ref(),
-	)
-	.map_err(|e| log_eprintln!("KeyConfig loading error: {e}"))
-	.unwrap_or_default();
-	let theme = Theme::init(&cliargs.theme);
+	if let Some(cmd) = cliargs.headless {
+		return headless::run(cmd, &cliargs.repo_path);
+	}
+
+	for repo_path in &cliargs.extra_repos {
+		ensure_valid_path(repo_path)?;
+	}
+
+	if let Some(path) = &cliargs.event_log {
+		event_log::init(path)?;
+		event_log::record(&event_log::Event::Start {
+			version: env!("GITUI_BUILD_NAME"),
+			repo: &repo_work_dir(&cliargs.repo_path)?,
+		});
+	}
+
+	string_utils::set_ambiguous_wide(cliargs.ambiguous_wide);
+	clipboard::set_osc52(cliargs.osc52);
+
+	let (theme, key_config) = load_config(&cliargs);
+
+	let ipc =
+		cliargs.socket.as_deref().map(IpcServer::new).transpose()?;
 
 	setup_terminal()?;
+	if cliargs.mouse {
+		input::set_mouse_capture(true)?;
+	}
 	defer! {
 		shutdown_terminal();
 	}
@@ -189,78 +235,188 @@ fn main() -> Result<()> {
 		start_terminal(io::stdout(), &cliargs.repo_path)?;
 	let input = Input::new();
 
-	let updater = if cliargs.notify_watcher {
-		Updater::NotifyWatcher
-	} else {
-		Updater::Ticker
-	};
+	let result = run_app(
+		app_start,
+		&cliargs,
+		theme,
+		key_config,
+		&input,
+		&ipc.as_ref().map_or_else(never, IpcServer::receiver),
+		&mut terminal,
+	);
+
+	if let Err(e) = &result {
+		event_log::record(&event_log::Event::Error {
+			message: &e.to_string(),
+		});
+	}
+	event_log::record(&event_log::Event::Exit);
+
+	result
+}
 
-	let mut args = cliargs;
+/// reads theme and key config from disk
+fn load_config(cliargs: &CliArgs) -> (Theme, KeyConfig) {
+	let key_config = KeyConfig::init(
+		cliargs.key_bindings_path.as_ref(),
+		cliargs.key_symbols_path.as_ref(),
+	)
+	.map_err(|e| log_eprintln!("KeyConfig loading error: {e}"))
+	.unwrap_or_default();
+	let theme = Theme::init(&cliargs.theme, cliargs.accessible);
 
-	loop {
-		let quit_state = run_app(
-			app_start,
-			args.clone(),
-			theme.clone(),
-			key_config.clone(),
-			&input,
-			updater,
-			&mut terminal,
+	(theme, key_config)
+}
+
+/// an open repository with its own app state and async workers
+struct RepoSession {
+	app: App,
+	name: String,
+	repo_path: RepoPath,
+	rx_git: Receiver<AsyncGitNotification>,
+	rx_app: Receiver<AsyncAppNotification>,
+	rx_ticker: Receiver<Instant>,
+	rx_watcher: Receiver<Vec<String>>,
+}
+
+impl RepoSession {
+	fn new(
+		cliargs: CliArgs,
+		theme: &Theme,
+		key_config: &KeyConfig,
+		input: &Input,
+	) -> Result<Self> {
+		let (tx_git, rx_git) = unbounded();
+		let (tx_app, rx_app) = unbounded();
+
+		let workdir = repo_work_dir(&cliargs.repo_path)?;
+		let name = Path::new(&workdir).file_name().map_or_else(
+			|| workdir.clone(),
+			|name| name.to_string_lossy().into_owned(),
+		);
+
+		let (rx_ticker, rx_watcher) =
+			Self::updaters(&cliargs, &workdir);
+
+		let repo_path = cliargs.repo_path.clone();
+		let mut repo_config = RepoConfig::load(&repo_path);
+		let app = App::new(
+			cliargs,
+			tx_git,
+			tx_app,
+			input.clone(),
+			repo_config.theme(theme),
+			repo_config.key_config(key_config),
 		)?;
 
-		match quit_state {
-			QuitState::OpenSubmodule(p) => {
-				args = CliArgs {
-					repo_path: p,
-					select_file: None,
-					theme: args.theme,
-					notify_watcher: args.notify_watcher,
-					key_bindings_path: args.key_bindings_path,
-					key_symbols_path: args.key_symbols_path,
-				}
-			}
-			_ => break,
+		Ok(Self {
+			app,
+			name,
+			repo_path,
+			rx_git,
+			rx_app,
+			rx_ticker,
+			rx_watcher,
+		})
+	}
+
+	/// ticker or file watcher, depending on the repo's options and
+	/// `--watcher`
+	fn updaters(
+		cliargs: &CliArgs,
+		workdir: &str,
+	) -> (Receiver<Instant>, Receiver<Vec<String>>) {
+		let mut options = Options::watcher_of(&cliargs.repo_path);
+		if cliargs.notify_watcher {
+			options.strategy = WatcherStrategy::Notify;
+		}
+
+		if options.strategy == WatcherStrategy::Tick {
+			return (tick(options.tick()), never());
 		}
+
+		let ignore = get_config_string(
+			&cliargs.repo_path,
+			WATCHER_IGNORE_CONFIG,
+		)
+		.ok()
+		.flatten()
+		.map(|patterns| {
+			patterns.split_whitespace().map(String::from).collect()
+		})
+		.unwrap_or_default();
+
+		(
+			never(),
+			RepoWatcher::new(
+				&cliargs.repo_path,
+				workdir,
+				options,
+				ignore,
+			)
+			.receiver(),
+		)
+	}
+}
+
+fn set_repo_tabs(sessions: &mut [RepoSession], active: usize) {
+	let names: Vec<String> =
+		sessions.iter().map(|s| s.name.clone()).collect();
+
+	for session in sessions {
+		session.app.set_repo_tabs(names.clone(), active);
+	}
+}
+
+/// recreates every session with a freshly loaded theme and key
+/// config, keeping the ui state that survives a restart
+fn reload_sessions(
+	sessions: &mut [RepoSession],
+	cliargs: &CliArgs,
+	theme: &Theme,
+	key_config: &KeyConfig,
+	input: &Input,
+) -> Result<()> {
+	for session in sessions {
+		session.app.store_ui_state();
+		*session = RepoSession::new(
+			cliargs.for_repo(session.repo_path.clone()),
+			theme,
+			key_config,
+			input,
+		)?;
 	}
 
 	Ok(())
 }
 
+#[allow(clippy::too_many_lines)]
 fn run_app(
 	app_start: Instant,
-	cliargs: CliArgs,
-	theme: Theme,
-	key_config: KeyConfig,
+	cliargs: &CliArgs,
+	mut theme: Theme,
+	mut key_config: KeyConfig,
 	input: &Input,
-	updater: Updater,
+	rx_ipc: &Receiver<IpcCommand>,
 	terminal: &mut Terminal,
-) -> Result<QuitState, anyhow::Error> {
-	let (tx_git, rx_git) = unbounded();
-	let (tx_app, rx_app) = unbounded();
-
+) -> Result<()> {
 	let rx_input = input.receiver();
 
-	let (rx_ticker, rx_watcher) = match updater {
-		Updater::NotifyWatcher => {
-			let repo_watcher = RepoWatcher::new(
-				repo_work_dir(&cliargs.repo_path)?.as_str(),
-			);
-
-			(never(), repo_watcher.receiver())
-		}
-		Updater::Ticker => (tick(TICK_INTERVAL), never()),
-	};
-
 	let spinner_ticker = tick(SPINNER_INTERVAL);
 
-	let mut app = App::new(
-		cliargs,
-		tx_git,
-		tx_app,
-		input.clone(),
-		theme,
-		key_config,
-	)?;
+	let mut sessions = std::iter::once(cliargs.clone())
+		.chain(
+			cliargs
+				.extra_repos
+				.iter()
+				.map(|repo_path| cliargs.for_repo(repo_path.clone())),
+		)
+		.map(|args| {
+			RepoSession::new(args, &theme, &key_config, input)
+		})
+		.collect::<Result<Vec<_>>>()?;
+	let mut active = 0;
+	set_repo_tabs(&mut sessions, active);
 
 	let mut spinner = Spinner::default();
 	let mut first_update = true;
@@ -268,17 +424,20 @@ fn run_app(
 	log::trace!("app start: {} ms", app_start.elapsed().as_millis());
 
 	loop {
+		let session = &mut sessions[active];
+
 		let event = if first_update {
 			first_update = false;
 			QueueEvent::Notify
 		} else {
 			select_event(
 				&rx_input,
-				&rx_git,
-				&rx_app,
-				&rx_ticker,
-				&rx_watcher,
+				&session.rx_git,
+				&session.rx_app,
+				&session.rx_ticker,
+				&session.rx_watcher,
 				&spinner_ticker,
+				rx_ipc,
 			)?
 		};
 
@@ -286,11 +445,19 @@ fn run_app(
 			if matches!(event, QueueEvent::SpinnerUpdate) {
 				spinner.update();
 				spinner.draw(terminal)?;
+				if session.app.tick_spinner() {
+					draw(terminal, &session.app)?;
+				}
 				continue;
 			}
 
 			scope_time!("loop");
 
+			let app = &mut session.app;
+
+			let job = event_log::enabled().then(|| job_name(&event));
+			let started = Instant::now();
+
 			match event {
 				QueueEvent::InputEvent(ev) => {
 					if matches!(
@@ -303,7 +470,10 @@ fn run_app(
 					app.event(ev)?;
 				}
 				QueueEvent::Tick | QueueEvent::Notify => {
-					app.update()?;
+					app.update_external(None)?;
+				}
+				QueueEvent::Changed(paths) => {
+					app.update_external(Some(&paths))?;
 				}
 				QueueEvent::AsyncEvent(ev) => {
 					if !matches!(
@@ -315,30 +485,107 @@ fn run_app(
 						app.update_async(ev)?;
 					}
 				}
+				QueueEvent::Ipc(cmd) => app.ipc_command(cmd)?,
 				QueueEvent::SpinnerUpdate => unreachable!(),
 			}
 
-			draw(terminal, &app)?;
+			if let Some(reverse) = app.take_repo_switch() {
+				let count = sessions.len();
+				active = if reverse {
+					active.checked_sub(1).unwrap_or(count - 1)
+				} else {
+					(active + 1) % count
+				};
+				set_repo_tabs(&mut sessions, active);
+				terminal.clear()?;
+				first_update = true;
+				continue;
+			}
+
+			if app.take_watcher_reload() {
+				// `app` still borrows the session
+				let workdir = repo_work_dir(&session.repo_path)?;
+				(session.rx_ticker, session.rx_watcher) =
+					RepoSession::updaters(
+						&cliargs.for_repo(session.repo_path.clone()),
+						&workdir,
+					);
+			}
+
+			if app.take_config_reload() {
+				(theme, key_config) = load_config(cliargs);
+				reload_sessions(
+					&mut sessions,
+					cliargs,
+					&theme,
+					&key_config,
+					input,
+				)?;
+				set_repo_tabs(&mut sessions, active);
+				terminal.clear()?;
+				first_update = true;
+				continue;
+			}
+
+			if let Some(job) = job {
+				event_log::record_job(&job, started.elapsed());
+			}
+
+			let started = Instant::now();
+			draw(terminal, app)?;
+			event_log::record_job("draw", started.elapsed());
 
-			spinner.set_state(app.any_work_pending());
+			spinner.set_state(
+				app.any_work_pending() && !theme.accessible(),
+			);
 			spinner.draw(terminal)?;
 
 			if app.is_quit() {
-				break;
+				if let QuitState::OpenSubmodule(repo_path) =
+					app.quit_state()
+				{
+					app.store_ui_state();
+					sessions[active] = RepoSession::new(
+						cliargs.for_repo(repo_path),
+						&theme,
+						&key_config,
+						input,
+					)?;
+					set_repo_tabs(&mut sessions, active);
+					first_update = true;
+				} else {
+					break;
+				}
 			}
 		}
 	}
 
-	Ok(app.quit_state())
+	for session in &sessions {
+		session.app.store_ui_state();
+	}
+
+	Ok(())
 }
 
 fn setup_terminal() -> Result<()> {
 	enable_raw_mode()?;
 	io::stdout().execute(EnterAlternateScreen)?;
+	// for `WatcherOptions::refresh_on_focus`
+	io::stdout().execute(EnableFocusChange)?;
 	Ok(())
 }
 
 fn shutdown_terminal() {
+	if input::mouse_capture() {
+		if let Err(e) = input::set_mouse_capture(false) {
+			log::error!("disable mouse capture failed:\n{e}");
+		}
+	}
+
+	if let Err(e) = io::stdout().execute(DisableFocusChange) {
+		log::error!("disable focus change failed:\n{e}");
+	}
+
 	let leave_screen =
 		io::stdout().execute(LeaveAlternateScreen).map(|_f| ());
 
@@ -354,17 +601,21 @@ fn shutdown_terminal() {
 }
 
 fn draw(terminal: &mut Terminal, app: &App) -> io::Result<()> {
-	if app.requires_redraw() {
+	let redraw = app.requires_redraw();
+	if redraw {
 		terminal.clear()?;
 	}
 
-	terminal.draw(|f| {
-		if let Err(e) = app.draw(f) {
-			log::error!("failed to draw: {e:?}");
-		}
-	})?;
+	let buffer = terminal
+		.draw(|f| {
+			if let Err(e) = app.draw(f) {
+				log::error!("failed to draw: {e:?}");
+			}
+		})?
+		.buffer
+		.clone();
 
-	Ok(())
+	app.flush_graphics(terminal.backend_mut(), &buffer, redraw)
 }
 
 fn ensure_valid_path(repo_path: &RepoPath) -> Result<()> {
@@ -377,13 +628,32 @@ fn ensure_valid_path(repo_path: &RepoPath) -> Result<()> {
 	}
 }
 
+/// what the event log calls the handling of `event`
+fn job_name(event: &QueueEvent) -> String {
+	match event {
+		QueueEvent::Tick
+		| QueueEvent::Notify
+		| QueueEvent::Changed(_) => String::from("update"),
+		QueueEvent::SpinnerUpdate => String::from("spinner"),
+		QueueEvent::AsyncEvent(AsyncNotification::Git(ev)) => {
+			format!("git:{ev:?}")
+		}
+		QueueEvent::AsyncEvent(AsyncNotification::App(ev)) => {
+			format!("app:{ev:?}")
+		}
+		QueueEvent::InputEvent(_) => String::from("input"),
+		QueueEvent::Ipc(_) => String::from("ipc"),
+	}
+}
+
 fn select_event(
 	rx_input: &Receiver<InputEvent>,
 	rx_git: &Receiver<AsyncGitNotification>,
 	rx_app: &Receiver<AsyncAppNotification>,
 	rx_ticker: &Receiver<Instant>,
-	rx_notify: &Receiver<()>,
+	rx_notify: &Receiver<Vec<String>>,
 	rx_spinner: &Receiver<Instant>,
+	rx_ipc: &Receiver<IpcCommand>,
 ) -> Result<QueueEvent> {
 	let mut sel = Select::new();
 
@@ -393,6 +663,7 @@ fn select_event(
 	sel.recv(rx_ticker);
 	sel.recv(rx_notify);
 	sel.recv(rx_spinner);
+	sel.recv(rx_ipc);
 
 	let oper = sel.select();
 	let index = oper.index();
@@ -406,8 +677,9 @@ fn select_event(
 			QueueEvent::AsyncEvent(AsyncNotification::App(e))
 		}),
 		3 => oper.recv(rx_ticker).map(|_| QueueEvent::Notify),
-		4 => oper.recv(rx_notify).map(|()| QueueEvent::Notify),
+		4 => oper.recv(rx_notify).map(QueueEvent::Changed),
 		5 => oper.recv(rx_spinner).map(|_| QueueEvent::SpinnerUpdate),
+		6 => oper.recv(rx_ipc).map(QueueEvent::Ipc),
 		_ => bail!("unknown select source"),
 	}?;
 
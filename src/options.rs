@@ -1,6 +1,8 @@
+use crate::{queue::Action, repo_config::RepoConfig};
 use anyhow::Result;
 use asyncgit::sync::{
-	diff::DiffOptions, repo_dir, RepoPathRef,
+	diff::DiffOptions, repo_dir, CommitId, LogFilterSearchOptions,
+	RepoPath, RepoPathRef, SearchFields, SearchOptions,
 	ShowUntrackedFilesConfig,
 };
 use ron::{
@@ -14,17 +16,406 @@ use std::{
 	io::{Read, Write},
 	path::PathBuf,
 	rc::Rc,
+	time::Duration,
 };
+use struct_patch::Patch;
 
-#[derive(Default, Clone, Serialize, Deserialize)]
-struct OptionsData {
+/// everything but the ui state and the safety nets against losing
+/// work can be overridden per repository, see `RepoConfig`
+#[derive(Default, Clone, Serialize, Deserialize, Patch)]
+#[patch(attribute(derive(Deserialize)))]
+#[allow(clippy::struct_excessive_bools)]
+pub struct OptionsData {
+	#[patch(skip)]
 	pub tab: usize,
 	pub diff: DiffOptions,
 	pub status_show_untracked: Option<ShowUntrackedFilesConfig>,
+	#[patch(skip)]
 	pub commit_msgs: Vec<String>,
+	#[serde(default)]
+	pub diff_wrap_lines: bool,
+	#[serde(default)]
+	pub status_show_ignored: bool,
+	#[serde(default)]
+	#[patch(skip)]
+	pub ui: UiState,
+	#[serde(default)]
+	#[patch(skip)]
+	splits: SplitRatios,
+	#[serde(default)]
+	pub branches: BranchListOptions,
+	#[serde(default)]
+	pub log: LogDecorations,
+	#[serde(default)]
+	pub watcher: WatcherOptions,
+	#[serde(default)]
+	#[patch(skip)]
+	pub confirm: ConfirmOptions,
+	/// keep discarded workdir content in the trash to restore it
+	#[serde(default)]
+	#[patch(skip)]
+	pub discard_to_trash: bool,
+	/// squash the fixup commits of absorb right away
+	#[serde(default)]
+	pub absorb_autosquash: bool,
+	/// stash local changes around branch switches instead of asking
+	#[serde(default)]
+	pub checkout_autostash: bool,
+}
+
+/// tabs with a resizable split between list and details
+#[derive(Clone, Copy)]
+pub enum SplitTab {
+	Status,
+	Log,
+	Files,
+}
+
+/// width of the left pane in percent
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct SplitRatios {
+	status: u16,
+	log: u16,
+	files: u16,
+}
+
+impl Default for SplitRatios {
+	fn default() -> Self {
+		Self {
+			status: 50,
+			log: 60,
+			files: 40,
+		}
+	}
+}
+
+impl SplitRatios {
+	const fn get(&self, tab: SplitTab) -> u16 {
+		match tab {
+			SplitTab::Status => self.status,
+			SplitTab::Log => self.log,
+			SplitTab::Files => self.files,
+		}
+	}
+
+	const fn get_mut(&mut self, tab: SplitTab) -> &mut u16 {
+		match tab {
+			SplitTab::Status => &mut self.status,
+			SplitTab::Log => &mut self.log,
+			SplitTab::Files => &mut self.files,
+		}
+	}
+}
+
+/// order of the branch list
+#[derive(
+	Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum BranchSort {
+	#[default]
+	Name,
+	/// newest top commit first
+	Recency,
+	/// most commits ahead of the upstream first
+	Ahead,
+}
+
+impl BranchSort {
+	pub const fn next(self) -> Self {
+		match self {
+			Self::Name => Self::Recency,
+			Self::Recency => Self::Ahead,
+			Self::Ahead => Self::Name,
+		}
+	}
+
+	pub const fn prev(self) -> Self {
+		match self {
+			Self::Name => Self::Ahead,
+			Self::Recency => Self::Name,
+			Self::Ahead => Self::Recency,
+		}
+	}
+
+	pub const fn label(self) -> &'static str {
+		match self {
+			Self::Name => "name",
+			Self::Recency => "recency",
+			Self::Ahead => "ahead",
+		}
+	}
+}
+
+/// optional columns and order of the branch list
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BranchListOptions {
+	pub ahead_behind: bool,
+	pub date: bool,
+	pub author: bool,
+	pub upstream: bool,
+	pub sort: BranchSort,
+	/// glob of remote branches left out of the list
+	pub hide_remote: Option<String>,
+}
+
+/// refs shown next to the commits of the log
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogDecorations {
+	/// all ref decorations, hides branches and tags when off
+	pub refs: bool,
+	pub remote_branches: bool,
+	pub tags: bool,
+}
+
+impl Default for LogDecorations {
+	fn default() -> Self {
+		Self {
+			refs: true,
+			remote_branches: true,
+			tags: true,
+		}
+	}
+}
+
+impl LogDecorations {
+	pub const fn show_tags(self) -> bool {
+		self.refs && self.tags
+	}
+
+	pub const fn show_remote_branches(self) -> bool {
+		self.refs && self.remote_branches
+	}
+}
+
+/// how changes in the workdir are noticed
+#[derive(
+	Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum WatcherStrategy {
+	/// refresh on a fixed interval
+	#[default]
+	Tick,
+	/// native file system events
+	Notify,
+	/// scan the workdir for changes, for network mounts and WSL
+	/// where native events are missing
+	Poll,
+}
+
+impl WatcherStrategy {
+	pub const fn next(self) -> Self {
+		match self {
+			Self::Tick => Self::Notify,
+			Self::Notify => Self::Poll,
+			Self::Poll => Self::Tick,
+		}
+	}
+
+	pub const fn prev(self) -> Self {
+		match self {
+			Self::Tick => Self::Poll,
+			Self::Notify => Self::Tick,
+			Self::Poll => Self::Notify,
+		}
+	}
+
+	pub const fn label(self) -> &'static str {
+		match self {
+			Self::Tick => "tick",
+			Self::Notify => "notify",
+			Self::Poll => "poll",
+		}
+	}
+}
+
+/// file watcher settings, applied when the repo is opened
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatcherOptions {
+	pub strategy: WatcherStrategy,
+	/// events within this time are merged into one refresh, also
+	/// the scan interval of `WatcherStrategy::Poll`
+	pub debounce_ms: u64,
+	/// the interval of `WatcherStrategy::Tick`
+	pub tick_secs: u64,
+	/// refresh when the terminal gets the focus back
+	pub refresh_on_focus: bool,
+}
+
+impl Default for WatcherOptions {
+	fn default() -> Self {
+		Self {
+			strategy: WatcherStrategy::default(),
+			debounce_ms: 2000,
+			tick_secs: 5,
+			refresh_on_focus: true,
+		}
+	}
+}
+
+impl WatcherOptions {
+	const DEBOUNCE_STEP: u64 = 250;
+	const DEBOUNCE_MIN: u64 = 250;
+	const DEBOUNCE_MAX: u64 = 10_000;
+	const TICK_MIN: u64 = 1;
+	const TICK_MAX: u64 = 60;
+
+	/// longer or shorter tick interval by a second
+	#[must_use]
+	pub fn tick_change(self, increase: bool) -> Self {
+		let tick_secs = if increase {
+			self.tick_secs.saturating_add(1)
+		} else {
+			self.tick_secs.saturating_sub(1)
+		}
+		.clamp(Self::TICK_MIN, Self::TICK_MAX);
+
+		Self { tick_secs, ..self }
+	}
+
+	pub const fn tick(self) -> Duration {
+		Duration::from_secs(self.tick_secs)
+	}
+
+	/// longer or shorter debounce by one step
+	#[must_use]
+	pub fn debounce_change(self, increase: bool) -> Self {
+		let debounce_ms = if increase {
+			self.debounce_ms.saturating_add(Self::DEBOUNCE_STEP)
+		} else {
+			self.debounce_ms.saturating_sub(Self::DEBOUNCE_STEP)
+		}
+		.clamp(Self::DEBOUNCE_MIN, Self::DEBOUNCE_MAX);
+
+		Self {
+			debounce_ms,
+			..self
+		}
+	}
+
+	pub const fn debounce(self) -> Duration {
+		Duration::from_millis(self.debounce_ms)
+	}
+}
+
+/// destructive actions that ask for confirmation first, the others
+/// always do
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfirmOptions {
+	/// discarding files, hunks and lines
+	pub discard: bool,
+	pub force_push: bool,
+	/// local and remote branches
+	pub branch_delete: bool,
+	/// only asked when uncommitted changes would be lost
+	pub reset_hard: bool,
+	pub stash_drop: bool,
+}
+
+impl Default for ConfirmOptions {
+	fn default() -> Self {
+		Self {
+			discard: true,
+			force_push: true,
+			branch_delete: true,
+			reset_hard: true,
+			stash_drop: true,
+		}
+	}
+}
+
+impl ConfirmOptions {
+	/// whether `action` is confirmed before running it
+	pub const fn requires(self, action: &Action) -> bool {
+		match action {
+			Action::Reset(_)
+			| Action::ResetItems(_)
+			| Action::ResetHunk(..)
+			| Action::ResetLines(..) => self.discard,
+			Action::ForcePush(..) => self.force_push,
+			Action::DeleteLocalBranch(_)
+			| Action::DeleteRemoteBranch(_)
+			| Action::DeleteBranches(_) => self.branch_delete,
+			Action::ResetHard(_) => self.reset_hard,
+			Action::StashDrop(_) => self.stash_drop,
+			_ => true,
+		}
+	}
+}
+
+/// log search filter with its flags stored as raw bits
+#[derive(Default, Clone, Serialize, Deserialize)]
+pub struct LogSearchState {
+	pub pattern: String,
+	pub fields: u32,
+	pub options: u32,
+}
+
+impl From<&LogFilterSearchOptions> for LogSearchState {
+	fn from(options: &LogFilterSearchOptions) -> Self {
+		Self {
+			pattern: options.search_pattern.clone(),
+			fields: options.fields.bits(),
+			options: options.options.bits(),
+		}
+	}
+}
+
+impl From<LogSearchState> for LogFilterSearchOptions {
+	fn from(state: LogSearchState) -> Self {
+		Self {
+			search_pattern: state.pattern,
+			fields: SearchFields::from_bits_truncate(state.fields),
+			options: SearchOptions::from_bits_truncate(state.options),
+		}
+	}
+}
+
+/// view state of the last session, restored on start
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+	/// selected commit in the log
+	pub log_selection: Option<String>,
+	/// active log search filter
+	pub log_search: Option<LogSearchState>,
+	/// stage list instead of workdir list focused in the status tab
+	pub status_stage_focused: bool,
+}
+
+impl UiState {
+	pub fn log_selection(&self) -> Option<CommitId> {
+		self.log_selection
+			.as_deref()
+			.and_then(|id| CommitId::from_str_unchecked(id).ok())
+	}
+
+	pub fn set_log_selection(&mut self, id: Option<CommitId>) {
+		self.log_selection = id.map(|id| id.to_string());
+	}
+
+	pub fn log_search(&self) -> Option<LogFilterSearchOptions> {
+		self.log_search.clone().map(Into::into)
+	}
+
+	pub fn set_log_search(
+		&mut self,
+		options: Option<&LogFilterSearchOptions>,
+	) {
+		self.log_search = options.map(Into::into);
+	}
 }
 
 const COMMIT_MSG_HISTORY_LENGTH: usize = 20;
+const SPLIT_STEP: u16 = 5;
+const SPLIT_MIN: u16 = 10;
+const SPLIT_MAX: u16 = 90;
 
 #[derive(Clone)]
 pub struct Options {
@@ -35,7 +426,6 @@ pub struct Options {
 #[cfg(test)]
 impl Options {
 	pub fn test_env() -> Self {
-		use asyncgit::sync::RepoPath;
 		Self {
 			repo: RefCell::new(RepoPath::Path(Default::default())),
 			data: Default::default(),
@@ -48,7 +438,7 @@ pub type SharedOptions = Rc<RefCell<Options>>;
 impl Options {
 	pub fn new(repo: RepoPathRef) -> SharedOptions {
 		Rc::new(RefCell::new(Self {
-			data: Self::read(&repo).unwrap_or_default(),
+			data: Self::load(&repo),
 			repo,
 		}))
 	}
@@ -107,6 +497,125 @@ impl Options {
 		self.save();
 	}
 
+	pub const fn status_show_ignored(&self) -> bool {
+		self.data.status_show_ignored
+	}
+
+	pub fn status_toggle_show_ignored(&mut self) {
+		self.data.status_show_ignored =
+			!self.data.status_show_ignored;
+
+		self.save();
+	}
+
+	pub const fn diff_wrap_lines(&self) -> bool {
+		self.data.diff_wrap_lines
+	}
+
+	pub fn diff_toggle_wrap_lines(&mut self) {
+		self.data.diff_wrap_lines = !self.data.diff_wrap_lines;
+
+		self.save();
+	}
+
+	/// percentage of the width used by the left pane of `tab`
+	pub const fn split_ratio(&self, tab: SplitTab) -> u16 {
+		self.data.splits.get(tab)
+	}
+
+	/// moves the split of `tab` left or right by one step
+	pub fn split_move(&mut self, tab: SplitTab, right: bool) {
+		let ratio = self.data.splits.get_mut(tab);
+		*ratio = if right {
+			ratio.saturating_add(SPLIT_STEP)
+		} else {
+			ratio.saturating_sub(SPLIT_STEP)
+		}
+		.clamp(SPLIT_MIN, SPLIT_MAX);
+
+		self.save();
+	}
+
+	pub fn branch_list(&self) -> BranchListOptions {
+		self.data.branches.clone()
+	}
+
+	pub fn set_branch_list(&mut self, options: BranchListOptions) {
+		self.data.branches = options;
+		self.save();
+	}
+
+	pub const fn log_decorations(&self) -> LogDecorations {
+		self.data.log
+	}
+
+	pub fn set_log_decorations(
+		&mut self,
+		decorations: LogDecorations,
+	) {
+		self.data.log = decorations;
+		self.save();
+	}
+
+	pub const fn watcher(&self) -> WatcherOptions {
+		self.data.watcher
+	}
+
+	pub fn set_watcher(&mut self, watcher: WatcherOptions) {
+		self.data.watcher = watcher;
+		self.save();
+	}
+
+	pub const fn discard_to_trash(&self) -> bool {
+		self.data.discard_to_trash
+	}
+
+	pub fn toggle_discard_to_trash(&mut self) {
+		self.data.discard_to_trash = !self.data.discard_to_trash;
+		self.save();
+	}
+
+	pub const fn absorb_autosquash(&self) -> bool {
+		self.data.absorb_autosquash
+	}
+
+	pub fn toggle_absorb_autosquash(&mut self) {
+		self.data.absorb_autosquash = !self.data.absorb_autosquash;
+		self.save();
+	}
+
+	pub const fn checkout_autostash(&self) -> bool {
+		self.data.checkout_autostash
+	}
+
+	pub fn toggle_checkout_autostash(&mut self) {
+		self.data.checkout_autostash = !self.data.checkout_autostash;
+		self.save();
+	}
+
+	pub const fn confirm(&self) -> ConfirmOptions {
+		self.data.confirm
+	}
+
+	pub fn set_confirm(&mut self, confirm: ConfirmOptions) {
+		self.data.confirm = confirm;
+		self.save();
+	}
+
+	/// watcher settings of `repo` without loading it into a session
+	pub fn watcher_of(repo: &RepoPath) -> WatcherOptions {
+		Self::load(&RefCell::new(repo.clone())).watcher
+	}
+
+	pub const fn ui_state(&self) -> &UiState {
+		&self.data.ui
+	}
+
+	pub fn set_ui_state(&mut self, state: UiState) {
+		self.data.ui = state;
+		self.save();
+	}
+
 	pub fn add_commit_msg(&mut self, msg: &str) {
 		self.data.commit_msgs.push(msg.to_owned());
 		while self.data.commit_msgs.len() > COMMIT_MSG_HISTORY_LENGTH
@@ -143,6 +652,17 @@ impl Options {
 		}
 	}
 
+	/// the stored options with the overrides of the repo applied
+	fn load(repo: &RepoPathRef) -> OptionsData {
+		let mut data = Self::read(repo).unwrap_or_default();
+		if let Some(patch) =
+			RepoConfig::load(&repo.borrow()).options()
+		{
+			data.apply(patch);
+		}
+		data
+	}
+
 	fn read(repo: &RepoPathRef) -> Result<OptionsData> {
 		let dir = Self::options_file(repo)?;
 
@@ -169,3 +689,126 @@ impl Options {
 		Ok(dir)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_split_move_clamps() {
+		let mut options = Options::test_env();
+		assert_eq!(options.split_ratio(SplitTab::Status), 50);
+
+		for _ in 0..20 {
+			options.split_move(SplitTab::Status, true);
+		}
+		options.split_move(SplitTab::Log, false);
+
+		assert_eq!(options.split_ratio(SplitTab::Status), SPLIT_MAX);
+		assert_eq!(options.split_ratio(SplitTab::Log), 55);
+	}
+
+	#[test]
+	fn test_ui_state_roundtrip() {
+		let mut state = UiState::default();
+		state.set_log_search(Some(&LogFilterSearchOptions {
+			search_pattern: "fix".into(),
+			fields: SearchFields::AUTHORS,
+			options: SearchOptions::FUZZY_SEARCH,
+		}));
+
+		let data = OptionsData {
+			ui: state,
+			..OptionsData::default()
+		};
+		let data: OptionsData = from_bytes(
+			to_string_pretty(&data, PrettyConfig::default())
+				.unwrap()
+				.as_bytes(),
+		)
+		.unwrap();
+
+		let search = data.ui.log_search().unwrap();
+		assert_eq!(search.search_pattern, "fix");
+		assert_eq!(
+			search.fields.bits(),
+			SearchFields::AUTHORS.bits()
+		);
+		assert_eq!(
+			search.options.bits(),
+			SearchOptions::FUZZY_SEARCH.bits()
+		);
+		assert_eq!(data.ui.log_selection(), None);
+	}
+
+	#[test]
+	fn test_log_decorations() {
+		let data: OptionsData = from_bytes(
+			b"(tab: 0, diff: (ignore_whitespace: false, context: 3, \
+			interhunk_lines: 0), status_show_untracked: None, \
+			commit_msgs: [])",
+		)
+		.unwrap();
+		assert!(data.log.show_tags());
+		assert!(data.log.show_remote_branches());
+
+		let data: OptionsData = from_bytes(
+			b"(tab: 0, diff: (ignore_whitespace: false, \
+			context: 3, interhunk_lines: 0), status_show_untracked: \
+			None, commit_msgs: [], log: (refs: false))",
+		)
+		.unwrap();
+		assert!(data.log.tags);
+		assert!(!data.log.show_tags());
+		assert!(!data.log.show_remote_branches());
+	}
+
+	#[test]
+	fn test_watcher_options() {
+		let data: OptionsData = from_bytes(
+			b"(tab: 0, diff: (ignore_whitespace: false, \
+			context: 3, interhunk_lines: 0), status_show_untracked: \
+			None, commit_msgs: [], watcher: (strategy: Poll))",
+		)
+		.unwrap();
+		assert!(data.watcher.strategy == WatcherStrategy::Poll);
+		assert_eq!(data.watcher.debounce_ms, 2000);
+
+		let watcher = data.watcher.debounce_change(false);
+		assert_eq!(watcher.debounce_ms, 1750);
+
+		let watcher = WatcherOptions {
+			debounce_ms: 300,
+			..watcher
+		};
+		assert_eq!(watcher.debounce_change(false).debounce_ms, 250);
+	}
+
+	#[test]
+	fn test_confirm_options() {
+		let data: OptionsData = from_bytes(
+			b"(tab: 0, diff: (ignore_whitespace: false, \
+			context: 3, interhunk_lines: 0), status_show_untracked: \
+			None, commit_msgs: [], confirm: (discard: false))",
+		)
+		.unwrap();
+		assert!(!data.confirm.discard);
+		assert!(data.confirm.stash_drop);
+
+		let confirm = data.confirm;
+		assert!(!confirm
+			.requires(&Action::ResetHunk(String::from("f"), 0)));
+		assert!(confirm.requires(&Action::StashDrop(Vec::new())));
+		assert!(confirm.requires(&Action::UndoCommit));
+
+		let confirm = ConfirmOptions {
+			force_push: false,
+			..confirm
+		};
+		assert!(!confirm.requires(&Action::ForcePush(
+			String::from("main"),
+			true
+		)));
+	}
+}
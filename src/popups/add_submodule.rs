@@ -0,0 +1,127 @@
+use crate::components::{
+	visibility_blocking, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState, InputType, TextInputComponent,
+};
+use crate::{
+	app::Environment,
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, Queue},
+	strings,
+};
+use anyhow::Result;
+use asyncgit::SubmoduleOperation;
+use crossterm::event::Event;
+use ratatui::{layout::Rect, Frame};
+
+pub struct AddSubmodulePopup {
+	input: TextInputComponent,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for AddSubmodulePopup {
+	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
+		if self.is_visible() {
+			self.input.draw(f, rect)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for AddSubmodulePopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			self.input.commands(out, force_all);
+
+			out.push(CommandInfo::new(
+				strings::commands::add_submodule_confirm_msg(
+					&self.key_config,
+				),
+				!self.input.get_text().trim().is_empty(),
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			if self.input.event(ev)?.is_consumed() {
+				return Ok(EventState::Consumed);
+			}
+
+			if let Event::Key(e) = ev {
+				if key_match(e, self.key_config.keys.enter)
+					&& !self.input.get_text().trim().is_empty()
+				{
+					self.add_submodule();
+				}
+
+				return Ok(EventState::Consumed);
+			}
+		}
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.input.is_visible()
+	}
+
+	fn hide(&mut self) {
+		self.input.hide();
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.input.show()?;
+
+		Ok(())
+	}
+}
+
+impl AddSubmodulePopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			queue: env.queue.clone(),
+			input: TextInputComponent::new(
+				env,
+				&strings::add_submodule_popup_title(&env.key_config),
+				&strings::add_submodule_popup_msg(&env.key_config),
+				true,
+			)
+			.with_input_type(InputType::Singleline),
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.show()?;
+
+		Ok(())
+	}
+
+	/// input is the url optionally followed by the path
+	fn add_submodule(&mut self) {
+		let text = self.input.get_text().trim().to_string();
+		let mut parts = text.split_whitespace();
+
+		if let Some(url) = parts.next() {
+			self.queue.push(InternalEvent::SubmoduleOperation(
+				SubmoduleOperation::Add {
+					url: url.to_string(),
+					path: parts.next().map(String::from),
+				},
+			));
+		}
+
+		self.input.clear();
+		self.hide();
+	}
+}
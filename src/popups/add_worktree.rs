@@ -0,0 +1,137 @@
+use crate::components::{
+	visibility_blocking, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState, InputType, TextInputComponent,
+};
+use crate::{
+	app::Environment,
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings,
+};
+use anyhow::Result;
+use asyncgit::sync::{self, RepoPathRef};
+use crossterm::event::Event;
+use ratatui::{layout::Rect, Frame};
+
+pub struct AddWorktreePopup {
+	repo: RepoPathRef,
+	input: TextInputComponent,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for AddWorktreePopup {
+	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
+		if self.is_visible() {
+			self.input.draw(f, rect)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for AddWorktreePopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			self.input.commands(out, force_all);
+
+			out.push(CommandInfo::new(
+				strings::commands::add_worktree_confirm_msg(
+					&self.key_config,
+				),
+				!self.input.get_text().trim().is_empty(),
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			if self.input.event(ev)?.is_consumed() {
+				return Ok(EventState::Consumed);
+			}
+
+			if let Event::Key(e) = ev {
+				if key_match(e, self.key_config.keys.enter)
+					&& !self.input.get_text().trim().is_empty()
+				{
+					self.add_worktree();
+				}
+
+				return Ok(EventState::Consumed);
+			}
+		}
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.input.is_visible()
+	}
+
+	fn hide(&mut self) {
+		self.input.hide();
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.input.show()?;
+
+		Ok(())
+	}
+}
+
+impl AddWorktreePopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			queue: env.queue.clone(),
+			input: TextInputComponent::new(
+				env,
+				&strings::add_worktree_popup_title(&env.key_config),
+				&strings::add_worktree_popup_msg(&env.key_config),
+				true,
+			)
+			.with_input_type(InputType::Singleline),
+			key_config: env.key_config.clone(),
+			repo: env.repo.clone(),
+		}
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.show()?;
+
+		Ok(())
+	}
+
+	/// checks out the branch named after the folder, like the git cli
+	fn add_worktree(&mut self) {
+		let res = sync::add_worktree(
+			&self.repo.borrow(),
+			self.input.get_text().trim(),
+			None,
+		);
+
+		self.input.clear();
+		self.hide();
+
+		match res {
+			Ok(()) => {
+				self.queue.push(InternalEvent::Update(
+					NeedsUpdate::ALL | NeedsUpdate::WORKTREES,
+				));
+			}
+			Err(e) => {
+				log::error!("add worktree: {e}");
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("add worktree error:\n{e}"),
+				));
+			}
+		}
+	}
+}
@@ -0,0 +1,276 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+	sync::{self, status::StatusType, RepoPathRef},
+	StatusItem,
+};
+use crossterm::event::{Event, MouseButton, MouseEventKind};
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// picks which of the staged files go into an amend, the others
+/// stay staged
+pub struct AmendFilesPopup {
+	repo: RepoPathRef,
+	files: Vec<StatusItem>,
+	included: Vec<bool>,
+	selection: usize,
+	visible: bool,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl AmendFilesPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			repo: env.repo.clone(),
+			files: Vec::new(),
+			included: Vec::new(),
+			selection: 0,
+			visible: false,
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	/// opens the picker with the files in `keep_staged` left out
+	pub fn open(&mut self, keep_staged: &[String]) -> Result<()> {
+		self.files = sync::status::get_status(
+			&self.repo.borrow(),
+			StatusType::Stage,
+			None,
+		)?;
+		self.included = self
+			.files
+			.iter()
+			.map(|file| !keep_staged.contains(&file.path))
+			.collect();
+		self.selection = 0;
+
+		self.show()
+	}
+
+	fn apply(&mut self) {
+		let keep_staged = self
+			.files
+			.iter()
+			.zip(&self.included)
+			.filter(|(_, included)| !**included)
+			.map(|(file, _)| file.path.clone())
+			.collect();
+
+		self.queue.push(InternalEvent::AmendKeepStaged(keep_staged));
+		self.hide();
+	}
+
+	fn toggle(&mut self) {
+		if let Some(included) = self.included.get_mut(self.selection)
+		{
+			*included = !*included;
+		}
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let count = self.files.len();
+		if count == 0 {
+			return;
+		}
+
+		self.selection = if up {
+			self.selection.checked_sub(1).unwrap_or(count - 1)
+		} else {
+			(self.selection + 1) % count
+		};
+	}
+
+	fn get_text(&self) -> Vec<Line<'_>> {
+		if self.files.is_empty() {
+			return vec![Line::from(Span::styled(
+				strings::AMEND_FILES_EMPTY,
+				self.theme.text(false, false),
+			))];
+		}
+
+		self.files
+			.iter()
+			.zip(&self.included)
+			.enumerate()
+			.map(|(idx, (file, included))| {
+				let selected = idx == self.selection;
+				let mark = if *included { "[x]" } else { "[ ]" };
+
+				Line::from(vec![
+					Span::styled(
+						format!("{mark} "),
+						self.theme.text(true, selected),
+					),
+					Span::styled(
+						file.path.as_str(),
+						self.theme.item(file.status, selected),
+					),
+				])
+			})
+			.collect()
+	}
+}
+
+impl DrawableComponent for AmendFilesPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			const MIN_WIDTH: u16 = 40;
+
+			let text = self.get_text();
+
+			let width = text
+				.iter()
+				.map(Line::width)
+				.max()
+				.and_then(|width| u16::try_from(width + 2).ok())
+				.unwrap_or_default()
+				.clamp(MIN_WIDTH, area.width);
+			let height = u16::try_from(text.len())
+				.unwrap_or_default()
+				.saturating_add(2)
+				.min(area.height);
+			let area =
+				ui::centered_rect_absolute(width, height, area);
+
+			// keep the selection in view of long lists
+			let scroll = u16::try_from(self.selection)
+				.unwrap_or_default()
+				.saturating_sub(height.saturating_sub(3));
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(text).scroll((scroll, 0)).block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							strings::POPUP_TITLE_AMEND_FILES,
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for AmendFilesPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::amend_files_toggle(
+						&self.key_config,
+						self.included
+							.get(self.selection)
+							.copied()
+							.unwrap_or_default(),
+					),
+					!self.files.is_empty(),
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			match event {
+				Event::Key(key) => {
+					if key_match(key, self.key_config.keys.exit_popup)
+						|| key_match(key, self.key_config.keys.enter)
+					{
+						self.apply();
+					} else if key_match(
+						key,
+						self.key_config.keys.move_up,
+					) {
+						self.move_selection(true);
+					} else if key_match(
+						key,
+						self.key_config.keys.move_down,
+					) {
+						self.move_selection(false);
+					} else if key_match(
+						key,
+						self.key_config.keys.log_mark_commit,
+					) {
+						self.toggle();
+					}
+				}
+				Event::Mouse(mouse)
+					if mouse.kind
+						== MouseEventKind::Down(
+							MouseButton::Right,
+						) =>
+				{
+					self.apply();
+				}
+				_ => (),
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
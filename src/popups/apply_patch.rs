@@ -0,0 +1,389 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings, try_or_popup,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::sync::{
+	self, utils::repo_work_dir, ApplyLocation, RepoPathRef,
+};
+use crossterm::event::{Event, MouseButton, MouseEventKind};
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+use std::{fs, path::PathBuf};
+
+/// files the picker offers next to the folders, hidden entries are
+/// left out
+const PATCH_EXTENSIONS: &[&str] = &["patch", "diff", "mbox", "eml"];
+
+struct Entry {
+	name: String,
+	is_dir: bool,
+}
+
+/// picks a patch file from disk and applies it
+pub struct ApplyPatchPopup {
+	repo: RepoPathRef,
+	dir: Option<PathBuf>,
+	entries: Vec<Entry>,
+	selection: usize,
+	location: ApplyLocation,
+	visible: bool,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl ApplyPatchPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			repo: env.repo.clone(),
+			dir: None,
+			entries: Vec::new(),
+			selection: 0,
+			location: ApplyLocation::default(),
+			visible: false,
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	/// opens in the folder of the last patch or the repo workdir
+	pub fn open(&mut self) -> Result<()> {
+		if self.dir.is_none() {
+			self.dir = Some(PathBuf::from(repo_work_dir(
+				&self.repo.borrow(),
+			)?));
+		}
+		self.reload()?;
+
+		self.show()
+	}
+
+	fn reload(&mut self) -> Result<()> {
+		let Some(dir) = &self.dir else {
+			return Ok(());
+		};
+
+		let mut entries = Vec::new();
+		for entry in fs::read_dir(dir)? {
+			let entry = entry?;
+			let name =
+				entry.file_name().to_string_lossy().to_string();
+			let is_dir = entry.path().is_dir();
+
+			let is_patch = entry
+				.path()
+				.extension()
+				.and_then(|ext| ext.to_str())
+				.is_some_and(|ext| PATCH_EXTENSIONS.contains(&ext));
+
+			if !name.starts_with('.') && (is_dir || is_patch) {
+				entries.push(Entry { name, is_dir });
+			}
+		}
+
+		// folders first
+		entries.sort_by(|a, b| {
+			b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name))
+		});
+
+		if dir.parent().is_some() {
+			entries.insert(
+				0,
+				Entry {
+					name: String::from(".."),
+					is_dir: true,
+				},
+			);
+		}
+
+		self.entries = entries;
+		self.selection = 0;
+
+		Ok(())
+	}
+
+	fn enter_selected(&mut self) {
+		let (Some(dir), Some(entry)) =
+			(&self.dir, self.entries.get(self.selection))
+		else {
+			return;
+		};
+
+		if entry.is_dir {
+			let dir = if entry.name == ".." {
+				dir.parent().map(PathBuf::from)
+			} else {
+				Some(dir.join(&entry.name))
+			};
+			if let Some(dir) = dir {
+				self.dir = Some(dir);
+				try_or_popup!(self, "folder failed:", self.reload());
+			}
+
+			return;
+		}
+
+		let path = dir.join(&entry.name);
+		let res = match sync::apply_patch(
+			&self.repo.borrow(),
+			&path,
+			self.location,
+		) {
+			Ok(res) => res,
+			Err(e) => {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("apply patch failed:\n{e}"),
+				));
+				return;
+			}
+		};
+
+		self.hide();
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+
+		if let Some(conflict) = &res.conflict {
+			self.queue.push(InternalEvent::ShowErrorMsg(
+				strings::apply_patch_conflict(
+					res.applied,
+					&conflict.patch,
+					&conflict.files,
+				),
+			));
+		} else {
+			self.queue.push(InternalEvent::ShowInfoMsg(
+				strings::apply_patch_done(res.applied, &res.files),
+			));
+		}
+	}
+
+	const fn toggle_location(&mut self) {
+		self.location = match self.location {
+			ApplyLocation::WorkDir => ApplyLocation::Index,
+			ApplyLocation::Index => ApplyLocation::Both,
+			ApplyLocation::Both => ApplyLocation::WorkDir,
+		};
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let count = self.entries.len();
+		if count == 0 {
+			return;
+		}
+
+		self.selection = if up {
+			self.selection.checked_sub(1).unwrap_or(count - 1)
+		} else {
+			(self.selection + 1) % count
+		};
+	}
+
+	fn get_text(&self) -> Vec<Line<'_>> {
+		let location = Line::from(Span::styled(
+			strings::apply_patch_location(self.location),
+			self.theme.text(false, false),
+		));
+
+		if self.entries.is_empty() {
+			return vec![
+				location,
+				Line::from(Span::styled(
+					strings::APPLY_PATCH_EMPTY,
+					self.theme.text(false, false),
+				)),
+			];
+		}
+
+		std::iter::once(location)
+			.chain(self.entries.iter().enumerate().map(
+				|(idx, entry)| {
+					let selected = idx == self.selection;
+					let name = if entry.is_dir {
+						format!("{}/", entry.name)
+					} else {
+						entry.name.clone()
+					};
+
+					Line::from(Span::styled(
+						name,
+						self.theme.text(true, selected),
+					))
+				},
+			))
+			.collect()
+	}
+}
+
+impl DrawableComponent for ApplyPatchPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			const MIN_WIDTH: u16 = 40;
+			const MAX_WIDTH: u16 = 100;
+
+			let title = self.dir.as_ref().map_or_else(
+				|| strings::POPUP_TITLE_APPLY_PATCH.to_string(),
+				|dir| {
+					format!(
+						"{}: {}",
+						strings::POPUP_TITLE_APPLY_PATCH,
+						dir.display()
+					)
+				},
+			);
+			let text = self.get_text();
+
+			let width = text
+				.iter()
+				.map(Line::width)
+				.chain(std::iter::once(title.len()))
+				.max()
+				.and_then(|width| u16::try_from(width + 2).ok())
+				.unwrap_or_default()
+				.clamp(MIN_WIDTH, MAX_WIDTH)
+				.min(area.width);
+			let height = u16::try_from(text.len())
+				.unwrap_or_default()
+				.saturating_add(2)
+				.min(area.height);
+			let area =
+				ui::centered_rect_absolute(width, height, area);
+
+			// keep the selection (below the location line) in view
+			let scroll = u16::try_from(self.selection + 1)
+				.unwrap_or_default()
+				.saturating_sub(height.saturating_sub(3));
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(text).scroll((scroll, 0)).block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							title,
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for ApplyPatchPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			let selected = self.entries.get(self.selection).is_some();
+
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::apply_patch_confirm(
+						&self.key_config,
+					),
+					selected,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::apply_patch_location(
+						&self.key_config,
+					),
+					true,
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			match event {
+				Event::Key(key) => {
+					if key_match(key, self.key_config.keys.exit_popup)
+					{
+						self.hide();
+					} else if key_match(
+						key,
+						self.key_config.keys.move_up,
+					) {
+						self.move_selection(true);
+					} else if key_match(
+						key,
+						self.key_config.keys.move_down,
+					) {
+						self.move_selection(false);
+					} else if key_match(
+						key,
+						self.key_config.keys.enter,
+					) {
+						self.enter_selected();
+					} else if key_match(
+						key,
+						self.key_config.keys.apply_patch_location,
+					) {
+						self.toggle_location();
+					}
+				}
+				Event::Mouse(mouse)
+					if mouse.kind
+						== MouseEventKind::Down(
+							MouseButton::Right,
+						) =>
+				{
+					self.hide();
+				}
+				_ => (),
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
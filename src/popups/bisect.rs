@@ -0,0 +1,296 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings, try_or_popup,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::sync::{
+	self, get_config_string, BisectMark, CommitId, RepoPathRef,
+};
+use crossterm::event::{Event, MouseButton, MouseEventKind};
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// git config entry of the command testing each commit
+const BISECT_COMMAND_CONFIG: &str = "gitui.bisectCommand";
+
+#[derive(Clone, PartialEq, Eq)]
+enum BisectAction {
+	Mark(BisectMark),
+	Run(String),
+	Reset,
+}
+
+impl BisectAction {
+	fn label(&self) -> String {
+		match self {
+			Self::Mark(BisectMark::Good) => String::from("Mark good"),
+			Self::Mark(BisectMark::Bad) => String::from("Mark bad"),
+			Self::Mark(BisectMark::Skip) => String::from("Skip"),
+			Self::Run(command) => format!("Run `{command}`"),
+			Self::Reset => String::from("Reset"),
+		}
+	}
+}
+
+pub struct BisectPopup {
+	repo: RepoPathRef,
+	commit: Option<CommitId>,
+	items: Vec<BisectAction>,
+	selection: usize,
+	visible: bool,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl BisectPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			repo: env.repo.clone(),
+			commit: None,
+			items: Vec::new(),
+			selection: 0,
+			visible: false,
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	/// actions on the selected `commit` of the log
+	pub fn open(&mut self, commit: CommitId) -> Result<()> {
+		let state = sync::bisect_state(&self.repo.borrow())?;
+
+		self.items = vec![
+			BisectAction::Mark(BisectMark::Bad),
+			BisectAction::Mark(BisectMark::Good),
+		];
+
+		if let Some(state) = state {
+			self.items.push(BisectAction::Mark(BisectMark::Skip));
+
+			if state.next.is_some() {
+				if let Some(command) = get_config_string(
+					&self.repo.borrow(),
+					BISECT_COMMAND_CONFIG,
+				)? {
+					self.items.push(BisectAction::Run(command));
+				}
+			}
+
+			self.items.push(BisectAction::Reset);
+		}
+
+		self.commit = Some(commit);
+		self.selection = 0;
+
+		self.show()
+	}
+
+	fn apply_selected(&mut self) {
+		self.hide();
+
+		let (Some(commit), Some(action)) =
+			(self.commit.take(), self.items.get(self.selection))
+		else {
+			return;
+		};
+
+		match action {
+			BisectAction::Mark(mark) => {
+				try_or_popup!(
+					self,
+					"bisect error:",
+					sync::bisect_mark(
+						&self.repo.borrow(),
+						commit,
+						*mark
+					)
+				);
+			}
+			BisectAction::Run(command) => {
+				self.queue
+					.push(InternalEvent::BisectRun(command.clone()));
+			}
+			BisectAction::Reset => {
+				try_or_popup!(
+					self,
+					"bisect reset error:",
+					sync::bisect_reset(&self.repo.borrow())
+				);
+			}
+		}
+
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let count = self.items.len();
+		if count == 0 {
+			return;
+		}
+
+		self.selection = if up {
+			self.selection.checked_sub(1).unwrap_or(count - 1)
+		} else {
+			(self.selection + 1) % count
+		};
+	}
+
+	fn get_text(&self) -> Vec<Line<'_>> {
+		self.items
+			.iter()
+			.enumerate()
+			.map(|(idx, item)| {
+				Line::from(Span::styled(
+					item.label(),
+					self.theme.text(true, idx == self.selection),
+				))
+			})
+			.collect()
+	}
+}
+
+impl DrawableComponent for BisectPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			const MIN_WIDTH: u16 = 30;
+
+			let width = self
+				.items
+				.iter()
+				.map(|item| item.label().chars().count())
+				.max()
+				.and_then(|width| u16::try_from(width + 2).ok())
+				.unwrap_or_default()
+				.clamp(MIN_WIDTH, area.width);
+			let height = u16::try_from(self.items.len())
+				.unwrap_or_default()
+				.saturating_add(2);
+			let area =
+				ui::centered_rect_absolute(width, height, area);
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(self.get_text()).block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							strings::POPUP_TITLE_BISECT,
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for BisectPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::bisect_confirm(
+						&self.key_config,
+					),
+					true,
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			match event {
+				Event::Key(key) => {
+					if key_match(key, self.key_config.keys.exit_popup)
+					{
+						self.hide();
+					} else if key_match(
+						key,
+						self.key_config.keys.move_up,
+					) {
+						self.move_selection(true);
+					} else if key_match(
+						key,
+						self.key_config.keys.move_down,
+					) {
+						self.move_selection(false);
+					} else if key_match(
+						key,
+						self.key_config.keys.enter,
+					) {
+						self.apply_selected();
+					}
+				}
+				Event::Mouse(mouse)
+					if mouse.kind
+						== MouseEventKind::Down(
+							MouseButton::Right,
+						) =>
+				{
+					self.hide();
+				}
+				_ => (),
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
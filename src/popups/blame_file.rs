@@ -6,7 +6,7 @@ use crate::{
 		EventState, ScrollType,
 	},
 	keys::{key_match, SharedKeyConfig},
-	popups::{FileRevOpen, InspectCommitOpen},
+	popups::{CopyContext, FileRevOpen, InspectCommitOpen},
 	queue::{InternalEvent, Queue, StackablePopupOpen},
 	string_utils::tabs_to_spaces,
 	strings,
@@ -17,7 +17,7 @@ use anyhow::Result;
 use asyncgit::{
 	asyncjob::AsyncSingleJob,
 	sync::{BlameHunk, CommitId, FileBlame, RepoPathRef},
-	AsyncBlame, AsyncGitNotification, BlameParams,
+	AsyncBlameJob, AsyncGitNotification, BlameParams,
 };
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
@@ -55,7 +55,11 @@ impl SyntaxFileBlame {
 }
 
 enum BlameProcess {
-	GettingBlame(AsyncBlame),
+	GettingBlame {
+		job: AsyncBlameJob,
+		/// lines blamed so far, shown while the rest is calculated
+		partial: Option<SyntaxFileBlame>,
+	},
 	SyntaxHighlighting {
 		unstyled_file_blame: SyntaxFileBlame,
 		job: AsyncSingleJob<AsyncSyntaxJob>,
@@ -66,7 +70,7 @@ enum BlameProcess {
 impl BlameProcess {
 	const fn result(&self) -> Option<&SyntaxFileBlame> {
 		match self {
-			Self::GettingBlame(_) => None,
+			Self::GettingBlame { partial, .. } => partial.as_ref(),
 			Self::SyntaxHighlighting {
 				unstyled_file_blame,
 				..
@@ -94,8 +98,8 @@ pub struct BlameFilePopup {
 	key_config: SharedKeyConfig,
 	current_height: std::cell::Cell<usize>,
 	blame: Option<BlameProcess>,
+	blame_job: AsyncSingleJob<AsyncBlameJob>,
 	app_sender: Sender<AsyncAppNotification>,
-	git_sender: Sender<AsyncGitNotification>,
 	repo: RepoPathRef,
 }
 
@@ -158,24 +162,10 @@ impl DrawableComponent for BlameFilePopup {
 				f,
 				area,
 				&self.theme,
-				// April 2021: `draw_scrollbar` assumes that the last parameter
-				// is `scroll_top`.  Therefore, it subtracts the area’s height
-				// before calculating the position of the scrollbar. To account
-				// for that, we add the current height.
-				number_of_rows + (area.height as usize),
-				// April 2021: we don’t have access to `table_state.offset`
-				// (it’s private), so we use `table_state.selected()` as a
-				// replacement.
-				//
-				// Other widgets, for example `BranchListComponent`, manage
-				// scroll state themselves and use `self.scroll_top` in this
-				// situation.
-				//
-				// There are plans to change `render_stateful_widgets`, so this
-				// might be acceptable as an interim solution.
-				//
-				// https://github.com/fdehau/tui-rs/issues/448
-				table_state.selected().unwrap_or(0),
+				number_of_rows.saturating_sub(usize::from(
+					area.height.saturating_sub(2),
+				)),
+				table_state.offset(),
 				ui::Orientation::Vertical,
 			);
 
@@ -244,6 +234,14 @@ impl Component for BlameFilePopup {
 				)
 				.order(1),
 			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::copy_menu(&self.key_config),
+					true,
+					has_result,
+				)
+				.order(1),
+			);
 		}
 
 		visibility_blocking(self)
@@ -333,6 +331,15 @@ impl Component for BlameFilePopup {
 							),
 						);
 					}
+				} else if key_match(
+					key,
+					self.key_config.keys.copy_menu,
+				) {
+					if let Some(context) = self.copy_context() {
+						self.queue.push(
+							InternalEvent::OpenCopyPopup(context),
+						);
+					}
 				}
 
 				return Ok(EventState::Consumed);
@@ -367,14 +374,15 @@ impl BlameFilePopup {
 			key_config: env.key_config.clone(),
 			current_height: std::cell::Cell::new(0),
 			app_sender: env.sender_app.clone(),
-			git_sender: env.sender_git.clone(),
 			blame: None,
+			blame_job: AsyncSingleJob::new(env.sender_git.clone()),
 			repo: env.repo.clone(),
 		}
 	}
 
 	fn hide_stacked(&mut self, stack: bool) {
 		self.visible = false;
+		self.cancel_blame();
 		if stack {
 			if let Some(request) = self.open_request.clone() {
 				self.queue.push(InternalEvent::PopupStackPush(
@@ -392,21 +400,34 @@ impl BlameFilePopup {
 
 	///
 	pub fn open(&mut self, open: BlameFileOpen) -> Result<()> {
-		self.open_request = Some(open.clone());
-		self.params = Some(BlameParams {
-			file_path: open.file_path,
+		self.cancel_blame();
+
+		let params = BlameParams {
+			file_path: open.file_path.clone(),
 			commit_id: open.commit_id,
-		});
+		};
+		let job = AsyncBlameJob::new(
+			self.repo.borrow().clone(),
+			params.clone(),
+		);
+		self.blame_job.spawn(job.clone());
+
+		self.open_request = Some(open);
+		self.params = Some(params);
 		self.blame =
-			Some(BlameProcess::GettingBlame(AsyncBlame::new(
-				self.repo.borrow().clone(),
-				&self.git_sender,
-			)));
+			Some(BlameProcess::GettingBlame { job, partial: None });
 		self.table_state.get_mut().select(Some(0));
-		self.visible = true;
-		self.update()?;
 
-		Ok(())
+		self.show()
+	}
+
+	/// stops calculating a blame that is no longer going to be shown
+	fn cancel_blame(&self) {
+		if let Some(BlameProcess::GettingBlame { job, .. }) =
+			&self.blame
+		{
+			job.cancel();
+		}
 	}
 
 	///
@@ -415,68 +436,62 @@ impl BlameFilePopup {
 			&& !matches!(self.blame, Some(BlameProcess::Result(_)))
 	}
 
-	pub fn update_async(
-		&mut self,
-		ev: AsyncNotification,
-	) -> Result<()> {
+	pub fn update_async(&mut self, ev: AsyncNotification) {
 		if let AsyncNotification::Git(ev) = ev {
-			return self.update_git(ev);
+			self.update_git(ev);
+		} else {
+			self.update_syntax(ev);
 		}
-
-		self.update_syntax(ev);
-		Ok(())
 	}
 
-	fn update_git(
-		&mut self,
-		event: AsyncGitNotification,
-	) -> Result<()> {
+	fn update_git(&mut self, event: AsyncGitNotification) {
 		if self.is_visible() && event == AsyncGitNotification::Blame {
-			self.update()?;
+			self.update();
 		}
-
-		Ok(())
 	}
 
-	fn update(&mut self) -> Result<()> {
-		if self.is_visible() {
-			if let Some(BlameProcess::GettingBlame(
-				ref mut async_blame,
-			)) = self.blame
-			{
-				if let Some(params) = &self.params {
-					if let Some((
-						previous_blame_params,
-						last_file_blame,
-					)) = async_blame.last()?
-					{
-						if previous_blame_params == *params {
-							self.blame = Some(
-								BlameProcess::SyntaxHighlighting {
-									unstyled_file_blame:
-										SyntaxFileBlame {
-											file_blame:
-												last_file_blame,
-											styled_text: None,
-										},
-									job: AsyncSingleJob::new(
-										self.app_sender.clone(),
-									),
-								},
-							);
-							self.set_open_selection();
-							self.highlight_blame_lines();
-
-							return Ok(());
-						}
-					}
+	fn update(&mut self) {
+		if !self.is_visible() {
+			return;
+		}
 
-					async_blame.request(params.clone())?;
-				}
+		let Some(BlameProcess::GettingBlame {
+			ref job,
+			ref mut partial,
+		}) = self.blame
+		else {
+			return;
+		};
+
+		let first_lines = partial.is_none();
+
+		match job.result() {
+			Some(Ok(file_blame)) => {
+				self.blame = Some(BlameProcess::SyntaxHighlighting {
+					unstyled_file_blame: SyntaxFileBlame {
+						file_blame,
+						styled_text: None,
+					},
+					job: AsyncSingleJob::new(self.app_sender.clone()),
+				});
+				self.highlight_blame_lines();
+			}
+			Some(Err(e)) => {
+				log::error!("blame error: {e}");
+				self.blame = None;
+			}
+			None => {
+				*partial =
+					job.partial().map(|file_blame| SyntaxFileBlame {
+						file_blame,
+						styled_text: None,
+					});
 			}
 		}
 
-		Ok(())
+		if first_lines {
+			self.set_open_selection();
+		}
 	}
 
 	fn update_syntax(&mut self, ev: AsyncNotification) {
@@ -526,6 +541,21 @@ impl BlameFilePopup {
 			self.params.as_ref(),
 			self.blame.as_ref().and_then(|blame| blame.result()),
 		) {
+			(true, Some(params), _)
+				if matches!(
+					self.blame,
+					Some(BlameProcess::GettingBlame { .. })
+				) =>
+			{
+				format!(
+					"{} -- {} -- <calculating.. (who is to blame?)> ({}%)",
+					self.title,
+					params.file_path,
+					self.blame_job
+						.progress()
+						.map_or(0, |p| p.progress)
+				)
+			}
 			(true, Some(params), _) => {
 				format!(
 					"{} -- {} -- <calculating.. (who is to blame?)>",
@@ -630,7 +660,14 @@ impl BlameFilePopup {
 			}
 		};
 
-		let mut cells = if show_metadata {
+		// lines not blamed yet stay empty instead of showing no author
+		let not_blamed_yet = hunk_for_line.is_none()
+			&& matches!(
+				self.blame,
+				Some(BlameProcess::GettingBlame { .. })
+			);
+
+		let mut cells = if show_metadata && !not_blamed_yet {
 			self.get_metadata_for_line_blame(width, hunk_for_line)
 		} else {
 			vec![Cell::from(""), Cell::from(""), Cell::from("")]
@@ -776,6 +813,20 @@ impl BlameFilePopup {
 		self.table_state.set(table_state);
 	}
 
+	/// clipboard context of the blamed revision and selected line
+	fn copy_context(&self) -> Option<CopyContext> {
+		let file_blame =
+			self.blame.as_ref().and_then(|blame| blame.result())?;
+		let line = self.get_selection()? + 1;
+
+		Some(CopyContext {
+			commit: Some(*file_blame.commit_id()),
+			path: Some(file_blame.path().to_string()),
+			lines: Some((line, line)),
+			..CopyContext::default()
+		})
+	}
+
 	fn selected_commit(&self) -> Option<CommitId> {
 		self.blame
 			.as_ref()
@@ -0,0 +1,387 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	queue::{Action, InternalEvent, NeedsUpdate, Queue},
+	string_utils::{pad_width, str_width},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+	asyncjob::AsyncSingleJob,
+	sync::{
+		cleanup_candidates, default_branch, CleanupCandidate,
+		CleanupReason, RepoPathRef,
+	},
+	AsyncDeleteBranchesJob, AsyncGitNotification,
+};
+use crossterm::event::{Event, MouseButton, MouseEventKind};
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// lists local branches that are merged into the default branch
+/// or whose upstream is gone and deletes the marked ones
+pub struct BranchCleanupPopup {
+	repo: RepoPathRef,
+	base: Option<String>,
+	candidates: Vec<CleanupCandidate>,
+	marked: Vec<bool>,
+	selection: usize,
+	visible: bool,
+	job: AsyncSingleJob<AsyncDeleteBranchesJob>,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl BranchCleanupPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			repo: env.repo.clone(),
+			base: None,
+			candidates: Vec::new(),
+			marked: Vec::new(),
+			selection: 0,
+			visible: false,
+			job: AsyncSingleJob::new(env.sender_git.clone()),
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.base = default_branch(&self.repo.borrow())?;
+		self.update_candidates()?;
+
+		self.show()
+	}
+
+	fn update_candidates(&mut self) -> Result<()> {
+		self.candidates = match &self.base {
+			Some(base) => {
+				cleanup_candidates(&self.repo.borrow(), base)?
+			}
+			None => Vec::new(),
+		};
+		// merged branches are safe to delete, gone upstreams are
+		// left for the user to decide
+		self.marked = self
+			.candidates
+			.iter()
+			.map(|candidate| {
+				candidate.reason == CleanupReason::Merged
+			})
+			.collect();
+		self.selection = self
+			.selection
+			.min(self.candidates.len().saturating_sub(1));
+
+		Ok(())
+	}
+
+	/// starts deleting `branch_refs` in the background
+	pub fn delete(&self, branch_refs: Vec<String>) {
+		self.job.spawn(AsyncDeleteBranchesJob::new(
+			self.repo.borrow().clone(),
+			branch_refs,
+		));
+	}
+
+	///
+	pub fn any_work_pending(&self) -> bool {
+		self.job.is_pending()
+	}
+
+	///
+	pub fn update_git(
+		&mut self,
+		ev: AsyncGitNotification,
+	) -> Result<()> {
+		if ev != AsyncGitNotification::DeleteBranches {
+			return Ok(());
+		}
+
+		if let Some(Err(e)) =
+			self.job.take_last().and_then(|job| job.result())
+		{
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"delete branches failed:\n{e}"
+			)));
+		}
+
+		if self.is_visible() {
+			self.update_candidates()?;
+		}
+
+		self.queue.push(InternalEvent::Update(
+			NeedsUpdate::ALL | NeedsUpdate::BRANCHES,
+		));
+
+		Ok(())
+	}
+
+	fn marked_refs(&self) -> Vec<String> {
+		self.candidates
+			.iter()
+			.zip(&self.marked)
+			.filter(|(_, marked)| **marked)
+			.map(|(candidate, _)| candidate.reference.clone())
+			.collect()
+	}
+
+	fn confirm_delete(&self) {
+		let branch_refs = self.marked_refs();
+		if branch_refs.is_empty() || self.job.is_pending() {
+			return;
+		}
+
+		self.queue.push(InternalEvent::ConfirmAction(
+			Action::DeleteBranches(branch_refs),
+		));
+	}
+
+	fn toggle_mark(&mut self) {
+		if let Some(marked) = self.marked.get_mut(self.selection) {
+			*marked = !*marked;
+		}
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let count = self.candidates.len();
+		if count == 0 {
+			return;
+		}
+
+		self.selection = if up {
+			self.selection.checked_sub(1).unwrap_or(count - 1)
+		} else {
+			(self.selection + 1) % count
+		};
+	}
+
+	fn reason_text(&self, reason: CleanupReason) -> String {
+		match reason {
+			CleanupReason::Merged => format!(
+				"merged into {}",
+				self.base.as_deref().unwrap_or_default()
+			),
+			CleanupReason::UpstreamGone => {
+				String::from("upstream gone")
+			}
+		}
+	}
+
+	fn get_text(&self) -> Vec<Line<'_>> {
+		if self.candidates.is_empty() {
+			return vec![Line::from(Span::styled(
+				strings::BRANCH_CLEANUP_EMPTY,
+				self.theme.text(false, false),
+			))];
+		}
+
+		let name_width = self
+			.candidates
+			.iter()
+			.map(|candidate| str_width(&candidate.name))
+			.max()
+			.unwrap_or(0);
+
+		self.candidates
+			.iter()
+			.zip(&self.marked)
+			.enumerate()
+			.map(|(idx, (candidate, marked))| {
+				let selected = idx == self.selection;
+				let mark = if *marked { "[x]" } else { "[ ]" };
+
+				Line::from(vec![
+					Span::styled(
+						format!("{mark} "),
+						self.theme.text(true, selected),
+					),
+					Span::styled(
+						format!(
+							"{}  ",
+							pad_width(&candidate.name, name_width)
+						),
+						self.theme.branch(selected, false),
+					),
+					Span::styled(
+						self.reason_text(candidate.reason),
+						self.theme.text(false, selected),
+					),
+				])
+			})
+			.collect()
+	}
+}
+
+impl DrawableComponent for BranchCleanupPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			const MIN_WIDTH: u16 = 40;
+
+			let text = self.get_text();
+
+			let width = text
+				.iter()
+				.map(Line::width)
+				.max()
+				.and_then(|width| u16::try_from(width + 2).ok())
+				.unwrap_or_default()
+				.clamp(MIN_WIDTH, area.width);
+			let height = u16::try_from(text.len())
+				.unwrap_or_default()
+				.saturating_add(2)
+				.min(area.height);
+			let area =
+				ui::centered_rect_absolute(width, height, area);
+
+			// keep the selection in view of long lists
+			let scroll = u16::try_from(self.selection)
+				.unwrap_or_default()
+				.saturating_sub(height.saturating_sub(3));
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(text).scroll((scroll, 0)).block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							strings::POPUP_TITLE_BRANCH_CLEANUP,
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for BranchCleanupPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::branch_cleanup_mark(
+						&self.key_config,
+						self.marked
+							.get(self.selection)
+							.copied()
+							.unwrap_or_default(),
+					),
+					!self.candidates.is_empty(),
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::branch_cleanup_confirm(
+						&self.key_config,
+					),
+					self.marked.contains(&true)
+						&& !self.job.is_pending(),
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			match event {
+				Event::Key(key) => {
+					if key_match(key, self.key_config.keys.exit_popup)
+					{
+						self.hide();
+					} else if key_match(
+						key,
+						self.key_config.keys.move_up,
+					) {
+						self.move_selection(true);
+					} else if key_match(
+						key,
+						self.key_config.keys.move_down,
+					) {
+						self.move_selection(false);
+					} else if key_match(
+						key,
+						self.key_config.keys.log_mark_commit,
+					) {
+						self.toggle_mark();
+					} else if key_match(
+						key,
+						self.key_config.keys.enter,
+					) {
+						self.confirm_delete();
+					}
+				}
+				Event::Mouse(mouse)
+					if mouse.kind
+						== MouseEventKind::Down(
+							MouseButton::Right,
+						) =>
+				{
+					self.hide();
+				}
+				_ => (),
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
@@ -1,14 +1,18 @@
 use crate::components::{
-	visibility_blocking, CommandBlocking, CommandInfo, Component,
-	DrawableComponent, EventState, FuzzyFinderTarget, VerticalScroll,
+	checkout_with_hook, list_mouse, string_width_align, tab_at,
+	time_to_string, visibility_blocking, CommandBlocking,
+	CommandInfo, Component, DrawableComponent, EventState,
+	FuzzyFinderTarget, ListMouse, VerticalScroll,
 };
 use crate::{
 	app::Environment,
 	components::ScrollType,
 	keys::{key_match, SharedKeyConfig},
+	options::{BranchListOptions, BranchSort, SharedOptions},
 	queue::{
 		Action, InternalEvent, NeedsUpdate, Queue, StackablePopupOpen,
 	},
+	string_utils::{glob_match, pad_width, truncate_with},
 	strings, try_or_popup,
 	ui::{self, Size},
 };
@@ -20,13 +24,17 @@ use asyncgit::{
 			checkout_remote_branch, BranchDetails, LocalBranch,
 			RemoteBranch,
 		},
-		checkout_branch, get_branches_info,
+		branches_compare_upstream, checkout_branch,
+		get_branches_info,
 		status::StatusType,
-		BranchInfo, BranchType, CommitId, RepoPathRef, RepoState,
+		BranchCompare, BranchInfo, BranchType, CommitId, RepoPathRef,
+		RepoState,
 	},
 	AsyncGitNotification,
 };
-use crossterm::event::{Event, KeyEvent};
+use crossterm::event::{
+	Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent,
+};
 use ratatui::{
 	layout::{
 		Alignment, Constraint, Direction, Layout, Margin, Rect,
@@ -37,23 +45,36 @@ use ratatui::{
 };
 use std::cell::Cell;
 use ui::style::SharedTheme;
-use unicode_truncate::UnicodeTruncateStr;
 
 use super::InspectCommitOpen;
+#[cfg(feature = "forge-api")]
+use crate::forge::PullRequest;
 
 ///
+#[allow(clippy::struct_excessive_bools)]
 pub struct BranchListPopup {
 	repo: RepoPathRef,
+	/// all branches of the current tab, sorted
+	unfiltered: Vec<(BranchInfo, Option<BranchCompare>)>,
+	filter: String,
+	filtering: bool,
 	branches: Vec<BranchInfo>,
+	/// upstream comparison of each entry in `branches`
+	compares: Vec<Option<BranchCompare>>,
 	local: bool,
 	has_remotes: bool,
 	visible: bool,
 	selection: u16,
 	scroll: VerticalScroll,
 	current_height: Cell<u16>,
+	list_area: Cell<Rect>,
+	tabs_area: Cell<Rect>,
 	queue: Queue,
 	theme: SharedTheme,
 	key_config: SharedKeyConfig,
+	options: SharedOptions,
+	#[cfg(feature = "forge-api")]
+	pull_request: Option<PullRequest>,
 }
 
 impl DrawableComponent for BranchListPopup {
@@ -75,7 +96,16 @@ impl DrawableComponent for BranchListPopup {
 
 			f.render_widget(
 				Block::default()
-					.title(strings::title_branches())
+					.title(
+						if self.filtering || !self.filter.is_empty() {
+							strings::title_branches_filtered(
+								&self.filter,
+								self.filtering,
+							)
+						} else {
+							strings::title_branches()
+						},
+					)
 					.border_type(BorderType::Thick)
 					.borders(Borders::ALL),
 				area,
@@ -119,14 +149,20 @@ impl Component for BranchListPopup {
 	}
 
 	//TODO: cleanup
-	#[allow(clippy::cognitive_complexity)]
+	#[allow(clippy::cognitive_complexity, clippy::too_many_lines)]
 	fn event(&mut self, ev: &Event) -> Result<EventState> {
 		if !self.visible {
 			return Ok(EventState::NotConsumed);
 		}
 
+		if let Event::Mouse(m) = ev {
+			self.mouse_event(*m)?;
+		}
+
 		if let Event::Key(e) = ev {
-			if self.move_event(e)?.is_consumed() {
+			if self.filter_event(e)?
+				|| self.move_event(e)?.is_consumed()
+			{
 				return Ok(EventState::Consumed);
 			}
 
@@ -147,6 +183,11 @@ impl Component for BranchListPopup {
 				&& self.valid_selection()
 			{
 				self.rename_branch();
+			} else if key_match(e, self.key_config.keys.copy_branch)
+				&& self.local
+				&& self.valid_selection()
+			{
+				self.copy_branch();
 			} else if key_match(e, self.key_config.keys.delete_branch)
 				&& !selection_is_cur_branch
 				&& self.valid_selection()
@@ -207,6 +248,24 @@ impl Component for BranchListPopup {
 			) {
 				//do not consume if its the more key
 				return Ok(EventState::NotConsumed);
+			} else if key_match(e, self.key_config.keys.branch_sort) {
+				self.cycle_sort()?;
+			} else if key_match(
+				e,
+				self.key_config.keys.branch_hide_remotes,
+			) && !self.local
+			{
+				self.hide_remotes()?;
+			} else if key_match(e, self.key_config.keys.branch_flow)
+				&& self.local
+			{
+				self.queue.push(InternalEvent::OpenFlow);
+			} else if key_match(
+				e,
+				self.key_config.keys.branch_cleanup,
+			) && self.local
+			{
+				self.queue.push(InternalEvent::OpenBranchCleanup);
 			} else if key_match(e, self.key_config.keys.branch_find) {
 				let branches = self
 					.branches
@@ -241,7 +300,11 @@ impl Component for BranchListPopup {
 impl BranchListPopup {
 	pub fn new(env: &Environment) -> Self {
 		Self {
+			unfiltered: Vec::new(),
+			filter: String::new(),
+			filtering: false,
 			branches: Vec::new(),
+			compares: Vec::new(),
 			local: true,
 			has_remotes: false,
 			visible: false,
@@ -250,11 +313,22 @@ impl BranchListPopup {
 			queue: env.queue.clone(),
 			theme: env.theme.clone(),
 			key_config: env.key_config.clone(),
+			options: env.options.clone(),
 			current_height: Cell::new(0),
+			list_area: Cell::new(Rect::default()),
+			tabs_area: Cell::new(Rect::default()),
 			repo: env.repo.clone(),
+			#[cfg(feature = "forge-api")]
+			pull_request: None,
 		}
 	}
 
+	/// pull request of the current branch, shown next to it
+	#[cfg(feature = "forge-api")]
+	pub fn set_pull_request(&mut self, pr: Option<PullRequest>) {
+		self.pull_request = pr;
+	}
+
 	fn move_event(&mut self, e: &KeyEvent) -> Result<EventState> {
 		if key_match(e, self.key_config.keys.exit_popup) {
 			self.hide();
@@ -290,6 +364,40 @@ impl BranchListPopup {
 		Ok(EventState::NotConsumed)
 	}
 
+	fn mouse_event(&mut self, ev: MouseEvent) -> Result<()> {
+		// note: the selection counts up when moving down the list
+		match list_mouse(ev, self.list_area.get()) {
+			Some(ListMouse::Scroll(ScrollType::Up)) => {
+				self.move_selection(ScrollType::Down)?;
+			}
+			Some(ListMouse::Scroll(_)) => {
+				self.move_selection(ScrollType::Up)?;
+			}
+			Some(ListMouse::Click(row)) => {
+				self.set_selection(
+					(self.scroll.get_top() + row).try_into()?,
+				)?;
+			}
+			None => {
+				let widths =
+					[Self::TAB_LOCAL.len(), Self::TAB_REMOTE.len()];
+				let divider =
+					strings::tab_divider(&self.key_config).len();
+				if let Some(tab) =
+					tab_at(ev, self.tabs_area.get(), &widths, divider)
+				{
+					if self.local != (tab == 0) {
+						self.local = tab == 0;
+						self.check_remotes();
+						self.update_branches()?;
+					}
+				}
+			}
+		}
+
+		Ok(())
+	}
+
 	///
 	pub fn open(&mut self) -> Result<()> {
 		self.show()?;
@@ -315,20 +423,163 @@ impl BranchListPopup {
 	pub fn update_branches(&mut self) -> Result<()> {
 		if self.is_visible() {
 			self.check_remotes();
-			self.branches =
+			let mut branches =
 				get_branches_info(&self.repo.borrow(), self.local)?;
 			//remove remote branch called `HEAD`
 			if !self.local {
-				self.branches
+				branches
 					.iter()
 					.position(|b| b.name.ends_with("/HEAD"))
-					.map(|idx| self.branches.remove(idx));
+					.map(|idx| branches.remove(idx));
 			}
-			self.set_selection(self.selection)?;
+			self.unfiltered = self.compare_and_sort(branches)?;
+			self.apply_filter()?;
 		}
 		Ok(())
 	}
 
+	fn cycle_sort(&mut self) -> Result<()> {
+		let mut options = self.options.borrow().branch_list();
+		options.sort = options.sort.next();
+		self.options.borrow_mut().set_branch_list(options);
+
+		self.update_branches()
+	}
+
+	/// compares `branches` with their upstream if needed and
+	/// orders them
+	fn compare_and_sort(
+		&self,
+		branches: Vec<BranchInfo>,
+	) -> Result<Vec<(BranchInfo, Option<BranchCompare>)>> {
+		let options = self.options.borrow().branch_list();
+
+		let compares = if self.local
+			&& (options.ahead_behind
+				|| options.sort == BranchSort::Ahead)
+		{
+			let names: Vec<String> =
+				branches.iter().map(|b| b.name.clone()).collect();
+			branches_compare_upstream(&self.repo.borrow(), &names)?
+		} else {
+			vec![None; branches.len()]
+		};
+
+		let mut entries: Vec<_> =
+			branches.into_iter().zip(compares).collect();
+
+		match options.sort {
+			BranchSort::Recency => entries.sort_by(|a, b| {
+				b.0.top_commit_time.cmp(&a.0.top_commit_time)
+			}),
+			BranchSort::Ahead => entries.sort_by(|a, b| {
+				let ahead =
+					|c: &Option<BranchCompare>| c.map(|c| c.ahead);
+				ahead(&b.1).cmp(&ahead(&a.1))
+			}),
+			BranchSort::Name => (),
+		}
+
+		Ok(entries)
+	}
+
+	/// `true` if `name` passes the filter typed by the user: a glob
+	/// if it contains `*` or `?`, a case insensitive substring
+	/// otherwise
+	fn filter_matches(
+		filter: &str,
+		name: &str,
+		remote: bool,
+	) -> bool {
+		if filter.contains(['*', '?']) {
+			Self::glob_matches(filter, name, remote)
+		} else {
+			name.to_lowercase().contains(&filter.to_lowercase())
+		}
+	}
+
+	/// matches remote branches (`remote/branch`) with or without
+	/// their remote
+	fn glob_matches(pattern: &str, name: &str, remote: bool) -> bool {
+		glob_match(pattern, name)
+			|| (remote
+				&& name.split_once('/').is_some_and(|(_, branch)| {
+					glob_match(pattern, branch)
+				}))
+	}
+
+	/// narrows `unfiltered` down to the displayed branches
+	fn apply_filter(&mut self) -> Result<()> {
+		let hide_remote = if self.local {
+			None
+		} else {
+			self.options.borrow().branch_list().hide_remote
+		};
+
+		(self.branches, self.compares) = self
+			.unfiltered
+			.iter()
+			.filter(|(branch, _)| {
+				hide_remote.as_ref().is_none_or(|pattern| {
+					!Self::glob_matches(pattern, &branch.name, true)
+				}) && Self::filter_matches(
+					&self.filter,
+					&branch.name,
+					!self.local,
+				)
+			})
+			.cloned()
+			.unzip();
+
+		self.set_selection(self.selection)
+	}
+
+	/// hides remote branches matching the current filter, or shows
+	/// all of them again if the filter is empty
+	fn hide_remotes(&mut self) -> Result<()> {
+		let mut options = self.options.borrow().branch_list();
+		let filter = std::mem::take(&mut self.filter);
+		options.hide_remote = if filter.is_empty() {
+			None
+		} else if filter.contains(['*', '?']) {
+			Some(filter)
+		} else {
+			Some(format!("*{filter}*"))
+		};
+		self.options.borrow_mut().set_branch_list(options);
+
+		self.apply_filter()
+	}
+
+	/// handles keys while the filter is typed, `true` if consumed
+	fn filter_event(&mut self, e: &KeyEvent) -> Result<bool> {
+		if !self.filtering {
+			if key_match(e, self.key_config.keys.branch_filter) {
+				self.filtering = true;
+				return Ok(true);
+			}
+			return Ok(false);
+		}
+
+		if key_match(e, self.key_config.keys.exit_popup) {
+			self.filtering = false;
+			self.filter.clear();
+		} else if key_match(e, self.key_config.keys.enter) {
+			self.filtering = false;
+		} else if e.code == KeyCode::Backspace {
+			self.filter.pop();
+		} else if let KeyCode::Char(c) = e.code {
+			if e.modifiers.difference(KeyModifiers::SHIFT).is_empty()
+			{
+				self.filter.push(c);
+			}
+		}
+
+		self.apply_filter()?;
+
+		Ok(true)
+	}
+
 	///
 	pub fn update_git(
 		&mut self,
@@ -468,6 +719,7 @@ impl BranchListPopup {
 	}
 
 	/// Get branches to display
+	#[allow(clippy::too_many_lines)]
 	fn get_text(
 		&self,
 		theme: &SharedTheme,
@@ -483,46 +735,51 @@ impl BranchListPopup {
 		const COMMIT_HASH_LENGTH: usize = 8;
 		const IS_HEAD_STAR_LENGTH: usize = 3; // "*  "
 
-		let branch_name_length: usize =
-			width_available as usize * 40 / 100;
+		let columns = self.options.borrow().branch_list();
+		// optional columns take their width from name and message
+		let width_available = (width_available as usize)
+			.saturating_sub(Self::columns_width(&columns));
+		let branch_name_length: usize = width_available * 40 / 100;
 		// commit message takes up the remaining width
-		let commit_message_length: usize = (width_available as usize)
+		let commit_message_length: usize = width_available
 			.saturating_sub(COMMIT_HASH_LENGTH)
 			.saturating_sub(branch_name_length)
 			.saturating_sub(IS_HEAD_STAR_LENGTH)
 			.saturating_sub(THREE_DOTS_LENGTH);
 		let mut txt = Vec::new();
 
-		for (i, displaybranch) in self
+		for (i, (displaybranch, compare)) in self
 			.branches
 			.iter()
+			.zip(&self.compares)
 			.skip(self.scroll.get_top())
 			.take(height)
 			.enumerate()
 		{
-			let mut commit_message =
-				displaybranch.top_commit_message.clone();
-			if commit_message.len() > commit_message_length {
-				commit_message.unicode_truncate(
-					commit_message_length
-						.saturating_sub(THREE_DOTS_LENGTH),
-				);
-				commit_message += THREE_DOTS;
+			#[cfg_attr(not(feature = "forge-api"), allow(unused_mut))]
+			let mut commit_message = displaybranch.top_commit_message.clone();
+
+			#[cfg(feature = "forge-api")]
+			if let Some(pr) =
+				self.pull_request.as_ref().filter(|_| {
+					displaybranch
+						.local_details()
+						.is_some_and(|details| details.is_head)
+				}) {
+				commit_message =
+					format!("[{}] {commit_message}", pr.summary());
 			}
 
-			let mut branch_name = displaybranch.name.clone();
-			if branch_name.len()
-				> branch_name_length.saturating_sub(THREE_DOTS_LENGTH)
-			{
-				branch_name = branch_name
-					.unicode_truncate(
-						branch_name_length
-							.saturating_sub(THREE_DOTS_LENGTH),
-					)
-					.0
-					.to_string();
-				branch_name += THREE_DOTS;
-			}
+			let commit_message = truncate_with(
+				&commit_message,
+				commit_message_length,
+				THREE_DOTS,
+			);
+			let branch_name = truncate_with(
+				&displaybranch.name,
+				branch_name_length,
+				THREE_DOTS,
+			);
 
 			let selected = (self.selection as usize
 				- self.scroll.get_top())
@@ -561,21 +818,128 @@ impl BranchListPopup {
 				theme.text(true, selected),
 			);
 			let span_name = Span::styled(
-				format!("{branch_name:branch_name_length$} "),
+				format!(
+					"{} ",
+					pad_width(&branch_name, branch_name_length)
+				),
 				theme.branch(selected, is_head),
 			);
 
-			txt.push(Line::from(vec![
-				span_prefix,
-				span_name,
-				span_hash,
-				span_msg,
-			]));
+			let mut spans = vec![span_prefix, span_name, span_hash];
+			spans.extend(self.column_spans(
+				&columns,
+				displaybranch,
+				*compare,
+				selected,
+			));
+			spans.push(span_msg);
+
+			txt.push(Line::from(spans));
 		}
 
 		Text::from(txt)
 	}
 
+	const AHEAD_BEHIND_WIDTH: usize = 10;
+	const DATE_WIDTH: usize = 10;
+	const AUTHOR_WIDTH: usize = 15;
+	const UPSTREAM_WIDTH: usize = 20;
+
+	/// width taken by the optional columns, including separators
+	const fn columns_width(columns: &BranchListOptions) -> usize {
+		let mut width = 0;
+		if columns.ahead_behind {
+			width += Self::AHEAD_BEHIND_WIDTH + 1;
+		}
+		if columns.date {
+			width += Self::DATE_WIDTH + 1;
+		}
+		if columns.author {
+			width += Self::AUTHOR_WIDTH + 1;
+		}
+		if columns.upstream {
+			width += Self::UPSTREAM_WIDTH + 1;
+		}
+		width
+	}
+
+	fn column_spans(
+		&self,
+		columns: &BranchListOptions,
+		branch: &BranchInfo,
+		compare: Option<BranchCompare>,
+		selected: bool,
+	) -> Vec<Span<'_>> {
+		let mut spans = Vec::new();
+
+		if columns.ahead_behind {
+			let text = compare
+				.map(|c| {
+					format!(
+						"\u{2191}{} \u{2193}{}",
+						c.ahead, c.behind
+					)
+				})
+				.unwrap_or_default();
+			spans.push(Span::styled(
+				format!(
+					"{} ",
+					string_width_align(
+						&text,
+						Self::AHEAD_BEHIND_WIDTH
+					)
+				),
+				self.theme.text(true, selected),
+			));
+		}
+		if columns.date {
+			spans.push(Span::styled(
+				format!(
+					"{} ",
+					time_to_string(branch.top_commit_time, true)
+				),
+				self.theme.commit_time(selected),
+			));
+		}
+		if columns.author {
+			spans.push(Span::styled(
+				format!(
+					"{} ",
+					string_width_align(
+						&branch.top_commit_author,
+						Self::AUTHOR_WIDTH
+					)
+				),
+				self.theme.commit_author(selected),
+			));
+		}
+		if columns.upstream {
+			let upstream = branch
+				.local_details()
+				.and_then(|details| details.upstream.as_ref())
+				.map(|upstream| {
+					upstream
+						.reference
+						.trim_start_matches("refs/remotes/")
+						.trim_start_matches("refs/heads/")
+						.to_string()
+				})
+				.unwrap_or_default();
+			spans.push(Span::styled(
+				format!(
+					"{} ",
+					string_width_align(
+						&upstream,
+						Self::UPSTREAM_WIDTH
+					)
+				),
+				self.theme.branch(selected, false),
+			));
+		}
+
+		spans
+	}
+
 	///
 	fn switch_to_selected_branch(&mut self) -> Result<()> {
 		if !self.valid_selection() {
@@ -592,20 +956,39 @@ impl BranchListPopup {
 		let selected_branch = &self.branches[self.selection as usize];
 		if status.is_empty() {
 			if self.local {
-				checkout_branch(
+				checkout_with_hook(
+					&self.queue,
 					&self.repo.borrow(),
-					&selected_branch.name,
+					true,
+					|| {
+						checkout_branch(
+							&self.repo.borrow(),
+							&selected_branch.name,
+						)
+					},
 				)?;
 				self.hide();
 			} else {
-				checkout_remote_branch(
+				checkout_with_hook(
+					&self.queue,
 					&self.repo.borrow(),
-					selected_branch,
+					true,
+					|| {
+						checkout_remote_branch(
+							&self.repo.borrow(),
+							selected_branch,
+						)
+					},
 				)?;
 				self.local = true;
 				self.update_branches()?;
 			}
 			self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+		} else if self.options.borrow().checkout_autostash() {
+			self.queue.push(InternalEvent::CheckoutAutostash(
+				selected_branch.clone(),
+			));
+			self.hide();
 		} else {
 			self.queue.push(InternalEvent::CheckoutOption(
 				selected_branch.clone(),
@@ -615,9 +998,14 @@ impl BranchListPopup {
 		Ok(())
 	}
 
+	const TAB_LOCAL: &'static str = "Local";
+	const TAB_REMOTE: &'static str = "Remote";
+
 	fn draw_tabs(&self, f: &mut Frame, r: Rect) {
+		self.tabs_area.set(Rect { height: 1, ..r });
+
 		let tabs: Vec<Line> =
-			[Span::raw("Local"), Span::raw("Remote")]
+			[Span::raw(Self::TAB_LOCAL), Span::raw(Self::TAB_REMOTE)]
 				.iter()
 				.cloned()
 				.map(Line::from)
@@ -641,6 +1029,7 @@ impl BranchListPopup {
 	fn draw_list(&self, f: &mut Frame, r: Rect) -> Result<()> {
 		let height_in_lines = r.height as usize;
 		self.current_height.set(height_in_lines.try_into()?);
+		self.list_area.set(r);
 
 		self.scroll.update(
 			self.selection as usize,
@@ -676,6 +1065,14 @@ impl BranchListPopup {
 		));
 	}
 
+	fn copy_branch(&self) {
+		let cur_branch = &self.branches[self.selection as usize];
+		self.queue.push(InternalEvent::CopyBranch(
+			cur_branch.reference.clone(),
+			cur_branch.name.clone(),
+		));
+	}
+
 	fn delete_branch(&self) {
 		let reference =
 			self.branches[self.selection as usize].reference.clone();
@@ -689,6 +1086,7 @@ impl BranchListPopup {
 		));
 	}
 
+	#[allow(clippy::too_many_lines)]
 	fn add_commands_internal(&self, out: &mut Vec<CommandInfo>) {
 		let selection_is_cur_branch = self.selection_is_cur_branch();
 
@@ -763,18 +1161,66 @@ impl BranchListPopup {
 			self.local,
 		));
 
+		out.push(CommandInfo::new(
+			strings::commands::copy_branch_popup(&self.key_config),
+			self.valid_selection(),
+			self.local,
+		));
+
 		out.push(CommandInfo::new(
 			strings::commands::fetch_remotes(&self.key_config),
 			self.has_remotes,
 			true,
 		));
 
+		out.push(CommandInfo::new(
+			strings::commands::sort_branches(
+				&self.key_config,
+				self.options.borrow().branch_list().sort,
+			),
+			true,
+			true,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::filter_branches(&self.key_config),
+			true,
+			true,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::hide_remote_branches(
+				&self.key_config,
+				!self.filter.is_empty(),
+			),
+			!self.filter.is_empty()
+				|| self
+					.options
+					.borrow()
+					.branch_list()
+					.hide_remote
+					.is_some(),
+			!self.local,
+		));
+
 		out.push(CommandInfo::new(
 			strings::commands::find_branch(&self.key_config),
 			true,
 			true,
 		));
 
+		out.push(CommandInfo::new(
+			strings::commands::branch_flow(&self.key_config),
+			true,
+			self.local,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::branch_cleanup(&self.key_config),
+			true,
+			self.local,
+		));
+
 		out.push(CommandInfo::new(
 			strings::commands::reset_branch(&self.key_config),
 			self.valid_selection(),
@@ -0,0 +1,330 @@
+use crate::{
+	app::Environment,
+	components::{
+		checkout_with_hook, visibility_blocking, CommandBlocking,
+		CommandInfo, Component, DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	string_utils::pad_width,
+	strings, try_or_popup,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::sync::{
+	checkout_branch_resolved, BranchInfo, ConflictResolution,
+	RepoPathRef,
+};
+use crossterm::event::{Event, MouseButton, MouseEventKind};
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// lists the files whose local changes a checkout would overwrite
+/// and gets them out of the way as chosen per file before checking
+/// out
+pub struct CheckoutConflictsPopup {
+	repo: RepoPathRef,
+	branch: Option<BranchInfo>,
+	files: Vec<(String, ConflictResolution)>,
+	selection: usize,
+	visible: bool,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl CheckoutConflictsPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			repo: env.repo.clone(),
+			branch: None,
+			files: Vec::new(),
+			selection: 0,
+			visible: false,
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	/// every file is stashed unless chosen otherwise
+	pub fn open(
+		&mut self,
+		branch: BranchInfo,
+		files: Vec<String>,
+	) -> Result<()> {
+		self.branch = Some(branch);
+		self.files = files
+			.into_iter()
+			.map(|path| (path, ConflictResolution::Stash))
+			.collect();
+		self.selection = 0;
+
+		self.show()
+	}
+
+	fn checkout(&mut self) -> Result<()> {
+		let Some(branch) = self.branch.take() else {
+			return Ok(());
+		};
+		self.hide();
+
+		let repo = self.repo.borrow();
+		let stash =
+			checkout_with_hook(&self.queue, &repo, true, || {
+				checkout_branch_resolved(&repo, &branch, &self.files)
+			})?;
+
+		if stash.is_some() {
+			self.queue.push(InternalEvent::ShowInfoMsg(
+				strings::checkout_conflicts_stashed(&branch.name),
+			));
+		}
+
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+		self.queue.push(InternalEvent::SelectBranch);
+
+		Ok(())
+	}
+
+	fn toggle_resolution(&mut self) {
+		if let Some((_, resolution)) =
+			self.files.get_mut(self.selection)
+		{
+			*resolution = match resolution {
+				ConflictResolution::Stash => {
+					ConflictResolution::Discard
+				}
+				ConflictResolution::Discard => {
+					ConflictResolution::Stash
+				}
+			};
+		}
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let count = self.files.len();
+		if count == 0 {
+			return;
+		}
+
+		self.selection = if up {
+			self.selection.checked_sub(1).unwrap_or(count - 1)
+		} else {
+			(self.selection + 1) % count
+		};
+	}
+
+	const fn resolution_text(
+		resolution: ConflictResolution,
+	) -> &'static str {
+		match resolution {
+			ConflictResolution::Stash => "stash",
+			ConflictResolution::Discard => "discard",
+		}
+	}
+
+	fn get_text(&self) -> Vec<Line<'_>> {
+		let mut txt = vec![Line::from(Span::styled(
+			strings::CHECKOUT_CONFLICTS_MSG,
+			self.theme.text(false, false),
+		))];
+
+		txt.extend(self.files.iter().enumerate().map(
+			|(idx, (path, resolution))| {
+				let selected = idx == self.selection;
+
+				Line::from(vec![
+					Span::styled(
+						format!(
+							"{} ",
+							pad_width(
+								Self::resolution_text(*resolution),
+								7
+							)
+						),
+						self.theme.text(true, selected),
+					),
+					Span::styled(
+						path.as_str(),
+						self.theme.text(true, selected),
+					),
+				])
+			},
+		));
+
+		txt
+	}
+}
+
+impl DrawableComponent for CheckoutConflictsPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			const MIN_WIDTH: u16 = 50;
+
+			let text = self.get_text();
+
+			let width = text
+				.iter()
+				.map(Line::width)
+				.max()
+				.and_then(|width| u16::try_from(width + 2).ok())
+				.unwrap_or_default()
+				.clamp(MIN_WIDTH, area.width);
+			let height = u16::try_from(text.len())
+				.unwrap_or_default()
+				.saturating_add(2)
+				.min(area.height);
+			let area =
+				ui::centered_rect_absolute(width, height, area);
+
+			// keep the selection (below the message) in view
+			let scroll = u16::try_from(self.selection + 1)
+				.unwrap_or_default()
+				.saturating_sub(height.saturating_sub(3));
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(text).scroll((scroll, 0)).block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							strings::checkout_conflicts_title(
+								self.branch
+									.as_ref()
+									.map(|branch| {
+										branch.name.as_str()
+									})
+									.unwrap_or_default(),
+							),
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for CheckoutConflictsPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::checkout_conflicts_abort(
+						&self.key_config,
+					),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::checkout_conflicts_toggle(
+						&self.key_config,
+					),
+					!self.files.is_empty(),
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::checkout_conflicts_confirm(
+						&self.key_config,
+					),
+					true,
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			match event {
+				Event::Key(key) => {
+					if key_match(key, self.key_config.keys.exit_popup)
+					{
+						self.hide();
+					} else if key_match(
+						key,
+						self.key_config.keys.move_up,
+					) {
+						self.move_selection(true);
+					} else if key_match(
+						key,
+						self.key_config.keys.move_down,
+					) {
+						self.move_selection(false);
+					} else if key_match(
+						key,
+						self.key_config.keys.log_mark_commit,
+					) {
+						self.toggle_resolution();
+					} else if key_match(
+						key,
+						self.key_config.keys.enter,
+					) {
+						try_or_popup!(
+							self,
+							"checkout error:",
+							self.checkout()
+						);
+					}
+				}
+				Event::Mouse(mouse)
+					if mouse.kind
+						== MouseEventKind::Down(
+							MouseButton::Right,
+						) =>
+				{
+					self.hide();
+				}
+				_ => (),
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
@@ -1,6 +1,6 @@
 use crate::components::{
-	visibility_blocking, CommandBlocking, CommandInfo, Component,
-	DrawableComponent, EventState,
+	checkout_with_hook, visibility_blocking, CommandBlocking,
+	CommandInfo, Component, DrawableComponent, EventState,
 };
 use crate::queue::{InternalEvent, NeedsUpdate};
 use crate::strings::CheckoutOptions;
@@ -15,7 +15,9 @@ use crate::{
 use anyhow::{Ok, Result};
 use asyncgit::sync::branch::checkout_remote_branch;
 use asyncgit::sync::status::discard_status;
-use asyncgit::sync::{checkout_branch, BranchInfo, RepoPath};
+use asyncgit::sync::{
+	checkout_branch, checkout_conflicts, BranchInfo, RepoPath,
+};
 use crossterm::event::Event;
 use ratatui::{
 	layout::{Alignment, Rect},
@@ -87,11 +89,18 @@ impl CheckoutOptionPopup {
 
 	fn checkout(&self) -> Result<()> {
 		if let Some(branch) = &self.branch {
-			if branch.is_local() {
-				checkout_branch(&self.repo, &branch.name)?;
-			} else {
-				checkout_remote_branch(&self.repo, branch)?;
-			}
+			checkout_with_hook(
+				&self.queue,
+				&self.repo,
+				true,
+				|| {
+					if branch.is_local() {
+						checkout_branch(&self.repo, &branch.name)
+					} else {
+						checkout_remote_branch(&self.repo, branch)
+					}
+				},
+			)?;
 		}
 
 		Ok(())
@@ -100,8 +109,34 @@ impl CheckoutOptionPopup {
 	fn handle_event(&mut self) -> Result<()> {
 		match self.option {
 			CheckoutOptions::KeepLocalChanges => {
+				// let the user choose what happens to local changes
+				// that are in the way
+				if let Some(branch) = self.branch.clone() {
+					let conflicts =
+						checkout_conflicts(&self.repo, &branch)?;
+					if !conflicts.is_empty() {
+						self.queue.push(
+							InternalEvent::OpenCheckoutConflicts(
+								branch, conflicts,
+							),
+						);
+						self.hide();
+						return Ok(());
+					}
+				}
+
 				self.checkout()?;
 			}
+			// switches in the background and updates once done
+			CheckoutOptions::StashLocalChanges => {
+				if let Some(branch) = self.branch.clone() {
+					self.queue.push(
+						InternalEvent::CheckoutAutostash(branch),
+					);
+				}
+				self.hide();
+				return Ok(());
+			}
 			CheckoutOptions::DiscardAllLocalChagnes => {
 				discard_status(&self.repo)?;
 				self.checkout()?;
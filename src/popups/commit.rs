@@ -4,9 +4,13 @@ use crate::components::{
 };
 use crate::{
 	app::Environment,
+	automation::Trigger,
+	commit_lint::CommitLint,
+	commit_templates::{self, CommitTemplate},
 	keys::{key_match, SharedKeyConfig},
 	options::SharedOptions,
 	queue::{InternalEvent, NeedsUpdate, Queue},
+	string_utils::truncate_width,
 	strings, try_or_popup,
 	ui::style::SharedTheme,
 };
@@ -15,10 +19,10 @@ use asyncgit::sync::commit::commit_message_prettify;
 use asyncgit::{
 	cached,
 	sync::{
-		self, get_config_string, CommitId, HookResult,
-		PrepareCommitMsgSource, RepoPathRef, RepoState,
+		self, get_config_string, BranchProtection, CommitId,
+		HookResult, PrepareCommitMsgSource, RepoPathRef, RepoState,
 	},
-	StatusItem, StatusItemType,
+	PostHook, StatusItem, StatusItemType,
 };
 use crossterm::event::Event;
 use easy_cast::Cast;
@@ -30,10 +34,8 @@ use ratatui::{
 
 use std::{
 	fmt::Write as _,
-	fs::{read_to_string, File},
+	fs::File,
 	io::{Read, Write},
-	path::PathBuf,
-	str::FromStr,
 };
 
 use super::ExternalEditorPopup;
@@ -48,6 +50,7 @@ enum Mode {
 	Amend(CommitId),
 	Merge(Vec<CommitId>),
 	Revert,
+	CherryPick,
 	Reword(CommitId),
 }
 
@@ -58,11 +61,16 @@ pub struct CommitPopup {
 	queue: Queue,
 	key_config: SharedKeyConfig,
 	git_branch_name: cached::BranchName,
-	commit_template: Option<String>,
+	templates: Vec<CommitTemplate>,
+	template_idx: usize,
+	/// of the branch committed to
+	protection: Option<BranchProtection>,
 	theme: SharedTheme,
 	commit_msg_history_idx: usize,
 	options: SharedOptions,
 	verify: bool,
+	lint: CommitLint,
+	keep_staged: Vec<String>,
 }
 
 const FIRST_LINE_LIMIT: usize = 50;
@@ -83,12 +91,16 @@ impl CommitPopup {
 			git_branch_name: cached::BranchName::new(
 				env.repo.clone(),
 			),
-			commit_template: None,
+			templates: Vec::new(),
+			template_idx: 0,
+			protection: None,
 			theme: env.theme.clone(),
 			repo: env.repo.clone(),
 			commit_msg_history_idx: 0,
 			options: env.options.clone(),
 			verify: true,
+			lint: CommitLint::new(&env.sender_app),
+			keep_staged: Vec::new(),
 		}
 	}
 
@@ -97,9 +109,39 @@ impl CommitPopup {
 		self.git_branch_name.lookup().ok();
 	}
 
+	/// takes the result of a finished lint command
+	pub fn update_lint(&mut self) {
+		self.lint.update();
+	}
+
+	fn lint(&mut self) {
+		// merge, revert and cherry-pick messages are written by git
+		if matches!(
+			self.mode,
+			Mode::Merge(_) | Mode::Revert | Mode::CherryPick
+		) {
+			return;
+		}
+
+		self.lint.check(self.input.get_text());
+	}
+
 	fn draw_branch_name(&self, f: &mut Frame) {
 		if let Some(name) = self.git_branch_name.last() {
-			let w = Paragraph::new(format!("{{{name}}}"))
+			let w = self
+				.protection
+				.map_or_else(
+					|| Paragraph::new(format!("{{{name}}}")),
+					|protection| {
+						Paragraph::new(
+							strings::commit_protected_branch(
+								&name,
+								protection == BranchProtection::Block,
+							),
+						)
+						.style(self.theme.text_danger())
+					},
+				)
 				.alignment(Alignment::Right);
 
 			let rect = {
@@ -114,6 +156,8 @@ impl CommitPopup {
 	}
 
 	fn draw_warnings(&self, f: &mut Frame) {
+		let mut length_warning = 0;
+
 		let first_line = self
 			.input
 			.get_text()
@@ -125,6 +169,7 @@ impl CommitPopup {
 		if first_line > FIRST_LINE_LIMIT {
 			let msg = strings::commit_first_line_warning(first_line);
 			let msg_length: u16 = msg.len().cast();
+			length_warning = msg_length + 1;
 			let w =
 				Paragraph::new(msg).style(self.theme.text_danger());
 
@@ -142,6 +187,25 @@ impl CommitPopup {
 
 			f.render_widget(w, rect);
 		}
+
+		if let Some(warning) = self.lint.warning() {
+			let rect = {
+				let mut rect = self.input.get_area();
+				rect.y += rect.height.saturating_sub(1);
+				rect.height = 1;
+				rect.x += 1;
+				rect.width =
+					rect.width.saturating_sub(length_warning + 3);
+				rect
+			};
+			let msg = strings::commit_lint_warning(warning);
+			let msg = truncate_width(&msg, rect.width.into());
+
+			f.render_widget(
+				Paragraph::new(msg).style(self.theme.text_danger()),
+				rect,
+			);
+		}
 	}
 
 	const fn item_status_char(
@@ -152,8 +216,10 @@ impl CommitPopup {
 			StatusItemType::New => "new file",
 			StatusItemType::Deleted => "deleted",
 			StatusItemType::Renamed => "renamed",
-			StatusItemType::Typechange => " ",
+			StatusItemType::Typechange => "typechange",
+			StatusItemType::ModeChange => "mode change",
 			StatusItemType::Conflicted => "conflicted",
+			StatusItemType::Ignored => "ignored",
 		}
 	}
 
@@ -189,6 +255,7 @@ impl CommitPopup {
 		ExternalEditorPopup::open_file_in_editor(
 			&self.repo.borrow(),
 			&file_path,
+			None,
 		)?;
 
 		let mut message = String::new();
@@ -221,6 +288,9 @@ impl CommitPopup {
 			self.hide();
 			self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
 			self.queue.push(InternalEvent::StatusLastFileMoved);
+			self.queue.push(InternalEvent::RunAutomation(
+				Trigger::PostCommit,
+			));
 			self.input.clear();
 		}
 
@@ -235,16 +305,27 @@ impl CommitPopup {
 		let verify = self.verify;
 		self.verify = true;
 
+		if verify && self.check_staged()? {
+			return Ok(CommitResult::Aborted);
+		}
+
 		if verify {
 			// run pre commit hook - can reject commit
-			if let HookResult::NotOk(e) =
-				sync::hooks_pre_commit(&self.repo.borrow())?
-			{
-				log::error!("pre-commit hook error: {e}");
-				self.queue.push(InternalEvent::ShowErrorMsg(
-					format!("pre-commit hook error:\n{e}"),
-				));
-				return Ok(CommitResult::Aborted);
+			match sync::hooks_pre_commit(&self.repo.borrow())? {
+				HookResult::NotOk(e) => {
+					log::error!("pre-commit hook error: {e}");
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("pre-commit hook error:\n{e}"),
+					));
+					return Ok(CommitResult::Aborted);
+				}
+				HookResult::Warning(e) => {
+					log::warn!("pre-commit hook warning: {e}");
+					self.queue.push(InternalEvent::ShowInfoMsg(
+						format!("pre-commit hook warning:\n{e}"),
+					));
+				}
+				HookResult::Ok => (),
 			}
 		}
 
@@ -253,19 +334,29 @@ impl CommitPopup {
 
 		if verify {
 			// run commit message check hook - can reject commit
-			if let HookResult::NotOk(e) =
-				sync::hooks_commit_msg(&self.repo.borrow(), &mut msg)?
-			{
-				log::error!("commit-msg hook error: {e}");
-				self.queue.push(InternalEvent::ShowErrorMsg(
-					format!("commit-msg hook error:\n{e}"),
-				));
-				return Ok(CommitResult::Aborted);
+			match sync::hooks_commit_msg(
+				&self.repo.borrow(),
+				&mut msg,
+			)? {
+				HookResult::NotOk(e) => {
+					log::error!("commit-msg hook error: {e}");
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("commit-msg hook error:\n{e}"),
+					));
+					return Ok(CommitResult::Aborted);
+				}
+				HookResult::Warning(e) => {
+					log::warn!("commit-msg hook warning: {e}");
+					self.queue.push(InternalEvent::ShowInfoMsg(
+						format!("commit-msg hook warning:\n{e}"),
+					));
+				}
+				HookResult::Ok => (),
 			}
 		}
 		self.do_commit(&msg)?;
 
-		if let HookResult::NotOk(e) =
+		if let HookResult::NotOk(e) | HookResult::Warning(e) =
 			sync::hooks_post_commit(&self.repo.borrow())?
 		{
 			log::error!("post-commit hook error: {e}");
@@ -277,16 +368,49 @@ impl CommitPopup {
 		Ok(CommitResult::CommitDone)
 	}
 
+	/// `true` if the built-in checks found problems in the staged
+	/// lines
+	fn check_staged(&self) -> Result<bool> {
+		// the staged lines are not what a reword commits
+		if matches!(self.mode, Mode::Reword(_))
+			|| !sync::commit_checks_enabled(&self.repo.borrow())?
+		{
+			return Ok(false);
+		}
+
+		let violations = sync::check_staged(&self.repo.borrow())?;
+		if violations.is_empty() {
+			return Ok(false);
+		}
+
+		self.queue.push(InternalEvent::OpenCommitChecks(violations));
+
+		Ok(true)
+	}
+
 	fn do_commit(&self, msg: &str) -> Result<()> {
 		match &self.mode {
 			Mode::Normal => sync::commit(&self.repo.borrow(), msg)?,
-			Mode::Amend(amend) => {
-				sync::amend(&self.repo.borrow(), *amend, msg)?
-			}
+			Mode::Amend(amend) => sync::amend_with_files(
+				&self.repo.borrow(),
+				*amend,
+				msg,
+				&self.keep_staged,
+			)?,
 			Mode::Merge(ids) => {
-				sync::merge_commit(&self.repo.borrow(), msg, ids)?
+				let commit = sync::merge_commit(
+					&self.repo.borrow(),
+					msg,
+					ids,
+				)?;
+				self.queue.push(InternalEvent::RunPostHook(
+					PostHook::Merge { squash: false },
+				));
+
+				commit
 			}
-			Mode::Revert => {
+			// both end the pending state after committing
+			Mode::Revert | Mode::CherryPick => {
 				sync::commit_revert(&self.repo.borrow(), msg)?
 			}
 			Mode::Reword(id) => {
@@ -316,13 +440,48 @@ impl CommitPopup {
 
 	fn is_changed(&self) -> bool {
 		Some(self.input.get_text().trim())
-			!= self.commit_template.as_ref().map(|s| s.trim())
+			!= self
+				.templates
+				.get(self.template_idx)
+				.map(|template| template.text.trim())
+	}
+
+	/// only while the message is still the template
+	fn can_switch_template(&self) -> bool {
+		matches!(self.mode, Mode::Normal)
+			&& self.templates.len() > 1
+			&& (self.is_empty() || !self.is_changed())
+	}
+
+	fn next_template(&mut self) {
+		if !self.can_switch_template() {
+			return;
+		}
+
+		self.template_idx =
+			(self.template_idx + 1) % self.templates.len();
+		self.set_template();
+	}
+
+	fn set_template(&mut self) {
+		if let Some(template) = self.templates.get(self.template_idx)
+		{
+			self.input.set_text(template.text.clone());
+			// behind the subject the template starts with
+			self.input.move_cursor_to_line_end();
+			self.input.set_title(if self.templates.len() > 1 {
+				strings::commit_title_template(&template.name)
+			} else {
+				strings::commit_title()
+			});
+		}
 	}
 
 	fn amend(&mut self) -> Result<()> {
 		if self.can_amend() {
 			let id = sync::get_head(&self.repo.borrow())?;
 			self.mode = Mode::Amend(id);
+			self.keep_staged.clear();
 
 			let details =
 				sync::get_commit_details(&self.repo.borrow(), id)?;
@@ -336,6 +495,21 @@ impl CommitPopup {
 
 		Ok(())
 	}
+
+	/// sets the staged files to leave out of the amend
+	pub fn set_keep_staged(&mut self, keep_staged: Vec<String>) {
+		if !matches!(self.mode, Mode::Amend(_)) {
+			return;
+		}
+
+		self.input.set_title(if keep_staged.is_empty() {
+			strings::commit_title_amend()
+		} else {
+			strings::commit_title_amend_keep_staged(keep_staged.len())
+		});
+		self.keep_staged = keep_staged;
+	}
+
 	fn signoff_commit(&mut self) {
 		let msg = self.input.get_text();
 		let signed_msg = self.add_sign_off(msg);
@@ -354,6 +528,11 @@ impl CommitPopup {
 		}
 
 		self.mode = Mode::Normal;
+		self.keep_staged.clear();
+		self.protection = sync::head_protection(&self.repo.borrow())
+			.map_err(|e| log::error!("branch protection: {e}"))
+			.ok()
+			.flatten();
 
 		let repo_state = sync::repo_state(&self.repo.borrow())?;
 
@@ -393,45 +572,34 @@ impl CommitPopup {
 					)?);
 					(Mode::Revert, PrepareCommitMsgSource::Message)
 				}
+				RepoState::CherryPick => {
+					self.input
+						.set_title(strings::commit_title_cherrypick());
+					self.input.set_text(sync::merge_msg(
+						&self.repo.borrow(),
+					)?);
+					(
+						Mode::CherryPick,
+						PrepareCommitMsgSource::Message,
+					)
+				}
 
 				_ => {
-					self.commit_template = get_config_string(
+					self.templates = commit_templates::load(
 						&self.repo.borrow(),
-						"commit.template",
-					)
-					.map_err(|e| {
-						log::error!("load git-config failed: {e}");
-						e
-					})
-					.ok()
-					.flatten()
-					.and_then(|path| {
-						shellexpand::full(path.as_str())
-							.ok()
-							.and_then(|path| {
-								PathBuf::from_str(path.as_ref()).ok()
-							})
-					})
-					.and_then(|path| {
-						read_to_string(&path)
-							.map_err(|e| {
-								log::error!("read commit.template failed: {e} (path: '{path:?}')");
-								e
-							})
-							.ok()
-					});
-
-					let msg_source = if self.is_empty() {
-						if let Some(s) = &self.commit_template {
-							self.input.set_text(s.clone());
-							PrepareCommitMsgSource::Template
-						} else {
-							PrepareCommitMsgSource::Message
-						}
+						self.git_branch_name.lookup().ok().as_deref(),
+					);
+					self.template_idx = 0;
+					self.input.set_title(strings::commit_title());
+
+					let msg_source = if self.is_empty()
+						&& !self.templates.is_empty()
+					{
+						self.set_template();
+						PrepareCommitMsgSource::Template
 					} else {
 						PrepareCommitMsgSource::Message
 					};
-					self.input.set_title(strings::commit_title());
 
 					(Mode::Normal, msg_source)
 				}
@@ -441,17 +609,24 @@ impl CommitPopup {
 		self.mode = mode;
 
 		let mut msg = self.input.get_text().to_string();
-		if let HookResult::NotOk(e) = sync::hooks_prepare_commit_msg(
-			&self.repo.borrow(),
-			msg_source,
-			&mut msg,
-		)? {
-			log::error!("prepare-commit-msg hook rejection: {e}",);
+		if let HookResult::NotOk(e) | HookResult::Warning(e) =
+			sync::hooks_prepare_commit_msg(
+				&self.repo.borrow(),
+				msg_source,
+				&mut msg,
+			)? {
+			log::error!("prepare-commit-msg hook rejection: {e}");
 		}
 		self.input.set_text(msg);
 
 		self.commit_msg_history_idx = 0;
 		self.input.show()?;
+		if matches!(self.mode, Mode::Normal) && !self.is_changed() {
+			self.input.move_cursor_to_line_end();
+		}
+
+		self.lint.reset(&self.repo.borrow());
+		self.lint();
 
 		Ok(())
 	}
@@ -521,6 +696,14 @@ impl Component for CommitPopup {
 				true,
 			));
 
+			out.push(CommandInfo::new(
+				strings::commands::commit_amend_files(
+					&self.key_config,
+				),
+				true,
+				matches!(self.mode, Mode::Amend(_)),
+			));
+
 			out.push(CommandInfo::new(
 				strings::commands::commit_signoff(&self.key_config),
 				true,
@@ -543,6 +726,14 @@ impl Component for CommitPopup {
 				true,
 			));
 
+			out.push(CommandInfo::new(
+				strings::commands::commit_next_template(
+					&self.key_config,
+				),
+				self.can_switch_template(),
+				!self.templates.is_empty(),
+			));
+
 			out.push(CommandInfo::new(
 				strings::commands::newline(&self.key_config),
 				true,
@@ -580,6 +771,17 @@ impl Component for CommitPopup {
 					{
 						self.amend()?;
 						true
+					} else if key_match(
+						e,
+						self.key_config.keys.amend_files,
+					) && matches!(self.mode, Mode::Amend(_))
+					{
+						self.queue.push(
+							InternalEvent::OpenAmendFiles(
+								self.keep_staged.clone(),
+							),
+						);
+						true
 					} else if key_match(
 						e,
 						self.key_config.keys.open_commit_editor,
@@ -602,6 +804,13 @@ impl Component for CommitPopup {
 							self.commit_msg_history_idx += 1;
 						}
 						true
+					} else if key_match(
+						e,
+						self.key_config.keys.commit_template_next,
+					) && self.can_switch_template()
+					{
+						self.next_template();
+						true
 					} else if key_match(
 						e,
 						self.key_config.keys.toggle_signoff,
@@ -616,6 +825,8 @@ impl Component for CommitPopup {
 					self.input.event(ev)?;
 				}
 
+				self.lint();
+
 				// stop key event propagation
 				return Ok(EventState::Consumed);
 			}
@@ -0,0 +1,246 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::sync::CheckViolation;
+use crossterm::event::{Event, MouseButton, MouseEventKind};
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// lists what the built-in commit checks found in the staged lines
+/// and opens them in the editor
+pub struct CommitChecksPopup {
+	violations: Vec<CheckViolation>,
+	selection: usize,
+	visible: bool,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl CommitChecksPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			violations: Vec::new(),
+			selection: 0,
+			visible: false,
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	///
+	pub fn open(
+		&mut self,
+		violations: Vec<CheckViolation>,
+	) -> Result<()> {
+		self.violations = violations;
+		self.selection = 0;
+
+		self.show()
+	}
+
+	fn open_in_editor(&self) {
+		if let Some(violation) = self.violations.get(self.selection) {
+			self.queue.push(InternalEvent::OpenExternalEditorAt(
+				violation.path.clone(),
+				violation.line,
+			));
+		}
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let count = self.violations.len();
+		if count == 0 {
+			return;
+		}
+
+		self.selection = if up {
+			self.selection.checked_sub(1).unwrap_or(count - 1)
+		} else {
+			(self.selection + 1) % count
+		};
+	}
+
+	fn get_text(&self) -> Vec<Line<'_>> {
+		let mut txt = vec![Line::from(Span::styled(
+			strings::commit_checks_msg(&self.key_config),
+			self.theme.text(false, false),
+		))];
+
+		txt.extend(self.violations.iter().enumerate().map(
+			|(idx, violation)| {
+				let selected = idx == self.selection;
+
+				Line::from(vec![
+					Span::styled(
+						format!(
+							"{}:{} ",
+							violation.path, violation.line
+						),
+						self.theme.text(true, selected),
+					),
+					Span::styled(
+						violation.kind.to_string(),
+						self.theme.text_danger(),
+					),
+				])
+			},
+		));
+
+		txt
+	}
+}
+
+impl DrawableComponent for CommitChecksPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			const MIN_WIDTH: u16 = 50;
+
+			let text = self.get_text();
+
+			let width = text
+				.iter()
+				.map(Line::width)
+				.max()
+				.and_then(|width| u16::try_from(width + 2).ok())
+				.unwrap_or_default()
+				.clamp(MIN_WIDTH, area.width);
+			let height = u16::try_from(text.len())
+				.unwrap_or_default()
+				.saturating_add(2)
+				.min(area.height);
+			let area =
+				ui::centered_rect_absolute(width, height, area);
+
+			// keep the selection (below the message) in view
+			let scroll = u16::try_from(self.selection + 1)
+				.unwrap_or_default()
+				.saturating_sub(height.saturating_sub(3));
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(text).scroll((scroll, 0)).block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							strings::COMMIT_CHECKS_TITLE,
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for CommitChecksPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::commit_checks_open(
+						&self.key_config,
+					),
+					!self.violations.is_empty(),
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			match event {
+				Event::Key(key) => {
+					if key_match(key, self.key_config.keys.exit_popup)
+					{
+						self.hide();
+					} else if key_match(
+						key,
+						self.key_config.keys.move_up,
+					) {
+						self.move_selection(true);
+					} else if key_match(
+						key,
+						self.key_config.keys.move_down,
+					) {
+						self.move_selection(false);
+					} else if key_match(
+						key,
+						self.key_config.keys.enter,
+					) {
+						self.open_in_editor();
+					}
+				}
+				Event::Mouse(mouse)
+					if mouse.kind
+						== MouseEventKind::Down(
+							MouseButton::Right,
+						) =>
+				{
+					self.hide();
+				}
+				_ => (),
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
@@ -0,0 +1,413 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	popups::InspectCommitOpen,
+	queue::{InternalEvent, Queue, StackablePopupOpen},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::sync::{self, CommitId, CommitInfo, RepoPath};
+use crossterm::event::{Event, MouseButton, MouseEventKind};
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// where a picked commit is shown
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CommitJumpTarget {
+	/// select it in the log
+	Log,
+	/// open it in the commit details popup
+	Inspect,
+	/// select it in the log, or open its details if the log does
+	/// not list it
+	LogOrInspect,
+}
+
+impl CommitJumpTarget {
+	fn jump(self, queue: &Queue, from: CommitId, to: CommitId) {
+		match self {
+			Self::Log => {
+				queue.push(InternalEvent::SelectCommitInRevlog(to));
+			}
+			Self::LogOrInspect => {
+				queue.push(InternalEvent::SelectOrInspectCommit(to));
+			}
+			Self::Inspect => {
+				// so that closing the details goes back to `from`
+				queue.push(InternalEvent::PopupStackPush(
+					StackablePopupOpen::InspectCommit(
+						InspectCommitOpen::new(from),
+					),
+				));
+				queue.push(InternalEvent::OpenPopup(
+					StackablePopupOpen::InspectCommit(
+						InspectCommitOpen::new(to),
+					),
+				));
+			}
+		}
+	}
+}
+
+/// parents, children or special refs of a commit to choose from
+#[derive(Clone)]
+pub struct CommitPickerContext {
+	pub from: CommitId,
+	pub title: &'static str,
+	pub commits: Vec<CommitInfo>,
+	/// in front of each commit, empty for none
+	pub labels: Vec<&'static str>,
+	pub target: CommitJumpTarget,
+}
+
+/// jumps to the parent (or child) of `id`, letting the user pick one
+/// if there are several
+pub fn goto_relative(
+	repo: &RepoPath,
+	queue: &Queue,
+	id: CommitId,
+	parents: bool,
+	target: CommitJumpTarget,
+) -> Result<()> {
+	let ids = if parents {
+		sync::get_commit_parents(repo, id)?
+	} else {
+		sync::get_commit_children(repo, id)?
+	};
+
+	match ids.as_slice() {
+		[] => {
+			queue.push(InternalEvent::ShowInfoMsg(
+				if parents {
+					strings::NO_COMMIT_PARENTS
+				} else {
+					strings::NO_COMMIT_CHILDREN
+				}
+				.to_string(),
+			));
+		}
+		[to] => target.jump(queue, id, *to),
+		ids => {
+			let commits = ids
+				.iter()
+				.map(|to| sync::get_commit_info(repo, to))
+				.collect::<asyncgit::Result<_>>()?;
+
+			queue.push(InternalEvent::OpenCommitPicker(
+				CommitPickerContext {
+					from: id,
+					title: if parents {
+						strings::POPUP_TITLE_COMMIT_PARENTS
+					} else {
+						strings::POPUP_TITLE_COMMIT_CHILDREN
+					},
+					commits,
+					labels: Vec::new(),
+					target,
+				},
+			));
+		}
+	}
+
+	Ok(())
+}
+
+/// jumps to the commit of `ORIG_HEAD` and the like, letting the user
+/// pick one if there are several
+pub fn goto_special_ref(
+	repo: &RepoPath,
+	queue: &Queue,
+	from: CommitId,
+) -> Result<()> {
+	let refs = sync::get_special_refs(repo)?;
+
+	match refs.as_slice() {
+		[] => {
+			queue.push(InternalEvent::ShowInfoMsg(
+				strings::NO_SPECIAL_REFS.to_string(),
+			));
+		}
+		[(_, to)] => {
+			CommitJumpTarget::LogOrInspect.jump(queue, from, *to);
+		}
+		refs => {
+			let commits = refs
+				.iter()
+				.map(|(_, to)| sync::get_commit_info(repo, to))
+				.collect::<asyncgit::Result<_>>()?;
+
+			queue.push(InternalEvent::OpenCommitPicker(
+				CommitPickerContext {
+					from,
+					title: strings::POPUP_TITLE_SPECIAL_REFS,
+					commits,
+					labels: refs
+						.iter()
+						.map(|(special, _)| special.name())
+						.collect(),
+					target: CommitJumpTarget::LogOrInspect,
+				},
+			));
+		}
+	}
+
+	Ok(())
+}
+
+pub struct CommitPickerPopup {
+	context: Option<CommitPickerContext>,
+	selection: usize,
+	visible: bool,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl CommitPickerPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			context: None,
+			selection: 0,
+			visible: false,
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	///
+	pub fn open(
+		&mut self,
+		context: CommitPickerContext,
+	) -> Result<()> {
+		self.context = Some(context);
+		self.selection = 0;
+
+		self.show()
+	}
+
+	fn count(&self) -> usize {
+		self.context.as_ref().map_or(0, |c| c.commits.len())
+	}
+
+	fn pick_selected(&mut self) {
+		self.hide();
+
+		let Some(context) = self.context.take() else {
+			return;
+		};
+
+		if let Some(commit) = context.commits.get(self.selection) {
+			context.target.jump(&self.queue, context.from, commit.id);
+		}
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let count = self.count();
+		if count == 0 {
+			return;
+		}
+
+		self.selection = if up {
+			self.selection.checked_sub(1).unwrap_or(count - 1)
+		} else {
+			(self.selection + 1) % count
+		};
+	}
+
+	fn get_text(&self, width: usize) -> Vec<Line<'_>> {
+		let Some(context) = &self.context else {
+			return Vec::new();
+		};
+
+		let label_width = context
+			.labels
+			.iter()
+			.map(|label| label.len() + 1)
+			.max()
+			.unwrap_or_default();
+
+		context
+			.commits
+			.iter()
+			.enumerate()
+			.map(|(idx, commit)| {
+				let selected = idx == self.selection;
+				let label = context
+					.labels
+					.get(idx)
+					.map_or_else(String::new, |label| {
+						format!("{label:label_width$}")
+					});
+				let hash = commit.id.get_short_string();
+				let summary: String =
+					commit
+						.message
+						.lines()
+						.next()
+						.unwrap_or_default()
+						.chars()
+						.take(width.saturating_sub(
+							label_width + hash.len() + 1,
+						))
+						.collect();
+
+				Line::from(vec![
+					Span::styled(
+						label,
+						self.theme.branch(selected, true),
+					),
+					Span::styled(
+						hash,
+						self.theme.commit_hash(selected),
+					),
+					Span::styled(
+						format!(" {summary}"),
+						self.theme.text(true, selected),
+					),
+				])
+			})
+			.collect()
+	}
+}
+
+impl DrawableComponent for CommitPickerPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			const WIDTH: u16 = 60;
+			let height = u16::try_from(self.count())
+				.unwrap_or_default()
+				.saturating_add(2);
+			let area =
+				ui::centered_rect_absolute(WIDTH, height, area);
+
+			let title = self
+				.context
+				.as_ref()
+				.map_or_else(String::new, |c| c.title.to_string());
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(self.get_text(usize::from(
+					area.width.saturating_sub(2),
+				)))
+				.block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							title,
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for CommitPickerPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::commit_picker_confirm(
+						&self.key_config,
+					),
+					true,
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			match event {
+				Event::Key(key) => {
+					if key_match(key, self.key_config.keys.exit_popup)
+					{
+						self.hide();
+					} else if key_match(
+						key,
+						self.key_config.keys.move_up,
+					) {
+						self.move_selection(true);
+					} else if key_match(
+						key,
+						self.key_config.keys.move_down,
+					) {
+						self.move_selection(false);
+					} else if key_match(
+						key,
+						self.key_config.keys.enter,
+					) {
+						self.pick_selected();
+					}
+				}
+				Event::Mouse(mouse)
+					if mouse.kind
+						== MouseEventKind::Down(
+							MouseButton::Right,
+						) =>
+				{
+					self.hide();
+				}
+				_ => (),
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
@@ -182,14 +182,22 @@ impl CompareCommitsPopup {
 
 	///
 	pub fn open(&mut self, open: InspectCommitOpen) -> Result<()> {
-		let compare_id = if let Some(compare_id) = open.compare_id {
-			compare_id
+		let ids = if let Some(compare_id) = open.compare_id {
+			// two marked commits are always diffed old to new,
+			// no matter in which order they got marked
+			sync::sort_commit_ids(
+				&self.repo.borrow(),
+				(open.commit_id, compare_id),
+			)?
 		} else {
-			sync::get_head_tuple(&self.repo.borrow())?.id
+			OldNew {
+				old: sync::get_head_tuple(&self.repo.borrow())?.id,
+				new: open.commit_id,
+			}
 		};
 		self.open_request = Some(InspectCommitOpen {
-			commit_id: open.commit_id,
-			compare_id: Some(compare_id),
+			commit_id: ids.new,
+			compare_id: Some(ids.old),
 			tags: open.tags,
 		});
 		self.show()?;
@@ -9,6 +9,7 @@ use crate::{
 	strings, ui,
 };
 use anyhow::Result;
+use asyncgit::sync::{self, RepoPath};
 use crossterm::event::Event;
 use ratatui::{layout::Rect, text::Text, widgets::Clear, Frame};
 use std::borrow::Cow;
@@ -19,6 +20,8 @@ use super::popup_paragraph;
 ///
 pub struct ConfirmPopup {
 	target: Option<Action>,
+	/// the protected branches `target` is about
+	protected: Vec<String>,
 	visible: bool,
 	queue: Queue,
 	theme: SharedTheme,
@@ -28,7 +31,12 @@ pub struct ConfirmPopup {
 impl DrawableComponent for ConfirmPopup {
 	fn draw(&self, f: &mut Frame, _rect: Rect) -> Result<()> {
 		if self.visible {
-			let (title, msg) = self.get_text();
+			let (title, mut msg) = self.get_text();
+			if !self.protected.is_empty() {
+				msg.push_str(&strings::confirm_msg_protected(
+					&self.protected,
+				));
+			}
 
 			let txt = Text::styled(
 				Cow::from(msg),
@@ -103,6 +111,7 @@ impl ConfirmPopup {
 	pub fn new(env: &Environment) -> Self {
 		Self {
 			target: None,
+			protected: Vec::new(),
 			visible: false,
 			queue: env.queue.clone(),
 			theme: env.theme.clone(),
@@ -110,8 +119,13 @@ impl ConfirmPopup {
 		}
 	}
 	///
-	pub fn open(&mut self, a: Action) -> Result<()> {
+	pub fn open(
+		&mut self,
+		a: Action,
+		protected: Vec<String>,
+	) -> Result<()> {
 		self.target = Some(a);
+		self.protected = protected;
 		self.show()?;
 
 		Ok(())
@@ -125,6 +139,44 @@ impl ConfirmPopup {
 		self.hide();
 	}
 
+	/// the protected branches `action` deletes or force pushes,
+	/// these are confirmed even if the confirmation is turned off
+	pub fn protected_branches(
+		repo: &RepoPath,
+		action: &Action,
+	) -> Vec<String> {
+		let local = |branch_ref: &str| {
+			branch_ref
+				.strip_prefix("refs/heads/")
+				.unwrap_or(branch_ref)
+				.to_string()
+		};
+
+		let branches = match action {
+			Action::DeleteLocalBranch(branch_ref) => {
+				vec![local(branch_ref)]
+			}
+			Action::DeleteBranches(branch_refs) => {
+				branch_refs.iter().map(|r| local(r)).collect()
+			}
+			Action::DeleteRemoteBranch(branch_ref) => branch_ref
+				.strip_prefix("refs/remotes/")
+				.and_then(|name| name.split_once('/'))
+				.map(|(_, name)| vec![name.to_string()])
+				.unwrap_or_default(),
+			Action::ForcePush(branch, _) => vec![local(branch)],
+			_ => return Vec::new(),
+		};
+
+		sync::protected_branches(
+			repo,
+			branches.iter().map(String::as_str),
+		)
+		.map_err(|e| log::error!("protected branches: {e}"))
+		.unwrap_or_default()
+	}
+
+	#[allow(clippy::too_many_lines)]
 	fn get_text(&self) -> (String, String) {
 		if let Some(ref a) = self.target {
 			return match a {
@@ -132,6 +184,10 @@ impl ConfirmPopup {
                     strings::confirm_title_reset(),
                     strings::confirm_msg_reset(),
                 ),
+                Action::ResetItems(paths) => (
+                    strings::confirm_title_reset(),
+                    strings::confirm_msg_reset_items(paths),
+                ),
                 Action::StashDrop(ids) => (
                     strings::confirm_title_stashdrop(
                         &self.key_config,ids.len()>1
@@ -159,6 +215,10 @@ impl ConfirmPopup {
                         branch_ref,
                     ),
                 ),
+                Action::DeleteBranches(branch_refs) => (
+                    strings::confirm_title_delete_branches(),
+                    strings::confirm_msg_delete_branches(branch_refs),
+                ),
                 Action::DeleteRemoteBranch(branch_ref) => (
                     strings::confirm_title_delete_remote_branch(
                         &self.key_config,
@@ -172,6 +232,14 @@ impl ConfirmPopup {
 			strings::confirm_title_delete_remote(&self.key_config),
 			strings::confirm_msg_delete_remote(&self.key_config,remote_name),
 		),
+		Action::RemoveWorktree(name)=>(
+			strings::confirm_title_remove_worktree(&self.key_config),
+			strings::confirm_msg_remove_worktree(&self.key_config,name),
+		),
+		Action::RemoveSubmodule(name)=>(
+			strings::confirm_title_remove_submodule(&self.key_config),
+			strings::confirm_msg_remove_submodule(&self.key_config,name),
+		),
                 Action::DeleteTag(tag_name) => (
                     strings::confirm_title_delete_tag(
                         &self.key_config,
@@ -185,6 +253,10 @@ impl ConfirmPopup {
                     strings::confirm_title_delete_tag_remote(),
                     strings::confirm_msg_delete_tag_remote(remote),
                 ),
+                Action::ResetHard(id) => (
+                    strings::confirm_title_reset_hard(),
+                    strings::confirm_msg_reset_hard(&id.get_short_string()),
+                ),
                 Action::ForcePush(branch, _force) => (
                     strings::confirm_title_force_push(
                         &self.key_config,
@@ -209,11 +281,29 @@ impl ConfirmPopup {
 				Action::AbortRevert => (
                     strings::confirm_title_abortrevert(),
                     strings::confirm_msg_revertchanges(),
+                ),
+				Action::AbortCherryPick => (
+                    strings::confirm_title_abortcherrypick(),
+                    strings::confirm_msg_revertchanges(),
+                ),
+				Action::AbortApplyMailbox => (
+                    strings::confirm_title_abortapplymailbox(),
+                    strings::confirm_msg_abortapplymailbox(),
+                ),
+                Action::FinishFlowBranch { branch, into } => (
+                    strings::confirm_title_finish_flow_branch(),
+                    strings::confirm_msg_finish_flow_branch(
+                        branch, into,
+                    ),
                 ),
                 Action::UndoCommit => (
                     strings::confirm_title_undo_commit(),
                     strings::confirm_msg_undo_commit(),
                 ),
+                Action::CheckoutFile { commit, path } => (
+                    strings::confirm_title_restore_version(),
+                    strings::confirm_msg_restore_version(path, *commit),
+                ),
             };
 		}
 
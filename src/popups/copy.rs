@@ -0,0 +1,458 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	forge::{self, WebTarget},
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, Queue},
+	string_utils::truncate_width,
+	strings,
+	tickets::Tickets,
+	try_or_popup,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::{anyhow, Result};
+use asyncgit::sync::{
+	get_commit_details, CommitId, CommitMessage, RepoPathRef,
+};
+use crossterm::event::Event;
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// everything that can be copied from where the popup was opened
+#[derive(Default, Clone)]
+pub struct CopyContext {
+	pub commit: Option<CommitId>,
+	pub path: Option<String>,
+	pub patch: Option<String>,
+	/// 1-based, inclusive line range inside `path`
+	pub lines: Option<(usize, usize)>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CopyItem {
+	ShortSha,
+	LongSha,
+	Message,
+	Patch,
+	Path,
+	Permalink,
+	TicketLink,
+}
+
+impl CopyItem {
+	const fn label(self) -> &'static str {
+		match self {
+			Self::ShortSha => "Commit SHA (short)",
+			Self::LongSha => "Commit SHA",
+			Self::Message => "Commit message",
+			Self::Patch => "Selected lines as patch",
+			Self::Path => "File path",
+			Self::Permalink => "Permalink URL",
+			Self::TicketLink => "Ticket URL",
+		}
+	}
+}
+
+pub struct CopyPopup {
+	repo: RepoPathRef,
+	context: CopyContext,
+	/// id and url of the ticket the commit message mentions
+	ticket: Option<(String, String)>,
+	items: Vec<CopyItem>,
+	selection: usize,
+	visible: bool,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl CopyPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			repo: env.repo.clone(),
+			context: CopyContext::default(),
+			ticket: None,
+			items: Vec::new(),
+			selection: 0,
+			visible: false,
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	///
+	pub fn open(&mut self, context: CopyContext) -> Result<()> {
+		let mut items = Vec::with_capacity(7);
+
+		if context.commit.is_some() {
+			items.extend([
+				CopyItem::ShortSha,
+				CopyItem::LongSha,
+				CopyItem::Message,
+			]);
+		}
+		if context.patch.is_some() {
+			items.push(CopyItem::Patch);
+		}
+		if context.path.is_some() {
+			items.push(CopyItem::Path);
+		}
+		if context.commit.is_some() {
+			items.push(CopyItem::Permalink);
+		}
+
+		self.ticket = context.commit.and_then(|commit| {
+			self.ticket_link(commit)
+				.map_err(|e| log::error!("ticket link: {e}"))
+				.ok()
+				.flatten()
+		});
+		if self.ticket.is_some() {
+			items.push(CopyItem::TicketLink);
+		}
+
+		if items.is_empty() {
+			return Ok(());
+		}
+
+		self.items = items;
+		self.context = context;
+		self.selection = 0;
+
+		self.show()
+	}
+
+	fn preview(&self, item: CopyItem) -> String {
+		match item {
+			CopyItem::ShortSha => self
+				.context
+				.commit
+				.map(|c| c.get_short_string())
+				.unwrap_or_default(),
+			CopyItem::LongSha => self
+				.context
+				.commit
+				.map(|c| c.to_string())
+				.unwrap_or_default(),
+			CopyItem::Path => {
+				self.context.path.clone().unwrap_or_default()
+			}
+			CopyItem::Patch => self
+				.context
+				.patch
+				.as_ref()
+				.map(|p| format!("{} lines", p.lines().count()))
+				.unwrap_or_default(),
+			CopyItem::Permalink => self
+				.context
+				.lines
+				.map(|(start, end)| {
+					if start == end {
+						format!("line {start}")
+					} else {
+						format!("lines {start}-{end}")
+					}
+				})
+				.unwrap_or_default(),
+			CopyItem::TicketLink => self
+				.ticket
+				.as_ref()
+				.map(|(ticket, _)| ticket.clone())
+				.unwrap_or_default(),
+			CopyItem::Message => String::new(),
+		}
+	}
+
+	fn content(&self, item: CopyItem) -> Result<String> {
+		let missing = || anyhow!("nothing to copy");
+		let repo = self.repo.borrow();
+
+		Ok(match item {
+			CopyItem::ShortSha => self
+				.context
+				.commit
+				.ok_or_else(missing)?
+				.get_short_string(),
+			CopyItem::LongSha => {
+				self.context.commit.ok_or_else(missing)?.to_string()
+			}
+			CopyItem::Message => {
+				let commit =
+					self.context.commit.ok_or_else(missing)?;
+				get_commit_details(&repo, commit)?
+					.message
+					.map(CommitMessage::combine)
+					.unwrap_or_default()
+			}
+			CopyItem::Patch => {
+				self.context.patch.clone().ok_or_else(missing)?
+			}
+			CopyItem::Path => {
+				self.context.path.clone().ok_or_else(missing)?
+			}
+			CopyItem::Permalink => self.permalink()?,
+			CopyItem::TicketLink => self
+				.ticket
+				.as_ref()
+				.map(|(_, url)| url.clone())
+				.ok_or_else(missing)?,
+		})
+	}
+
+	fn ticket_link(
+		&self,
+		commit: CommitId,
+	) -> Result<Option<(String, String)>> {
+		let repo = self.repo.borrow();
+		let Some(tickets) =
+			Tickets::load(&repo).filter(Tickets::has_links)
+		else {
+			return Ok(None);
+		};
+
+		let msg = get_commit_details(&repo, commit)?
+			.message
+			.map(CommitMessage::combine)
+			.unwrap_or_default();
+
+		Ok(tickets.find(&msg).and_then(|ticket| {
+			let url = tickets.url(&ticket)?;
+			Some((ticket, url))
+		}))
+	}
+
+	fn permalink(&self) -> Result<String> {
+		let commit = self
+			.context
+			.commit
+			.ok_or_else(|| anyhow!("no commit"))?;
+
+		forge::permalink(
+			&self.repo.borrow(),
+			&WebTarget {
+				commit,
+				path: self.context.path.as_deref(),
+				lines: self.context.lines,
+			},
+		)
+	}
+
+	/// the ticket if it is selected, the permalink otherwise
+	fn open_in_browser(&mut self) {
+		let selected = self.items.get(self.selection).copied();
+		let url = match (selected, &self.ticket) {
+			(Some(CopyItem::TicketLink), Some((_, url))) => {
+				Ok(url.clone())
+			}
+			_ => self.permalink(),
+		};
+
+		let result = url.and_then(|url| {
+			forge::open_in_browser(&url)?;
+			Ok(url)
+		});
+
+		self.hide();
+
+		try_or_popup!(self, strings::POPUP_FAIL_OPEN_URL, result);
+	}
+
+	fn copy_selected(&mut self) {
+		let Some(item) = self.items.get(self.selection).copied()
+		else {
+			return;
+		};
+
+		let result = self.content(item).and_then(|content| {
+			crate::clipboard::copy_string(&content)?;
+			Ok(content)
+		});
+
+		self.hide();
+
+		if let Ok(content) = &result {
+			let first_line =
+				content.lines().next().unwrap_or_default();
+			self.queue.push(InternalEvent::ShowInfoMsg(
+				strings::copy_success(first_line),
+			));
+		}
+
+		try_or_popup!(self, strings::POPUP_FAIL_COPY, result);
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let count = self.items.len();
+		if count == 0 {
+			return;
+		}
+
+		self.selection = if up {
+			self.selection.checked_sub(1).unwrap_or(count - 1)
+		} else {
+			(self.selection + 1) % count
+		};
+	}
+
+	fn get_text(&self, width: usize) -> Vec<Line<'_>> {
+		const LABEL_WIDTH: usize = 24;
+
+		self.items
+			.iter()
+			.enumerate()
+			.map(|(idx, item)| {
+				let selected = idx == self.selection;
+				let preview = self.preview(*item);
+				let preview = truncate_width(
+					&preview,
+					width.saturating_sub(LABEL_WIDTH),
+				);
+
+				Line::from(vec![
+					Span::styled(
+						format!("{:LABEL_WIDTH$}", item.label()),
+						self.theme.text(true, selected),
+					),
+					Span::styled(
+						preview.to_string(),
+						self.theme.text(false, selected),
+					),
+				])
+			})
+			.collect()
+	}
+}
+
+impl DrawableComponent for CopyPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			const WIDTH: u16 = 60;
+			let height = u16::try_from(self.items.len())
+				.unwrap_or_default()
+				.saturating_add(2);
+			let area =
+				ui::centered_rect_absolute(WIDTH, height, area);
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(self.get_text(usize::from(
+					area.width.saturating_sub(2),
+				)))
+				.block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							strings::POPUP_TITLE_COPY,
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for CopyPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::copy_menu_confirm(
+						&self.key_config,
+					),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::open_in_browser(
+						&self.key_config,
+					),
+					self.context.commit.is_some(),
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = &event {
+				if key_match(key, self.key_config.keys.exit_popup) {
+					self.hide();
+				} else if key_match(key, self.key_config.keys.move_up)
+				{
+					self.move_selection(true);
+				} else if key_match(
+					key,
+					self.key_config.keys.move_down,
+				) {
+					self.move_selection(false);
+				} else if key_match(key, self.key_config.keys.enter) {
+					self.copy_selected();
+				} else if key_match(
+					key,
+					self.key_config.keys.open_in_browser,
+				) && self.context.commit.is_some()
+				{
+					self.open_in_browser();
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
@@ -4,6 +4,7 @@ use crate::components::{
 };
 use crate::{
 	app::Environment,
+	completion::CompletionSource,
 	keys::{key_match, SharedKeyConfig},
 	queue::{InternalEvent, NeedsUpdate, Queue},
 	strings,
@@ -98,7 +99,8 @@ impl CreateBranchPopup {
 				&strings::create_branch_popup_msg(&env.key_config),
 				true,
 			)
-			.with_input_type(InputType::Singleline),
+			.with_input_type(InputType::Singleline)
+			.with_completion(env, CompletionSource::Branches),
 			theme: env.theme.clone(),
 			key_config: env.key_config.clone(),
 			repo: env.repo.clone(),
@@ -131,7 +133,7 @@ impl CreateBranchPopup {
 			Err(e) => {
 				log::error!("create branch: {e}");
 				self.queue.push(InternalEvent::ShowErrorMsg(
-					format!("create branch error:\n{e}",),
+					format!("create branch error:\n{e}"),
 				));
 			}
 		}
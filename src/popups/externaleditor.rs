@@ -4,6 +4,7 @@ use crate::{
 		visibility_blocking, CommandBlocking, CommandInfo, Component,
 		DrawableComponent, EventState,
 	},
+	input,
 	keys::SharedKeyConfig,
 	strings,
 	ui::{self, style::SharedTheme},
@@ -44,10 +45,12 @@ impl ExternalEditorPopup {
 		}
 	}
 
-	/// opens file at given `path` in an available editor
+	/// opens file at given `path` in an available editor, at `line`
+	/// if the editor takes a `+<line>` argument
 	pub fn open_file_in_editor(
 		repo: &RepoPath,
 		path: &Path,
+		line: Option<usize>,
 	) -> Result<()> {
 		let work_dir = repo_work_dir(repo)?;
 
@@ -61,9 +64,17 @@ impl ExternalEditorPopup {
 			bail!("file not found: {path:?}");
 		}
 
+		// the editor shall not receive our mouse reports
+		let mouse = input::mouse_capture();
+		if mouse {
+			input::set_mouse_capture(false)?;
+		}
 		io::stdout().execute(LeaveAlternateScreen)?;
 		defer! {
 			io::stdout().execute(EnterAlternateScreen).expect("reset terminal");
+			if mouse {
+				input::set_mouse_capture(true).expect("reset terminal");
+			}
 		}
 
 		let environment_options = ["GIT_EDITOR", "VISUAL", "EDITOR"];
@@ -107,6 +118,13 @@ impl ExternalEditorPopup {
 		let mut args: Vec<&OsStr> =
 			remainder.map(OsStr::new).collect();
 
+		let line = line
+			.filter(|_| Self::takes_line_arg(&command))
+			.map(|line| format!("+{line}"));
+		if let Some(line) = &line {
+			args.push(OsStr::new(line));
+		}
+
 		args.push(path.as_os_str());
 
 		Command::new(command.clone())
@@ -117,6 +135,26 @@ impl ExternalEditorPopup {
 
 		Ok(())
 	}
+
+	fn takes_line_arg(command: &str) -> bool {
+		const EDITORS: [&str; 10] = [
+			"vi",
+			"vim",
+			"nvim",
+			"gvim",
+			"nano",
+			"emacs",
+			"emacsclient",
+			"micro",
+			"kak",
+			"joe",
+		];
+
+		Path::new(command)
+			.file_stem()
+			.and_then(OsStr::to_str)
+			.is_some_and(|name| EDITORS.contains(&name))
+	}
 }
 
 impl DrawableComponent for ExternalEditorPopup {
@@ -19,23 +19,20 @@ use asyncgit::{
 		},
 		RepoPathRef,
 	},
-	AsyncFetchJob, AsyncGitNotification, ProgressPercent,
+	AsyncFetchJob, AsyncGitNotification, Progress,
 };
 use crossterm::event::Event;
-use ratatui::{
-	layout::Rect,
-	text::Span,
-	widgets::{Block, BorderType, Borders, Clear, Gauge},
-	Frame,
-};
+use ratatui::{layout::Rect, Frame};
 
 ///
 pub struct FetchPopup {
 	repo: RepoPathRef,
 	visible: bool,
 	async_fetch: AsyncSingleJob<AsyncFetchJob>,
-	progress: Option<ProgressPercent>,
+	progress: Option<Progress>,
 	pending: bool,
+	/// fetches the missing history of a shallow clone as well
+	unshallow: bool,
 	queue: Queue,
 	theme: SharedTheme,
 	key_config: SharedKeyConfig,
@@ -48,6 +45,7 @@ impl FetchPopup {
 		Self {
 			queue: env.queue.clone(),
 			pending: false,
+			unshallow: false,
 			visible: false,
 			async_fetch: AsyncSingleJob::new(env.sender_git.clone()),
 			progress: None,
@@ -60,6 +58,17 @@ impl FetchPopup {
 
 	///
 	pub fn fetch(&mut self) -> Result<()> {
+		self.unshallow = false;
+		self.start()
+	}
+
+	/// fetches the history a shallow clone is missing
+	pub fn unshallow(&mut self) -> Result<()> {
+		self.unshallow = true;
+		self.start()
+	}
+
+	fn start(&mut self) -> Result<()> {
 		self.show()?;
 		if need_username_password(&self.repo.borrow())? {
 			let cred = extract_username_password(&self.repo.borrow())
@@ -82,11 +91,14 @@ impl FetchPopup {
 	fn fetch_all(&mut self, cred: Option<BasicAuthCredential>) {
 		self.pending = true;
 		self.progress = None;
-		self.progress = Some(ProgressPercent::empty());
-		self.async_fetch.spawn(AsyncFetchJob::new(
-			self.repo.borrow().clone(),
-			cred,
-		));
+
+		let job =
+			AsyncFetchJob::new(self.repo.borrow().clone(), cred);
+		self.async_fetch.spawn(if self.unshallow {
+			job.with_unshallow()
+		} else {
+			job
+		});
 	}
 
 	///
@@ -108,8 +120,27 @@ impl FetchPopup {
 
 		if !self.pending {
 			self.hide();
-			self.queue
-				.push(InternalEvent::Update(NeedsUpdate::BRANCHES));
+
+			if self.unshallow {
+				if let Some(Err(e)) = self
+					.async_fetch
+					.take_last()
+					.and_then(|job| job.result())
+				{
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("unshallow failed:\n{e}"),
+					));
+				}
+
+				// the log reloads once the history is complete
+				self.queue.push(InternalEvent::Update(
+					NeedsUpdate::ALL | NeedsUpdate::BRANCHES,
+				));
+			} else {
+				self.queue.push(InternalEvent::Update(
+					NeedsUpdate::BRANCHES,
+				));
+			}
 		}
 	}
 }
@@ -117,26 +148,15 @@ impl FetchPopup {
 impl DrawableComponent for FetchPopup {
 	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
 		if self.visible {
-			let progress = self.progress.unwrap_or_default().progress;
-
-			let area = ui::centered_rect_absolute(30, 3, f.area());
-
-			f.render_widget(Clear, area);
-			f.render_widget(
-				Gauge::default()
-					.block(
-						Block::default()
-							.title(Span::styled(
-								strings::FETCH_POPUP_MSG,
-								self.theme.title(true),
-							))
-							.borders(Borders::ALL)
-							.border_type(BorderType::Thick)
-							.border_style(self.theme.block(true)),
-					)
-					.gauge_style(self.theme.push_gauge())
-					.percent(u16::from(progress)),
-				area,
+			ui::draw_progress(
+				f,
+				if self.unshallow {
+					strings::UNSHALLOW_POPUP_MSG
+				} else {
+					strings::FETCH_POPUP_MSG
+				},
+				self.progress.as_ref(),
+				&self.theme,
 			);
 			self.input_cred.draw(f, rect)?;
 		}
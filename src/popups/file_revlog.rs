@@ -7,7 +7,9 @@ use crate::{
 	},
 	keys::{key_match, SharedKeyConfig},
 	options::SharedOptions,
-	queue::{InternalEvent, NeedsUpdate, Queue, StackablePopupOpen},
+	queue::{
+		Action, InternalEvent, NeedsUpdate, Queue, StackablePopupOpen,
+	},
 	strings,
 	ui::{draw_scrollbar, style::SharedTheme, Orientation},
 };
@@ -238,6 +240,21 @@ impl FileRevlogPopup {
 		commit_id
 	}
 
+	/// asks to restore the file in the workdir to the selected
+	/// version
+	fn restore_version(&self) {
+		if let (Some(commit), Some(open_request)) =
+			(self.selected_commit(), &self.open_request)
+		{
+			self.queue.push(InternalEvent::ConfirmAction(
+				Action::CheckoutFile {
+					commit,
+					path: open_request.file_path.clone(),
+				},
+			));
+		}
+	}
+
 	fn can_focus_diff(&self) -> bool {
 		self.selected_commit().is_some()
 	}
@@ -430,8 +447,10 @@ impl FileRevlogPopup {
 			f,
 			area,
 			&self.theme,
-			self.count_total,
-			table_state.selected().unwrap_or(0),
+			self.count_total.saturating_sub(usize::from(
+				area.height.saturating_sub(2),
+			)),
+			table_state.offset(),
 			Orientation::Vertical,
 		);
 
@@ -537,6 +556,11 @@ impl Component for FileRevlogPopup {
 							),
 						));
 					}
+				} else if key_match(
+					key,
+					self.key_config.keys.restore_version,
+				) {
+					self.restore_version();
 				} else if key_match(key, self.key_config.keys.move_up)
 				{
 					self.move_selection(ScrollType::Up)?;
@@ -610,6 +634,16 @@ impl Component for FileRevlogPopup {
 				)
 				.order(1),
 			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::restore_version(
+						&self.key_config,
+					),
+					true,
+					self.selected_commit().is_some(),
+				)
+				.order(1),
+			);
 
 			out.push(CommandInfo::new(
 				strings::commands::diff_focus_right(&self.key_config),
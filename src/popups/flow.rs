@@ -0,0 +1,343 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState, InputType, TextInputComponent,
+	},
+	keys::{key_match, SharedKeyConfig},
+	queue::{Action, InternalEvent, NeedsUpdate, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+	cached::BranchName,
+	sync::{self, FlowConfig, FlowKind, RepoPathRef},
+};
+use crossterm::event::Event;
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+enum FlowItem {
+	Start(FlowKind),
+	/// the current branch
+	Finish(String),
+}
+
+/// starts and finishes git-flow style branches
+pub struct FlowPopup {
+	repo: RepoPathRef,
+	branch_name: BranchName,
+	config: Option<FlowConfig>,
+	items: Vec<FlowItem>,
+	selection: usize,
+	/// kind of the branch whose name is entered in `input`
+	starting: Option<FlowKind>,
+	input: TextInputComponent,
+	visible: bool,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl FlowPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			repo: env.repo.clone(),
+			branch_name: BranchName::new(env.repo.clone()),
+			config: None,
+			items: Vec::new(),
+			selection: 0,
+			starting: None,
+			input: TextInputComponent::new(env, "", "", true)
+				.with_input_type(InputType::Singleline),
+			visible: false,
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		let config = sync::flow_config(&self.repo.borrow())?;
+		let current = self.branch_name.lookup().ok();
+
+		self.items =
+			FlowKind::ALL.into_iter().map(FlowItem::Start).collect();
+		if let Some(current) = current
+			.filter(|branch| config.parse_branch(branch).is_some())
+		{
+			self.items.push(FlowItem::Finish(current));
+		}
+
+		// finishing is the likely thing to do on a flow branch
+		self.selection = self.items.len().saturating_sub(1);
+		if self.items.len() == FlowKind::ALL.len() {
+			self.selection = 0;
+		}
+
+		self.config = Some(config);
+		self.starting = None;
+
+		self.show()
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let count = self.items.len();
+		if count == 0 {
+			return;
+		}
+
+		self.selection = if up {
+			self.selection.checked_sub(1).unwrap_or(count - 1)
+		} else {
+			(self.selection + 1) % count
+		};
+	}
+
+	fn confirm_selected(&mut self) -> Result<()> {
+		let Some(config) = &self.config else {
+			return Ok(());
+		};
+
+		match self.items.get(self.selection) {
+			Some(FlowItem::Start(kind)) => {
+				self.input.set_title(strings::flow_start_title(
+					kind.label(),
+				));
+				self.input.set_default_msg(strings::flow_start_msg(
+					config.prefix(*kind),
+					config.base(*kind),
+				));
+				self.input.clear();
+				self.input.show()?;
+				self.starting = Some(*kind);
+			}
+			Some(FlowItem::Finish(branch)) => {
+				let into = config
+					.parse_branch(branch)
+					.map(|(kind, _)| config.targets(kind))
+					.unwrap_or_default()
+					.into_iter()
+					.map(String::from)
+					.collect();
+
+				self.queue.push(InternalEvent::ConfirmAction(
+					Action::FinishFlowBranch {
+						branch: branch.clone(),
+						into,
+					},
+				));
+				self.hide();
+			}
+			None => (),
+		}
+
+		Ok(())
+	}
+
+	fn start_branch(&mut self) {
+		let Some(kind) = self.starting else {
+			return;
+		};
+
+		let res = sync::flow_start(
+			&self.repo.borrow(),
+			kind,
+			self.input.get_text(),
+		);
+
+		self.hide();
+
+		match res {
+			Ok(_) => {
+				self.queue.push(InternalEvent::Update(
+					NeedsUpdate::ALL | NeedsUpdate::BRANCHES,
+				));
+			}
+			Err(e) => {
+				log::error!("flow start: {e}");
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("start {} error:\n{e}", kind.label()),
+				));
+			}
+		}
+	}
+
+	fn get_text(&self) -> Vec<Line<'_>> {
+		self.items
+			.iter()
+			.enumerate()
+			.map(|(idx, item)| {
+				let text = match item {
+					FlowItem::Start(kind) => {
+						format!("start {}", kind.label())
+					}
+					FlowItem::Finish(branch) => {
+						format!("finish {branch}")
+					}
+				};
+
+				Line::from(Span::styled(
+					text,
+					self.theme.text(true, idx == self.selection),
+				))
+			})
+			.collect()
+	}
+}
+
+impl DrawableComponent for FlowPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.starting.is_some() {
+			return self.input.draw(f, area);
+		}
+
+		if self.is_visible() {
+			const MIN_WIDTH: u16 = 30;
+
+			let text = self.get_text();
+
+			let width = text
+				.iter()
+				.map(Line::width)
+				.max()
+				.and_then(|width| u16::try_from(width + 2).ok())
+				.unwrap_or_default()
+				.clamp(MIN_WIDTH, area.width);
+			let height = u16::try_from(text.len())
+				.unwrap_or_default()
+				.saturating_add(2)
+				.min(area.height);
+			let area =
+				ui::centered_rect_absolute(width, height, area);
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(text).block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							strings::POPUP_TITLE_FLOW,
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for FlowPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.starting.is_some() {
+			self.input.commands(out, force_all);
+
+			out.push(CommandInfo::new(
+				strings::commands::create_branch_confirm_msg(
+					&self.key_config,
+				),
+				true,
+				true,
+			));
+		} else if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::flow_confirm(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.starting.is_some() {
+			if self.input.event(event)?.is_consumed() {
+				if !self.input.is_visible() {
+					// esc goes back to the list
+					self.starting = None;
+				}
+				return Ok(EventState::Consumed);
+			}
+
+			if let Event::Key(key) = event {
+				if key_match(key, self.key_config.keys.enter) {
+					self.start_branch();
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		if self.is_visible() {
+			if let Event::Key(key) = event {
+				if key_match(key, self.key_config.keys.exit_popup) {
+					self.hide();
+				} else if key_match(key, self.key_config.keys.move_up)
+				{
+					self.move_selection(true);
+				} else if key_match(
+					key,
+					self.key_config.keys.move_down,
+				) {
+					self.move_selection(false);
+				} else if key_match(key, self.key_config.keys.enter) {
+					self.confirm_selected()?;
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+		self.starting = None;
+		self.input.hide();
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
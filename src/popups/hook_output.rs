@@ -0,0 +1,220 @@
+use crate::components::{
+	visibility_blocking, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState,
+};
+use crate::{
+	app::Environment,
+	queue::{InternalEvent, Queue},
+	spinner::spinner_char,
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+	asyncjob::AsyncSingleJob,
+	sync::{HookOutput, HookResult, RepoPath, HOOK_PRE_PUSH},
+	AsyncGitNotification, AsyncPrePushJob, PrePushRequest,
+};
+use crossterm::event::Event;
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, BorderType, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// shows what a hook prints while it runs, for hooks like `pre-push`
+/// that may run whole test suites
+pub struct HookOutputPopup {
+	job: AsyncSingleJob<AsyncPrePushJob>,
+	/// shares the output with the spawned job
+	running: Option<AsyncPrePushJob>,
+	output: Vec<HookOutput>,
+	spinner: usize,
+	visible: bool,
+	queue: Queue,
+	theme: SharedTheme,
+}
+
+impl HookOutputPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			job: AsyncSingleJob::new(env.sender_git.clone()),
+			running: None,
+			output: Vec::new(),
+			spinner: 0,
+			visible: false,
+			queue: env.queue.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	///
+	pub fn run_pre_push(
+		&mut self,
+		repo: RepoPath,
+		request: PrePushRequest,
+	) {
+		let job = AsyncPrePushJob::new(repo, request);
+
+		self.running = Some(job.clone());
+		self.job.spawn(job);
+		self.output.clear();
+		self.visible = true;
+	}
+
+	/// once the hook is done: `true` if the push may go on, failures
+	/// and warnings are shown to the user
+	pub fn update_git(
+		&mut self,
+		ev: AsyncGitNotification,
+	) -> Option<bool> {
+		match ev {
+			AsyncGitNotification::HookOutput => {
+				if let Some(job) = &self.running {
+					self.output = job.output();
+				}
+				None
+			}
+			AsyncGitNotification::PrePush => {
+				let result = self.job.take_last()?.result()?;
+
+				self.running = None;
+				self.hide();
+
+				Some(self.report(result))
+			}
+			_ => None,
+		}
+	}
+
+	fn report(&self, result: asyncgit::Result<HookResult>) -> bool {
+		match result {
+			Ok(HookResult::Ok) => true,
+			Ok(HookResult::Warning(e)) => {
+				log::warn!("pre-push hook warning: {e}");
+				self.queue.push(InternalEvent::ShowInfoMsg(format!(
+					"pre-push hook warning:\n{e}"
+				)));
+				true
+			}
+			Ok(HookResult::NotOk(e)) => {
+				log::error!("pre-push hook failed: {e}");
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("pre-push hook failed:\n{e}"),
+				));
+				false
+			}
+			Err(e) => {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("pre-push hook failed:\n{e}"),
+				));
+				false
+			}
+		}
+	}
+
+	/// next spinner frame, `true` if it needs a redraw
+	pub fn tick_spinner(&mut self) -> bool {
+		if self.visible && !self.theme.accessible() {
+			self.spinner = self.spinner.wrapping_add(1);
+			true
+		} else {
+			false
+		}
+	}
+
+	fn title(&self) -> String {
+		let title = strings::hook_running(HOOK_PRE_PUSH);
+
+		if self.theme.accessible() {
+			title
+		} else {
+			format!("{} {title}", spinner_char(self.spinner))
+		}
+	}
+}
+
+impl DrawableComponent for HookOutputPopup {
+	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
+		if self.visible {
+			const WIDTH: u16 = 80;
+			const HEIGHT: u16 = 20;
+
+			let area =
+				ui::centered_rect_absolute(WIDTH, HEIGHT, rect);
+
+			// the latest lines, like a terminal would
+			let height = usize::from(area.height.saturating_sub(2));
+			let lines: Vec<Line> = self
+				.output
+				.iter()
+				.skip(self.output.len().saturating_sub(height))
+				.map(|output| match output {
+					HookOutput::Stdout(line) => {
+						Line::from(Span::styled(
+							line.as_str(),
+							self.theme.text(true, false),
+						))
+					}
+					HookOutput::Stderr(line) => {
+						Line::from(Span::styled(
+							line.as_str(),
+							self.theme.text_danger(),
+						))
+					}
+				})
+				.collect();
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(lines).block(
+					Block::default()
+						.title(Span::styled(
+							self.title(),
+							self.theme.title(true),
+						))
+						.borders(Borders::ALL)
+						.border_type(BorderType::Thick)
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for HookOutputPopup {
+	fn commands(
+		&self,
+		_out: &mut Vec<CommandInfo>,
+		_force_all: bool,
+	) -> CommandBlocking {
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, _ev: &Event) -> Result<EventState> {
+		// the hook cannot be stopped
+		Ok(if self.visible {
+			EventState::Consumed
+		} else {
+			EventState::NotConsumed
+		})
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+		Ok(())
+	}
+}
@@ -0,0 +1,355 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	string_utils::pad_width,
+	strings, try_or_popup,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::sync::{self, IgnoreFile, RepoPathRef};
+use crossterm::event::{Event, MouseButton, MouseEventKind};
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+use std::path::Path;
+
+/// workdir entry to ignore
+#[derive(Clone)]
+pub struct IgnorePatternContext {
+	pub path: String,
+	pub is_folder: bool,
+}
+
+/// patterns to choose from: the exact path, all files with the same
+/// extension and the folder containing the file
+fn patterns(context: &IgnorePatternContext) -> Vec<(String, String)> {
+	let path = Path::new(&context.path);
+
+	let mut res = vec![(
+		if context.is_folder {
+			format!("/{}/", context.path)
+		} else {
+			format!("/{}", context.path)
+		},
+		String::from("exact path"),
+	)];
+
+	if !context.is_folder {
+		if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+			res.push((
+				format!("*.{ext}"),
+				format!("all .{ext} files"),
+			));
+		}
+	}
+
+	if let Some(parent) = path
+		.parent()
+		.and_then(Path::to_str)
+		.filter(|parent| !parent.is_empty())
+	{
+		res.push((format!("/{parent}/"), String::from("folder")));
+	}
+
+	res
+}
+
+pub struct IgnorePatternPopup {
+	repo: RepoPathRef,
+	items: Vec<(String, String)>,
+	selection: usize,
+	file: IgnoreFile,
+	visible: bool,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl IgnorePatternPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			repo: env.repo.clone(),
+			items: Vec::new(),
+			selection: 0,
+			file: IgnoreFile::GitIgnore,
+			visible: false,
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	///
+	pub fn open(
+		&mut self,
+		context: &IgnorePatternContext,
+	) -> Result<()> {
+		self.items = patterns(context);
+		self.selection = 0;
+
+		self.show()
+	}
+
+	fn apply_selected(&mut self) {
+		self.hide();
+
+		let Some((pattern, _)) = self.items.get(self.selection)
+		else {
+			return;
+		};
+
+		try_or_popup!(
+			self,
+			"ignore error:",
+			sync::add_ignore_pattern(
+				&self.repo.borrow(),
+				pattern,
+				self.file,
+			)
+		);
+
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let count = self.items.len();
+		if count == 0 {
+			return;
+		}
+
+		self.selection = if up {
+			self.selection.checked_sub(1).unwrap_or(count - 1)
+		} else {
+			(self.selection + 1) % count
+		};
+	}
+
+	fn switch_file(&mut self) {
+		self.file = match self.file {
+			IgnoreFile::GitIgnore => IgnoreFile::InfoExclude,
+			IgnoreFile::InfoExclude => IgnoreFile::GitIgnore,
+		};
+	}
+
+	fn get_text(&self, width: usize) -> Vec<Line<'_>> {
+		let mut res: Vec<Line> = self
+			.items
+			.iter()
+			.enumerate()
+			.map(|(idx, (pattern, label))| {
+				let selected = idx == self.selection;
+				Line::from(vec![
+					Span::styled(
+						pad_width(pattern, width),
+						self.theme.text(true, selected),
+					),
+					Span::styled(
+						format!(" {label}"),
+						self.theme.text(false, selected),
+					),
+				])
+			})
+			.collect();
+
+		res.push(Line::default());
+		res.push(Line::from(vec![
+			Span::styled("add to: ", self.theme.text(false, false)),
+			Span::styled(
+				match self.file {
+					IgnoreFile::GitIgnore => ".gitignore",
+					IgnoreFile::InfoExclude => ".git/info/exclude",
+				},
+				self.theme.text(true, false),
+			),
+		]));
+
+		res
+	}
+}
+
+impl DrawableComponent for IgnorePatternPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			const MIN_WIDTH: u16 = 30;
+			const LABEL_WIDTH: usize = 20;
+
+			let pattern_width = self
+				.items
+				.iter()
+				.map(|(pattern, _)| pattern.chars().count())
+				.max()
+				.unwrap_or_default();
+			let width = u16::try_from(pattern_width + LABEL_WIDTH)
+				.unwrap_or(u16::MAX)
+				.clamp(MIN_WIDTH, area.width);
+			let height = u16::try_from(self.items.len())
+				.unwrap_or_default()
+				.saturating_add(4);
+			let area =
+				ui::centered_rect_absolute(width, height, area);
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(self.get_text(pattern_width)).block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							strings::POPUP_TITLE_IGNORE_PATTERN,
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for IgnorePatternPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::ignore_pattern_confirm(
+						&self.key_config,
+					),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::ignore_pattern_switch_file(
+						&self.key_config,
+					),
+					true,
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			match event {
+				Event::Key(key) => {
+					if key_match(key, self.key_config.keys.exit_popup)
+					{
+						self.hide();
+					} else if key_match(
+						key,
+						self.key_config.keys.move_up,
+					) {
+						self.move_selection(true);
+					} else if key_match(
+						key,
+						self.key_config.keys.move_down,
+					) {
+						self.move_selection(false);
+					} else if key_match(
+						key,
+						self.key_config.keys.tab_toggle,
+					) {
+						self.switch_file();
+					} else if key_match(
+						key,
+						self.key_config.keys.enter,
+					) {
+						self.apply_selected();
+					}
+				}
+				Event::Mouse(mouse)
+					if mouse.kind
+						== MouseEventKind::Down(
+							MouseButton::Right,
+						) =>
+				{
+					self.hide();
+				}
+				_ => (),
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_patterns() {
+		let patterns = |path: &str, is_folder| {
+			patterns(&IgnorePatternContext {
+				path: path.into(),
+				is_folder,
+			})
+			.into_iter()
+			.map(|(pattern, _)| pattern)
+			.collect::<Vec<_>>()
+		};
+
+		assert_eq!(patterns("a.log", false), vec!["/a.log", "*.log"]);
+		assert_eq!(
+			patterns("src/a.rs", false),
+			vec!["/src/a.rs", "*.rs", "/src/"]
+		);
+		assert_eq!(patterns("Makefile", false), vec!["/Makefile"]);
+		assert_eq!(
+			patterns("src/gen", true),
+			vec!["/src/gen/", "/src/"]
+		);
+	}
+}
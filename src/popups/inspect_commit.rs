@@ -9,12 +9,13 @@ use crate::{
 	keys::{key_match, SharedKeyConfig},
 	options::SharedOptions,
 	queue::{InternalEvent, Queue, StackablePopupOpen},
-	strings,
+	strings, try_or_popup,
 };
 use anyhow::Result;
 use asyncgit::{
-	sync::{CommitId, CommitTags},
+	sync::{CommitId, CommitTags, RepoPathRef},
 	AsyncDiff, AsyncGitNotification, DiffParams, DiffType,
+	StatusItemType,
 };
 use crossterm::event::Event;
 use ratatui::{
@@ -23,7 +24,9 @@ use ratatui::{
 	Frame,
 };
 
-use super::FileTreeOpen;
+use super::{
+	goto_relative, CommitJumpTarget, FileTreeOpen, TreeSearchOpen,
+};
 
 #[derive(Clone, Debug)]
 pub struct InspectCommitOpen {
@@ -55,6 +58,7 @@ impl InspectCommitOpen {
 }
 
 pub struct InspectCommitPopup {
+	repo: RepoPathRef,
 	queue: Queue,
 	open_request: Option<InspectCommitOpen>,
 	diff: DiffComponent,
@@ -136,6 +140,24 @@ impl Component for InspectCommitPopup {
 				true,
 				true,
 			));
+
+			out.push(CommandInfo::new(
+				strings::commands::search_contents(&self.key_config),
+				true,
+				true,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::goto_parent(&self.key_config),
+				true,
+				true,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::goto_child(&self.key_config),
+				true,
+				true,
+			));
 		}
 
 		visibility_blocking(self)
@@ -143,6 +165,47 @@ impl Component for InspectCommitPopup {
 
 	fn event(&mut self, ev: &Event) -> Result<EventState> {
 		if self.is_visible() {
+			if let Event::Key(e) = ev {
+				if self.details.is_filtering() {
+					self.details.event(ev)?;
+					return Ok(EventState::Consumed);
+				}
+
+				if key_match(e, self.key_config.keys.copy_menu)
+					&& self.diff.focused()
+				{
+					let mut context = self.diff.copy_context();
+					context.commit = self
+						.open_request
+						.as_ref()
+						.map(|open| open.commit_id);
+					self.queue
+						.push(InternalEvent::OpenCopyPopup(context));
+					return Ok(EventState::Consumed);
+				}
+
+				let parents =
+					key_match(e, self.key_config.keys.goto_parent);
+				if parents
+					|| key_match(e, self.key_config.keys.goto_child)
+				{
+					if let Some(open) = &self.open_request {
+						try_or_popup!(
+							self,
+							"jump error:",
+							goto_relative(
+								&self.repo.borrow(),
+								&self.queue,
+								open.commit_id,
+								parents,
+								CommitJumpTarget::Inspect,
+							)
+						);
+					}
+					return Ok(EventState::Consumed);
+				}
+			}
+
 			if event_pump(ev, self.components_mut().as_mut_slice())?
 				.is_consumed()
 			{
@@ -173,22 +236,22 @@ impl Component for InspectCommitPopup {
 					self.hide_stacked(false);
 				} else if key_match(
 					e,
-					self.key_config.keys.open_file_tree,
+					self.key_config.keys.search_contents,
 				) {
-					if let Some(commit_id) = self
-						.open_request
-						.as_ref()
-						.map(|open_commit| open_commit.commit_id)
-					{
+					if let Some(open) = &self.open_request {
+						let open =
+							TreeSearchOpen::new(open.commit_id);
 						self.hide_stacked(true);
 						self.queue.push(InternalEvent::OpenPopup(
-							StackablePopupOpen::FileTree(
-								FileTreeOpen::new(commit_id),
-							),
+							StackablePopupOpen::TreeSearch(open),
 						));
-						return Ok(EventState::Consumed);
 					}
-					return Ok(EventState::NotConsumed);
+					return Ok(EventState::Consumed);
+				} else if key_match(
+					e,
+					self.key_config.keys.open_file_tree,
+				) {
+					return Ok(self.open_file_tree());
 				}
 
 				return Ok(EventState::Consumed);
@@ -220,6 +283,7 @@ impl InspectCommitPopup {
 	///
 	pub fn new(env: &Environment) -> Self {
 		Self {
+			repo: env.repo.clone(),
 			queue: env.queue.clone(),
 			details: CommitDetailsComponent::new(env),
 			diff: DiffComponent::new(env, true),
@@ -310,6 +374,34 @@ impl InspectCommitPopup {
 		Ok(())
 	}
 
+	fn open_file_tree(&mut self) -> EventState {
+		let Some(commit_id) = self
+			.open_request
+			.as_ref()
+			.map(|open_commit| open_commit.commit_id)
+		else {
+			return EventState::NotConsumed;
+		};
+
+		let mut open = FileTreeOpen::new(commit_id);
+		// deleted files are not in the tree
+		if let Some(file) = self
+			.details
+			.files()
+			.selection_file()
+			.filter(|f| f.status != StatusItemType::Deleted)
+		{
+			open = open.with_selection(file.path.into());
+		}
+
+		self.hide_stacked(true);
+		self.queue.push(InternalEvent::OpenPopup(
+			StackablePopupOpen::FileTree(open),
+		));
+
+		EventState::Consumed
+	}
+
 	fn can_focus_diff(&self) -> bool {
 		self.details.files().selection_file().is_some()
 	}
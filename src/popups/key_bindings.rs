@@ -0,0 +1,313 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{key_match, GituiKeyEvent, KeysList, SharedKeyConfig},
+	queue::{InternalEvent, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::{Event, KeyCode, KeyEvent};
+use ratatui::{
+	layout::{Constraint, Direction, Layout, Rect},
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// lists all bindings and lets the user rebind them, saving the
+/// changes to the key config file
+pub struct KeyBindingsPopup {
+	keys: KeysList,
+	bindings: Vec<(String, GituiKeyEvent)>,
+	selection: usize,
+	capturing: bool,
+	visible: bool,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl KeyBindingsPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			keys: KeysList::default(),
+			bindings: Vec::new(),
+			selection: 0,
+			capturing: false,
+			visible: false,
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.keys = self.key_config.keys.clone();
+		self.bindings = self.keys.bindings();
+		self.selection = 0;
+		self.capturing = false;
+
+		self.show()
+	}
+
+	/// names of all other actions bound to the same key as `idx`
+	fn conflicts(&self, idx: usize) -> Vec<&str> {
+		let Some((_, key)) = self.bindings.get(idx) else {
+			return Vec::new();
+		};
+
+		self.bindings
+			.iter()
+			.enumerate()
+			.filter(|(other, (_, binding))| {
+				*other != idx && binding == key
+			})
+			.map(|(_, (name, _))| name.as_str())
+			.collect()
+	}
+
+	fn rebind(&mut self, key: &KeyEvent) {
+		let Some((name, _)) = self.bindings.get(self.selection)
+		else {
+			return;
+		};
+
+		let result = self
+			.keys
+			.set_binding(
+				name,
+				GituiKeyEvent::new(key.code, key.modifiers),
+			)
+			.and_then(|()| {
+				self.keys.save(self.key_config.bindings_file())
+			});
+
+		match result {
+			Ok(()) => {
+				self.bindings = self.keys.bindings();
+				self.queue.push(InternalEvent::ShowInfoMsg(
+					strings::KEY_BINDINGS_SAVED.to_string(),
+				));
+			}
+			Err(e) => {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!(
+						"{}:\n{e}",
+						strings::POPUP_FAIL_SAVE_KEYS
+					),
+				));
+			}
+		}
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let count = self.bindings.len();
+		if count == 0 {
+			return;
+		}
+
+		self.selection = if up {
+			self.selection.checked_sub(1).unwrap_or(count - 1)
+		} else {
+			(self.selection + 1) % count
+		};
+	}
+
+	fn get_text(&self) -> Vec<Line<'_>> {
+		const NAME_WIDTH: usize = 32;
+
+		self.bindings
+			.iter()
+			.enumerate()
+			.map(|(idx, (name, key))| {
+				let selected = idx == self.selection;
+				let conflict = !self.conflicts(idx).is_empty();
+
+				Line::from(vec![
+					Span::styled(
+						format!("{name:NAME_WIDTH$}"),
+						self.theme.text(true, selected),
+					),
+					Span::styled(
+						self.key_config.get_hint(*key),
+						self.theme.text(true, selected),
+					),
+					Span::styled(
+						if conflict { " !" } else { "" },
+						self.theme.text_danger(),
+					),
+				])
+			})
+			.collect()
+	}
+
+	fn get_status(&self) -> Line<'_> {
+		let name = self
+			.bindings
+			.get(self.selection)
+			.map(|(name, _)| name.as_str())
+			.unwrap_or_default();
+
+		if self.capturing {
+			return Line::from(strings::key_bindings_capture(name));
+		}
+
+		let conflicts = self.conflicts(self.selection);
+		if conflicts.is_empty() {
+			Line::default()
+		} else {
+			Line::from(Span::styled(
+				strings::key_bindings_conflicts(&conflicts),
+				self.theme.text_danger(),
+			))
+		}
+	}
+}
+
+impl DrawableComponent for KeyBindingsPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			const SIZE: (u16, u16) = (65, 24);
+			let scroll_threshold = SIZE.1 / 3;
+			let scroll = u16::try_from(self.selection)
+				.unwrap_or(u16::MAX)
+				.saturating_sub(scroll_threshold);
+
+			let area =
+				ui::centered_rect_absolute(SIZE.0, SIZE.1, area);
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Block::default()
+					.borders(Borders::ALL)
+					.title(Span::styled(
+						strings::POPUP_TITLE_KEY_BINDINGS,
+						self.theme.title(true),
+					))
+					.border_style(self.theme.block(true)),
+				area,
+			);
+
+			let chunks = Layout::default()
+				.vertical_margin(1)
+				.horizontal_margin(1)
+				.direction(Direction::Vertical)
+				.constraints([
+					Constraint::Min(1),
+					Constraint::Length(1),
+				])
+				.split(area);
+
+			f.render_widget(
+				Paragraph::new(self.get_text()).scroll((scroll, 0)),
+				chunks[0],
+			);
+			f.render_widget(
+				Paragraph::new(self.get_status()),
+				chunks[1],
+			);
+
+			ui::draw_scrollbar(
+				f,
+				area,
+				&self.theme,
+				self.bindings.len(),
+				self.selection,
+				ui::Orientation::Vertical,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for KeyBindingsPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::key_binding_change(
+						&self.key_config,
+					),
+					!self.capturing,
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = &event {
+				if self.capturing {
+					self.capturing = false;
+					if key.code != KeyCode::Esc {
+						self.rebind(key);
+					}
+				} else if key_match(
+					key,
+					self.key_config.keys.exit_popup,
+				) {
+					self.hide();
+				} else if key_match(key, self.key_config.keys.move_up)
+				{
+					self.move_selection(true);
+				} else if key_match(
+					key,
+					self.key_config.keys.move_down,
+				) {
+					self.move_selection(false);
+				} else if key_match(key, self.key_config.keys.enter) {
+					self.capturing = true;
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
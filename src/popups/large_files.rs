@@ -0,0 +1,336 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	string_utils::pad_width,
+	strings, try_or_popup,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::sync::{self, LargeFile, RepoPathRef};
+use bytesize::ByteSize;
+use crossterm::event::{Event, MouseButton, MouseEventKind};
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+use std::path::Path;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LargeFileAction {
+	Skip,
+	Stage,
+	Lfs,
+}
+
+impl LargeFileAction {
+	const fn next(self) -> Self {
+		match self {
+			Self::Skip => Self::Stage,
+			Self::Stage => Self::Lfs,
+			Self::Lfs => Self::Skip,
+		}
+	}
+
+	const fn text(self) -> &'static str {
+		match self {
+			Self::Skip => "skip",
+			Self::Stage => "stage",
+			Self::Lfs => "lfs",
+		}
+	}
+}
+
+/// lists the files that staging left out for being large or tracked
+/// by git-lfs and stages them as chosen per file
+pub struct LargeFilesPopup {
+	repo: RepoPathRef,
+	files: Vec<(LargeFile, LargeFileAction)>,
+	selection: usize,
+	visible: bool,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl LargeFilesPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			repo: env.repo.clone(),
+			files: Vec::new(),
+			selection: 0,
+			visible: false,
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	/// large files are skipped and lfs files go through git-lfs
+	/// unless chosen otherwise
+	pub fn open(&mut self, files: Vec<LargeFile>) -> Result<()> {
+		self.files = files
+			.into_iter()
+			.map(|file| {
+				let action = if file.lfs {
+					LargeFileAction::Lfs
+				} else {
+					LargeFileAction::Skip
+				};
+				(file, action)
+			})
+			.collect();
+		self.selection = 0;
+
+		self.show()
+	}
+
+	fn apply(&mut self) -> Result<()> {
+		self.hide();
+
+		let files = std::mem::take(&mut self.files);
+		let repo = self.repo.borrow();
+
+		for (file, action) in &files {
+			match action {
+				LargeFileAction::Skip => (),
+				LargeFileAction::Stage => {
+					sync::stage_add_file(
+						&repo,
+						Path::new(&file.path),
+					)?;
+				}
+				LargeFileAction::Lfs => {
+					sync::stage_lfs(&repo, &file.path)?;
+				}
+			}
+		}
+
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+
+		Ok(())
+	}
+
+	fn toggle_action(&mut self) {
+		if let Some((_, action)) = self.files.get_mut(self.selection)
+		{
+			*action = action.next();
+		}
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let count = self.files.len();
+		if count == 0 {
+			return;
+		}
+
+		self.selection = if up {
+			self.selection.checked_sub(1).unwrap_or(count - 1)
+		} else {
+			(self.selection + 1) % count
+		};
+	}
+
+	fn get_text(&self) -> Vec<Line<'_>> {
+		let mut txt = vec![Line::from(Span::styled(
+			strings::LARGE_FILES_MSG,
+			self.theme.text(false, false),
+		))];
+
+		txt.extend(self.files.iter().enumerate().map(
+			|(idx, (file, action))| {
+				let selected = idx == self.selection;
+
+				Line::from(vec![
+					Span::styled(
+						format!("{} ", pad_width(action.text(), 6)),
+						self.theme.text(true, selected),
+					),
+					Span::styled(
+						format!(
+							"{} ",
+							pad_width(
+								&ByteSize::b(file.size).to_string(),
+								9
+							)
+						),
+						self.theme.text(!file.lfs, selected),
+					),
+					Span::styled(
+						file.path.as_str(),
+						self.theme.text(true, selected),
+					),
+				])
+			},
+		));
+
+		txt
+	}
+}
+
+impl DrawableComponent for LargeFilesPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			const MIN_WIDTH: u16 = 50;
+
+			let text = self.get_text();
+
+			let width = text
+				.iter()
+				.map(Line::width)
+				.max()
+				.and_then(|width| u16::try_from(width + 2).ok())
+				.unwrap_or_default()
+				.clamp(MIN_WIDTH, area.width);
+			let height = u16::try_from(text.len())
+				.unwrap_or_default()
+				.saturating_add(2)
+				.min(area.height);
+			let area =
+				ui::centered_rect_absolute(width, height, area);
+
+			// keep the selection (below the message) in view
+			let scroll = u16::try_from(self.selection + 1)
+				.unwrap_or_default()
+				.saturating_sub(height.saturating_sub(3));
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(text).scroll((scroll, 0)).block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							strings::LARGE_FILES_TITLE,
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for LargeFilesPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::large_files_skip(
+						&self.key_config,
+					),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::large_files_toggle(
+						&self.key_config,
+					),
+					!self.files.is_empty(),
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::large_files_confirm(
+						&self.key_config,
+					),
+					true,
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			match event {
+				Event::Key(key) => {
+					if key_match(key, self.key_config.keys.exit_popup)
+					{
+						self.hide();
+					} else if key_match(
+						key,
+						self.key_config.keys.move_up,
+					) {
+						self.move_selection(true);
+					} else if key_match(
+						key,
+						self.key_config.keys.move_down,
+					) {
+						self.move_selection(false);
+					} else if key_match(
+						key,
+						self.key_config.keys.log_mark_commit,
+					) {
+						self.toggle_action();
+					} else if key_match(
+						key,
+						self.key_config.keys.enter,
+					) {
+						try_or_popup!(
+							self,
+							"stage error:",
+							self.apply()
+						);
+					}
+				}
+				Event::Mouse(mouse)
+					if mouse.kind
+						== MouseEventKind::Down(
+							MouseButton::Right,
+						) =>
+				{
+					self.hide();
+				}
+				_ => (),
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
@@ -0,0 +1,287 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	queue::{Action, InternalEvent, NeedsUpdate, Queue},
+	strings, try_or_popup,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::sync::{self, diff::DiffLinePosition, RepoPathRef};
+use crossterm::event::{Event, MouseButton, MouseEventKind};
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// diff lines selected (with the mouse) to act on
+#[derive(Clone)]
+pub struct LineActionsContext {
+	pub path: String,
+	pub is_stage: bool,
+	/// untracked lines can only be staged
+	pub untracked: bool,
+	pub lines: Vec<DiffLinePosition>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LineAction {
+	Stage,
+	Unstage,
+	Discard,
+}
+
+impl LineAction {
+	const fn label(self) -> &'static str {
+		match self {
+			Self::Stage => "Stage lines",
+			Self::Unstage => "Unstage lines",
+			Self::Discard => "Discard lines",
+		}
+	}
+}
+
+pub struct LineActionsPopup {
+	repo: RepoPathRef,
+	context: Option<LineActionsContext>,
+	items: Vec<LineAction>,
+	selection: usize,
+	visible: bool,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl LineActionsPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			repo: env.repo.clone(),
+			context: None,
+			items: Vec::new(),
+			selection: 0,
+			visible: false,
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	///
+	pub fn open(
+		&mut self,
+		context: LineActionsContext,
+	) -> Result<()> {
+		if context.lines.is_empty() {
+			return Ok(());
+		}
+
+		self.items = if context.is_stage {
+			vec![LineAction::Unstage]
+		} else if context.untracked {
+			vec![LineAction::Stage]
+		} else {
+			vec![LineAction::Stage, LineAction::Discard]
+		};
+		self.context = Some(context);
+		self.selection = 0;
+
+		self.show()
+	}
+
+	fn apply_selected(&mut self) {
+		self.hide();
+
+		let (Some(context), Some(action)) =
+			(self.context.take(), self.items.get(self.selection))
+		else {
+			return;
+		};
+
+		match action {
+			LineAction::Stage | LineAction::Unstage => {
+				let repo = self.repo.borrow();
+
+				try_or_popup!(
+					self,
+					"(un)stage lines:",
+					if context.is_stage {
+						sync::unstage_lines(
+							&repo,
+							&context.path,
+							&context.lines,
+						)
+					} else {
+						sync::stage_lines(
+							&repo,
+							&context.path,
+							&context.lines,
+						)
+					}
+				);
+
+				self.queue
+					.push(InternalEvent::Update(NeedsUpdate::ALL));
+			}
+			LineAction::Discard => {
+				self.queue.push(InternalEvent::ConfirmAction(
+					Action::ResetLines(context.path, context.lines),
+				));
+			}
+		}
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let count = self.items.len();
+		if count == 0 {
+			return;
+		}
+
+		self.selection = if up {
+			self.selection.checked_sub(1).unwrap_or(count - 1)
+		} else {
+			(self.selection + 1) % count
+		};
+	}
+
+	fn get_text(&self) -> Vec<Line<'_>> {
+		self.items
+			.iter()
+			.enumerate()
+			.map(|(idx, item)| {
+				Line::from(Span::styled(
+					item.label(),
+					self.theme.text(true, idx == self.selection),
+				))
+			})
+			.collect()
+	}
+}
+
+impl DrawableComponent for LineActionsPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			const WIDTH: u16 = 30;
+			let height = u16::try_from(self.items.len())
+				.unwrap_or_default()
+				.saturating_add(2);
+			let area =
+				ui::centered_rect_absolute(WIDTH, height, area);
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(self.get_text()).block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							strings::POPUP_TITLE_LINE_ACTIONS,
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for LineActionsPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::line_actions_confirm(
+						&self.key_config,
+					),
+					true,
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			match event {
+				Event::Key(key) => {
+					if key_match(key, self.key_config.keys.exit_popup)
+					{
+						self.hide();
+					} else if key_match(
+						key,
+						self.key_config.keys.move_up,
+					) {
+						self.move_selection(true);
+					} else if key_match(
+						key,
+						self.key_config.keys.move_down,
+					) {
+						self.move_selection(false);
+					} else if key_match(
+						key,
+						self.key_config.keys.enter,
+					) {
+						self.apply_selected();
+					}
+				}
+				Event::Mouse(mouse)
+					if mouse.kind
+						== MouseEventKind::Down(
+							MouseButton::Right,
+						) =>
+				{
+					self.hide();
+				}
+				_ => (),
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
@@ -25,14 +25,74 @@ use ratatui::{
 	Frame,
 };
 
+#[derive(PartialEq, Eq)]
 enum Selection {
 	EnterText,
 	FuzzyOption,
 	CaseOption,
+	RegexOption,
 	SummarySearch,
 	MessageBodySearch,
 	FilenameSearch,
 	AuthorsSearch,
+	ContentSearch,
+}
+
+/// presets of search fields and options to cycle through
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SearchMode {
+	Message,
+	Regex,
+	Author,
+	Content,
+}
+
+impl SearchMode {
+	const ALL: [Self; 4] =
+		[Self::Message, Self::Regex, Self::Author, Self::Content];
+
+	const fn label(self) -> &'static str {
+		match self {
+			Self::Message => "message",
+			Self::Regex => "message regex",
+			Self::Author => "author",
+			Self::Content => "diff content",
+		}
+	}
+
+	const fn next(self) -> Self {
+		match self {
+			Self::Message => Self::Regex,
+			Self::Regex => Self::Author,
+			Self::Author => Self::Content,
+			Self::Content => Self::Message,
+		}
+	}
+
+	/// keeps the case sensitivity of `options`
+	fn options(
+		self,
+		options: SearchOptions,
+	) -> (SearchFields, SearchOptions) {
+		let options = options & SearchOptions::CASE_SENSITIVE;
+		let message = SearchFields::MESSAGE_SUMMARY
+			| SearchFields::MESSAGE_BODY;
+
+		match self {
+			Self::Message => (message, options),
+			Self::Regex => (message, options | SearchOptions::REGEX),
+			Self::Author => (SearchFields::AUTHORS, options),
+			Self::Content => (SearchFields::DIFFS, options),
+		}
+	}
+
+	fn current(
+		(fields, options): (SearchFields, SearchOptions),
+	) -> Option<Self> {
+		Self::ALL
+			.into_iter()
+			.find(|mode| mode.options(options) == (fields, options))
+	}
 }
 
 enum PopupMode {
@@ -113,6 +173,14 @@ impl LogSearchPopupPopup {
 		}
 	}
 
+	fn search_options(&self) -> LogFilterSearchOptions {
+		LogFilterSearchOptions {
+			fields: self.options.0,
+			options: self.options.1,
+			search_pattern: self.find_text.get_text().to_string(),
+		}
+	}
+
 	fn execute_confirm(&mut self) {
 		self.hide();
 
@@ -123,14 +191,7 @@ impl LogSearchPopupPopup {
 		match self.mode {
 			PopupMode::Search => {
 				self.queue.push(InternalEvent::CommitSearch(
-					LogFilterSearchOptions {
-						fields: self.options.0,
-						options: self.options.1,
-						search_pattern: self
-							.find_text
-							.get_text()
-							.to_string(),
-					},
+					self.search_options(),
 				));
 			}
 			PopupMode::JumpCommitSha => {
@@ -147,6 +208,7 @@ impl LogSearchPopupPopup {
 		match self.mode {
 			PopupMode::Search => {
 				!self.find_text.get_text().trim().is_empty()
+					&& self.search_options().regex().is_ok()
 			}
 			PopupMode::JumpCommitSha => self.jump_commit_id.is_some(),
 		}
@@ -165,106 +227,79 @@ impl LogSearchPopupPopup {
 	}
 
 	fn get_text_options(&self) -> Vec<Line<'_>> {
-		let x_summary =
-			if self.options.0.contains(SearchFields::MESSAGE_SUMMARY)
-			{
-				"X"
-			} else {
-				" "
-			};
+		let (fields, options) = self.options;
 
-		let x_body =
-			if self.options.0.contains(SearchFields::MESSAGE_BODY) {
-				"X"
-			} else {
-				" "
-			};
-
-		let x_files =
-			if self.options.0.contains(SearchFields::FILENAMES) {
-				"X"
-			} else {
-				" "
-			};
-
-		let x_authors =
-			if self.options.0.contains(SearchFields::AUTHORS) {
-				"X"
-			} else {
-				" "
-			};
+		[
+			(
+				options.contains(SearchOptions::FUZZY_SEARCH),
+				"fuzzy search",
+				Selection::FuzzyOption,
+			),
+			(
+				options.contains(SearchOptions::CASE_SENSITIVE),
+				"case sensitive",
+				Selection::CaseOption,
+			),
+			(
+				options.contains(SearchOptions::REGEX),
+				"regular expression",
+				Selection::RegexOption,
+			),
+			(
+				fields.contains(SearchFields::MESSAGE_SUMMARY),
+				"summary",
+				Selection::SummarySearch,
+			),
+			(
+				fields.contains(SearchFields::MESSAGE_BODY),
+				"message body",
+				Selection::MessageBodySearch,
+			),
+			(
+				fields.contains(SearchFields::FILENAMES),
+				"committed files",
+				Selection::FilenameSearch,
+			),
+			(
+				fields.contains(SearchFields::AUTHORS),
+				"authors",
+				Selection::AuthorsSearch,
+			),
+			(
+				fields.contains(SearchFields::DIFFS),
+				"diff content (added or removed lines)",
+				Selection::ContentSearch,
+			),
+		]
+		.into_iter()
+		.map(|(checked, label, selection)| {
+			Line::from(vec![Span::styled(
+				format!(
+					"[{}] {label}",
+					if checked { "X" } else { " " }
+				),
+				self.theme.text(self.selection == selection, false),
+			)])
+		})
+		.collect()
+	}
 
-		let x_opt_fuzzy =
-			if self.options.1.contains(SearchOptions::FUZZY_SEARCH) {
-				"X"
-			} else {
-				" "
-			};
+	fn get_text_mode(&self) -> Line<'_> {
+		Line::from(vec![
+			Span::styled("mode: ", self.theme.text(false, false)),
+			Span::styled(
+				SearchMode::current(self.options)
+					.map_or("custom", SearchMode::label),
+				self.theme.text(true, false),
+			),
+		])
+	}
 
-		let x_opt_casesensitive =
-			if self.options.1.contains(SearchOptions::CASE_SENSITIVE)
-			{
-				"X"
-			} else {
-				" "
-			};
+	fn cycle_mode(&mut self) {
+		let mode = SearchMode::current(self.options)
+			.map_or(SearchMode::Message, SearchMode::next);
 
-		vec![
-			Line::from(vec![Span::styled(
-				format!("[{x_opt_fuzzy}] fuzzy search"),
-				self.theme.text(
-					matches!(self.selection, Selection::FuzzyOption),
-					false,
-				),
-			)]),
-			Line::from(vec![Span::styled(
-				format!("[{x_opt_casesensitive}] case sensitive"),
-				self.theme.text(
-					matches!(self.selection, Selection::CaseOption),
-					false,
-				),
-			)]),
-			Line::from(vec![Span::styled(
-				format!("[{x_summary}] summary",),
-				self.theme.text(
-					matches!(
-						self.selection,
-						Selection::SummarySearch
-					),
-					false,
-				),
-			)]),
-			Line::from(vec![Span::styled(
-				format!("[{x_body}] message body",),
-				self.theme.text(
-					matches!(
-						self.selection,
-						Selection::MessageBodySearch
-					),
-					false,
-				),
-			)]),
-			Line::from(vec![Span::styled(
-				format!("[{x_files}] committed files",),
-				self.theme.text(
-					matches!(
-						self.selection,
-						Selection::FilenameSearch
-					),
-					false,
-				),
-			)]),
-			Line::from(vec![Span::styled(
-				format!("[{x_authors}] authors",),
-				self.theme.text(
-					matches!(
-						self.selection,
-						Selection::AuthorsSearch
-					),
-					false,
-				),
-			)]),
-		]
+		self.options = mode.options(self.options.1);
 	}
 
 	const fn option_selected(&self) -> bool {
@@ -276,10 +311,15 @@ impl LogSearchPopupPopup {
 			Selection::EnterText => (),
 			Selection::FuzzyOption => {
 				self.options.1.toggle(SearchOptions::FUZZY_SEARCH);
+				self.options.1.remove(SearchOptions::REGEX);
 			}
 			Selection::CaseOption => {
 				self.options.1.toggle(SearchOptions::CASE_SENSITIVE);
 			}
+			Selection::RegexOption => {
+				self.options.1.toggle(SearchOptions::REGEX);
+				self.options.1.remove(SearchOptions::FUZZY_SEARCH);
+			}
 			Selection::SummarySearch => {
 				self.options.0.toggle(SearchFields::MESSAGE_SUMMARY);
 
@@ -306,6 +346,13 @@ impl LogSearchPopupPopup {
 			Selection::AuthorsSearch => {
 				self.options.0.toggle(SearchFields::AUTHORS);
 
+				if self.options.0.is_empty() {
+					self.options.0.set(SearchFields::DIFFS, true);
+				}
+			}
+			Selection::ContentSearch => {
+				self.options.0.toggle(SearchFields::DIFFS);
+
 				if self.options.0.is_empty() {
 					self.options
 						.0
@@ -319,10 +366,11 @@ impl LogSearchPopupPopup {
 		if arg {
 			//up
 			self.selection = match self.selection {
-				Selection::EnterText => Selection::AuthorsSearch,
+				Selection::EnterText => Selection::ContentSearch,
 				Selection::FuzzyOption => Selection::EnterText,
 				Selection::CaseOption => Selection::FuzzyOption,
-				Selection::SummarySearch => Selection::CaseOption,
+				Selection::RegexOption => Selection::CaseOption,
+				Selection::SummarySearch => Selection::RegexOption,
 				Selection::MessageBodySearch => {
 					Selection::SummarySearch
 				}
@@ -330,12 +378,14 @@ impl LogSearchPopupPopup {
 					Selection::MessageBodySearch
 				}
 				Selection::AuthorsSearch => Selection::FilenameSearch,
+				Selection::ContentSearch => Selection::AuthorsSearch,
 			};
 		} else {
 			self.selection = match self.selection {
 				Selection::EnterText => Selection::FuzzyOption,
 				Selection::FuzzyOption => Selection::CaseOption,
-				Selection::CaseOption => Selection::SummarySearch,
+				Selection::CaseOption => Selection::RegexOption,
+				Selection::RegexOption => Selection::SummarySearch,
 				Selection::SummarySearch => {
 					Selection::MessageBodySearch
 				}
@@ -343,7 +393,8 @@ impl LogSearchPopupPopup {
 					Selection::FilenameSearch
 				}
 				Selection::FilenameSearch => Selection::AuthorsSearch,
-				Selection::AuthorsSearch => Selection::EnterText,
+				Selection::AuthorsSearch => Selection::ContentSearch,
+				Selection::ContentSearch => Selection::EnterText,
 			};
 		}
 
@@ -356,14 +407,21 @@ impl LogSearchPopupPopup {
 		f: &mut Frame,
 		area: Rect,
 	) -> Result<()> {
-		const SIZE: (u16, u16) = (60, 10);
+		const SIZE: (u16, u16) = (60, 13);
 		let area = ui::centered_rect_absolute(SIZE.0, SIZE.1, area);
 
+		let mut block_style = self.theme.title(true);
+		if !self.is_valid()
+			&& !self.find_text.get_text().trim().is_empty()
+		{
+			block_style = block_style.patch(self.theme.text_danger());
+		}
+
 		f.render_widget(Clear, area);
 		f.render_widget(
 			Block::default()
 				.borders(Borders::all())
-				.style(self.theme.title(true))
+				.style(block_style)
 				.title(Span::styled(
 					strings::POPUP_TITLE_LOG_SEARCH,
 					self.theme.title(true),
@@ -374,8 +432,12 @@ impl LogSearchPopupPopup {
 		let chunks = Layout::default()
 			.direction(Direction::Vertical)
 			.constraints(
-				[Constraint::Length(1), Constraint::Percentage(100)]
-					.as_ref(),
+				[
+					Constraint::Length(1),
+					Constraint::Length(1),
+					Constraint::Percentage(100),
+				]
+				.as_ref(),
 			)
 			.split(area.inner(Margin {
 				horizontal: 1,
@@ -384,6 +446,11 @@ impl LogSearchPopupPopup {
 
 		self.find_text.draw(f, chunks[0])?;
 
+		f.render_widget(
+			Paragraph::new(self.get_text_mode()),
+			chunks[1],
+		);
+
 		f.render_widget(
 			Paragraph::new(self.get_text_options())
 				.block(
@@ -392,7 +459,7 @@ impl LogSearchPopupPopup {
 						.border_style(self.theme.block(true)),
 				)
 				.alignment(Alignment::Left),
-			chunks[1],
+			chunks[2],
 		);
 
 		Ok(())
@@ -477,6 +544,9 @@ impl LogSearchPopupPopup {
 				self.execute_confirm();
 			} else if key_match(key, self.key_config.keys.popup_up) {
 				self.move_selection(true);
+			} else if key_match(key, self.key_config.keys.tab_toggle)
+			{
+				self.cycle_mode();
 			} else if key_match(
 				key,
 				self.key_config.keys.find_commit_sha,
@@ -577,6 +647,16 @@ impl Component for LogSearchPopupPopup {
 					)
 					.order(1),
 				);
+				out.push(
+					CommandInfo::new(
+						strings::commands::log_search_cycle_mode(
+							&self.key_config,
+						),
+						true,
+						true,
+					)
+					.order(1),
+				);
 				out.push(
 					CommandInfo::new(
 						strings::commands::find_commit_sha(
@@ -0,0 +1,127 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+	layout::Rect,
+	widgets::{Block, Clear, Paragraph},
+	Frame,
+};
+
+/// asks how often to replay the recorded macro
+pub struct MacroReplayPopup {
+	visible: bool,
+	input: String,
+	key_config: SharedKeyConfig,
+	queue: Queue,
+	theme: SharedTheme,
+}
+
+impl MacroReplayPopup {
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			visible: false,
+			input: String::new(),
+			key_config: env.key_config.clone(),
+			queue: env.queue.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	pub fn open(&mut self) {
+		self.input.clear();
+		self.visible = true;
+	}
+}
+
+impl Component for MacroReplayPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::macro_replay_confirm(
+						&self.key_config,
+					),
+					true,
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = event {
+				if key_match(key, self.key_config.keys.exit_popup) {
+					self.visible = false;
+				} else if key_match(key, self.key_config.keys.enter) {
+					self.visible = false;
+					let times = self.input.parse().unwrap_or(1);
+					self.queue
+						.push(InternalEvent::ReplayMacro(times));
+				} else if let KeyCode::Char(c) = key.code {
+					if c.is_ascii_digit() && self.input.len() < 4 {
+						self.input.push(c);
+					}
+				} else if key.code == KeyCode::Backspace {
+					self.input.pop();
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+}
+
+impl DrawableComponent for MacroReplayPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			let input = Paragraph::new(if self.input.is_empty() {
+				"1"
+			} else {
+				self.input.as_str()
+			})
+			.style(self.theme.text(!self.input.is_empty(), false))
+			.block(
+				Block::bordered()
+					.title(strings::POPUP_TITLE_MACRO_REPLAY),
+			);
+
+			let input_area = ui::centered_rect_absolute(20, 3, area);
+			f.render_widget(Clear, input_area);
+			f.render_widget(input, input_area);
+		}
+
+		Ok(())
+	}
+}
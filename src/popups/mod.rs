@@ -1,66 +1,117 @@
+mod add_submodule;
+mod add_worktree;
+mod amend_files;
+mod apply_patch;
+mod bisect;
 mod blame_file;
+mod branch_cleanup;
 mod branchlist;
+mod checkout_conflicts;
 mod checkout_option;
 mod commit;
+mod commit_checks;
+mod commit_picker;
 mod compare_commits;
 mod confirm;
+mod copy;
 mod create_branch;
 mod create_remote;
 mod externaleditor;
 mod fetch;
 mod file_revlog;
+mod flow;
 mod fuzzy_find;
 mod goto_line;
 mod help;
+mod hook_output;
+mod ignore_pattern;
 mod inspect_commit;
+mod key_bindings;
+mod large_files;
+mod line_actions;
 mod log_search;
+mod macro_replay;
 mod msg;
 mod options;
+mod patch_series;
 mod pull;
 mod push;
 mod push_tags;
+mod rebase_interactive;
 mod remotelist;
 mod rename_branch;
 mod rename_remote;
 mod reset;
 mod revision_files;
+mod stack;
 mod stashmsg;
 mod submodules;
 mod tag_commit;
 mod taglist;
+mod tour;
+mod trash;
+mod tree_search;
 mod update_remote_url;
+mod worktrees;
 
+pub use add_submodule::AddSubmodulePopup;
+pub use add_worktree::AddWorktreePopup;
+pub use amend_files::AmendFilesPopup;
+pub use apply_patch::ApplyPatchPopup;
+pub use bisect::BisectPopup;
 pub use blame_file::{BlameFileOpen, BlameFilePopup};
+pub use branch_cleanup::BranchCleanupPopup;
 pub use branchlist::BranchListPopup;
+pub use checkout_conflicts::CheckoutConflictsPopup;
 pub use checkout_option::CheckoutOptionPopup;
 pub use commit::CommitPopup;
+pub use commit_checks::CommitChecksPopup;
+pub use commit_picker::{
+	goto_relative, goto_special_ref, CommitJumpTarget,
+	CommitPickerContext, CommitPickerPopup,
+};
 pub use compare_commits::CompareCommitsPopup;
 pub use confirm::ConfirmPopup;
+pub use copy::{CopyContext, CopyPopup};
 pub use create_branch::CreateBranchPopup;
 pub use create_remote::CreateRemotePopup;
 pub use externaleditor::ExternalEditorPopup;
 pub use fetch::FetchPopup;
 pub use file_revlog::{FileRevOpen, FileRevlogPopup};
+pub use flow::FlowPopup;
 pub use fuzzy_find::FuzzyFindPopup;
 pub use goto_line::GotoLinePopup;
 pub use help::HelpPopup;
+pub use hook_output::HookOutputPopup;
+pub use ignore_pattern::{IgnorePatternContext, IgnorePatternPopup};
 pub use inspect_commit::{InspectCommitOpen, InspectCommitPopup};
+pub use key_bindings::KeyBindingsPopup;
+pub use large_files::LargeFilesPopup;
+pub use line_actions::{LineActionsContext, LineActionsPopup};
 pub use log_search::LogSearchPopupPopup;
+pub use macro_replay::MacroReplayPopup;
 pub use msg::MsgPopup;
 pub use options::{AppOption, OptionsPopup};
+pub use patch_series::{PatchSeriesContext, PatchSeriesPopup};
 pub use pull::PullPopup;
 pub use push::PushPopup;
 pub use push_tags::PushTagsPopup;
+pub use rebase_interactive::RebaseInteractivePopup;
 pub use remotelist::RemoteListPopup;
 pub use rename_branch::RenameBranchPopup;
 pub use rename_remote::RenameRemotePopup;
 pub use reset::ResetPopup;
 pub use revision_files::{FileTreeOpen, RevisionFilesPopup};
+pub use stack::StackPopup;
 pub use stashmsg::StashMsgPopup;
 pub use submodules::SubmodulesListPopup;
 pub use tag_commit::TagCommitPopup;
 pub use taglist::TagListPopup;
+pub use tour::{TourPane, TourPopup};
+pub use trash::TrashPopup;
+pub use tree_search::{TreeSearchOpen, TreeSearchPopup};
 pub use update_remote_url::UpdateRemoteUrlPopup;
+pub use worktrees::WorktreesPopup;
 
 use crate::ui::style::Theme;
 use ratatui::{
@@ -11,7 +11,10 @@ use crate::{
 	ui::{self, style::SharedTheme},
 };
 use anyhow::Result;
-use asyncgit::sync::ShowUntrackedFilesConfig;
+use asyncgit::sync::{
+	self, RepoPathRef, ShowUntrackedFilesConfig, HOOK_COMMIT_MSG,
+	HOOK_PRE_COMMIT, HOOK_PRE_PUSH,
+};
 use crossterm::event::Event;
 use ratatui::{
 	layout::{Alignment, Rect},
@@ -24,9 +27,34 @@ use ratatui::{
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum AppOption {
 	StatusShowUntracked,
+	StatusDiscardToTrash,
+	StatusAbsorbAutosquash,
 	DiffIgnoreWhitespaces,
 	DiffContextLines,
 	DiffInterhunkLines,
+	BranchAheadBehind,
+	BranchDate,
+	BranchAuthor,
+	BranchUpstream,
+	BranchSort,
+	BranchHideRemote,
+	BranchAutostash,
+	LogDecorations,
+	LogRemoteBranches,
+	LogTags,
+	WatcherStrategy,
+	WatcherDebounce,
+	WatcherTick,
+	WatcherFocus,
+	ConfirmDiscard,
+	ConfirmForcePush,
+	ConfirmBranchDelete,
+	ConfirmResetHard,
+	ConfirmStashDrop,
+	HookPreCommit,
+	HookCommitMsg,
+	HookPrePush,
+	KeyBindings,
 }
 
 pub struct OptionsPopup {
@@ -36,6 +64,7 @@ pub struct OptionsPopup {
 	key_config: SharedKeyConfig,
 	options: SharedOptions,
 	theme: SharedTheme,
+	repo: RepoPathRef,
 }
 
 impl OptionsPopup {
@@ -48,6 +77,7 @@ impl OptionsPopup {
 			key_config: env.key_config.clone(),
 			options: env.options.clone(),
 			theme: env.theme.clone(),
+			repo: env.repo.clone(),
 		}
 	}
 
@@ -74,6 +104,28 @@ impl OptionsPopup {
 			},
 			self.is_select(AppOption::StatusShowUntracked),
 		);
+		self.add_entry(
+			txt,
+			width,
+			"Discard to trash",
+			if self.options.borrow().discard_to_trash() {
+				"on"
+			} else {
+				"off"
+			},
+			self.is_select(AppOption::StatusDiscardToTrash),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Absorb autosquash",
+			if self.options.borrow().absorb_autosquash() {
+				"on"
+			} else {
+				"off"
+			},
+			self.is_select(AppOption::StatusAbsorbAutosquash),
+		);
 		Self::add_header(txt, "");
 
 		let diff = self.options.borrow().diff_options();
@@ -99,8 +151,211 @@ impl OptionsPopup {
 			&diff.interhunk_lines.to_string(),
 			self.is_select(AppOption::DiffInterhunkLines),
 		);
+		Self::add_header(txt, "");
+
+		self.add_branches(txt, width);
+		self.add_log(txt, width);
+		self.add_watcher(txt, width);
+		self.add_confirm(txt, width);
+		self.add_hooks(txt, width);
+
+		Self::add_header(txt, "Keys");
+		self.add_entry(
+			txt,
+			width,
+			"Key bindings",
+			"edit",
+			self.is_select(AppOption::KeyBindings),
+		);
+	}
+
+	fn add_branches(&self, txt: &mut Vec<Line>, width: u16) {
+		let branches = self.options.borrow().branch_list();
+		let on_off = |on: bool| if on { "on" } else { "off" };
+		Self::add_header(txt, "Branches");
+		self.add_entry(
+			txt,
+			width,
+			"Ahead/behind",
+			on_off(branches.ahead_behind),
+			self.is_select(AppOption::BranchAheadBehind),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Last commit date",
+			on_off(branches.date),
+			self.is_select(AppOption::BranchDate),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Author",
+			on_off(branches.author),
+			self.is_select(AppOption::BranchAuthor),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Upstream",
+			on_off(branches.upstream),
+			self.is_select(AppOption::BranchUpstream),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Sort by",
+			branches.sort.label(),
+			self.is_select(AppOption::BranchSort),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Hide remote",
+			branches.hide_remote.as_deref().unwrap_or("-"),
+			self.is_select(AppOption::BranchHideRemote),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Stash on switch",
+			on_off(self.options.borrow().checkout_autostash()),
+			self.is_select(AppOption::BranchAutostash),
+		);
+		Self::add_header(txt, "");
+	}
+
+	fn add_log(&self, txt: &mut Vec<Line>, width: u16) {
+		let on_off = |on: bool| if on { "on" } else { "off" };
+		let log = self.options.borrow().log_decorations();
+		Self::add_header(txt, "Log");
+		self.add_entry(
+			txt,
+			width,
+			"Ref decorations",
+			on_off(log.refs),
+			self.is_select(AppOption::LogDecorations),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Remote branches",
+			on_off(log.remote_branches),
+			self.is_select(AppOption::LogRemoteBranches),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Tags",
+			on_off(log.tags),
+			self.is_select(AppOption::LogTags),
+		);
+		Self::add_header(txt, "");
+	}
+
+	fn add_watcher(&self, txt: &mut Vec<Line>, width: u16) {
+		let watcher = self.options.borrow().watcher();
+		Self::add_header(txt, "Watcher");
+		self.add_entry(
+			txt,
+			width,
+			"Strategy",
+			watcher.strategy.label(),
+			self.is_select(AppOption::WatcherStrategy),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Debounce",
+			&format!("{} ms", watcher.debounce_ms),
+			self.is_select(AppOption::WatcherDebounce),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Tick interval",
+			&format!("{} s", watcher.tick_secs),
+			self.is_select(AppOption::WatcherTick),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Refresh on focus",
+			if watcher.refresh_on_focus {
+				"on"
+			} else {
+				"off"
+			},
+			self.is_select(AppOption::WatcherFocus),
+		);
+		Self::add_header(txt, "");
+	}
+
+	fn add_confirm(&self, txt: &mut Vec<Line>, width: u16) {
+		let on_off = |on: bool| if on { "on" } else { "off" };
+		let confirm = self.options.borrow().confirm();
+		Self::add_header(txt, "Confirm");
+		self.add_entry(
+			txt,
+			width,
+			"Discard",
+			on_off(confirm.discard),
+			self.is_select(AppOption::ConfirmDiscard),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Force push",
+			on_off(confirm.force_push),
+			self.is_select(AppOption::ConfirmForcePush),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Branch delete",
+			on_off(confirm.branch_delete),
+			self.is_select(AppOption::ConfirmBranchDelete),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Reset hard",
+			on_off(confirm.reset_hard),
+			self.is_select(AppOption::ConfirmResetHard),
+		);
+		self.add_entry(
+			txt,
+			width,
+			"Stash drop",
+			on_off(confirm.stash_drop),
+			self.is_select(AppOption::ConfirmStashDrop),
+		);
+		Self::add_header(txt, "");
 	}
 
+	fn add_hooks(&self, txt: &mut Vec<Line>, width: u16) {
+		Self::add_header(txt, "Hooks");
+		for (name, hook, option) in Self::HOOKS {
+			let policy = sync::hook_policy(&self.repo.borrow(), hook)
+				.unwrap_or_default();
+			self.add_entry(
+				txt,
+				width,
+				name,
+				policy.as_str(),
+				self.is_select(option),
+			);
+		}
+		Self::add_header(txt, "");
+	}
+
+	/// the hooks that can stop an operation
+	const HOOKS: [(&'static str, &'static str, AppOption); 3] = [
+		("Pre-commit", HOOK_PRE_COMMIT, AppOption::HookPreCommit),
+		("Commit msg", HOOK_COMMIT_MSG, AppOption::HookCommitMsg),
+		("Pre-push", HOOK_PRE_PUSH, AppOption::HookPrePush),
+	];
+
 	fn is_select(&self, kind: AppOption) -> bool {
 		self.selection == kind
 	}
@@ -134,25 +389,81 @@ impl OptionsPopup {
 		]));
 	}
 
+	#[allow(clippy::too_many_lines)]
 	fn move_selection(&mut self, up: bool) {
 		if up {
 			self.selection = match self.selection {
 				AppOption::StatusShowUntracked => {
-					AppOption::DiffInterhunkLines
+					AppOption::KeyBindings
 				}
-				AppOption::DiffIgnoreWhitespaces => {
+				AppOption::StatusDiscardToTrash => {
 					AppOption::StatusShowUntracked
 				}
+				AppOption::StatusAbsorbAutosquash => {
+					AppOption::StatusDiscardToTrash
+				}
+				AppOption::DiffIgnoreWhitespaces => {
+					AppOption::StatusAbsorbAutosquash
+				}
 				AppOption::DiffContextLines => {
 					AppOption::DiffIgnoreWhitespaces
 				}
 				AppOption::DiffInterhunkLines => {
 					AppOption::DiffContextLines
 				}
+				AppOption::BranchAheadBehind => {
+					AppOption::DiffInterhunkLines
+				}
+				AppOption::BranchDate => AppOption::BranchAheadBehind,
+				AppOption::BranchAuthor => AppOption::BranchDate,
+				AppOption::BranchUpstream => AppOption::BranchAuthor,
+				AppOption::BranchSort => AppOption::BranchUpstream,
+				AppOption::BranchHideRemote => AppOption::BranchSort,
+				AppOption::BranchAutostash => {
+					AppOption::BranchHideRemote
+				}
+				AppOption::LogDecorations => {
+					AppOption::BranchAutostash
+				}
+				AppOption::LogRemoteBranches => {
+					AppOption::LogDecorations
+				}
+				AppOption::LogTags => AppOption::LogRemoteBranches,
+				AppOption::WatcherStrategy => AppOption::LogTags,
+				AppOption::WatcherDebounce => {
+					AppOption::WatcherStrategy
+				}
+				AppOption::WatcherTick => AppOption::WatcherDebounce,
+				AppOption::WatcherFocus => AppOption::WatcherTick,
+				AppOption::ConfirmDiscard => AppOption::WatcherFocus,
+				AppOption::ConfirmForcePush => {
+					AppOption::ConfirmDiscard
+				}
+				AppOption::ConfirmBranchDelete => {
+					AppOption::ConfirmForcePush
+				}
+				AppOption::ConfirmResetHard => {
+					AppOption::ConfirmBranchDelete
+				}
+				AppOption::ConfirmStashDrop => {
+					AppOption::ConfirmResetHard
+				}
+				AppOption::HookPreCommit => {
+					AppOption::ConfirmStashDrop
+				}
+				AppOption::HookCommitMsg => AppOption::HookPreCommit,
+				AppOption::HookPrePush => AppOption::HookCommitMsg,
+				AppOption::KeyBindings => AppOption::HookPrePush,
 			};
 		} else {
 			self.selection = match self.selection {
 				AppOption::StatusShowUntracked => {
+					AppOption::StatusDiscardToTrash
+				}
+				AppOption::StatusDiscardToTrash => {
+					AppOption::StatusAbsorbAutosquash
+				}
+				AppOption::StatusAbsorbAutosquash => {
 					AppOption::DiffIgnoreWhitespaces
 				}
 				AppOption::DiffIgnoreWhitespaces => {
@@ -162,13 +473,186 @@ impl OptionsPopup {
 					AppOption::DiffInterhunkLines
 				}
 				AppOption::DiffInterhunkLines => {
+					AppOption::BranchAheadBehind
+				}
+				AppOption::BranchAheadBehind => AppOption::BranchDate,
+				AppOption::BranchDate => AppOption::BranchAuthor,
+				AppOption::BranchAuthor => AppOption::BranchUpstream,
+				AppOption::BranchUpstream => AppOption::BranchSort,
+				AppOption::BranchSort => AppOption::BranchHideRemote,
+				AppOption::BranchHideRemote => {
+					AppOption::BranchAutostash
+				}
+				AppOption::BranchAutostash => {
+					AppOption::LogDecorations
+				}
+				AppOption::LogDecorations => {
+					AppOption::LogRemoteBranches
+				}
+				AppOption::LogRemoteBranches => AppOption::LogTags,
+				AppOption::LogTags => AppOption::WatcherStrategy,
+				AppOption::WatcherStrategy => {
+					AppOption::WatcherDebounce
+				}
+				AppOption::WatcherDebounce => AppOption::WatcherTick,
+				AppOption::WatcherTick => AppOption::WatcherFocus,
+				AppOption::WatcherFocus => AppOption::ConfirmDiscard,
+				AppOption::ConfirmDiscard => {
+					AppOption::ConfirmForcePush
+				}
+				AppOption::ConfirmForcePush => {
+					AppOption::ConfirmBranchDelete
+				}
+				AppOption::ConfirmBranchDelete => {
+					AppOption::ConfirmResetHard
+				}
+				AppOption::ConfirmResetHard => {
+					AppOption::ConfirmStashDrop
+				}
+				AppOption::ConfirmStashDrop => {
+					AppOption::HookPreCommit
+				}
+				AppOption::HookPreCommit => AppOption::HookCommitMsg,
+				AppOption::HookCommitMsg => AppOption::HookPrePush,
+				AppOption::HookPrePush => AppOption::KeyBindings,
+				AppOption::KeyBindings => {
 					AppOption::StatusShowUntracked
 				}
 			};
 		}
 	}
 
+	fn switch_branch_option(&self, right: bool) {
+		let mut branches = self.options.borrow().branch_list();
+
+		match self.selection {
+			AppOption::BranchAheadBehind => {
+				branches.ahead_behind = !branches.ahead_behind;
+			}
+			AppOption::BranchDate => branches.date = !branches.date,
+			AppOption::BranchAuthor => {
+				branches.author = !branches.author;
+			}
+			AppOption::BranchUpstream => {
+				branches.upstream = !branches.upstream;
+			}
+			AppOption::BranchSort => {
+				branches.sort = if right {
+					branches.sort.next()
+				} else {
+					branches.sort.prev()
+				};
+			}
+			// the pattern is set from the branch list filter
+			AppOption::BranchHideRemote => {
+				branches.hide_remote = None;
+			}
+			_ => return,
+		}
+
+		self.options.borrow_mut().set_branch_list(branches);
+	}
+
+	fn switch_log_option(&self) {
+		let mut log = self.options.borrow().log_decorations();
+
+		match self.selection {
+			AppOption::LogDecorations => log.refs = !log.refs,
+			AppOption::LogRemoteBranches => {
+				log.remote_branches = !log.remote_branches;
+			}
+			AppOption::LogTags => log.tags = !log.tags,
+			_ => return,
+		}
+
+		self.options.borrow_mut().set_log_decorations(log);
+	}
+
+	fn switch_watcher_option(&self, right: bool) {
+		let mut watcher = self.options.borrow().watcher();
+
+		match self.selection {
+			AppOption::WatcherStrategy => {
+				watcher.strategy = if right {
+					watcher.strategy.next()
+				} else {
+					watcher.strategy.prev()
+				};
+			}
+			AppOption::WatcherDebounce => {
+				watcher = watcher.debounce_change(right);
+			}
+			AppOption::WatcherTick => {
+				watcher = watcher.tick_change(right);
+			}
+			AppOption::WatcherFocus => {
+				watcher.refresh_on_focus = !watcher.refresh_on_focus;
+			}
+			_ => return,
+		}
+
+		self.options.borrow_mut().set_watcher(watcher);
+	}
+
+	fn switch_confirm_option(&self) {
+		let mut confirm = self.options.borrow().confirm();
+
+		match self.selection {
+			AppOption::ConfirmDiscard => {
+				confirm.discard = !confirm.discard;
+			}
+			AppOption::ConfirmForcePush => {
+				confirm.force_push = !confirm.force_push;
+			}
+			AppOption::ConfirmBranchDelete => {
+				confirm.branch_delete = !confirm.branch_delete;
+			}
+			AppOption::ConfirmResetHard => {
+				confirm.reset_hard = !confirm.reset_hard;
+			}
+			AppOption::ConfirmStashDrop => {
+				confirm.stash_drop = !confirm.stash_drop;
+			}
+			_ => return,
+		}
+
+		self.options.borrow_mut().set_confirm(confirm);
+	}
+
+	fn switch_hook_option(&self, right: bool) {
+		let Some((_, hook, _)) = Self::HOOKS
+			.into_iter()
+			.find(|(_, _, option)| *option == self.selection)
+		else {
+			return;
+		};
+
+		let repo = self.repo.borrow();
+		let policy =
+			sync::hook_policy(&repo, hook).unwrap_or_default();
+		let policy =
+			if right { policy.next() } else { policy.prev() };
+
+		if let Err(e) = sync::set_hook_policy(&repo, hook, policy) {
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"failed to set the {hook} hook policy:\n{e}"
+			)));
+		}
+	}
+
+	#[allow(clippy::too_many_lines)]
 	fn switch_option(&self, right: bool) {
+		if self.selection == AppOption::KeyBindings {
+			self.queue.push(InternalEvent::OpenKeyBindingsPopup);
+			return;
+		}
+
+		self.switch_branch_option(right);
+		self.switch_log_option();
+		self.switch_watcher_option(right);
+		self.switch_confirm_option();
+		self.switch_hook_option(right);
+
 		if right {
 			match self.selection {
 				AppOption::StatusShowUntracked => {
@@ -192,6 +676,21 @@ impl OptionsPopup {
 						.borrow_mut()
 						.set_status_show_untracked(untracked);
 				}
+				AppOption::StatusDiscardToTrash => {
+					self.options
+						.borrow_mut()
+						.toggle_discard_to_trash();
+				}
+				AppOption::StatusAbsorbAutosquash => {
+					self.options
+						.borrow_mut()
+						.toggle_absorb_autosquash();
+				}
+				AppOption::BranchAutostash => {
+					self.options
+						.borrow_mut()
+						.toggle_checkout_autostash();
+				}
 				AppOption::DiffIgnoreWhitespaces => {
 					self.options
 						.borrow_mut()
@@ -207,6 +706,28 @@ impl OptionsPopup {
 						.borrow_mut()
 						.diff_hunk_lines_change(true);
 				}
+				AppOption::BranchAheadBehind
+				| AppOption::BranchDate
+				| AppOption::BranchAuthor
+				| AppOption::BranchUpstream
+				| AppOption::BranchSort
+				| AppOption::BranchHideRemote
+				| AppOption::LogDecorations
+				| AppOption::LogRemoteBranches
+				| AppOption::LogTags
+				| AppOption::WatcherStrategy
+				| AppOption::WatcherDebounce
+				| AppOption::WatcherTick
+				| AppOption::WatcherFocus
+				| AppOption::ConfirmDiscard
+				| AppOption::ConfirmForcePush
+				| AppOption::ConfirmBranchDelete
+				| AppOption::ConfirmResetHard
+				| AppOption::ConfirmStashDrop
+				| AppOption::HookPreCommit
+				| AppOption::HookCommitMsg
+				| AppOption::HookPrePush
+				| AppOption::KeyBindings => (),
 			}
 		} else {
 			match self.selection {
@@ -231,6 +752,21 @@ impl OptionsPopup {
 						.borrow_mut()
 						.set_status_show_untracked(untracked);
 				}
+				AppOption::StatusDiscardToTrash => {
+					self.options
+						.borrow_mut()
+						.toggle_discard_to_trash();
+				}
+				AppOption::StatusAbsorbAutosquash => {
+					self.options
+						.borrow_mut()
+						.toggle_absorb_autosquash();
+				}
+				AppOption::BranchAutostash => {
+					self.options
+						.borrow_mut()
+						.toggle_checkout_autostash();
+				}
 				AppOption::DiffIgnoreWhitespaces => {
 					self.options
 						.borrow_mut()
@@ -246,6 +782,28 @@ impl OptionsPopup {
 						.borrow_mut()
 						.diff_hunk_lines_change(false);
 				}
+				AppOption::BranchAheadBehind
+				| AppOption::BranchDate
+				| AppOption::BranchAuthor
+				| AppOption::BranchUpstream
+				| AppOption::BranchSort
+				| AppOption::BranchHideRemote
+				| AppOption::LogDecorations
+				| AppOption::LogRemoteBranches
+				| AppOption::LogTags
+				| AppOption::WatcherStrategy
+				| AppOption::WatcherDebounce
+				| AppOption::WatcherTick
+				| AppOption::WatcherFocus
+				| AppOption::ConfirmDiscard
+				| AppOption::ConfirmForcePush
+				| AppOption::ConfirmBranchDelete
+				| AppOption::ConfirmResetHard
+				| AppOption::ConfirmStashDrop
+				| AppOption::HookPreCommit
+				| AppOption::HookCommitMsg
+				| AppOption::HookPrePush
+				| AppOption::KeyBindings => (),
 			}
 		}
 
@@ -257,7 +815,7 @@ impl OptionsPopup {
 impl DrawableComponent for OptionsPopup {
 	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
 		if self.is_visible() {
-			const SIZE: (u16, u16) = (50, 10);
+			const SIZE: (u16, u16) = (50, 45);
 			let area =
 				ui::centered_rect_absolute(SIZE.0, SIZE.1, area);
 
@@ -340,6 +898,10 @@ impl Component for OptionsPopup {
 					self.key_config.keys.move_left,
 				) {
 					self.switch_option(false);
+				} else if key_match(key, self.key_config.keys.enter)
+					&& self.selection == AppOption::KeyBindings
+				{
+					self.switch_option(true);
 				}
 			}
 
@@ -0,0 +1,310 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::{bail, Result};
+use asyncgit::sync::{self, CommitId, RepoPathRef};
+use crossterm::event::{Event, MouseButton, MouseEventKind};
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+use std::path::Path;
+
+/// commits (oldest first) to export
+#[derive(Clone)]
+pub struct PatchSeriesContext {
+	pub commits: Vec<CommitId>,
+	/// commits are a range without gaps in the log
+	pub contiguous: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PatchAction {
+	FormatPatch,
+	CopyDiff,
+}
+
+impl PatchAction {
+	const fn label(self) -> &'static str {
+		match self {
+			Self::FormatPatch => "Write numbered patch files",
+			Self::CopyDiff => "Copy combined diff",
+		}
+	}
+}
+
+pub struct PatchSeriesPopup {
+	repo: RepoPathRef,
+	context: Option<PatchSeriesContext>,
+	items: Vec<PatchAction>,
+	selection: usize,
+	visible: bool,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl PatchSeriesPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			repo: env.repo.clone(),
+			context: None,
+			items: vec![
+				PatchAction::FormatPatch,
+				PatchAction::CopyDiff,
+			],
+			selection: 0,
+			visible: false,
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	///
+	pub fn open(
+		&mut self,
+		context: PatchSeriesContext,
+	) -> Result<()> {
+		if context.commits.is_empty() {
+			return Ok(());
+		}
+
+		self.context = Some(context);
+		self.selection = 0;
+
+		self.show()
+	}
+
+	fn apply_selected(&mut self) {
+		self.hide();
+
+		let (Some(context), Some(action)) =
+			(self.context.take(), self.items.get(self.selection))
+		else {
+			return;
+		};
+
+		let result = match action {
+			PatchAction::FormatPatch => self.format_patches(&context),
+			PatchAction::CopyDiff => self.copy_diff(&context),
+		};
+
+		self.queue.push(match result {
+			Ok(msg) => InternalEvent::ShowInfoMsg(msg),
+			Err(e) => InternalEvent::ShowErrorMsg(format!(
+				"{}:\n{e}",
+				strings::POPUP_FAIL_PATCH_SERIES
+			)),
+		});
+	}
+
+	fn format_patches(
+		&self,
+		context: &PatchSeriesContext,
+	) -> Result<String> {
+		let dir = sync::utils::repo_work_dir(&self.repo.borrow())?;
+		let files = sync::format_patches(
+			&self.repo.borrow(),
+			&context.commits,
+			Path::new(&dir),
+		)?;
+
+		Ok(strings::patches_written(files.len(), &dir))
+	}
+
+	fn copy_diff(
+		&self,
+		context: &PatchSeriesContext,
+	) -> Result<String> {
+		if !context.contiguous {
+			bail!(strings::PATCH_SERIES_NOT_CONTIGUOUS);
+		}
+
+		let (Some(oldest), Some(newest)) =
+			(context.commits.first(), context.commits.last())
+		else {
+			return Ok(String::new());
+		};
+
+		let diff = sync::get_range_diff(
+			&self.repo.borrow(),
+			*oldest,
+			*newest,
+		)?;
+		crate::clipboard::copy_string(&diff)?;
+
+		Ok(strings::POPUP_SUCCESS_COPY.to_string())
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let count = self.items.len();
+		if count == 0 {
+			return;
+		}
+
+		self.selection = if up {
+			self.selection.checked_sub(1).unwrap_or(count - 1)
+		} else {
+			(self.selection + 1) % count
+		};
+	}
+
+	fn get_text(&self) -> Vec<Line<'_>> {
+		let contiguous =
+			self.context.as_ref().is_some_and(|c| c.contiguous);
+
+		self.items
+			.iter()
+			.enumerate()
+			.map(|(idx, item)| {
+				let enabled =
+					*item != PatchAction::CopyDiff || contiguous;
+				Line::from(Span::styled(
+					item.label(),
+					self.theme.text(enabled, idx == self.selection),
+				))
+			})
+			.collect()
+	}
+}
+
+impl DrawableComponent for PatchSeriesPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			const WIDTH: u16 = 32;
+			let height = u16::try_from(self.items.len())
+				.unwrap_or_default()
+				.saturating_add(2);
+			let area =
+				ui::centered_rect_absolute(WIDTH, height, area);
+
+			let title = format!(
+				"{} ({})",
+				strings::POPUP_TITLE_PATCH_SERIES,
+				self.context.as_ref().map_or(0, |c| c.commits.len())
+			);
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(self.get_text()).block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							title,
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for PatchSeriesPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::patch_series_confirm(
+						&self.key_config,
+					),
+					true,
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			match event {
+				Event::Key(key) => {
+					if key_match(key, self.key_config.keys.exit_popup)
+					{
+						self.hide();
+					} else if key_match(
+						key,
+						self.key_config.keys.move_up,
+					) {
+						self.move_selection(true);
+					} else if key_match(
+						key,
+						self.key_config.keys.move_down,
+					) {
+						self.move_selection(false);
+					} else if key_match(
+						key,
+						self.key_config.keys.enter,
+					) {
+						self.apply_selected();
+					}
+				}
+				Event::Mouse(mouse)
+					if mouse.kind
+						== MouseEventKind::Down(
+							MouseButton::Right,
+						) =>
+				{
+					self.hide();
+				}
+				_ => (),
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
@@ -5,7 +5,6 @@ use crate::{
 		CredComponent, DrawableComponent, EventState,
 	},
 	keys::SharedKeyConfig,
-	popups::PushPopup,
 	queue::{Action, InternalEvent, Queue},
 	strings, try_or_popup,
 	ui::{self, style::SharedTheme},
@@ -21,23 +20,19 @@ use asyncgit::{
 		remotes::get_default_remote_for_fetch,
 		RepoPathRef,
 	},
-	AsyncGitNotification, AsyncPull, FetchRequest, RemoteProgress,
+	AsyncGitNotification, AsyncPull, FetchRequest, PostHook,
+	Progress,
 };
 
 use crossterm::event::Event;
-use ratatui::{
-	layout::Rect,
-	text::Span,
-	widgets::{Block, BorderType, Borders, Clear, Gauge},
-	Frame,
-};
+use ratatui::{layout::Rect, Frame};
 
 ///
 pub struct PullPopup {
 	repo: RepoPathRef,
 	visible: bool,
 	git_fetch: AsyncPull,
-	progress: Option<RemoteProgress>,
+	progress: Option<Progress>,
 	pending: bool,
 	branch: String,
 	queue: Queue,
@@ -123,7 +118,7 @@ impl PullPopup {
 
 	///
 	fn update(&mut self) -> Result<()> {
-		self.pending = self.git_fetch.is_pending()?;
+		self.pending = self.git_fetch.is_pending();
 		self.progress = self.git_fetch.progress()?;
 
 		if !self.pending {
@@ -155,6 +150,10 @@ impl PullPopup {
 			if let Err(e) = ff_res {
 				log::trace!("ff failed: {e}");
 				self.confirm_merge(branch_compare.behind);
+			} else {
+				self.queue.push(InternalEvent::RunPostHook(
+					PostHook::Merge { squash: false },
+				));
 			}
 		}
 
@@ -174,14 +173,22 @@ impl PullPopup {
 				)
 			);
 		} else {
-			try_or_popup!(
-				self,
-				"merge failed:",
-				sync::merge_upstream_commit(
-					&self.repo.borrow(),
-					&self.branch
-				)
-			);
+			match sync::merge_upstream_commit(
+				&self.repo.borrow(),
+				&self.branch,
+			) {
+				Ok(_) => {
+					self.queue.push(InternalEvent::RunPostHook(
+						PostHook::Merge { squash: false },
+					));
+				}
+				Err(e) => {
+					log::error!("merge failed: {e}");
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("merge failed:\n{e}"),
+					));
+				}
+			}
 		}
 	}
 
@@ -202,28 +209,11 @@ impl PullPopup {
 impl DrawableComponent for PullPopup {
 	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
 		if self.visible {
-			let (state, progress) =
-				PushPopup::get_progress(self.progress.as_ref());
-
-			let area = ui::centered_rect_absolute(30, 3, f.area());
-
-			f.render_widget(Clear, area);
-			f.render_widget(
-				Gauge::default()
-					.label(state.as_str())
-					.block(
-						Block::default()
-							.title(Span::styled(
-								strings::PULL_POPUP_MSG,
-								self.theme.title(true),
-							))
-							.borders(Borders::ALL)
-							.border_type(BorderType::Thick)
-							.border_style(self.theme.block(true)),
-					)
-					.gauge_style(self.theme.push_gauge())
-					.percent(u16::from(progress)),
-				area,
+			ui::draw_progress(
+				f,
+				strings::PULL_POPUP_MSG,
+				self.progress.as_ref(),
+				&self.theme,
 			);
 			self.input_cred.draw(f, rect)?;
 		}
@@ -1,3 +1,4 @@
+use super::HookOutputPopup;
 use crate::{
 	app::Environment,
 	components::{
@@ -16,20 +17,15 @@ use asyncgit::{
 			extract_username_password_for_push,
 			need_username_password_for_push, BasicAuthCredential,
 		},
-		get_branch_remote, hooks_pre_push,
+		get_branch_remote, hook_runs,
 		remotes::get_default_remote_for_push,
-		HookResult, RepoPathRef,
+		RepoPathRef, HOOK_PRE_PUSH,
 	},
-	AsyncGitNotification, AsyncPush, PushRequest, PushType,
-	RemoteProgress, RemoteProgressState,
+	AsyncGitNotification, AsyncPush, PrePushRequest, Progress,
+	PushRequest, PushType,
 };
 use crossterm::event::Event;
-use ratatui::{
-	layout::Rect,
-	text::Span,
-	widgets::{Block, BorderType, Borders, Clear, Gauge},
-	Frame,
-};
+use ratatui::{layout::Rect, Frame};
 
 ///
 #[derive(PartialEq, Eq)]
@@ -55,7 +51,7 @@ pub struct PushPopup {
 	modifier: PushComponentModifier,
 	visible: bool,
 	git_push: AsyncPush,
-	progress: Option<RemoteProgress>,
+	progress: Option<Progress>,
 	pending: bool,
 	branch: String,
 	push_type: PushType,
@@ -63,6 +59,9 @@ pub struct PushPopup {
 	theme: SharedTheme,
 	key_config: SharedKeyConfig,
 	input_cred: CredComponent,
+	hook_output: HookOutputPopup,
+	/// waits for the pre-push hook
+	pending_push: Option<PushRequest>,
 }
 
 impl PushPopup {
@@ -82,6 +81,8 @@ impl PushPopup {
 			),
 			progress: None,
 			input_cred: CredComponent::new(env),
+			hook_output: HookOutputPopup::new(env),
+			pending_push: None,
 			theme: env.theme.clone(),
 			key_config: env.key_config.clone(),
 		}
@@ -144,36 +145,40 @@ impl PushPopup {
 			remote
 		};
 
-		// run pre push hook - can reject push
-		let repo = self.repo.borrow();
-		if let HookResult::NotOk(e) = hooks_pre_push(
-			&repo,
-			&remote,
-			&asyncgit::sync::PrePushTarget::Branch {
-				branch: &self.branch,
-				delete: self.modifier.delete(),
-			},
-			cred.clone(),
-		)? {
-			log::error!("pre-push hook failed: {e}");
-			self.queue.push(InternalEvent::ShowErrorMsg(format!(
-				"pre-push hook failed:\n{e}"
-			)));
-			self.pending = false;
-			self.visible = false;
-			return Ok(());
-		}
-
-		self.pending = true;
-		self.progress = None;
-		self.git_push.request(PushRequest {
+		let request = PushRequest {
 			remote,
 			branch: self.branch.clone(),
 			push_type: self.push_type,
 			force,
 			delete: self.modifier.delete(),
 			basic_credential: cred,
-		})?;
+		};
+
+		self.pending = true;
+
+		// run pre push hook - can reject push
+		if hook_runs(&self.repo.borrow(), HOOK_PRE_PUSH)? {
+			self.hook_output.run_pre_push(
+				self.repo.borrow().clone(),
+				PrePushRequest {
+					remote: request.remote.clone(),
+					branch: Some(request.branch.clone()),
+					delete: request.delete,
+					basic_credential: request
+						.basic_credential
+						.clone(),
+				},
+			);
+			self.pending_push = Some(request);
+			return Ok(());
+		}
+
+		self.start_push(request)
+	}
+
+	fn start_push(&mut self, request: PushRequest) -> Result<()> {
+		self.progress = None;
+		self.git_push.request(request)?;
 		Ok(())
 	}
 
@@ -182,6 +187,18 @@ impl PushPopup {
 		&mut self,
 		ev: AsyncGitNotification,
 	) -> Result<()> {
+		if let Some(passed) = self.hook_output.update_git(ev) {
+			match self.pending_push.take() {
+				Some(request) if passed => {
+					self.start_push(request)?;
+				}
+				_ => {
+					self.pending = false;
+					self.hide();
+				}
+			}
+		}
+
 		if self.is_visible() && ev == AsyncGitNotification::Push {
 			self.update()?;
 		}
@@ -189,9 +206,14 @@ impl PushPopup {
 		Ok(())
 	}
 
+	/// next frame of the hook spinner, `true` if it needs a redraw
+	pub fn tick_spinner(&mut self) -> bool {
+		self.hook_output.tick_spinner()
+	}
+
 	///
 	fn update(&mut self) -> Result<()> {
-		self.pending = self.git_push.is_pending()?;
+		self.pending = self.git_push.is_pending();
 		self.progress = self.git_push.progress()?;
 
 		if !self.pending {
@@ -210,73 +232,22 @@ impl PushPopup {
 	pub const fn any_work_pending(&self) -> bool {
 		self.pending
 	}
-
-	///
-	pub fn get_progress(
-		progress: Option<&RemoteProgress>,
-	) -> (String, u8) {
-		progress.as_ref().map_or_else(
-			|| (strings::PUSH_POPUP_PROGRESS_NONE.into(), 0),
-			|progress| {
-				(
-					Self::progress_state_name(&progress.state),
-					progress.get_progress_percent(),
-				)
-			},
-		)
-	}
-
-	fn progress_state_name(state: &RemoteProgressState) -> String {
-		match state {
-			RemoteProgressState::PackingAddingObject => {
-				strings::PUSH_POPUP_STATES_ADDING
-			}
-			RemoteProgressState::PackingDeltafiction => {
-				strings::PUSH_POPUP_STATES_DELTAS
-			}
-			RemoteProgressState::Pushing => {
-				strings::PUSH_POPUP_STATES_PUSHING
-			}
-			RemoteProgressState::Transfer => {
-				strings::PUSH_POPUP_STATES_TRANSFER
-			}
-			RemoteProgressState::Done => {
-				strings::PUSH_POPUP_STATES_DONE
-			}
-		}
-		.into()
-	}
 }
 
 impl DrawableComponent for PushPopup {
 	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
-		if self.visible {
-			let (state, progress) =
-				Self::get_progress(self.progress.as_ref());
-
-			let area = ui::centered_rect_absolute(30, 3, f.area());
-
-			f.render_widget(Clear, area);
-			f.render_widget(
-				Gauge::default()
-					.label(state.as_str())
-					.block(
-						Block::default()
-							.title(Span::styled(
-								if self.modifier.force() {
-									strings::FORCE_PUSH_POPUP_MSG
-								} else {
-									strings::PUSH_POPUP_MSG
-								},
-								self.theme.title(true),
-							))
-							.borders(Borders::ALL)
-							.border_type(BorderType::Thick)
-							.border_style(self.theme.block(true)),
-					)
-					.gauge_style(self.theme.push_gauge())
-					.percent(u16::from(progress)),
-				area,
+		if self.hook_output.is_visible() {
+			self.hook_output.draw(f, rect)?;
+		} else if self.visible {
+			ui::draw_progress(
+				f,
+				if self.modifier.force() {
+					strings::FORCE_PUSH_POPUP_MSG
+				} else {
+					strings::PUSH_POPUP_MSG
+				},
+				self.progress.as_ref(),
+				&self.theme,
 			);
 			self.input_cred.draw(f, rect)?;
 		}
@@ -1,3 +1,4 @@
+use super::HookOutputPopup;
 use crate::{
 	app::Environment,
 	components::{
@@ -16,30 +17,28 @@ use asyncgit::{
 			extract_username_password, need_username_password,
 			BasicAuthCredential,
 		},
-		get_default_remote, hooks_pre_push, AsyncProgress,
-		HookResult, PushTagsProgress, RepoPathRef,
+		get_default_remote, hook_runs, RepoPathRef, HOOK_PRE_PUSH,
 	},
-	AsyncGitNotification, AsyncPushTags, PushTagsRequest,
+	AsyncGitNotification, AsyncPushTags, PrePushRequest, Progress,
+	PushTagsRequest,
 };
 use crossterm::event::Event;
-use ratatui::{
-	layout::Rect,
-	text::Span,
-	widgets::{Block, BorderType, Borders, Clear, Gauge},
-	Frame,
-};
+use ratatui::{layout::Rect, Frame};
 
 ///
 pub struct PushTagsPopup {
 	repo: RepoPathRef,
 	visible: bool,
 	git_push: AsyncPushTags,
-	progress: Option<PushTagsProgress>,
+	progress: Option<Progress>,
 	pending: bool,
 	queue: Queue,
 	theme: SharedTheme,
 	key_config: SharedKeyConfig,
 	input_cred: CredComponent,
+	hook_output: HookOutputPopup,
+	/// waits for the pre-push hook
+	pending_push: Option<PushTagsRequest>,
 }
 
 impl PushTagsPopup {
@@ -56,6 +55,8 @@ impl PushTagsPopup {
 			),
 			progress: None,
 			input_cred: CredComponent::new(env),
+			hook_output: HookOutputPopup::new(env),
+			pending_push: None,
 			theme: env.theme.clone(),
 			key_config: env.key_config.clone(),
 		}
@@ -86,28 +87,36 @@ impl PushTagsPopup {
 	) -> Result<()> {
 		let remote = get_default_remote(&self.repo.borrow())?;
 
-		let repo = self.repo.borrow();
-		if let HookResult::NotOk(e) = hooks_pre_push(
-			&repo,
-			&remote,
-			&asyncgit::sync::PrePushTarget::Tags,
-			cred.clone(),
-		)? {
-			log::error!("pre-push hook failed: {e}");
-			self.queue.push(InternalEvent::ShowErrorMsg(format!(
-				"pre-push hook failed:\n{e}"
-			)));
-			self.pending = false;
-			self.visible = false;
+		let request = PushTagsRequest {
+			remote,
+			basic_credential: cred,
+		};
+
+		self.pending = true;
+
+		// run pre push hook - can reject push
+		if hook_runs(&self.repo.borrow(), HOOK_PRE_PUSH)? {
+			self.hook_output.run_pre_push(
+				self.repo.borrow().clone(),
+				PrePushRequest {
+					remote: request.remote.clone(),
+					branch: None,
+					delete: false,
+					basic_credential: request
+						.basic_credential
+						.clone(),
+				},
+			);
+			self.pending_push = Some(request);
 			return Ok(());
 		}
 
-		self.pending = true;
+		self.start_push(request)
+	}
+
+	fn start_push(&mut self, request: PushTagsRequest) -> Result<()> {
 		self.progress = None;
-		self.git_push.request(PushTagsRequest {
-			remote,
-			basic_credential: cred,
-		})?;
+		self.git_push.request(request)?;
 		Ok(())
 	}
 
@@ -116,6 +125,18 @@ impl PushTagsPopup {
 		&mut self,
 		ev: AsyncGitNotification,
 	) -> Result<()> {
+		if let Some(passed) = self.hook_output.update_git(ev) {
+			match self.pending_push.take() {
+				Some(request) if passed => {
+					self.start_push(request)?;
+				}
+				_ => {
+					self.pending = false;
+					self.hide();
+				}
+			}
+		}
+
 		if self.is_visible() && ev == AsyncGitNotification::PushTags {
 			self.update()?;
 		}
@@ -125,7 +146,7 @@ impl PushTagsPopup {
 
 	///
 	fn update(&mut self) -> Result<()> {
-		self.pending = self.git_push.is_pending()?;
+		self.pending = self.git_push.is_pending();
 		self.progress = self.git_push.progress()?;
 
 		if !self.pending {
@@ -145,60 +166,22 @@ impl PushTagsPopup {
 		self.pending
 	}
 
-	///
-	pub fn get_progress(
-		progress: Option<&PushTagsProgress>,
-	) -> (String, u8) {
-		progress.as_ref().map_or_else(
-			|| (strings::PUSH_POPUP_PROGRESS_NONE.into(), 0),
-			|progress| {
-				(
-					Self::progress_state_name(progress),
-					progress.progress().progress,
-				)
-			},
-		)
-	}
-
-	fn progress_state_name(progress: &PushTagsProgress) -> String {
-		match progress {
-			PushTagsProgress::CheckRemote => {
-				strings::PUSH_TAGS_STATES_FETCHING
-			}
-			PushTagsProgress::Push { .. } => {
-				strings::PUSH_TAGS_STATES_PUSHING
-			}
-			PushTagsProgress::Done => strings::PUSH_TAGS_STATES_DONE,
-		}
-		.to_string()
+	/// next frame of the hook spinner, `true` if it needs a redraw
+	pub fn tick_spinner(&mut self) -> bool {
+		self.hook_output.tick_spinner()
 	}
 }
 
 impl DrawableComponent for PushTagsPopup {
 	fn draw(&self, f: &mut Frame, rect: Rect) -> Result<()> {
-		if self.visible {
-			let (state, progress) =
-				Self::get_progress(self.progress.as_ref());
-
-			let area = ui::centered_rect_absolute(30, 3, f.area());
-
-			f.render_widget(Clear, area);
-			f.render_widget(
-				Gauge::default()
-					.label(state.as_str())
-					.block(
-						Block::default()
-							.title(Span::styled(
-								strings::PUSH_TAGS_POPUP_MSG,
-								self.theme.title(true),
-							))
-							.borders(Borders::ALL)
-							.border_type(BorderType::Thick)
-							.border_style(self.theme.block(true)),
-					)
-					.gauge_style(self.theme.push_gauge())
-					.percent(u16::from(progress)),
-				area,
+		if self.hook_output.is_visible() {
+			self.hook_output.draw(f, rect)?;
+		} else if self.visible {
+			ui::draw_progress(
+				f,
+				strings::PUSH_TAGS_POPUP_MSG,
+				self.progress.as_ref(),
+				&self.theme,
 			);
 			self.input_cred.draw(f, rect)?;
 		}
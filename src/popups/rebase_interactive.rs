@@ -0,0 +1,478 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState, InputType, TextInputComponent,
+	},
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings, try_or_popup,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::sync::{
+	self, get_commit_details, get_commit_parents, get_commits_info,
+	rebase_interactive, rebase_interactive_todo, CommitId,
+	CommitInfo, CommitMessage, RebaseAction, RebaseTodo, RepoPathRef,
+	RepoState,
+};
+use crossterm::event::{
+	Event, KeyEvent, MouseButton, MouseEventKind,
+};
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// summaries are cut to this many chars
+const SUMMARY_LENGTH: usize = 80;
+
+/// a commit of the todo list
+struct TodoItem {
+	commit: CommitInfo,
+	action: RebaseAction,
+	/// new message when reworded
+	message: Option<String>,
+}
+
+/// the todo list of an interactive rebase from a commit up to HEAD,
+/// to reorder the commits and pick, reword, edit, squash, fixup or
+/// drop them
+pub struct RebaseInteractivePopup {
+	repo: RepoPathRef,
+	/// the commits after it get rebased
+	base: Option<CommitId>,
+	/// newest first, in the order the user arranged them
+	todo: Vec<TodoItem>,
+	/// newest first, as they are now
+	original: Vec<CommitId>,
+	selection: usize,
+	visible: bool,
+	/// new message of the selected commit
+	message: TextInputComponent,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl RebaseInteractivePopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			repo: env.repo.clone(),
+			base: None,
+			todo: Vec::new(),
+			original: Vec::new(),
+			selection: 0,
+			visible: false,
+			message: TextInputComponent::new(
+				env,
+				strings::REBASE_REWORD_TITLE,
+				strings::REBASE_REWORD_MSG,
+				true,
+			)
+			.with_input_type(InputType::Multiline),
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	/// rebases `from` and the commits after it
+	pub fn open(&mut self, from: CommitId) -> Result<()> {
+		let repo = self.repo.borrow().clone();
+
+		let Some(base) =
+			get_commit_parents(&repo, from)?.first().copied()
+		else {
+			anyhow::bail!("cannot rebase the first commit");
+		};
+
+		let todo = rebase_interactive_todo(&repo, base)?;
+		let ids: Vec<_> = todo.iter().map(|t| t.id).collect();
+		let mut infos =
+			get_commits_info(&repo, &ids, SUMMARY_LENGTH)?;
+
+		self.todo = todo
+			.iter()
+			.rev()
+			.filter_map(|t| {
+				let idx = infos.iter().position(|c| c.id == t.id)?;
+				Some(TodoItem {
+					commit: infos.swap_remove(idx),
+					action: t.action,
+					message: None,
+				})
+			})
+			.collect();
+		self.original = ids.into_iter().rev().collect();
+		self.base = Some(base);
+		self.selection = 0;
+
+		self.show()
+	}
+
+	/// whether the list got rearranged or any commit is not picked
+	fn modified(&self) -> bool {
+		self.original
+			.iter()
+			.ne(self.todo.iter().map(|item| &item.commit.id))
+			|| self
+				.todo
+				.iter()
+				.any(|item| item.action != RebaseAction::Pick)
+	}
+
+	fn move_commit(&mut self, up: bool) {
+		let target = if up {
+			self.selection.checked_sub(1)
+		} else {
+			Some(self.selection + 1)
+				.filter(|idx| *idx < self.todo.len())
+		};
+
+		if let Some(target) = target {
+			self.todo.swap(self.selection, target);
+			self.selection = target;
+		}
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let count = self.todo.len();
+		if count == 0 {
+			return;
+		}
+
+		self.selection = if up {
+			self.selection.checked_sub(1).unwrap_or(count - 1)
+		} else {
+			(self.selection + 1) % count
+		};
+	}
+
+	fn set_action(&mut self, action: RebaseAction) -> Result<()> {
+		let Some(item) = self.todo.get_mut(self.selection) else {
+			return Ok(());
+		};
+
+		item.action = action;
+
+		if action == RebaseAction::Reword {
+			let message = if let Some(message) = &item.message {
+				message.clone()
+			} else {
+				get_commit_details(
+					&self.repo.borrow(),
+					item.commit.id,
+				)?
+				.message
+				.map(CommitMessage::combine)
+				.unwrap_or_default()
+			};
+
+			self.message.set_text(message);
+			self.message.show()?;
+		}
+
+		Ok(())
+	}
+
+	fn confirm_message(&mut self) {
+		if let Some(item) = self.todo.get_mut(self.selection) {
+			item.message = Some(self.message.get_text().to_string());
+		}
+		self.message.hide();
+	}
+
+	/// the action a key sets, if any
+	fn key_action(&self, key: &KeyEvent) -> Option<RebaseAction> {
+		let keys = &self.key_config.keys;
+
+		[
+			(keys.rebase_pick, RebaseAction::Pick),
+			(keys.rebase_reword, RebaseAction::Reword),
+			(keys.rebase_edit, RebaseAction::Edit),
+			(keys.rebase_squash, RebaseAction::Squash),
+			(keys.rebase_fixup, RebaseAction::Fixup),
+			(keys.rebase_drop, RebaseAction::Drop),
+		]
+		.into_iter()
+		.find_map(|(k, action)| key_match(key, k).then_some(action))
+	}
+
+	fn start(&mut self) -> Result<()> {
+		let Some(base) = self.base else {
+			return Ok(());
+		};
+
+		let todo: Vec<_> = self
+			.todo
+			.iter()
+			.rev()
+			.map(|item| RebaseTodo {
+				id: item.commit.id,
+				action: item.action,
+				message: item.message.clone(),
+			})
+			.collect();
+
+		try_or_popup!(
+			self,
+			"rebase failed:",
+			rebase_interactive(&self.repo.borrow(), base, &todo)
+		);
+
+		self.hide();
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+
+		// edits and conflicts are continued or aborted in the status
+		// tab
+		if sync::repo_state(&self.repo.borrow())? != RepoState::Clean
+		{
+			self.queue.push(InternalEvent::TabSwitchStatus);
+		}
+
+		Ok(())
+	}
+
+	fn get_text(&self) -> Vec<Line<'_>> {
+		self.todo
+			.iter()
+			.enumerate()
+			.map(|(idx, item)| {
+				let selected = idx == self.selection;
+				let enabled = item.action != RebaseAction::Drop;
+
+				let summary = item
+					.message
+					.as_deref()
+					.filter(|_| item.action == RebaseAction::Reword)
+					.and_then(|m| m.lines().next())
+					.unwrap_or(&item.commit.message);
+
+				Line::from(vec![
+					Span::styled(
+						strings::rebase_action(item.action),
+						self.theme.text(enabled, selected),
+					),
+					Span::styled(
+						format!(
+							"{} ",
+							item.commit.id.get_short_string()
+						),
+						self.theme.commit_hash(selected),
+					),
+					Span::styled(
+						summary.to_string(),
+						self.theme.text(enabled, selected),
+					),
+				])
+			})
+			.collect()
+	}
+}
+
+impl DrawableComponent for RebaseInteractivePopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			const MIN_WIDTH: u16 = 50;
+			const MAX_WIDTH: u16 = 100;
+
+			let text = self.get_text();
+			let title = strings::rebase_interactive_title(
+				self.todo.len(),
+				self.base.as_ref(),
+				self.modified(),
+			);
+
+			let width = text
+				.iter()
+				.map(Line::width)
+				.chain(std::iter::once(title.chars().count()))
+				.max()
+				.and_then(|width| u16::try_from(width + 4).ok())
+				.unwrap_or_default()
+				.clamp(MIN_WIDTH, MAX_WIDTH)
+				.min(area.width);
+			let height = u16::try_from(text.len())
+				.unwrap_or_default()
+				.saturating_add(2)
+				.min(area.height);
+			let popup_area =
+				ui::centered_rect_absolute(width, height, area);
+
+			// keep the selection in view of long lists
+			let scroll = u16::try_from(self.selection)
+				.unwrap_or_default()
+				.saturating_sub(height.saturating_sub(3));
+
+			f.render_widget(Clear, popup_area);
+			f.render_widget(
+				Paragraph::new(text).scroll((scroll, 0)).block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							title,
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				popup_area,
+			);
+
+			self.message.draw(f, area)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for RebaseInteractivePopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.message.is_visible() && !force_all {
+			self.message.commands(out, force_all);
+
+			out.push(CommandInfo::new(
+				strings::commands::rebase_reword_confirm(
+					&self.key_config,
+				),
+				true,
+				true,
+			));
+
+			return visibility_blocking(self);
+		}
+
+		if self.is_visible() || force_all {
+			let selected = self.todo.get(self.selection).is_some();
+
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::rebase_interactive_move(
+						&self.key_config,
+					),
+					self.todo.len() > 1,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::rebase_interactive_action(
+						&self.key_config,
+					),
+					selected,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::rebase_interactive_start(
+						&self.key_config,
+					),
+					selected,
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			if self.message.is_visible() {
+				if let Event::Key(key) = event {
+					if key_match(key, self.key_config.keys.commit) {
+						self.confirm_message();
+						return Ok(EventState::Consumed);
+					}
+				}
+
+				self.message.event(event)?;
+				return Ok(EventState::Consumed);
+			}
+
+			match event {
+				Event::Key(key) => {
+					if key_match(key, self.key_config.keys.exit_popup)
+					{
+						self.hide();
+					} else if key_match(
+						key,
+						self.key_config.keys.shift_up,
+					) {
+						self.move_commit(true);
+					} else if key_match(
+						key,
+						self.key_config.keys.shift_down,
+					) {
+						self.move_commit(false);
+					} else if key_match(
+						key,
+						self.key_config.keys.move_up,
+					) {
+						self.move_selection(true);
+					} else if key_match(
+						key,
+						self.key_config.keys.move_down,
+					) {
+						self.move_selection(false);
+					} else if key_match(
+						key,
+						self.key_config.keys.enter,
+					) {
+						self.start()?;
+					} else if let Some(action) = self.key_action(key)
+					{
+						self.set_action(action)?;
+					}
+				}
+				Event::Mouse(mouse)
+					if mouse.kind
+						== MouseEventKind::Down(
+							MouseButton::Right,
+						) =>
+				{
+					self.hide();
+				}
+				_ => (),
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.message.hide();
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
@@ -9,7 +9,6 @@ use ratatui::{
 	widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
 	Frame,
 };
-use unicode_truncate::UnicodeTruncateStr;
 
 use crate::{
 	app::Environment,
@@ -19,6 +18,9 @@ use crate::{
 	},
 	keys::{key_match, SharedKeyConfig},
 	queue::{Action, InternalEvent, Queue},
+	string_utils::{
+		pad_width, str_width, truncate_start_width, truncate_with,
+	},
 	strings,
 	ui::{self, style::SharedTheme, Size},
 };
@@ -247,23 +249,13 @@ impl RemoteListPopup {
 					let selected = (self.selection as usize
 						- self.scroll.get_top())
 						== i;
-					let mut remote_name = remote.clone();
-					if remote_name.len()
-						> name_length
-							.saturating_sub(THREE_DOTS_LENGTH)
-					{
-						remote_name = remote_name
-							.unicode_truncate(
-								name_length.saturating_sub(
-									THREE_DOTS_LENGTH,
-								),
-							)
-							.0
-							.to_string();
-						remote_name += THREE_DOTS;
-					}
+					let remote_name = truncate_with(
+						remote,
+						name_length,
+						THREE_DOTS,
+					);
 					let span_name = Span::styled(
-						format!("{remote_name:name_length$}"),
+						pad_width(&remote_name, name_length),
 						theme.text(true, selected),
 					);
 					Line::from(vec![span_name])
@@ -335,45 +327,33 @@ impl RemoteListPopup {
 		let remote =
 			self.remote_names.get(usize::from(self.selection));
 		if let Some(remote) = remote {
-			let mut remote_name = remote.clone();
-			if remote_name.len()
-				> name_length.saturating_sub(THREE_DOTS_LENGTH)
-			{
-				remote_name = remote_name
-					.unicode_truncate(
-						name_length.saturating_sub(THREE_DOTS_LENGTH),
-					)
-					.0
-					.to_string();
-				remote_name += THREE_DOTS;
-			}
+			let remote_name =
+				truncate_with(remote, name_length, THREE_DOTS);
 			let mut lines = Vec::<Line>::new();
 			lines.push(Line::from(Span::styled(
 				format!(
-					"{REMOTE_NAME_LABEL}{remote_name:name_length$}"
+					"{REMOTE_NAME_LABEL}{}",
+					pad_width(&remote_name, name_length)
 				),
 				self.theme.text(true, false),
 			)));
 			let remote_url =
 				get_remote_url(&self.repo.borrow(), remote);
 			if let Ok(Some(mut remote_url)) = remote_url {
-				if remote_url.len()
-					> url_length.saturating_sub(THREE_DOTS_LENGTH)
-				{
-					remote_url = remote_url
-						.chars()
-						.skip(
-							remote_url.len()
-								- url_length.saturating_sub(
-									THREE_DOTS_LENGTH,
-								),
+				if str_width(&remote_url) > url_length {
+					remote_url = format!(
+						"{THREE_DOTS}{}",
+						truncate_start_width(
+							&remote_url,
+							url_length
+								.saturating_sub(THREE_DOTS_LENGTH),
 						)
-						.collect::<String>();
-					remote_url = format!("{THREE_DOTS}{remote_url}");
+					);
 				}
 				lines.push(Line::from(Span::styled(
 					format!(
-						"{REMOTE_URL_LABEL}{remote_url:url_length$}"
+						"{REMOTE_URL_LABEL}{}",
+						pad_width(&remote_url, url_length)
 					),
 					self.theme.text(true, false),
 				)));
@@ -5,6 +5,7 @@ use crate::components::{
 use crate::ui::style::SharedTheme;
 use crate::{
 	app::Environment,
+	completion::CompletionSource,
 	keys::{key_match, SharedKeyConfig},
 	queue::{InternalEvent, NeedsUpdate, Queue},
 	strings,
@@ -19,6 +20,8 @@ pub struct RenameBranchPopup {
 	repo: RepoPathRef,
 	input: TextInputComponent,
 	branch_ref: Option<String>,
+	/// keeps the branch and creates a copy instead
+	copy: bool,
 	queue: Queue,
 	key_config: SharedKeyConfig,
 	theme: SharedTheme,
@@ -44,9 +47,15 @@ impl Component for RenameBranchPopup {
 			self.input.commands(out, force_all);
 
 			out.push(CommandInfo::new(
-				strings::commands::rename_branch_confirm_msg(
-					&self.key_config,
-				),
+				if self.copy {
+					strings::commands::copy_branch_confirm_msg(
+						&self.key_config,
+					)
+				} else {
+					strings::commands::rename_branch_confirm_msg(
+						&self.key_config,
+					)
+				},
 				true,
 				true,
 			));
@@ -99,8 +108,10 @@ impl RenameBranchPopup {
 				&strings::rename_branch_popup_msg(&env.key_config),
 				true,
 			)
-			.with_input_type(InputType::Singleline),
+			.with_input_type(InputType::Singleline)
+			.with_completion(env, CompletionSource::Branches),
 			branch_ref: None,
+			copy: false,
 			key_config: env.key_config.clone(),
 			theme: env.theme.clone(),
 		}
@@ -112,8 +123,31 @@ impl RenameBranchPopup {
 		branch_ref: String,
 		cur_name: String,
 	) -> Result<()> {
-		self.branch_ref = None;
+		self.open_as(branch_ref, cur_name, false)
+	}
+
+	///
+	pub fn open_copy(
+		&mut self,
+		branch_ref: String,
+		cur_name: String,
+	) -> Result<()> {
+		self.open_as(branch_ref, cur_name, true)
+	}
+
+	fn open_as(
+		&mut self,
+		branch_ref: String,
+		cur_name: String,
+		copy: bool,
+	) -> Result<()> {
 		self.branch_ref = Some(branch_ref);
+		self.copy = copy;
+		self.input.set_title(if copy {
+			strings::copy_branch_popup_title(&self.key_config)
+		} else {
+			strings::rename_branch_popup_title(&self.key_config)
+		});
 		self.input.set_text(cur_name);
 		self.show()?;
 
@@ -122,12 +156,22 @@ impl RenameBranchPopup {
 
 	///
 	pub fn rename_branch(&mut self) {
+		let action = if self.copy { "copy" } else { "rename" };
+
 		if let Some(br) = &self.branch_ref {
-			let res = sync::rename_branch(
-				&self.repo.borrow(),
-				br,
-				self.input.get_text(),
-			);
+			let res = if self.copy {
+				sync::copy_branch(
+					&self.repo.borrow(),
+					br,
+					self.input.get_text(),
+				)
+			} else {
+				sync::rename_branch(
+					&self.repo.borrow(),
+					br,
+					self.input.get_text(),
+				)
+			};
 
 			match res {
 				Ok(()) => {
@@ -138,18 +182,17 @@ impl RenameBranchPopup {
 					self.queue.push(InternalEvent::SelectBranch);
 				}
 				Err(e) => {
-					log::error!("create branch: {e}");
+					log::error!("{action} branch: {e}");
 					self.queue.push(InternalEvent::ShowErrorMsg(
-						format!("rename branch error:\n{e}",),
+						format!("{action} branch error:\n{e}"),
 					));
 				}
 			}
 		} else {
-			log::error!("create branch: No branch selected");
-			self.queue.push(InternalEvent::ShowErrorMsg(
-				"rename branch error: No branch selected to rename"
-					.to_string(),
-			));
+			log::error!("{action} branch: No branch selected");
+			self.queue.push(InternalEvent::ShowErrorMsg(format!(
+				"{action} branch error: No branch selected to {action}"
+			)));
 		}
 
 		self.input.clear();
@@ -6,6 +6,7 @@ use ratatui::{layout::Rect, widgets::Paragraph, Frame};
 
 use crate::{
 	app::Environment,
+	completion::CompletionSource,
 	components::{
 		visibility_blocking, CommandBlocking, CommandInfo, Component,
 		DrawableComponent, EventState, InputType, TextInputComponent,
@@ -98,7 +99,8 @@ impl RenameRemotePopup {
 				&strings::rename_remote_popup_msg(&env.key_config),
 				true,
 			)
-			.with_input_type(InputType::Singleline),
+			.with_input_type(InputType::Singleline)
+			.with_completion(env, CompletionSource::Remotes),
 			theme: env.theme.clone(),
 			key_config: env.key_config.clone(),
 			queue: env.queue.clone(),
@@ -163,7 +165,7 @@ impl RenameRemotePopup {
 					Err(e) => {
 						log::error!("rename remote: {e}");
 						self.queue.push(InternalEvent::ShowErrorMsg(
-							format!("rename remote error:\n{e}",),
+							format!("rename remote error:\n{e}"),
 						));
 					}
 				}
@@ -5,14 +5,14 @@ use crate::components::{
 use crate::{
 	app::Environment,
 	keys::{key_match, SharedKeyConfig},
-	queue::Queue,
+	queue::{Action, InternalEvent, Queue},
 	strings, try_or_popup,
 	ui::{self, style::SharedTheme},
 };
 use anyhow::Result;
 use asyncgit::{
 	cached,
-	sync::{CommitId, RepoPath, ResetType},
+	sync::{self, CommitId, RepoPath, ResetPreview, ResetType},
 };
 use crossterm::event::Event;
 use ratatui::{
@@ -43,6 +43,8 @@ pub struct ResetPopup {
 	queue: Queue,
 	repo: RepoPath,
 	commit: Option<CommitId>,
+	/// what resetting to `commit` affects
+	preview: Option<ResetPreview>,
 	kind: ResetType,
 	git_branch_name: cached::BranchName,
 	visible: bool,
@@ -57,6 +59,7 @@ impl ResetPopup {
 			queue: env.queue.clone(),
 			repo: env.repo.borrow().clone(),
 			commit: None,
+			preview: None,
 			kind: ResetType::Soft,
 			git_branch_name: cached::BranchName::new(
 				env.repo.clone(),
@@ -105,14 +108,80 @@ impl ResetPopup {
 			Span::styled(kind_desc, self.theme.text(true, false)),
 		]));
 
+		if let Some(preview) = self.preview {
+			self.push_preview(&mut txt, preview);
+		}
+
 		txt
 	}
 
+	fn push_preview(
+		&self,
+		txt: &mut Vec<Line>,
+		preview: ResetPreview,
+	) {
+		let commits =
+			match (preview.commits_removed, preview.commits_added) {
+				(0, 0) => String::from("none"),
+				(removed, 0) => {
+					format!("{removed} removed from branch")
+				}
+				(0, added) => format!("{added} added to branch"),
+				(removed, added) => {
+					format!("{removed} removed, {added} added")
+				}
+			};
+
+		txt.push(Line::from(vec![
+			Span::styled(
+				String::from("Commits: "),
+				self.theme.text(true, false),
+			),
+			Span::styled(commits, self.theme.text(true, false)),
+		]));
+
+		let files = match self.kind {
+			ResetType::Soft => "staged",
+			ResetType::Mixed => "unstaged",
+			ResetType::Hard => "overwritten",
+		};
+
+		txt.push(Line::from(vec![
+			Span::styled(
+				String::from("Files: "),
+				self.theme.text(true, false),
+			),
+			Span::styled(
+				format!("{} changed, {files}", preview.files_changed),
+				self.theme.text(true, false),
+			),
+		]));
+
+		if self.kind == ResetType::Hard && preview.local_changes > 0 {
+			txt.push(Line::from(vec![
+				Span::styled(
+					String::from("Discards: "),
+					self.theme.text(true, false),
+				),
+				Span::styled(
+					format!(
+						"{} uncommitted changes",
+						preview.local_changes
+					),
+					self.theme.text_danger(),
+				),
+			]));
+		}
+	}
+
 	///
 	pub fn open(&mut self, id: CommitId) -> Result<()> {
 		self.show()?;
 
 		self.commit = Some(id);
+		self.preview = sync::reset_preview(&self.repo, id)
+			.map_err(|e| log::error!("reset preview: {e}"))
+			.ok();
 
 		Ok(())
 	}
@@ -126,11 +195,21 @@ impl ResetPopup {
 	}
 
 	fn reset(&mut self) {
-		if let Some(id) = self.commit {
+		let discards_changes = self.kind == ResetType::Hard
+			&& self
+				.preview
+				.as_ref()
+				.is_some_and(|preview| preview.local_changes > 0);
+
+		if let (Some(id), true) = (self.commit, discards_changes) {
+			self.queue.push(InternalEvent::ConfirmAction(
+				Action::ResetHard(id),
+			));
+		} else if let Some(id) = self.commit {
 			try_or_popup!(
 				self,
 				"reset:",
-				asyncgit::sync::reset_repo(&self.repo, id, self.kind)
+				sync::reset_repo(&self.repo, id, self.kind)
 			);
 		}
 
@@ -157,15 +236,18 @@ impl ResetPopup {
 impl DrawableComponent for ResetPopup {
 	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
 		if self.is_visible() {
-			const SIZE: (u16, u16) = (55, 5);
-			let area =
-				ui::centered_rect_absolute(SIZE.0, SIZE.1, area);
+			const WIDTH: u16 = 55;
 
-			let width = area.width;
+			let text = self.get_text(WIDTH);
+			let height = u16::try_from(text.len())
+				.unwrap_or_default()
+				.saturating_add(2);
+			let area =
+				ui::centered_rect_absolute(WIDTH, height, area);
 
 			f.render_widget(Clear, area);
 			f.render_widget(
-				Paragraph::new(self.get_text(width))
+				Paragraph::new(text)
 					.block(
 						Block::default()
 							.borders(Borders::ALL)
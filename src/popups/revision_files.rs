@@ -13,16 +13,28 @@ use anyhow::Result;
 use asyncgit::sync::CommitId;
 use crossterm::event::Event;
 use ratatui::{layout::Rect, widgets::Clear, Frame};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[derive(Clone, Debug)]
 pub struct FileTreeOpen {
 	pub commit_id: CommitId,
+	/// file (relative to the workdir) to select once the tree is
+	/// loaded
+	pub selection: Option<PathBuf>,
 }
 
 impl FileTreeOpen {
 	pub const fn new(commit_id: CommitId) -> Self {
-		Self { commit_id }
+		Self {
+			commit_id,
+			selection: None,
+		}
+	}
+
+	///
+	pub fn with_selection(mut self, file: PathBuf) -> Self {
+		self.selection = Some(file);
+		self
 	}
 }
 
@@ -49,6 +61,10 @@ impl RevisionFilesPopup {
 	///
 	pub fn open(&mut self, request: FileTreeOpen) -> Result<()> {
 		self.files.set_commit(request.commit_id)?;
+		if let Some(file) = &request.selection {
+			// the tree's paths start at its `.` root
+			self.files.select_file(Path::new(".").join(file));
+		}
 		self.open_request = Some(request);
 		self.show()?;
 
@@ -75,9 +91,9 @@ impl RevisionFilesPopup {
 		if stack {
 			if let Some(revision) = self.files.revision() {
 				self.queue.push(InternalEvent::PopupStackPush(
-					StackablePopupOpen::FileTree(FileTreeOpen {
-						commit_id: revision.id,
-					}),
+					StackablePopupOpen::FileTree(FileTreeOpen::new(
+						revision.id,
+					)),
 				));
 			}
 		} else {
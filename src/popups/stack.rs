@@ -0,0 +1,495 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings, try_or_popup,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::sync::{
+	self, get_commits_info, get_patch_stack, stack_autosquash_todo,
+	stack_fixup, stack_rewrite, CommitInfo, PatchStack, RepoPathRef,
+	RepoState, StackAction, StackTodo,
+};
+use crossterm::event::{Event, MouseButton, MouseEventKind};
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// summaries are cut to this many chars
+const SUMMARY_LENGTH: usize = 80;
+
+/// the commits of the current branch over its upstream, to reorder
+/// them, fix them up with the staged changes and rebase them
+pub struct StackPopup {
+	repo: RepoPathRef,
+	stack: Option<PatchStack>,
+	/// why there is no stack
+	error: Option<String>,
+	/// newest first, in the order the user arranged them
+	commits: Vec<(CommitInfo, StackAction)>,
+	selection: usize,
+	visible: bool,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl StackPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			repo: env.repo.clone(),
+			stack: None,
+			error: None,
+			commits: Vec::new(),
+			selection: 0,
+			visible: false,
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.selection = 0;
+		self.reload()?;
+
+		self.show()
+	}
+
+	fn reload(&mut self) -> Result<()> {
+		let stack = get_patch_stack(&self.repo.borrow());
+		match stack {
+			Ok(stack) => {
+				let commits = get_commits_info(
+					&self.repo.borrow(),
+					&stack.commits,
+					SUMMARY_LENGTH,
+				)?;
+
+				self.commits = commits
+					.into_iter()
+					.rev()
+					.map(|commit| (commit, StackAction::Pick))
+					.collect();
+				self.error = None;
+
+				let autosquash = stack.autosquash;
+				self.stack = Some(stack);
+				if autosquash {
+					self.autosquash()?;
+				}
+			}
+			Err(e) => {
+				self.commits.clear();
+				self.stack = None;
+				self.error = Some(e.to_string());
+			}
+		}
+
+		self.selection =
+			self.selection.min(self.commits.len().saturating_sub(1));
+
+		Ok(())
+	}
+
+	/// whether the commits got rearranged but not applied yet
+	fn reordered(&self) -> bool {
+		self.stack.as_ref().is_some_and(|stack| {
+			stack
+				.commits
+				.iter()
+				.rev()
+				.ne(self.commits.iter().map(|(commit, _)| &commit.id))
+				|| self
+					.commits
+					.iter()
+					.any(|(_, action)| *action != StackAction::Pick)
+		})
+	}
+
+	/// moves `fixup!` and `squash!` commits onto their targets
+	fn autosquash(&mut self) -> Result<()> {
+		let Some(stack) = &self.stack else {
+			return Ok(());
+		};
+
+		let todo = stack_autosquash_todo(&self.repo.borrow(), stack)?;
+
+		let mut commits = std::mem::take(&mut self.commits);
+		self.commits = todo
+			.iter()
+			.rev()
+			.filter_map(|t| {
+				let idx =
+					commits.iter().position(|(c, _)| c.id == t.id)?;
+				Some((commits.swap_remove(idx).0, t.action))
+			})
+			.collect();
+		self.selection = 0;
+
+		Ok(())
+	}
+
+	fn move_commit(&mut self, up: bool) {
+		let target = if up {
+			self.selection.checked_sub(1)
+		} else {
+			Some(self.selection + 1)
+				.filter(|idx| *idx < self.commits.len())
+		};
+
+		if let Some(target) = target {
+			self.commits.swap(self.selection, target);
+			self.selection = target;
+		}
+	}
+
+	fn apply_order(&mut self) -> Result<()> {
+		if !self.reordered() {
+			return Ok(());
+		}
+
+		let todo: Vec<_> = self
+			.commits
+			.iter()
+			.rev()
+			.map(|(commit, action)| StackTodo {
+				id: commit.id,
+				action: *action,
+			})
+			.collect();
+
+		try_or_popup!(
+			self,
+			"rewrite failed:",
+			stack_rewrite(&self.repo.borrow(), &todo)
+		);
+
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+		self.reload_or_show_conflicts()
+	}
+
+	fn fixup_selected(&mut self) -> Result<()> {
+		let Some((commit, _)) = self.commits.get(self.selection)
+		else {
+			return Ok(());
+		};
+
+		try_or_popup!(
+			self,
+			"fixup failed:",
+			stack_fixup(&self.repo.borrow(), commit.id)
+		);
+
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+		self.reload_or_show_conflicts()
+	}
+
+	fn rebase(&mut self) -> Result<()> {
+		let Some(stack) = &self.stack else {
+			return Ok(());
+		};
+
+		try_or_popup!(
+			self,
+			"rebase failed:",
+			sync::rebase_branch(
+				&self.repo.borrow(),
+				&stack.upstream,
+				stack.upstream_type,
+			)
+		);
+
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+		self.reload_or_show_conflicts()
+	}
+
+	/// conflicts are continued or aborted in the status tab
+	fn reload_or_show_conflicts(&mut self) -> Result<()> {
+		if sync::repo_state(&self.repo.borrow())? != RepoState::Clean
+		{
+			self.hide();
+			self.queue.push(InternalEvent::TabSwitchStatus);
+			return Ok(());
+		}
+
+		self.reload()
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let count = self.commits.len();
+		if count == 0 {
+			return;
+		}
+
+		self.selection = if up {
+			self.selection.checked_sub(1).unwrap_or(count - 1)
+		} else {
+			(self.selection + 1) % count
+		};
+	}
+
+	fn get_title(&self) -> String {
+		self.stack.as_ref().map_or_else(
+			|| strings::POPUP_TITLE_STACK.to_string(),
+			|stack| {
+				strings::stack_title(
+					&stack.branch,
+					&stack.upstream,
+					stack.behind,
+					self.reordered(),
+				)
+			},
+		)
+	}
+
+	fn get_text(&self) -> Vec<Line<'_>> {
+		if let Some(error) = &self.error {
+			return vec![Line::from(Span::styled(
+				error.as_str(),
+				self.theme.text(false, false),
+			))];
+		}
+
+		if self.commits.is_empty() {
+			return vec![Line::from(Span::styled(
+				strings::STACK_EMPTY,
+				self.theme.text(false, false),
+			))];
+		}
+
+		self.commits
+			.iter()
+			.enumerate()
+			.map(|(idx, (commit, action))| {
+				let selected = idx == self.selection;
+
+				Line::from(vec![
+					Span::styled(
+						strings::stack_action(*action),
+						self.theme.text(false, selected),
+					),
+					Span::styled(
+						format!("{} ", commit.id.get_short_string()),
+						self.theme.commit_hash(selected),
+					),
+					Span::styled(
+						commit.message.as_str(),
+						self.theme.text(true, selected),
+					),
+				])
+			})
+			.collect()
+	}
+}
+
+impl DrawableComponent for StackPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			const MIN_WIDTH: u16 = 50;
+			const MAX_WIDTH: u16 = 100;
+
+			let text = self.get_text();
+			let title = self.get_title();
+
+			let width = text
+				.iter()
+				.map(Line::width)
+				.chain(std::iter::once(title.chars().count()))
+				.max()
+				.and_then(|width| u16::try_from(width + 4).ok())
+				.unwrap_or_default()
+				.clamp(MIN_WIDTH, MAX_WIDTH)
+				.min(area.width);
+			let height = u16::try_from(text.len())
+				.unwrap_or_default()
+				.saturating_add(2)
+				.min(area.height);
+			let area =
+				ui::centered_rect_absolute(width, height, area);
+
+			// keep the selection in view of long stacks
+			let scroll = u16::try_from(self.selection)
+				.unwrap_or_default()
+				.saturating_sub(height.saturating_sub(3));
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(text).scroll((scroll, 0)).block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							title,
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for StackPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			let selected = self.commits.get(self.selection).is_some();
+			let reordered = self.reordered();
+			let behind =
+				self.stack.as_ref().is_some_and(|s| s.behind > 0);
+
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::stack_move(&self.key_config),
+					self.commits.len() > 1,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::stack_apply_order(
+						&self.key_config,
+					),
+					reordered,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::stack_autosquash(
+						&self.key_config,
+					),
+					self.commits.len() > 1,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::stack_fixup(&self.key_config),
+					selected && !reordered,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::stack_rebase(&self.key_config),
+					behind && !reordered,
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			match event {
+				Event::Key(key) => {
+					if key_match(key, self.key_config.keys.exit_popup)
+					{
+						self.hide();
+					} else if key_match(
+						key,
+						self.key_config.keys.shift_up,
+					) {
+						self.move_commit(true);
+					} else if key_match(
+						key,
+						self.key_config.keys.shift_down,
+					) {
+						self.move_commit(false);
+					} else if key_match(
+						key,
+						self.key_config.keys.move_up,
+					) {
+						self.move_selection(true);
+					} else if key_match(
+						key,
+						self.key_config.keys.move_down,
+					) {
+						self.move_selection(false);
+					} else if key_match(
+						key,
+						self.key_config.keys.enter,
+					) {
+						self.apply_order()?;
+					} else if key_match(
+						key,
+						self.key_config.keys.stack_autosquash,
+					) {
+						self.autosquash()?;
+					} else if !self.reordered() {
+						if key_match(
+							key,
+							self.key_config.keys.stack_fixup,
+						) {
+							self.fixup_selected()?;
+						} else if key_match(
+							key,
+							self.key_config.keys.rebase_branch,
+						) {
+							self.rebase()?;
+						}
+					}
+				}
+				Event::Mouse(mouse)
+					if mouse.kind
+						== MouseEventKind::Down(
+							MouseButton::Right,
+						) =>
+				{
+					self.hide();
+				}
+				_ => (),
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
@@ -10,7 +10,7 @@ use crate::{
 	tabs::StashingOptions,
 };
 use anyhow::Result;
-use asyncgit::sync::{self, RepoPathRef};
+use asyncgit::sync::{self, RepoPathRef, StashPushOptions};
 use crossterm::event::Event;
 use ratatui::{layout::Rect, Frame};
 
@@ -59,16 +59,33 @@ impl Component for StashMsgPopup {
 
 			if let Event::Key(e) = ev {
 				if key_match(e, self.key_config.keys.enter) {
-					let result = sync::stash_save(
-						&self.repo.borrow(),
-						if self.input.get_text().is_empty() {
-							None
-						} else {
-							Some(self.input.get_text())
-						},
-						self.options.stash_untracked,
-						self.options.keep_index,
-					);
+					let msg = if self.input.get_text().is_empty() {
+						None
+					} else {
+						Some(self.input.get_text())
+					};
+					let result = if self.options.paths.is_empty()
+						&& !self.options.untracked_only
+					{
+						sync::stash_save(
+							&self.repo.borrow(),
+							msg,
+							self.options.stash_untracked,
+							self.options.keep_index,
+						)
+					} else {
+						sync::stash_push_paths(
+							&self.repo.borrow(),
+							&self.options.paths,
+							&StashPushOptions {
+								message: msg.map(String::from),
+								keep_index: self.options.keep_index,
+								untracked_only: self
+									.options
+									.untracked_only,
+							},
+						)
+					};
 					match result {
 						Ok(_) => {
 							self.input.clear();
@@ -5,17 +5,21 @@ use crate::{
 		DrawableComponent, EventState, ScrollType, VerticalScroll,
 	},
 	keys::{key_match, SharedKeyConfig},
-	queue::{InternalEvent, NeedsUpdate, Queue},
+	queue::{Action, InternalEvent, NeedsUpdate, Queue},
+	string_utils::{pad_width, truncate_with},
 	strings, try_or_popup,
 	ui::{self, Size},
 };
 use anyhow::Result;
-use asyncgit::sync::{
-	get_submodules, repo_dir, submodule_parent_info,
-	update_submodule, RepoPathRef, SubmoduleInfo,
-	SubmoduleParentInfo,
+use asyncgit::{
+	asyncjob::AsyncSingleJob,
+	sync::{
+		get_submodules_recursive, repo_dir, submodule_parent_info,
+		RepoPath, RepoPathRef, SubmoduleInfo, SubmoduleParentInfo,
+	},
+	AsyncGitNotification, AsyncSubmoduleJob, SubmoduleOperation,
 };
-use crossterm::event::Event;
+use crossterm::event::{Event, KeyEvent};
 use ratatui::{
 	layout::{
 		Alignment, Constraint, Direction, Layout, Margin, Rect,
@@ -26,7 +30,6 @@ use ratatui::{
 };
 use std::cell::Cell;
 use ui::style::SharedTheme;
-use unicode_truncate::UnicodeTruncateStr;
 
 ///
 pub struct SubmodulesListPopup {
@@ -35,6 +38,7 @@ pub struct SubmodulesListPopup {
 	queue: Queue,
 	submodules: Vec<SubmoduleInfo>,
 	submodule_parent: Option<SubmoduleParentInfo>,
+	async_submodule: AsyncSingleJob<AsyncSubmoduleJob>,
 	visible: bool,
 	current_height: Cell<u16>,
 	selection: u16,
@@ -91,6 +95,15 @@ impl DrawableComponent for SubmodulesListPopup {
 			self.draw_list(f, chunks[0])?;
 			self.draw_info(f, chunks[1]);
 			self.draw_local_info(f, chunks_vertical[1]);
+
+			if self.is_pending() {
+				ui::draw_progress(
+					f,
+					strings::SUBMODULE_POPUP_MSG,
+					self.async_submodule.progress().as_ref(),
+					&self.theme,
+				);
+			}
 		}
 
 		Ok(())
@@ -126,9 +139,36 @@ impl Component for SubmodulesListPopup {
 				true,
 			));
 
+			let can_run =
+				self.is_valid_selection() && !self.is_pending();
+
 			out.push(CommandInfo::new(
 				strings::commands::update_submodule(&self.key_config),
-				self.is_valid_selection(),
+				can_run,
+				true,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::submodule_init(&self.key_config),
+				can_run,
+				true,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::submodule_sync(&self.key_config),
+				can_run,
+				true,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::submodule_add(&self.key_config),
+				!self.is_pending(),
+				true,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::submodule_remove(&self.key_config),
+				self.can_remove_submodule(),
 				true,
 			));
 
@@ -149,6 +189,10 @@ impl Component for SubmodulesListPopup {
 		}
 
 		if let Event::Key(e) = ev {
+			if self.operation_event(e) {
+				return Ok(EventState::Consumed);
+			}
+
 			if key_match(e, self.key_config.keys.exit_popup) {
 				self.hide();
 			} else if key_match(e, self.key_config.keys.move_down) {
@@ -183,26 +227,6 @@ impl Component for SubmodulesListPopup {
 						});
 					}
 				}
-			} else if key_match(
-				e,
-				self.key_config.keys.update_submodule,
-			) {
-				if let Some(submodule) = self.selected_entry() {
-					try_or_popup!(
-						self,
-						"update submodule:",
-						update_submodule(
-							&self.repo.borrow(),
-							&submodule.name,
-						)
-					);
-
-					self.update_submodules()?;
-
-					self.queue.push(InternalEvent::Update(
-						NeedsUpdate::ALL,
-					));
-				}
 			} else if key_match(
 				e,
 				self.key_config.keys.view_submodule_parent,
@@ -244,6 +268,9 @@ impl SubmodulesListPopup {
 		Self {
 			submodules: Vec::new(),
 			submodule_parent: None,
+			async_submodule: AsyncSingleJob::new(
+				env.sender_git.clone(),
+			),
 			scroll: VerticalScroll::new(),
 			queue: env.queue.clone(),
 			selection: 0,
@@ -267,7 +294,8 @@ impl SubmodulesListPopup {
 	///
 	pub fn update_submodules(&mut self) -> Result<()> {
 		if self.is_visible() {
-			self.submodules = get_submodules(&self.repo.borrow())?;
+			self.submodules =
+				get_submodules_recursive(&self.repo.borrow())?;
 
 			self.submodule_parent =
 				submodule_parent_info(&self.repo.borrow())?;
@@ -281,6 +309,117 @@ impl SubmodulesListPopup {
 		Ok(())
 	}
 
+	/// runs `operation` on the current repo in the background
+	pub fn run_operation(&self, operation: SubmoduleOperation) {
+		let repo = self.repo.borrow().clone();
+		self.spawn(repo, operation);
+	}
+
+	///
+	pub fn update_git(
+		&mut self,
+		ev: AsyncGitNotification,
+	) -> Result<()> {
+		if ev != AsyncGitNotification::Submodules {
+			return Ok(());
+		}
+
+		if let Some(job) = self.async_submodule.take_last() {
+			if let Some(Err(e)) = job.result() {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("submodule error:\n{e}"),
+				));
+			}
+
+			self.update_submodules()?;
+
+			self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+		}
+
+		Ok(())
+	}
+
+	fn spawn(&self, repo: RepoPath, operation: SubmoduleOperation) {
+		if !self.is_pending() {
+			self.async_submodule
+				.spawn(AsyncSubmoduleJob::new(repo, operation));
+		}
+	}
+
+	/// returns whether `e` triggered a submodule operation
+	fn operation_event(&self, e: &KeyEvent) -> bool {
+		if key_match(e, self.key_config.keys.update_submodule) {
+			try_or_popup!(
+				self,
+				"update submodule:",
+				self.run_on_selected(|name| {
+					SubmoduleOperation::Update {
+						name,
+						recursive: true,
+					}
+				})
+			);
+		} else if key_match(e, self.key_config.keys.submodule_init) {
+			try_or_popup!(
+				self,
+				"init submodule:",
+				self.run_on_selected(SubmoduleOperation::Init)
+			);
+		} else if key_match(e, self.key_config.keys.submodule_sync) {
+			try_or_popup!(
+				self,
+				"sync submodule:",
+				self.run_on_selected(SubmoduleOperation::Sync)
+			);
+		} else if key_match(e, self.key_config.keys.submodule_add) {
+			if !self.is_pending() {
+				self.queue.push(InternalEvent::AddSubmodule);
+			}
+		} else if key_match(e, self.key_config.keys.submodule_remove)
+		{
+			if self.can_remove_submodule() {
+				if let Some(submodule) = self.selected_entry() {
+					self.queue.push(InternalEvent::ConfirmAction(
+						Action::RemoveSubmodule(
+							submodule.name.clone(),
+						),
+					));
+				}
+			}
+		} else {
+			return false;
+		}
+
+		true
+	}
+
+	/// nested submodules are handled by the repo containing them
+	fn run_on_selected(
+		&self,
+		operation: impl FnOnce(String) -> SubmoduleOperation,
+	) -> Result<()> {
+		if let Some(submodule) = self.selected_entry() {
+			let name = submodule.name.clone();
+			let repo = submodule
+				.get_parent_repo_path(&self.repo.borrow())?;
+
+			self.spawn(repo, operation(name));
+		}
+
+		Ok(())
+	}
+
+	fn is_pending(&self) -> bool {
+		self.async_submodule.is_pending()
+	}
+
+	fn can_remove_submodule(&self) -> bool {
+		!self.is_pending()
+			&& self
+				.selected_entry()
+				.is_some_and(|s| s.parent.is_none())
+	}
+
 	fn selected_entry(&self) -> Option<&SubmoduleInfo> {
 		self.submodules.get(self.selection as usize)
 	}
@@ -354,18 +493,11 @@ impl SubmodulesListPopup {
 			.take(height)
 			.enumerate()
 		{
-			let mut module_path = submodule
-				.path
-				.as_os_str()
-				.to_string_lossy()
-				.to_string();
-
-			if module_path.len() > name_length {
-				module_path.unicode_truncate(
-					name_length.saturating_sub(THREE_DOTS_LENGTH),
-				);
-				module_path += THREE_DOTS;
-			}
+			let module_path = truncate_with(
+				&submodule.path.to_string_lossy(),
+				name_length,
+				THREE_DOTS,
+			);
 
 			let selected = (self.selection as usize
 				- self.scroll.get_top())
@@ -383,7 +515,7 @@ impl SubmodulesListPopup {
 			);
 
 			let span_name = Span::styled(
-				format!("{module_path:name_length$} "),
+				format!("{} ", pad_width(&module_path, name_length)),
 				theme.text(true, selected),
 			);
 
@@ -20,9 +20,10 @@ use asyncgit::{
 		BasicAuthCredential,
 	},
 	sync::{
-		self, get_tags_with_metadata, RepoPathRef, TagWithMetadata,
+		self, get_tag_sort, RepoPathRef, TagSort, TagSortKey,
+		TagWithMetadata,
 	},
-	AsyncGitNotification,
+	AsyncGitNotification, AsyncTagListJob,
 };
 
 use crossterm::event::Event;
@@ -43,6 +44,7 @@ pub struct TagListPopup {
 	theme: SharedTheme,
 	queue: Queue,
 	tags: Option<Vec<TagWithMetadata>>,
+	sort: TagSort,
 	visible: bool,
 	table_state: std::cell::Cell<TableState>,
 	current_height: std::cell::Cell<usize>,
@@ -50,6 +52,7 @@ pub struct TagListPopup {
 	has_remotes: bool,
 	basic_credential: Option<BasicAuthCredential>,
 	async_remote_tags: AsyncSingleJob<AsyncRemoteTagsJob>,
+	async_tags: AsyncSingleJob<AsyncTagListJob>,
 	key_config: SharedKeyConfig,
 }
 
@@ -81,10 +84,12 @@ impl DrawableComponent for TagListPopup {
 				Constraint::Length(tag_name_width.try_into()?),
 				// commit date
 				Constraint::Length(10),
-				// author width
+				// tagger or author width
 				Constraint::Length(19),
 				// attachment
 				Constraint::Length(1),
+				// signature
+				Constraint::Length(1),
 				// commit id
 				Constraint::Percentage(100),
 			];
@@ -111,20 +116,22 @@ impl DrawableComponent for TagListPopup {
 			f.render_widget(Clear, area);
 			f.render_stateful_widget(table, area, &mut table_state);
 
-			let area = area.inner(Margin {
-				vertical: 1,
-				horizontal: 0,
-			});
-
 			ui::draw_scrollbar(
 				f,
 				area,
 				&self.theme,
-				number_of_rows,
-				table_state.selected().unwrap_or(0),
+				number_of_rows.saturating_sub(usize::from(
+					area.height.saturating_sub(2),
+				)),
+				table_state.offset(),
 				ui::Orientation::Vertical,
 			);
 
+			let area = area.inner(Margin {
+				vertical: 1,
+				horizontal: 0,
+			});
+
 			self.table_state.set(table_state);
 			self.current_height.set(area.height.into());
 		}
@@ -171,6 +178,14 @@ impl Component for TagListPopup {
 				self.has_remotes,
 				true,
 			));
+			out.push(CommandInfo::new(
+				strings::commands::sort_tags(
+					&self.key_config,
+					self.sort.key,
+				),
+				true,
+				true,
+			));
 			out.push(CommandInfo::new(
 				strings::commands::show_tag_annotation(
 					&self.key_config,
@@ -261,6 +276,11 @@ impl Component for TagListPopup {
 					&& self.has_remotes
 				{
 					self.queue.push(InternalEvent::PushTags);
+				} else if key_match(
+					key,
+					self.key_config.keys.tag_sort,
+				) {
+					self.cycle_sort();
 				}
 			}
 
@@ -291,6 +311,7 @@ impl TagListPopup {
 			theme: env.theme.clone(),
 			queue: env.queue.clone(),
 			tags: None,
+			sort: TagSort::default(),
 			visible: false,
 			has_remotes: false,
 			table_state: std::cell::Cell::new(TableState::default()),
@@ -300,6 +321,7 @@ impl TagListPopup {
 			async_remote_tags: AsyncSingleJob::new(
 				env.sender_git.clone(),
 			),
+			async_tags: AsyncSingleJob::new(env.sender_git.clone()),
 			key_config: env.key_config.clone(),
 			repo: env.repo.clone(),
 		}
@@ -333,7 +355,8 @@ impl TagListPopup {
 
 		self.basic_credential = basic_credential;
 
-		self.update_tags()?;
+		self.sort = get_tag_sort(&self.repo.borrow())?;
+		self.update_tags();
 		self.update_missing_remote_tags();
 
 		Ok(())
@@ -342,6 +365,21 @@ impl TagListPopup {
 	///
 	pub fn update(&mut self, ev: AsyncNotification) {
 		if matches!(
+			ev,
+			AsyncNotification::Git(AsyncGitNotification::TagList)
+		) {
+			if let Some(job) = self.async_tags.take_last() {
+				match job.result() {
+					Some(Ok(tags)) => self.set_tags(tags),
+					Some(Err(e)) => {
+						self.queue.push(InternalEvent::ShowErrorMsg(
+							format!("tags failed:\n{e}"),
+						));
+					}
+					None => (),
+				}
+			}
+		} else if matches!(
 			ev,
 			AsyncNotification::Git(AsyncGitNotification::RemoteTags)
 		) {
@@ -362,15 +400,46 @@ impl TagListPopup {
 	///
 	pub fn any_work_pending(&self) -> bool {
 		self.async_remote_tags.is_pending()
+			|| self.async_tags.is_pending()
+	}
+
+	/// fetch list of tags in the background
+	pub fn update_tags(&self) {
+		self.async_tags
+			.spawn(AsyncTagListJob::new(self.repo.borrow().clone()));
 	}
 
-	/// fetch list of tags
-	pub fn update_tags(&mut self) -> Result<()> {
-		let tags = get_tags_with_metadata(&self.repo.borrow())?;
+	fn set_tags(&mut self, mut tags: Vec<TagWithMetadata>) {
+		self.sort.sort(&mut tags);
+
+		// the list may have shrunk since the last update
+		let table_state = self.table_state.get_mut();
+		if let Some(selected) = table_state.selected() {
+			table_state.select(Some(
+				selected.min(tags.len().saturating_sub(1)),
+			));
+		}
 
 		self.tags = Some(tags);
+	}
 
-		Ok(())
+	/// switches to the next sort key, newest/highest first except
+	/// for names
+	fn cycle_sort(&mut self) {
+		let key = match self.sort.key {
+			TagSortKey::CommitDate => TagSortKey::CreatorDate,
+			TagSortKey::CreatorDate => TagSortKey::Version,
+			TagSortKey::Version => TagSortKey::Name,
+			TagSortKey::Name => TagSortKey::CommitDate,
+		};
+		self.sort = TagSort {
+			key,
+			descending: key != TagSortKey::Name,
+		};
+
+		if let Some(tags) = self.tags.as_mut() {
+			self.sort.sort(tags);
+		}
 	}
 
 	pub fn update_missing_remote_tags(&self) {
@@ -442,6 +511,7 @@ impl TagListPopup {
 	fn get_row(&self, tag: &TagWithMetadata) -> Row<'_> {
 		const UPSTREAM_SYMBOL: &str = "\u{2191}";
 		const ATTACHMENT_SYMBOL: &str = "@";
+		const SIGNED_SYMBOL: &str = "\u{2713}";
 		const EMPTY_SYMBOL: &str = " ";
 
 		let is_tag_missing_on_remote = self
@@ -465,6 +535,12 @@ impl TagListPopup {
 			EMPTY_SYMBOL
 		};
 
+		let signed_str = if tag.signed {
+			SIGNED_SYMBOL
+		} else {
+			EMPTY_SYMBOL
+		};
+
 		let cells: Vec<Cell> = vec![
 			Cell::from(has_remote_str)
 				.style(self.theme.commit_author(false)),
@@ -472,10 +548,14 @@ impl TagListPopup {
 				.style(self.theme.text(true, false)),
 			Cell::from(time_to_string(tag.time, true))
 				.style(self.theme.commit_time(false)),
-			Cell::from(tag.author.clone())
-				.style(self.theme.commit_author(false)),
+			Cell::from(
+				tag.tagger.as_ref().unwrap_or(&tag.author).clone(),
+			)
+			.style(self.theme.commit_author(false)),
 			Cell::from(has_attachment_str)
 				.style(self.theme.text_danger()),
+			Cell::from(signed_str)
+				.style(self.theme.commit_hash(false)),
 			Cell::from(tag.message.clone())
 				.style(self.theme.text(true, false)),
 		];
@@ -0,0 +1,277 @@
+use crate::{
+	app::Environment,
+	args::get_app_config_path,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, Queue},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use ratatui::{
+	layout::{Margin, Rect},
+	text::Span,
+	widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+	Frame,
+};
+use std::{cell::Cell, fs, path::PathBuf};
+
+/// created in the config dir once the tour was shown on first launch
+const TOUR_MARKER_FILE: &str = "tour_seen";
+
+/// part of the ui a step of the tour points at
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TourPane {
+	Tabs,
+	Unstaged,
+	Staged,
+	CommandBar,
+}
+
+const STEPS: [TourPane; 4] = [
+	TourPane::Tabs,
+	TourPane::Unstaged,
+	TourPane::Staged,
+	TourPane::CommandBar,
+];
+
+/// onboarding overlay walking through the basic workflow
+pub struct TourPopup {
+	step: usize,
+	visible: bool,
+	highlight: Cell<Option<Rect>>,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl TourPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			step: 0,
+			visible: false,
+			highlight: Cell::new(None),
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	/// starts the tour unless it was already shown on an earlier
+	/// launch
+	pub fn open_on_first_run(&mut self) -> Result<()> {
+		let Ok(marker) = Self::marker_file() else {
+			return Ok(());
+		};
+
+		if marker.exists() {
+			return Ok(());
+		}
+
+		if let Err(e) = fs::write(&marker, "") {
+			log::error!("tour marker error: {e}");
+			return Ok(());
+		}
+
+		self.open()
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.step = 0;
+		self.queue.push(InternalEvent::TabSwitchStatus);
+
+		self.show()
+	}
+
+	/// pane the current step points at, `None` while hidden
+	pub fn pane(&self) -> Option<TourPane> {
+		self.is_visible().then(|| STEPS[self.step])
+	}
+
+	/// area of the `pane` in the last drawn frame
+	pub fn set_highlight(&self, area: Option<Rect>) {
+		self.highlight.set(area);
+	}
+
+	fn marker_file() -> Result<PathBuf> {
+		Ok(get_app_config_path()?.join(TOUR_MARKER_FILE))
+	}
+
+	fn next(&mut self) {
+		if self.step + 1 < STEPS.len() {
+			self.step += 1;
+		} else {
+			self.hide();
+		}
+	}
+
+	fn back(&mut self) {
+		self.step = self.step.saturating_sub(1);
+	}
+
+	fn content(&self) -> (&'static str, String) {
+		match STEPS[self.step] {
+			TourPane::Tabs => (
+				strings::TOUR_TITLE_TABS,
+				strings::tour_tabs(&self.key_config),
+			),
+			TourPane::Unstaged => (
+				strings::TOUR_TITLE_STAGING,
+				strings::tour_staging(&self.key_config),
+			),
+			TourPane::Staged => (
+				strings::TOUR_TITLE_COMMITTING,
+				strings::tour_committing(&self.key_config),
+			),
+			TourPane::CommandBar => (
+				strings::TOUR_TITLE_PUSHING,
+				strings::tour_pushing(&self.key_config),
+			),
+		}
+	}
+}
+
+impl DrawableComponent for TourPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			const SIZE: (u16, u16) = (50, 8);
+
+			let highlight = self.highlight.get();
+
+			if let Some(highlight) = highlight {
+				let highlight = highlight.intersection(f.area());
+				f.buffer_mut().set_style(
+					highlight,
+					self.theme.attention_block(),
+				);
+			}
+
+			// keep the text clear of the panes on the left
+			let text_area = match highlight {
+				Some(highlight)
+					if area
+						.right()
+						.saturating_sub(highlight.right())
+						>= SIZE.0 =>
+				{
+					Rect {
+						x: highlight.right(),
+						width: area.right() - highlight.right(),
+						..area
+					}
+				}
+				_ => area,
+			};
+			let centered =
+				ui::centered_rect_absolute(SIZE.0, SIZE.1, text_area);
+			let text_area = Rect {
+				x: text_area.x + centered.x,
+				y: text_area.y + centered.y,
+				..centered
+			};
+
+			let (title, text) = self.content();
+
+			f.render_widget(Clear, text_area);
+			f.render_widget(
+				Block::default()
+					.borders(Borders::ALL)
+					.border_type(BorderType::Thick)
+					.border_style(self.theme.block(true))
+					.title(Span::styled(
+						strings::tour_title(
+							title,
+							self.step + 1,
+							STEPS.len(),
+						),
+						self.theme.title(true),
+					)),
+				text_area,
+			);
+			f.render_widget(
+				Paragraph::new(text).wrap(Wrap { trim: true }),
+				text_area.inner(Margin {
+					horizontal: 1,
+					vertical: 1,
+				}),
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for TourPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			if !force_all {
+				out.clear();
+			}
+
+			out.push(CommandInfo::new(
+				strings::commands::tour_next(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::tour_back(&self.key_config),
+				self.step > 0,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::close_popup(&self.key_config),
+				true,
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, ev: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = ev {
+				if key_match(key, self.key_config.keys.exit_popup) {
+					self.hide();
+				} else if key_match(key, self.key_config.keys.enter)
+					|| key_match(key, self.key_config.keys.move_right)
+				{
+					self.next();
+				} else if key_match(
+					key,
+					self.key_config.keys.move_left,
+				) {
+					self.back();
+				}
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
@@ -0,0 +1,285 @@
+use crate::{
+	app::Environment,
+	components::{
+		time_to_string, visibility_blocking, CommandBlocking,
+		CommandInfo, Component, DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	queue::{InternalEvent, NeedsUpdate, Queue},
+	strings, try_or_popup,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::sync::{
+	trash_drop, trash_list, trash_restore, RepoPathRef, TrashEntry,
+};
+use crossterm::event::{Event, MouseButton, MouseEventKind};
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+/// lists what discards moved to the trash and restores it
+pub struct TrashPopup {
+	repo: RepoPathRef,
+	entries: Vec<TrashEntry>,
+	selection: usize,
+	visible: bool,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl TrashPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			repo: env.repo.clone(),
+			entries: Vec::new(),
+			selection: 0,
+			visible: false,
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.selection = 0;
+		self.reload()?;
+
+		self.show()
+	}
+
+	fn reload(&mut self) -> Result<()> {
+		self.entries = trash_list(&self.repo.borrow())?;
+		self.selection =
+			self.selection.min(self.entries.len().saturating_sub(1));
+
+		Ok(())
+	}
+
+	fn restore_selected(&mut self) -> Result<()> {
+		let Some(entry) = self.entries.get(self.selection) else {
+			return Ok(());
+		};
+
+		try_or_popup!(
+			self,
+			"restore failed:",
+			trash_restore(&self.repo.borrow(), &entry.id)
+		);
+
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+		self.reload()
+	}
+
+	fn drop_selected(&mut self) -> Result<()> {
+		let Some(entry) = self.entries.get(self.selection) else {
+			return Ok(());
+		};
+
+		try_or_popup!(
+			self,
+			"drop failed:",
+			trash_drop(&self.repo.borrow(), &entry.id)
+		);
+
+		self.reload()
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let count = self.entries.len();
+		if count == 0 {
+			return;
+		}
+
+		self.selection = if up {
+			self.selection.checked_sub(1).unwrap_or(count - 1)
+		} else {
+			(self.selection + 1) % count
+		};
+	}
+
+	fn get_text(&self) -> Vec<Line<'_>> {
+		if self.entries.is_empty() {
+			return vec![Line::from(Span::styled(
+				strings::TRASH_EMPTY,
+				self.theme.text(false, false),
+			))];
+		}
+
+		self.entries
+			.iter()
+			.enumerate()
+			.map(|(idx, entry)| {
+				let selected = idx == self.selection;
+
+				Line::from(vec![
+					Span::styled(
+						format!(
+							"{}  ",
+							time_to_string(entry.time, false)
+						),
+						self.theme.commit_time(selected),
+					),
+					Span::styled(
+						entry.files.join(", "),
+						self.theme.text(true, selected),
+					),
+				])
+			})
+			.collect()
+	}
+}
+
+impl DrawableComponent for TrashPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			const MIN_WIDTH: u16 = 40;
+			const MAX_WIDTH: u16 = 100;
+
+			let text = self.get_text();
+
+			let width = text
+				.iter()
+				.map(Line::width)
+				.max()
+				.and_then(|width| u16::try_from(width + 2).ok())
+				.unwrap_or_default()
+				.clamp(MIN_WIDTH, MAX_WIDTH)
+				.min(area.width);
+			let height = u16::try_from(text.len())
+				.unwrap_or_default()
+				.saturating_add(2)
+				.min(area.height);
+			let area =
+				ui::centered_rect_absolute(width, height, area);
+
+			// keep the selection in view of long lists
+			let scroll = u16::try_from(self.selection)
+				.unwrap_or_default()
+				.saturating_sub(height.saturating_sub(3));
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(text).scroll((scroll, 0)).block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							strings::POPUP_TITLE_TRASH,
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for TrashPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			let selected = self.entries.get(self.selection).is_some();
+
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::trash_restore(
+						&self.key_config,
+					),
+					selected,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::trash_drop(&self.key_config),
+					selected,
+					true,
+				)
+				.order(1),
+			);
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			match event {
+				Event::Key(key) => {
+					if key_match(key, self.key_config.keys.exit_popup)
+					{
+						self.hide();
+					} else if key_match(
+						key,
+						self.key_config.keys.move_up,
+					) {
+						self.move_selection(true);
+					} else if key_match(
+						key,
+						self.key_config.keys.move_down,
+					) {
+						self.move_selection(false);
+					} else if key_match(
+						key,
+						self.key_config.keys.enter,
+					) {
+						self.restore_selected()?;
+					} else if key_match(
+						key,
+						self.key_config.keys.stash_drop,
+					) {
+						self.drop_selected()?;
+					}
+				}
+				Event::Mouse(mouse)
+					if mouse.kind
+						== MouseEventKind::Down(
+							MouseButton::Right,
+						) =>
+				{
+					self.hide();
+				}
+				_ => (),
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
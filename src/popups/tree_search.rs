@@ -0,0 +1,409 @@
+use crate::components::{
+	visibility_blocking, CommandBlocking, CommandInfo, Component,
+	DrawableComponent, EventState, InputType, ScrollType,
+	TextInputComponent,
+};
+use crate::{
+	app::Environment,
+	keys::{key_match, SharedKeyConfig},
+	popups::BlameFileOpen,
+	queue::{InternalEvent, Queue, StackablePopupOpen},
+	strings,
+	ui::{self, style::SharedTheme},
+	AsyncNotification,
+};
+use anyhow::Result;
+use asyncgit::{
+	asyncjob::AsyncSingleJob,
+	sync::{CommitId, RepoPathRef, TreeSearchMatch},
+	AsyncGitNotification, AsyncTreeSearchJob,
+};
+use crossterm::event::Event;
+use ratatui::{
+	layout::{Constraint, Direction, Layout, Margin, Rect},
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear},
+	Frame,
+};
+
+#[derive(Clone, Debug)]
+pub struct TreeSearchOpen {
+	pub commit_id: CommitId,
+	/// searched before, to come back to the same matches
+	pub pattern: Option<String>,
+	pub selection: usize,
+}
+
+impl TreeSearchOpen {
+	pub const fn new(commit_id: CommitId) -> Self {
+		Self {
+			commit_id,
+			pattern: None,
+			selection: 0,
+		}
+	}
+}
+
+/// searches the file contents of a commit, opening the blame of a
+/// match at its line
+pub struct TreeSearchPopup {
+	repo: RepoPathRef,
+	queue: Queue,
+	visible: bool,
+	commit: Option<CommitId>,
+	find_text: TextInputComponent,
+	/// pattern `matches` were found for
+	searched: Option<String>,
+	matches: Vec<TreeSearchMatch>,
+	selection: usize,
+	async_search: AsyncSingleJob<AsyncTreeSearchJob>,
+	theme: SharedTheme,
+	key_config: SharedKeyConfig,
+}
+
+impl TreeSearchPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		let mut find_text = TextInputComponent::new(
+			env,
+			"",
+			"text to search, enter to start..",
+			false,
+		)
+		.with_input_type(InputType::Singleline);
+		find_text.embed();
+
+		Self {
+			repo: env.repo.clone(),
+			queue: env.queue.clone(),
+			visible: false,
+			commit: None,
+			find_text,
+			searched: None,
+			matches: Vec::new(),
+			selection: 0,
+			async_search: AsyncSingleJob::new(env.sender_git.clone()),
+			theme: env.theme.clone(),
+			key_config: env.key_config.clone(),
+		}
+	}
+
+	///
+	pub fn open(&mut self, open: TreeSearchOpen) -> Result<()> {
+		if self.commit != Some(open.commit_id) {
+			self.commit = Some(open.commit_id);
+			self.searched = None;
+			self.matches.clear();
+		}
+
+		let pattern = open.pattern.unwrap_or_default();
+		if self.searched.as_deref() != Some(pattern.as_str()) {
+			self.searched = None;
+			self.matches.clear();
+		}
+		self.find_text.set_text(pattern);
+		self.find_text.show()?;
+		self.selection =
+			open.selection.min(self.matches.len().saturating_sub(1));
+
+		if self.searched.is_none() {
+			self.search();
+		}
+
+		self.show()
+	}
+
+	///
+	pub fn any_work_pending(&self) -> bool {
+		self.async_search.is_pending()
+	}
+
+	///
+	pub fn update(&mut self, ev: AsyncNotification) {
+		if ev
+			!= AsyncNotification::Git(
+				AsyncGitNotification::TreeSearch,
+			) {
+			return;
+		}
+
+		let Some(result) = self
+			.async_search
+			.take_last()
+			.and_then(|job| job.result())
+		else {
+			return;
+		};
+
+		// a later search is on its way
+		if Some(result.commit) != self.commit
+			|| self.find_text.get_text() != result.pattern
+		{
+			return;
+		}
+
+		match result.result {
+			Ok(matches) => {
+				self.matches = matches;
+				self.searched = Some(result.pattern);
+				self.selection = 0;
+			}
+			Err(e) => {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("search error:\n{e}"),
+				));
+			}
+		}
+	}
+
+	fn search(&self) {
+		let pattern = self.find_text.get_text();
+
+		if let Some(commit) = self.commit {
+			if !pattern.is_empty() {
+				self.async_search.spawn(AsyncTreeSearchJob::new(
+					self.repo.borrow().clone(),
+					commit,
+					pattern.to_string(),
+				));
+			}
+		}
+	}
+
+	/// `true` if the matches belong to what was typed
+	fn is_current(&self) -> bool {
+		self.searched.as_deref() == Some(self.find_text.get_text())
+	}
+
+	fn open_selected(&mut self) {
+		let (Some(commit), Some(found)) =
+			(self.commit, self.matches.get(self.selection).cloned())
+		else {
+			return;
+		};
+
+		self.hide();
+
+		self.queue.push(InternalEvent::PopupStackPush(
+			StackablePopupOpen::TreeSearch(TreeSearchOpen {
+				commit_id: commit,
+				pattern: self.searched.clone(),
+				selection: self.selection,
+			}),
+		));
+		self.queue.push(InternalEvent::OpenPopup(
+			StackablePopupOpen::BlameFile(BlameFileOpen {
+				file_path: found.path.clone(),
+				commit_id: Some(commit),
+				selection: Some(found.line.saturating_sub(1)),
+			}),
+		));
+	}
+
+	fn move_selection(&mut self, move_type: ScrollType) {
+		let max = self.matches.len().saturating_sub(1);
+
+		self.selection = match move_type {
+			ScrollType::Up => self.selection.saturating_sub(1),
+			ScrollType::Down => self.selection.saturating_add(1),
+			ScrollType::PageUp => self.selection.saturating_sub(10),
+			ScrollType::PageDown => self.selection.saturating_add(10),
+			_ => self.selection,
+		}
+		.min(max);
+	}
+
+	fn title(&self) -> String {
+		if self.async_search.is_pending() {
+			String::from(strings::TREE_SEARCH_PENDING)
+		} else if self.is_current() {
+			strings::tree_search_hits(self.matches.len())
+		} else {
+			String::new()
+		}
+	}
+
+	fn draw_matches(&self, f: &mut Frame, area: Rect) {
+		// the block's top border
+		let height = usize::from(area.height.saturating_sub(1));
+		let width = usize::from(area.width);
+
+		let scroll_skip = (self.selection + 1).saturating_sub(height);
+
+		let items = self
+			.matches
+			.iter()
+			.enumerate()
+			.skip(scroll_skip)
+			.take(height)
+			.map(|(idx, found)| {
+				let selected = idx == self.selection;
+				let location =
+					format!("{}:{}: ", found.path, found.line);
+				let content: String = found
+					.content
+					.trim()
+					.chars()
+					.take(width.saturating_sub(location.len()))
+					.collect();
+
+				Line::from(vec![
+					Span::styled(
+						location,
+						self.theme.commit_hash(selected),
+					),
+					Span::styled(
+						content,
+						self.theme.text(true, selected),
+					),
+				])
+			});
+
+		ui::draw_list_block(
+			f,
+			area,
+			Block::default()
+				.title(Span::styled(
+					self.title(),
+					self.theme.title(true),
+				))
+				.borders(Borders::TOP),
+			items,
+		);
+	}
+}
+
+impl DrawableComponent for TreeSearchPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			let area = ui::centered_rect(80, 80, area);
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Block::default()
+					.borders(Borders::all())
+					.style(self.theme.title(true))
+					.title(Span::styled(
+						strings::tree_search_title(
+							self.commit.as_ref(),
+						),
+						self.theme.title(true),
+					)),
+				area,
+			);
+
+			let chunks = Layout::default()
+				.direction(Direction::Vertical)
+				.constraints(
+					[
+						Constraint::Length(1),
+						Constraint::Percentage(100),
+					]
+					.as_ref(),
+				)
+				.split(area.inner(Margin {
+					horizontal: 1,
+					vertical: 1,
+				}));
+
+			self.find_text.draw(f, chunks[0])?;
+			self.draw_matches(f, chunks[1]);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for TreeSearchPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(CommandInfo::new(
+				strings::commands::scroll_popup(&self.key_config),
+				!self.matches.is_empty(),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::tree_search_confirm(
+					&self.key_config,
+					self.is_current(),
+				),
+				!self.find_text.get_text().is_empty(),
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			if let Event::Key(key) = event {
+				if key_match(key, self.key_config.keys.exit_popup) {
+					self.hide();
+					self.queue.push(InternalEvent::PopupStackPop);
+					return Ok(EventState::Consumed);
+				} else if key_match(key, self.key_config.keys.enter) {
+					if self.is_current() {
+						self.open_selected();
+					} else {
+						self.search();
+					}
+					return Ok(EventState::Consumed);
+				} else if key_match(
+					key,
+					self.key_config.keys.popup_down,
+				) {
+					self.move_selection(ScrollType::Down);
+					return Ok(EventState::Consumed);
+				} else if key_match(
+					key,
+					self.key_config.keys.popup_up,
+				) {
+					self.move_selection(ScrollType::Up);
+					return Ok(EventState::Consumed);
+				} else if key_match(
+					key,
+					self.key_config.keys.page_down,
+				) {
+					self.move_selection(ScrollType::PageDown);
+					return Ok(EventState::Consumed);
+				} else if key_match(key, self.key_config.keys.page_up)
+				{
+					self.move_selection(ScrollType::PageUp);
+					return Ok(EventState::Consumed);
+				}
+			}
+
+			self.find_text.event(event)?;
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+		Ok(())
+	}
+}
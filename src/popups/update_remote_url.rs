@@ -140,7 +140,7 @@ impl UpdateRemoteUrlPopup {
 				Err(e) => {
 					log::error!("update remote url: {e}");
 					self.queue.push(InternalEvent::ShowErrorMsg(
-						format!("update remote url error:\n{e}",),
+						format!("update remote url error:\n{e}"),
 					));
 				}
 			}
@@ -0,0 +1,454 @@
+use crate::{
+	app::Environment,
+	components::{
+		visibility_blocking, CommandBlocking, CommandInfo, Component,
+		DrawableComponent, EventState,
+	},
+	keys::{key_match, SharedKeyConfig},
+	queue::{Action, InternalEvent, Queue},
+	string_utils::{pad_width, str_width},
+	strings,
+	ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+	asyncjob::AsyncSingleJob,
+	sync::{self, RepoPathRef, WorktreeInfo},
+	AsyncGitNotification, AsyncWorktreesJob,
+};
+use crossterm::event::{Event, MouseButton, MouseEventKind};
+use ratatui::{
+	layout::Rect,
+	text::{Line, Span},
+	widgets::{Block, Borders, Clear, Paragraph},
+	Frame,
+};
+
+pub struct WorktreesPopup {
+	repo: RepoPathRef,
+	worktrees: Vec<WorktreeInfo>,
+	selection: usize,
+	/// select the current worktree once the list arrives
+	select_current: bool,
+	async_worktrees: AsyncSingleJob<AsyncWorktreesJob>,
+	visible: bool,
+	queue: Queue,
+	key_config: SharedKeyConfig,
+	theme: SharedTheme,
+}
+
+impl WorktreesPopup {
+	///
+	pub fn new(env: &Environment) -> Self {
+		Self {
+			repo: env.repo.clone(),
+			worktrees: Vec::new(),
+			selection: 0,
+			select_current: false,
+			async_worktrees: AsyncSingleJob::new(
+				env.sender_git.clone(),
+			),
+			visible: false,
+			queue: env.queue.clone(),
+			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
+		}
+	}
+
+	///
+	pub fn open(&mut self) -> Result<()> {
+		self.select_current = true;
+		self.show()?;
+		self.update_worktrees();
+
+		Ok(())
+	}
+
+	/// lists the worktrees in the background
+	pub fn update_worktrees(&self) {
+		if self.is_visible() {
+			self.async_worktrees.spawn(AsyncWorktreesJob::new(
+				self.repo.borrow().clone(),
+			));
+		}
+	}
+
+	///
+	pub fn update_git(&mut self, ev: AsyncGitNotification) {
+		if ev != AsyncGitNotification::Worktrees {
+			return;
+		}
+
+		let Some(job) = self.async_worktrees.take_last() else {
+			return;
+		};
+
+		match job.result() {
+			Some(Ok(worktrees)) => {
+				self.worktrees = worktrees;
+				if std::mem::take(&mut self.select_current) {
+					self.selection = self
+						.worktrees
+						.iter()
+						.position(|worktree| worktree.is_current)
+						.unwrap_or_default();
+				} else {
+					self.selection = self
+						.selection
+						.min(self.worktrees.len().saturating_sub(1));
+				}
+			}
+			Some(Err(e)) => {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("worktrees error:\n{e}"),
+				));
+			}
+			None => (),
+		}
+	}
+
+	fn selected(&self) -> Option<&WorktreeInfo> {
+		self.worktrees.get(self.selection)
+	}
+
+	/// a linked worktree other than the current one
+	fn selected_linked(&self) -> Option<&str> {
+		self.selected()
+			.filter(|worktree| !worktree.is_current)
+			.and_then(|worktree| worktree.name.as_deref())
+	}
+
+	fn remove_selected(&self) {
+		if let Some(name) = self.selected_linked() {
+			self.queue.push(InternalEvent::ConfirmAction(
+				Action::RemoveWorktree(name.to_string()),
+			));
+		}
+	}
+
+	fn toggle_lock_selected(&self) {
+		let Some(worktree) = self.selected() else {
+			return;
+		};
+		let Some(name) = &worktree.name else {
+			return;
+		};
+
+		let repo = self.repo.borrow();
+		let res = if worktree.is_locked {
+			sync::unlock_worktree(&repo, name)
+		} else {
+			sync::lock_worktree(&repo, name, None)
+		};
+
+		match res {
+			Ok(()) => self.update_worktrees(),
+			Err(e) => {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("lock worktree error:\n{e}"),
+				));
+			}
+		}
+	}
+
+	fn prune(&self) {
+		match sync::prune_worktrees(&self.repo.borrow()) {
+			Ok(_) => self.update_worktrees(),
+			Err(e) => {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("prune worktrees error:\n{e}"),
+				));
+			}
+		}
+	}
+
+	fn switch_selected(&mut self) {
+		let Some(worktree) = self.worktrees.get(self.selection)
+		else {
+			return;
+		};
+
+		if worktree.is_current || worktree.is_prunable {
+			return;
+		}
+
+		self.queue.push(InternalEvent::OpenRepo {
+			path: worktree.path.clone(),
+		});
+		self.hide();
+	}
+
+	fn move_selection(&mut self, up: bool) {
+		let count = self.worktrees.len();
+		if count == 0 {
+			return;
+		}
+
+		self.selection = if up {
+			self.selection.checked_sub(1).unwrap_or(count - 1)
+		} else {
+			(self.selection + 1) % count
+		};
+	}
+
+	fn columns(worktree: &WorktreeInfo) -> [String; 3] {
+		let name = worktree
+			.name
+			.clone()
+			.unwrap_or_else(|| String::from(strings::WORKTREE_MAIN));
+
+		let mut branch = worktree.branch.clone().unwrap_or_default();
+		if worktree.is_locked {
+			branch.push_str(" (locked)");
+		}
+		if worktree.is_prunable {
+			branch.push_str(" (missing)");
+		}
+
+		[name, branch, worktree.path.to_string_lossy().to_string()]
+	}
+
+	fn get_text(&self) -> Vec<Line<'_>> {
+		let rows: Vec<_> =
+			self.worktrees.iter().map(Self::columns).collect();
+		let name_width = rows
+			.iter()
+			.map(|row| str_width(&row[0]))
+			.max()
+			.unwrap_or(0);
+		let branch_width = rows
+			.iter()
+			.map(|row| str_width(&row[1]))
+			.max()
+			.unwrap_or(0);
+
+		rows.into_iter()
+			.zip(&self.worktrees)
+			.enumerate()
+			.map(|(idx, ([name, branch, path], worktree))| {
+				let selected = idx == self.selection;
+				let marker =
+					if worktree.is_current { "*" } else { " " };
+
+				Line::from(vec![
+					Span::styled(
+						format!(
+							"{marker} {}  ",
+							pad_width(&name, name_width)
+						),
+						self.theme
+							.text(!worktree.is_prunable, selected),
+					),
+					Span::styled(
+						format!(
+							"{}  ",
+							pad_width(&branch, branch_width)
+						),
+						self.theme
+							.branch(selected, worktree.is_current),
+					),
+					Span::styled(
+						path,
+						self.theme
+							.text(!worktree.is_prunable, selected),
+					),
+				])
+			})
+			.collect()
+	}
+}
+
+impl DrawableComponent for WorktreesPopup {
+	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
+		if self.is_visible() {
+			const MIN_WIDTH: u16 = 40;
+
+			let text = self.get_text();
+
+			let width = text
+				.iter()
+				.map(Line::width)
+				.max()
+				.and_then(|width| u16::try_from(width + 2).ok())
+				.unwrap_or_default()
+				.clamp(MIN_WIDTH, area.width);
+			let height = u16::try_from(text.len())
+				.unwrap_or_default()
+				.saturating_add(2)
+				.min(area.height);
+			let area =
+				ui::centered_rect_absolute(width, height, area);
+
+			// keep the selection in view of long lists
+			let scroll = u16::try_from(self.selection)
+				.unwrap_or_default()
+				.saturating_sub(height.saturating_sub(3));
+
+			f.render_widget(Clear, area);
+			f.render_widget(
+				Paragraph::new(text).scroll((scroll, 0)).block(
+					Block::default()
+						.borders(Borders::ALL)
+						.title(Span::styled(
+							strings::POPUP_TITLE_WORKTREES,
+							self.theme.title(true),
+						))
+						.border_style(self.theme.block(true)),
+				),
+				area,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Component for WorktreesPopup {
+	fn commands(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) -> CommandBlocking {
+		if self.is_visible() || force_all {
+			out.push(
+				CommandInfo::new(
+					strings::commands::close_popup(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::scroll(&self.key_config),
+					true,
+					true,
+				)
+				.order(1),
+			);
+			out.push(
+				CommandInfo::new(
+					strings::commands::worktree_open(
+						&self.key_config,
+					),
+					self.worktrees.get(self.selection).is_some_and(
+						|worktree| {
+							!worktree.is_current
+								&& !worktree.is_prunable
+						},
+					),
+					true,
+				)
+				.order(1),
+			);
+			out.push(CommandInfo::new(
+				strings::commands::worktree_add(&self.key_config),
+				true,
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::worktree_remove(&self.key_config),
+				self.selected().is_some_and(|worktree| {
+					worktree.name.is_some()
+						&& !worktree.is_current
+						&& !worktree.is_locked
+				}),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::worktree_lock(
+					&self.key_config,
+					self.selected()
+						.is_some_and(|worktree| worktree.is_locked),
+				),
+				self.selected()
+					.is_some_and(|worktree| worktree.name.is_some()),
+				true,
+			));
+			out.push(CommandInfo::new(
+				strings::commands::worktree_prune(&self.key_config),
+				self.worktrees.iter().any(|worktree| {
+					worktree.is_prunable && !worktree.is_locked
+				}),
+				true,
+			));
+		}
+
+		visibility_blocking(self)
+	}
+
+	fn event(&mut self, event: &Event) -> Result<EventState> {
+		if self.is_visible() {
+			match event {
+				Event::Key(key) => {
+					if key_match(key, self.key_config.keys.exit_popup)
+					{
+						self.hide();
+					} else if key_match(
+						key,
+						self.key_config.keys.move_up,
+					) {
+						self.move_selection(true);
+					} else if key_match(
+						key,
+						self.key_config.keys.move_down,
+					) {
+						self.move_selection(false);
+					} else if key_match(
+						key,
+						self.key_config.keys.enter,
+					) {
+						self.switch_selected();
+					} else if key_match(
+						key,
+						self.key_config.keys.worktree_add,
+					) {
+						self.queue.push(InternalEvent::AddWorktree);
+					} else if key_match(
+						key,
+						self.key_config.keys.worktree_remove,
+					) {
+						self.remove_selected();
+					} else if key_match(
+						key,
+						self.key_config.keys.worktree_lock,
+					) {
+						self.toggle_lock_selected();
+					} else if key_match(
+						key,
+						self.key_config.keys.worktree_prune,
+					) {
+						self.prune();
+					}
+				}
+				Event::Mouse(mouse)
+					if mouse.kind
+						== MouseEventKind::Down(
+							MouseButton::Right,
+						) =>
+				{
+					self.hide();
+				}
+				_ => (),
+			}
+
+			return Ok(EventState::Consumed);
+		}
+
+		Ok(EventState::NotConsumed)
+	}
+
+	fn is_visible(&self) -> bool {
+		self.visible
+	}
+
+	fn hide(&mut self) {
+		self.visible = false;
+	}
+
+	fn show(&mut self) -> Result<()> {
+		self.visible = true;
+
+		Ok(())
+	}
+}
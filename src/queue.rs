@@ -1,22 +1,27 @@
 use crate::{
+	automation::Trigger,
 	components::FuzzyFinderTarget,
 	popups::{
-		AppOption, BlameFileOpen, FileRevOpen, FileTreeOpen,
-		InspectCommitOpen,
+		AppOption, BlameFileOpen, CommitPickerContext, CopyContext,
+		FileRevOpen, FileTreeOpen, IgnorePatternContext,
+		InspectCommitOpen, LineActionsContext, PatchSeriesContext,
+		TreeSearchOpen,
 	},
 	tabs::StashingOptions,
 };
 use asyncgit::{
 	sync::{
-		diff::DiffLinePosition, BranchInfo, CommitId,
+		diff::{DiffLinePosition, Hunk},
+		BranchInfo, CheckViolation, CommitId, LargeFile,
 		LogFilterSearchOptions,
 	},
-	PushType,
+	PostHook, PushType, SubmoduleOperation,
 };
 use bitflags::bitflags;
 use std::{
 	cell::RefCell, collections::VecDeque, path::PathBuf, rc::Rc,
 };
+use strum::IntoStaticStr;
 
 bitflags! {
 	/// flags defining what part of the app need to update
@@ -31,6 +36,8 @@ bitflags! {
 		const BRANCHES = 0b1000;
 		/// Remotes have changed
 		const REMOTES = 0b1001;
+		/// worktrees have changed
+		const WORKTREES = 0b1_0000;
 	}
 }
 
@@ -41,23 +48,50 @@ pub struct ResetItem {
 }
 
 ///
+#[derive(IntoStaticStr)]
 pub enum Action {
 	Reset(ResetItem),
+	/// discards the changes of all the paths
+	ResetItems(Vec<String>),
 	ResetHunk(String, u64),
 	ResetLines(String, Vec<DiffLinePosition>),
 	StashDrop(Vec<CommitId>),
 	StashPop(CommitId),
 	DeleteLocalBranch(String),
 	DeleteRemoteBranch(String),
+	/// deletes a batch of local branches in the background
+	DeleteBranches(Vec<String>),
 	DeleteTag(String),
 	DeleteRemoteTag(String, String),
 	DeleteRemote(String),
+	/// linked worktree by name
+	RemoveWorktree(String),
+	/// direct submodule by name
+	RemoveSubmodule(String),
 	ForcePush(String, bool),
-	PullMerge { incoming: usize, rebase: bool },
+	/// hard reset of the current branch that discards local changes
+	ResetHard(CommitId),
+	PullMerge {
+		incoming: usize,
+		rebase: bool,
+	},
 	AbortMerge,
 	AbortRebase,
 	AbortRevert,
+	AbortCherryPick,
+	/// `git am --abort`
+	AbortApplyMailbox,
 	UndoCommit,
+	/// writes the version of `path` in `commit` to the workdir
+	CheckoutFile {
+		commit: CommitId,
+		path: String,
+	},
+	/// merges a flow branch into `into` and deletes it
+	FinishFlowBranch {
+		branch: String,
+		into: Vec<String>,
+	},
 }
 
 #[derive(Debug)]
@@ -69,6 +103,8 @@ pub enum StackablePopupOpen {
 	///
 	FileTree(FileTreeOpen),
 	///
+	TreeSearch(TreeSearchOpen),
+	///
 	InspectCommit(InspectCommitOpen),
 	///
 	CompareCommits(InspectCommitOpen),
@@ -83,6 +119,7 @@ pub enum AppTabs {
 }
 
 ///
+#[derive(IntoStaticStr)]
 pub enum InternalEvent {
 	///
 	ConfirmAction(Action),
@@ -98,6 +135,10 @@ pub enum InternalEvent {
 	StatusLastFileMoved,
 	/// open commit msg input
 	OpenCommit,
+	/// pick the staged files to amend, leaving out the given ones
+	OpenAmendFiles(Vec<String>),
+	/// staged files to leave out of the amend
+	AmendKeepStaged(Vec<String>),
 	///
 	PopupStashing(StashingOptions),
 	///
@@ -106,22 +147,38 @@ pub enum InternalEvent {
 	TabSwitch(AppTabs),
 	///
 	SelectCommitInRevlog(CommitId),
+	/// like `SelectCommitInRevlog`, inspecting the commit if the log
+	/// does not list it
+	SelectOrInspectCommit(CommitId),
 	///
 	TagCommit(CommitId),
+	/// interactive rebase of the commit and the ones after it
+	RebaseInteractive(CommitId),
 	///
 	Tags,
 	///
 	CreateBranch,
 	///
+	OpenFlow,
+	///
+	OpenBranchCleanup,
+	///
 	RenameRemote(String),
 	///
 	UpdateRemoteUrl(String, String),
 	///
 	RenameBranch(String, String),
+	/// opens the rename popup to copy the branch instead
+	CopyBranch(String, String),
 	///
 	SelectBranch,
 	///
 	OpenExternalEditor(Option<String>),
+	/// opens the file at the line in the external editor
+	OpenExternalEditorAt(String, usize),
+	/// edits the unstaged hunk of the file in the external editor
+	/// and stages the result
+	EditHunk(String, Hunk),
 	///
 	Push(String, PushType, bool, bool),
 	///
@@ -138,6 +195,8 @@ pub enum InternalEvent {
 	FuzzyFinderChanged(usize, String, FuzzyFinderTarget),
 	///
 	FetchRemotes,
+	/// fetches the history missing in a shallow clone
+	Unshallow,
 	///
 	OpenPopup(StackablePopupOpen),
 	///
@@ -146,24 +205,68 @@ pub enum InternalEvent {
 	PopupStackPush(StackablePopupOpen),
 	///
 	ViewSubmodules,
+	/// pick a patch file to apply
+	ApplyPatch,
 	///
 	ViewRemotes,
 	///
+	OpenTour,
+	///
 	CreateRemote,
 	///
+	AddWorktree,
+	///
+	AddSubmodule,
+	/// runs in the background in the submodules popup
+	SubmoduleOperation(SubmoduleOperation),
+	///
 	OpenRepo { path: PathBuf },
 	///
 	OpenResetPopup(CommitId),
 	///
+	OpenCopyPopup(CopyContext),
+	///
+	OpenLineActionsPopup(LineActionsContext),
+	///
+	OpenIgnorePatternPopup(IgnorePatternContext),
+	///
+	OpenBisectPopup(CommitId),
+	/// runs the test command on each commit to bisect
+	BisectRun(String),
+	///
+	OpenCommitPicker(CommitPickerContext),
+	///
+	OpenPatchSeriesPopup(PatchSeriesContext),
+	///
 	RewordCommit(CommitId),
 	///
 	CommitSearch(LogFilterSearchOptions),
 	///
 	OpenGotoLinePopup(usize),
 	///
+	ReplayMacro(usize),
+	/// runs the steps configured for `Trigger`
+	RunAutomation(Trigger),
+	///
+	OpenKeyBindingsPopup,
+	///
 	GotoLine(usize),
 	///
 	CheckoutOption(BranchInfo),
+	/// runs a post-checkout or post-merge hook in the background
+	RunPostHook(PostHook),
+	/// switches to the branch in the background with the local
+	/// changes stashed for the switch
+	CheckoutAutostash(BranchInfo),
+	/// lets the user choose what to do with the local changes of
+	/// files that are in the way of checking out the branch
+	OpenCheckoutConflicts(BranchInfo, Vec<String>),
+	/// lets the user choose whether to stage files that were left
+	/// out for their size or for being tracked by git-lfs
+	OpenLargeFiles(Vec<LargeFile>),
+	/// lists what the built-in commit checks found in the staged
+	/// lines
+	OpenCommitChecks(Vec<CheckViolation>),
 }
 
 /// single threaded simple queue for components to communicate with each other
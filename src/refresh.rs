@@ -0,0 +1,107 @@
+use asyncgit::sync::{self, RepoChanges, RepoPath, RepoSnapshot};
+use std::time::{Duration, Instant};
+
+/// notices changes of `HEAD`, the index and refs that gitui did not
+/// make itself
+#[derive(Default)]
+pub struct ExternalChanges {
+	known: Option<RepoSnapshot>,
+}
+
+impl ExternalChanges {
+	/// takes the current state as one gitui made itself
+	pub fn acknowledge(&mut self, repo: &RepoPath) {
+		self.known = sync::repo_snapshot(repo).ok();
+	}
+
+	/// what changed since the last check or acknowledgement
+	pub fn check(&mut self, repo: &RepoPath) -> RepoChanges {
+		let Ok(current) = sync::repo_snapshot(repo) else {
+			return RepoChanges::empty();
+		};
+
+		let changes = self
+			.known
+			.as_ref()
+			.map_or_else(RepoChanges::empty, |known| {
+				known.changes(&current)
+			});
+		self.known = Some(current);
+
+		changes
+	}
+}
+
+/// what a manual refresh re-ran, shown until the next key press
+pub struct RefreshReport {
+	started: Instant,
+	/// name of each job and how long it took once it is done
+	jobs: Vec<(&'static str, Option<Duration>)>,
+}
+
+impl RefreshReport {
+	/// `pending` are the jobs right after the refresh started them,
+	/// the ones not running had nothing to do
+	pub fn new(pending: &[(&'static str, bool)]) -> Self {
+		Self {
+			started: Instant::now(),
+			jobs: pending
+				.iter()
+				.filter(|(_, pending)| *pending)
+				.map(|(name, _)| (*name, None))
+				.collect(),
+		}
+	}
+
+	/// marks the jobs that are no longer `pending` as done
+	pub fn update(&mut self, pending: &[(&'static str, bool)]) {
+		let elapsed = self.started.elapsed();
+
+		for (name, done) in &mut self.jobs {
+			let running = pending
+				.iter()
+				.any(|(job, pending)| job == name && *pending);
+
+			if done.is_none() && !running {
+				*done = Some(elapsed);
+			}
+		}
+	}
+
+	pub fn text(&self) -> String {
+		if self.jobs.is_empty() {
+			return String::from("refreshed: up to date");
+		}
+
+		let jobs: Vec<String> = self
+			.jobs
+			.iter()
+			.map(|(name, done)| {
+				done.map_or_else(
+					|| format!("{name} …"),
+					|took| format!("{name} ✓ {}ms", took.as_millis()),
+				)
+			})
+			.collect();
+
+		format!("refreshed: {}", jobs.join(", "))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::RefreshReport;
+
+	#[test]
+	fn test_report() {
+		let mut report =
+			RefreshReport::new(&[("status", true), ("log", false)]);
+		assert_eq!(report.text(), "refreshed: status …");
+
+		report.update(&[("status", false), ("log", true)]);
+		assert!(report.text().starts_with("refreshed: status ✓ "));
+
+		let report = RefreshReport::new(&[("status", false)]);
+		assert_eq!(report.text(), "refreshed: up to date");
+	}
+}
@@ -0,0 +1,177 @@
+//! overrides for a single repository in `.gitui.ron` at the root of
+//! its workdir, merged over the global theme, key bindings and the
+//! options of the repo whenever it is opened or the config reloaded:
+//!
+//! ```ron
+//! (
+//!     theme: (selection_bg: Some("Magenta")),
+//!     keys: (status_stage_all: Some((code: Char('S'), modifiers: "SHIFT"))),
+//!     options: (diff_wrap_lines: Some(true)),
+//! )
+//! ```
+
+use crate::{
+	keys::{KeyConfig, KeysListPatch},
+	options::OptionsDataPatch,
+	ui::style::{Theme, ThemePatch},
+};
+use anyhow::Result;
+use asyncgit::sync::{utils::repo_work_dir, RepoPath};
+use ron::extensions::Extensions;
+use serde::Deserialize;
+use std::{fs::File, path::Path};
+use struct_patch::traits::Patch;
+
+const REPO_CONFIG_FILE: &str = ".gitui.ron";
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct RepoConfig {
+	theme: Option<ThemePatch>,
+	keys: Option<KeysListPatch>,
+	options: Option<OptionsDataPatch>,
+}
+
+impl RepoConfig {
+	/// reads `.gitui.ron` of `repo`, overriding nothing without one
+	pub fn load(repo: &RepoPath) -> Self {
+		repo_work_dir(repo).map_or_else(
+			|_| Self::default(),
+			|workdir| Self::from_workdir(Path::new(&workdir)),
+		)
+	}
+
+	fn from_workdir(workdir: &Path) -> Self {
+		let path = workdir.join(REPO_CONFIG_FILE);
+
+		if !path.exists() {
+			return Self::default();
+		}
+
+		Self::read_file(&path).unwrap_or_else(|e| {
+			log::error!("repo config error [{path:?}]: {e}");
+			Self::default()
+		})
+	}
+
+	/// without the `Some(…)` around each section
+	fn read_file(path: &Path) -> Result<Self> {
+		let file = File::open(path)?;
+		Ok(ron::Options::default()
+			.with_default_extension(Extensions::IMPLICIT_SOME)
+			.from_reader(file)?)
+	}
+
+	///
+	pub fn theme(&mut self, theme: &Theme) -> Theme {
+		let mut theme = theme.clone();
+		if let Some(patch) = self.theme.take() {
+			theme.apply(patch);
+		}
+		theme
+	}
+
+	///
+	pub fn key_config(
+		&mut self,
+		key_config: &KeyConfig,
+	) -> KeyConfig {
+		self.keys.take().map_or_else(
+			|| key_config.clone(),
+			|patch| key_config.patched(patch),
+		)
+	}
+
+	/// the overrides of `OptionsData`, applied in `Options::load`
+	pub fn options(self) -> Option<OptionsDataPatch> {
+		self.options
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::RepoConfig;
+	use crate::{
+		keys::KeyConfig, options::OptionsData, ui::style::Theme,
+	};
+	use crossterm::event::KeyCode;
+	use ratatui::style::Color;
+	use std::fs;
+	use struct_patch::traits::Patch;
+	use tempfile::TempDir;
+
+	#[test]
+	fn test_load() {
+		let td = TempDir::new().unwrap();
+
+		fs::write(
+			td.path().join(".gitui.ron"),
+			r#"(
+				theme: (selection_bg: Some("Magenta")),
+				keys: (status_stage_all: Some((code: Char('S'), modifiers: "SHIFT"))),
+				options: (diff_wrap_lines: Some(true)),
+			)"#,
+		)
+		.unwrap();
+
+		let mut config = RepoConfig::from_workdir(td.path());
+
+		let theme = config.theme(&Theme::default());
+		assert_eq!(theme.text(true, true).bg, Some(Color::Magenta));
+
+		let key_config = config.key_config(&KeyConfig::default());
+		assert_eq!(
+			key_config.keys.status_stage_all.code,
+			KeyCode::Char('S')
+		);
+		// untouched bindings keep the global ones
+		assert_eq!(
+			key_config.keys.move_up,
+			KeyConfig::default().keys.move_up
+		);
+
+		let mut options = OptionsData::default();
+		options.apply(config.options().unwrap());
+		assert!(options.diff_wrap_lines);
+	}
+
+	#[test]
+	fn test_load_keeps_safety_options() {
+		let td = TempDir::new().unwrap();
+
+		fs::write(
+			td.path().join(".gitui.ron"),
+			r#"(
+				options: (
+					diff_wrap_lines: Some(true),
+					confirm: Some((discard: false, force_push: false)),
+					discard_to_trash: Some(false),
+				),
+			)"#,
+		)
+		.unwrap();
+
+		let mut options = OptionsData::default();
+		options.discard_to_trash = true;
+		options.apply(
+			RepoConfig::from_workdir(td.path()).options().unwrap(),
+		);
+
+		assert!(options.diff_wrap_lines);
+		assert!(options.discard_to_trash);
+		assert!(options.confirm.discard);
+		assert!(options.confirm.force_push);
+	}
+
+	#[test]
+	fn test_load_invalid() {
+		let td = TempDir::new().unwrap();
+
+		fs::write(td.path().join(".gitui.ron"), "(theme: 3)")
+			.unwrap();
+
+		assert!(RepoConfig::from_workdir(td.path())
+			.options()
+			.is_none());
+	}
+}
@@ -9,6 +9,11 @@ use std::{cell::Cell, char, io};
 static SPINNER_CHARS: &[char] =
 	&['⣷', '⣯', '⣟', '⡿', '⢿', '⣻', '⣽', '⣾'];
 
+/// the `idx`th frame of the spinner, for popups drawing their own
+pub fn spinner_char(idx: usize) -> char {
+	SPINNER_CHARS[idx % SPINNER_CHARS.len()]
+}
+
 ///
 pub struct Spinner {
 	idx: usize,
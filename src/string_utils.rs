@@ -1,6 +1,80 @@
+pub use asyncgit::sync::glob_match;
+use std::sync::atomic::{AtomicBool, Ordering};
 use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
+/// east asian ambiguous characters (like `·` or `○`) take two columns
+static AMBIGUOUS_WIDE: AtomicBool = AtomicBool::new(false);
+
+/// sets how wide east asian ambiguous characters are drawn by the
+/// terminal, most only use two columns in a CJK locale
+pub fn set_ambiguous_wide(wide: bool) {
+	AMBIGUOUS_WIDE.store(wide, Ordering::Relaxed);
+}
+
+/// columns `s` takes in the terminal
+pub fn str_width(s: &str) -> usize {
+	width_of(s, AMBIGUOUS_WIDE.load(Ordering::Relaxed))
+}
+
+fn width_of(s: &str, ambiguous_wide: bool) -> usize {
+	if ambiguous_wide {
+		s.width_cjk()
+	} else {
+		s.width()
+	}
+}
+
+/// longest start of `s` that fits into `width` columns, without
+/// splitting a grapheme (like an emoji with a skin tone)
+pub fn truncate_width(s: &str, width: usize) -> &str {
+	let mut used = 0;
+
+	for (idx, grapheme) in s.grapheme_indices(true) {
+		used += str_width(grapheme);
+		if used > width {
+			return &s[..idx];
+		}
+	}
+
+	s
+}
+
+/// longest end of `s` that fits into `width` columns
+pub fn truncate_start_width(s: &str, width: usize) -> &str {
+	let mut used = 0;
+
+	for (idx, grapheme) in s.grapheme_indices(true).rev() {
+		used += str_width(grapheme);
+		if used > width {
+			return &s[idx + grapheme.len()..];
+		}
+	}
+
+	s
+}
+
+/// `s` if it fits into `width` columns, otherwise its start followed
+/// by `postfix` in at most `width` columns
+pub fn truncate_with(s: &str, width: usize, postfix: &str) -> String {
+	if str_width(s) <= width {
+		s.to_string()
+	} else {
+		let start = truncate_width(
+			s,
+			width.saturating_sub(str_width(postfix)),
+		);
+		format!("{start}{postfix}")
+	}
+}
+
+/// `s` filled up with spaces to `width` columns, unlike `format!`
+/// padding this counts columns instead of chars
+pub fn pad_width(s: &str, width: usize) -> String {
+	let fill = width.saturating_sub(str_width(s));
+	format!("{s}{:fill$}", "")
+}
+
 ///
 pub fn trim_length_left(s: &str, width: usize) -> &str {
 	let len = s.len();
@@ -29,7 +103,7 @@ pub fn tabs_to_spaces(input: String) -> String {
 pub fn trim_offset(src: &str, mut offset: usize) -> &str {
 	let mut start = 0;
 	for c in UnicodeSegmentation::graphemes(src, true) {
-		let w = c.width();
+		let w = str_width(c);
 		if w <= offset {
 			offset -= w;
 			start += c.len();
@@ -40,11 +114,80 @@ pub fn trim_offset(src: &str, mut offset: usize) -> &str {
 	&src[start..]
 }
 
+/// splits `src` into chunks that each fit into `width` columns.
+/// always returns at least one (possibly empty) chunk.
+pub fn wrap_by_width(src: &str, width: usize) -> Vec<&str> {
+	let width = width.max(1);
+	let mut res = Vec::new();
+	let mut start = 0;
+	let mut current = 0;
+
+	for (idx, c) in UnicodeSegmentation::grapheme_indices(src, true) {
+		let w = str_width(c);
+		if current + w > width && idx > start {
+			res.push(&src[start..idx]);
+			start = idx;
+			current = 0;
+		}
+		current += w;
+	}
+
+	res.push(&src[start..]);
+
+	res
+}
+
 #[cfg(test)]
 mod test {
 	use pretty_assertions::assert_eq;
 
-	use crate::string_utils::trim_length_left;
+	use crate::string_utils::{
+		pad_width, str_width, trim_length_left, truncate_start_width,
+		truncate_width, truncate_with, width_of, wrap_by_width,
+	};
+
+	#[test]
+	fn test_wrap_by_width() {
+		assert_eq!(wrap_by_width("", 3), vec![""]);
+		assert_eq!(
+			wrap_by_width("abcdefg", 3),
+			vec!["abc", "def", "g"]
+		);
+		assert_eq!(wrap_by_width("abc", 3), vec!["abc"]);
+		assert_eq!(wrap_by_width("a👍👍", 2), vec!["a", "👍", "👍"]);
+	}
+
+	#[test]
+	fn test_truncate_width() {
+		assert_eq!(truncate_width("日本語", 5), "日本");
+		assert_eq!(truncate_width("日本語", 6), "日本語");
+		assert_eq!(truncate_width("a👍🏽b", 2), "a");
+		assert_eq!(truncate_width("a👍🏽b", 3), "a👍🏽");
+		assert_eq!(truncate_width("e\u{301}x", 1), "e\u{301}");
+
+		assert_eq!(truncate_start_width("日本語", 5), "本語");
+		assert_eq!(truncate_start_width("abc", 5), "abc");
+	}
+
+	#[test]
+	fn test_truncate_with() {
+		assert_eq!(truncate_with("日本語", 6, ".."), "日本語");
+		assert_eq!(truncate_with("日本語", 5, ".."), "日..");
+		assert_eq!(str_width(&truncate_with("日本語", 4, "..")), 4);
+	}
+
+	#[test]
+	fn test_pad_width() {
+		assert_eq!(pad_width("日本", 6), "日本  ");
+		assert_eq!(pad_width("abc", 2), "abc");
+	}
+
+	#[test]
+	fn test_ambiguous_width() {
+		assert_eq!(width_of("○·", false), 2);
+		assert_eq!(width_of("○·", true), 4);
+		assert_eq!(width_of("abc", true), 3);
+	}
 
 	#[test]
 	fn test_trim() {
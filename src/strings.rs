@@ -1,10 +1,17 @@
 use std::borrow::Cow;
 
-use asyncgit::sync::CommitId;
-use unicode_truncate::UnicodeTruncateStr;
-use unicode_width::UnicodeWidthStr;
-
-use crate::keys::SharedKeyConfig;
+use crate::{
+	keys::SharedKeyConfig,
+	string_utils::{str_width, truncate_start_width},
+};
+use asyncgit::{
+	sync::{
+		AbsorbResult, ApplyLocation, BisectState, CommitId,
+		RebaseAction, RepoChanges, StackAction,
+	},
+	Progress, ProgressPhase,
+};
+use bytesize::ByteSize;
 
 pub mod order {
 	pub const RARE_ACTION: i8 = 30;
@@ -17,17 +24,11 @@ pub static PUSH_POPUP_MSG: &str = "Push";
 pub static FORCE_PUSH_POPUP_MSG: &str = "Force Push";
 pub static PULL_POPUP_MSG: &str = "Pull";
 pub static FETCH_POPUP_MSG: &str = "Fetch";
-pub static PUSH_POPUP_PROGRESS_NONE: &str = "preparing...";
-pub static PUSH_POPUP_STATES_ADDING: &str = "adding objects (1/3)";
-pub static PUSH_POPUP_STATES_DELTAS: &str = "deltas (2/3)";
-pub static PUSH_POPUP_STATES_PUSHING: &str = "pushing (3/3)";
-pub static PUSH_POPUP_STATES_TRANSFER: &str = "transfer";
-pub static PUSH_POPUP_STATES_DONE: &str = "done";
+pub static UNSHALLOW_POPUP_MSG: &str = "Unshallow";
+pub static SUBMODULE_POPUP_MSG: &str = "Submodule";
+pub static PROGRESS_NONE: &str = "preparing...";
 
 pub static PUSH_TAGS_POPUP_MSG: &str = "Push Tags";
-pub static PUSH_TAGS_STATES_FETCHING: &str = "fetching";
-pub static PUSH_TAGS_STATES_PUSHING: &str = "pushing";
-pub static PUSH_TAGS_STATES_DONE: &str = "done";
 
 pub static POPUP_TITLE_SUBMODULES: &str = "Submodules";
 pub static POPUP_TITLE_REMOTES: &str = "Remotes";
@@ -35,7 +36,203 @@ pub static POPUP_SUBTITLE_REMOTES: &str = "Details";
 pub static POPUP_TITLE_FUZZY_FIND: &str = "Fuzzy Finder";
 pub static POPUP_TITLE_LOG_SEARCH: &str = "Search";
 
+pub static POPUP_TITLE_COPY: &str = "Copy";
+pub static POPUP_TITLE_LINE_ACTIONS: &str = "Selected lines";
+pub static POPUP_TITLE_IGNORE_PATTERN: &str = "Ignore";
+pub static POPUP_TITLE_BISECT: &str = "Bisect";
+pub static POPUP_TITLE_WORKTREES: &str = "Worktrees";
+pub static POPUP_TITLE_FLOW: &str = "Branch flow";
+pub static POPUP_TITLE_AMEND_FILES: &str = "Amend files";
+pub static AMEND_FILES_EMPTY: &str = "no staged files";
+pub static POPUP_TITLE_BRANCH_CLEANUP: &str = "Clean up branches";
+pub static BRANCH_CLEANUP_EMPTY: &str =
+	"no merged branches or gone upstreams";
+pub static WORKTREE_MAIN: &str = "(main)";
+pub static POPUP_TITLE_TRASH: &str = "Trash";
+pub static TRASH_EMPTY: &str = "nothing discarded to the trash";
+pub static POPUP_TITLE_APPLY_PATCH: &str = "Apply patch";
+pub static APPLY_PATCH_EMPTY: &str = "no folders or patch files";
+pub static POPUP_TITLE_STACK: &str = "Stack";
+pub static STACK_EMPTY: &str = "no commits on top of the upstream";
+pub static INLINE_BLAME_PENDING: &str = " (blaming…)";
+pub static CHECKOUT_CONFLICTS_MSG: &str =
+	"the checkout would overwrite local changes of:";
+
+pub fn apply_patch_location(location: ApplyLocation) -> String {
+	let location = match location {
+		ApplyLocation::WorkDir => "worktree",
+		ApplyLocation::Index => "index",
+		ApplyLocation::Both => "worktree and index",
+	};
+	format!("apply to: {location}")
+}
+pub fn apply_patch_done(applied: usize, files: &[String]) -> String {
+	format!(
+		"applied {applied} patch{} to:\n{}",
+		if applied == 1 { "" } else { "es" },
+		files.join("\n")
+	)
+}
+pub fn apply_patch_conflict(
+	applied: usize,
+	patch: &str,
+	files: &[String],
+) -> String {
+	let applied = if applied > 0 {
+		format!(
+			"applied {applied} patch{}, then ",
+			if applied == 1 { "" } else { "es" }
+		)
+	} else {
+		String::new()
+	};
+	format!(
+		"{applied}'{patch}' does not apply to:\n{}",
+		files.join("\n")
+	)
+}
+
+pub fn external_changes(changes: RepoChanges) -> String {
+	let what: Vec<&str> = [
+		(RepoChanges::HEAD, "HEAD"),
+		(RepoChanges::INDEX, "index"),
+		(RepoChanges::REFS, "refs"),
+	]
+	.into_iter()
+	.filter(|(change, _)| changes.contains(*change))
+	.map(|(_, name)| name)
+	.collect();
+
+	format!("{} changed outside gitui", what.join(", "))
+}
+
+pub fn checkout_conflicts_title(branch: &str) -> String {
+	format!("Checkout {branch}")
+}
+pub fn checkout_conflicts_stashed(branch: &str) -> String {
+	format!("the local changes in the way of '{branch}' were stashed")
+}
+
+pub static LARGE_FILES_TITLE: &str = "Large Files";
+pub static LARGE_FILES_MSG: &str =
+	"left unstaged for their size or for git-lfs:";
+
+pub fn stack_title(
+	branch: &str,
+	upstream: &str,
+	behind: usize,
+	reordered: bool,
+) -> String {
+	let behind = if behind > 0 {
+		format!(" ({behind} behind)")
+	} else {
+		String::new()
+	};
+	let reordered = if reordered { " [modified]" } else { "" };
+
+	format!("Stack: {branch} on {upstream}{behind}{reordered}")
+}
+
+pub const fn stack_action(action: StackAction) -> &'static str {
+	match action {
+		StackAction::Pick => "",
+		StackAction::Fixup => "fixup  ",
+		StackAction::Squash => "squash ",
+	}
+}
+
+pub static REBASE_REWORD_TITLE: &str = "Reword";
+pub static REBASE_REWORD_MSG: &str = "new commit message..";
+
+pub fn rebase_interactive_title(
+	commits: usize,
+	base: Option<&CommitId>,
+	modified: bool,
+) -> String {
+	let base =
+		base.map(CommitId::get_short_string).unwrap_or_default();
+	let modified = if modified { " [modified]" } else { "" };
+
+	format!("Rebase: {commits} commits onto {base}{modified}")
+}
+
+pub const fn rebase_action(action: RebaseAction) -> &'static str {
+	match action {
+		RebaseAction::Pick => "pick   ",
+		RebaseAction::Reword => "reword ",
+		RebaseAction::Edit => "edit   ",
+		RebaseAction::Squash => "squash ",
+		RebaseAction::Fixup => "fixup  ",
+		RebaseAction::Drop => "drop   ",
+	}
+}
+
+pub fn untracked_flood_notice(
+	key_config: &SharedKeyConfig,
+	count: usize,
+	dir: &str,
+) -> String {
+	format!(
+		"{count} new untracked files in {dir}/, ignore it [{}]",
+		key_config.get_hint(key_config.keys.ignore_untracked_flood),
+	)
+}
+pub fn absorb_msg(result: &AbsorbResult) -> String {
+	let msg = if result.absorbed == 0 {
+		String::from("no staged hunk belongs to a single commit")
+	} else if result.conflicted {
+		format!(
+			"squashing the fixup commits for {} hunks stopped on conflicts, continue or abort the rebase",
+			result.absorbed
+		)
+	} else if result.fixups.is_empty() {
+		format!(
+			"squashed {} hunks into {} commits",
+			result.absorbed, result.targets
+		)
+	} else {
+		format!(
+			"created {} fixup commits for {} hunks",
+			result.fixups.len(),
+			result.absorbed
+		)
+	};
+
+	if result.left > 0 {
+		format!("{msg}, {} hunks left staged", result.left)
+	} else {
+		msg
+	}
+}
+pub static WORKTREE_INDICATOR: &str = "worktree:";
+pub static UNKNOWN_REVISION: &str = "unknown revision";
+pub static TOUR_TITLE_TABS: &str = "Tabs";
+pub static TOUR_TITLE_STAGING: &str = "Staging";
+pub static TOUR_TITLE_COMMITTING: &str = "Committing";
+pub static TOUR_TITLE_PUSHING: &str = "Pushing";
+pub static POPUP_TITLE_COMMIT_PARENTS: &str = "Parents";
+pub static POPUP_TITLE_PATCH_SERIES: &str = "Patch series";
+pub static POPUP_FAIL_PATCH_SERIES: &str = "patch export failed";
+pub static PATCH_SERIES_NOT_CONTIGUOUS: &str =
+	"a combined diff needs a contiguous range of commits";
+pub static POPUP_TITLE_COMMIT_CHILDREN: &str = "Children";
+pub static NO_COMMIT_PARENTS: &str = "commit has no parents";
+pub static NO_COMMIT_CHILDREN: &str =
+	"commit has no children in any branch";
+pub static POPUP_TITLE_SPECIAL_REFS: &str = "Special refs";
+pub static NO_SPECIAL_REFS: &str =
+	"no ORIG_HEAD, MERGE_HEAD, CHERRY_PICK_HEAD or FETCH_HEAD";
+pub static TREE_SEARCH_PENDING: &str = "Searching..";
+pub static IMAGE_NO_PREVIEW: &str = "no preview for this format";
+pub static POPUP_TITLE_MACRO_REPLAY: &str = "Replay times";
+pub static MACRO_RECORDING: &str = "recording macro";
+pub static POPUP_TITLE_KEY_BINDINGS: &str =
+	"Key bindings (applied on restart)";
+pub static POPUP_FAIL_SAVE_KEYS: &str = "Failed to save key bindings";
+pub static KEY_BINDINGS_SAVED: &str =
+	"Key bindings saved, reload the config [F5] to apply them";
 pub static POPUP_FAIL_COPY: &str = "Failed to copy text";
+pub static POPUP_FAIL_OPEN_URL: &str = "Failed to open url";
 pub static POPUP_SUCCESS_COPY: &str = "Copied Text";
 pub static POPUP_COMMIT_SHA_INVALID: &str = "Invalid commit sha";
 
@@ -49,9 +246,60 @@ pub mod symbol {
 	pub const ELLIPSIS: char = '\u{2026}'; // …
 }
 
+pub fn progress_label(progress: &Progress) -> String {
+	let phase = match progress.phase {
+		ProgressPhase::Preparing => PROGRESS_NONE,
+		ProgressPhase::CheckingRemote => "fetching",
+		ProgressPhase::AddingObjects => "adding objects",
+		ProgressPhase::Deltas => "deltas",
+		ProgressPhase::Sending => "pushing",
+		ProgressPhase::Receiving => "receiving",
+		ProgressPhase::Rebasing => "rebasing",
+		ProgressPhase::Scanning => "scanning",
+		ProgressPhase::Retrying => {
+			return format!(
+				"retrying {}/{}",
+				progress.current, progress.total
+			);
+		}
+		ProgressPhase::Done => "done",
+	};
+
+	match progress.bytes {
+		Some(bytes) if bytes > 0 => {
+			format!("{phase} {}", ByteSize::b(bytes as u64))
+		}
+		_ => phase.to_string(),
+	}
+}
+
 pub fn title_branches() -> String {
 	"Branches".to_string()
 }
+/// `[filter: ..]` as appended to list titles
+pub fn filter_suffix(filter: &str, typing: bool) -> String {
+	format!("[filter: {filter}{}]", if typing { "_" } else { "" })
+}
+pub fn tree_search_title(commit: Option<&CommitId>) -> String {
+	commit.map_or_else(
+		|| String::from("Search contents"),
+		|commit| {
+			format!(
+				"Search contents of {}",
+				commit.get_short_string()
+			)
+		},
+	)
+}
+pub fn hook_running(hook: &str) -> String {
+	format!("Running {hook} hook")
+}
+pub fn tree_search_hits(count: usize) -> String {
+	format!("Hits: {count}")
+}
+pub fn title_branches_filtered(filter: &str, typing: bool) -> String {
+	format!("Branches {}", filter_suffix(filter, typing))
+}
 pub fn title_tags() -> String {
 	"Tags".to_string()
 }
@@ -61,6 +309,19 @@ pub fn title_status(_key_config: &SharedKeyConfig) -> String {
 pub fn title_diff(_key_config: &SharedKeyConfig) -> String {
 	"Diff: ".to_string()
 }
+pub fn diff_lines_hidden(
+	key_config: &SharedKeyConfig,
+	shown: usize,
+	total: usize,
+) -> String {
+	format!(
+		"({shown}/{total} lines, more [{}])",
+		key_config.get_hint(key_config.keys.diff_load_more),
+	)
+}
+pub fn diff_text_format(line_ending: &str, encoding: &str) -> String {
+	format!("[{line_ending}, {encoding}]")
+}
 pub fn title_index(_key_config: &SharedKeyConfig) -> String {
 	"Staged Changes".to_string()
 }
@@ -109,6 +370,26 @@ pub fn msg_title_info(_key_config: &SharedKeyConfig) -> String {
 pub fn commit_title() -> String {
 	"Commit".to_string()
 }
+pub fn commit_title_template(template: &str) -> String {
+	format!("Commit ({template})")
+}
+pub fn commit_protected_branch(
+	branch: &str,
+	blocked: bool,
+) -> String {
+	if blocked {
+		format!("{{{branch}}} protected, committing is blocked")
+	} else {
+		format!("{{{branch}}} protected")
+	}
+}
+pub static COMMIT_CHECKS_TITLE: &str = "Commit Checks";
+pub fn commit_checks_msg(key_config: &SharedKeyConfig) -> String {
+	format!(
+		"fix and stage these or commit without verify [{}]:",
+		key_config.get_hint(key_config.keys.toggle_verify),
+	)
+}
 pub fn commit_reword_title() -> String {
 	"Reword Commit".to_string()
 }
@@ -119,15 +400,24 @@ pub fn commit_title_merge() -> String {
 pub fn commit_title_revert() -> String {
 	"Commit (Revert)".to_string()
 }
+pub fn commit_title_cherrypick() -> String {
+	"Commit (Cherry-pick)".to_string()
+}
 pub fn commit_title_amend() -> String {
 	"Commit (Amend)".to_string()
 }
+pub fn commit_title_amend_keep_staged(count: usize) -> String {
+	format!("Commit (Amend, {count} staged left out)")
+}
 pub fn commit_msg(_key_config: &SharedKeyConfig) -> String {
 	"type commit message..".to_string()
 }
 pub fn commit_first_line_warning(count: usize) -> String {
 	format!("[subject length: {count}]")
 }
+pub fn commit_lint_warning(warning: &str) -> String {
+	format!("[{warning}]")
+}
 pub const fn branch_name_invalid() -> &'static str {
 	"[invalid name]"
 }
@@ -137,6 +427,16 @@ pub fn commit_editor_msg(_key_config: &SharedKeyConfig) -> String {
 # Lines starting with '#' will be ignored"
 		.to_string()
 }
+pub fn edit_hunk_editor_msg(_key_config: &SharedKeyConfig) -> String {
+	r"# ---
+# To remove '-' lines, make them ' ' lines (context).
+# To remove '+' lines, delete them.
+# Lines starting with '#' will be removed.
+# If the patch applies cleanly, the edited hunk will be staged.
+# To abort, delete all lines.
+"
+	.to_string()
+}
 pub fn stash_popup_title(_key_config: &SharedKeyConfig) -> String {
 	"Stash".to_string()
 }
@@ -188,6 +488,16 @@ pub fn confirm_title_abortmerge() -> String {
 pub fn confirm_title_abortrevert() -> String {
 	"Abort revert?".to_string()
 }
+pub fn confirm_title_abortcherrypick() -> String {
+	"Abort cherry-pick?".to_string()
+}
+pub fn confirm_title_abortapplymailbox() -> String {
+	"Abort am?".to_string()
+}
+pub fn confirm_msg_abortapplymailbox() -> String {
+	"This will drop the patches applied so far and revert all uncommitted changes. Are you sure?"
+		.to_string()
+}
 pub fn confirm_msg_revertchanges() -> String {
 	"This will revert all uncommitted changes. Are you sure?"
 		.to_string()
@@ -202,6 +512,21 @@ pub fn confirm_msg_abortrebase() -> String {
 pub fn confirm_msg_reset() -> String {
 	"confirm file reset?".to_string()
 }
+pub fn confirm_title_reset_hard() -> String {
+	"Reset Hard".to_string()
+}
+pub fn confirm_msg_reset_hard(id: &str) -> String {
+	format!(
+		"confirm hard reset to {id}?\nuncommitted changes will be lost."
+	)
+}
+pub fn confirm_msg_reset_items(paths: &[String]) -> String {
+	format!(
+		"confirm reset of {} files?\n\n{}",
+		paths.len(),
+		paths.join("\n")
+	)
+}
 pub fn confirm_msg_reset_lines(lines: usize) -> String {
 	format!(
 		"are you sure you want to discard {lines} selected lines?"
@@ -210,6 +535,44 @@ pub fn confirm_msg_reset_lines(lines: usize) -> String {
 pub fn confirm_msg_undo_commit() -> String {
 	"confirm undo last commit?".to_string()
 }
+pub fn confirm_title_restore_version() -> String {
+	"Restore version".to_string()
+}
+pub fn confirm_msg_restore_version(
+	path: &str,
+	commit: CommitId,
+) -> String {
+	format!(
+		"Overwrite '{path}' in the workdir with its version in {}?",
+		commit.get_short_string()
+	)
+}
+pub fn confirm_title_finish_flow_branch() -> String {
+	"Finish branch".to_string()
+}
+pub fn confirm_msg_finish_flow_branch(
+	branch: &str,
+	into: &[String],
+) -> String {
+	format!(
+		"Merge '{branch}' into {} and delete it?",
+		into.join(" and ")
+	)
+}
+pub fn confirm_title_delete_branches() -> String {
+	"Delete Branches".to_string()
+}
+pub fn confirm_msg_delete_branches(branch_refs: &[String]) -> String {
+	format!(
+		"Delete {} branches?\n\n{}",
+		branch_refs.len(),
+		branch_refs
+			.iter()
+			.map(|r| r.trim_start_matches("refs/heads/"))
+			.collect::<Vec<_>>()
+			.join("\n")
+	)
+}
 pub fn confirm_msg_stashdrop(
 	_key_config: &SharedKeyConfig,
 	ids: &[CommitId],
@@ -264,6 +627,28 @@ pub fn confirm_msg_delete_remote(
 ) -> String {
 	format!("Confirm deleting remote \"{remote_name}\"")
 }
+pub fn confirm_title_remove_worktree(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"Remove Worktree".to_string()
+}
+pub fn confirm_msg_remove_worktree(
+	_key_config: &SharedKeyConfig,
+	name: &str,
+) -> String {
+	format!("Confirm removing worktree \"{name}\" and its folder")
+}
+pub fn confirm_title_remove_submodule(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"Remove Submodule".to_string()
+}
+pub fn confirm_msg_remove_submodule(
+	_key_config: &SharedKeyConfig,
+	name: &str,
+) -> String {
+	format!("Confirm removing submodule \"{name}\" and its checkout")
+}
 pub fn confirm_msg_delete_remote_branch(
 	_key_config: &SharedKeyConfig,
 	branch_ref: &str,
@@ -300,8 +685,77 @@ pub fn confirm_msg_force_push(
         "Confirm force push to branch '{branch_ref}' ?  This may rewrite history."
     )
 }
-pub fn log_title(_key_config: &SharedKeyConfig) -> String {
-	"Commit".to_string()
+pub fn confirm_msg_protected(branches: &[String]) -> String {
+	format!("\nprotected branch: {}", branches.join(", "))
+}
+pub fn log_title(
+	key_config: &SharedKeyConfig,
+	shallow: bool,
+) -> String {
+	if shallow {
+		format!(
+			"Commit (shallow clone, history cut off, unshallow [{}])",
+			key_config.get_hint(key_config.keys.log_unshallow),
+		)
+	} else {
+		"Commit".to_string()
+	}
+}
+pub fn bisect_progress(state: &BisectState) -> String {
+	match (state.found, state.next) {
+		(Some(found), _) => {
+			format!("first bad commit: {}", found.get_short_string())
+		}
+		(None, Some(next)) => format!(
+			"testing {}: {} revisions left (roughly {} steps)",
+			next.get_short_string(),
+			state.remaining,
+			state.steps()
+		),
+		(None, None) if state.only_skipped_left() => format!(
+			"only skipped commits left to test ({})",
+			state.remaining
+		),
+		(None, None) if state.bad.is_none() => {
+			"mark a bad commit".to_string()
+		}
+		(None, None) => "mark a good commit".to_string(),
+	}
+}
+pub fn tour_tabs(key_config: &SharedKeyConfig) -> String {
+	format!(
+		"Welcome to gitui! Everything is organized in tabs: the status of the working dir, the log, the files of the repo and the stashes. Jump to a tab with [{}]-[{}] or cycle through them with [{}].",
+		key_config.get_hint(key_config.keys.tab_status),
+		key_config.get_hint(key_config.keys.tab_stashes),
+		key_config.get_hint(key_config.keys.tab_toggle),
+	)
+}
+pub fn tour_staging(key_config: &SharedKeyConfig) -> String {
+	format!(
+		"Changed files show up here, their diff on the right. Stage the selected file with [{}] or all of them with [{}]. [{}] switches to the staged changes below.",
+		key_config.get_hint(key_config.keys.stage_unstage_item),
+		key_config.get_hint(key_config.keys.status_stage_all),
+		key_config.get_hint(key_config.keys.toggle_workarea),
+	)
+}
+pub fn tour_committing(key_config: &SharedKeyConfig) -> String {
+	format!(
+		"Staged changes end up here. Press [{}] to write a commit message and commit them.",
+		key_config.get_hint(key_config.keys.open_commit),
+	)
+}
+pub fn tour_pushing(key_config: &SharedKeyConfig) -> String {
+	format!(
+		"The command bar lists what the focused view can do. Push your commits with [{}], fetch with [{}] and pull with [{}]. Open the help with [{}] to see all commands and to take this tour again with [{}].",
+		key_config.get_hint(key_config.keys.push),
+		key_config.get_hint(key_config.keys.fetch),
+		key_config.get_hint(key_config.keys.pull),
+		key_config.get_hint(key_config.keys.open_help),
+		key_config.get_hint(key_config.keys.open_tour),
+	)
+}
+pub fn tour_title(title: &str, step: usize, steps: usize) -> String {
+	format!("{title} ({step}/{steps})")
 }
 pub fn file_log_title(
 	file_path: &str,
@@ -342,6 +796,12 @@ pub fn stashing_options_title(
 pub fn loading_text(_key_config: &SharedKeyConfig) -> String {
 	"Loading ...".to_string()
 }
+pub fn flow_start_title(kind: &str) -> String {
+	format!("Start {kind}")
+}
+pub fn flow_start_msg(prefix: &str, base: &str) -> String {
+	format!("name after '{prefix}', starts from {base}")
+}
 pub fn create_branch_popup_title(
 	_key_config: &SharedKeyConfig,
 ) -> String {
@@ -352,6 +812,26 @@ pub fn create_branch_popup_msg(
 ) -> String {
 	"type branch name".to_string()
 }
+pub fn add_worktree_popup_title(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"Add worktree".to_string()
+}
+pub fn add_worktree_popup_msg(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"path of the new worktree, checks out the branch named after its folder".to_string()
+}
+pub fn add_submodule_popup_title(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"Add submodule".to_string()
+}
+pub fn add_submodule_popup_msg(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"url [path], path defaults to the repo name".to_string()
+}
 pub fn rename_remote_popup_title(
 	_key_config: &SharedKeyConfig,
 ) -> String {
@@ -407,6 +887,12 @@ pub fn password_popup_title(_key_config: &SharedKeyConfig) -> String {
 pub fn password_popup_msg(_key_config: &SharedKeyConfig) -> String {
 	"type password".to_string()
 }
+pub fn token_popup_title(_key_config: &SharedKeyConfig) -> String {
+	"Access Token".to_string()
+}
+pub fn token_popup_msg(_key_config: &SharedKeyConfig) -> String {
+	"type or paste token".to_string()
+}
 
 pub fn rename_branch_popup_title(
 	_key_config: &SharedKeyConfig,
@@ -418,22 +904,39 @@ pub fn rename_branch_popup_msg(
 ) -> String {
 	"new branch name".to_string()
 }
+pub fn copy_branch_popup_title(
+	_key_config: &SharedKeyConfig,
+) -> String {
+	"Copy Branch".to_string()
+}
+
+pub fn patches_written(count: usize, dir: &str) -> String {
+	format!("wrote {count} patch file(s) to {dir}")
+}
 
 pub fn copy_success(s: &str) -> String {
 	format!("{POPUP_SUCCESS_COPY} \"{s}\"")
 }
 
+pub fn key_bindings_capture(name: &str) -> String {
+	format!("press the new key for '{name}' (esc to cancel)")
+}
+
+pub fn key_bindings_conflicts(names: &[&str]) -> String {
+	format!("also bound to: {}", names.join(", "))
+}
+
 pub fn ellipsis_trim_start(s: &str, width: usize) -> Cow<'_, str> {
-	if s.width() <= width {
+	if str_width(s) <= width {
 		Cow::Borrowed(s)
 	} else {
 		Cow::Owned(format!(
 			"[{}]{}",
 			symbol::ELLIPSIS,
-			s.unicode_truncate_start(
+			truncate_start_width(
+				s,
 				width.saturating_sub(3 /* front indicator */)
 			)
-			.0
 		))
 	}
 }
@@ -441,6 +944,7 @@ pub fn ellipsis_trim_start(s: &str, width: usize) -> Cow<'_, str> {
 #[derive(PartialEq, Eq, Clone, Copy)]
 pub enum CheckoutOptions {
 	KeepLocalChanges,
+	StashLocalChanges,
 	DiscardAllLocalChagnes,
 }
 
@@ -448,13 +952,15 @@ impl CheckoutOptions {
 	pub const fn previous(self) -> Self {
 		match self {
 			Self::KeepLocalChanges => Self::DiscardAllLocalChagnes,
-			Self::DiscardAllLocalChagnes => Self::KeepLocalChanges,
+			Self::StashLocalChanges => Self::KeepLocalChanges,
+			Self::DiscardAllLocalChagnes => Self::StashLocalChanges,
 		}
 	}
 
 	pub const fn next(self) -> Self {
 		match self {
-			Self::KeepLocalChanges => Self::DiscardAllLocalChagnes,
+			Self::KeepLocalChanges => Self::StashLocalChanges,
+			Self::StashLocalChanges => Self::DiscardAllLocalChagnes,
 			Self::DiscardAllLocalChagnes => Self::KeepLocalChanges,
 		}
 	}
@@ -464,6 +970,8 @@ impl CheckoutOptions {
 	) -> (&'static str, &'static str) {
 		const CHECKOUT_OPTION_UNCHANGE: &str =
 			" 🟡 Keep local changes";
+		const CHECKOUT_OPTION_STASH: &str =
+			" 🟢 Stash and restore local changes";
 		const CHECKOUT_OPTION_DISCARD: &str =
 			" 🔴 Discard all local changes";
 
@@ -471,6 +979,9 @@ impl CheckoutOptions {
 			Self::KeepLocalChanges => {
 				("Don't change", CHECKOUT_OPTION_UNCHANGE)
 			}
+			Self::StashLocalChanges => {
+				("Stash", CHECKOUT_OPTION_STASH)
+			}
 			Self::DiscardAllLocalChagnes => {
 				("Discard", CHECKOUT_OPTION_DISCARD)
 			}
@@ -504,6 +1015,7 @@ pub mod commit {
 	) -> String {
 		"Info".to_string()
 	}
+	pub static COMPARE_DIRSTAT_TITLE: &str = "Dirstat";
 	pub fn compare_details_info_title(
 		old: bool,
 		hash: &str,
@@ -525,6 +1037,8 @@ pub mod commit {
 pub mod commands {
 	use crate::components::CommandText;
 	use crate::keys::SharedKeyConfig;
+	use crate::options::BranchSort;
+	use asyncgit::sync::TagSortKey;
 
 	static CMD_GROUP_GENERAL: &str = "-- General --";
 	static CMD_GROUP_DIFF: &str = "-- Diff --";
@@ -555,6 +1069,59 @@ pub mod commands {
 			CMD_GROUP_GENERAL,
 		)
 	}
+	pub fn filter_branches(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Filter [{}]",
+				key_config.get_hint(key_config.keys.branch_filter)
+			),
+			"filter branches by substring or glob (`*`, `?`)",
+			CMD_GROUP_BRANCHES,
+		)
+	}
+	pub fn filter_files(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Filter [{}]",
+				key_config.get_hint(key_config.keys.files_filter)
+			),
+			"filter changed files by substring or glob (`*`, `?`)",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn search_contents(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Search contents [{}]",
+				key_config.get_hint(key_config.keys.search_contents)
+			),
+			"search the file contents at this commit",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn hide_remote_branches(
+		key_config: &SharedKeyConfig,
+		filtered: bool,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"{} [{}]",
+				if filtered {
+					"Hide matching"
+				} else {
+					"Show hidden"
+				},
+				key_config
+					.get_hint(key_config.keys.branch_hide_remotes)
+			),
+			"hide remote branches matching the filter from now on",
+			CMD_GROUP_BRANCHES,
+		)
+	}
 	pub fn find_branch(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
@@ -581,6 +1148,28 @@ pub mod commands {
 			CMD_GROUP_GENERAL,
 		)
 	}
+	pub fn switch_repo(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Repo [{}{}]",
+				key_config.get_hint(key_config.keys.repo_prev),
+				key_config.get_hint(key_config.keys.repo_next),
+			),
+			"switch between the opened repositories",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn resize_split(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Resize [{}{}]",
+				key_config.get_hint(key_config.keys.split_left),
+				key_config.get_hint(key_config.keys.split_right),
+			),
+			"move the split between list and details",
+			CMD_GROUP_GENERAL,
+		)
+	}
 	pub fn options_popup(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -603,6 +1192,36 @@ pub mod commands {
 			CMD_GROUP_GENERAL,
 		)
 	}
+	pub fn open_tour(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Tour [{}]",
+				key_config.get_hint(key_config.keys.open_tour)
+			),
+			"walk through the basics of gitui",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn tour_next(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Next [{}]",
+				key_config.get_hint(key_config.keys.enter)
+			),
+			"continue with the next step of the tour",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn tour_back(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Back [{}]",
+				key_config.get_hint(key_config.keys.move_left)
+			),
+			"go back to the previous step of the tour",
+			CMD_GROUP_GENERAL,
+		)
+	}
 	pub fn navigate_commit_message(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -656,34 +1275,225 @@ pub mod commands {
 			CMD_GROUP_GENERAL,
 		)
 	}
-	pub fn copy(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn commit_list_mark_range(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Copy [{}]",
-				key_config.get_hint(key_config.keys.copy),
+				"Mark range [{}]",
+				key_config.get_hint(key_config.keys.log_mark_range),
 			),
-			"copy selected lines to clipboard",
-			CMD_GROUP_DIFF,
+			"mark all commits from the last marked one to the selection",
+			CMD_GROUP_GENERAL,
 		)
 	}
-	pub fn copy_hash(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn log_export_patches(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Copy Hash [{}]",
-				key_config.get_hint(key_config.keys.copy),
+				"Patches [{}]",
+				key_config
+					.get_hint(key_config.keys.log_export_patches),
 			),
-			"copy selected commit hash to clipboard",
+			"export marked commits as patches or a combined diff",
 			CMD_GROUP_LOG,
 		)
 	}
-	pub fn copy_path(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn patch_series_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Copy Path [{}]",
-				key_config.get_hint(key_config.keys.copy),
+				"Run [{}]",
+				key_config.get_hint(key_config.keys.enter),
 			),
-			"copy selected file path to clipboard",
-			CMD_GROUP_LOG,
+			"run the selected export",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn copy(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Copy [{}]",
+				key_config.get_hint(key_config.keys.copy),
+			),
+			"copy selected lines to clipboard",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn copy_menu(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Copy.. [{}]",
+				key_config.get_hint(key_config.keys.copy_menu),
+			),
+			"open clipboard actions for the selection",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn copy_menu_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Copy [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"copy selected entry to clipboard",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn line_actions_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Apply [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"apply the selected action to the lines",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn ignore_pattern_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Ignore [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"add the selected pattern to the ignore file",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn bisect_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Apply [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"apply the selected bisect action to the commit",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn worktree_open(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Switch [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"switch to the selected worktree",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn worktree_add(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Add [{}]",
+				key_config.get_hint(key_config.keys.worktree_add),
+			),
+			"add a linked worktree",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn add_worktree_confirm_msg(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Add worktree [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"add the worktree at the given path",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn worktree_remove(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Remove [{}]",
+				key_config.get_hint(key_config.keys.worktree_remove),
+			),
+			"remove the selected worktree and its folder",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn worktree_lock(
+		key_config: &SharedKeyConfig,
+		locked: bool,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"{} [{}]",
+				if locked { "Unlock" } else { "Lock" },
+				key_config.get_hint(key_config.keys.worktree_lock),
+			),
+			"lock the selected worktree against removal and pruning",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn worktree_prune(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Prune [{}]",
+				key_config.get_hint(key_config.keys.worktree_prune),
+			),
+			"forget the worktrees whose folder is missing",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn ignore_pattern_switch_file(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Switch file [{}]",
+				key_config.get_hint(key_config.keys.tab_toggle),
+			),
+			"switch between .gitignore and .git/info/exclude",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn open_in_browser(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Open in browser [{}]",
+				key_config.get_hint(key_config.keys.open_in_browser),
+			),
+			"open permalink in the web browser",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn copy_hash(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Copy Hash [{}]",
+				key_config.get_hint(key_config.keys.copy),
+			),
+			"copy selected commit hash to clipboard",
+			CMD_GROUP_LOG,
+		)
+	}
+	pub fn copy_path(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Copy Path [{}]",
+				key_config.get_hint(key_config.keys.copy),
+			),
+			"copy selected file path to clipboard",
+			CMD_GROUP_LOG,
 		)
 	}
 	pub fn push_tags(key_config: &SharedKeyConfig) -> CommandText {
@@ -704,224 +1514,742 @@ pub mod commands {
 				"Toggle Option [{}]",
 				key_config.get_hint(key_config.keys.log_mark_commit),
 			),
-			"toggle search option selected",
-			CMD_GROUP_LOG,
+			"toggle search option selected",
+			CMD_GROUP_LOG,
+		)
+	}
+	pub fn show_tag_annotation(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Annotation [{}]",
+				key_config.get_hint(key_config.keys.move_right),
+			),
+			"show tag annotation",
+			CMD_GROUP_LOG,
+		)
+	}
+	pub fn diff_hunk_next(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Next hunk [{}]",
+				key_config.get_hint(key_config.keys.diff_hunk_next),
+			),
+			"move cursor to next hunk, in the next file after the last one",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_split_hunk(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Split hunk [{}]",
+				key_config.get_hint(key_config.keys.diff_split_hunk),
+			),
+			"split the hunk into smaller ones at the unchanged lines between its changes",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_edit_hunk(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Edit hunk [{}]",
+				key_config.get_hint(key_config.keys.diff_edit_hunk),
+			),
+			"edit the hunk in the external editor and stage the result",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_conflict_next(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Next conflict [{}]",
+				key_config
+					.get_hint(key_config.keys.diff_conflict_next),
+			),
+			"move cursor to next merge conflict",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_conflict_prev(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Prev conflict [{}]",
+				key_config
+					.get_hint(key_config.keys.diff_conflict_prev),
+			),
+			"move cursor to prev merge conflict",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_toggle_wrap(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Wrap lines [{}]",
+				key_config.get_hint(key_config.keys.diff_toggle_wrap),
+			),
+			"toggle soft wrapping of long lines",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_normalize_eol(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Normalize EOL [{}]",
+				key_config
+					.get_hint(key_config.keys.diff_normalize_eol),
+			),
+			"replace CRLF with LF in the staged version of the file",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_load_more(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Load more [{}]",
+				key_config.get_hint(key_config.keys.diff_load_more),
+			),
+			"show the next part of a large diff",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_hunk_prev(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Prev hunk [{}]",
+				key_config.get_hint(key_config.keys.diff_hunk_prev),
+			),
+			"move cursor to prev hunk, in the prev file before the first one",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_home_end(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Jump up/down [{},{},{},{}]",
+				key_config.get_hint(key_config.keys.home),
+				key_config.get_hint(key_config.keys.end),
+				key_config.get_hint(key_config.keys.move_up),
+				key_config.get_hint(key_config.keys.move_down)
+			),
+			"scroll to top or bottom of diff",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_hunk_add(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Add hunk [{}]",
+				key_config
+					.get_hint(key_config.keys.stage_unstage_item),
+			),
+			"adds selected hunk to stage",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_hunk_revert(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Reset hunk [{}]",
+				key_config
+					.get_hint(key_config.keys.status_reset_item),
+			),
+			"reverts selected hunk",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_lines_revert(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Reset lines [{}]",
+				key_config.get_hint(key_config.keys.diff_reset_lines),
+			),
+			"resets selected lines",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_lines_stage(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Stage lines [{}]",
+				key_config.get_hint(key_config.keys.diff_stage_lines),
+			),
+			"stage selected lines",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_lines_unstage(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Unstage lines [{}]",
+				key_config.get_hint(key_config.keys.diff_stage_lines),
+			),
+			"unstage selected lines",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn diff_hunk_remove(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Remove hunk [{}]",
+				key_config
+					.get_hint(key_config.keys.stage_unstage_item),
+			),
+			"removes selected hunk from stage",
+			CMD_GROUP_DIFF,
+		)
+	}
+	pub fn close_fuzzy_finder(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Close [{}{}]",
+				key_config.get_hint(key_config.keys.exit_popup),
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"close fuzzy finder",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn close_popup(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Close [{}]",
+				key_config.get_hint(key_config.keys.exit_popup),
+			),
+			"close overlay (e.g commit, help)",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn complete(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Complete [{}]",
+				key_config.get_hint(key_config.keys.tab_toggle),
+			),
+			"cycle through the matching names",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn scroll_popup(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Scroll [{}{}]",
+				key_config.get_hint(key_config.keys.popup_down),
+				key_config.get_hint(key_config.keys.popup_up),
+			),
+			"scroll up or down in popup",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn close_msg(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Close [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"close msg popup (e.g msg)",
+			CMD_GROUP_GENERAL,
+		)
+		.hide_help()
+	}
+	pub fn validate_msg(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Validate [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"validate msg",
+			CMD_GROUP_GENERAL,
+		)
+		.hide_help()
+	}
+
+	pub fn cred_toggle_token(
+		key_config: &SharedKeyConfig,
+		token: bool,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"{} [{}]",
+				if token { "Use password" } else { "Use token" },
+				key_config.get_hint(key_config.keys.tab_toggle),
+			),
+			"enter an access token instead of username and password",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn abort_merge(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Abort merge [{}]",
+				key_config.get_hint(key_config.keys.abort_merge),
+			),
+			"abort ongoing merge",
+			CMD_GROUP_GENERAL,
+		)
+	}
+
+	pub fn abort_revert(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Abort revert [{}]",
+				key_config.get_hint(key_config.keys.abort_merge),
+			),
+			"abort ongoing revert",
+			CMD_GROUP_GENERAL,
+		)
+	}
+
+	pub fn abort_cherrypick(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Abort cherry-pick [{}]",
+				key_config.get_hint(key_config.keys.abort_merge),
+			),
+			"abort ongoing cherry-pick",
+			CMD_GROUP_GENERAL,
+		)
+	}
+
+	pub fn abort_apply_mailbox(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Abort am [{}]",
+				key_config.get_hint(key_config.keys.abort_merge),
+			),
+			"abort ongoing am and drop the patches applied so far",
+			CMD_GROUP_GENERAL,
+		)
+	}
+
+	pub fn view_submodules(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Submodules [{}]",
+				key_config.get_hint(key_config.keys.view_submodules),
+			),
+			"open submodule view",
+			CMD_GROUP_GENERAL,
+		)
+	}
+
+	pub fn branch_flow(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Flow [{}]",
+				key_config.get_hint(key_config.keys.branch_flow),
+			),
+			"start or finish feature, release and hotfix branches",
+			CMD_GROUP_BRANCHES,
+		)
+	}
+	pub fn branch_cleanup(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Clean up [{}]",
+				key_config.get_hint(key_config.keys.branch_cleanup),
+			),
+			"delete merged branches and branches with gone upstreams",
+			CMD_GROUP_BRANCHES,
+		)
+	}
+	pub fn branch_cleanup_mark(
+		key_config: &SharedKeyConfig,
+		marked: bool,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"{} [{}]",
+				if marked { "Unmark" } else { "Mark" },
+				key_config.get_hint(key_config.keys.log_mark_commit),
+			),
+			"mark branch for deletion",
+			CMD_GROUP_BRANCHES,
+		)
+	}
+	pub fn branch_cleanup_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Delete [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"delete all marked branches",
+			CMD_GROUP_BRANCHES,
+		)
+	}
+	pub fn checkout_conflicts_toggle(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Stash/discard [{}]",
+				key_config.get_hint(key_config.keys.log_mark_commit),
+			),
+			"stash or discard the local changes of the file",
+			CMD_GROUP_BRANCHES,
+		)
+	}
+	pub fn checkout_conflicts_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Checkout [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"get the local changes out of the way and checkout",
+			CMD_GROUP_BRANCHES,
+		)
+	}
+	pub fn checkout_conflicts_abort(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Abort [{}]",
+				key_config.get_hint(key_config.keys.exit_popup),
+			),
+			"keep the local changes and do not checkout",
+			CMD_GROUP_BRANCHES,
+		)
+	}
+	pub fn commit_checks_open(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Open [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"open the file at the line in the external editor",
+			CMD_GROUP_COMMIT_POPUP,
+		)
+	}
+	pub fn large_files_toggle(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Skip/stage/lfs [{}]",
+				key_config.get_hint(key_config.keys.log_mark_commit),
+			),
+			"skip the file, stage it as is or track it with git-lfs",
+			CMD_GROUP_CHANGES,
+		)
+	}
+	pub fn large_files_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Apply [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"stage the large files as chosen",
+			CMD_GROUP_CHANGES,
+		)
+	}
+	pub fn large_files_skip(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Skip all [{}]",
+				key_config.get_hint(key_config.keys.exit_popup),
+			),
+			"leave all large files unstaged",
+			CMD_GROUP_CHANGES,
+		)
+	}
+	pub fn flow_confirm(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Select [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"start or finish the selected branch",
+			CMD_GROUP_BRANCHES,
+		)
+	}
+	pub fn view_worktrees(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Worktrees [{}]",
+				key_config.get_hint(key_config.keys.view_worktrees),
+			),
+			"list the worktrees of the repository to switch between",
+			CMD_GROUP_GENERAL,
+		)
+	}
+
+	pub fn absorb(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Absorb [{}]",
+				key_config.get_hint(key_config.keys.absorb),
+			),
+			"turn staged hunks into fixups of the commits that last touched their lines",
+			CMD_GROUP_CHANGES,
 		)
 	}
-	pub fn show_tag_annotation(
+	pub fn ignore_untracked_flood(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Annotation [{}]",
-				key_config.get_hint(key_config.keys.move_right),
+				"Ignore new folder [{}]",
+				key_config
+					.get_hint(key_config.keys.ignore_untracked_flood),
 			),
-			"show tag annotation",
-			CMD_GROUP_LOG,
+			"add the folder many untracked files appeared in to .gitignore",
+			CMD_GROUP_CHANGES,
 		)
 	}
-	pub fn diff_hunk_next(
+	pub fn toggle_inline_blame(
 		key_config: &SharedKeyConfig,
+		shown: bool,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Next hunk [{}]",
-				key_config.get_hint(key_config.keys.diff_hunk_next),
+				"{} blame [{}]",
+				if shown { "Hide" } else { "Show" },
+				key_config
+					.get_hint(key_config.keys.toggle_inline_blame),
 			),
-			"move cursor to next hunk",
-			CMD_GROUP_DIFF,
+			"show commit, age and summary of each line next to it",
+			CMD_GROUP_GENERAL,
 		)
 	}
-	pub fn diff_hunk_prev(
-		key_config: &SharedKeyConfig,
-	) -> CommandText {
+	pub fn apply_patch(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"Prev hunk [{}]",
-				key_config.get_hint(key_config.keys.diff_hunk_prev),
+				"Apply patch [{}]",
+				key_config.get_hint(key_config.keys.apply_patch),
 			),
-			"move cursor to prev hunk",
-			CMD_GROUP_DIFF,
+			"pick a patch or mbox file to apply",
+			CMD_GROUP_GENERAL,
 		)
 	}
-	pub fn diff_home_end(
+	pub fn apply_patch_confirm(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Jump up/down [{},{},{},{}]",
-				key_config.get_hint(key_config.keys.home),
-				key_config.get_hint(key_config.keys.end),
-				key_config.get_hint(key_config.keys.move_up),
-				key_config.get_hint(key_config.keys.move_down)
+				"Open/Apply [{}]",
+				key_config.get_hint(key_config.keys.enter),
 			),
-			"scroll to top or bottom of diff",
-			CMD_GROUP_DIFF,
+			"open the folder or apply the patch file",
+			CMD_GROUP_GENERAL,
 		)
 	}
-	pub fn diff_hunk_add(
+	pub fn apply_patch_location(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Add hunk [{}]",
+				"Location [{}]",
 				key_config
-					.get_hint(key_config.keys.stage_unstage_item),
+					.get_hint(key_config.keys.apply_patch_location),
 			),
-			"adds selected hunk to stage",
-			CMD_GROUP_DIFF,
+			"apply to the worktree, the index or both",
+			CMD_GROUP_GENERAL,
 		)
 	}
-	pub fn diff_hunk_revert(
-		key_config: &SharedKeyConfig,
-	) -> CommandText {
+	pub fn view_trash(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"Reset hunk [{}]",
-				key_config
-					.get_hint(key_config.keys.status_reset_item),
+				"Trash [{}]",
+				key_config.get_hint(key_config.keys.view_trash),
 			),
-			"reverts selected hunk",
-			CMD_GROUP_DIFF,
+			"restore discarded changes from the trash",
+			CMD_GROUP_GENERAL,
 		)
 	}
-	pub fn diff_lines_revert(
-		key_config: &SharedKeyConfig,
-	) -> CommandText {
+	pub fn view_stack(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"Reset lines [{}]",
-				key_config.get_hint(key_config.keys.diff_reset_lines),
+				"Stack [{}]",
+				key_config.get_hint(key_config.keys.view_stack),
 			),
-			"resets selected lines",
-			CMD_GROUP_DIFF,
+			"reorder, fixup and rebase the commits over the upstream",
+			CMD_GROUP_GENERAL,
 		)
 	}
-	pub fn diff_lines_stage(
-		key_config: &SharedKeyConfig,
-	) -> CommandText {
+	pub fn stack_move(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"Stage lines [{}]",
-				key_config.get_hint(key_config.keys.diff_stage_lines),
+				"Move [{}{}]",
+				key_config.get_hint(key_config.keys.shift_up),
+				key_config.get_hint(key_config.keys.shift_down),
 			),
-			"stage selected lines",
-			CMD_GROUP_DIFF,
+			"move the selected commit up or down the stack",
+			CMD_GROUP_GENERAL,
 		)
 	}
-	pub fn diff_lines_unstage(
+	pub fn stack_apply_order(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Unstage lines [{}]",
-				key_config.get_hint(key_config.keys.diff_stage_lines),
+				"Apply order [{}]",
+				key_config.get_hint(key_config.keys.enter),
 			),
-			"unstage selected lines",
-			CMD_GROUP_DIFF,
+			"rewrite the stack in the new order, folding in fixups",
+			CMD_GROUP_GENERAL,
 		)
 	}
-	pub fn diff_hunk_remove(
+	pub fn stack_autosquash(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Remove hunk [{}]",
-				key_config
-					.get_hint(key_config.keys.stage_unstage_item),
+				"Autosquash [{}]",
+				key_config.get_hint(key_config.keys.stack_autosquash),
 			),
-			"removes selected hunk from stage",
-			CMD_GROUP_DIFF,
+			"move fixup! and squash! commits onto their targets",
+			CMD_GROUP_GENERAL,
 		)
 	}
-	pub fn close_fuzzy_finder(
-		key_config: &SharedKeyConfig,
-	) -> CommandText {
+	pub fn stack_fixup(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"Close [{}{}]",
-				key_config.get_hint(key_config.keys.exit_popup),
-				key_config.get_hint(key_config.keys.enter),
+				"Fixup [{}]",
+				key_config.get_hint(key_config.keys.stack_fixup),
 			),
-			"close fuzzy finder",
+			"fold the staged changes into the selected commit",
 			CMD_GROUP_GENERAL,
 		)
 	}
-	pub fn close_popup(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn stack_rebase(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"Close [{}]",
-				key_config.get_hint(key_config.keys.exit_popup),
+				"Rebase [{}]",
+				key_config.get_hint(key_config.keys.rebase_branch),
 			),
-			"close overlay (e.g commit, help)",
+			"rebase the stack onto its upstream",
 			CMD_GROUP_GENERAL,
 		)
 	}
-	pub fn scroll_popup(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn rebase_interactive_move(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Scroll [{}{}]",
-				key_config.get_hint(key_config.keys.popup_down),
-				key_config.get_hint(key_config.keys.popup_up),
+				"Move [{}{}]",
+				key_config.get_hint(key_config.keys.shift_up),
+				key_config.get_hint(key_config.keys.shift_down),
 			),
-			"scroll up or down in popup",
+			"move the selected commit up or down the todo list",
 			CMD_GROUP_GENERAL,
 		)
 	}
-	pub fn close_msg(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn rebase_interactive_action(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		let keys = &key_config.keys;
 		CommandText::new(
 			format!(
-				"Close [{}]",
-				key_config.get_hint(key_config.keys.enter),
+				"Pick/Reword/Edit/Squash/Fixup/Drop [{}{}{}{}{}{}]",
+				key_config.get_hint(keys.rebase_pick),
+				key_config.get_hint(keys.rebase_reword),
+				key_config.get_hint(keys.rebase_edit),
+				key_config.get_hint(keys.rebase_squash),
+				key_config.get_hint(keys.rebase_fixup),
+				key_config.get_hint(keys.rebase_drop),
 			),
-			"close msg popup (e.g msg)",
+			"what to do with the selected commit",
 			CMD_GROUP_GENERAL,
 		)
-		.hide_help()
 	}
-	pub fn validate_msg(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn rebase_interactive_start(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Validate [{}]",
+				"Rebase [{}]",
 				key_config.get_hint(key_config.keys.enter),
 			),
-			"validate msg",
+			"rebase the commits as the todo list says",
 			CMD_GROUP_GENERAL,
 		)
-		.hide_help()
 	}
-
-	pub fn abort_merge(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn rebase_reword_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Abort merge [{}]",
-				key_config.get_hint(key_config.keys.abort_merge),
+				"Confirm [{}]",
+				key_config.get_hint(key_config.keys.commit),
 			),
-			"abort ongoing merge",
+			"use the message when rewording the commit",
 			CMD_GROUP_GENERAL,
 		)
 	}
-
-	pub fn abort_revert(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn trash_restore(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Abort revert [{}]",
-				key_config.get_hint(key_config.keys.abort_merge),
+				"Restore [{}]",
+				key_config.get_hint(key_config.keys.enter),
 			),
-			"abort ongoing revert",
+			"write the discarded files back into the workdir",
 			CMD_GROUP_GENERAL,
 		)
 	}
-
-	pub fn view_submodules(
-		key_config: &SharedKeyConfig,
-	) -> CommandText {
+	pub fn trash_drop(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"Submodules [{}]",
-				key_config.get_hint(key_config.keys.view_submodules),
+				"Drop [{}]",
+				key_config.get_hint(key_config.keys.stash_drop),
 			),
-			"open submodule view",
+			"delete the discarded files for good",
 			CMD_GROUP_GENERAL,
 		)
 	}
@@ -1054,7 +2382,72 @@ pub mod commands {
 				"Update [{}]",
 				key_config.get_hint(key_config.keys.update_submodule),
 			),
-			"update submodule",
+			"checkout the recorded commit, including nested submodules",
+			CMD_GROUP_GENERAL,
+		)
+	}
+
+	pub fn submodule_init(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Init [{}]",
+				key_config.get_hint(key_config.keys.submodule_init),
+			),
+			"register the submodule url in the repo config",
+			CMD_GROUP_GENERAL,
+		)
+	}
+
+	pub fn submodule_sync(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Sync [{}]",
+				key_config.get_hint(key_config.keys.submodule_sync),
+			),
+			"sync the submodule url from .gitmodules",
+			CMD_GROUP_GENERAL,
+		)
+	}
+
+	pub fn submodule_add(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Add [{}]",
+				key_config.get_hint(key_config.keys.submodule_add),
+			),
+			"add a new submodule",
+			CMD_GROUP_GENERAL,
+		)
+	}
+
+	pub fn submodule_remove(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Remove [{}]",
+				key_config.get_hint(key_config.keys.submodule_remove),
+			),
+			"remove the submodule and its checkout",
+			CMD_GROUP_GENERAL,
+		)
+	}
+
+	pub fn add_submodule_confirm_msg(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Add submodule [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"clone and add the submodule",
 			CMD_GROUP_GENERAL,
 		)
 	}
@@ -1153,6 +2546,19 @@ pub mod commands {
 			CMD_GROUP_COMMIT_POPUP,
 		)
 	}
+	pub fn commit_next_template(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Next Template [{}]",
+				key_config
+					.get_hint(key_config.keys.commit_template_next),
+			),
+			"use the next commit message template",
+			CMD_GROUP_COMMIT_POPUP,
+		)
+	}
 	pub fn commit_submit(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -1203,36 +2609,119 @@ pub mod commands {
 			CMD_GROUP_COMMIT_POPUP,
 		)
 	}
-	pub fn commit_signoff(
-		key_config: &SharedKeyConfig,
-	) -> CommandText {
+	pub fn commit_amend_files(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Files [{}]",
+				key_config.get_hint(key_config.keys.amend_files),
+			),
+			"pick the staged files to amend, the others stay staged",
+			CMD_GROUP_COMMIT_POPUP,
+		)
+	}
+	pub fn amend_files_toggle(
+		key_config: &SharedKeyConfig,
+		included: bool,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"{} [{}]",
+				if included { "Leave out" } else { "Include" },
+				key_config.get_hint(key_config.keys.log_mark_commit),
+			),
+			"toggle whether the file goes into the amend",
+			CMD_GROUP_COMMIT_POPUP,
+		)
+	}
+	pub fn commit_signoff(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Sign-off [{}]",
+				key_config.get_hint(key_config.keys.toggle_signoff),
+			),
+			"sign-off commit (-s option)",
+			CMD_GROUP_COMMIT_POPUP,
+		)
+	}
+	pub fn edit_item(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Edit [{}]",
+				key_config.get_hint(key_config.keys.edit_file),
+			),
+			"edit the currently selected file in an external editor",
+			CMD_GROUP_CHANGES,
+		)
+	}
+	pub fn stage_item(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Stage [{}]",
+				key_config
+					.get_hint(key_config.keys.stage_unstage_item),
+			),
+			"stage currently selected file or entire path",
+			CMD_GROUP_CHANGES,
+		)
+	}
+	pub fn stage_marked(
+		key_config: &SharedKeyConfig,
+		count: usize,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Stage {count} marked [{}]",
+				key_config
+					.get_hint(key_config.keys.stage_unstage_item),
+			),
+			"stage all marked files",
+			CMD_GROUP_CHANGES,
+		)
+	}
+	pub fn mark_file(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
-				"Sign-off [{}]",
-				key_config.get_hint(key_config.keys.toggle_signoff),
+				"Mark [{}]",
+				key_config.get_hint(key_config.keys.log_mark_commit),
 			),
-			"sign-off commit (-s option)",
-			CMD_GROUP_COMMIT_POPUP,
+			"mark or unmark the selected file or entire path",
+			CMD_GROUP_CHANGES,
 		)
 	}
-	pub fn edit_item(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn stash_files(
+		key_config: &SharedKeyConfig,
+		marked: usize,
+	) -> CommandText {
 		CommandText::new(
-			format!(
-				"Edit [{}]",
-				key_config.get_hint(key_config.keys.edit_file),
-			),
-			"edit the currently selected file in an external editor",
+			if marked > 0 {
+				format!(
+					"Stash {marked} marked [{}]",
+					key_config.get_hint(key_config.keys.stashing_save),
+				)
+			} else {
+				format!(
+					"Stash selected [{}]",
+					key_config.get_hint(key_config.keys.stashing_save),
+				)
+			},
+			"stash the changes of the marked files only, or of the selected file if none are marked",
 			CMD_GROUP_CHANGES,
 		)
 	}
-	pub fn stage_item(key_config: &SharedKeyConfig) -> CommandText {
+	pub fn stash_untracked(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
 		CommandText::new(
 			format!(
-				"Stage [{}]",
+				"Stash untracked [{}]",
 				key_config
-					.get_hint(key_config.keys.stage_unstage_item),
+					.get_hint(key_config.keys.status_stash_untracked),
 			),
-			"stage currently selected file or entire path",
+			"stash only the untracked files, of the marked files if any are marked",
 			CMD_GROUP_CHANGES,
 		)
 	}
@@ -1257,6 +2746,20 @@ pub mod commands {
 			CMD_GROUP_CHANGES,
 		)
 	}
+	pub fn unstage_marked(
+		key_config: &SharedKeyConfig,
+		count: usize,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Unstage {count} marked [{}]",
+				key_config
+					.get_hint(key_config.keys.stage_unstage_item),
+			),
+			"unstage all marked files",
+			CMD_GROUP_CHANGES,
+		)
+	}
 	pub fn unstage_all(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
@@ -1278,6 +2781,20 @@ pub mod commands {
 			CMD_GROUP_CHANGES,
 		)
 	}
+	pub fn reset_marked(
+		key_config: &SharedKeyConfig,
+		count: usize,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Reset {count} marked [{}]",
+				key_config
+					.get_hint(key_config.keys.status_reset_item),
+			),
+			"revert changes in all marked files",
+			CMD_GROUP_CHANGES,
+		)
+	}
 	pub fn ignore_item(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
@@ -1285,7 +2802,48 @@ pub mod commands {
 				key_config
 					.get_hint(key_config.keys.status_ignore_file),
 			),
-			"Add file or path to .gitignore",
+			"choose a pattern to ignore the file or path with",
+			CMD_GROUP_CHANGES,
+		)
+	}
+	pub fn unignore_item(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Unignore [{}]",
+				key_config
+					.get_hint(key_config.keys.status_ignore_file),
+			),
+			"stop ignoring the file or path in .gitignore",
+			CMD_GROUP_CHANGES,
+		)
+	}
+	pub fn force_add_item(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Force add [{}]",
+				key_config
+					.get_hint(key_config.keys.stage_unstage_item),
+			),
+			"stage the ignored file or path anyway",
+			CMD_GROUP_CHANGES,
+		)
+	}
+	pub fn toggle_ignored(
+		key_config: &SharedKeyConfig,
+		shown: bool,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"{} ignored [{}]",
+				if shown { "Hide" } else { "Show" },
+				key_config
+					.get_hint(key_config.keys.status_toggle_ignored),
+			),
+			"list files excluded by ignore rules",
 			CMD_GROUP_CHANGES,
 		)
 	}
@@ -1526,6 +3084,89 @@ pub mod commands {
 			CMD_GROUP_LOG,
 		)
 	}
+	pub fn goto_parent(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Parent [{}]",
+				key_config.get_hint(key_config.keys.goto_parent),
+			),
+			"jump to the parent commit",
+			CMD_GROUP_LOG,
+		)
+	}
+	pub fn log_special_refs(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Special refs [{}]",
+				key_config.get_hint(key_config.keys.log_special_refs),
+			),
+			"jump to ORIG_HEAD, MERGE_HEAD, CHERRY_PICK_HEAD or FETCH_HEAD",
+			CMD_GROUP_LOG,
+		)
+	}
+	pub fn log_rebase_interactive(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Rebase -i [{}]",
+				key_config
+					.get_hint(key_config.keys.log_rebase_interactive),
+			),
+			"interactively rebase the selected commit and the ones after it",
+			CMD_GROUP_LOG,
+		)
+	}
+	pub fn log_unshallow(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Unshallow [{}]",
+				key_config.get_hint(key_config.keys.log_unshallow),
+			),
+			"fetch the history missing in this shallow clone",
+			CMD_GROUP_LOG,
+		)
+	}
+	pub fn goto_child(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Child [{}]",
+				key_config.get_hint(key_config.keys.goto_child),
+			),
+			"jump to a commit based on this one",
+			CMD_GROUP_LOG,
+		)
+	}
+	pub fn commit_picker_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Go to [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"jump to the selected commit",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn tree_search_confirm(
+		key_config: &SharedKeyConfig,
+		searched: bool,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"{} [{}]",
+				if searched { "Blame" } else { "Search" },
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"search for the text, or blame the file at the selected match",
+			CMD_GROUP_GENERAL,
+		)
+	}
 	pub fn inspect_file_tree(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -1538,6 +3179,18 @@ pub mod commands {
 			CMD_GROUP_LOG,
 		)
 	}
+	pub fn restore_version(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Restore version [{}]",
+				key_config.get_hint(key_config.keys.restore_version),
+			),
+			"restore the file in the workdir to this version",
+			CMD_GROUP_LOG,
+		)
+	}
 	pub fn revert_commit(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -1588,6 +3241,41 @@ pub mod commands {
 			CMD_GROUP_LOG,
 		)
 	}
+	pub fn log_bisect(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Bisect [{}]",
+				key_config.get_hint(key_config.keys.log_bisect),
+			),
+			"mark the commit good or bad to find the one introducing a change",
+			CMD_GROUP_LOG,
+		)
+	}
+	pub fn log_bisect_stop(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Stop bisect run [{}]",
+				key_config.get_hint(key_config.keys.exit_popup),
+			),
+			"stop running the bisect command after the current commit",
+			CMD_GROUP_LOG,
+		)
+	}
+	pub fn log_toggle_decorations(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Decorations [{}]",
+				key_config
+					.get_hint(key_config.keys.log_toggle_decorations),
+			),
+			"show or hide branches and tags next to the commits",
+			CMD_GROUP_LOG,
+		)
+	}
 	pub fn log_close_search(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -1703,6 +3391,31 @@ pub mod commands {
 		)
 		.hide_help()
 	}
+	pub fn copy_branch_confirm_msg(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Copy Branch [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"copy branch",
+			CMD_GROUP_BRANCHES,
+		)
+		.hide_help()
+	}
+	pub fn copy_branch_popup(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Copy Branch [{}]",
+				key_config.get_hint(key_config.keys.copy_branch),
+			),
+			"create a branch at the same commit with the same upstream",
+			CMD_GROUP_BRANCHES,
+		)
+	}
 	pub fn rename_branch_popup(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -1805,6 +3518,39 @@ pub mod commands {
 			CMD_GROUP_BRANCHES,
 		)
 	}
+	pub fn sort_branches(
+		key_config: &SharedKeyConfig,
+		sort: BranchSort,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Sort: {} [{}]",
+				sort.label(),
+				key_config.get_hint(key_config.keys.branch_sort),
+			),
+			"sort branches by name, recency or ahead count",
+			CMD_GROUP_BRANCHES,
+		)
+	}
+	pub fn sort_tags(
+		key_config: &SharedKeyConfig,
+		sort: TagSortKey,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Sort: {} [{}]",
+				match sort {
+					TagSortKey::CommitDate => "commit date",
+					TagSortKey::CreatorDate => "creator date",
+					TagSortKey::Version => "version",
+					TagSortKey::Name => "name",
+				},
+				key_config.get_hint(key_config.keys.tag_sort),
+			),
+			"sort tags by commit date, creator date, version or name",
+			CMD_GROUP_GENERAL,
+		)
+	}
 	pub fn open_branch_select_popup(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -1910,6 +3656,19 @@ pub mod commands {
 		)
 	}
 
+	pub fn log_search_cycle_mode(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Mode [{}]",
+				key_config.get_hint(key_config.keys.tab_toggle),
+			),
+			"cycle through message, regex, author and diff content search",
+			CMD_GROUP_LOG,
+		)
+	}
+
 	pub fn find_commit_sha(
 		key_config: &SharedKeyConfig,
 	) -> CommandText {
@@ -1923,6 +3682,80 @@ pub mod commands {
 		)
 	}
 
+	pub fn macro_record(
+		key_config: &SharedKeyConfig,
+		recording: bool,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"{} [{}]",
+				if recording {
+					"Stop macro"
+				} else {
+					"Record macro"
+				},
+				key_config.get_hint(key_config.keys.macro_record),
+			),
+			"record keys to replay them later",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn macro_replay(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Replay macro [{}]",
+				key_config.get_hint(key_config.keys.macro_replay),
+			),
+			"replay the recorded keys a number of times",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn reload_config(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Reload config [{}]",
+				key_config.get_hint(key_config.keys.reload_config),
+			),
+			"re-read theme and key bindings from disk",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn refresh(key_config: &SharedKeyConfig) -> CommandText {
+		CommandText::new(
+			format!(
+				"Refresh [{}]",
+				key_config.get_hint(key_config.keys.refresh),
+			),
+			"reload the repo state and show which jobs re-ran",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn key_binding_change(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Change [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"bind the selected action to the next key pressed",
+			CMD_GROUP_GENERAL,
+		)
+	}
+	pub fn macro_replay_confirm(
+		key_config: &SharedKeyConfig,
+	) -> CommandText {
+		CommandText::new(
+			format!(
+				"Replay [{}]",
+				key_config.get_hint(key_config.keys.enter),
+			),
+			"replay the macro the given number of times",
+			CMD_GROUP_GENERAL,
+		)
+	}
 	pub fn goto_line(key_config: &SharedKeyConfig) -> CommandText {
 		CommandText::new(
 			format!(
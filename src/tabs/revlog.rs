@@ -1,3 +1,5 @@
+#[cfg(feature = "forge-api")]
+use crate::forge::CommitChecks;
 use crate::{
 	app::Environment,
 	components::{
@@ -6,22 +8,27 @@ use crate::{
 		DrawableComponent, EventState,
 	},
 	keys::{key_match, SharedKeyConfig},
-	popups::{FileTreeOpen, InspectCommitOpen},
+	options::{SharedOptions, SplitTab, UiState},
+	popups::{
+		goto_relative, goto_special_ref, CommitJumpTarget,
+		CopyContext, FileTreeOpen, InspectCommitOpen,
+		PatchSeriesContext,
+	},
 	queue::{InternalEvent, Queue, StackablePopupOpen},
 	strings::{self, order},
 	try_or_popup,
-	ui::style::{SharedTheme, Theme},
+	ui::style::SharedTheme,
 };
 use anyhow::Result;
 use asyncgit::{
 	asyncjob::AsyncSingleJob,
 	sync::{
-		self, filter_commit_by_search, CommitId, LogFilterSearch,
-		LogFilterSearchOptions, RepoPathRef,
+		self, filter_commit_by_search, BisectState, CommitId,
+		LogFilterSearch, LogFilterSearchOptions, RepoPathRef,
 	},
-	AsyncBranchesJob, AsyncCommitFilterJob, AsyncGitNotification,
-	AsyncLog, AsyncTags, CommitFilesParams, FetchStatus,
-	ProgressPercent,
+	AsyncBisectJob, AsyncBranchesJob, AsyncCommitFilterJob,
+	AsyncGitNotification, AsyncLog, AsyncTags, CommitFilesParams,
+	FetchStatus, ProgressPercent,
 };
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
@@ -32,14 +39,7 @@ use ratatui::{
 	widgets::{Block, Borders, Paragraph},
 	Frame,
 };
-use std::{
-	rc::Rc,
-	sync::{
-		atomic::{AtomicBool, Ordering},
-		Arc,
-	},
-	time::Duration,
-};
+use std::{rc::Rc, time::Duration};
 use sync::CommitTags;
 
 struct LogSearchResult {
@@ -54,7 +54,8 @@ enum LogSearch {
 		AsyncSingleJob<AsyncCommitFilterJob>,
 		LogFilterSearchOptions,
 		Option<ProgressPercent>,
-		Arc<AtomicBool>,
+		// cancelled by the user
+		bool,
 	),
 	Results(LogSearchResult),
 }
@@ -69,23 +70,36 @@ pub struct Revlog {
 	git_tags: AsyncTags,
 	git_local_branches: AsyncSingleJob<AsyncBranchesJob>,
 	git_remote_branches: AsyncSingleJob<AsyncBranchesJob>,
+	bisect: Option<BisectState>,
+	git_bisect: AsyncSingleJob<AsyncBisectJob>,
+	/// running bisect command, kept to cancel it
+	bisect_run: Option<AsyncBisectJob>,
+	/// whether the history is cut off by a shallow clone
+	shallow: bool,
 	queue: Queue,
 	visible: bool,
 	key_config: SharedKeyConfig,
 	sender: Sender<AsyncGitNotification>,
 	theme: SharedTheme,
+	options: SharedOptions,
+	restore_selection: Option<CommitId>,
+	restore_search: Option<LogFilterSearchOptions>,
+	#[cfg(feature = "forge-api")]
+	checks: CommitChecks,
 }
 
 impl Revlog {
 	///
 	pub fn new(env: &Environment) -> Self {
+		let ui_state = env.options.borrow().ui_state().clone();
+
 		Self {
 			repo: env.repo.clone(),
 			queue: env.queue.clone(),
 			commit_details: CommitDetailsComponent::new(env),
 			list: CommitList::new(
 				env,
-				&strings::log_title(&env.key_config),
+				&strings::log_title(&env.key_config, false),
 			),
 			git_log: AsyncLog::new(
 				env.repo.borrow().clone(),
@@ -103,10 +117,22 @@ impl Revlog {
 			git_remote_branches: AsyncSingleJob::new(
 				env.sender_git.clone(),
 			),
+			bisect: None,
+			git_bisect: AsyncSingleJob::new(env.sender_git.clone()),
+			bisect_run: None,
+			shallow: false,
 			visible: false,
 			key_config: env.key_config.clone(),
 			sender: env.sender_git.clone(),
 			theme: env.theme.clone(),
+			options: env.options.clone(),
+			restore_selection: ui_state.log_selection(),
+			restore_search: ui_state.log_search(),
+			#[cfg(feature = "forge-api")]
+			checks: CommitChecks::new(
+				env.repo.clone(),
+				&env.sender_app,
+			),
 		}
 	}
 
@@ -117,6 +143,7 @@ impl Revlog {
 			|| self.git_tags.is_pending()
 			|| self.git_local_branches.is_pending()
 			|| self.git_remote_branches.is_pending()
+			|| self.git_bisect.is_pending()
 			|| self.commit_details.any_work_pending()
 	}
 
@@ -134,8 +161,15 @@ impl Revlog {
 			self.list
 				.refresh_extend_data(self.git_log.extract_items()?);
 
+			self.update_bisect();
+			self.update_shallow();
+			self.restore_state()?;
+
 			self.git_tags.request(Duration::from_secs(3), false)?;
 
+			#[cfg(feature = "forge-api")]
+			self.checks.request(&self.list.visible_commits());
+
 			if self.commit_details.is_visible() {
 				let commit = self.selected_commit();
 				let tags = self.selected_commit_tags(commit.as_ref());
@@ -150,11 +184,55 @@ impl Revlog {
 		Ok(())
 	}
 
+	/// applies the state of the last session once the log is loaded
+	fn restore_state(&mut self) -> Result<()> {
+		let loaded =
+			!self.git_log.is_pending() && self.git_log.count()? > 0;
+
+		if let Some(id) = self.restore_selection {
+			if self.list.select_commit(id).is_ok() || loaded {
+				self.restore_selection = None;
+			}
+		}
+
+		if loaded && self.can_start_search() {
+			if let Some(options) = self.restore_search.take() {
+				self.search(options);
+			}
+		}
+
+		Ok(())
+	}
+
+	/// stores the state to restore in the next session
+	pub fn store_state(&self, state: &mut UiState) {
+		state.set_log_selection(
+			self.restore_selection.or_else(|| self.selected_commit()),
+		);
+
+		let search = match &self.search {
+			LogSearch::Off => self.restore_search.as_ref(),
+			LogSearch::Searching(_, options, _, _) => Some(options),
+			LogSearch::Results(results) => Some(&results.options),
+		};
+		state.set_log_search(search);
+	}
+
+	/// takes the result of a finished ci state query
+	#[cfg(feature = "forge-api")]
+	pub fn update_checks(&mut self) {
+		self.list.set_ci_states(self.checks.update());
+	}
+
 	///
 	pub fn update_git(
 		&mut self,
 		ev: AsyncGitNotification,
 	) -> Result<()> {
+		if ev == AsyncGitNotification::Bisect {
+			self.update_bisect_run()?;
+		}
+
 		if self.visible {
 			match ev {
 				AsyncGitNotification::CommitFiles
@@ -241,6 +319,139 @@ impl Revlog {
 		}
 	}
 
+	/// marked commits, or the selected one if none are marked
+	fn export_patches(&self) {
+		let (mut commits, contiguous) =
+			if self.list.marked_count() > 0 {
+				(
+					self.list.marked_commits(),
+					self.list.marked_contiguous(),
+				)
+			} else {
+				(self.selected_commit().into_iter().collect(), true)
+			};
+
+		// the log lists the newest first
+		commits.reverse();
+
+		self.queue.push(InternalEvent::OpenPatchSeriesPopup(
+			PatchSeriesContext {
+				commits,
+				contiguous,
+			},
+		));
+	}
+
+	/// tests the commits of the bisect with `command` until the first
+	/// bad one is found
+	pub fn bisect_run(&mut self, command: String) {
+		if self.git_bisect.is_pending() {
+			return;
+		}
+
+		let job =
+			AsyncBisectJob::new(self.repo.borrow().clone(), command);
+		self.bisect_run = Some(job.clone());
+		self.git_bisect.spawn(job);
+	}
+
+	/// selects the commit to test next once the log is reloaded
+	fn update_bisect(&mut self) {
+		let bisect = sync::bisect_state(&self.repo.borrow())
+			.unwrap_or_else(|e| {
+				log::error!("bisect state error: {e}");
+				None
+			});
+
+		let target = |bisect: Option<&BisectState>| {
+			bisect.and_then(|state| state.found.or(state.next))
+		};
+		let next = target(bisect.as_ref());
+		if next.is_some() && next != target(self.bisect.as_ref()) {
+			self.restore_selection = next;
+		}
+
+		self.bisect = bisect;
+	}
+
+	fn update_shallow(&mut self) {
+		let shallow = sync::is_shallow(&self.repo.borrow())
+			.unwrap_or_else(|e| {
+				log::error!("shallow check error: {e}");
+				false
+			});
+
+		if shallow != self.shallow {
+			self.shallow = shallow;
+			self.list.set_title(&strings::log_title(
+				&self.key_config,
+				shallow,
+			));
+		}
+	}
+
+	fn update_bisect_run(&mut self) -> Result<()> {
+		if !self.git_bisect.is_pending() {
+			if let Some(result) = self
+				.git_bisect
+				.take_last()
+				.and_then(|job| job.result())
+			{
+				self.bisect_run = None;
+
+				if let Err(e) = result {
+					self.queue.push(InternalEvent::ShowErrorMsg(
+						format!("bisect run error:\n{e}"),
+					));
+				}
+			}
+		}
+
+		self.update()
+	}
+
+	fn draw_bisect(&self, f: &mut Frame, area: Rect) {
+		let Some(state) = &self.bisect else {
+			return;
+		};
+
+		let title = self.bisect_run.as_ref().map_or_else(
+			|| strings::POPUP_TITLE_BISECT.to_string(),
+			|job| {
+				format!(
+					"{} `{}` ({}%)",
+					strings::POPUP_TITLE_BISECT,
+					job.command(),
+					self.git_bisect
+						.progress()
+						.map(|progress| progress.progress)
+						.unwrap_or_default()
+				)
+			},
+		);
+
+		f.render_widget(
+			Paragraph::new(strings::bisect_progress(state))
+				.block(
+					Block::default()
+						.title(Span::styled(
+							title,
+							self.theme.title(true),
+						))
+						.borders(Borders::ALL)
+						.border_style(self.theme.attention_block()),
+				)
+				.alignment(Alignment::Left),
+			area,
+		);
+	}
+
+	fn toggle_decorations(&self) {
+		let mut decorations = self.options.borrow().log_decorations();
+		decorations.refs = !decorations.refs;
+		self.options.borrow_mut().set_log_decorations(decorations);
+	}
+
 	pub fn search(&mut self, options: LogFilterSearchOptions) {
 		if !self.can_start_search() {
 			return;
@@ -256,32 +467,26 @@ impl Revlog {
 				LogFilterSearch::new(options.clone()),
 			);
 
-			let cancellation_flag = Arc::new(AtomicBool::new(false));
-
 			let job = AsyncSingleJob::new(self.sender.clone());
 			job.spawn(AsyncCommitFilterJob::new(
 				self.repo.borrow().clone(),
 				self.list.copy_items(),
 				filter,
-				Arc::clone(&cancellation_flag),
 			));
 
-			self.search = LogSearch::Searching(
-				job,
-				options,
-				None,
-				Arc::clone(&cancellation_flag),
-			);
+			self.search =
+				LogSearch::Searching(job, options, None, false);
 
 			self.list.set_highlighting(None);
 		}
 	}
 
 	fn cancel_search(&mut self) -> bool {
-		if let LogSearch::Searching(_, _, _, cancellation_flag) =
-			&self.search
+		if let LogSearch::Searching(job, _, _, cancelled) =
+			&mut self.search
 		{
-			cancellation_flag.store(true, Ordering::Relaxed);
+			job.cancel();
+			*cancelled = true;
 			self.list.set_highlighting(None);
 			return true;
 		}
@@ -307,10 +512,7 @@ impl Revlog {
 				{
 					match search {
 						Ok(search) => {
-							let was_aborted =
-								cancel.load(Ordering::Relaxed);
-
-							self.search = if was_aborted {
+							self.search = if *cancel {
 								LogSearch::Off
 							} else {
 								self.list.set_highlighting(Some(
@@ -321,6 +523,11 @@ impl Revlog {
 											.collect::<IndexSet<_>>(),
 									),
 								));
+								self.list.set_search(Some(
+									LogFilterSearch::new(
+										options.clone(),
+									),
+								));
 
 								LogSearch::Results(LogSearchResult {
 									options: options.clone(),
@@ -390,7 +597,7 @@ impl Revlog {
 							self.theme.title(true),
 						))
 						.borders(Borders::ALL)
-						.border_style(Theme::attention_block()),
+						.border_style(self.theme.attention_block()),
 				)
 				.alignment(Alignment::Left),
 			area,
@@ -408,24 +615,25 @@ impl Revlog {
 
 impl DrawableComponent for Revlog {
 	fn draw(&self, f: &mut Frame, area: Rect) -> Result<()> {
-		let area = if self.is_in_search_mode() {
-			Layout::default()
-				.direction(Direction::Vertical)
-				.constraints(
-					[Constraint::Min(1), Constraint::Length(3)]
-						.as_ref(),
-				)
-				.split(area)
-		} else {
-			Rc::new([area])
-		};
+		let bars = [self.bisect.is_some(), self.is_in_search_mode()];
+		let area = Layout::default()
+			.direction(Direction::Vertical)
+			.constraints(
+				std::iter::once(Constraint::Min(1)).chain(
+					bars.iter()
+						.filter(|shown| **shown)
+						.map(|_| Constraint::Length(3)),
+				),
+			)
+			.split(area);
 
+		let ratio = self.options.borrow().split_ratio(SplitTab::Log);
 		let chunks = Layout::default()
 			.direction(Direction::Horizontal)
 			.constraints(
 				[
-					Constraint::Percentage(60),
-					Constraint::Percentage(40),
+					Constraint::Percentage(ratio),
+					Constraint::Percentage(100 - ratio),
 				]
 				.as_ref(),
 			)
@@ -438,8 +646,12 @@ impl DrawableComponent for Revlog {
 			self.list.draw(f, area[0])?;
 		}
 
+		if self.bisect.is_some() {
+			self.draw_bisect(f, area[1]);
+		}
+
 		if self.is_in_search_mode() {
-			self.draw_search(f, area[1]);
+			self.draw_search(f, area[area.len() - 1]);
 		}
 
 		Ok(())
@@ -465,7 +677,9 @@ impl Component for Revlog {
 					k,
 					self.key_config.keys.exit_popup,
 				) {
-					if self.is_search_pending() {
+					if self.bisect_run.is_some() {
+						self.git_bisect.cancel();
+					} else if self.is_search_pending() {
 						self.cancel_search();
 					} else if self.can_close_search() {
 						self.list.set_highlighting(None);
@@ -479,6 +693,19 @@ impl Component for Revlog {
 						self.list.copy_commit_hash()
 					);
 					return Ok(EventState::Consumed);
+				} else if key_match(k, self.key_config.keys.copy_menu)
+				{
+					if let Some(commit) = self.selected_commit() {
+						self.queue.push(
+							InternalEvent::OpenCopyPopup(
+								CopyContext {
+									commit: Some(commit),
+									..CopyContext::default()
+								},
+							),
+						);
+					}
+					return Ok(EventState::Consumed);
 				} else if key_match(k, self.key_config.keys.push) {
 					self.queue.push(InternalEvent::PushTags);
 					return Ok(EventState::Consumed);
@@ -541,6 +768,73 @@ impl Component for Revlog {
 				} else if key_match(k, self.key_config.keys.tags) {
 					self.queue.push(InternalEvent::Tags);
 					return Ok(EventState::Consumed);
+				} else if key_match(
+					k,
+					self.key_config.keys.log_export_patches,
+				) && !self.is_search_pending()
+				{
+					self.export_patches();
+					return Ok(EventState::Consumed);
+				} else if (key_match(
+					k,
+					self.key_config.keys.goto_parent,
+				) || key_match(
+					k,
+					self.key_config.keys.goto_child,
+				)) && !self.is_search_pending()
+				{
+					if let Some(id) = self.selected_commit() {
+						try_or_popup!(
+							self,
+							"jump error:",
+							goto_relative(
+								&self.repo.borrow(),
+								&self.queue,
+								id,
+								key_match(
+									k,
+									self.key_config.keys.goto_parent,
+								),
+								CommitJumpTarget::Log,
+							)
+						);
+					}
+					return Ok(EventState::Consumed);
+				} else if key_match(
+					k,
+					self.key_config.keys.log_rebase_interactive,
+				) && !self.is_search_pending()
+				{
+					if let Some(id) = self.selected_commit() {
+						self.queue.push(
+							InternalEvent::RebaseInteractive(id),
+						);
+					}
+					return Ok(EventState::Consumed);
+				} else if key_match(
+					k,
+					self.key_config.keys.log_unshallow,
+				) && self.shallow
+				{
+					self.queue.push(InternalEvent::Unshallow);
+					return Ok(EventState::Consumed);
+				} else if key_match(
+					k,
+					self.key_config.keys.log_special_refs,
+				) && !self.is_search_pending()
+				{
+					if let Some(id) = self.selected_commit() {
+						try_or_popup!(
+							self,
+							"jump error:",
+							goto_special_ref(
+								&self.repo.borrow(),
+								&self.queue,
+								id,
+							)
+						);
+					}
+					return Ok(EventState::Consumed);
 				} else if key_match(
 					k,
 					self.key_config.keys.log_reset_commit,
@@ -575,6 +869,26 @@ impl Component for Revlog {
 					self.queue
 						.push(InternalEvent::OpenLogSearchPopup);
 					return Ok(EventState::Consumed);
+				} else if key_match(
+					k,
+					self.key_config.keys.log_bisect,
+				) && self.bisect_run.is_none()
+				{
+					return self.selected_commit().map_or(
+						Ok(EventState::NotConsumed),
+						|id| {
+							self.queue.push(
+								InternalEvent::OpenBisectPopup(id),
+							);
+							Ok(EventState::Consumed)
+						},
+					);
+				} else if key_match(
+					k,
+					self.key_config.keys.log_toggle_decorations,
+				) {
+					self.toggle_decorations();
+					return Ok(EventState::Consumed);
 				} else if key_match(
 					k,
 					self.key_config.keys.compare_commits,
@@ -612,6 +926,7 @@ impl Component for Revlog {
 		Ok(EventState::NotConsumed)
 	}
 
+	#[allow(clippy::too_many_lines)]
 	fn commands(
 		&self,
 		out: &mut Vec<CommandInfo>,
@@ -639,6 +954,44 @@ impl Component for Revlog {
 			self.visible,
 		));
 
+		out.push(CommandInfo::new(
+			strings::commands::log_export_patches(&self.key_config),
+			self.selected_commit().is_some(),
+			self.visible || force_all,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::goto_parent(&self.key_config),
+			self.selected_commit().is_some(),
+			self.visible || force_all,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::goto_child(&self.key_config),
+			self.selected_commit().is_some(),
+			self.visible || force_all,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::log_special_refs(&self.key_config),
+			self.selected_commit().is_some(),
+			self.visible || force_all,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::log_rebase_interactive(
+				&self.key_config,
+			),
+			self.selected_commit().is_some(),
+			self.visible || force_all,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::log_unshallow(&self.key_config),
+			self.shallow,
+			(self.visible && self.shallow) || force_all,
+		));
+
 		out.push(CommandInfo::new(
 			strings::commands::commit_details_open(&self.key_config),
 			true,
@@ -678,6 +1031,12 @@ impl Component for Revlog {
 			self.visible || force_all,
 		));
 
+		out.push(CommandInfo::new(
+			strings::commands::copy_menu(&self.key_config),
+			self.selected_commit().is_some(),
+			self.visible || force_all,
+		));
+
 		out.push(CommandInfo::new(
 			strings::commands::log_tag_commit(&self.key_config),
 			self.selected_commit().is_some(),
@@ -729,6 +1088,24 @@ impl Component for Revlog {
 			self.can_start_search(),
 			self.visible || force_all,
 		));
+		out.push(CommandInfo::new(
+			strings::commands::log_bisect(&self.key_config),
+			self.selected_commit().is_some()
+				&& self.bisect_run.is_none(),
+			self.visible || force_all,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::log_bisect_stop(&self.key_config),
+			true,
+			(self.visible && self.bisect_run.is_some()) || force_all,
+		));
+		out.push(CommandInfo::new(
+			strings::commands::log_toggle_decorations(
+				&self.key_config,
+			),
+			true,
+			self.visible || force_all,
+		));
 
 		visibility_blocking(self)
 	}
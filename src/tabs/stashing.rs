@@ -24,10 +24,14 @@ use ratatui::{
 };
 use std::borrow::Cow;
 
-#[derive(Default, Clone, Copy, Debug)]
+#[derive(Default, Clone, Debug)]
 pub struct StashingOptions {
 	pub stash_untracked: bool,
 	pub keep_index: bool,
+	/// stash only these paths, all changes if empty
+	pub paths: Vec<String>,
+	/// stash only the untracked files (of `paths`)
+	pub untracked_only: bool,
 }
 
 pub struct Stashing {
@@ -57,6 +61,8 @@ impl Stashing {
 			options: StashingOptions {
 				keep_index: false,
 				stash_untracked: true,
+				paths: Vec::new(),
+				untracked_only: false,
 			},
 			theme: env.theme.clone(),
 			git_status: AsyncStatus::new(
@@ -73,7 +79,11 @@ impl Stashing {
 		if self.is_visible() {
 			self.git_status
 				//TODO: support options
-				.fetch(&StatusParams::new(StatusType::Both, None))?;
+				.fetch(&StatusParams::new(
+					StatusType::Both,
+					None,
+					false,
+				))?;
 		}
 
 		Ok(())
@@ -222,7 +232,7 @@ impl Component for Stashing {
 				) && !self.index.is_empty()
 				{
 					self.queue.push(InternalEvent::PopupStashing(
-						self.options,
+						self.options.clone(),
 					));
 
 					Ok(EventState::Consumed)
@@ -1,3 +1,5 @@
+#[cfg(feature = "forge-api")]
+use crate::forge::{BranchReview, PullRequest};
 use crate::{
 	accessors,
 	app::Environment,
@@ -5,31 +7,32 @@ use crate::{
 		command_pump, event_pump, visibility_blocking,
 		ChangesComponent, CommandBlocking, CommandInfo, Component,
 		DiffComponent, DrawableComponent, EventState,
-		FileTreeItemKind,
+		FileTreeItemKind, HunkJump, UntrackedNotice,
 	},
 	keys::{key_match, SharedKeyConfig},
-	options::SharedOptions,
+	options::{SharedOptions, SplitTab, UiState},
 	queue::{Action, InternalEvent, NeedsUpdate, Queue, ResetItem},
 	strings, try_or_popup,
-	ui::style::Theme,
+	ui::style::SharedTheme,
 };
 use anyhow::Result;
 use asyncgit::{
 	cached,
 	sync::{
-		self, status::StatusType, RepoPath, RepoPathRef, RepoState,
+		self, status::StatusType, IgnoreFile, RepoPath, RepoPathRef,
+		RepoState,
 	},
 	sync::{BranchCompare, CommitId},
 	AsyncDiff, AsyncGitNotification, AsyncStatus, DiffParams,
-	DiffType, PushType, StatusItem, StatusParams,
+	DiffType, PushType, StatusItem, StatusItemType, StatusParams,
 };
-use crossterm::event::Event;
+use crossterm::event::{Event, MouseEvent, MouseEventKind};
 use itertools::Itertools;
 use ratatui::{
-	layout::{Alignment, Constraint, Direction, Layout},
-	style::{Color, Style},
+	layout::{Alignment, Constraint, Direction, Layout, Rect},
 	widgets::{Block, BorderType, Borders, Paragraph},
 };
+use std::cell::Cell;
 
 /// what part of the screen is focused
 #[derive(PartialEq)]
@@ -77,10 +80,16 @@ pub struct Status {
 	git_status_stage: AsyncStatus,
 	git_branch_state: Option<BranchCompare>,
 	git_branch_name: cached::BranchName,
+	#[cfg(feature = "forge-api")]
+	review: BranchReview,
 	queue: Queue,
 	git_action_executed: bool,
+	/// unstaged and staged list as last drawn
+	pane_areas: Cell<[Rect; 2]>,
+	untracked_notice: UntrackedNotice,
 	options: SharedOptions,
 	key_config: SharedKeyConfig,
+	theme: SharedTheme,
 }
 
 impl DrawableComponent for Status {
@@ -111,9 +120,13 @@ impl DrawableComponent for Status {
 						Constraint::Percentage(100),
 					]
 				} else {
+					let ratio = self
+						.options
+						.borrow()
+						.split_ratio(SplitTab::Status);
 					[
-						Constraint::Percentage(50),
-						Constraint::Percentage(50),
+						Constraint::Percentage(ratio),
+						Constraint::Percentage(100 - ratio),
 					]
 				}
 				.as_ref(),
@@ -138,10 +151,12 @@ impl DrawableComponent for Status {
 			)
 			.split(chunks[0]);
 
+		self.pane_areas.set([left_chunks[0], left_chunks[1]]);
 		self.index_wd.draw(f, left_chunks[0])?;
 		self.index.draw(f, left_chunks[1])?;
 		self.diff.draw(f, chunks[1])?;
 		self.draw_branch_state(f, &left_chunks);
+		self.untracked_notice.draw(f, left_chunks[0]);
 
 		if repo_unclean {
 			self.draw_repo_state(f, rects[1]);
@@ -157,6 +172,9 @@ impl Status {
 	///
 	pub fn new(env: &Environment) -> Self {
 		let repo_clone = env.repo.borrow().clone();
+		let stage_focused =
+			env.options.borrow().ui_state().status_stage_focused;
+
 		Self {
 			queue: env.queue.clone(),
 			visible: true,
@@ -165,21 +183,30 @@ impl Status {
 				has_remote_for_push: false,
 			},
 			git_state: RepoState::Clean,
-			focus: Focus::WorkDir,
-			diff_target: DiffTarget::WorkingDir,
+			focus: if stage_focused {
+				Focus::Stage
+			} else {
+				Focus::WorkDir
+			},
+			diff_target: if stage_focused {
+				DiffTarget::Stage
+			} else {
+				DiffTarget::WorkingDir
+			},
 			index_wd: ChangesComponent::new(
 				env,
 				&strings::title_status(&env.key_config),
-				true,
+				!stage_focused,
 				true,
 			),
 			index: ChangesComponent::new(
 				env,
 				&strings::title_index(&env.key_config),
-				false,
+				stage_focused,
 				false,
 			),
-			diff: DiffComponent::new(env, false),
+			diff: DiffComponent::new(env, false)
+				.with_jumps_across_files(),
 			git_diff: AsyncDiff::new(
 				repo_clone.clone(),
 				&env.sender_git,
@@ -193,11 +220,22 @@ impl Status {
 				env.sender_git.clone(),
 			),
 			git_action_executed: false,
+			pane_areas: Cell::new([Rect::default(); 2]),
+			untracked_notice: UntrackedNotice::new(
+				env.key_config.clone(),
+				env.theme.clone(),
+			),
 			git_branch_state: None,
 			git_branch_name: cached::BranchName::new(
 				env.repo.clone(),
 			),
+			#[cfg(feature = "forge-api")]
+			review: BranchReview::new(
+				env.repo.clone(),
+				&env.sender_app,
+			),
 			key_config: env.key_config.clone(),
+			theme: env.theme.clone(),
 			options: env.options.clone(),
 			repo: env.repo.clone(),
 		}
@@ -219,8 +257,16 @@ impl Status {
 					)
 				});
 
+			#[cfg(feature = "forge-api")]
+			let pull_request =
+				self.review.last().map_or_else(String::new, |pr| {
+					format!("{} ", pr.summary())
+				});
+			#[cfg(not(feature = "forge-api"))]
+			let pull_request = "";
+
 			let w = Paragraph::new(format!(
-				"{ahead_behind}{{{branch_name}}}"
+				"{pull_request}{ahead_behind}{{{branch_name}}}"
 			))
 			.alignment(Alignment::Right);
 
@@ -280,7 +326,28 @@ impl Status {
 						.unwrap_or_default(),
 				)
 			}
-			_ => format!("{state:?}"),
+			RepoState::CherryPick => {
+				format!(
+					"Cherry-pick {}",
+					sync::cherrypick_head(repo)
+						.ok()
+						.as_ref()
+						.map(CommitId::get_short_string)
+						.unwrap_or_default(),
+				)
+			}
+			RepoState::Bisect => sync::bisect_state(repo)
+				.ok()
+				.flatten()
+				.map(|state| strings::bisect_progress(&state))
+				.unwrap_or_default(),
+			RepoState::ApplyMailbox => {
+				sync::apply_mailbox_progress(repo).map_or_else(
+					|_| String::new(),
+					|p| format!("Patch: {}/{}", p.current, p.patches),
+				)
+			}
+			RepoState::Clean => String::new(),
 		}
 	}
 
@@ -300,13 +367,10 @@ impl Status {
 					Block::default()
 						.border_type(BorderType::Plain)
 						.borders(Borders::all())
-						.border_style(Theme::attention_block())
-						.title(format!(
-							"Pending {:?}",
-							self.git_state
-						)),
+						.border_style(self.theme.attention_block())
+						.title(format!("Pending {}", self.git_state)),
 				)
-				.style(Style::default().fg(Color::Red))
+				.style(self.theme.text_danger())
 				.alignment(Alignment::Left);
 
 			f.render_widget(w, r);
@@ -325,6 +389,12 @@ impl Status {
 		}
 	}
 
+	/// stores the state to restore in the next session
+	pub fn store_state(&self, state: &mut UiState) {
+		state.status_stage_focused =
+			self.diff_target == DiffTarget::Stage;
+	}
+
 	fn is_focus_on_diff(&self) -> bool {
 		self.focus == Focus::Diff
 	}
@@ -358,6 +428,25 @@ impl Status {
 		Ok(false)
 	}
 
+	/// clicking into a pane focuses it
+	fn mouse_focus(&mut self, mouse: MouseEvent) -> Result<()> {
+		if !matches!(mouse.kind, MouseEventKind::Down(_)) {
+			return Ok(());
+		}
+
+		if self.index_wd.contains_mouse(mouse) {
+			self.switch_focus(Focus::WorkDir)?;
+		} else if self.index.contains_mouse(mouse) {
+			self.switch_focus(Focus::Stage)?;
+		} else if self.diff.contains_mouse(mouse)
+			&& self.can_focus_diff()
+		{
+			self.switch_focus(Focus::Diff)?;
+		}
+
+		Ok(())
+	}
+
 	fn set_diff_target(&mut self, target: DiffTarget) {
 		self.diff_target = target;
 		let is_stage = self.diff_target == DiffTarget::Stage;
@@ -366,6 +455,11 @@ impl Status {
 		self.index.focus_select(is_stage);
 	}
 
+	/// areas of the unstaged and the staged changes
+	pub fn pane_areas(&self) -> [Rect; 2] {
+		self.pane_areas.get()
+	}
+
 	pub fn selected_path(&self) -> Option<(String, bool)> {
 		let (idx, is_stage) = match self.diff_target {
 			DiffTarget::Stage => (&self.index, true),
@@ -387,26 +481,76 @@ impl Status {
 		if self.is_visible() {
 			let config =
 				self.options.borrow().status_show_untracked();
+			let show_ignored =
+				self.options.borrow().status_show_ignored();
 
 			self.git_diff.refresh()?;
 			self.git_status_workdir.fetch(&StatusParams::new(
 				StatusType::WorkingDir,
 				config,
+				show_ignored,
 			))?;
 			self.git_status_stage.fetch(&StatusParams::new(
 				StatusType::Stage,
 				config,
+				false,
 			))?;
 
 			self.git_state = sync::repo_state(&self.repo.borrow())
 				.unwrap_or(RepoState::Clean);
 
 			self.branch_compare();
+
+			#[cfg(feature = "forge-api")]
+			if let Some(branch_name) = self.git_branch_name.last() {
+				self.review.request(&branch_name);
+			}
 		}
 
 		Ok(())
 	}
 
+	/// like `update` after only the files `paths` (relative to the
+	/// workdir) changed, which leaves the stage, repo state and
+	/// branches alone unless something in the git dir changed too
+	pub fn update_changed(&mut self, paths: &[String]) -> Result<()> {
+		let in_git_dir = |path: &String| {
+			path == ".git" || path.starts_with(".git/")
+		};
+
+		if !self.is_visible() || paths.iter().any(in_git_dir) {
+			return self.update();
+		}
+
+		let config = self.options.borrow().status_show_untracked();
+		let show_ignored =
+			self.options.borrow().status_show_ignored();
+
+		self.git_diff.refresh()?;
+		self.git_status_workdir.fetch_changed(
+			&StatusParams::new(
+				StatusType::WorkingDir,
+				config,
+				show_ignored,
+			),
+			paths,
+		)?;
+
+		Ok(())
+	}
+
+	/// takes the result of a finished pull request query
+	#[cfg(feature = "forge-api")]
+	pub fn update_review(&mut self) {
+		self.review.update();
+	}
+
+	/// open pull request of the current branch
+	#[cfg(feature = "forge-api")]
+	pub const fn pull_request(&self) -> Option<&PullRequest> {
+		self.review.last()
+	}
+
 	///
 	pub fn anything_pending(&self) -> bool {
 		self.git_diff.is_pending()
@@ -461,6 +605,7 @@ impl Status {
 
 		let workdir_status = self.git_status_workdir.last()?;
 		self.index_wd.set_items(&workdir_status.items)?;
+		self.untracked_notice.update(&workdir_status.items);
 
 		self.update_diff()?;
 
@@ -524,6 +669,47 @@ impl Status {
 		Ok(())
 	}
 
+	/// moves a hunk jump the diff has no hunk left for on to the
+	/// next (or previous) file in the list
+	fn jump_hunk_across_files(
+		&mut self,
+		k: &crossterm::event::KeyEvent,
+	) -> Result<bool> {
+		let keys = &self.key_config.keys;
+		let (forward, conflicts) =
+			if key_match(k, keys.diff_hunk_next) {
+				(true, false)
+			} else if key_match(k, keys.diff_hunk_prev) {
+				(false, false)
+			} else if key_match(k, keys.diff_conflict_next) {
+				(true, true)
+			} else if key_match(k, keys.diff_conflict_prev) {
+				(false, true)
+			} else {
+				return Ok(false);
+			};
+		let jump = HunkJump { forward, conflicts };
+
+		if !self.is_focus_on_diff() || self.diff.can_jump_hunk(jump) {
+			return Ok(false);
+		}
+
+		let files = match self.diff_target {
+			DiffTarget::Stage => &mut self.index,
+			DiffTarget::WorkingDir => &mut self.index_wd,
+		};
+		if !files.select_file(forward, |item| {
+			!conflicts || item.status == StatusItemType::Conflicted
+		}) {
+			return Ok(false);
+		}
+
+		self.diff.jump_hunk_on_update(jump);
+		self.update_diff()?;
+
+		Ok(true)
+	}
+
 	fn request_diff(
 		&mut self,
 		diff_params: DiffParams,
@@ -555,6 +741,46 @@ impl Status {
 		}
 	}
 
+	/// adds the folder of the untracked files flood to `.gitignore`
+	fn ignore_untracked_flood(&mut self) {
+		if let Some(flood) = self.untracked_notice.take() {
+			try_or_popup!(
+				self,
+				"ignore failed:",
+				sync::add_ignore_pattern(
+					&self.repo.borrow(),
+					&flood.ignore_pattern(),
+					IgnoreFile::GitIgnore,
+				)
+			);
+
+			self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+		}
+	}
+
+	fn absorb(&self) {
+		let autosquash = self.options.borrow().absorb_autosquash();
+
+		match sync::absorb(&self.repo.borrow(), autosquash) {
+			Ok(result) => {
+				self.queue.push(InternalEvent::ShowInfoMsg(
+					strings::absorb_msg(&result),
+				));
+			}
+			Err(e) => {
+				self.queue.push(InternalEvent::ShowErrorMsg(
+					format!("absorb failed:\n{e}"),
+				));
+			}
+		}
+
+		self.queue.push(InternalEvent::Update(NeedsUpdate::ALL));
+	}
+
+	fn can_absorb(&self) -> bool {
+		!self.index.is_empty() && self.git_state == RepoState::Clean
+	}
+
 	pub fn last_file_moved(&mut self) -> Result<()> {
 		if !self.is_focus_on_diff() && self.is_visible() {
 			self.switch_focus(self.focus.toggled_focus())?;
@@ -635,6 +861,14 @@ impl Status {
 		self.git_state == RepoState::Revert
 	}
 
+	fn pending_cherrypick(&self) -> bool {
+		self.git_state == RepoState::CherryPick
+	}
+
+	fn pending_apply_mailbox(&self) -> bool {
+		self.git_state == RepoState::ApplyMailbox
+	}
+
 	pub fn revert_pending_state(&self) {
 		try_or_popup!(
 			self,
@@ -643,6 +877,14 @@ impl Status {
 		);
 	}
 
+	pub fn abort_apply_mailbox(&self) {
+		try_or_popup!(
+			self,
+			"abort am",
+			sync::abort_apply_mailbox(&self.repo.borrow())
+		);
+	}
+
 	pub fn abort_rebase(&self) {
 		try_or_popup!(
 			self,
@@ -659,6 +901,49 @@ impl Status {
 		);
 	}
 
+	/// continue and abort of the pending repo state
+	fn commands_pending_state(
+		&self,
+		out: &mut Vec<CommandInfo>,
+		force_all: bool,
+	) {
+		out.push(CommandInfo::new(
+			strings::commands::abort_merge(&self.key_config),
+			true,
+			self.can_abort_merge() || force_all,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::continue_rebase(&self.key_config),
+			true,
+			self.pending_rebase() || force_all,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::abort_rebase(&self.key_config),
+			true,
+			self.pending_rebase() || force_all,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::abort_revert(&self.key_config),
+			true,
+			self.pending_revert() || force_all,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::abort_cherrypick(&self.key_config),
+			true,
+			self.pending_cherrypick() || force_all,
+		));
+
+		out.push(CommandInfo::new(
+			strings::commands::abort_apply_mailbox(&self.key_config),
+			true,
+			self.pending_apply_mailbox() || force_all,
+		));
+	}
+
 	fn commands_nav(
 		&self,
 		out: &mut Vec<CommandInfo>,
@@ -736,6 +1021,21 @@ impl Component for Status {
 				.order(-1),
 			);
 
+			out.push(CommandInfo::new(
+				strings::commands::ignore_untracked_flood(
+					&self.key_config,
+				),
+				true,
+				self.untracked_notice.current().is_some()
+					|| force_all,
+			));
+
+			out.push(CommandInfo::new(
+				strings::commands::absorb(&self.key_config),
+				self.can_absorb(),
+				!focus_on_diff || force_all,
+			));
+
 			out.push(CommandInfo::new(
 				strings::commands::open_branch_select_popup(
 					&self.key_config,
@@ -775,32 +1075,15 @@ impl Component for Status {
 					|| force_all,
 			));
 
-			out.push(CommandInfo::new(
-				strings::commands::abort_merge(&self.key_config),
-				true,
-				self.can_abort_merge() || force_all,
-			));
-
-			out.push(CommandInfo::new(
-				strings::commands::continue_rebase(&self.key_config),
-				true,
-				self.pending_rebase() || force_all,
-			));
+			self.commands_pending_state(out, force_all);
 
 			out.push(CommandInfo::new(
-				strings::commands::abort_rebase(&self.key_config),
+				strings::commands::view_submodules(&self.key_config),
 				true,
-				self.pending_rebase() || force_all,
-			));
-
-			out.push(CommandInfo::new(
-				strings::commands::abort_revert(&self.key_config),
 				true,
-				self.pending_revert() || force_all,
 			));
-
 			out.push(CommandInfo::new(
-				strings::commands::view_submodules(&self.key_config),
+				strings::commands::apply_patch(&self.key_config),
 				true,
 				true,
 			));
@@ -817,6 +1100,16 @@ impl Component for Status {
 		ev: &crossterm::event::Event,
 	) -> Result<EventState> {
 		if self.visible {
+			if let Event::Mouse(mouse) = ev {
+				self.mouse_focus(*mouse)?;
+			}
+
+			if let Event::Key(k) = ev {
+				if self.jump_hunk_across_files(k)? {
+					return Ok(EventState::Consumed);
+				}
+			}
+
 			if event_pump(ev, self.components_mut().as_mut_slice())?
 				.is_consumed()
 			{
@@ -896,6 +1189,22 @@ impl Component for Status {
 				{
 					self.pull();
 					Ok(EventState::Consumed)
+				} else if key_match(
+					k,
+					self.key_config.keys.ignore_untracked_flood,
+				) && self
+					.untracked_notice
+					.current()
+					.is_some()
+				{
+					self.ignore_untracked_flood();
+					Ok(EventState::Consumed)
+				} else if key_match(k, self.key_config.keys.absorb)
+					&& !self.is_focus_on_diff()
+					&& self.can_absorb()
+				{
+					self.absorb();
+					Ok(EventState::Consumed)
 				} else if key_match(
 					k,
 					self.key_config.keys.undo_commit,
@@ -928,6 +1237,18 @@ impl Component for Status {
 								Action::AbortRevert,
 							),
 						);
+					} else if self.pending_cherrypick() {
+						self.queue.push(
+							InternalEvent::ConfirmAction(
+								Action::AbortCherryPick,
+							),
+						);
+					} else if self.pending_apply_mailbox() {
+						self.queue.push(
+							InternalEvent::ConfirmAction(
+								Action::AbortApplyMailbox,
+							),
+						);
 					}
 
 					Ok(EventState::Consumed)
@@ -947,6 +1268,12 @@ impl Component for Status {
 				) {
 					self.queue.push(InternalEvent::ViewSubmodules);
 					Ok(EventState::Consumed)
+				} else if key_match(
+					k,
+					self.key_config.keys.apply_patch,
+				) {
+					self.queue.push(InternalEvent::ApplyPatch);
+					Ok(EventState::Consumed)
 				} else {
 					Ok(EventState::NotConsumed)
 				};
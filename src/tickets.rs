@@ -0,0 +1,166 @@
+//! issue tracker ids in branch names and commit messages, configured
+//! per repo in git config:
+//! * `gitui.ticketPattern`: regex matching a ticket id (its first
+//!   group if it has one), `[A-Z][A-Z0-9]+-[0-9]+` by default
+//! * `gitui.ticketUrl`: link to a ticket with `{ticket}` in place of
+//!   the id, ids in commit messages are shown as links to it
+//! * `gitui.ticketPrefix`: put in front of new commit messages with
+//!   `{ticket}` replaced by the id in the branch name, e.g.
+//!   `[{ticket}] `
+
+use asyncgit::sync::{get_config_string, RepoPath};
+use regex::Regex;
+use std::ops::Range;
+
+const PATTERN_CONFIG: &str = "gitui.ticketPattern";
+const URL_CONFIG: &str = "gitui.ticketUrl";
+const PREFIX_CONFIG: &str = "gitui.ticketPrefix";
+const DEFAULT_PATTERN: &str = "[A-Z][A-Z0-9]+-[0-9]+";
+
+///
+pub struct Tickets {
+	pattern: Regex,
+	url: Option<String>,
+	prefix: Option<String>,
+}
+
+impl Tickets {
+	/// `None` if `gitui.ticketPattern` is not a valid regex
+	pub fn load(repo: &RepoPath) -> Option<Self> {
+		let config = |key| {
+			get_config_string(repo, key)
+				.map_err(|e| {
+					log::error!("load git-config failed: {e}");
+				})
+				.ok()
+				.flatten()
+				.filter(|value| !value.trim().is_empty())
+		};
+
+		Self::new(
+			config(PATTERN_CONFIG)
+				.as_deref()
+				.unwrap_or(DEFAULT_PATTERN),
+			config(URL_CONFIG),
+			config(PREFIX_CONFIG),
+		)
+	}
+
+	fn new(
+		pattern: &str,
+		url: Option<String>,
+		prefix: Option<String>,
+	) -> Option<Self> {
+		let pattern = Regex::new(pattern)
+			.map_err(|e| log::error!("{PATTERN_CONFIG}: {e}"))
+			.ok()?;
+
+		Some(Self {
+			pattern,
+			url,
+			prefix,
+		})
+	}
+
+	/// the first ticket id in `text`
+	pub fn find(&self, text: &str) -> Option<String> {
+		self.find_all(text)
+			.next()
+			.map(|range| text[range].to_string())
+	}
+
+	/// byte ranges of the ticket ids in `text`
+	pub fn find_all<'a>(
+		&'a self,
+		text: &'a str,
+	) -> impl Iterator<Item = Range<usize>> + 'a {
+		self.pattern.captures_iter(text).filter_map(|captures| {
+			captures
+				.get(1)
+				.or_else(|| captures.get(0))
+				.filter(|id| !id.is_empty())
+				.map(|id| id.range())
+		})
+	}
+
+	/// ids are only links with `gitui.ticketUrl` set
+	pub const fn has_links(&self) -> bool {
+		self.url.is_some()
+	}
+
+	///
+	#[allow(clippy::literal_string_with_formatting_args)]
+	pub fn url(&self, ticket: &str) -> Option<String> {
+		self.url.as_ref().map(|url| url.replace("{ticket}", ticket))
+	}
+
+	/// `msg` with `gitui.ticketPrefix` for `ticket` in front, unless
+	/// it mentions `ticket` already
+	#[allow(clippy::literal_string_with_formatting_args)]
+	pub fn prefixed(&self, msg: &str, ticket: &str) -> String {
+		match &self.prefix {
+			Some(prefix) if !msg.contains(ticket) => {
+				format!("{}{msg}", prefix.replace("{ticket}", ticket))
+			}
+			_ => msg.to_string(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{Tickets, DEFAULT_PATTERN};
+
+	#[test]
+	fn test_find() {
+		let tickets =
+			Tickets::new(DEFAULT_PATTERN, None, None).unwrap();
+
+		assert_eq!(
+			tickets.find("feature/ABC-123-login").as_deref(),
+			Some("ABC-123")
+		);
+		assert_eq!(tickets.find("master"), None);
+
+		let text = "fix AB-1 and CD-22";
+		assert_eq!(
+			tickets
+				.find_all(text)
+				.map(|range| &text[range])
+				.collect::<Vec<_>>(),
+			vec!["AB-1", "CD-22"]
+		);
+
+		let tickets = Tickets::new(r"#(\d+)", None, None).unwrap();
+		assert_eq!(tickets.find("fixes #42").as_deref(), Some("42"));
+
+		assert!(Tickets::new("(", None, None).is_none());
+	}
+
+	#[test]
+	fn test_url_and_prefix() {
+		let tickets = Tickets::new(
+			DEFAULT_PATTERN,
+			Some(String::from("https://jira/browse/{ticket}")),
+			Some(String::from("[{ticket}] ")),
+		)
+		.unwrap();
+
+		assert!(tickets.has_links());
+		assert_eq!(
+			tickets.url("AB-1").as_deref(),
+			Some("https://jira/browse/AB-1")
+		);
+		assert_eq!(tickets.prefixed("", "AB-1"), "[AB-1] ");
+		assert_eq!(
+			tickets.prefixed("AB-1: fix", "AB-1"),
+			"AB-1: fix"
+		);
+
+		let tickets =
+			Tickets::new(DEFAULT_PATTERN, None, None).unwrap();
+		assert!(!tickets.has_links());
+		assert_eq!(tickets.url("AB-1"), None);
+		assert_eq!(tickets.prefixed("fix", "AB-1"), "fix");
+	}
+}
@@ -0,0 +1,806 @@
+//! previews of images using the graphics protocol of the terminal,
+//! or half block characters where there is none
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use crossterm::{cursor::MoveTo, queue, style::Print};
+use ratatui::{
+	buffer::Buffer,
+	layout::Rect,
+	style::{Color, Style},
+	text::{Line, Span},
+	widgets::Paragraph,
+	Frame,
+};
+use std::{
+	cell::RefCell,
+	collections::{hash_map::DefaultHasher, BTreeMap},
+	fmt::Write as _,
+	hash::{Hash, Hasher},
+	io::{self, Write},
+	rc::Rc,
+};
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+/// larger images are not decoded
+const MAX_PIXELS: usize = 4096 * 4096;
+/// guessed if the terminal does not report its size in pixels
+const DEFAULT_CELL_SIZE: (usize, usize) = (10, 20);
+/// kitty takes the image data in chunks of at most this size
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+/// how images are drawn into the terminal
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ImageProtocol {
+	/// kitty graphics protocol
+	Kitty,
+	/// iTerm2 inline images
+	Iterm,
+	///
+	Sixel,
+	/// colored half blocks, works everywhere
+	#[default]
+	Blocks,
+}
+
+impl ImageProtocol {
+	/// guesses the protocol from the environment,
+	/// `GITUI_IMAGE_PROTOCOL` overrides it
+	pub fn detect() -> Self {
+		let var = |name| std::env::var(name).unwrap_or_default();
+
+		match var("GITUI_IMAGE_PROTOCOL").to_lowercase().as_str() {
+			"kitty" => return Self::Kitty,
+			"iterm" => return Self::Iterm,
+			"sixel" => return Self::Sixel,
+			"blocks" => return Self::Blocks,
+			_ => (),
+		}
+
+		let term = var("TERM");
+		let program = var("TERM_PROGRAM");
+
+		if term.contains("kitty")
+			|| std::env::var_os("KITTY_WINDOW_ID").is_some()
+			|| program == "ghostty"
+		{
+			Self::Kitty
+		} else if matches!(program.as_str(), "iTerm.app" | "WezTerm")
+		{
+			Self::Iterm
+		} else if term.contains("sixel")
+			|| term.starts_with("foot")
+			|| program == "mlterm"
+		{
+			Self::Sixel
+		} else {
+			Self::Blocks
+		}
+	}
+}
+
+/// the image data of `height` rows, each with its filter byte in
+/// front, `None` if it inflates to more than that
+fn inflate(
+	compressed: &[u8],
+	stride: usize,
+	height: usize,
+) -> Option<Vec<u8>> {
+	let limit = stride.checked_mul(height)?.checked_add(height)?;
+
+	miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(
+		compressed, limit,
+	)
+	.ok()
+}
+
+/// decoded image
+#[derive(Debug, PartialEq, Eq)]
+pub struct RgbaImage {
+	width: usize,
+	height: usize,
+	pixels: Vec<[u8; 4]>,
+}
+
+impl RgbaImage {
+	/// decodes non interlaced pngs of all color types
+	pub fn decode_png(data: &[u8]) -> Option<Self> {
+		let PngChunks {
+			header,
+			palette,
+			transparency,
+			compressed,
+		} = PngChunks::read(data)?;
+
+		let header =
+			Some(header).filter(|header| header.len() == 13)?;
+		let width = usize::try_from(u32::from_be_bytes(
+			header[0..4].try_into().ok()?,
+		))
+		.ok()?;
+		let height = usize::try_from(u32::from_be_bytes(
+			header[4..8].try_into().ok()?,
+		))
+		.ok()?;
+		let (depth, color_type) = (usize::from(header[8]), header[9]);
+
+		if header[12] != 0
+			|| width == 0
+			|| height == 0
+			|| width.checked_mul(height)? > MAX_PIXELS
+			|| !matches!(depth, 1 | 2 | 4 | 8 | 16)
+		{
+			return None;
+		}
+
+		let channels = match color_type {
+			0 | 3 => 1,
+			2 => 3,
+			4 => 2,
+			6 => 4,
+			_ => return None,
+		};
+
+		let bits = channels * depth;
+		let stride = width.checked_mul(bits)?.div_ceil(8);
+		let raw = inflate(&compressed, stride, height)?;
+		let data = unfilter(&raw, stride, height, bits.div_ceil(8))?;
+
+		let mut pixels =
+			Vec::with_capacity(width.checked_mul(height)?);
+		for row in data.chunks_exact(stride) {
+			let sample = |idx: usize| sample(row, idx, depth);
+			// scales gray values of less than 8 bit up
+			let gray = |idx: usize| {
+				let value = sample(idx);
+				if depth < 8 {
+					value.saturating_mul(
+						u8::MAX / ((1_u8 << depth) - 1),
+					)
+				} else {
+					value
+				}
+			};
+
+			for x in 0..width {
+				pixels.push(match color_type {
+					0 => {
+						let g = gray(x);
+						[g, g, g, u8::MAX]
+					}
+					2 => [
+						sample(x * 3),
+						sample(x * 3 + 1),
+						sample(x * 3 + 2),
+						u8::MAX,
+					],
+					3 => {
+						let idx = usize::from(sample(x));
+						let rgb =
+							palette.get(idx * 3..idx * 3 + 3)?;
+						[
+							rgb[0],
+							rgb[1],
+							rgb[2],
+							transparency
+								.get(idx)
+								.copied()
+								.unwrap_or(u8::MAX),
+						]
+					}
+					4 => {
+						let g = gray(x * 2);
+						[g, g, g, sample(x * 2 + 1)]
+					}
+					_ => [
+						sample(x * 4),
+						sample(x * 4 + 1),
+						sample(x * 4 + 2),
+						sample(x * 4 + 3),
+					],
+				});
+			}
+		}
+
+		Some(Self {
+			width,
+			height,
+			pixels,
+		})
+	}
+
+	/// nearest neighbour scaling
+	fn resize(&self, (width, height): (usize, usize)) -> Self {
+		let pixels = (0..height)
+			.flat_map(|y| {
+				let row = y * self.height / height * self.width;
+				(0..width).map(move |x| {
+					self.pixels[row + x * self.width / width]
+				})
+			})
+			.collect();
+
+		Self {
+			width,
+			height,
+			pixels,
+		}
+	}
+
+	fn pixel(&self, x: usize, y: usize) -> Option<[u8; 4]> {
+		(x < self.width && y < self.height)
+			.then(|| self.pixels[y * self.width + x])
+			// mostly transparent pixels are left out
+			.filter(|pixel| pixel[3] >= 128)
+	}
+}
+
+/// the chunks of a png needed to decode it
+struct PngChunks<'a> {
+	header: &'a [u8],
+	palette: &'a [u8],
+	transparency: &'a [u8],
+	/// all image data chunks joined
+	compressed: Vec<u8>,
+}
+
+impl<'a> PngChunks<'a> {
+	fn read(data: &'a [u8]) -> Option<Self> {
+		let mut rest = data.strip_prefix(PNG_SIGNATURE)?;
+		let mut header = None;
+		let mut palette: &[u8] = &[];
+		let mut transparency: &[u8] = &[];
+		let mut compressed = Vec::new();
+
+		while rest.len() >= 12 {
+			let len = usize::try_from(u32::from_be_bytes(
+				rest[..4].try_into().ok()?,
+			))
+			.ok()?;
+			let body = rest.get(8..8 + len)?;
+
+			match &rest[4..8] {
+				b"IHDR" => header = Some(body),
+				b"PLTE" => palette = body,
+				b"tRNS" => transparency = body,
+				b"IDAT" => compressed.extend_from_slice(body),
+				b"IEND" => break,
+				_ => (),
+			}
+
+			rest = rest.get(12 + len..)?;
+		}
+
+		Some(Self {
+			header: header?,
+			palette,
+			transparency,
+			compressed,
+		})
+	}
+}
+
+/// reverts the per row filters of png
+fn unfilter(
+	raw: &[u8],
+	stride: usize,
+	height: usize,
+	bpp: usize,
+) -> Option<Vec<u8>> {
+	let mut out = vec![0_u8; stride * height];
+
+	for y in 0..height {
+		let line =
+			raw.get(y * (stride + 1)..(y + 1) * (stride + 1))?;
+		let (filter, line) = (line[0], &line[1..]);
+		let (done, current) = out.split_at_mut(y * stride);
+		let previous = done.get(done.len().saturating_sub(stride)..);
+		let above = |x: usize| {
+			previous.filter(|_| y > 0).map_or(0, |row| row[x])
+		};
+
+		for x in 0..stride {
+			let left = if x >= bpp { current[x - bpp] } else { 0 };
+			let up = above(x);
+			let up_left = if x >= bpp { above(x - bpp) } else { 0 };
+
+			let predicted = match filter {
+				0 => 0,
+				1 => left,
+				2 => up,
+				3 => u8::try_from(
+					(u16::from(left) + u16::from(up)) / 2,
+				)
+				.unwrap_or_default(),
+				4 => paeth(left, up, up_left),
+				_ => return None,
+			};
+
+			current[x] = line[x].wrapping_add(predicted);
+		}
+	}
+
+	Some(out)
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+	let p = i16::from(a) + i16::from(b) - i16::from(c);
+	let pa = (p - i16::from(a)).abs();
+	let pb = (p - i16::from(b)).abs();
+	let pc = (p - i16::from(c)).abs();
+
+	if pa <= pb && pa <= pc {
+		a
+	} else if pb <= pc {
+		b
+	} else {
+		c
+	}
+}
+
+/// sample `idx` of a row of samples `depth` bits wide,
+/// 16 bit samples are cut down to their high byte
+fn sample(row: &[u8], idx: usize, depth: usize) -> u8 {
+	match depth {
+		8 => row[idx],
+		16 => row[idx * 2],
+		_ => {
+			let bit = idx * depth;
+			let shift = 8 - depth - bit % 8;
+			(row[bit / 8] >> shift) & ((1 << depth) - 1)
+		}
+	}
+}
+
+/// size `size` is scaled to, to fit into `max` keeping its aspect
+/// ratio, only growing if `grow` is set
+fn fit(
+	(width, height): (usize, usize),
+	(max_width, max_height): (usize, usize),
+	grow: bool,
+) -> (usize, usize) {
+	if !grow && width <= max_width && height <= max_height {
+		return (width, height);
+	}
+
+	let (width, height) = if width * max_height > height * max_width {
+		(max_width, height * max_width / width)
+	} else {
+		(width * max_height / height, max_height)
+	};
+
+	(width.max(1), height.max(1))
+}
+
+/// an image file to preview
+pub struct PreviewImage {
+	data: Vec<u8>,
+	decoded: Option<RgbaImage>,
+	id: u64,
+}
+
+impl PreviewImage {
+	///
+	pub fn new(data: Vec<u8>) -> Self {
+		let mut hasher = DefaultHasher::new();
+		data.hash(&mut hasher);
+
+		Self {
+			decoded: RgbaImage::decode_png(&data),
+			id: hasher.finish(),
+			data,
+		}
+	}
+}
+
+/// an image to write into the terminal after the frame is drawn
+struct Placement {
+	area: Rect,
+	image: Rc<PreviewImage>,
+	/// size in pixels it is shown at
+	size: (usize, usize),
+}
+
+/// draws image previews, see `ImageProtocol`
+#[derive(Default)]
+pub struct Graphics {
+	protocol: ImageProtocol,
+	cell_size: (usize, usize),
+	placements: Vec<Placement>,
+	/// what the terminal currently shows
+	shown: Vec<(Rect, u64)>,
+	screen: Rect,
+}
+
+pub type SharedGraphics = Rc<RefCell<Graphics>>;
+
+impl Graphics {
+	///
+	pub fn new() -> SharedGraphics {
+		let cell_size = crossterm::terminal::window_size()
+			.ok()
+			.filter(|size| {
+				size.width > 0 && size.columns > 0 && size.rows > 0
+			})
+			.map_or(DEFAULT_CELL_SIZE, |size| {
+				(
+					usize::from(size.width / size.columns),
+					usize::from(size.height / size.rows),
+				)
+			});
+
+		Rc::new(RefCell::new(Self {
+			protocol: ImageProtocol::detect(),
+			cell_size,
+			..Self::default()
+		}))
+	}
+
+	/// iTerm shows the file as it is, the others need a png
+	pub const fn can_preview(&self, image: &PreviewImage) -> bool {
+		matches!(self.protocol, ImageProtocol::Iterm)
+			|| image.decoded.is_some()
+	}
+
+	/// draws `image` into the top left of `area`, the graphics
+	/// protocols are written to the terminal in `flush`
+	pub fn draw(
+		&mut self,
+		f: &mut Frame,
+		area: Rect,
+		image: &Rc<PreviewImage>,
+	) {
+		let (cols, rows) =
+			(usize::from(area.width), usize::from(area.height));
+
+		if self.protocol == ImageProtocol::Blocks {
+			if let Some(decoded) = &image.decoded {
+				let size = fit(
+					(decoded.width, decoded.height),
+					(cols, rows * 2),
+					true,
+				);
+				f.render_widget(
+					Paragraph::new(half_blocks(
+						&decoded.resize(size),
+					)),
+					area,
+				);
+			}
+			return;
+		}
+
+		let (cell_width, cell_height) = self.cell_size;
+		let max = (cols * cell_width, rows * cell_height);
+		let size = image.decoded.as_ref().map_or(max, |decoded| {
+			fit((decoded.width, decoded.height), max, false)
+		});
+		let footprint = Rect {
+			width: u16::try_from(size.0.div_ceil(cell_width))
+				.unwrap_or(area.width)
+				.min(area.width),
+			height: u16::try_from(size.1.div_ceil(cell_height))
+				.unwrap_or(area.height)
+				.min(area.height),
+			..area
+		};
+
+		// keeps ratatui from drawing over the image
+		let buf = f.buffer_mut();
+		for y in footprint.top()..footprint.bottom() {
+			for x in footprint.left()..footprint.right() {
+				buf[(x, y)].set_skip(true);
+			}
+		}
+
+		self.placements.push(Placement {
+			area: footprint,
+			image: Rc::clone(image),
+			size,
+		});
+	}
+
+	/// writes the images placed while drawing `buffer` if they
+	/// changed since the last time or `force` is set
+	pub fn flush(
+		&mut self,
+		out: &mut impl Write,
+		buffer: &Buffer,
+		force: bool,
+	) -> io::Result<()> {
+		let placements = std::mem::take(&mut self.placements);
+
+		// images covered by a popup are left out
+		let visible: Vec<_> = placements
+			.into_iter()
+			.filter(|placement| {
+				(placement.area.top()..placement.area.bottom()).all(
+					|y| {
+						(placement.area.left()
+							..placement.area.right())
+							.all(|x| buffer[(x, y)].skip)
+					},
+				)
+			})
+			.collect();
+
+		let shown: Vec<_> = visible
+			.iter()
+			.map(|placement| (placement.area, placement.image.id))
+			.collect();
+
+		if !force && shown == self.shown && buffer.area == self.screen
+		{
+			return Ok(());
+		}
+
+		if self.protocol == ImageProtocol::Kitty {
+			queue!(out, Print("\x1b_Ga=d,d=A,q=2\x1b\\"))?;
+		}
+
+		for placement in &visible {
+			self.write_image(out, placement)?;
+		}
+
+		self.shown = shown;
+		self.screen = buffer.area;
+
+		out.flush()
+	}
+
+	fn write_image(
+		&self,
+		out: &mut impl Write,
+		placement: &Placement,
+	) -> io::Result<()> {
+		let area = placement.area;
+		let blank = " ".repeat(usize::from(area.width));
+		for y in area.top()..area.bottom() {
+			queue!(out, MoveTo(area.x, y), Print(&blank))?;
+		}
+		queue!(out, MoveTo(area.x, area.y))?;
+
+		let image = &placement.image;
+		match (self.protocol, &image.decoded) {
+			(ImageProtocol::Kitty, _) => {
+				queue!(out, Print(kitty(&image.data, area)))
+			}
+			(ImageProtocol::Iterm, _) => {
+				queue!(out, Print(iterm(&image.data, area)))
+			}
+			(ImageProtocol::Sixel, Some(decoded)) => queue!(
+				out,
+				Print(sixel(&decoded.resize(placement.size)))
+			),
+			_ => Ok(()),
+		}
+	}
+}
+
+fn kitty(png: &[u8], area: Rect) -> String {
+	let data = STANDARD.encode(png);
+	let chunks: Vec<_> =
+		data.as_bytes().chunks(KITTY_CHUNK_SIZE).collect();
+
+	let mut out = String::new();
+	for (idx, chunk) in chunks.iter().enumerate() {
+		let more = u8::from(idx + 1 < chunks.len());
+		let chunk = String::from_utf8_lossy(chunk);
+		if idx == 0 {
+			let _ = write!(
+				out,
+				"\x1b_Ga=T,f=100,q=2,C=1,c={},r={},m={more};{chunk}\x1b\\",
+				area.width, area.height
+			);
+		} else {
+			let _ = write!(out, "\x1b_Gm={more};{chunk}\x1b\\");
+		}
+	}
+
+	out
+}
+
+fn iterm(data: &[u8], area: Rect) -> String {
+	format!(
+		"\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+		data.len(),
+		area.width,
+		area.height,
+		STANDARD.encode(data)
+	)
+}
+
+/// sixel graphics using a 6x6x6 color cube
+fn sixel(image: &RgbaImage) -> String {
+	let level = |value: u8| (usize::from(value) * 5 + 127) / 255;
+	let color = |pixel: [u8; 4]| {
+		level(pixel[0]) * 36 + level(pixel[1]) * 6 + level(pixel[2])
+	};
+
+	let mut out =
+		format!("\x1bPq\"1;1;{};{}", image.width, image.height);
+
+	for idx in 0..216 {
+		let _ = write!(
+			out,
+			"#{idx};2;{};{};{}",
+			idx / 36 * 20,
+			idx / 6 % 6 * 20,
+			idx % 6 * 20
+		);
+	}
+
+	for band in (0..image.height).step_by(6) {
+		// the six pixel high columns of every color in this band
+		let mut colors: BTreeMap<usize, Vec<u8>> = BTreeMap::new();
+		for row in 0..6 {
+			for x in 0..image.width {
+				if let Some(pixel) = image.pixel(x, band + row) {
+					colors
+						.entry(color(pixel))
+						.or_insert_with(|| vec![0; image.width])[x] |= 1 << row;
+				}
+			}
+		}
+
+		for (idx, columns) in colors {
+			let _ = write!(out, "#{idx}");
+			for run in columns.chunk_by(|a, b| a == b) {
+				let c = char::from(63 + run[0]);
+				if run.len() > 3 {
+					let _ = write!(out, "!{}{c}", run.len());
+				} else {
+					out.extend(std::iter::repeat_n(c, run.len()));
+				}
+			}
+			out.push('$');
+		}
+		out.push('-');
+	}
+
+	out.push_str("\x1b\\");
+	out
+}
+
+/// two pixels per cell using the upper half block
+fn half_blocks(image: &RgbaImage) -> Vec<Line<'static>> {
+	let rgb =
+		|pixel: [u8; 4]| Color::Rgb(pixel[0], pixel[1], pixel[2]);
+
+	(0..image.height.div_ceil(2))
+		.map(|row| {
+			Line::from(
+				(0..image.width)
+					.map(|x| {
+						match (
+							image.pixel(x, row * 2),
+							image.pixel(x, row * 2 + 1),
+						) {
+							(Some(top), Some(bottom)) => {
+								Span::styled(
+									"▀",
+									Style::default()
+										.fg(rgb(top))
+										.bg(rgb(bottom)),
+								)
+							}
+							(Some(top), None) => Span::styled(
+								"▀",
+								Style::default().fg(rgb(top)),
+							),
+							(None, Some(bottom)) => Span::styled(
+								"▄",
+								Style::default().fg(rgb(bottom)),
+							),
+							(None, None) => Span::raw(" "),
+						}
+					})
+					.collect::<Vec<_>>(),
+			)
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	/// signature and header of a 2x2 rgba png
+	const PNG: &[u8] = &[
+		0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00,
+		0x00, 0x0d, 0x49, 0x48, 0x44, 0x52, 0x00, 0x00, 0x00, 0x02,
+		0x00, 0x00, 0x00, 0x02, 0x08, 0x06, 0x00, 0x00, 0x00, 0x72,
+		0xb6, 0x0d, 0x24,
+	];
+
+	#[test]
+	fn test_fit() {
+		assert_eq!(fit((10, 5), (100, 100), false), (10, 5));
+		assert_eq!(fit((10, 5), (100, 100), true), (100, 50));
+		assert_eq!(fit((200, 100), (50, 50), false), (50, 25));
+		assert_eq!(fit((100, 200), (50, 50), false), (25, 50));
+	}
+
+	#[test]
+	fn test_sample() {
+		assert_eq!(sample(&[0b1011_0000], 0, 1), 1);
+		assert_eq!(sample(&[0b1011_0000], 1, 1), 0);
+		assert_eq!(sample(&[0b1011_0000], 0, 2), 2);
+		assert_eq!(sample(&[0b1011_0000], 1, 4), 0);
+		assert_eq!(sample(&[0x12, 0x34], 0, 16), 0x12);
+	}
+
+	#[test]
+	fn test_decode_png() {
+		let mut png = PNG.to_vec();
+		png.extend_from_slice(&png_chunk(
+			b"IDAT",
+			&miniz_oxide::deflate::compress_to_vec_zlib(
+				&[
+					// no filter
+					0, 255, 0, 0, 255, 0, 255, 0, 255,
+					// up filter, so the same as above
+					2, 0, 0, 255, 0, 0, 0, 0, 0,
+				],
+				6,
+			),
+		));
+		png.extend_from_slice(&png_chunk(b"IEND", &[]));
+
+		let image = RgbaImage::decode_png(&png).unwrap();
+
+		assert_eq!(
+			image,
+			RgbaImage {
+				width: 2,
+				height: 2,
+				pixels: vec![
+					[255, 0, 0, 255],
+					[0, 255, 0, 255],
+					[255, 0, 255, 255],
+					[0, 255, 0, 255],
+				],
+			}
+		);
+		assert_eq!(RgbaImage::decode_png(b"GIF89a"), None);
+	}
+
+	#[test]
+	fn test_decode_png_oversized_data() {
+		let mut png = PNG.to_vec();
+		png.extend_from_slice(&png_chunk(
+			b"IDAT",
+			&miniz_oxide::deflate::compress_to_vec_zlib(
+				&[0; 1024], 6,
+			),
+		));
+		png.extend_from_slice(&png_chunk(b"IEND", &[]));
+
+		assert_eq!(RgbaImage::decode_png(&png), None);
+	}
+
+	/// chunk with a wrong crc, which is not checked
+	fn png_chunk(kind: &[u8], body: &[u8]) -> Vec<u8> {
+		let mut chunk =
+			u32::try_from(body.len()).unwrap().to_be_bytes().to_vec();
+		chunk.extend_from_slice(kind);
+		chunk.extend_from_slice(body);
+		chunk.extend_from_slice(&[0; 4]);
+		chunk
+	}
+
+	#[test]
+	fn test_sixel() {
+		let image = RgbaImage {
+			width: 3,
+			height: 2,
+			pixels: vec![[255, 255, 255, 255]; 6],
+		};
+
+		let out = sixel(&image);
+
+		assert!(out.starts_with("\x1bPq\"1;1;3;2#0;2;0;0;0"));
+		// white is the last color, set in the top two rows
+		assert!(out.ends_with("#215BBB$-\x1b\\"));
+	}
+}
@@ -0,0 +1,205 @@
+use crate::AsyncAppNotification;
+use asyncgit::{
+	asyncjob::{AsyncJob, RunParams},
+	sync::{blame::blame_file, get_commits_info, CommitId, RepoPath},
+};
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+};
+
+/// summaries are cut to this many chars
+const SUMMARY_LENGTH: usize = 50;
+
+/// the commit a blamed line comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameCommit {
+	pub id: CommitId,
+	/// unix time of the commit in seconds
+	pub time: i64,
+	pub summary: String,
+}
+
+/// blame of a file reduced to what the gutter of the file view shows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineBlame {
+	pub path: String,
+	pub commit: CommitId,
+	commits: Vec<BlameCommit>,
+	/// index into `commits` per line, `None` for lines not blamed
+	lines: Vec<Option<usize>>,
+}
+
+impl InlineBlame {
+	/// commit of `line` (0-based) and whether it starts a new hunk
+	pub fn line(&self, line: usize) -> Option<(&BlameCommit, bool)> {
+		let idx = (*self.lines.get(line)?)?;
+		let starts_hunk = line == 0
+			|| self.lines.get(line - 1).copied().flatten()
+				!= Some(idx);
+
+		self.commits.get(idx).map(|commit| (commit, starts_hunk))
+	}
+}
+
+/// short age like `5m`, `3h`, `2d`, `4w`, `3mo` or `2y`
+pub fn age_short(secs: i64) -> String {
+	const MINUTE: i64 = 60;
+	const HOUR: i64 = 60 * MINUTE;
+	const DAY: i64 = 24 * HOUR;
+	const WEEK: i64 = 7 * DAY;
+	const MONTH: i64 = 30 * DAY;
+	const YEAR: i64 = 365 * DAY;
+
+	let secs = secs.max(0);
+
+	match secs {
+		s if s < HOUR => format!("{}m", s / MINUTE),
+		s if s < DAY => format!("{}h", s / HOUR),
+		s if s < WEEK => format!("{}d", s / DAY),
+		s if s < MONTH => format!("{}w", s / WEEK),
+		s if s < YEAR => format!("{}mo", s / MONTH),
+		s => format!("{}y", s / YEAR),
+	}
+}
+
+fn blame(
+	repo: &RepoPath,
+	path: &str,
+	commit: CommitId,
+) -> asyncgit::Result<InlineBlame> {
+	let file_blame = blame_file(repo, path, Some(commit))?;
+
+	let mut index: HashMap<CommitId, usize> = HashMap::new();
+	let mut ids = Vec::new();
+	let lines = file_blame
+		.lines
+		.iter()
+		.map(|(hunk, _)| {
+			hunk.as_ref().map(|hunk| {
+				*index.entry(hunk.commit_id).or_insert_with(|| {
+					ids.push(hunk.commit_id);
+					ids.len() - 1
+				})
+			})
+		})
+		.collect();
+
+	let infos = get_commits_info(repo, &ids, SUMMARY_LENGTH)?;
+	let commits = ids
+		.iter()
+		.map(|id| {
+			infos.iter().find(|info| info.id == *id).map_or_else(
+				|| BlameCommit {
+					id: *id,
+					time: 0,
+					summary: String::new(),
+				},
+				|info| BlameCommit {
+					id: *id,
+					time: info.time,
+					summary: info.message.clone(),
+				},
+			)
+		})
+		.collect();
+
+	Ok(InlineBlame {
+		path: path.to_string(),
+		commit,
+		commits,
+		lines,
+	})
+}
+
+/// blames a file and looks up the summaries of its commits
+#[derive(Clone)]
+pub struct AsyncInlineBlameJob {
+	repo: RepoPath,
+	path: String,
+	commit: CommitId,
+	result: Arc<Mutex<Option<InlineBlame>>>,
+}
+
+impl AsyncInlineBlameJob {
+	///
+	pub fn new(
+		repo: RepoPath,
+		path: String,
+		commit: CommitId,
+	) -> Self {
+		Self {
+			repo,
+			path,
+			commit,
+			result: Arc::new(Mutex::new(None)),
+		}
+	}
+
+	///
+	pub fn result(&self) -> Option<InlineBlame> {
+		self.result.lock().ok()?.take()
+	}
+}
+
+impl AsyncJob for AsyncInlineBlameJob {
+	type Notification = AsyncAppNotification;
+	type Progress = ();
+
+	fn run(
+		&mut self,
+		_params: RunParams<Self::Notification, Self::Progress>,
+	) -> asyncgit::Result<Self::Notification> {
+		let blame = blame(&self.repo, &self.path, self.commit)
+			.map_err(|e| log::error!("inline blame: {e}"))
+			.ok();
+
+		if let Ok(mut result) = self.result.lock() {
+			*result = blame;
+		}
+
+		Ok(AsyncAppNotification::InlineBlame)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{age_short, InlineBlame};
+	use crate::ui::inline_blame::BlameCommit;
+	use asyncgit::sync::CommitId;
+
+	#[test]
+	fn test_age_short() {
+		assert_eq!(age_short(-5), "0m");
+		assert_eq!(age_short(5 * 60), "5m");
+		assert_eq!(age_short(3 * 3600), "3h");
+		assert_eq!(age_short(2 * 86400), "2d");
+		assert_eq!(age_short(15 * 86400), "2w");
+		assert_eq!(age_short(90 * 86400), "3mo");
+		assert_eq!(age_short(800 * 86400), "2y");
+	}
+
+	#[test]
+	fn test_hunk_starts() {
+		let commit = BlameCommit {
+			id: CommitId::default(),
+			time: 0,
+			summary: String::from("s"),
+		};
+		let blame = InlineBlame {
+			path: String::from("f"),
+			commit: CommitId::default(),
+			commits: vec![commit.clone(), commit],
+			lines: vec![Some(0), Some(0), Some(1), None],
+		};
+
+		assert_eq!(blame.line(0).map(|(_, start)| start), Some(true));
+		assert_eq!(
+			blame.line(1).map(|(_, start)| start),
+			Some(false)
+		);
+		assert_eq!(blame.line(2).map(|(_, start)| start), Some(true));
+		assert!(blame.line(3).is_none());
+		assert!(blame.line(4).is_none());
+	}
+}
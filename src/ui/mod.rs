@@ -1,3 +1,6 @@
+mod image;
+mod inline_blame;
+mod progress;
 mod reflow;
 mod scrollbar;
 mod scrolllist;
@@ -6,6 +9,9 @@ pub mod style;
 mod syntax_text;
 
 use filetreelist::MoveSelection;
+pub use image::{Graphics, PreviewImage, SharedGraphics};
+pub use inline_blame::{age_short, AsyncInlineBlameJob, InlineBlame};
+pub use progress::draw_progress;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 pub use scrollbar::{draw_scrollbar, Orientation};
 pub use scrolllist::{draw_list, draw_list_block};
@@ -0,0 +1,45 @@
+use super::{centered_rect_absolute, style::SharedTheme};
+use crate::strings;
+use asyncgit::Progress;
+use ratatui::{
+	text::Span,
+	widgets::{Block, BorderType, Borders, Clear, Gauge},
+	Frame,
+};
+
+/// draws the gauge all popups waiting for a job show, `None` before
+/// the job reported anything
+pub fn draw_progress(
+	f: &mut Frame,
+	title: &str,
+	progress: Option<&Progress>,
+	theme: &SharedTheme,
+) {
+	let (label, percent) = progress.map_or_else(
+		|| (String::from(strings::PROGRESS_NONE), 0),
+		|progress| {
+			(
+				strings::progress_label(progress),
+				progress.percent().progress,
+			)
+		},
+	);
+
+	let area = centered_rect_absolute(30, 3, f.area());
+
+	f.render_widget(Clear, area);
+	f.render_widget(
+		Gauge::default()
+			.label(label)
+			.block(
+				Block::default()
+					.title(Span::styled(title, theme.title(true)))
+					.borders(Borders::ALL)
+					.border_type(BorderType::Thick)
+					.border_style(theme.block(true)),
+			)
+			.gauge_style(theme.push_gauge())
+			.percent(u16::from(percent)),
+		area,
+	);
+}
@@ -46,9 +46,12 @@ where
 	L: Iterator<Item = S>,
 {
 	fn render(self, area: Rect, buf: &mut Buffer) {
-		// Render items
-		List::new(self.items.map(ListItem::new))
-			.block(self.block.unwrap_or_default())
+		let block = self.block.unwrap_or_default();
+		// only lay out as many items as fit
+		let height = block.inner(area).height as usize;
+
+		List::new(self.items.take(height).map(ListItem::new))
+			.block(block)
 			.style(self.style)
 			.render(area, buf);
 	}
@@ -36,11 +36,32 @@ pub struct Theme {
 	line_break: String,
 	block_title_focused: Color,
 	syntax: String,
+	show_scrollbar: bool,
+	scrollbar_fg: Color,
+	invalid_input_fg: Color,
+	attention_fg: Color,
+	/// screen reader friendly mode, see `Theme::high_contrast`
+	accessible: bool,
 }
 
 impl Theme {
 	pub fn scroll_bar_pos(&self) -> Style {
-		Style::default().fg(self.selection_bg)
+		// `Reset` keeps the thumb in the selection color
+		Style::default().fg(if self.scrollbar_fg == Color::Reset {
+			self.selection_bg
+		} else {
+			self.scrollbar_fg
+		})
+	}
+
+	/// `true` if animations are off and changes are marked by text
+	/// instead of only by color
+	pub const fn accessible(&self) -> bool {
+		self.accessible
+	}
+
+	pub const fn show_scrollbar(&self) -> bool {
+		self.show_scrollbar
 	}
 
 	pub fn block(&self, focus: bool) -> Style {
@@ -113,7 +134,9 @@ impl Theme {
 			StatusItemType::New => {
 				Style::default().fg(self.diff_file_added)
 			}
-			StatusItemType::Modified => {
+			StatusItemType::Modified
+			| StatusItemType::Typechange
+			| StatusItemType::ModeChange => {
 				Style::default().fg(self.diff_file_modified)
 			}
 			StatusItemType::Deleted => {
@@ -125,7 +148,9 @@ impl Theme {
 			StatusItemType::Conflicted => Style::default()
 				.fg(self.diff_file_modified)
 				.add_modifier(Modifier::BOLD),
-			StatusItemType::Typechange => Style::default(),
+			StatusItemType::Ignored => Style::default()
+				.fg(self.disabled_fg)
+				.add_modifier(Modifier::ITALIC),
 		};
 
 		self.apply_select(style, selected)
@@ -230,6 +255,16 @@ impl Theme {
 		Style::default().fg(self.disabled_fg)
 	}
 
+	/// text matching the log search
+	pub fn search_match(&self, selected: bool) -> Style {
+		self.apply_select(
+			Style::default()
+				.fg(self.attention_fg)
+				.add_modifier(Modifier::BOLD),
+			selected,
+		)
+	}
+
 	pub fn log_marker(&self, selected: bool) -> Style {
 		let mut style = Style::default()
 			.fg(self.commit_author)
@@ -273,8 +308,12 @@ impl Theme {
 			.bg(self.push_gauge_bg)
 	}
 
-	pub fn attention_block() -> Style {
-		Style::default().fg(Color::Yellow)
+	pub fn attention_block(&self) -> Style {
+		Style::default().fg(self.attention_fg)
+	}
+
+	pub fn invalid_input(&self) -> Style {
+		Style::default().fg(self.invalid_input_fg)
 	}
 
 	fn load_patch(theme_path: &PathBuf) -> Result<ThemePatch> {
@@ -304,13 +343,24 @@ impl Theme {
 		self.syntax.clone()
 	}
 
-	pub fn init(theme_path: &PathBuf) -> Self {
-		let mut theme = Self::default();
+	/// `accessible` forces the accessible mode, which otherwise is
+	/// enabled in the theme file
+	pub fn init(theme_path: &PathBuf, accessible: bool) -> Self {
+		let mut theme = if accessible {
+			Self::high_contrast()
+		} else {
+			Self::default()
+		};
 
 		if let Ok(patch) = Self::load_patch(theme_path).map_err(|e| {
 			log::error!("theme error [{theme_path:?}]: {e}");
 			e
 		}) {
+			// colors of the theme file still win over the contrast
+			// defaults
+			if patch.accessible == Some(true) {
+				theme = Self::high_contrast();
+			}
 			theme.apply(patch);
 		} else if let Ok(old_theme) = Self::load_old_theme(theme_path)
 		{
@@ -323,8 +373,35 @@ impl Theme {
 			}
 		}
 
+		theme.accessible |= accessible;
+
 		theme
 	}
+
+	/// defaults of the accessible mode, with bright colors and black
+	/// on white selections
+	fn high_contrast() -> Self {
+		Self {
+			command_fg: Color::Black,
+			selection_bg: Color::White,
+			selection_fg: Color::Black,
+			use_selection_fg: true,
+			cmdbar_bg: Color::White,
+			disabled_fg: Color::Gray,
+			diff_line_add: Color::LightGreen,
+			diff_line_delete: Color::LightRed,
+			commit_hash: Color::LightMagenta,
+			commit_author: Color::LightGreen,
+			danger_fg: Color::LightRed,
+			push_gauge_bg: Color::White,
+			push_gauge_fg: Color::Black,
+			scrollbar_fg: Color::White,
+			invalid_input_fg: Color::LightRed,
+			attention_fg: Color::LightYellow,
+			accessible: true,
+			..Self::default()
+		}
+	}
 }
 
 impl Default for Theme {
@@ -356,6 +433,11 @@ impl Default for Theme {
 			// Available themes can be found in:
 			// [ThemeSet::load_defaults function](https://github.com/trishume/syntect/blob/7fe13c0fd53cdfa0f9fea1aa14c5ba37f81d8b71/src/dumps.rs#L215).
 			syntax: DEFAULT_SYNTAX_THEME.to_string(),
+			show_scrollbar: true,
+			scrollbar_fg: Color::Reset,
+			invalid_input_fg: Color::Red,
+			attention_fg: Color::Yellow,
+			accessible: false,
 		}
 	}
 }
@@ -388,7 +470,7 @@ mod tests {
 		)
 		.unwrap();
 
-		let theme = Theme::init(&file.path().to_path_buf());
+		let theme = Theme::init(&file.path().to_path_buf(), false);
 
 		assert_eq!(theme.selected_tab, Theme::default().selected_tab);
 
@@ -398,4 +480,61 @@ mod tests {
 		assert_eq!(theme.selection_fg, Color::Rgb(255, 255, 255));
 		assert_eq!(theme.syntax, "InspiredGitHub");
 	}
+
+	#[test]
+	fn test_accessible() {
+		let mut file = NamedTempFile::new().unwrap();
+
+		writeln!(
+			file,
+			r##"
+(
+	accessible: Some(true),
+	selection_bg: Some("Yellow"),
+)
+"##
+		)
+		.unwrap();
+
+		let theme = Theme::init(&file.path().to_path_buf(), false);
+
+		assert!(theme.accessible());
+		assert_eq!(theme.selection_bg, Color::Yellow);
+		assert_eq!(theme.selection_fg, Color::Black);
+
+		let theme = Theme::init(&PathBuf::from("missing.ron"), true);
+
+		assert!(theme.accessible());
+		assert_eq!(theme.selection_bg, Color::White);
+	}
+
+	#[test]
+	fn test_truecolor_and_indexed() {
+		let mut file = NamedTempFile::new().unwrap();
+
+		writeln!(
+			file,
+			r##"
+(
+	invalid_input_fg: Some("#ff8700"),
+	attention_fg: Some("214"),
+	scrollbar_fg: Some("#3a3a3a"),
+)
+"##
+		)
+		.unwrap();
+
+		let theme = Theme::init(&file.path().to_path_buf(), false);
+
+		assert_eq!(theme.invalid_input_fg, Color::Rgb(255, 135, 0));
+		assert_eq!(theme.attention_fg, Color::Indexed(214));
+		assert_eq!(
+			theme.scroll_bar_pos().fg,
+			Some(Color::Rgb(58, 58, 58))
+		);
+		assert_eq!(
+			Theme::default().scroll_bar_pos().fg,
+			Some(Theme::default().selection_bg)
+		);
+	}
 }
@@ -126,6 +126,11 @@ impl SyntaxText {
 			))?;
 
 			for (number, line) in text.lines().enumerate() {
+				// the file is not shown anymore
+				if params.is_cancelled() {
+					break;
+				}
+
 				let ops = state
 					.parse_line(line, &SYNTAX_SET)
 					.map_err(|e| {
@@ -1,82 +1,353 @@
+use crate::{
+	options::{WatcherOptions, WatcherStrategy},
+	string_utils::glob_match,
+};
 use anyhow::Result;
+use asyncgit::sync::{repo_common_dir, IgnoreChecker, RepoPath};
 use crossbeam_channel::{unbounded, Sender};
-use notify::{RecommendedWatcher, RecursiveMode, Watcher};
-use notify_debouncer_mini::{new_debouncer, DebounceEventResult};
+use notify::{
+	PollWatcher, RecommendedWatcher, RecursiveMode, Watcher,
+};
+use notify_debouncer_mini::{
+	new_debouncer_opt, Config, DebounceEventResult, Debouncer,
+};
 use scopetime::scope_time;
-use std::{path::Path, thread, time::Duration};
+use std::{
+	collections::HashSet,
+	ffi::OsStr,
+	fs,
+	path::{Path, PathBuf},
+	sync::mpsc,
+	thread,
+};
 
 pub struct RepoWatcher {
-	receiver: crossbeam_channel::Receiver<()>,
+	receiver: crossbeam_channel::Receiver<Vec<String>>,
 }
 
 impl RepoWatcher {
-	pub fn new(workdir: &str) -> Self {
+	/// watches `workdir` with the `options` strategy (which must not
+	/// be `WatcherStrategy::Tick`), folders ignored by git or matching
+	/// one of the `ignore` globs are not watched at all
+	pub fn new(
+		repo_path: &RepoPath,
+		workdir: &str,
+		options: WatcherOptions,
+		ignore: Vec<String>,
+	) -> Self {
 		log::trace!(
 			"recommended watcher: {:?}",
 			RecommendedWatcher::kind()
 		);
 
-		let (tx, rx) = std::sync::mpsc::channel();
+		let (out_tx, out_rx) = unbounded();
 
-		let workdir = workdir.to_string();
+		let repo_path = repo_path.clone();
+		let workdir = PathBuf::from(workdir);
 
 		thread::spawn(move || {
-			let timeout = Duration::from_secs(2);
-			create_watcher(timeout, tx, &workdir);
-		});
-
-		let (out_tx, out_rx) = unbounded();
+			let result = match options.strategy {
+				WatcherStrategy::Poll => Self::watch::<PollWatcher>(
+					options, &repo_path, workdir, ignore, &out_tx,
+				),
+				_ => Self::watch::<RecommendedWatcher>(
+					options, &repo_path, workdir, ignore, &out_tx,
+				),
+			};
 
-		thread::spawn(move || {
-			if let Err(e) = Self::forwarder(&rx, &out_tx) {
-				//maybe we need to restart the forwarder now?
-				log::error!("notify receive error: {e}");
+			if let Err(e) = result {
+				log::error!("watcher error: {e}");
 			}
 		});
 
 		Self { receiver: out_rx }
 	}
 
-	///
-	pub fn receiver(&self) -> crossbeam_channel::Receiver<()> {
+	/// the paths (relative to the workdir) of the files that changed
+	pub fn receiver(
+		&self,
+	) -> crossbeam_channel::Receiver<Vec<String>> {
 		self.receiver.clone()
 	}
 
-	fn forwarder(
-		receiver: &std::sync::mpsc::Receiver<DebounceEventResult>,
-		sender: &Sender<()>,
+	fn watch<T: Watcher>(
+		options: WatcherOptions,
+		repo_path: &RepoPath,
+		workdir: PathBuf,
+		globs: Vec<String>,
+		sender: &Sender<Vec<String>>,
 	) -> Result<()> {
+		let (tx, rx) = mpsc::channel();
+
+		let mut filter = PathFilter {
+			checker: IgnoreChecker::new(repo_path)?,
+			workdir,
+			globs,
+		};
+
+		// the debouncer stops watching once dropped, so it lives as
+		// long as someone listens to the forwarded events
+		let mut watches = Watches {
+			bouncer: create_watcher::<T>(options, tx)?,
+			dirs: HashSet::new(),
+		};
+		watches.arm(&filter.workdir, &filter);
+
+		// linked worktrees keep their index and refs in the git dir
+		// of the main worktree
+		let git_dir = repo_common_dir(repo_path)?;
+		let git_dir = (!git_dir.starts_with(&filter.workdir))
+			.then_some(git_dir);
+		if let Some(git_dir) = &git_dir {
+			watches
+				.bouncer
+				.watcher()
+				.watch(git_dir, RecursiveMode::Recursive)?;
+		}
+
 		loop {
-			let ev = receiver.recv()?;
+			let Ok(ev) = rx.recv()? else {
+				continue;
+			};
 
-			if let Ok(ev) = ev {
-				log::debug!("notify events: {}", ev.len());
+			log::debug!("notify events: {}", ev.len());
+
+			for (idx, ev) in ev.iter().enumerate() {
+				log::debug!("notify [{idx}]: {ev:?}");
+			}
 
-				for (idx, ev) in ev.iter().enumerate() {
-					log::debug!("notify [{idx}]: {ev:?}");
+			if ev.iter().any(|ev| is_ignore_file(&ev.path)) {
+				filter.checker = IgnoreChecker::new(repo_path)?;
+				watches.rearm(&filter);
+			} else {
+				for ev in &ev {
+					if ev.path.is_dir() {
+						watches.arm(&ev.path, &filter);
+					} else if !ev.path.exists() {
+						watches.disarm(&ev.path);
+					}
 				}
+			}
+
+			// a directory changes with every entry added or removed,
+			// which is reported on its own anyway
+			let changed: Vec<String> = ev
+				.iter()
+				.filter(|ev| {
+					!ev.path.is_dir() && !filter.is_ignored(&ev.path)
+				})
+				.map(|ev| {
+					if git_dir
+						.as_ref()
+						.is_some_and(|dir| ev.path.starts_with(dir))
+					{
+						String::from(".git")
+					} else {
+						relative_path(&ev.path, &filter.workdir)
+					}
+				})
+				.collect::<HashSet<_>>()
+				.into_iter()
+				.collect();
+
+			if !changed.is_empty() {
+				sender.send(changed)?;
+			}
+		}
+	}
+}
+
+/// decides which changes are worth a watch and a refresh
+struct PathFilter {
+	checker: IgnoreChecker,
+	workdir: PathBuf,
+	globs: Vec<String>,
+}
+
+impl PathFilter {
+	fn is_ignored(&self, path: &Path) -> bool {
+		matches_globs(path, &self.workdir, &self.globs)
+			|| self.checker.is_ignored(path)
+	}
+}
+
+/// one non-recursive watch per folder that is not ignored
+struct Watches<T: Watcher> {
+	bouncer: Debouncer<T>,
+	dirs: HashSet<PathBuf>,
+}
+
+impl<T: Watcher> Watches<T> {
+	/// watches `dir` and all folders below it that are not ignored
+	fn arm(&mut self, dir: &Path, filter: &PathFilter) {
+		scope_time!("watches_arm");
+
+		let mut pending = vec![dir.to_path_buf()];
+
+		while let Some(dir) = pending.pop() {
+			// index and refs live deep inside the git dir, so it is
+			// watched as a whole
+			let git_dir = dir.file_name() == Some(OsStr::new(".git"));
+			let in_git_dir = !git_dir
+				&& dir
+					.strip_prefix(&filter.workdir)
+					.unwrap_or(&dir)
+					.components()
+					.any(|c| c.as_os_str() == ".git");
+
+			if in_git_dir
+				|| self.dirs.contains(&dir)
+				|| filter.is_ignored(&dir)
+			{
+				continue;
+			}
 
-				if !ev.is_empty() {
-					sender.send(())?;
+			let mode = if git_dir {
+				RecursiveMode::Recursive
+			} else {
+				RecursiveMode::NonRecursive
+			};
+
+			if let Err(e) = self.bouncer.watcher().watch(&dir, mode) {
+				log::error!("watch error: {e}");
+				continue;
+			}
+
+			if !git_dir {
+				if let Ok(entries) = fs::read_dir(&dir) {
+					pending.extend(
+						entries
+							.flatten()
+							.filter(|entry| {
+								entry
+									.file_type()
+									.is_ok_and(|kind| kind.is_dir())
+							})
+							.map(|entry| entry.path()),
+					);
 				}
 			}
+
+			self.dirs.insert(dir);
 		}
 	}
+
+	/// drops the watches of the removed folder `dir` and below
+	fn disarm(&mut self, dir: &Path) {
+		let watcher = self.bouncer.watcher();
+
+		self.dirs.retain(|watched| {
+			if watched.starts_with(dir) {
+				// the os already dropped the watch of removed folders
+				watcher.unwatch(watched).ok();
+				false
+			} else {
+				true
+			}
+		});
+	}
+
+	/// rebuilds all watches after the ignore rules changed
+	fn rearm(&mut self, filter: &PathFilter) {
+		self.disarm(&filter.workdir);
+		self.arm(&filter.workdir, filter);
+	}
+}
+
+/// `true` for the files git reads ignore rules from
+fn is_ignore_file(path: &Path) -> bool {
+	path.file_name() == Some(OsStr::new(".gitignore"))
+		|| path.ends_with("info/exclude")
+}
+
+/// `path` relative to `workdir` the way git names it, unchanged if it
+/// is outside
+fn relative_path(path: &Path, workdir: &Path) -> String {
+	path.strip_prefix(workdir).map_or_else(
+		|_| path.to_string_lossy().into_owned(),
+		|path| {
+			path.components()
+				.map(|c| c.as_os_str().to_string_lossy())
+				.collect::<Vec<_>>()
+				.join("/")
+		},
+	)
 }
 
-fn create_watcher(
-	timeout: Duration,
-	tx: std::sync::mpsc::Sender<DebounceEventResult>,
-	workdir: &str,
-) {
+/// `true` if `path` relative to `workdir` matches one of the globs
+fn matches_globs(
+	path: &Path,
+	workdir: &Path,
+	globs: &[String],
+) -> bool {
+	let path = path.strip_prefix(workdir).unwrap_or(path);
+	let path = path.to_string_lossy();
+
+	globs.iter().any(|pattern| glob_match(pattern, &path))
+}
+
+fn create_watcher<T: Watcher>(
+	options: WatcherOptions,
+	tx: mpsc::Sender<DebounceEventResult>,
+) -> Result<Debouncer<T>> {
 	scope_time!("create_watcher");
 
-	let mut bouncer =
-		new_debouncer(timeout, tx).expect("Watch create error");
-	bouncer
-		.watcher()
-		.watch(Path::new(&workdir), RecursiveMode::Recursive)
-		.expect("Watch error");
+	let config = Config::default()
+		.with_timeout(options.debounce())
+		.with_notify_config(
+			notify::Config::default()
+				.with_poll_interval(options.debounce()),
+		);
+
+	Ok(new_debouncer_opt::<_, T>(config, tx)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{is_ignore_file, matches_globs, relative_path};
+	use std::path::Path;
 
-	std::mem::forget(bouncer);
+	#[test]
+	fn test_matches_globs() {
+		let workdir = Path::new("/repo");
+		let globs =
+			vec![String::from("target/*"), String::from("*.log")];
+
+		assert!(matches_globs(
+			Path::new("/repo/target/debug/gitui"),
+			workdir,
+			&globs
+		));
+		assert!(matches_globs(
+			Path::new("/repo/logs/run.log"),
+			workdir,
+			&globs
+		));
+		assert!(!matches_globs(
+			Path::new("/repo/src/target.rs"),
+			workdir,
+			&globs
+		));
+	}
+
+	#[test]
+	fn test_relative_path() {
+		let workdir = Path::new("/repo");
+
+		assert_eq!(
+			relative_path(Path::new("/repo/src/main.rs"), workdir),
+			"src/main.rs"
+		);
+		assert_eq!(
+			relative_path(Path::new("/other/main.rs"), workdir),
+			"/other/main.rs"
+		);
+	}
+
+	#[test]
+	fn test_is_ignore_file() {
+		assert!(is_ignore_file(Path::new("/repo/src/.gitignore")));
+		assert!(is_ignore_file(Path::new("/repo/.git/info/exclude")));
+		assert!(!is_ignore_file(Path::new("/repo/exclude")));
+	}
 }